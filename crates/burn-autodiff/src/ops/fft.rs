@@ -0,0 +1,115 @@
+use alloc::{vec, vec::Vec};
+
+use super::{Backward, Ops, unary};
+use crate::{checkpoint::base::Checkpointer, grads::Gradients};
+use burn_tensor::{ElementConversion, Shape, TensorData, TensorMetadata, backend::Backend};
+
+/// The transform being linear, its gradient is the transform with the conjugate matrix, which is
+/// the unnormalized opposite transform.
+#[derive(Debug)]
+pub(crate) struct Fft;
+
+/// The gradient is the real part of the unnormalized inverse transform of the gradient padded
+/// with zeros to the full spectrum.
+#[derive(Debug)]
+pub(crate) struct Rfft;
+
+/// The gradient is the real transform of the gradient, with each frequency weighted by how many
+/// times it appears in the full spectrum.
+#[derive(Debug)]
+pub(crate) struct Irfft;
+
+impl<B: Backend> Backward<B, 1> for Fft {
+    // The transformed dimension and whether the transform is inverse.
+    type State = (usize, bool);
+
+    fn backward(
+        self,
+        ops: Ops<Self::State, 1>,
+        grads: &mut Gradients,
+        _checkpointer: &mut Checkpointer,
+    ) {
+        let (dim, inverse) = ops.state;
+
+        unary::<B, _>(ops.parents, ops.node, grads, |grad| {
+            let n = grad.shape().dims[dim] as f64;
+            let scale = match inverse {
+                true => 1.0 / n,
+                false => n,
+            };
+
+            B::float_mul_scalar(B::float_fft(grad, dim, !inverse), scale.elem())
+        });
+    }
+}
+
+impl<B: Backend> Backward<B, 1> for Rfft {
+    // The transformed dimension and its size.
+    type State = (usize, usize);
+
+    fn backward(
+        self,
+        ops: Ops<Self::State, 1>,
+        grads: &mut Gradients,
+        _checkpointer: &mut Checkpointer,
+    ) {
+        let (dim, n) = ops.state;
+
+        unary::<B, _>(ops.parents, ops.node, grads, |grad| {
+            let mut dims = grad.shape().dims;
+            let rank = dims.len();
+            let num_freqs = dims[dim];
+
+            let grad = match num_freqs < n {
+                true => {
+                    dims[dim] = n - num_freqs;
+                    let dtype = grad.dtype();
+                    let zeros = B::float_zeros(Shape::from(dims.clone()), &B::float_device(&grad));
+                    B::float_cat(vec![grad, B::float_cast(zeros, dtype.into())], dim)
+                }
+                false => grad,
+            };
+            let grad = B::float_mul_scalar(B::float_fft(grad, dim, true), (n as f64).elem());
+
+            dims[dim] = n;
+            let mut ranges = dims.iter().map(|size| 0..*size).collect::<Vec<_>>();
+            ranges[rank - 1] = 0..1;
+            let real = B::float_slice(grad, &ranges);
+
+            B::float_reshape(real, Shape::from(&dims[..rank - 1]))
+        });
+    }
+}
+
+impl<B: Backend> Backward<B, 1> for Irfft {
+    // The transformed dimension and the size of the output.
+    type State = (usize, usize);
+
+    fn backward(
+        self,
+        ops: Ops<Self::State, 1>,
+        grads: &mut Gradients,
+        _checkpointer: &mut Checkpointer,
+    ) {
+        let (dim, n) = ops.state;
+
+        unary::<B, _>(ops.parents, ops.node, grads, |grad| {
+            let device = B::float_device(&grad);
+            let dtype = grad.dtype();
+            let grad = B::float_rfft(grad, dim);
+            let num_freqs = grad.shape().dims[dim];
+
+            let weights = (0..num_freqs)
+                .map(|k| match k == 0 || 2 * k == n {
+                    true => 1.0 / n as f64,
+                    false => 2.0 / n as f64,
+                })
+                .collect::<Vec<_>>();
+            let mut dims = vec![1; grad.shape().num_dims()];
+            dims[dim] = num_freqs;
+            let weights = TensorData::new(weights, dims).convert_dtype(dtype);
+
+            B::float_mul(grad, B::float_from_data(weights, &device))
+        });
+    }
+}
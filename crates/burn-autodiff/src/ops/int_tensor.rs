@@ -2,7 +2,7 @@ use crate::{Autodiff, checkpoint::strategy::CheckpointStrategy, tensor::Autodiff
 use alloc::vec::Vec;
 
 use burn_tensor::{
-    Device, Distribution, Shape, TensorData,
+    Device, Distribution, ScatterReduce, Shape, TensorData,
     backend::Backend,
     ops::{BoolTensor, IntTensor, IntTensorOps},
 };
@@ -189,6 +189,16 @@ impl<B: Backend, C: CheckpointStrategy> IntTensorOps<Self> for Autodiff<B, C> {
         B::int_scatter(dim, tensor, indices, value)
     }
 
+    fn int_scatter_reduce(
+        dim: usize,
+        tensor: IntTensor<B>,
+        indices: IntTensor<B>,
+        value: IntTensor<B>,
+        reduce: ScatterReduce,
+    ) -> IntTensor<B> {
+        B::int_scatter_reduce(dim, tensor, indices, value, reduce)
+    }
+
     fn int_select(tensor: IntTensor<B>, dim: usize, indices: IntTensor<B>) -> IntTensor<B> {
         B::int_select(tensor, dim, indices)
     }
@@ -301,20 +311,41 @@ impl<B: Backend, C: CheckpointStrategy> IntTensorOps<Self> for Autodiff<B, C> {
         B::int_expand(tensor, shape)
     }
 
-    fn int_sort(tensor: IntTensor<Self>, dim: usize, descending: bool) -> IntTensor<Self> {
-        B::int_sort(tensor, dim, descending)
+    fn int_sort(
+        tensor: IntTensor<Self>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<Self> {
+        B::int_sort(tensor, dim, descending, stable)
     }
 
     fn int_sort_with_indices(
         tensor: IntTensor<Self>,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (IntTensor<Self>, IntTensor<Self>) {
-        B::int_sort_with_indices(tensor, dim, descending)
+        B::int_sort_with_indices(tensor, dim, descending, stable)
+    }
+
+    fn int_argsort(
+        tensor: IntTensor<Self>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<Self> {
+        B::int_argsort(tensor, dim, descending, stable)
+    }
+
+    async fn int_unique(
+        tensor: IntTensor<Self>,
+    ) -> (IntTensor<Self>, IntTensor<Self>, IntTensor<Self>) {
+        B::int_unique(tensor).await
     }
 
-    fn int_argsort(tensor: IntTensor<Self>, dim: usize, descending: bool) -> IntTensor<Self> {
-        B::int_argsort(tensor, dim, descending)
+    fn int_bincount(tensor: IntTensor<Self>, num_bins: usize) -> IntTensor<Self> {
+        B::int_bincount(tensor, num_bins)
     }
 
     fn bitwise_and(lhs: IntTensor<Self>, rhs: IntTensor<Self>) -> IntTensor<Self> {
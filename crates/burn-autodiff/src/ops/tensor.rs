@@ -20,7 +20,7 @@ use crate::{
 };
 
 use burn_tensor::{
-    Device, ElementConversion, Shape, TensorData, TensorMetadata,
+    Device, ElementConversion, ScatterReduce, Shape, TensorData, TensorMetadata,
     backend::Backend,
     ops::{BoolTensor, FloatElem, FloatTensor, FloatTensorOps, IntTensor},
 };
@@ -944,7 +944,7 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         struct Scatter;
 
         impl<B: Backend> Backward<B, 2> for Scatter {
-            type State = (usize, IntTensor<B>, Shape, Shape, B::Device);
+            type State = (usize, IntTensor<B>);
 
             fn backward(
                 self,
@@ -952,21 +952,15 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 grads: &mut Gradients,
                 _checkpointer: &mut Checkpointer,
             ) {
-                let (dim, indices, shape_lhs, shape_rhs, device) = ops.state;
-                let [indices_4lhs, indices_4rhs] = duplicate(&ops.parents, Some(indices));
+                let (dim, indices) = ops.state;
 
                 binary::<B, _, _>(
                     ops.parents,
                     ops.node,
                     grads,
-                    |grad| {
-                        let zeros = B::float_zeros(shape_lhs, &device);
-                        B::float_scatter(dim, grad, indices_4lhs.unwrap(), zeros)
-                    },
-                    |grad| {
-                        let zeros = B::float_zeros(shape_rhs, &device);
-                        B::float_scatter(dim, zeros, indices_4rhs.unwrap(), grad)
-                    },
+                    |grad| grad,
+                    // Each scattered element receives the gradient of the position it was added to.
+                    |grad| B::float_gather(dim, grad, indices),
                 );
             }
         }
@@ -977,13 +971,7 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             .stateful()
         {
             OpsKind::Tracked(prep) => prep.finish(
-                (
-                    dim,
-                    indices.clone(),
-                    tensor.primitive.shape(),
-                    value.primitive.shape(),
-                    B::float_device(&value.primitive),
-                ),
+                (dim, indices.clone()),
                 B::float_scatter(dim, tensor.primitive, indices, value.primitive),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_scatter(
@@ -995,6 +983,185 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         }
     }
 
+    fn float_scatter_reduce(
+        dim: usize,
+        tensor: FloatTensor<Self>,
+        indices: IntTensor<B>,
+        value: FloatTensor<Self>,
+        reduce: ScatterReduce,
+    ) -> FloatTensor<Self> {
+        #[derive(Debug)]
+        struct ScatterReduction;
+
+        impl<B: Backend> Backward<B, 2> for ScatterReduction {
+            type State = (
+                usize,
+                IntTensor<B>,
+                ScatterReduce,
+                FloatTensor<B>,
+                FloatTensor<B>,
+                FloatTensor<B>,
+            );
+
+            fn backward(
+                self,
+                ops: Ops<Self::State, 2>,
+                grads: &mut Gradients,
+                _checkpointer: &mut Checkpointer,
+            ) {
+                let (dim, indices, reduce, tensor, value, output) = ops.state;
+                let (weight_tensor, weight_value) = match reduce {
+                    ScatterReduce::Prod => {
+                        scatter_prod_weights::<B>(dim, indices.clone(), tensor, value)
+                    }
+                    _ => scatter_extremum_weights::<B>(dim, indices.clone(), tensor, value, output),
+                };
+                binary::<B, _, _>(
+                    ops.parents,
+                    ops.node,
+                    grads,
+                    |grad| B::float_mul(grad, weight_tensor),
+                    |grad| {
+                        let grad = B::float_gather(dim, grad, indices);
+                        B::float_mul(grad, weight_value)
+                    },
+                );
+            }
+        }
+
+        /// The gradient weights of the tensor and value elements for the min and max reductions,
+        /// the gradient being split evenly between the elements equal to the result.
+        fn scatter_extremum_weights<B: Backend>(
+            dim: usize,
+            indices: IntTensor<B>,
+            tensor: FloatTensor<B>,
+            value: FloatTensor<B>,
+            output: FloatTensor<B>,
+        ) -> (FloatTensor<B>, FloatTensor<B>) {
+            let output_value = B::float_gather(dim, output.clone(), indices.clone());
+            let weight_tensor = B::bool_into_float(B::float_equal(tensor, output));
+            let weight_value = B::bool_into_float(B::float_equal(value, output_value));
+
+            let counts = B::float_scatter(
+                dim,
+                weight_tensor.clone(),
+                indices.clone(),
+                weight_value.clone(),
+            );
+            let counts_value = B::float_gather(dim, counts.clone(), indices);
+
+            (
+                B::float_div(weight_tensor, counts),
+                B::float_div(weight_value, counts_value),
+            )
+        }
+
+        /// The gradient weights of the tensor and value elements for the product reduction, which
+        /// are the products of the other elements reduced to the same position.
+        fn scatter_prod_weights<B: Backend>(
+            dim: usize,
+            indices: IntTensor<B>,
+            tensor: FloatTensor<B>,
+            value: FloatTensor<B>,
+        ) -> (FloatTensor<B>, FloatTensor<B>) {
+            // Zeros are left out of the product, so that it can be divided by each element.
+            let zeros_tensor = B::float_equal_elem(tensor.clone(), 0.elem());
+            let zeros_value = B::float_equal_elem(value.clone(), 0.elem());
+            let tensor = B::float_mask_fill(tensor, zeros_tensor.clone(), 1.elem());
+            let value = B::float_mask_fill(value, zeros_value.clone(), 1.elem());
+            let zeros_tensor = B::bool_into_float(zeros_tensor);
+            let zeros_value = B::bool_into_float(zeros_value);
+
+            let prod = B::float_scatter_reduce(
+                dim,
+                tensor.clone(),
+                indices.clone(),
+                value.clone(),
+                ScatterReduce::Prod,
+            );
+            let num_zeros = B::float_scatter(
+                dim,
+                zeros_tensor.clone(),
+                indices.clone(),
+                zeros_value.clone(),
+            );
+
+            // The product of the other elements is zero if any of them is zero.
+            let others = |prod, num_zeros, element, zeros| {
+                let others = B::float_div(prod, element);
+                let other_zeros = B::float_sub(num_zeros, zeros);
+                B::float_mask_fill(
+                    others,
+                    B::float_greater_elem(other_zeros, 0.elem()),
+                    0.elem(),
+                )
+            };
+
+            (
+                others(prod.clone(), num_zeros.clone(), tensor, zeros_tensor),
+                others(
+                    B::float_gather(dim, prod, indices.clone()),
+                    B::float_gather(dim, num_zeros, indices),
+                    value,
+                    zeros_value,
+                ),
+            )
+        }
+
+        match reduce {
+            ScatterReduce::Sum => return Self::float_scatter(dim, tensor, indices, value),
+            ScatterReduce::Mean => {
+                // Each element of the tensor counts as one element of the mean.
+                let device = B::float_device(&tensor.primitive);
+                let counts = B::float_scatter(
+                    dim,
+                    B::float_ones(tensor.primitive.shape(), &device),
+                    indices.clone(),
+                    B::float_ones(value.primitive.shape(), &device),
+                );
+                let sum = Self::float_scatter(dim, tensor, indices, value);
+
+                return Self::float_div(sum, AutodiffTensor::new(counts));
+            }
+            _ => {}
+        }
+
+        match ScatterReduction
+            .prepare::<C>([tensor.node, value.node])
+            .compute_bound()
+            .stateful()
+        {
+            OpsKind::Tracked(prep) => {
+                let output = B::float_scatter_reduce(
+                    dim,
+                    tensor.primitive.clone(),
+                    indices.clone(),
+                    value.primitive.clone(),
+                    reduce,
+                );
+
+                prep.finish(
+                    (
+                        dim,
+                        indices,
+                        reduce,
+                        tensor.primitive,
+                        value.primitive,
+                        output.clone(),
+                    ),
+                    output,
+                )
+            }
+            OpsKind::UnTracked(prep) => prep.finish(B::float_scatter_reduce(
+                dim,
+                tensor.primitive,
+                indices,
+                value.primitive,
+                reduce,
+            )),
+        }
+    }
+
     fn float_select(
         tensor: FloatTensor<Self>,
         dim: usize,
@@ -2439,7 +2606,52 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         }
     }
 
-    fn float_sort(tensor: FloatTensor<Self>, dim: usize, descending: bool) -> FloatTensor<Self> {
+    fn float_fft(tensor: FloatTensor<Self>, dim: usize, inverse: bool) -> FloatTensor<Self> {
+        match super::fft::Fft
+            .prepare::<C>([tensor.node])
+            .compute_bound()
+            .stateful()
+        {
+            OpsKind::Tracked(prep) => {
+                prep.finish((dim, inverse), B::float_fft(tensor.primitive, dim, inverse))
+            }
+            OpsKind::UnTracked(prep) => prep.finish(B::float_fft(tensor.primitive, dim, inverse)),
+        }
+    }
+
+    fn float_rfft(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
+        match super::fft::Rfft
+            .prepare::<C>([tensor.node])
+            .compute_bound()
+            .stateful()
+        {
+            OpsKind::Tracked(prep) => {
+                let n = tensor.primitive.shape().dims[dim];
+                prep.finish((dim, n), B::float_rfft(tensor.primitive, dim))
+            }
+            OpsKind::UnTracked(prep) => prep.finish(B::float_rfft(tensor.primitive, dim)),
+        }
+    }
+
+    fn float_irfft(tensor: FloatTensor<Self>, dim: usize, n: usize) -> FloatTensor<Self> {
+        match super::fft::Irfft
+            .prepare::<C>([tensor.node])
+            .compute_bound()
+            .stateful()
+        {
+            OpsKind::Tracked(prep) => {
+                prep.finish((dim, n), B::float_irfft(tensor.primitive, dim, n))
+            }
+            OpsKind::UnTracked(prep) => prep.finish(B::float_irfft(tensor.primitive, dim, n)),
+        }
+    }
+
+    fn float_sort(
+        tensor: FloatTensor<Self>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> FloatTensor<Self> {
         match super::sort::SortDim
             .prepare::<C>([tensor.node])
             .compute_bound()
@@ -2448,11 +2660,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::Tracked(prep) => {
                 let shape = tensor.primitive.shape();
                 let (tensor, indices) =
-                    B::float_sort_with_indices(tensor.primitive, dim, descending);
+                    B::float_sort_with_indices(tensor.primitive, dim, descending, stable);
                 prep.finish((indices, shape), tensor)
             }
             OpsKind::UnTracked(prep) => {
-                prep.finish(B::float_sort(tensor.primitive, dim, descending))
+                prep.finish(B::float_sort(tensor.primitive, dim, descending, stable))
             }
         }
     }
@@ -2461,6 +2673,7 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         tensor: FloatTensor<Self>,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (FloatTensor<Self>, IntTensor<B>) {
         match super::sort::SortDim
             .prepare::<C>([tensor.node])
@@ -2470,14 +2683,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::Tracked(prep) => {
                 let shape = tensor.primitive.shape();
                 let (tensor, indices) =
-                    B::float_sort_with_indices(tensor.primitive, dim, descending);
+                    B::float_sort_with_indices(tensor.primitive, dim, descending, stable);
                 let tensor = prep.finish((indices.clone(), shape), tensor);
 
                 (tensor, indices)
             }
             OpsKind::UnTracked(prep) => {
                 let (tensor, indices) =
-                    B::float_sort_with_indices(tensor.primitive, dim, descending);
+                    B::float_sort_with_indices(tensor.primitive, dim, descending, stable);
                 let tensor = prep.finish(tensor);
 
                 (tensor, indices)
@@ -2485,8 +2698,13 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         }
     }
 
-    fn float_argsort(tensor: FloatTensor<Self>, dim: usize, descending: bool) -> IntTensor<B> {
-        B::float_argsort(tensor.primitive, dim, descending)
+    fn float_argsort(
+        tensor: FloatTensor<Self>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<B> {
+        B::float_argsort(tensor.primitive, dim, descending, stable)
     }
 
     fn float_repeat_dim(tensor: FloatTensor<Self>, dim: usize, times: usize) -> FloatTensor<Self> {
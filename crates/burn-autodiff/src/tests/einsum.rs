@@ -0,0 +1,55 @@
+#[burn_tensor_testgen::testgen(ad_einsum)]
+mod tests {
+    use super::*;
+    use burn_tensor::TensorData;
+
+    #[test]
+    fn should_diff_einsum_matmul() {
+        let data_1 = TensorData::from([[1.0, 7.0], [2.0, 3.0]]);
+        let data_2 = TensorData::from([[4.0, 7.0], [2.0, 3.0]]);
+
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<2>::from_data(data_1, &device).require_grad();
+        let tensor_2 = TestAutodiffTensor::<2>::from_data(data_2, &device).require_grad();
+
+        let tensor_3: TestAutodiffTensor<2> =
+            tensor_1.clone().einsum("ij,jk->ik", tensor_2.clone());
+        let grads = tensor_3.backward();
+
+        let grad_1 = tensor_1.grad(&grads).unwrap();
+        let grad_2 = tensor_2.grad(&grads).unwrap();
+
+        grad_1
+            .to_data()
+            .assert_eq(&TensorData::from([[11.0, 5.0], [11.0, 5.0]]), false);
+        grad_2
+            .to_data()
+            .assert_eq(&TensorData::from([[3.0, 3.0], [10.0, 10.0]]), false);
+    }
+
+    #[test]
+    fn should_diff_einsum_diagonal() {
+        let data_1 = TensorData::from([[1.0, 7.0], [2.0, 3.0]]);
+        let data_2 = TensorData::from([10.0, 20.0]);
+
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<2>::from_data(data_1, &device).require_grad();
+        let tensor_2 = TestAutodiffTensor::<1>::from_data(data_2, &device).require_grad();
+
+        let tensor_3: TestAutodiffTensor<1> = tensor_1.clone().einsum("ii,i->i", tensor_2.clone());
+        let grads = tensor_3.backward();
+
+        let grad_1 = tensor_1.grad(&grads).unwrap();
+        let grad_2 = tensor_2.grad(&grads).unwrap();
+
+        grad_1
+            .to_data()
+            .assert_eq(&TensorData::from([[10.0, 0.0], [0.0, 20.0]]), false);
+        grad_2
+            .to_data()
+            .assert_eq(&TensorData::from([1.0, 3.0]), false);
+        tensor_3
+            .to_data()
+            .assert_eq(&TensorData::from([10.0, 60.0]), false);
+    }
+}
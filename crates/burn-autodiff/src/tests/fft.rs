@@ -0,0 +1,78 @@
+#[burn_tensor_testgen::testgen(ad_fft)]
+mod tests {
+    use super::*;
+    use burn_tensor::{TensorData, Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    #[test]
+    fn should_diff_fft() {
+        let data = TensorData::from([[1.0, 0.0], [2.0, 0.0]]);
+
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<2>::from_data(data, &device).require_grad();
+
+        let tensor_2 = tensor_1.clone().fft(0);
+        let grads = tensor_2.sum().backward();
+
+        let grad = tensor_1.grad(&grads).unwrap();
+
+        grad.to_data().assert_approx_eq::<FT>(
+            &TensorData::from([[2.0, 2.0], [0.0, 0.0]]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn should_diff_ifft() {
+        let data = TensorData::from([[1.0, 0.0], [2.0, 0.0]]);
+
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<2>::from_data(data, &device).require_grad();
+
+        let tensor_2 = tensor_1.clone().ifft(0);
+        let grads = tensor_2.sum().backward();
+
+        let grad = tensor_1.grad(&grads).unwrap();
+
+        grad.to_data().assert_approx_eq::<FT>(
+            &TensorData::from([[1.0, 1.0], [0.0, 0.0]]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn should_diff_rfft() {
+        let data = TensorData::from([1.0, 2.0, 3.0, 4.0]);
+
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<1>::from_data(data, &device).require_grad();
+
+        let tensor_2: TestAutodiffTensor<2> = tensor_1.clone().rfft(0);
+        let grads = tensor_2.sum().backward();
+
+        let grad = tensor_1.grad(&grads).unwrap();
+
+        grad.to_data().assert_approx_eq::<FT>(
+            &TensorData::from([3.0, -1.0, 1.0, 1.0]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn should_diff_irfft() {
+        let data = TensorData::from([[10.0, 0.0], [-2.0, 2.0], [-2.0, 0.0]]);
+
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<2>::from_data(data, &device).require_grad();
+
+        let tensor_2: TestAutodiffTensor<1> = tensor_1.clone().irfft(0, None);
+        let grads = tensor_2.sum().backward();
+
+        let grad = tensor_1.grad(&grads).unwrap();
+
+        grad.to_data().assert_approx_eq::<FT>(
+            &TensorData::from([[1.0, 0.0], [0.0, 0.0], [0.0, 0.0]]),
+            Tolerance::default(),
+        );
+    }
+}
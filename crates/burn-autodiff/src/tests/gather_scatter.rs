@@ -65,4 +65,40 @@ mod tests {
             .to_data()
             .assert_eq(&TensorData::from([[19., 19., 19.], [64., 64., 64.]]), false);
     }
+
+    #[test]
+    fn test_scatter_grad_with_non_uniform_gradient() {
+        let device = Default::default();
+        let tensor = TestAutodiffTensor::from_data(
+            TensorData::from([[0.0, 1.0, 2.0, 3.0], [4.0, 5.0, 6.0, 7.0]]),
+            &device,
+        )
+        .require_grad();
+        let values =
+            TestAutodiffTensor::from_data(TensorData::from([[1.0, 2.0], [3.0, 4.0]]), &device)
+                .require_grad();
+        let indices = Tensor::<TestAutodiffBackend, 2, Int>::from_data(
+            TensorData::from([[3, 1], [0, 2]]),
+            &device,
+        );
+        let weights = TestAutodiffTensor::from_data(
+            TensorData::from([[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]]),
+            &device,
+        );
+
+        let output = tensor.clone().scatter(1, indices, values.clone()) * weights;
+        let grads = output.sum().backward();
+
+        let grad_tensor = tensor.grad(&grads).unwrap();
+        let grad_values = values.grad(&grads).unwrap();
+
+        grad_tensor.to_data().assert_eq(
+            &TensorData::from([[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]]),
+            false,
+        );
+        // Each value receives the gradient of the position it was added to.
+        grad_values
+            .to_data()
+            .assert_eq(&TensorData::from([[4.0, 2.0], [5.0, 7.0]]), false);
+    }
 }
@@ -70,4 +70,32 @@ mod tests {
             .into_data()
             .assert_approx_eq::<FT>(&expected, tolerance);
     }
+
+    #[test]
+    fn should_diff_mask_select_and_assign() {
+        let device = Default::default();
+        let tensor_1 =
+            TestAutodiffTensor::from_data([[1.0, 7.0], [2.0, 3.0]], &device).require_grad();
+        let tensor_2 = TestAutodiffTensor::from_data([4.0, 5.0], &device).require_grad();
+        let mask = Tensor::<TestAutodiffBackend, 2, Bool>::from_bool(
+            TensorData::from([[true, false], [false, true]]),
+            &device,
+        );
+
+        let selected = tensor_1.clone().mask_select(mask.clone());
+        let tensor_3 = tensor_1
+            .clone()
+            .mask_assign(mask, selected * tensor_2.clone());
+        let grads = tensor_3.sum().backward();
+
+        let grad_1 = tensor_1.grad(&grads).unwrap();
+        let grad_2 = tensor_2.grad(&grads).unwrap();
+
+        grad_1
+            .to_data()
+            .assert_eq(&TensorData::from([[4.0, 1.0], [1.0, 5.0]]), false);
+        grad_2
+            .to_data()
+            .assert_eq(&TensorData::from([1.0, 3.0]), false);
+    }
 }
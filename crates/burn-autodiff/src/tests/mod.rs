@@ -24,9 +24,11 @@ mod cos;
 mod cross_entropy;
 mod deform_conv2d;
 mod div;
+mod einsum;
 mod erf;
 mod exp;
 mod expand;
+mod fft;
 mod flip;
 mod floor;
 mod gather_scatter;
@@ -54,6 +56,7 @@ mod remainder;
 mod repeat_dim;
 mod reshape;
 mod round;
+mod scatter_reduce;
 mod select;
 mod sigmoid;
 mod sign;
@@ -192,11 +195,14 @@ macro_rules! testgen_with_float_param {
         burn_autodiff::testgen_ad_cos!();
         burn_autodiff::testgen_ad_cross_entropy_loss!();
         burn_autodiff::testgen_ad_div!();
+        burn_autodiff::testgen_ad_einsum!();
+        burn_autodiff::testgen_ad_fft!();
         burn_autodiff::testgen_ad_remainder!();
         burn_autodiff::testgen_ad_erf!();
         burn_autodiff::testgen_ad_exp!();
         burn_autodiff::testgen_ad_slice!();
         burn_autodiff::testgen_ad_gather_scatter!();
+        burn_autodiff::testgen_ad_scatter_reduce!();
         burn_autodiff::testgen_ad_select!();
         burn_autodiff::testgen_ad_log!();
         burn_autodiff::testgen_ad_log1p!();
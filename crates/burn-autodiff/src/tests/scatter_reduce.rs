@@ -0,0 +1,79 @@
+#[burn_tensor_testgen::testgen(ad_scatter_reduce)]
+mod tests {
+    use super::*;
+    use burn_tensor::{Int, ScatterReduce, Tensor, TensorData, Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    #[test]
+    fn should_diff_scatter_reduce_max_with_ties() {
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<1>::from_data([0.0, 0.0, 0.0], &device).require_grad();
+        let values = TestAutodiffTensor::from_data([1.0, -1.0, 1.0, 3.0], &device).require_grad();
+        let indices = Tensor::<TestAutodiffBackend, 1, Int>::from_data([0, 2, 0, 2], &device);
+
+        let tensor_2 =
+            tensor_1
+                .clone()
+                .scatter_reduce(0, indices, values.clone(), ScatterReduce::Max);
+        let grads = tensor_2.sum().backward();
+
+        let grad_1 = tensor_1.grad(&grads).unwrap();
+        let grad_values = values.grad(&grads).unwrap();
+
+        grad_1
+            .to_data()
+            .assert_approx_eq::<FT>(&TensorData::from([0.0, 1.0, 0.0]), Tolerance::default());
+        grad_values.to_data().assert_approx_eq::<FT>(
+            &TensorData::from([0.5, 0.0, 0.5, 1.0]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn should_diff_scatter_reduce_prod_with_zero() {
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<1>::from_data([2.0, 3.0], &device).require_grad();
+        let values = TestAutodiffTensor::from_data([0.0, 4.0, 5.0], &device).require_grad();
+        let indices = Tensor::<TestAutodiffBackend, 1, Int>::from_data([0, 0, 1], &device);
+
+        let tensor_2 =
+            tensor_1
+                .clone()
+                .scatter_reduce(0, indices, values.clone(), ScatterReduce::Prod);
+        let grads = tensor_2.sum().backward();
+
+        let grad_1 = tensor_1.grad(&grads).unwrap();
+        let grad_values = values.grad(&grads).unwrap();
+
+        grad_1
+            .to_data()
+            .assert_approx_eq::<FT>(&TensorData::from([0.0, 5.0]), Tolerance::default());
+        grad_values
+            .to_data()
+            .assert_approx_eq::<FT>(&TensorData::from([8.0, 0.0, 3.0]), Tolerance::default());
+    }
+
+    #[test]
+    fn should_diff_scatter_reduce_mean() {
+        let device = Default::default();
+        let tensor_1 = TestAutodiffTensor::<1>::from_data([1.0, 2.0], &device).require_grad();
+        let values = TestAutodiffTensor::from_data([3.0, 5.0, 7.0], &device).require_grad();
+        let indices = Tensor::<TestAutodiffBackend, 1, Int>::from_data([0, 0, 0], &device);
+
+        let tensor_2 =
+            tensor_1
+                .clone()
+                .scatter_reduce(0, indices, values.clone(), ScatterReduce::Mean);
+        let grads = tensor_2.sum().backward();
+
+        let grad_1 = tensor_1.grad(&grads).unwrap();
+        let grad_values = values.grad(&grads).unwrap();
+
+        grad_1
+            .to_data()
+            .assert_approx_eq::<FT>(&TensorData::from([0.25, 1.0]), Tolerance::default());
+        grad_values
+            .to_data()
+            .assert_approx_eq::<FT>(&TensorData::from([0.25, 0.25, 0.25]), Tolerance::default());
+    }
+}
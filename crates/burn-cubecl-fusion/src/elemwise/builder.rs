@@ -1,5 +1,6 @@
 use burn_fusion::OptimizationBuilder;
 use cubecl::Runtime;
+use std::sync::Arc;
 
 use crate::{
     CubeOptimization,
@@ -58,8 +59,12 @@ impl<R: Runtime> OptimizationBuilder<CubeOptimization<R>> for ElementWiseBuilder
     fn build(&self) -> CubeOptimization<R> {
         let client = R::client(&self.device);
         let trace = self.builder.build();
-        let elementwise =
-            ElemwiseOptimization::<R>::new(trace, client, self.device.clone(), self.len());
+        let elementwise = ElemwiseOptimization::<R>::new(
+            Arc::new(trace),
+            client,
+            self.device.clone(),
+            self.len(),
+        );
 
         CubeOptimization::ElementWise(elementwise)
     }
@@ -76,6 +81,10 @@ impl<R: Runtime> OptimizationBuilder<CubeOptimization<R>> for ElementWiseBuilder
         self.builder.properties()
     }
 
+    fn cost_hint(&self) -> burn_fusion::OptimizationCostHint {
+        self.builder.cost_hint()
+    }
+
     fn len(&self) -> usize {
         self.builder.len()
     }
@@ -1,2 +1,4 @@
 pub mod builder;
 pub mod optimization;
+
+pub(crate) mod tune;
@@ -1,22 +1,31 @@
+use std::sync::Arc;
+
 use crate::CubeFusionHandle;
 use crate::shared::io::ref_len;
 use crate::shared::ir::{GlobalArgs, RefLayout};
 use crate::shared::kernel::fuse_on_write;
 use crate::shared::kernel::init_locals;
 use crate::shared::trace::Vectorization;
+use crate::shared::trace::vectorization::{Vect, vectorization_default};
 use burn_fusion::stream::Context;
+use burn_ir::{TensorId, TensorIr};
+use cubecl::ir::Elem;
 use cubecl::{CubeDim, calculate_cube_count_elemwise, client::ComputeClient, prelude::*};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use crate::shared::{
     ir::{Arg, FuseBlockConfig, GlobalArgsLaunch},
-    trace::{FuseTrace, TraceRunner},
+    trace::{FuseTrace, TraceRunner, TuneOutput},
 };
 
+#[cfg(feature = "autotune")]
+use super::tune::fused_elemwise_autotune;
+
 #[derive(new)]
 /// Fuse element wise operations into a single kernel.
 pub struct ElemwiseOptimization<R: Runtime> {
-    trace: FuseTrace,
+    trace: Arc<FuseTrace>,
     client: ComputeClient<R::Server, R::Channel>,
     device: R::Device,
     len: usize,
@@ -29,11 +38,35 @@ pub struct ElemwiseOptimizationState {
     len: usize,
 }
 
+/// The arguments of the [elemwise optimization](ElemwiseOptimization) when tuning its launch
+/// configuration.
+pub struct ElemwiseOptimizationTuneArg<R: Runtime> {
+    pub(crate) trace: Arc<FuseTrace>,
+    pub(crate) client: ComputeClient<R::Server, R::Channel>,
+    pub(crate) device: R::Device,
+}
+
 impl<R: Runtime> ElemwiseOptimization<R> {
     /// Execute the optimization.
     pub fn execute<BT: CubeElement>(&mut self, context: &mut Context<'_, CubeFusionHandle<R>>) {
+        #[cfg(feature = "autotune")]
+        fused_elemwise_autotune::<R, BT>(
+            ElemwiseOptimizationTuneArg {
+                trace: self.trace.clone(),
+                client: self.client.clone(),
+                device: self.device.clone(),
+            },
+            context,
+        );
+
+        #[cfg(not(feature = "autotune"))]
         self.trace
-            .run::<R, BT, ElemwiseRunner>(&self.client, &self.device, context, &ElemwiseRunner)
+            .run::<R, BT, ElemwiseRunner>(
+                &self.client,
+                &self.device,
+                context,
+                &ElemwiseRunner::default(),
+            )
             .unwrap();
     }
 
@@ -45,7 +78,7 @@ impl<R: Runtime> ElemwiseOptimization<R> {
     /// Create an optimization from its [state](ElemwiseOptimizationState).
     pub fn from_state(device: &R::Device, state: ElemwiseOptimizationState) -> Self {
         Self {
-            trace: state.trace,
+            trace: Arc::new(state.trace),
             len: state.len,
             client: R::client(device),
             device: device.clone(),
@@ -55,15 +88,61 @@ impl<R: Runtime> ElemwiseOptimization<R> {
     /// Convert the optimization to its [state](ElemwiseOptimizationState).
     pub fn to_state(&self) -> ElemwiseOptimizationState {
         ElemwiseOptimizationState {
-            trace: self.trace.clone(),
+            trace: self.trace.as_ref().clone(),
             len: self.len,
         }
     }
 }
 
-pub struct ElemwiseRunner;
+impl<R: Runtime> ElemwiseOptimizationTuneArg<R> {
+    pub(crate) fn execute<BT: CubeElement>(
+        &self,
+        context: &mut Context<'_, CubeFusionHandle<R>>,
+        runner: &ElemwiseRunner,
+    ) -> Result<TuneOutput<R>, String> {
+        self.trace
+            .run::<R, BT, ElemwiseRunner>(&self.client, &self.device, context, runner)
+            .map_err(|err| format!("{err:?}"))
+    }
+}
 
-impl<R: Runtime> Vectorization<R> for ElemwiseRunner {}
+/// Launch the fused element wise kernel, with the biggest line size and the default cube
+/// dimension unless [tuned](super::tune::fused_elemwise_autotune) otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ElemwiseRunner {
+    pub(crate) cube_dim: CubeDim,
+    pub(crate) max_line_size: Option<u8>,
+}
+
+impl<R: Runtime> Vectorization<R> for ElemwiseRunner {
+    fn vectorization<'a>(
+        &self,
+        _context: &Context<'_, CubeFusionHandle<R>>,
+        vectorizations: &mut BTreeMap<TensorId, Vect>,
+        handles_inputs: impl Iterator<Item = &'a CubeFusionHandle<R>>,
+        inputs: impl Iterator<Item = &'a TensorIr>,
+        outputs: impl Iterator<Item = &'a TensorIr>,
+        reshaped: impl Iterator<Item = (&'a TensorIr, &'a TensorIr, bool)>,
+        swapped: impl Iterator<Item = (&'a TensorIr, &'a TensorIr, bool, &'a (u32, u32))>,
+        ref_elem: &Elem,
+        max: u8,
+        axis: Option<usize>,
+    ) {
+        vectorization_default(
+            vectorizations,
+            handles_inputs,
+            inputs,
+            outputs,
+            reshaped,
+            swapped,
+            ref_elem,
+            &Default::default(),
+            self.max_line_size
+                .map_or(max, |line_size| Ord::min(max, line_size)),
+            axis,
+        )
+    }
+}
 impl<R: Runtime> TraceRunner<R> for ElemwiseRunner {
     type Error = (); // No error possible
 
@@ -84,7 +163,7 @@ impl<R: Runtime> TraceRunner<R> for ElemwiseRunner {
             RefLayout::Virtual(_) => inputs.shape_ref(&config.ref_layout, config.rank as usize),
         };
         let total_elem = shape.iter().product::<usize>() / config.width as usize;
-        let cube_dim = CubeDim::default();
+        let cube_dim = self.cube_dim;
         let cube_count = calculate_cube_count_elemwise(total_elem, cube_dim);
 
         unsafe {
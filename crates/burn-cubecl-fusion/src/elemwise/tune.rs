@@ -0,0 +1,185 @@
+use crate::{
+    CubeFusionHandle,
+    elemwise::optimization::ElemwiseRunner,
+    shared::trace::TuneOutput,
+    tune::{TuneContext, TuneInput},
+};
+use burn_fusion::stream::Context;
+use cubecl::{
+    AutotuneKey, CubeDim, CubeElement, CubeTuneId, Runtime,
+    tune::{LocalTuner, Tunable, TunableSet, local_tuner},
+};
+use serde::{Deserialize, Serialize};
+
+use super::optimization::ElemwiseOptimizationTuneArg;
+
+/// The key of the launch configurations tuned for fused element wise kernels.
+///
+/// Kernels are bucketed by the number of elements of their biggest output, so the configuration
+/// tuned for a shape is reused for shapes of the same magnitude, including after a restart when
+/// the autotune cache is persisted.
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize, AutotuneKey)]
+pub struct FusedElemwiseAutotuneKey {
+    #[autotune(anchor)]
+    num_elems: usize,
+    rank: usize,
+    #[autotune(anchor)]
+    fuse_num_reads: usize,
+    #[autotune(anchor)]
+    fuse_num_writes: usize,
+    #[autotune(anchor)]
+    fuse_num_ops: usize,
+}
+
+/// Executes autotune on fused element wise operations, selecting the line size and cube
+/// dimension of the kernel.
+pub fn fused_elemwise_autotune<R: Runtime, BT: CubeElement>(
+    arg: ElemwiseOptimizationTuneArg<R>,
+    context: &mut Context<CubeFusionHandle<R>>,
+) {
+    static TUNER: LocalTuner<FusedElemwiseAutotuneKey, CubeTuneId> = local_tuner!();
+
+    let tunables = TUNER.init(|| {
+        TunableSet::new(create_key::<R>, input_gen::<R>)
+            .with(Tunable::new(tune_default::<R, BT>)) // First one should always work.
+            .with(Tunable::new(tune_small_cube::<R, BT>))
+            .with(Tunable::new(tune_tiny_cube::<R, BT>))
+            .with(Tunable::new(tune_no_vectorization::<R, BT>))
+    });
+
+    TUNER.execute(
+        &CubeTuneId::new::<R>(&arg.client, &arg.device),
+        &arg.client.clone(),
+        tunables,
+        TuneInput::new(context, arg),
+    );
+}
+
+pub(crate) fn create_key<R: Runtime>(
+    input: &TuneInput<R, ElemwiseOptimizationTuneArg<R>>,
+) -> FusedElemwiseAutotuneKey {
+    let opt = input.optimization();
+    let context = match input.context() {
+        TuneContext::Original(context) => context,
+        TuneContext::Fork(_) => panic!("Not supported when generating key"),
+    };
+
+    let (num_elems, rank) = biggest_output(
+        opt.trace
+            .resources
+            .outputs
+            .iter()
+            .filter_map(|(tensor, _)| context.tensors.get(&tensor.id))
+            .map(|tensor| tensor.shape.as_slice()),
+    );
+    let blocks = &opt.trace.blocks;
+
+    FusedElemwiseAutotuneKey::new(
+        num_elems,
+        rank,
+        blocks.iter().map(|block| block.reads.len()).sum(),
+        blocks.iter().map(|block| block.writes.len()).sum(),
+        blocks.iter().map(|block| block.ops.len()).sum(),
+    )
+}
+
+/// The number of elements and the rank of the biggest of the output shapes.
+fn biggest_output<'a>(shapes: impl Iterator<Item = &'a [usize]>) -> (usize, usize) {
+    shapes
+        .map(|shape| (shape.iter().product::<usize>(), shape.len()))
+        .max()
+        .unwrap_or_default()
+}
+
+fn input_gen<R: Runtime>(
+    _key: &FusedElemwiseAutotuneKey,
+    input: &TuneInput<R, ElemwiseOptimizationTuneArg<R>>,
+) -> TuneInput<R, ElemwiseOptimizationTuneArg<R>> {
+    input.clone()
+}
+
+fn tune_default<R: Runtime, BT: CubeElement>(
+    input: TuneInput<R, ElemwiseOptimizationTuneArg<R>>,
+) -> Result<TuneOutput<R>, String> {
+    tune_runner::<R, BT>(input, ElemwiseRunner::default())
+}
+
+fn tune_small_cube<R: Runtime, BT: CubeElement>(
+    input: TuneInput<R, ElemwiseOptimizationTuneArg<R>>,
+) -> Result<TuneOutput<R>, String> {
+    let runner = ElemwiseRunner {
+        cube_dim: CubeDim::new_1d(128),
+        ..Default::default()
+    };
+
+    tune_runner::<R, BT>(input, runner)
+}
+
+fn tune_tiny_cube<R: Runtime, BT: CubeElement>(
+    input: TuneInput<R, ElemwiseOptimizationTuneArg<R>>,
+) -> Result<TuneOutput<R>, String> {
+    let runner = ElemwiseRunner {
+        cube_dim: CubeDim::new_1d(64),
+        ..Default::default()
+    };
+
+    tune_runner::<R, BT>(input, runner)
+}
+
+fn tune_no_vectorization<R: Runtime, BT: CubeElement>(
+    input: TuneInput<R, ElemwiseOptimizationTuneArg<R>>,
+) -> Result<TuneOutput<R>, String> {
+    let runner = ElemwiseRunner {
+        max_line_size: Some(1),
+        ..Default::default()
+    };
+
+    tune_runner::<R, BT>(input, runner)
+}
+
+fn tune_runner<R: Runtime, BT: CubeElement>(
+    input: TuneInput<R, ElemwiseOptimizationTuneArg<R>>,
+    runner: ElemwiseRunner,
+) -> Result<TuneOutput<R>, String> {
+    let optimization = input.optimization();
+    let context = input.context();
+
+    match context {
+        TuneContext::Original(context) => optimization.execute::<BT>(context, &runner),
+        TuneContext::Fork(mut context_owned) => {
+            optimization.execute::<BT>(&mut context_owned.as_context(), &runner)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(shapes: &[&[usize]]) -> FusedElemwiseAutotuneKey {
+        let (num_elems, rank) = biggest_output(shapes.iter().copied());
+
+        FusedElemwiseAutotuneKey::new(num_elems, rank, 2, 1, 3)
+    }
+
+    #[test]
+    fn should_bucket_keys_by_the_biggest_output() {
+        let tuned = key(&[&[32, 32], &[32]]);
+
+        // The winner tuned for a shape is reused for shapes of the same magnitude.
+        assert_eq!(key(&[&[30, 33]]), tuned);
+        assert_eq!(key(&[&[4], &[31, 32]]), tuned);
+        assert_ne!(key(&[&[33, 32]]), tuned);
+        assert_ne!(key(&[&[1024]]), tuned);
+    }
+
+    #[test]
+    fn should_keep_the_bucket_of_persisted_keys() {
+        let tuned = key(&[&[30, 33]]);
+
+        let json = serde_json::to_string(&tuned).unwrap();
+        let persisted: FusedElemwiseAutotuneKey = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(persisted, key(&[&[32, 32]]));
+    }
+}
@@ -173,7 +173,7 @@ impl<R: Runtime> MatmulOptimizationTuneArg<R> {
                 &self.info.client,
                 &self.info.device,
                 context,
-                &ElemwiseRunner,
+                &ElemwiseRunner::default(),
             )
             .unwrap();
 
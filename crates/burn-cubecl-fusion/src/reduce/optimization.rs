@@ -176,7 +176,7 @@ impl<R: Runtime> ReduceOptimizationTuneArg<R> {
                 &self.info.client,
                 &self.info.device,
                 context,
-                &ElemwiseRunner,
+                &ElemwiseRunner::default(),
             )
             .unwrap();
 
@@ -202,7 +202,7 @@ impl<R: Runtime> ReduceOptimizationTuneArg<R> {
                 &self.info.client,
                 &self.info.device,
                 context,
-                &ElemwiseRunner,
+                &ElemwiseRunner::default(),
             )
             .unwrap();
 
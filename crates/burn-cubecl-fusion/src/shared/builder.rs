@@ -3,7 +3,9 @@ use super::{
     settings::FuseSettings,
     trace::{FuseTrace, FuseTraceBuilder},
 };
-use burn_fusion::{OptimizationBuilder, OptimizationProperties, OptimizationStatus};
+use burn_fusion::{
+    OptimizationBuilder, OptimizationCostHint, OptimizationProperties, OptimizationStatus,
+};
 use burn_ir::{
     BaseOperationIr, BinaryOpIr, FloatOperationIr, NumericOperationIr, OperationIr, ScalarOpIr,
     TensorIr, UnaryOpIr,
@@ -122,6 +124,13 @@ impl OptimizationBuilder<FuseTrace> for FuseOptimizationBuilder {
         }
     }
 
+    fn cost_hint(&self) -> OptimizationCostHint {
+        OptimizationCostHint {
+            num_launches: 1,
+            register_pressure: Some(self.builder.builder.estimate_bindings()),
+        }
+    }
+
     fn clone_dyn(&self) -> Box<dyn OptimizationBuilder<FuseTrace>> {
         Box::new(self.clone())
     }
@@ -220,27 +229,21 @@ impl FuseOptimizationBuilder {
                 FuseOp::Assign(UnaryFuseArgs { input, out })
             }),
             BaseOperationIr::SwapDims(desc) => {
-                if !self.output_is_compatible(&desc.out) {
-                    return false;
-                }
-
-                if self.input_is_quantized(&desc.input) {
-                    return false;
-                }
-
-                if self.builder.register(|build| {
-                    build.input_swap_dims(
-                        &desc.input,
-                        &desc.out,
-                        (desc.dim1 as u32, desc.dim2 as u32),
-                    )?;
-
-                    Some(())
-                }) {
-                    self.num_views += 1;
-                    true
-                } else {
-                    false
+                self.register_swap_dims(&desc.input, &desc.out, (desc.dim1, desc.dim2))
+            }
+            BaseOperationIr::Permute(desc) => {
+                // Only permutations swapping two dimensions are folded into the indexing.
+                let swapped = desc
+                    .axes
+                    .iter()
+                    .enumerate()
+                    .filter(|(dim, axis)| dim != *axis)
+                    .map(|(dim, _)| dim)
+                    .collect::<Vec<_>>();
+
+                match swapped.as_slice() {
+                    [dim1, dim2] => self.register_swap_dims(&desc.input, &desc.out, (*dim1, *dim2)),
+                    _ => false,
                 }
             }
             BaseOperationIr::Reshape(desc) => {
@@ -277,6 +280,32 @@ impl FuseOptimizationBuilder {
         }
     }
 
+    fn register_swap_dims(
+        &mut self,
+        input: &TensorIr,
+        out: &TensorIr,
+        dims: (usize, usize),
+    ) -> bool {
+        if !self.output_is_compatible(out) {
+            return false;
+        }
+
+        if self.input_is_quantized(input) {
+            return false;
+        }
+
+        if self.builder.register(|build| {
+            build.input_swap_dims(input, out, (dims.0 as u32, dims.1 as u32))?;
+
+            Some(())
+        }) {
+            self.num_views += 1;
+            true
+        } else {
+            false
+        }
+    }
+
     fn register_float(&mut self, ops: &FloatOperationIr) -> bool {
         match ops {
             FloatOperationIr::Exp(desc) => self
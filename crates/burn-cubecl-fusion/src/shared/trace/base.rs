@@ -181,6 +181,9 @@ impl FuseTrace {
         VectorizationPlanner::<R>::new(&self.resources, &self.blocks)
             .run(runner, context, &mut plan);
 
+        #[cfg(feature = "guard-checks")]
+        let guards = core::mem::take(&mut plan.guards);
+
         match LaunchPlanExecutor::<R>::new(&self.resources, &self.blocks)
             .execute::<_, BT>(client, runner, context, plan)
         {
@@ -188,7 +191,12 @@ impl FuseTrace {
                 self.rollback(context, err.handles_input, err.handles_output);
                 Err(err.error)
             }
-            Ok(val) => Ok(val),
+            Ok(val) => {
+                #[cfg(feature = "guard-checks")]
+                guards.validate::<R>(client, core::any::type_name::<Runner>());
+
+                Ok(val)
+            }
         }
     }
 
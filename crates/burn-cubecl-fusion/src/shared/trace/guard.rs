@@ -0,0 +1,110 @@
+use burn_ir::TensorId;
+use cubecl::{Runtime, client::ComputeClient, server::Handle};
+
+/// The number of guard bytes allocated before and after each output buffer.
+///
+/// It matches the largest storage buffer offset alignment required by the supported runtimes, so
+/// that the output buffer can start right after the leading guard region.
+pub const GUARD_SIZE: usize = 256;
+
+/// The value written in every byte of the guard regions.
+pub const GUARD_POISON: u8 = 0xCD;
+
+/// An output buffer surrounded by guard regions filled with [poison](GUARD_POISON).
+///
+/// # Notes
+///
+/// Backends where bindings are bound checked (e.g. wgpu) silently discard out-of-bounds writes,
+/// so the guards can only catch them on backends writing through raw pointers (e.g. cuda).
+#[derive(Debug)]
+pub struct GuardedOutput {
+    global_id: TensorId,
+    /// The whole buffer, including the guard regions.
+    buffer: Handle,
+    size: usize,
+}
+
+/// The output buffers of a fused kernel, validated after the kernel is launched.
+#[derive(Debug, Default)]
+pub struct GuardedOutputs {
+    outputs: Vec<GuardedOutput>,
+}
+
+impl GuardedOutputs {
+    /// Allocate a new guarded buffer of `size` bytes for the given output tensor.
+    pub fn allocate<R: Runtime>(
+        &mut self,
+        client: &ComputeClient<R::Server, R::Channel>,
+        global_id: TensorId,
+        size: usize,
+    ) -> Handle {
+        let data = vec![GUARD_POISON; size + 2 * GUARD_SIZE];
+        let buffer = client.create(&data);
+
+        self.outputs.push(GuardedOutput {
+            global_id,
+            buffer: buffer.clone(),
+            size,
+        });
+
+        buffer
+            .offset_start(GUARD_SIZE as u64)
+            .offset_end(GUARD_SIZE as u64)
+    }
+
+    /// Check that no guard region was overwritten by the fused kernel.
+    ///
+    /// # Panics
+    ///
+    /// If a guard region was overwritten, naming the kernel and the output tensor.
+    pub fn validate<R: Runtime>(self, client: &ComputeClient<R::Server, R::Channel>, kernel: &str) {
+        for output in self.outputs {
+            let data = client.read_one(output.buffer.binding());
+            let (num_before, num_after) = overwritten_guard_bytes(&data, output.size);
+
+            if num_before + num_after > 0 {
+                panic!(
+                    "Fused kernel `{kernel}` wrote out of bounds of the output tensor {:?} ({} bytes): \
+                     {num_before} guard bytes overwritten before the buffer and {num_after} after.",
+                    output.global_id, output.size,
+                );
+            }
+        }
+    }
+}
+
+/// The number of guard bytes overwritten before and after an output of `size` bytes, given the
+/// content of its whole guarded buffer.
+fn overwritten_guard_bytes(data: &[u8], size: usize) -> (usize, usize) {
+    let before = &data[..GUARD_SIZE];
+    let after = &data[GUARD_SIZE + size..GUARD_SIZE * 2 + size];
+
+    let num_before = before.iter().filter(|b| **b != GUARD_POISON).count();
+    let num_after = after.iter().filter(|b| **b != GUARD_POISON).count();
+
+    (num_before, num_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_not_report_writes_inside_the_output() {
+        let size = 16;
+        let mut data = vec![GUARD_POISON; size + 2 * GUARD_SIZE];
+        data[GUARD_SIZE..GUARD_SIZE + size].fill(0);
+
+        assert_eq!(overwritten_guard_bytes(&data, size), (0, 0));
+    }
+
+    #[test]
+    fn should_report_writes_in_guard_regions() {
+        let size = 16;
+        let mut data = vec![GUARD_POISON; size + 2 * GUARD_SIZE];
+        data[GUARD_SIZE - 1] = 0;
+        data[GUARD_SIZE + size..GUARD_SIZE + size + 4].fill(0);
+
+        assert_eq!(overwritten_guard_bytes(&data, size), (1, 4));
+    }
+}
@@ -9,6 +9,11 @@ mod builder;
 mod plan;
 mod runner;
 
+#[cfg(feature = "guard-checks")]
+mod guard;
+#[cfg(feature = "guard-checks")]
+pub use guard::*;
+
 pub use base::*;
 pub use builder::*;
 pub use plan::*;
@@ -414,10 +414,21 @@ impl<'a, R: Runtime> OutputPlanner<'a, R> {
             _ => tensor_global.dtype,
         };
         let size = tensor_global.shape.iter().product::<usize>() * Elem::from(dtype).size();
+        // Pooled buffers may come from strided tensors, so the size must be checked.
+        #[cfg(not(feature = "guard-checks"))]
+        let buffer = match context.pool.take_if(&tensor_global, |pooled| {
+            pooled.handle.size() as usize >= size
+        }) {
+            Some(pooled) => pooled.handle,
+            None => client.empty(size),
+        };
+        // Pooled buffers aren't guarded, so every output gets a fresh allocation.
+        #[cfg(feature = "guard-checks")]
+        let buffer = plan.guards.allocate::<R>(client, tensor_global.id, size);
 
         let handle = CubeFusionHandle {
             client: client.clone(),
-            handle: client.empty(size),
+            handle: buffer,
             device: device.clone(),
             strides,
             dtype,
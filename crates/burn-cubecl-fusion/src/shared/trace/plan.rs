@@ -21,6 +21,8 @@ pub(crate) struct LaunchPlan<'a, R: Runtime> {
     pub blocks: Vec<BlockPlan<'a>>,
     pub vectorizations: BTreeMap<TensorId, Vect>,
     pub cleared: Vec<TensorId>,
+    #[cfg(feature = "guard-checks")]
+    pub guards: super::GuardedOutputs,
 }
 
 #[derive(Debug)]
@@ -111,6 +113,8 @@ impl<R: Runtime> LaunchPlan<'_, R> {
             blocks,
             vectorizations: Default::default(),
             cleared: Default::default(),
+            #[cfg(feature = "guard-checks")]
+            guards: Default::default(),
         }
     }
 }
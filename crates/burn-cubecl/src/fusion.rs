@@ -10,11 +10,12 @@ use burn_cubecl_fusion::{CubeFusionHandle, FallbackOperation};
 use burn_cubecl_fusion::{
     CubeOptimization, CubeOptimizationState, elemwise::builder::ElementWiseBuilder,
 };
-use burn_fusion::stream::{Operation, OrderedExecution};
+use burn_fusion::stream::{Operation, OrderedExecution, TimingSource};
 use burn_fusion::{FusionBackend, FusionRuntime, client::MutexFusionClient};
 use burn_ir::{BackendIr, TensorHandle};
 use burn_tensor::{DType, Shape};
-use core::marker::PhantomData;
+use core::{marker::PhantomData, time::Duration};
+use cubecl::benchmark::TimingMethod;
 use half::{bf16, f16};
 use std::sync::Arc;
 
@@ -147,6 +148,71 @@ impl<R: CubeRuntime, BT: BoolElement> FusionRuntime for FusionCubeRuntime<R, BT>
             )),
         ]
     }
+
+    fn can_recycle(handle: &Self::FusionHandle) -> bool {
+        handle.handle.can_mut()
+    }
+
+    fn handle_size(handle: &Self::FusionHandle) -> Option<u64> {
+        Some(handle.handle.size())
+    }
+
+    fn reallocate(handle: &Self::FusionHandle) -> Option<Self::FusionHandle> {
+        // Moving a shared buffer would duplicate it.
+        if !handle.handle.can_mut() {
+            return None;
+        }
+
+        let size = handle.handle.size() as usize;
+        let num_words = size / core::mem::size_of::<u32>();
+        if num_words == 0 || num_words * core::mem::size_of::<u32>() != size {
+            return None;
+        }
+
+        // The buffer is copied as a flat list of words, preserving the layout of the handle.
+        let buffer = CubeTensor::<R>::new_contiguous(
+            handle.client.clone(),
+            handle.device.clone(),
+            Shape::new([num_words]),
+            handle.handle.clone(),
+            DType::U32,
+        );
+        let copied = buffer.copy();
+
+        Some(CubeFusionHandle {
+            handle: copied.handle,
+            ..handle.clone()
+        })
+    }
+
+    fn memory_reserved(device: &Self::FusionDevice) -> Option<u64> {
+        Some(R::client(device).memory_usage().bytes_reserved)
+    }
+
+    fn memory_cleanup(device: &Self::FusionDevice) {
+        R::client(device).memory_cleanup();
+    }
+
+    fn profile<O>(
+        device: &Self::FusionDevice,
+        func: impl FnOnce() -> O,
+    ) -> (O, Option<(Duration, TimingSource)>) {
+        let mut output = None;
+        let profiled = R::client(device).profile(|| output = Some(func()), "fusion_plan");
+        let output = output.expect("The profiled function should be executed");
+
+        let measured = profiled.ok().map(|duration| {
+            let source = match duration.timing_method() {
+                TimingMethod::System => TimingSource::DeviceSync,
+                TimingMethod::Device => TimingSource::DeviceTimestamps,
+            };
+            let ticks = futures_lite::future::block_on(duration.resolve());
+
+            (ticks.duration(), source)
+        });
+
+        (output, measured)
+    }
 }
 
 /// Fusion runtime for JIT runtimes.
@@ -0,0 +1,623 @@
+use crate::{
+    CubeRuntime, FloatElement,
+    kernel::{into_contiguous, slice},
+    ops::numeric::empty_device,
+    tensor::CubeTensor,
+};
+use burn_tensor::Shape;
+use cubecl::{calculate_cube_count_elemwise, prelude::*};
+
+/// Each unit computes one radix-2 butterfly of a Stockham pass over a contiguous complex tensor,
+/// whose last dimension holds the real and imaginary parts.
+///
+/// The pass combines the sub-transforms of size `stride` into ones of size `2 * stride`, writing
+/// them in their final place, so the frequencies are in their natural order after the last pass
+/// without any bit-reversal permutation.
+#[cube(launch_unchecked)]
+fn stockham_kernel<F: Float>(
+    input: &Tensor<F>,
+    output: &mut Tensor<F>,
+    step: F,
+    scale: F,
+    n: u32,
+    dim_stride: u32,
+    stride: u32,
+) {
+    // Each butterfly reads and writes two complex elements.
+    if ABSOLUTE_POS >= output.len() / 4 {
+        terminate!();
+    }
+
+    let half = n / 2;
+    let butterfly = ABSOLUTE_POS % half;
+    let batch = ABSOLUTE_POS / half;
+    // Consecutive complex elements along the dimension are `dim_stride` apart.
+    let num_inner = dim_stride / 2;
+    let offset = batch / num_inner * n * dim_stride + batch % num_inner * 2;
+
+    let p = butterfly / stride;
+    let q = butterfly % stride;
+    let lhs = offset + (q + stride * p) * dim_stride;
+    let rhs = lhs + half * dim_stride;
+    let (lhs_real, lhs_imag) = (input[lhs], input[lhs + 1]);
+    let (rhs_real, rhs_imag) = (input[rhs], input[rhs + 1]);
+
+    let angle = F::cast_from(p * stride) * step;
+    let cos: F = Cos::cos(angle);
+    let sin: F = Sin::sin(angle);
+    let diff_real = lhs_real - rhs_real;
+    let diff_imag = lhs_imag - rhs_imag;
+
+    let even = offset + (q + stride * 2 * p) * dim_stride;
+    let odd = even + stride * dim_stride;
+    output[even] = (lhs_real + rhs_real) * scale;
+    output[even + 1] = (lhs_imag + rhs_imag) * scale;
+    output[odd] = (diff_real * cos - diff_imag * sin) * scale;
+    output[odd + 1] = (diff_real * sin + diff_imag * cos) * scale;
+}
+
+/// Each unit copies one component of a real tensor into a contiguous complex tensor, whose
+/// imaginary parts are zeros.
+#[cube(launch_unchecked)]
+fn real_to_complex_kernel<F: Float>(
+    input: &Tensor<F>,
+    output: &mut Tensor<F>,
+    #[comptime] rank: u32,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    if ABSOLUTE_POS % 2 == 1 {
+        output[ABSOLUTE_POS] = F::new(0.0);
+    } else {
+        let mut offset = 0;
+
+        #[unroll]
+        for i in 0..rank {
+            offset += ABSOLUTE_POS / output.stride(i) % output.shape(i) * input.stride(i);
+        }
+
+        output[ABSOLUTE_POS] = input[offset];
+    }
+}
+
+/// Each unit computes one component of the full spectrum of a real signal from its
+/// non-redundant frequencies, the other ones being their conjugates.
+///
+/// Missing frequencies are zeros, and the imaginary parts of the frequencies that are their own
+/// conjugates are ignored, like when summing the non-redundant frequencies directly.
+#[cube(launch_unchecked)]
+fn hermitian_kernel<F: Float>(
+    input: &Tensor<F>,
+    output: &mut Tensor<F>,
+    #[comptime] dim: u32,
+    #[comptime] rank: u32,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    // The output is contiguous, with the size of the real signal along `dim`.
+    let freq = ABSOLUTE_POS / output.stride(dim) % output.shape(dim);
+    let component = ABSOLUTE_POS % 2;
+    let mut offset = 0;
+
+    #[unroll]
+    for i in 0..rank {
+        offset += ABSOLUTE_POS / output.stride(i) % output.shape(i) * input.stride(i);
+    }
+    offset -= freq * input.stride(dim);
+
+    let n = output.shape(dim);
+    let half = n / 2;
+    let mut source = freq;
+    if freq > half {
+        source = n - freq;
+    }
+
+    let mut value = F::new(0.0);
+    if source < input.shape(dim) {
+        value = input[offset + source * input.stride(dim)];
+    }
+    if component == 1 {
+        if source == 0 || source == half {
+            value = F::new(0.0);
+        } else if freq > half {
+            value = -value;
+        }
+    }
+
+    output[ABSOLUTE_POS] = value;
+}
+
+/// Each unit copies the real part of one element of a contiguous complex tensor.
+#[cube(launch_unchecked)]
+fn real_part_kernel<F: Float>(input: &Tensor<F>, output: &mut Tensor<F>) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    output[ABSOLUTE_POS] = input[ABSOLUTE_POS * 2];
+}
+
+/// Each unit computes one component of one frequency of a complex tensor, whose last dimension
+/// holds the real and imaginary parts, as a direct sum over the transformed dimension.
+#[cube(launch_unchecked)]
+fn dft_kernel<F: Float>(
+    input: &Tensor<F>,
+    output: &mut Tensor<F>,
+    step: F,
+    #[comptime] dim: u32,
+    #[comptime] rank: u32,
+    #[comptime] inverse: bool,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    // The output is contiguous with the same shape as the input.
+    let freq = ABSOLUTE_POS / output.stride(dim) % output.shape(dim);
+    let component = ABSOLUTE_POS % 2;
+    let mut offset = 0;
+
+    #[unroll]
+    for i in 0..rank {
+        offset += ABSOLUTE_POS / output.stride(i) % output.shape(i) * input.stride(i);
+    }
+    offset -= freq * input.stride(dim) + component * input.stride(rank - 1);
+
+    let n = input.shape(dim);
+    let stride = input.stride(dim);
+    let stride_imag = input.stride(rank - 1);
+    let mut sum = F::new(0.0);
+    // The angle index is kept reduced modulo `n` to stay accurate for large sizes.
+    let mut index = 0;
+
+    for j in 0..n {
+        let angle = F::cast_from(index) * step;
+        let cos: F = Cos::cos(angle);
+        let sin: F = Sin::sin(angle);
+        let real = input[offset + j * stride];
+        let imag = input[offset + j * stride + stride_imag];
+
+        // The forward transform multiplies with e^{-iθ}, the inverse one with e^{iθ}.
+        if comptime![inverse] {
+            if component == 0 {
+                sum += real * cos - imag * sin;
+            } else {
+                sum += imag * cos + real * sin;
+            }
+        } else if component == 0 {
+            sum += real * cos + imag * sin;
+        } else {
+            sum += imag * cos - real * sin;
+        }
+
+        index = (index + freq) % n;
+    }
+
+    if comptime![inverse] {
+        sum /= F::cast_from(n);
+    }
+
+    output[ABSOLUTE_POS] = sum;
+}
+
+/// Each unit computes one component of one non-redundant frequency of a real tensor, as a
+/// direct sum over the transformed dimension.
+#[cube(launch_unchecked)]
+fn rdft_kernel<F: Float>(
+    input: &Tensor<F>,
+    output: &mut Tensor<F>,
+    step: F,
+    #[comptime] dim: u32,
+    #[comptime] rank: u32,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    // The output is contiguous, with one more dimension holding the complex components.
+    let freq = ABSOLUTE_POS / output.stride(dim) % output.shape(dim);
+    let component = ABSOLUTE_POS % 2;
+    let mut offset = 0;
+
+    #[unroll]
+    for i in 0..rank {
+        offset += ABSOLUTE_POS / output.stride(i) % output.shape(i) * input.stride(i);
+    }
+    offset -= freq * input.stride(dim);
+
+    let n = input.shape(dim);
+    let stride = input.stride(dim);
+    let mut sum = F::new(0.0);
+    let mut index = 0;
+
+    for j in 0..n {
+        let angle = F::cast_from(index) * step;
+        let value = input[offset + j * stride];
+
+        if component == 0 {
+            let cos: F = Cos::cos(angle);
+            sum += value * cos;
+        } else {
+            let sin: F = Sin::sin(angle);
+            sum -= value * sin;
+        }
+
+        index = (index + freq) % n;
+    }
+
+    output[ABSOLUTE_POS] = sum;
+}
+
+/// Each unit computes one element of the real signal recovered from its non-redundant
+/// frequencies, as a direct sum over them, the other ones being their conjugates.
+#[cube(launch_unchecked)]
+fn irdft_kernel<F: Float>(
+    input: &Tensor<F>,
+    output: &mut Tensor<F>,
+    step: F,
+    #[comptime] dim: u32,
+    #[comptime] rank: u32,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    // The output has one less dimension than the input, without the complex components.
+    let position = ABSOLUTE_POS / output.stride(dim) % output.shape(dim);
+    let output_rank = comptime![rank - 1];
+    let mut offset = 0;
+
+    #[unroll]
+    for i in 0..output_rank {
+        offset += ABSOLUTE_POS / output.stride(i) % output.shape(i) * input.stride(i);
+    }
+    offset -= position * input.stride(dim);
+
+    let n = output.shape(dim);
+    let num_freqs = input.shape(dim);
+    let stride = input.stride(dim);
+    let stride_imag = input.stride(rank - 1);
+    let mut sum = F::new(0.0);
+    let mut index = 0;
+
+    for k in 0..num_freqs {
+        let angle = F::cast_from(index) * step;
+        let real = input[offset + k * stride];
+        let imag = input[offset + k * stride + stride_imag];
+        let cos: F = Cos::cos(angle);
+        let sin: F = Sin::sin(angle);
+        let value = real * cos - imag * sin;
+
+        // Frequencies with a conjugate in the other half of the spectrum count twice.
+        if k == 0 || 2 * k == n {
+            sum += value;
+        } else {
+            sum += value * F::new(2.0);
+        }
+
+        index = (index + position) % n;
+    }
+
+    output[ABSOLUTE_POS] = sum / F::cast_from(n);
+}
+
+/// Computes the discrete Fourier transform of a complex tensor along `dim`.
+///
+/// Sizes that are powers of two are transformed with a radix-2 FFT in `log2(n)` passes, the
+/// other ones with a direct DFT in `O(n²)`.
+pub(crate) fn fft<R: CubeRuntime, E: FloatElement>(
+    tensor: CubeTensor<R>,
+    dim: usize,
+    inverse: bool,
+) -> CubeTensor<R> {
+    let n = tensor.shape.dims[dim];
+
+    match is_radix_2(n) {
+        true => stockham::<R, E>(into_contiguous(tensor), dim, inverse),
+        false => dft::<R, E>(tensor, dim, inverse),
+    }
+}
+
+/// Computes the non-redundant frequencies of the transform of a real tensor along `dim`.
+pub(crate) fn rfft<R: CubeRuntime, E: FloatElement>(
+    tensor: CubeTensor<R>,
+    dim: usize,
+) -> CubeTensor<R> {
+    let n = tensor.shape.dims[dim];
+    if !is_radix_2(n) {
+        return rdft::<R, E>(tensor, dim);
+    }
+
+    let spectrum = stockham::<R, E>(real_to_complex::<R, E>(tensor), dim, false);
+    let mut ranges = spectrum
+        .shape
+        .dims
+        .iter()
+        .map(|size| 0..*size)
+        .collect::<Vec<_>>();
+    ranges[dim] = 0..n / 2 + 1;
+
+    slice::<R, E>(spectrum, &ranges)
+}
+
+/// Recovers the real signal of size `n` along `dim` from its non-redundant frequencies.
+pub(crate) fn irfft<R: CubeRuntime, E: FloatElement>(
+    tensor: CubeTensor<R>,
+    dim: usize,
+    n: usize,
+) -> CubeTensor<R> {
+    if !is_radix_2(n) {
+        return irdft::<R, E>(tensor, dim, n);
+    }
+
+    let spectrum = hermitian::<R, E>(tensor, dim, n);
+    real_part::<R, E>(stockham::<R, E>(spectrum, dim, true))
+}
+
+/// Whether the size can be transformed with the radix-2 FFT.
+fn is_radix_2(n: usize) -> bool {
+    n > 1 && n.is_power_of_two()
+}
+
+/// The radix-2 FFT of a contiguous complex tensor along `dim`, whose size is a power of two.
+fn stockham<R: CubeRuntime, E: FloatElement>(
+    tensor: CubeTensor<R>,
+    dim: usize,
+    inverse: bool,
+) -> CubeTensor<R> {
+    let n = tensor.shape.dims[dim];
+    let dim_stride = tensor.strides[dim];
+    let step = match inverse {
+        true => angle_step(n),
+        false => -angle_step(n),
+    };
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(tensor.shape.num_elements() / 4, cube_dim);
+    let mut input = tensor;
+    let mut spare = None;
+    let mut stride = 1;
+
+    while stride < n {
+        let output = spare.take().unwrap_or_else(|| {
+            empty_device::<R, E>(
+                input.client.clone(),
+                input.device.clone(),
+                input.shape.clone(),
+            )
+        });
+        // The inverse transform is normalized by the last pass.
+        let scale = match inverse && 2 * stride == n {
+            true => 1.0 / n as f64,
+            false => 1.0,
+        };
+
+        unsafe {
+            stockham_kernel::launch_unchecked::<E, R>(
+                &input.client,
+                cube_count.clone(),
+                cube_dim,
+                input.as_tensor_arg::<E>(1),
+                output.as_tensor_arg::<E>(1),
+                ScalarArg::new(E::from_elem(step)),
+                ScalarArg::new(E::from_elem(scale)),
+                ScalarArg::new(n as u32),
+                ScalarArg::new(dim_stride as u32),
+                ScalarArg::new(stride as u32),
+            );
+        }
+
+        let previous = core::mem::replace(&mut input, output);
+        // Only the buffers of the previous passes are reused, the input might be shared.
+        if stride > 1 {
+            spare = Some(previous);
+        }
+        stride *= 2;
+    }
+
+    input
+}
+
+/// A contiguous complex tensor with the values of the real tensor as real parts.
+fn real_to_complex<R: CubeRuntime, E: FloatElement>(tensor: CubeTensor<R>) -> CubeTensor<R> {
+    let rank = tensor.shape.num_dims();
+    let mut dims = tensor.shape.dims.clone();
+    dims.push(2);
+
+    let output = empty_device::<R, E>(
+        tensor.client.clone(),
+        tensor.device.clone(),
+        Shape::from(dims),
+    );
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(output.shape.num_elements(), cube_dim);
+
+    unsafe {
+        real_to_complex_kernel::launch_unchecked::<E, R>(
+            &tensor.client,
+            cube_count,
+            cube_dim,
+            tensor.as_tensor_arg::<E>(1),
+            output.as_tensor_arg::<E>(1),
+            rank as u32,
+        );
+    }
+
+    output
+}
+
+/// The contiguous spectrum of size `n` along `dim` of the real signal with the given
+/// non-redundant frequencies.
+fn hermitian<R: CubeRuntime, E: FloatElement>(
+    tensor: CubeTensor<R>,
+    dim: usize,
+    n: usize,
+) -> CubeTensor<R> {
+    let rank = tensor.shape.num_dims();
+    let mut dims = tensor.shape.dims.clone();
+    dims[dim] = n;
+
+    let output = empty_device::<R, E>(
+        tensor.client.clone(),
+        tensor.device.clone(),
+        Shape::from(dims),
+    );
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(output.shape.num_elements(), cube_dim);
+
+    unsafe {
+        hermitian_kernel::launch_unchecked::<E, R>(
+            &tensor.client,
+            cube_count,
+            cube_dim,
+            tensor.as_tensor_arg::<E>(1),
+            output.as_tensor_arg::<E>(1),
+            dim as u32,
+            rank as u32,
+        );
+    }
+
+    output
+}
+
+/// The real parts of a contiguous complex tensor.
+fn real_part<R: CubeRuntime, E: FloatElement>(tensor: CubeTensor<R>) -> CubeTensor<R> {
+    let mut dims = tensor.shape.dims.clone();
+    dims.pop();
+
+    let output = empty_device::<R, E>(
+        tensor.client.clone(),
+        tensor.device.clone(),
+        Shape::from(dims),
+    );
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(output.shape.num_elements(), cube_dim);
+
+    unsafe {
+        real_part_kernel::launch_unchecked::<E, R>(
+            &tensor.client,
+            cube_count,
+            cube_dim,
+            tensor.as_tensor_arg::<E>(1),
+            output.as_tensor_arg::<E>(1),
+        );
+    }
+
+    output
+}
+
+/// The direct DFT of a complex tensor along `dim`.
+fn dft<R: CubeRuntime, E: FloatElement>(
+    tensor: CubeTensor<R>,
+    dim: usize,
+    inverse: bool,
+) -> CubeTensor<R> {
+    let rank = tensor.shape.num_dims();
+    let n = tensor.shape.dims[dim];
+    let output = empty_device::<R, E>(
+        tensor.client.clone(),
+        tensor.device.clone(),
+        tensor.shape.clone(),
+    );
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(output.shape.num_elements(), cube_dim);
+
+    unsafe {
+        dft_kernel::launch_unchecked::<E, R>(
+            &tensor.client,
+            cube_count,
+            cube_dim,
+            tensor.as_tensor_arg::<E>(1),
+            output.as_tensor_arg::<E>(1),
+            ScalarArg::new(E::from_elem(angle_step(n))),
+            dim as u32,
+            rank as u32,
+            inverse,
+        );
+    }
+
+    output
+}
+
+/// The non-redundant frequencies of the direct DFT of a real tensor along `dim`.
+fn rdft<R: CubeRuntime, E: FloatElement>(tensor: CubeTensor<R>, dim: usize) -> CubeTensor<R> {
+    let rank = tensor.shape.num_dims();
+    let n = tensor.shape.dims[dim];
+    let mut dims = tensor.shape.dims.clone();
+    dims[dim] = n / 2 + 1;
+    dims.push(2);
+
+    let output = empty_device::<R, E>(
+        tensor.client.clone(),
+        tensor.device.clone(),
+        Shape::from(dims),
+    );
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(output.shape.num_elements(), cube_dim);
+
+    unsafe {
+        rdft_kernel::launch_unchecked::<E, R>(
+            &tensor.client,
+            cube_count,
+            cube_dim,
+            tensor.as_tensor_arg::<E>(1),
+            output.as_tensor_arg::<E>(1),
+            ScalarArg::new(E::from_elem(angle_step(n))),
+            dim as u32,
+            rank as u32,
+        );
+    }
+
+    output
+}
+
+/// The real signal of size `n` along `dim` recovered with a direct sum over its non-redundant
+/// frequencies.
+fn irdft<R: CubeRuntime, E: FloatElement>(
+    tensor: CubeTensor<R>,
+    dim: usize,
+    n: usize,
+) -> CubeTensor<R> {
+    let rank = tensor.shape.num_dims();
+    let mut dims = tensor.shape.dims.clone();
+    dims.pop();
+    dims[dim] = n;
+
+    let output = empty_device::<R, E>(
+        tensor.client.clone(),
+        tensor.device.clone(),
+        Shape::from(dims),
+    );
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(output.shape.num_elements(), cube_dim);
+
+    unsafe {
+        irdft_kernel::launch_unchecked::<E, R>(
+            &tensor.client,
+            cube_count,
+            cube_dim,
+            tensor.as_tensor_arg::<E>(1),
+            output.as_tensor_arg::<E>(1),
+            ScalarArg::new(E::from_elem(angle_step(n))),
+            dim as u32,
+            rank as u32,
+        );
+    }
+
+    output
+}
+
+/// The angle between two consecutive roots of unity of order `n`.
+fn angle_step(n: usize) -> f64 {
+    2.0 * core::f64::consts::PI / n.max(1) as f64
+}
@@ -2,8 +2,10 @@ use crate::{
     CubeRuntime, IntElement,
     element::CubeElement,
     kernel::{self},
+    ops::numeric::{div, ones_device},
     tensor::CubeTensor,
 };
+use burn_tensor::ScatterReduce;
 use cubecl::prelude::*;
 use cubecl::{CubeDim, calculate_cube_count_elemwise};
 
@@ -13,6 +15,7 @@ fn scatter_kernel<T: Numeric, I: Int>(
     indices: &Tensor<I>,
     value: &Tensor<T>,
     dim: &u32,
+    #[comptime] reduce: ScatterReduce,
 ) {
     let stride_input = input.stride(*dim);
     let shape_value = value.shape(*dim);
@@ -59,9 +62,19 @@ fn scatter_kernel<T: Numeric, I: Int>(
         let mut index_input = stride_input * result_indices;
         index_input += offset_input;
 
-        let mut result_input = input[index_input];
-        result_input += result_value;
-        input[index_input] = result_input;
+        let result_input = input[index_input];
+
+        // Each unit reduces a whole lane along the dimension in order, which keeps the result
+        // deterministic.
+        input[index_input] = if comptime![reduce == ScatterReduce::Prod] {
+            result_input * result_value
+        } else if comptime![reduce == ScatterReduce::Min] {
+            Min::min(result_input, result_value)
+        } else if comptime![reduce == ScatterReduce::Max] {
+            Max::max(result_input, result_value)
+        } else {
+            result_input + result_value
+        };
     }
 }
 
@@ -71,6 +84,30 @@ pub(crate) fn scatter<R: CubeRuntime, E: CubeElement, I: IntElement>(
     indices: CubeTensor<R>,
     value: CubeTensor<R>,
 ) -> CubeTensor<R> {
+    scatter_reduce::<R, E, I>(dim, tensor, indices, value, ScatterReduce::Sum)
+}
+
+pub(crate) fn scatter_reduce<R: CubeRuntime, E: CubeElement, I: IntElement>(
+    dim: usize,
+    tensor: CubeTensor<R>,
+    indices: CubeTensor<R>,
+    value: CubeTensor<R>,
+    reduce: ScatterReduce,
+) -> CubeTensor<R> {
+    if reduce == ScatterReduce::Mean {
+        // Each element of the tensor counts as one element of the mean.
+        let ones = |shape| ones_device::<R, E>(tensor.client.clone(), tensor.device.clone(), shape);
+        let counts = scatter::<R, E, I>(
+            dim,
+            ones(tensor.shape.clone()),
+            indices.clone(),
+            ones(value.shape.clone()),
+        );
+        let sum = scatter::<R, E, I>(dim, tensor, indices, value);
+
+        return div::<R, E>(sum, counts);
+    }
+
     let ndims = tensor.shape.num_dims();
     let mut indices = kernel::into_contiguous(indices);
     let tensor = kernel::into_contiguous(tensor);
@@ -113,6 +150,7 @@ pub(crate) fn scatter<R: CubeRuntime, E: CubeElement, I: IntElement>(
             indices.as_tensor_arg::<I>(1),
             value.as_tensor_arg::<E>(1),
             ScalarArg::new(dim as u32),
+            reduce,
         )
     }
     tensor
@@ -4,6 +4,7 @@ mod cast;
 mod clamp;
 mod comparison;
 mod contiguous;
+mod fft;
 mod index;
 mod mask;
 mod unary_float;
@@ -14,6 +15,7 @@ pub(crate) use binary::*;
 pub(crate) use binary_int::*;
 pub use cast::*;
 pub use contiguous::*;
+pub(crate) use fft::*;
 pub use mask::*;
 pub(crate) use unary_float::*;
 pub(crate) use unary_int::*;
@@ -11,7 +11,7 @@ use crate::{
     kernel::matmul::{MatmulStrategy, matmul},
 };
 use burn_tensor::ops::{BoolTensor, Device, FloatElem, FloatTensor, IntTensor};
-use burn_tensor::{DType, ElementConversion, FloatDType};
+use burn_tensor::{DType, ElementConversion, FloatDType, ScatterReduce};
 use burn_tensor::{Distribution, Shape, TensorData, ops::FloatTensorOps};
 use cubecl::prelude::*;
 use cubecl::reduce::ReducePrecision;
@@ -209,6 +209,20 @@ where
         )
     }
 
+    fn float_scatter_reduce(
+        dim: usize,
+        tensor: FloatTensor<Self>,
+        indices: IntTensor<Self>,
+        value: FloatTensor<Self>,
+        reduce: ScatterReduce,
+    ) -> FloatTensor<Self> {
+        execute_with_dtype!(
+            float(tensor.dtype, value.dtype),
+            E,
+            kernel::scatter_reduce::<R, E, I>(dim, tensor, indices, value, reduce)
+        )
+    }
+
     fn float_select(
         tensor: FloatTensor<Self>,
         dim: usize,
@@ -618,6 +632,26 @@ where
         expand(tensor, shape)
     }
 
+    fn float_fft(tensor: FloatTensor<Self>, dim: usize, inverse: bool) -> FloatTensor<Self> {
+        execute_with_dtype!(
+            float(tensor.dtype),
+            E,
+            kernel::fft::<R, E>(tensor, dim, inverse)
+        )
+    }
+
+    fn float_rfft(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
+        execute_with_dtype!(float(tensor.dtype), E, kernel::rfft::<R, E>(tensor, dim))
+    }
+
+    fn float_irfft(tensor: FloatTensor<Self>, dim: usize, n: usize) -> FloatTensor<Self> {
+        execute_with_dtype!(
+            float(tensor.dtype),
+            E,
+            kernel::irfft::<R, E>(tensor, dim, n)
+        )
+    }
+
     fn float_flip(tensor: FloatTensor<Self>, axes: &[usize]) -> FloatTensor<Self> {
         execute_with_dtype!(
             float(tensor.dtype),
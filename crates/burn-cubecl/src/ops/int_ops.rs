@@ -10,8 +10,8 @@ use crate::{
     element::BoolElement,
     kernel::prng::{random_bernoulli, random_normal, random_uniform},
 };
-use burn_tensor::DType;
 use burn_tensor::ops::{BoolTensor, Device, FloatTensor, IntElem, IntTensor};
+use burn_tensor::{DType, ScatterReduce};
 use burn_tensor::{Distribution, ElementConversion, Shape, TensorData, ops::IntTensorOps};
 use cubecl::frontend::Numeric;
 use cubecl::prelude::*;
@@ -100,6 +100,16 @@ where
         kernel::scatter::<R, I, I>(dim, tensor, indices, value)
     }
 
+    fn int_scatter_reduce(
+        dim: usize,
+        tensor: IntTensor<Self>,
+        indices: IntTensor<Self>,
+        value: IntTensor<Self>,
+        reduce: ScatterReduce,
+    ) -> IntTensor<Self> {
+        kernel::scatter_reduce::<R, I, I>(dim, tensor, indices, value, reduce)
+    }
+
     fn int_select(
         tensor: IntTensor<Self>,
         dim: usize,
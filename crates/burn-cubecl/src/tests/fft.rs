@@ -0,0 +1,65 @@
+#[burn_tensor_testgen::testgen(fft)]
+mod tests {
+    use super::*;
+    use burn_tensor::{Distribution, Tensor};
+    use burn_tensor::{Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    fn random<const D: usize>(shape: [usize; D]) -> (TestTensor<D>, ReferenceTensor<D>) {
+        let tensor = TestTensor::<D>::random(shape, Distribution::Default, &Default::default());
+        let tensor_ref = ReferenceTensor::<D>::from_data(tensor.to_data(), &Default::default());
+
+        (tensor, tensor_ref)
+    }
+
+    fn assert_close<const D: usize>(actual: TestTensor<D>, expected: ReferenceTensor<D>) {
+        expected
+            .into_data()
+            .assert_approx_eq::<FT>(&actual.into_data(), Tolerance::rel_abs(1e-3, 1e-2));
+    }
+
+    #[test]
+    fn fft_should_match_reference_for_powers_of_two() {
+        let (tensor, tensor_ref) = random([3, 1024, 2]);
+
+        assert_close(tensor.clone().fft(1), tensor_ref.clone().fft(1));
+        assert_close(tensor.ifft(1), tensor_ref.ifft(1));
+    }
+
+    #[test]
+    fn fft_should_match_reference_along_outer_dims() {
+        let (tensor, tensor_ref) = random([2048, 3, 2]);
+
+        assert_close(tensor.fft(0), tensor_ref.fft(0));
+    }
+
+    #[test]
+    fn fft_should_match_reference_for_other_sizes() {
+        let (tensor, tensor_ref) = random([2, 1000, 2]);
+
+        assert_close(tensor.fft(1), tensor_ref.fft(1));
+    }
+
+    #[test]
+    fn rfft_should_match_reference_for_powers_of_two() {
+        let (tensor, tensor_ref) = random([3, 1024]);
+
+        let spectrum = tensor.rfft::<3>(1);
+        let spectrum_ref = tensor_ref.rfft::<3>(1);
+        assert_close(spectrum.clone(), spectrum_ref.clone());
+        assert_close(
+            spectrum.irfft::<2>(1, None),
+            spectrum_ref.irfft::<2>(1, None),
+        );
+    }
+
+    #[test]
+    fn irfft_should_match_reference_with_missing_frequencies() {
+        let (tensor, tensor_ref) = random([2, 300, 2]);
+
+        assert_close(
+            tensor.irfft::<2>(1, Some(1024)),
+            tensor_ref.irfft::<2>(1, Some(1024)),
+        );
+    }
+}
@@ -9,6 +9,7 @@ mod conv2d;
 mod conv3d;
 mod conv_transpose2d;
 mod conv_transpose3d;
+mod fft;
 mod gather;
 mod mask_fill;
 mod mask_where;
@@ -80,6 +81,8 @@ macro_rules! testgen_all {
                 burn_cubecl::testgen_clamp!();
                 burn_cubecl::testgen_unary!();
 
+                burn_cubecl::testgen_fft!();
+
                 burn_cubecl::testgen_reduce!();
 
                 burn_cubecl::testgen_quantization!();
@@ -1,16 +1,45 @@
 use crate::{
-    FusionClientLocator, FusionTensor,
+    CostModel, CustomOp, CustomOperation, ExplorationPolicy, FusionClientLocator, FusionConfig,
+    FusionError, FusionFilter, FusionTensor, ReadCacheStats, TensorKind,
     client::FusionClient,
-    stream::{Context, OrderedExecution},
+    debug::{
+        CrossStreamReport, DetailedPlans, FusionDebugSummary, FusionExplanation, FusionHook,
+        FusionSnapshot, MirrorDivergence, MirrorOptions, OperationHistogram, PlanCacheStats,
+        PlanGraphRecorder, RecompilationRecorder, SnapshotOptions, StreamInfo, TriggerReport,
+    },
+    infer_outputs,
+    memory::{
+        DefragmentationReport, FragmentationReport, MemoryPressureReport, MemoryPressureSignal,
+        MemoryReport, OrphanReport, TrackedTensor,
+    },
+    stream::{
+        AheadOfTimeReport, CapturedGraph, Context, EventId, ExecutionPlanId,
+        ExecutionPlanStoreStats, FusionCheckpoint, FusionStream, OperationStreams,
+        OrderedExecution, PlanTrigger, RewriteRule, ScalarParameterization, StreamId,
+        StreamPriority, TimingSource,
+    },
 };
-use burn_ir::{BackendIr, OperationIr, TensorHandle};
+use burn_common::future::DynFut;
+use burn_ir::{BackendIr, CustomOpIr, OperationIr, TensorHandle, TensorId, TensorIr};
 use burn_tensor::{
-    Device, Element,
+    Device, Element, TensorData,
     backend::{Backend, DeviceOps},
     ops::{BoolTensor, FloatTensor, IntTensor, QuantizedTensor},
 };
 use serde::{Serialize, de::DeserializeOwned};
-use std::marker::PhantomData;
+#[cfg(feature = "plan-export")]
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, mpsc::Receiver},
+    time::Duration,
+};
+
+#[cfg(feature = "plan-export")]
+use crate::stream::PlanExportMode;
 
 pub(crate) static CLIENTS: FusionClientLocator = FusionClientLocator::new();
 
@@ -18,6 +47,14 @@ pub(crate) fn get_client<B: FusionBackend>(device: &Device<B>) -> Client<B::Fusi
     CLIENTS.client::<B::FusionRuntime>(device)
 }
 
+/// The tensors of the same stream read by [Fusion::read_batch].
+struct ReadBatch<R: FusionRuntime> {
+    client: Client<R>,
+    stream: StreamId,
+    indices: Vec<usize>,
+    tensors: Vec<(TensorIr, TensorKind)>,
+}
+
 /// Enable dynamic operation fusion on a backend that implements [fusion backend](crate::FusionBackend).
 #[derive(Clone, Debug, Default)]
 pub struct Fusion<B: FusionBackend> {
@@ -62,6 +99,736 @@ impl<B: FusionBackend> Backend for Fusion<B> {
     }
 }
 
+impl<B: FusionBackend> Fusion<B> {
+    /// Read the values of a tensor of the given kind, returning an error instead of panicking
+    /// when the tensor isn't tracked by its device anymore, e.g. after a [restore](Self::restore).
+    pub async fn try_read(
+        tensor: FusionTensor<B::FusionRuntime>,
+        kind: TensorKind,
+    ) -> Result<TensorData, FusionError> {
+        let client = tensor.client.clone();
+        let stream = tensor.stream;
+
+        client
+            .try_read_tensor::<B>(tensor.into_ir(), kind, stream)
+            .await
+    }
+
+    /// Read the values of multiple tensors, of any data type, at once.
+    ///
+    /// The kind of each tensor is [guessed](TensorKind::of) from its data type, so bool tensors
+    /// stored as integers are read as int tensors; use [read_batch](Self::read_batch) to read
+    /// them as bool tensors.
+    pub async fn read_many(tensors: Vec<FusionTensor<B::FusionRuntime>>) -> Vec<TensorData> {
+        let tensors = tensors
+            .into_iter()
+            .map(|tensor| {
+                let kind = TensorKind::of(tensor.dtype);
+                (tensor, kind)
+            })
+            .collect();
+
+        Self::read_batch(tensors).await
+    }
+
+    /// Read the values of multiple tensors of the given kinds at once.
+    ///
+    /// Reading tensors one by one drains their stream and waits for each of them, whereas the
+    /// tensors of the same stream are read here after a single drain, the reads being awaited
+    /// concurrently. The values are returned in the order of the tensors.
+    pub async fn read_batch(
+        tensors: Vec<(FusionTensor<B::FusionRuntime>, TensorKind)>,
+    ) -> Vec<TensorData> {
+        let num_tensors = tensors.len();
+        let mut batches = Vec::<ReadBatch<B::FusionRuntime>>::new();
+
+        for (index, (tensor, kind)) in tensors.into_iter().enumerate() {
+            let position = batches.iter().position(|batch| {
+                batch.stream == tensor.stream && batch.client.device() == tensor.client.device()
+            });
+            let batch = match position {
+                Some(position) => &mut batches[position],
+                None => {
+                    batches.push(ReadBatch {
+                        client: tensor.client.clone(),
+                        stream: tensor.stream,
+                        indices: Vec::new(),
+                        tensors: Vec::new(),
+                    });
+                    batches.last_mut().unwrap()
+                }
+            };
+            batch.indices.push(index);
+            batch.tensors.push((tensor.into_ir(), kind));
+        }
+
+        let reads = batches
+            .into_iter()
+            .map(|batch| {
+                let read = batch.client.read_tensors::<B>(batch.tensors, batch.stream);
+                (batch.indices, read)
+            })
+            .collect::<Vec<_>>();
+
+        let mut values = vec![None; num_tensors];
+        for (indices, read) in reads {
+            for (index, data) in indices.into_iter().zip(read.await) {
+                values[index] = Some(data);
+            }
+        }
+
+        values
+            .into_iter()
+            .map(|data| data.expect("Every tensor should be read"))
+            .collect()
+    }
+
+    /// How often the reads of the given device were served by the data of a previous read of
+    /// the same tensor, when the [read cache](FusionConfig::read_cache_bytes) is enabled.
+    pub fn read_cache_stats(device: &B::Device) -> ReadCacheStats {
+        get_client::<B>(device).read_cache_stats()
+    }
+
+    /// Create a [stream](FusionStream) on the given device with the given name.
+    ///
+    /// Operations registered while the stream is [bound](FusionStream::bind) are queued on it
+    /// instead of the stream of the current thread, so compute and transfers can be split into
+    /// overlapping pipelines on purpose.
+    pub fn stream(device: &B::Device, name: &str) -> FusionStream<B::FusionRuntime> {
+        FusionStream::new(name, get_client::<B>(device))
+    }
+
+    /// Release the buffer [pinned](FusionTensor::pin) for the tensor with the given id, returning
+    /// `false` if it wasn't pinned.
+    pub fn unpin(device: &B::Device, id: TensorId) -> bool {
+        get_client::<B>(device).unpin_tensor(id)
+    }
+
+    /// The ids of all tensors [pinned](FusionTensor::pin) on the given device.
+    pub fn pinned_tensors(device: &B::Device) -> Vec<TensorId> {
+        get_client::<B>(device).pinned_tensors()
+    }
+
+    /// The statistics of the store of execution plans of the given device.
+    pub fn execution_plan_stats(device: &B::Device) -> ExecutionPlanStoreStats {
+        get_client::<B>(device).execution_plan_stats()
+    }
+
+    /// Limit the number of execution plans kept by the given device, `None` meaning unbounded.
+    ///
+    /// Each new sequence of operations creates a new plan, so workloads with highly dynamic shapes
+    /// should set a capacity to avoid growing the store indefinitely. The least recently executed
+    /// plans are evicted first and are explored again if their operations come back.
+    ///
+    /// Fails with [FusionError::StoreCapacityExceeded] when the capacity can't fit the pinned
+    /// plans, in which case the previous capacity is kept.
+    pub fn set_execution_plan_capacity(
+        device: &B::Device,
+        capacity: Option<usize>,
+    ) -> Result<(), FusionError> {
+        get_client::<B>(device).set_execution_plan_capacity(capacity)
+    }
+
+    /// Pin the execution plan with the given id so that it is never evicted.
+    ///
+    /// Plan ids can be found in the [trigger report](Self::trigger_report). Fails with
+    /// [FusionError::PlanNotFound] when the plan isn't stored, and with
+    /// [FusionError::StoreCapacityExceeded] when the capacity of the store can't fit one more
+    /// pinned plan.
+    pub fn pin_plan(device: &B::Device, id: ExecutionPlanId) -> Result<(), FusionError> {
+        get_client::<B>(device).pin_plan(id)
+    }
+
+    /// Add a trigger to the execution plan with the given id, controlling when the plan is
+    /// executed on top of the triggers found during exploration.
+    ///
+    /// Plan ids can be found in the [trigger report](Self::trigger_report). Fails with
+    /// [FusionError::PlanNotFound] when the plan isn't stored.
+    pub fn add_plan_trigger(
+        device: &B::Device,
+        id: ExecutionPlanId,
+        trigger: PlanTrigger,
+    ) -> Result<(), FusionError> {
+        get_client::<B>(device).add_plan_trigger(id, trigger)
+    }
+
+    /// Remove the execution plans of the given device that were only executed by threads that
+    /// are now finished, along with their compiled kernels, returning the ids of the removed
+    /// plans.
+    ///
+    /// Plans are keyed by their operations, not by thread, so a new thread running the same
+    /// operations would reuse them. Collecting them is only worth it when finished threads ran
+    /// one-off workloads, e.g. a per-request worker with its own shapes. Pinned plans are kept.
+    pub fn collect_finished_stream_plans(device: &B::Device) -> Vec<ExecutionPlanId> {
+        get_client::<B>(device).collect_finished_stream_plans()
+    }
+
+    /// Unpin the execution plan with the given id, returning `false` if it wasn't pinned.
+    pub fn unpin_plan(device: &B::Device, id: ExecutionPlanId) -> bool {
+        get_client::<B>(device).unpin_plan(id)
+    }
+
+    /// The ids of all execution plans pinned on the given device.
+    pub fn pinned_plans(device: &B::Device) -> Vec<ExecutionPlanId> {
+        get_client::<B>(device).pinned_plans()
+    }
+
+    /// Pin every execution plan executed on the given device while enabled, returning the
+    /// previous state.
+    ///
+    /// Enabling it around a latency-critical region of a model, e.g. the forward pass used when
+    /// serving, keeps the plans of that region from being evicted and explored again. Restoring
+    /// the returned state afterward allows regions to be nested.
+    ///
+    /// Plans are only pinned once executed, so the queued operations of the region should be
+    /// executed, e.g. by syncing the device, before disabling it.
+    pub fn pin_executed_plans(device: &B::Device, enabled: bool) -> bool {
+        get_client::<B>(device).pin_executed_plans(enabled)
+    }
+
+    /// Write every execution plan explored on the given device to the file at `path`, returning
+    /// the number of plans written.
+    ///
+    /// Plans explored on a build machine, e.g. by running a model once, can be shipped with an
+    /// application and loaded at startup with [import_plans](Self::import_plans), so that the
+    /// first iterations don't pay for the exploration.
+    #[cfg(feature = "plan-export")]
+    pub fn export_plans(device: &B::Device, path: impl AsRef<Path>) -> Result<usize, FusionError> {
+        get_client::<B>(device).export_plans(path.as_ref())
+    }
+
+    /// Stream the execution plans explored on the given device to `writer` as newline-delimited
+    /// JSON, one plan per line, returning the number of plans written.
+    ///
+    /// Plans are serialized one at a time instead of being buffered, which keeps the memory
+    /// usage flat for huge plan stores. With [PlanExportMode::Incremental], only the plans
+    /// created since the last export of the device are written, so a long-running service can
+    /// periodically append to the same file.
+    #[cfg(feature = "plan-export")]
+    pub fn export_plans_ndjson(
+        device: &B::Device,
+        writer: &mut dyn Write,
+        mode: PlanExportMode,
+    ) -> Result<usize, FusionError> {
+        get_client::<B>(device).export_plans_ndjson(writer, mode)
+    }
+
+    /// Load the execution plans written by [export_plans](Self::export_plans) on the given
+    /// device, returning the number of plans added.
+    ///
+    /// Files holding the bytes of [export_plans_to_bytes](Self::export_plans_to_bytes) are
+    /// detected and loaded as well. Plans with the same operations as a plan already stored are
+    /// skipped. Fails with [FusionError::PlanSerialization] when the file can't be read or
+    /// parsed.
+    #[cfg(feature = "plan-export")]
+    pub fn import_plans(device: &B::Device, path: impl AsRef<Path>) -> Result<usize, FusionError> {
+        get_client::<B>(device).import_plans(path.as_ref())
+    }
+
+    /// Encode the execution plans explored on the given device in a compact binary format.
+    ///
+    /// The bytes are much smaller and faster to load than the JSON written by
+    /// [export_plans](Self::export_plans), which matters for huge plan stores. They can be
+    /// loaded with [import_plans_from_bytes](Self::import_plans_from_bytes), or written to a file
+    /// loaded with [import_plans](Self::import_plans).
+    #[cfg(feature = "plan-export")]
+    pub fn export_plans_to_bytes(device: &B::Device) -> Result<Vec<u8>, FusionError> {
+        get_client::<B>(device).export_plans_to_bytes()
+    }
+
+    /// Load the execution plans encoded by [export_plans_to_bytes](Self::export_plans_to_bytes)
+    /// on the given device, returning the number of plans added.
+    #[cfg(feature = "plan-export")]
+    pub fn import_plans_from_bytes(device: &B::Device, bytes: &[u8]) -> Result<usize, FusionError> {
+        get_client::<B>(device).import_plans_from_bytes(bytes)
+    }
+
+    /// Plan the given operations on the given device as if they were registered on a new stream
+    /// and synced, without executing them, and [compile](crate::Optimization::compile) the
+    /// optimizations of the plans they use.
+    ///
+    /// The operations are typically the ones of a [captured graph](CapturedGraph::document) or of
+    /// a recorded workload, read from an [IR document](burn_ir::IrDocument). Running them at
+    /// deploy time warms the plan store, which can then be exported with the `plan-export`
+    /// feature, so that the first requests served don't pay for the exploration. The
+    /// [rewrite passes](Self::register_rewrite) aren't run on the operations.
+    pub fn compile_ahead_of_time(
+        device: &B::Device,
+        operations: &[OperationIr],
+    ) -> AheadOfTimeReport {
+        get_client::<B>(device).compile_ahead_of_time(operations)
+    }
+
+    /// Update the [priority](StreamPriority) of a stream of the given device.
+    ///
+    /// When multiple streams must be drained at once, e.g. on a device sync or when a tensor is
+    /// shared between streams, the streams with the highest priority are drained first.
+    pub fn set_stream_priority(device: &B::Device, stream: StreamId, priority: StreamPriority) {
+        get_client::<B>(device).set_stream_priority(stream, priority);
+    }
+
+    /// Execute the operations queued on the given stream from another thread, the returned future
+    /// completing once they are submitted to the backend.
+    ///
+    /// Server applications can start the execution of a request without blocking its thread on
+    /// the fusion server, and await it later. Reading the outputs still waits for the device to
+    /// finish executing them.
+    pub fn drain_stream_async(device: &B::Device, stream: StreamId) -> DynFut<()> {
+        get_client::<B>(device).drain_stream_async(stream)
+    }
+
+    /// Execute the operations queued on the given stream from another thread, without waiting
+    /// for them to be submitted.
+    pub fn flush(device: &B::Device, stream: StreamId) {
+        get_client::<B>(device).flush(stream);
+    }
+
+    /// Record an event after the operations currently queued on the given stream.
+    ///
+    /// Use [current_stream](crate::stream::current_stream) to record an event on the stream of
+    /// the current thread.
+    pub fn record_event(device: &B::Device, stream: StreamId) -> EventId {
+        get_client::<B>(device).record_event(stream)
+    }
+
+    /// Make the given stream wait on an event recorded on another stream.
+    ///
+    /// The operations queued on the recording stream before the event are executed, the ones
+    /// queued after it staying lazy, so the operations registered next on the waiting stream are
+    /// ordered after the event without reading anything back on the host. For instance, a compute
+    /// stream can wait on the upload of its next batch while the transfer stream keeps uploading.
+    ///
+    /// Waiting on an event that's already complete does nothing.
+    pub fn wait_event(device: &B::Device, stream: StreamId, event: EventId) {
+        get_client::<B>(device).wait_event(stream, event);
+    }
+
+    /// Capture the operations registered by the function on the current stream as a
+    /// [graph](CapturedGraph) that can be replayed with new inputs, returning the outputs of the
+    /// function along with the graph.
+    ///
+    /// The function is called with tensors bound to the buffers of the inputs, and must only
+    /// register operations on the current stream, e.g. a whole forward pass; its operations are
+    /// executed before returning. Replaying the graph skips the registration, the exploration and
+    /// the dispatch of its operations, executing its plans directly, so iterations that only
+    /// differ by their inputs should be captured once and replayed afterward.
+    #[allow(clippy::type_complexity)]
+    pub fn capture<F>(
+        device: &B::Device,
+        inputs: Vec<FusionTensor<B::FusionRuntime>>,
+        func: F,
+    ) -> Result<
+        (
+            Vec<FusionTensor<B::FusionRuntime>>,
+            CapturedGraph<B::FusionRuntime>,
+        ),
+        FusionError,
+    >
+    where
+        F: FnOnce(Vec<FusionTensor<B::FusionRuntime>>) -> Vec<FusionTensor<B::FusionRuntime>>,
+    {
+        let client = get_client::<B>(device);
+        let inputs = client.begin_capture(inputs);
+        let captured = inputs.iter().map(FusionTensor::to_ir_out).collect();
+
+        let outputs = func(inputs);
+        let graph = client.end_capture(captured, &outputs)?;
+
+        Ok((outputs, graph))
+    }
+
+    /// Update which scalars are replaced by parameters in the execution plans of the given
+    /// device.
+    ///
+    /// By default only the scalars passed to fused kernels are parameters, so plans differing by
+    /// e.g. a dropout probability are explored and stored separately. With
+    /// [ScalarParameterization::All], a single plan serves every value, as long as no
+    /// optimization relies on those values.
+    pub fn set_scalar_parameterization(
+        device: &B::Device,
+        parameterization: ScalarParameterization,
+    ) {
+        get_client::<B>(device).set_scalar_parameterization(parameterization);
+    }
+
+    /// Update the [policy](ExplorationPolicy) deciding when the streams of the given device stop
+    /// exploring, when their optimizations are built and which optimization is selected for
+    /// each block of operations.
+    ///
+    /// Streams exploring operations apply the new policy from their next operation. Plans
+    /// already in the store are kept.
+    pub fn set_exploration_policy<P: ExplorationPolicy + 'static>(device: &B::Device, policy: P) {
+        get_client::<B>(device).set_exploration_policy(Arc::new(policy));
+    }
+
+    /// Register a [custom operation](CustomOp) on the given device, replacing any operation
+    /// registered with the same name.
+    pub fn register_custom_op<Op>(device: &B::Device, op: Op)
+    where
+        Op: CustomOp<B::FusionRuntime> + 'static,
+    {
+        get_client::<B>(device).register_custom_op(Arc::new(op));
+    }
+
+    /// Queue the [custom operation](CustomOp) registered on the given device with the given
+    /// name, returning its outputs.
+    ///
+    /// The outputs are created from the shapes and data types inferred by the operation, and the
+    /// operation is executed lazily like any other operation of the current stream.
+    pub fn custom_op(
+        device: &B::Device,
+        name: &str,
+        inputs: Vec<FusionTensor<B::FusionRuntime>>,
+    ) -> Result<Vec<FusionTensor<B::FusionRuntime>>, FusionError> {
+        let client = get_client::<B>(device);
+        let op = client
+            .custom_op(name)
+            .ok_or_else(|| FusionError::UnsupportedOperation {
+                operation: format!("Custom::{name}"),
+                reason: "no custom operation is registered with this name".into(),
+            })?;
+
+        let mut streams = OperationStreams::default();
+        for input in inputs.iter() {
+            streams.tensor(input);
+        }
+        // Consuming the inputs without registering the operation would leak them.
+        let views = inputs
+            .iter()
+            .map(FusionTensor::to_ir_out)
+            .collect::<Vec<_>>();
+        let outputs = infer_outputs(op.as_ref(), &views)?
+            .into_iter()
+            .map(|output| client.tensor_uninitialized(output.shape, output.dtype))
+            .collect::<Vec<_>>();
+
+        let desc = CustomOpIr {
+            id: name.to_string(),
+            inputs: inputs.into_iter().map(FusionTensor::into_ir).collect(),
+            outputs: outputs.iter().map(FusionTensor::to_ir_out).collect(),
+        };
+        client.register(
+            streams,
+            OperationIr::Custom(desc.clone()),
+            CustomOperation { desc, op },
+        );
+
+        Ok(outputs)
+    }
+
+    /// Rewrite the operations of the streams of the given device matching the pattern of the
+    /// [rule](RewriteRule) when they are drained, before they are explored.
+    ///
+    /// Rules run in the order they are registered, after the built-in rewrites folding constants
+    /// and simplifying operations, and before the ones merging and removing operations.
+    pub fn register_rewrite<Rule>(device: &B::Device, rule: Rule)
+    where
+        Rule: RewriteRule<B::FusionRuntime> + 'static,
+    {
+        get_client::<B>(device).register_rewrite(Box::new(rule));
+    }
+
+    /// Update the [cost model](CostModel) consulted before fusing the operations of a block on
+    /// the given device, executing them one by one when fusing is predicted to be slower.
+    ///
+    /// Only plans explored afterward are affected, so it's usually set before the first
+    /// operation.
+    pub fn set_cost_model<M: CostModel + 'static>(device: &B::Device, cost_model: M) {
+        get_client::<B>(device).set_cost_model(Arc::new(cost_model));
+    }
+
+    /// Update which kinds of operations can be fused on the given device, overriding the
+    /// [environment](crate::FUSION_FILTER_ENV).
+    ///
+    /// Only plans explored afterward are affected, so it's usually set before the first
+    /// operation.
+    pub fn set_fusion_filter(device: &B::Device, filter: FusionFilter) {
+        get_client::<B>(device).set_fusion_filter(filter);
+    }
+
+    /// Drain the streams of the given device that didn't get a new operation for `timeout`,
+    /// `None` disabling it, which is the default.
+    ///
+    /// Shorthand for updating the [idle timeout](FusionConfig::idle_timeout) of the
+    /// configuration.
+    pub fn set_idle_timeout(device: &B::Device, timeout: Option<Duration>) {
+        let client = get_client::<B>(device);
+        let config = client.config();
+
+        client.set_config(FusionConfig {
+            idle_timeout: timeout,
+            ..config
+        });
+    }
+
+    /// The [configuration](FusionConfig) of the fusion server of the given device.
+    pub fn config(device: &B::Device) -> FusionConfig {
+        get_client::<B>(device).config()
+    }
+
+    /// Update the [configuration](FusionConfig) of the fusion server of the given device.
+    ///
+    /// Streams are otherwise only drained when a tensor is read or the device is synced, so a
+    /// training step that never reads anything back can queue thousands of operations. With queue
+    /// limits, a stream is drained as soon as one of them is exceeded, bounding the memory held by
+    /// its lazy operations.
+    pub fn set_config(device: &B::Device, config: FusionConfig) {
+        get_client::<B>(device).set_config(config);
+    }
+
+    /// Register a [debug hook](FusionHook) on the fusion server of the given device.
+    pub fn register_debug_hook(device: &B::Device, hook: Box<dyn FusionHook>) {
+        get_client::<B>(device).register_debug_hook(hook);
+    }
+
+    /// Start recording which execution plans of the given device feed which other plans.
+    ///
+    /// The returned recorder builds the [plan graph](crate::debug::PlanGraph) of everything
+    /// executed from now on.
+    pub fn record_plan_graph(device: &B::Device) -> PlanGraphRecorder {
+        let recorder = PlanGraphRecorder::default();
+        Self::register_debug_hook(device, Box::new(recorder.clone()));
+
+        recorder
+    }
+
+    /// Write everything needed to investigate a fusion issue on the given device into the
+    /// directory, created if needed, returning the paths of the written files.
+    ///
+    /// The bundle holds:
+    /// - `streams.json`: the queued operations of every stream.
+    /// - `stream_<id>.dot`: the queued operations of each stream as a Graphviz graph.
+    /// - `plans.json`: the [exported](Self::export_plans) execution plans.
+    /// - `plan_stats.csv`: the executions and timings of every plan.
+    /// - `reports.txt`: the summary, cache, trigger, cross-stream, histogram and snapshot
+    ///   reports.
+    /// - `manifest.json`: the backend, device, burn version, IR schema and configuration.
+    ///
+    /// Nothing is executed, so the queues are written as they are when this is called.
+    #[cfg(feature = "plan-export")]
+    pub fn dump_all(
+        device: &B::Device,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<PathBuf>, FusionError> {
+        crate::debug::write_bundle::<B>(device, path.as_ref())
+    }
+
+    /// Start recording why new execution plans are created on the given device.
+    ///
+    /// The returned recorder builds the [recompilation report](crate::debug::RecompilationReport)
+    /// of everything executed from now on, telling apart new sequences of operations from shape
+    /// changes for each [scope](crate::debug::scope).
+    pub fn record_recompilations(device: &B::Device) -> RecompilationRecorder {
+        let recorder = RecompilationRecorder::default();
+        Self::register_debug_hook(device, Box::new(recorder.clone()));
+
+        recorder
+    }
+
+    /// Group the operations of the given device by kind, data type, rank and rounded shape.
+    ///
+    /// Each bucket counts the operations still queued as well as the operations of every
+    /// execution plan, split between the ones executed as part of an optimization and the others.
+    pub fn operation_histogram(device: &B::Device) -> OperationHistogram {
+        get_client::<B>(device).operation_histogram()
+    }
+
+    /// Explain when each execution plan of the given device is executed, along with the number
+    /// of times each trigger fired.
+    pub fn trigger_report(device: &B::Device) -> TriggerReport {
+        get_client::<B>(device).trigger_report()
+    }
+
+    /// Explain how the given operations would be partitioned into execution plans on the given
+    /// device, with the reason each pair of consecutive operations isn't fused, e.g. an
+    /// unsupported operation, the fusion limits or different data types.
+    ///
+    /// The operations are typically the ones queued on a stream, as seen by a
+    /// [debug hook](crate::debug::FusionHook). Nothing is executed.
+    pub fn explain_fusion(device: &B::Device, operations: &[OperationIr]) -> FusionExplanation {
+        get_client::<B>(device).explain_fusion(operations)
+    }
+
+    /// Collect how often the execution plans of the given device are found and reused, along
+    /// with the number of executions and the [measured execution times](crate::stream::PlanTimings) of each
+    /// plan.
+    pub fn debug_cache_stats(device: &B::Device) -> PlanCacheStats {
+        get_client::<B>(device).debug_cache_stats()
+    }
+
+    /// Collect the accumulated execution times of the plans of the given device, slowest first,
+    /// along with the operations they execute.
+    ///
+    /// Plans are timed on the host unless [device timing](crate::PlanTiming::Device) is
+    /// [configured](Self::set_config), which measures their completion on the device.
+    pub fn debug_detailed_plans(device: &B::Device) -> DetailedPlans {
+        get_client::<B>(device).debug_detailed_plans()
+    }
+
+    /// Copy a [snapshot](FusionSnapshot) of the queued operations and plans of the given device.
+    ///
+    /// Unlike the other reports, nothing is analyzed while the server is locked: a bounded
+    /// sample of the state is copied into owned structures and the lock is released right away,
+    /// so external tooling can inspect live high-throughput workloads without stalling them.
+    pub fn debug_snapshot(device: &B::Device, options: SnapshotOptions) -> FusionSnapshot {
+        get_client::<B>(device).debug_snapshot(options)
+    }
+
+    /// The [thread and creation time](StreamInfo) of every stream used on the given device,
+    /// sorted by creation time.
+    ///
+    /// Streams are identified by opaque ids in the other reports, this maps them back to the
+    /// threads that use them.
+    pub fn debug_stream_info(device: &B::Device) -> Vec<StreamInfo> {
+        get_client::<B>(device).debug_stream_info()
+    }
+
+    /// Which streams of the given device were synced for other streams, because of tensors
+    /// shared between them.
+    ///
+    /// Using a tensor from another stream only executes the operations of that stream up to the
+    /// last one using the tensor, the report tells how many operations were left queued.
+    pub fn cross_stream_report(device: &B::Device) -> CrossStreamReport {
+        get_client::<B>(device).cross_stream_report()
+    }
+
+    /// Collect a [summary](FusionDebugSummary) of the fusion server of the given device.
+    pub fn debug_summary(device: &B::Device) -> FusionDebugSummary {
+        get_client::<B>(device).debug_summary()
+    }
+
+    /// Receive a [summary](FusionDebugSummary) of the fusion server of the given device every
+    /// `interval`.
+    ///
+    /// Summaries are collected on a background thread, which locks the server once per interval,
+    /// so dashboards can poll the receiver as often as they like without slowing down the
+    /// registration of operations. The thread stops once the receiver is dropped.
+    pub fn subscribe_summaries(
+        device: &B::Device,
+        interval: Duration,
+    ) -> Receiver<FusionDebugSummary> {
+        get_client::<B>(device).subscribe_summaries(interval)
+    }
+
+    /// Enable differential testing on the given device.
+    ///
+    /// Every following operation is also executed unfused on the same device, and the fused values
+    /// are compared with the reference values at each sync point. Only the first divergent
+    /// operation is kept, since all its dependents are likely to diverge as well.
+    ///
+    /// # Notes
+    ///
+    /// Mirrored tensors share their inputs with the reference execution, so fewer operations can
+    /// be executed inplace.
+    pub fn enable_mirror(device: &B::Device, options: MirrorOptions) {
+        get_client::<B>(device).enable_mirror(options);
+    }
+
+    /// Compare every mirrored tensor alive on the given device with its reference, returning the
+    /// first divergence found so far.
+    pub fn mirror_sync(device: &B::Device) -> Option<MirrorDivergence> {
+        get_client::<B>(device).mirror_sync::<B>()
+    }
+
+    /// The first divergence found by the mirror of the given device.
+    pub fn mirror_divergence(device: &B::Device) -> Option<MirrorDivergence> {
+        get_client::<B>(device).mirror_divergence()
+    }
+
+    /// Report where the memory of the given device goes: the live handles by data type, and the
+    /// tensors that the operations queued on each stream will allocate.
+    pub fn memory_report(device: &B::Device) -> MemoryReport {
+        get_client::<B>(device).memory_report()
+    }
+
+    /// Report how the memory of the live tensors on the given device is fragmented.
+    pub fn fragmentation_report(device: &B::Device) -> FragmentationReport {
+        get_client::<B>(device).fragmentation_report()
+    }
+
+    /// Every tensor tracked by the given device, initialized or not, ordered by id.
+    ///
+    /// Useful to build memory dashboards or to find leaked tensors without downcasting the
+    /// backend handles.
+    pub fn tracked_tensors(device: &B::Device) -> Vec<TrackedTensor> {
+        get_client::<B>(device).tracked_tensors()
+    }
+
+    /// Take a checkpoint of the fusion server of the given device: the handles of its tensors and
+    /// the operations queued on its streams, without executing them.
+    ///
+    /// The device buffers aren't copied, the checkpoint only keeps them alive.
+    pub fn checkpoint(device: &B::Device) -> FusionCheckpoint<B::FusionRuntime> {
+        get_client::<B>(device).checkpoint()
+    }
+
+    /// Bring the fusion server of the given device back to a [checkpoint](Self::checkpoint).
+    ///
+    /// The operations currently queued are discarded without being executed, and the operations
+    /// of the checkpoint are queued again. Tensor ids are handed out from where they were, so a
+    /// restored workload creates the same tensors and plans as the original one.
+    ///
+    /// Tensors created after the checkpoint must be dropped before restoring it, since their
+    /// ids are reused. Tensors dropped after the checkpoint leave their handles behind, which can
+    /// be [collected](Self::collect_orphaned_handles). The annotations of the queued operations
+    /// and the [mirror](Self::enable_mirror) aren't restored.
+    pub fn restore(device: &B::Device, checkpoint: &FusionCheckpoint<B::FusionRuntime>) {
+        get_client::<B>(device).restore(checkpoint);
+    }
+
+    /// Start tracking the references of the tensors created on the given device from now on,
+    /// returning `false` if already tracked.
+    ///
+    /// Required to find [orphaned handles](Self::orphaned_handles); tensors created before are
+    /// never reported.
+    pub fn track_tensor_references(device: &B::Device) -> bool {
+        get_client::<B>(device).track_tensor_references()
+    }
+
+    /// The handles of the given device that no tensor references anymore, and that no queued
+    /// operation reads or writes.
+    ///
+    /// Only tensors created after [track_tensor_references](Self::track_tensor_references) are
+    /// checked; the report is empty when the references aren't tracked. A tensor being created by
+    /// another thread might be reported, so prefer calling it when the device is idle.
+    pub fn orphaned_handles(device: &B::Device) -> OrphanReport {
+        get_client::<B>(device).orphaned_handles(false)
+    }
+
+    /// Remove the [orphaned handles](Self::orphaned_handles) of the given device, releasing
+    /// their memory.
+    ///
+    /// Pinned handles are never collected. Only call it when no other thread creates tensors on
+    /// the device, since their handles could be collected before the tensors are tracked.
+    pub fn collect_orphaned_handles(device: &B::Device) -> OrphanReport {
+        get_client::<B>(device).orphaned_handles(true)
+    }
+
+    /// The [signal](MemoryPressureSignal) raising memory pressure on the given device, to hand
+    /// to the allocator of the backend.
+    pub fn memory_pressure_signal(device: &B::Device) -> MemoryPressureSignal {
+        get_client::<B>(device).memory_pressure_signal()
+    }
+
+    /// Relieve the memory pressure on the given device right away.
+    ///
+    /// All streams are drained so that the tensors freed by the queued operations are released,
+    /// then the output pool is emptied and the orphaned handles and cold execution plans are
+    /// released as set by the [policy](crate::MemoryPressurePolicy) of the
+    /// [configuration](Self::set_config), before releasing the unused memory of the device.
+    pub fn relieve_memory_pressure(device: &B::Device) -> MemoryPressureReport {
+        get_client::<B>(device).relieve_memory_pressure()
+    }
+
+    /// Defragment the memory of the given device.
+    ///
+    /// All streams are drained, then every live tensor that the runtime allows is moved into a
+    /// fresh allocation, after which unused memory is released.
+    pub fn defragment(device: &B::Device) -> DefragmentationReport {
+        get_client::<B>(device).defragment()
+    }
+}
+
 /// The status of a [builder](OptimizationBuilder).
 #[derive(Clone, Debug, Copy)]
 pub enum OptimizationStatus {
@@ -80,6 +847,25 @@ pub struct OptimizationProperties {
     pub ready: bool,
 }
 
+/// The hints of a [builder](OptimizationBuilder) about the cost of its optimization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptimizationCostHint {
+    /// The number of kernels launched by the optimization.
+    pub num_launches: usize,
+    /// The pressure of the optimization on registers, e.g. the number of bindings of the fused
+    /// kernel, `None` when unknown.
+    pub register_pressure: Option<u32>,
+}
+
+impl Default for OptimizationCostHint {
+    fn default() -> Self {
+        Self {
+            num_launches: 1,
+            register_pressure: None,
+        }
+    }
+}
+
 /// The fusion operation abstraction allows implementations to fuse many
 /// [tensor operations](OperationIr) into one, improving the performance of the backend.
 ///
@@ -103,6 +889,10 @@ pub trait OptimizationBuilder<O>: Send {
     fn status(&self) -> OptimizationStatus;
     /// Return the builder [properties](OptimizationProperties).
     fn properties(&self) -> OptimizationProperties;
+    /// Return the hints given to the [cost model](crate::CostModel) about the optimization.
+    fn cost_hint(&self) -> OptimizationCostHint {
+        OptimizationCostHint::default()
+    }
     /// The number of operation fused.
     fn len(&self) -> usize;
     /// If no operations are fused.
@@ -132,6 +922,12 @@ pub trait Optimization<R: FusionRuntime>: Send + NumOperations {
         execution: &OrderedExecution<R>,
     );
 
+    /// Compile the kernels of the optimization before it's first executed, when
+    /// [compiling ahead of time](Fusion::compile_ahead_of_time).
+    ///
+    /// Does nothing by default, the kernels being compiled when the optimization is executed.
+    fn compile(&mut self) {}
+
     /// Returns the state that can be serialized.
     fn to_state(&self) -> R::OptimizationState;
     /// Create the optimization from the state.
@@ -146,11 +942,11 @@ pub type FusionHandle<R> = <R as FusionRuntime>::FusionHandle;
 pub type Client<R> = <R as FusionRuntime>::FusionClient;
 
 /// Trait that defines a runtime that will benefits from fused operations.
-pub trait FusionRuntime: Send + Sync + Sized + core::fmt::Debug {
+pub trait FusionRuntime: Send + Sync + Sized + core::fmt::Debug + 'static {
     /// The state that can be serialized for an optimization.
     type OptimizationState: Serialize + DeserializeOwned;
     /// Optimization type for the backend.
-    type Optimization: Optimization<Self>;
+    type Optimization: Optimization<Self> + 'static;
     /// Handle used to store tensor dynamically.
     type FusionHandle: Clone + Send;
     /// Device used by the runtime.
@@ -164,6 +960,47 @@ pub trait FusionRuntime: Send + Sync + Sized + core::fmt::Debug {
     fn optimizations(
         device: Self::FusionDevice,
     ) -> Vec<Box<dyn OptimizationBuilder<Self::Optimization>>>;
+
+    /// If the buffer of the given handle isn't shared with any other handle, and can therefore be
+    /// recycled by the [output pool](crate::stream::OutputPool) once its tensor is freed.
+    ///
+    /// Returning `false` disables output buffer recycling for the runtime.
+    fn can_recycle(_handle: &Self::FusionHandle) -> bool {
+        false
+    }
+
+    /// The number of bytes used by the buffer of the given handle, if known by the runtime.
+    fn handle_size(_handle: &Self::FusionHandle) -> Option<u64> {
+        None
+    }
+
+    /// Copy the buffer of the given handle into a fresh allocation, used to
+    /// [defragment](Fusion::defragment) the device memory.
+    ///
+    /// Returns `None` when the handle can't be moved, e.g. when its buffer is shared.
+    fn reallocate(_handle: &Self::FusionHandle) -> Option<Self::FusionHandle> {
+        None
+    }
+
+    /// The total number of bytes reserved on the device, if known by the runtime.
+    fn memory_reserved(_device: &Self::FusionDevice) -> Option<u64> {
+        None
+    }
+
+    /// Release the memory reserved on the device that isn't used anymore.
+    fn memory_cleanup(_device: &Self::FusionDevice) {}
+
+    /// Execute the function and measure how long the device takes to complete the work it
+    /// submits, used for [device timing](crate::PlanTiming::Device).
+    ///
+    /// Returns no measure when the runtime can't time the device, in which case the execution is
+    /// timed on the host.
+    fn profile<O>(
+        _device: &Self::FusionDevice,
+        func: impl FnOnce() -> O,
+    ) -> (O, Option<(Duration, TimingSource)>) {
+        (func(), None)
+    }
 }
 
 /// Trait that allows an existing [backend](Backend) to specify graph optimizations using
@@ -179,6 +1016,13 @@ pub trait FusionBackend:
 
     /// Pointer to the full precision fusion backend.
     type FullPrecisionBackend: FusionBackend<FusionRuntime = Self::FusionRuntime>;
+
+    /// Whether tensors can be copied directly from the source device to the target device,
+    /// without being staged through the host, when moved by a [MultiDevice](crate::MultiDevice)
+    /// coordinator.
+    fn supports_peer_transfer(_source: &Self::Device, _target: &Self::Device) -> bool {
+        false
+    }
 }
 
 // Fusion implements `BackendIr` to enable router backend usage.
@@ -0,0 +1,264 @@
+use core::fmt::Display;
+use std::time::{Duration, Instant};
+
+use burn_tensor::backend::Backend;
+
+use crate::{Fusion, FusionBackend};
+
+/// How a closure is [benchmarked](benchmark).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkConfig {
+    /// The minimum number of warm-up iterations.
+    pub min_warmup: usize,
+    /// The maximum number of warm-up iterations, after which the steady state is measured even if
+    /// plans are still being created.
+    pub max_warmup: usize,
+    /// The number of consecutive warm-up iterations without any new plan before the closure is
+    /// considered warm.
+    pub num_stable: usize,
+    /// The number of measured iterations once warm.
+    pub num_samples: usize,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            min_warmup: 1,
+            max_warmup: 100,
+            num_stable: 3,
+            num_samples: 10,
+        }
+    }
+}
+
+/// The durations of the warm-up and steady-state iterations of a [benchmark].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchmarkResult {
+    /// The iterations executed while plans were still being created.
+    pub warmup: IterationTimings,
+    /// The iterations measured once no new plan was created.
+    pub steady: IterationTimings,
+    /// The number of plans created during the warm-up.
+    pub num_plans_created: usize,
+    /// Whether the warm-up stopped creating plans before
+    /// [max_warmup](BenchmarkConfig::max_warmup) iterations.
+    pub converged: bool,
+}
+
+/// The durations of benchmarked iterations, in execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IterationTimings {
+    /// The duration of each iteration, device sync included.
+    pub durations: Vec<Duration>,
+}
+
+/// Benchmark a closure running on a fusion device, excluding its warm-up.
+///
+/// Lazy fused backends explore and compile new execution plans the first times a workload runs,
+/// so the first iterations are much slower than the following ones. The closure is executed until
+/// no new plan is created for [num_stable](BenchmarkConfig::num_stable) iterations, then measured
+/// for [num_samples](BenchmarkConfig::num_samples) iterations.
+///
+/// The device is synced after each iteration, once the output of the closure is dropped, so that
+/// the measured durations include the execution of the lazy operations.
+pub fn benchmark<B, O, F>(
+    device: &B::Device,
+    config: BenchmarkConfig,
+    mut func: F,
+) -> BenchmarkResult
+where
+    B: FusionBackend,
+    F: FnMut() -> O,
+{
+    let mut iteration = || {
+        let start = Instant::now();
+        core::mem::drop(func());
+        Fusion::<B>::sync(device);
+        start.elapsed()
+    };
+    let num_created = || Fusion::<B>::execution_plan_stats(device).num_created;
+
+    let num_created_before = num_created();
+    let mut warmup = Warmup::new(config, num_created_before);
+    let mut timings = IterationTimings::default();
+
+    let converged = loop {
+        timings.durations.push(iteration());
+
+        match warmup.step(num_created()) {
+            WarmupState::Running => continue,
+            WarmupState::Converged => break true,
+            WarmupState::Exhausted => break false,
+        }
+    };
+    let num_plans_created = num_created() - num_created_before;
+
+    BenchmarkResult {
+        warmup: timings,
+        steady: IterationTimings {
+            durations: (0..config.num_samples).map(|_| iteration()).collect(),
+        },
+        num_plans_created,
+        converged,
+    }
+}
+
+impl IterationTimings {
+    /// The number of iterations.
+    pub fn num_iterations(&self) -> usize {
+        self.durations.len()
+    }
+
+    /// The total duration of the iterations.
+    pub fn total(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+
+    /// The mean duration of an iteration.
+    pub fn mean(&self) -> Duration {
+        match self.durations.len() {
+            0 => Duration::ZERO,
+            num_iterations => self.total() / num_iterations as u32,
+        }
+    }
+
+    /// The median duration of an iteration.
+    pub fn median(&self) -> Duration {
+        let mut durations = self.durations.clone();
+        durations.sort();
+
+        durations
+            .get(durations.len() / 2)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The shortest iteration.
+    pub fn min(&self) -> Duration {
+        self.durations.iter().min().copied().unwrap_or_default()
+    }
+
+    /// The longest iteration.
+    pub fn max(&self) -> Duration {
+        self.durations.iter().max().copied().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WarmupState {
+    Running,
+    Converged,
+    Exhausted,
+}
+
+/// Decides when the warm-up is over from the number of plans created after each iteration.
+struct Warmup {
+    config: BenchmarkConfig,
+    num_iterations: usize,
+    num_stable: usize,
+    num_created: usize,
+}
+
+impl Warmup {
+    fn new(config: BenchmarkConfig, num_created: usize) -> Self {
+        Self {
+            config,
+            num_iterations: 0,
+            num_stable: 0,
+            num_created,
+        }
+    }
+
+    fn step(&mut self, num_created: usize) -> WarmupState {
+        self.num_iterations += 1;
+
+        match num_created == self.num_created {
+            true => self.num_stable += 1,
+            false => self.num_stable = 0,
+        }
+        self.num_created = num_created;
+
+        if self.num_iterations >= self.config.min_warmup
+            && self.num_stable >= self.config.num_stable
+        {
+            WarmupState::Converged
+        } else if self.num_iterations >= self.config.max_warmup {
+            WarmupState::Exhausted
+        } else {
+            WarmupState::Running
+        }
+    }
+}
+
+impl Display for BenchmarkResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Benchmark ====\n")?;
+
+        let mut write_timings = |name: &str, timings: &IterationTimings| {
+            f.write_fmt(format_args!(
+                " - {name} => iterations: {} mean: {:?} median: {:?} min: {:?} max: {:?}\n",
+                timings.num_iterations(),
+                timings.mean(),
+                timings.median(),
+                timings.min(),
+                timings.max()
+            ))
+        };
+        write_timings("Warm-up", &self.warmup)?;
+        write_timings("Steady state", &self.steady)?;
+
+        f.write_fmt(format_args!(
+            " - Plans created: {}{}\n",
+            self.num_plans_created,
+            if self.converged {
+                ""
+            } else {
+                " (still creating plans)"
+            }
+        ))?;
+
+        f.write_str("==========================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_stop_warming_up_once_no_plan_is_created() {
+        let config = BenchmarkConfig {
+            min_warmup: 1,
+            max_warmup: 10,
+            num_stable: 2,
+            num_samples: 1,
+        };
+
+        let mut warmup = Warmup::new(config, 0);
+        assert_eq!(warmup.step(3), WarmupState::Running);
+        assert_eq!(warmup.step(3), WarmupState::Running);
+        assert_eq!(warmup.step(4), WarmupState::Running);
+        assert_eq!(warmup.step(4), WarmupState::Running);
+        assert_eq!(warmup.step(4), WarmupState::Converged);
+
+        // Plans are created at every iteration.
+        let mut warmup = Warmup::new(config, 0);
+        for num_created in 1..10 {
+            assert_eq!(warmup.step(num_created), WarmupState::Running);
+        }
+        assert_eq!(warmup.step(10), WarmupState::Exhausted);
+    }
+
+    #[test]
+    fn should_summarize_iterations() {
+        let timings = IterationTimings {
+            durations: [4, 1, 3, 2].map(Duration::from_millis).to_vec(),
+        };
+
+        assert_eq!(timings.total(), Duration::from_millis(10));
+        assert_eq!(timings.mean(), Duration::from_micros(2500));
+        assert_eq!(timings.median(), Duration::from_millis(3));
+        assert_eq!(timings.min(), Duration::from_millis(1));
+        assert_eq!(timings.max(), Duration::from_millis(4));
+    }
+}
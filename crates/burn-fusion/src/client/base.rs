@@ -1,12 +1,36 @@
-use std::future::Future;
+use std::{
+    future::Future,
+    sync::{Arc, atomic::AtomicU32, mpsc::Receiver},
+    time::Duration,
+};
+#[cfg(feature = "plan-export")]
+use std::{io::Write, path::Path};
 
 use crate::{
-    FusionBackend, FusionDevice, FusionHandle, FusionRuntime, FusionTensor,
-    stream::{OperationStreams, StreamId, execution::Operation},
+    CostModel, CustomOp, ExplorationPolicy, FusionBackend, FusionConfig, FusionDevice, FusionError,
+    FusionFilter, FusionHandle, FusionRuntime, FusionTensor, ReadCacheStats, TensorKind,
+    debug::{
+        CrossStreamReport, DetailedPlans, FusionDebugSummary, FusionExplanation, FusionHook,
+        FusionSnapshot, MirrorDivergence, MirrorOptions, OperationHistogram, PlanCacheStats,
+        SnapshotOptions, StreamInfo, TriggerReport,
+    },
+    memory::{
+        DefragmentationReport, FragmentationReport, MemoryPressureReport, MemoryPressureSignal,
+        MemoryReport, OrphanReport, TrackedTensor,
+    },
+    stream::{
+        AheadOfTimeReport, CapturedGraph, EventId, ExecutionPlanId, ExecutionPlanStoreStats,
+        FusionCheckpoint, OperationStreams, OutputPoolStats, PlanTrigger, RewriteRule,
+        ScalarParameterization, StreamId, StreamPriority, execution::Operation,
+    },
 };
-use burn_ir::{OperationIr, TensorIr};
+use burn_common::future::DynFut;
+use burn_ir::{Dim, OperationIr, TensorId, TensorIr};
 use burn_tensor::{DType, TensorData};
 
+#[cfg(feature = "plan-export")]
+use crate::stream::PlanExportMode;
+
 /// Define how to interact with the fusion server.
 pub trait FusionClient<R>: Send + Sync + Clone + Sized
 where
@@ -16,12 +40,197 @@ where
     fn new(device: FusionDevice<R>) -> Self;
     /// Register a new [tensor operation intermediate representation](OperationIr).
     fn register<O>(&self, streams: OperationStreams, repr: OperationIr, operation: O)
+    where
+        O: Operation<R> + 'static;
+    /// Register a new operation once checked, returning an error instead of panicking when it's
+    /// inconsistent or reads tensors that aren't tracked.
+    fn try_register<O>(
+        &self,
+        streams: OperationStreams,
+        repr: OperationIr,
+        operation: O,
+    ) -> Result<(), FusionError>
     where
         O: Operation<R> + 'static;
     /// Register all lazy computation.
     fn drain(&self);
+    /// Register all lazy computation of the given stream.
+    fn drain_stream(&self, stream: StreamId);
+    /// Register all lazy computation of the given stream from another thread, the returned
+    /// future completing once it's registered.
+    fn drain_stream_async(&self, stream: StreamId) -> DynFut<()>;
+    /// Register all lazy computation of the given stream from another thread, without waiting.
+    fn flush(&self, stream: StreamId);
+    /// Record an event after the operations currently queued on the given stream.
+    fn record_event(&self, stream: StreamId) -> EventId;
+    /// Execute the operations of the stream that recorded the event up to the event, on behalf
+    /// of the given stream.
+    fn wait_event(&self, stream: StreamId, event: EventId);
+    /// Start capturing the current stream, returning the tensors bound to the buffers of the
+    /// inputs.
+    fn begin_capture(&self, inputs: Vec<FusionTensor<R>>) -> Vec<FusionTensor<R>>;
+    /// Stop capturing the current stream, its operations being drained first.
+    ///
+    /// The inputs are the tensors returned by [begin_capture](Self::begin_capture), usually
+    /// consumed by the captured operations.
+    fn end_capture(
+        &self,
+        inputs: Vec<TensorIr>,
+        outputs: &[FusionTensor<R>],
+    ) -> Result<CapturedGraph<R>, FusionError>;
+    /// Execute a [captured graph](CapturedGraph) with new inputs.
+    fn replay_graph(
+        &self,
+        graph: &CapturedGraph<R>,
+        inputs: Vec<FusionTensor<R>>,
+    ) -> Result<Vec<FusionTensor<R>>, FusionError>;
+    /// Get the statistics of the [output pool](crate::stream::OutputPool) of the device.
+    fn output_pool_stats(&self) -> OutputPoolStats;
+    /// Set the maximum number of buffers kept alive by the [output pool](crate::stream::OutputPool)
+    /// of the device.
+    fn set_output_pool_capacity(&self, capacity: usize);
+    /// The statistics of the store of execution plans of the device.
+    fn execution_plan_stats(&self) -> ExecutionPlanStoreStats;
+    /// Update the maximum number of execution plans kept by the device, `None` meaning unbounded.
+    ///
+    /// The least recently executed plans are evicted first. Fails when the capacity can't fit the
+    /// pinned plans.
+    fn set_execution_plan_capacity(&self, capacity: Option<usize>) -> Result<(), FusionError>;
+    /// Pin the execution plan so that it is never evicted.
+    fn pin_plan(&self, id: ExecutionPlanId) -> Result<(), FusionError>;
+    /// Add a user trigger to the execution plan.
+    fn add_plan_trigger(
+        &self,
+        id: ExecutionPlanId,
+        trigger: PlanTrigger,
+    ) -> Result<(), FusionError>;
+    /// Unpin the execution plan, returning `false` if it wasn't pinned.
+    fn unpin_plan(&self, id: ExecutionPlanId) -> bool;
+    /// The ids of all pinned execution plans.
+    fn pinned_plans(&self) -> Vec<ExecutionPlanId>;
+    /// Pin every execution plan executed while enabled, returning the previous state.
+    fn pin_executed_plans(&self, enabled: bool) -> bool;
+    /// Write every execution plan to the file at `path`, returning the number of plans written.
+    #[cfg(feature = "plan-export")]
+    fn export_plans(&self, path: &Path) -> Result<usize, FusionError>;
+    /// Write the execution plans to `writer` as newline-delimited JSON, returning the number of
+    /// plans written.
+    #[cfg(feature = "plan-export")]
+    fn export_plans_ndjson(
+        &self,
+        writer: &mut dyn Write,
+        mode: PlanExportMode,
+    ) -> Result<usize, FusionError>;
+    /// Load the execution plans of the file at `path`, returning the number of plans added.
+    #[cfg(feature = "plan-export")]
+    fn import_plans(&self, path: &Path) -> Result<usize, FusionError>;
+    /// Encode every execution plan in the compact binary format.
+    #[cfg(feature = "plan-export")]
+    fn export_plans_to_bytes(&self) -> Result<Vec<u8>, FusionError>;
+    /// Load the execution plans encoded in the binary format, returning the number of plans
+    /// added.
+    #[cfg(feature = "plan-export")]
+    fn import_plans_from_bytes(&self, bytes: &[u8]) -> Result<usize, FusionError>;
+    /// Load the execution plans of another client of the same runtime, returning the number of
+    /// plans added.
+    fn import_plans_from(&self, other: &Self) -> usize;
+    /// Plan and compile the operations without executing them.
+    fn compile_ahead_of_time(&self, operations: &[OperationIr]) -> AheadOfTimeReport;
+    /// Update the [priority](StreamPriority) of the stream, deciding which streams are drained
+    /// first.
+    fn set_stream_priority(&self, stream: StreamId, priority: StreamPriority);
+    /// Update which scalars are replaced by parameters in execution plans, so that plans only
+    /// differing by those scalars are shared.
+    fn set_scalar_parameterization(&self, parameterization: ScalarParameterization);
+    /// Register the [custom operation](CustomOp), replacing any operation with the same name.
+    fn register_custom_op(&self, op: Arc<dyn CustomOp<R>>);
+    /// The [custom operation](CustomOp) registered with the given name.
+    fn custom_op(&self, name: &str) -> Option<Arc<dyn CustomOp<R>>>;
+    /// Run the [rule](RewriteRule) on the operations of every stream when it's drained.
+    fn register_rewrite(&self, rule: Box<dyn RewriteRule<R>>);
+    /// Update the [policy](ExplorationPolicy) deciding how new optimizations are explored on
+    /// every stream.
+    fn set_exploration_policy(&self, policy: Arc<dyn ExplorationPolicy>);
+    /// Update the [cost model](CostModel) deciding whether the optimizations explored on every
+    /// stream are used.
+    fn set_cost_model(&self, cost_model: Arc<dyn CostModel>);
+    /// Update which kinds of operations can be fused on every stream.
+    fn set_fusion_filter(&self, filter: FusionFilter);
+    /// The configuration of the fusion server.
+    fn config(&self) -> FusionConfig;
+    /// Update the configuration of the fusion server.
+    fn set_config(&self, config: FusionConfig);
+    /// Remove the execution plans only executed by streams whose thread is finished, returning
+    /// the ids of the removed plans.
+    fn collect_finished_stream_plans(&self) -> Vec<ExecutionPlanId>;
+    /// Register a [debug hook](FusionHook) on the fusion server.
+    fn register_debug_hook(&self, hook: Box<dyn FusionHook>);
+    /// Group the queued and planned operations of the device in a histogram.
+    fn operation_histogram(&self) -> OperationHistogram;
+    /// Explain the triggers of every execution plan of the device.
+    fn trigger_report(&self) -> TriggerReport;
+    /// Explain why the given operations are or aren't fused by the explorer of the device.
+    fn explain_fusion(&self, operations: &[OperationIr]) -> FusionExplanation;
+    /// Collect the plan cache counters of the device.
+    fn debug_cache_stats(&self) -> PlanCacheStats;
+    /// The accumulated execution times of the plans of the device, slowest first.
+    fn debug_detailed_plans(&self) -> DetailedPlans;
+    /// Copy a bounded snapshot of the queues and plans of the device.
+    fn debug_snapshot(&self, options: SnapshotOptions) -> FusionSnapshot;
+    /// The syncs between the streams of the device caused by shared tensors.
+    fn cross_stream_report(&self) -> CrossStreamReport;
+    /// The fingerprint of the computation that produced the given tensor.
+    fn tensor_fingerprint(&self, id: TensorId) -> u64;
+    /// Bind a dimension of the tensor to the symbol with the given name.
+    fn bind_symbolic_dim(&self, tensor: &TensorIr, axis: usize, name: &str);
+    /// The dimensions of the tensor, symbolic where bound to a symbol.
+    fn symbolic_dims(&self, tensor: &TensorIr) -> Vec<Dim>;
+    /// Where and when each stream of the device was first used.
+    fn debug_stream_info(&self) -> Vec<StreamInfo>;
+    /// Collect a summary of the fusion server of the device.
+    fn debug_summary(&self) -> FusionDebugSummary;
+    /// Send a summary of the fusion server on the returned channel every `interval`, until the
+    /// receiver is dropped.
+    fn subscribe_summaries(&self, interval: Duration) -> Receiver<FusionDebugSummary>;
+    /// Mirror every following operation on the reference execution of the device.
+    fn enable_mirror(&self, options: MirrorOptions);
+    /// Drain all streams and compare every mirrored tensor with its reference.
+    fn mirror_sync<B>(&self) -> Option<MirrorDivergence>
+    where
+        B: FusionBackend<FusionRuntime = R>;
+    /// The first divergence found by the mirror, if any.
+    fn mirror_divergence(&self) -> Option<MirrorDivergence>;
+    /// Report where the memory of the device goes.
+    fn memory_report(&self) -> MemoryReport;
+    /// Report how the memory of the live tensors on the device is fragmented.
+    fn fragmentation_report(&self) -> FragmentationReport;
+    /// Every tensor tracked by the device, initialized or not.
+    fn tracked_tensors(&self) -> Vec<TrackedTensor>;
+    /// Drain all streams and move the live tensors into fresh allocations.
+    fn defragment(&self) -> DefragmentationReport;
+    /// Copy the handles and the queued operations of the server.
+    fn checkpoint(&self) -> FusionCheckpoint<R>;
+    /// Replace the handles and the queued operations of the server with the ones of the checkpoint.
+    fn restore(&self, checkpoint: &FusionCheckpoint<R>);
+    /// Start recording the references of the tensors created, returning `false` if already started.
+    fn track_tensor_references(&self) -> bool;
+    /// Record the reference count of a new tensor, if the references are being tracked.
+    fn track_tensor(&self, id: TensorId, count: &Arc<AtomicU32>);
+    /// The handles created since the references are tracked that no tensor references anymore,
+    /// removing them when `collect` is set.
+    fn orphaned_handles(&self, collect: bool) -> OrphanReport;
+    /// The signal raising memory pressure on the server.
+    fn memory_pressure_signal(&self) -> MemoryPressureSignal;
+    /// Drain all streams and release the memory that the configuration allows.
+    fn relieve_memory_pressure(&self) -> MemoryPressureReport;
     /// Get the current device used by all operations handled by this client.
     fn device(&self) -> &FusionDevice<R>;
+    /// Pin the handle of the given tensor, returning `false` if the tensor has no handle.
+    fn pin_tensor(&self, id: TensorId, stream: StreamId) -> bool;
+    /// Release the handle pinned for the given tensor, returning `false` if it wasn't pinned.
+    fn unpin_tensor(&self, id: TensorId) -> bool;
+    /// The ids of all pinned tensors.
+    fn pinned_tensors(&self) -> Vec<TensorId>;
     /// Create a new [fusion tensor](FusionTensor), but with no resources allocated to it.
     fn tensor_uninitialized(&self, shape: Vec<usize>, dtype: DType) -> FusionTensor<R>;
     /// Create a tensor with the given handle and shape.
@@ -64,6 +273,26 @@ where
     ) -> impl Future<Output = TensorData> + Send
     where
         B: FusionBackend<FusionRuntime = R>;
+    /// Read the values contained by a tensor of the given kind, returning an error instead of
+    /// panicking when the tensor has no buffer.
+    fn try_read_tensor<B>(
+        self,
+        tensor: TensorIr,
+        kind: TensorKind,
+        stream: StreamId,
+    ) -> impl Future<Output = Result<TensorData, FusionError>> + Send
+    where
+        B: FusionBackend<FusionRuntime = R>;
+    /// Read the values contained by multiple tensors of the given stream, of any kind.
+    fn read_tensors<B>(
+        self,
+        tensors: Vec<(TensorIr, TensorKind)>,
+        stream: StreamId,
+    ) -> impl Future<Output = Vec<TensorData>> + Send
+    where
+        B: FusionBackend<FusionRuntime = R>;
+    /// How often reads were served by the data of a previous read.
+    fn read_cache_stats(&self) -> ReadCacheStats;
     /// Resolve the given float tensor to a primitive tensor.
     fn resolve_tensor_float<B>(&self, tensor: FusionTensor<R>) -> B::FloatTensorPrimitive
     where
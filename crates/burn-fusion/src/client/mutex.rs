@@ -1,17 +1,92 @@
 use super::FusionClient;
 use crate::{
-    FusionBackend, FusionDevice, FusionHandle, FusionRuntime, FusionServer, FusionTensor,
-    stream::{OperationStreams, StreamId, execution::Operation},
+    CostModel, CustomOp, ExplorationPolicy, FusionBackend, FusionConfig, FusionDevice, FusionError,
+    FusionFilter, FusionHandle, FusionRuntime, FusionServer, FusionTensor, QueueLimitPolicy,
+    ReadCacheStats, TensorKind,
+    debug::{
+        CrossStreamReport, DetailedPlans, FusionDebugSummary, FusionExplanation, FusionHook,
+        FusionSnapshot, MirrorDivergence, MirrorOptions, OperationHistogram, PlanCacheStats,
+        SnapshotOptions, StreamInfo, TriggerReport,
+    },
+    memory::{
+        DefragmentationReport, FragmentationReport, MemoryPressureReport, MemoryPressureSignal,
+        MemoryReport, OrphanReport, TensorReferences, TrackedTensor,
+    },
+    stream::{
+        AheadOfTimeReport, CapturedGraph, EventId, ExecutionPlanId, ExecutionPlanStoreStats,
+        ExecutionSignal, FusionCheckpoint, OperationStreams, OutputPoolStats, PlanTrigger,
+        RewriteRule, ScalarParameterization, StreamId, StreamPriority, current_stream,
+        execution::Operation, is_deterministic,
+    },
+    transfer::{
+        ReceiveOp, SendOp, ToDeviceFn, bool_to_device, float_to_device, int_to_device,
+        quantized_to_device,
+    },
 };
-use burn_ir::{OperationIr, TensorIr};
+use burn_common::future::DynFut;
+use burn_ir::{CustomOpIr, Dim, OperationIr, TensorId, TensorIr};
 use burn_tensor::{DType, TensorData};
-use spin::Mutex;
-use std::sync::Arc;
+use spin::{Mutex, MutexGuard};
+#[cfg(feature = "plan-export")]
+use std::{io::Write, path::Path};
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::AtomicU32,
+        mpsc::{self, Receiver},
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use web_time::Instant;
+
+#[cfg(feature = "plan-export")]
+use crate::stream::PlanExportMode;
 
 /// Use a mutex to communicate with the fusion server.
 pub struct MutexFusionClient<R: FusionRuntime> {
     server: Arc<Mutex<FusionServer<R>>>,
     device: FusionDevice<R>,
+    references: Arc<TensorReferences>,
+}
+
+/// Completed by the thread draining a stream in the background.
+#[derive(Default)]
+struct DrainFuture {
+    state: Arc<Mutex<DrainState>>,
+}
+
+#[derive(Default)]
+struct DrainState {
+    completed: bool,
+    waker: Option<Waker>,
+}
+
+impl DrainState {
+    fn complete(&mut self) {
+        self.completed = true;
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for DrainFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock();
+
+        match state.completed {
+            true => Poll::Ready(()),
+            false => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
 }
 
 impl<R> Clone for MutexFusionClient<R>
@@ -22,7 +97,161 @@ where
         Self {
             server: self.server.clone(),
             device: self.device.clone(),
+            references: self.references.clone(),
+        }
+    }
+}
+
+impl<R: FusionRuntime> MutexFusionClient<R> {
+    /// Relieve the memory pressure when signaled or detected by the server.
+    fn check_memory_pressure(&self, server: &mut FusionServer<R>) {
+        if server.take_memory_pressure() {
+            let report = self.relieve_memory_pressure_locked(server);
+            log::debug!("Relieved memory pressure: {report}");
+        }
+    }
+
+    fn relieve_memory_pressure_locked(&self, server: &mut FusionServer<R>) -> MemoryPressureReport {
+        let references = self.references.is_enabled().then(|| self.references.live());
+
+        server.relieve_memory_pressure(references.as_ref().map(|(since, live)| (*since, live)))
+    }
+
+    /// Start a thread draining the idle streams when operations are queued with an idle timeout.
+    ///
+    /// A single thread drains them, until every queue is empty, the timeout is disabled or the
+    /// server is dropped.
+    #[cfg(not(target_family = "wasm"))]
+    fn arm_idle_watcher(&self, server: &mut FusionServer<R>) {
+        if !server.arm_idle_watcher() {
+            return;
+        }
+
+        let server = Arc::downgrade(&self.server);
+        std::thread::spawn(move || {
+            let mut wait = Duration::ZERO;
+
+            loop {
+                std::thread::sleep(wait);
+
+                let server = match server.upgrade() {
+                    Some(server) => server,
+                    None => break,
+                };
+                match server.lock().drain_idle_streams(Instant::now()) {
+                    Some(next) => wait = next,
+                    None => break,
+                }
+            }
+        });
+    }
+
+    /// Threads can't be spawned on wasm, where idle streams are only drained when read.
+    #[cfg(target_family = "wasm")]
+    fn arm_idle_watcher(&self, _server: &mut FusionServer<R>) {}
+
+    /// Release the server, then wait for the stream to get back within the queue limits when
+    /// the configuration blocks on them.
+    fn block_on_queue_limits(&self, mut server: MutexGuard<'_, FusionServer<R>>, stream: StreamId) {
+        let QueueLimitPolicy::Block(timeout) = server.config().on_queue_limit else {
+            return;
+        };
+        if !server.exceeds_queue_limits(stream) {
+            return;
+        }
+        if is_deterministic() {
+            server.drain_stream(stream);
+            return;
         }
+
+        let signal = server.execution_signal();
+        let num_notified = signal.num_notified();
+        core::mem::drop(server);
+        self.wait_for_queue(stream, &signal, num_notified, timeout);
+    }
+
+    /// Wait for the stream to get back within the queue limits, draining it after the timeout.
+    ///
+    /// The server notifies the signal every time it executes operations, which are counted
+    /// while it's locked, so no execution is missed between releasing the server and waiting.
+    fn wait_for_queue(
+        &self,
+        stream: StreamId,
+        signal: &ExecutionSignal,
+        mut num_notified: u64,
+        timeout: Duration,
+    ) {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let notified = signal.wait(num_notified, deadline);
+
+            let mut server = self.server.lock();
+            if !server.exceeds_queue_limits(stream) {
+                return;
+            }
+            if !notified {
+                log::debug!(
+                    "Draining stream {stream} exceeding the queue limits after {timeout:?}"
+                );
+                server.drain_stream(stream);
+                return;
+            }
+            num_notified = signal.num_notified();
+        }
+    }
+}
+
+impl<R> MutexFusionClient<R>
+where
+    R: FusionRuntime<FusionClient = Self> + 'static,
+{
+    /// Move the tensor to the device of the other client without waiting for it.
+    ///
+    /// The tensor is sent by an operation queued on the source device, whose stream is drained
+    /// before the receiving operation is queued on the target device. The transfer is only
+    /// enqueued on the source device, so it overlaps with the operations registered next on both
+    /// devices, and the target server never waits on the source server while holding its lock.
+    fn transfer<B>(
+        &self,
+        tensor: TensorIr,
+        client: Self,
+        stream: StreamId,
+        to_device: ToDeviceFn<B>,
+    ) -> FusionTensor<R>
+    where
+        B: FusionBackend<FusionRuntime = R>,
+    {
+        let current = current_stream();
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let out = client.tensor_uninitialized(tensor.shape.clone(), tensor.dtype);
+
+        let mut streams = OperationStreams::default();
+        streams.streams.insert(tensor.id, stream);
+        let send = CustomOpIr {
+            id: "transfer_send".to_string(),
+            inputs: vec![tensor.clone()],
+            outputs: Vec::new(),
+        };
+        self.register(
+            streams,
+            OperationIr::Custom(send),
+            SendOp::<B>::new(tensor, client.device.clone(), to_device, sender),
+        );
+        self.server.lock().drain_stream(current);
+
+        let receive = CustomOpIr {
+            id: "transfer_receive".to_string(),
+            inputs: Vec::new(),
+            outputs: vec![out.to_ir_out()],
+        };
+        client.register(
+            OperationStreams::default(),
+            OperationIr::Custom(receive),
+            ReceiveOp::new(out.id, receiver),
+        );
+
+        out
     }
 }
 
@@ -34,6 +263,7 @@ where
         Self {
             device: device.clone(),
             server: Arc::new(Mutex::new(FusionServer::new(device))),
+            references: Arc::new(TensorReferences::default()),
         }
     }
 
@@ -41,20 +271,431 @@ where
     where
         O: Operation<R> + 'static,
     {
-        self.server
-            .lock()
-            .register(streams, repr, Arc::new(operation))
+        let current = streams.current;
+        let mut server = self.server.lock();
+        server.register(streams, repr, Arc::new(operation));
+        self.check_memory_pressure(&mut server);
+        self.arm_idle_watcher(&mut server);
+        self.block_on_queue_limits(server, current);
+    }
+
+    fn try_register<O>(
+        &self,
+        streams: OperationStreams,
+        repr: OperationIr,
+        operation: O,
+    ) -> Result<(), FusionError>
+    where
+        O: Operation<R> + 'static,
+    {
+        let current = streams.current;
+        let mut server = self.server.lock();
+        server.try_register(streams, repr, Arc::new(operation))?;
+        self.check_memory_pressure(&mut server);
+        self.arm_idle_watcher(&mut server);
+        self.block_on_queue_limits(server, current);
+
+        Ok(())
     }
 
     fn drain(&self) {
-        let id = StreamId::current();
+        let id = current_stream();
         self.server.lock().drain_stream(id);
     }
 
+    fn drain_stream(&self, stream: StreamId) {
+        self.server.lock().drain_stream(stream);
+    }
+
+    fn drain_stream_async(&self, stream: StreamId) -> DynFut<()> {
+        let future = DrainFuture::default();
+        let state = future.state.clone();
+
+        if is_deterministic() {
+            self.server.lock().drain_stream(stream);
+            state.lock().complete();
+            return Box::pin(future);
+        }
+        let server = self.server.clone();
+
+        std::thread::spawn(move || {
+            server.lock().drain_stream(stream);
+            state.lock().complete();
+        });
+
+        Box::pin(future)
+    }
+
+    fn flush(&self, stream: StreamId) {
+        if is_deterministic() {
+            self.server.lock().drain_stream(stream);
+            return;
+        }
+
+        let server = self.server.clone();
+        std::thread::spawn(move || server.lock().drain_stream(stream));
+    }
+
+    fn record_event(&self, stream: StreamId) -> EventId {
+        self.server.lock().record_event(stream)
+    }
+
+    fn wait_event(&self, stream: StreamId, event: EventId) {
+        self.server.lock().wait_event(stream, event);
+    }
+
+    fn begin_capture(&self, inputs: Vec<FusionTensor<R>>) -> Vec<FusionTensor<R>> {
+        let stream = current_stream();
+        let inputs = inputs
+            .into_iter()
+            .map(|input| {
+                let stream = input.stream;
+                (input.into_ir(), stream)
+            })
+            .collect::<Vec<_>>();
+        let shapes = inputs
+            .iter()
+            .map(|(input, _)| (input.shape.clone(), input.dtype))
+            .collect::<Vec<_>>();
+
+        let mut server = self.server.lock();
+        let ids = server.begin_capture(stream, inputs);
+
+        ids.into_iter()
+            .zip(shapes)
+            .map(|(id, (shape, dtype))| FusionTensor::new(id, shape, dtype, self.clone(), stream))
+            .collect()
+    }
+
+    fn end_capture(
+        &self,
+        inputs: Vec<TensorIr>,
+        outputs: &[FusionTensor<R>],
+    ) -> Result<CapturedGraph<R>, FusionError> {
+        let capture = self.server.lock().end_capture(
+            current_stream(),
+            inputs,
+            outputs.iter().map(FusionTensor::to_ir_out).collect(),
+        )?;
+
+        Ok(CapturedGraph {
+            client: self.clone(),
+            capture: Arc::new(capture),
+        })
+    }
+
+    fn replay_graph(
+        &self,
+        graph: &CapturedGraph<R>,
+        inputs: Vec<FusionTensor<R>>,
+    ) -> Result<Vec<FusionTensor<R>>, FusionError> {
+        let stream = current_stream();
+        let inputs = inputs
+            .into_iter()
+            .map(|input| {
+                let stream = input.stream;
+                (input.into_ir(), stream)
+            })
+            .collect();
+
+        let mut server = self.server.lock();
+        let ids = server.replay_graph(&graph.capture, inputs)?;
+
+        Ok(ids
+            .into_iter()
+            .zip(graph.capture.tensors.outputs())
+            .map(|(id, output)| {
+                FusionTensor::new(id, output.shape.clone(), output.dtype, self.clone(), stream)
+            })
+            .collect())
+    }
+
+    fn output_pool_stats(&self) -> OutputPoolStats {
+        self.server.lock().output_pool_stats()
+    }
+
+    fn set_output_pool_capacity(&self, capacity: usize) {
+        self.server.lock().set_output_pool_capacity(capacity);
+    }
+
+    fn execution_plan_stats(&self) -> ExecutionPlanStoreStats {
+        self.server.lock().execution_plan_stats()
+    }
+
+    fn set_execution_plan_capacity(&self, capacity: Option<usize>) -> Result<(), FusionError> {
+        self.server.lock().set_execution_plan_capacity(capacity)
+    }
+
+    fn pin_plan(&self, id: ExecutionPlanId) -> Result<(), FusionError> {
+        self.server.lock().pin_plan(id)
+    }
+
+    fn add_plan_trigger(
+        &self,
+        id: ExecutionPlanId,
+        trigger: PlanTrigger,
+    ) -> Result<(), FusionError> {
+        self.server.lock().add_plan_trigger(id, trigger)
+    }
+
+    fn unpin_plan(&self, id: ExecutionPlanId) -> bool {
+        self.server.lock().unpin_plan(id)
+    }
+
+    fn pinned_plans(&self) -> Vec<ExecutionPlanId> {
+        self.server.lock().pinned_plans()
+    }
+
+    fn pin_executed_plans(&self, enabled: bool) -> bool {
+        self.server.lock().pin_executed_plans(enabled)
+    }
+
+    #[cfg(feature = "plan-export")]
+    fn export_plans(&self, path: &Path) -> Result<usize, FusionError> {
+        self.server.lock().export_plans(path)
+    }
+
+    #[cfg(feature = "plan-export")]
+    fn export_plans_ndjson(
+        &self,
+        writer: &mut dyn Write,
+        mode: PlanExportMode,
+    ) -> Result<usize, FusionError> {
+        self.server.lock().export_plans_ndjson(writer, mode)
+    }
+
+    #[cfg(feature = "plan-export")]
+    fn import_plans(&self, path: &Path) -> Result<usize, FusionError> {
+        self.server.lock().import_plans(path)
+    }
+
+    #[cfg(feature = "plan-export")]
+    fn export_plans_to_bytes(&self) -> Result<Vec<u8>, FusionError> {
+        self.server.lock().export_plans_to_bytes()
+    }
+
+    #[cfg(feature = "plan-export")]
+    fn import_plans_from_bytes(&self, bytes: &[u8]) -> Result<usize, FusionError> {
+        self.server.lock().import_plans_from_bytes(bytes)
+    }
+
+    fn import_plans_from(&self, other: &Self) -> usize {
+        // The servers are locked one after the other, since they might be the same.
+        let state = other.server.lock().plans_state();
+        self.server.lock().import_plans_state(state)
+    }
+
+    fn compile_ahead_of_time(&self, operations: &[OperationIr]) -> AheadOfTimeReport {
+        self.server.lock().compile_ahead_of_time(operations)
+    }
+
+    fn set_stream_priority(&self, stream: StreamId, priority: StreamPriority) {
+        self.server.lock().set_stream_priority(stream, priority);
+    }
+
+    fn set_scalar_parameterization(&self, parameterization: ScalarParameterization) {
+        self.server
+            .lock()
+            .set_scalar_parameterization(parameterization);
+    }
+
+    fn register_custom_op(&self, op: Arc<dyn CustomOp<R>>) {
+        self.server.lock().register_custom_op(op);
+    }
+
+    fn custom_op(&self, name: &str) -> Option<Arc<dyn CustomOp<R>>> {
+        self.server.lock().custom_op(name)
+    }
+
+    fn register_rewrite(&self, rule: Box<dyn RewriteRule<R>>) {
+        self.server.lock().register_rewrite(rule);
+    }
+
+    fn set_exploration_policy(&self, policy: Arc<dyn ExplorationPolicy>) {
+        self.server.lock().set_exploration_policy(policy);
+    }
+
+    fn set_cost_model(&self, cost_model: Arc<dyn CostModel>) {
+        self.server.lock().set_cost_model(cost_model);
+    }
+
+    fn set_fusion_filter(&self, filter: FusionFilter) {
+        self.server.lock().set_fusion_filter(filter);
+    }
+
+    fn config(&self) -> FusionConfig {
+        self.server.lock().config()
+    }
+
+    fn set_config(&self, config: FusionConfig) {
+        let mut server = self.server.lock();
+        server.set_config(config);
+        self.arm_idle_watcher(&mut server);
+    }
+
+    fn collect_finished_stream_plans(&self) -> Vec<ExecutionPlanId> {
+        self.server.lock().collect_finished_stream_plans()
+    }
+
+    fn register_debug_hook(&self, hook: Box<dyn FusionHook>) {
+        self.server.lock().register_debug_hook(hook);
+    }
+
+    fn operation_histogram(&self) -> OperationHistogram {
+        self.server.lock().operation_histogram()
+    }
+
+    fn trigger_report(&self) -> TriggerReport {
+        self.server.lock().trigger_report()
+    }
+
+    fn explain_fusion(&self, operations: &[OperationIr]) -> FusionExplanation {
+        self.server.lock().explain_fusion(operations)
+    }
+
+    fn debug_cache_stats(&self) -> PlanCacheStats {
+        self.server.lock().debug_cache_stats()
+    }
+
+    fn debug_detailed_plans(&self) -> DetailedPlans {
+        self.server.lock().debug_detailed_plans()
+    }
+
+    fn debug_snapshot(&self, options: SnapshotOptions) -> FusionSnapshot {
+        // The lock is released as soon as the snapshot is copied.
+        self.server.lock().debug_snapshot(options)
+    }
+
+    fn cross_stream_report(&self) -> CrossStreamReport {
+        self.server.lock().cross_stream_report()
+    }
+
+    fn tensor_fingerprint(&self, id: TensorId) -> u64 {
+        self.server.lock().tensor_fingerprint(id)
+    }
+
+    fn bind_symbolic_dim(&self, tensor: &TensorIr, axis: usize, name: &str) {
+        self.server.lock().bind_symbolic_dim(tensor, axis, name);
+    }
+
+    fn symbolic_dims(&self, tensor: &TensorIr) -> Vec<Dim> {
+        self.server.lock().symbolic_dims(tensor)
+    }
+
+    fn debug_stream_info(&self) -> Vec<StreamInfo> {
+        self.server.lock().debug_stream_info()
+    }
+
+    fn debug_summary(&self) -> FusionDebugSummary {
+        self.server.lock().debug_summary()
+    }
+
+    fn subscribe_summaries(&self, interval: Duration) -> Receiver<FusionDebugSummary> {
+        let (sender, receiver) = mpsc::channel();
+        let server = self.server.clone();
+
+        // The summary is collected on its own thread, so the server is only locked once per
+        // interval no matter how many times the receiver is polled.
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+                let summary = server.lock().debug_summary();
+
+                if sender.send(summary).is_err() {
+                    break;
+                }
+            }
+        });
+
+        receiver
+    }
+
+    fn enable_mirror(&self, options: MirrorOptions) {
+        self.server.lock().enable_mirror(options);
+    }
+
+    fn mirror_sync<B>(&self) -> Option<MirrorDivergence>
+    where
+        B: FusionBackend<FusionRuntime = R>,
+    {
+        self.server.lock().mirror_sync::<B>()
+    }
+
+    fn mirror_divergence(&self) -> Option<MirrorDivergence> {
+        self.server.lock().mirror_divergence()
+    }
+
+    fn memory_report(&self) -> MemoryReport {
+        self.server.lock().memory_report()
+    }
+
+    fn fragmentation_report(&self) -> FragmentationReport {
+        self.server.lock().fragmentation_report()
+    }
+
+    fn tracked_tensors(&self) -> Vec<TrackedTensor> {
+        self.server.lock().tracked_tensors()
+    }
+
+    fn defragment(&self) -> DefragmentationReport {
+        self.server.lock().defragment()
+    }
+
+    fn checkpoint(&self) -> FusionCheckpoint<R> {
+        self.server.lock().checkpoint()
+    }
+
+    fn restore(&self, checkpoint: &FusionCheckpoint<R>) {
+        self.server.lock().restore(checkpoint)
+    }
+
+    fn track_tensor_references(&self) -> bool {
+        let server = self.server.lock();
+        self.references.enable(server.num_tensors_created())
+    }
+
+    fn track_tensor(&self, id: TensorId, count: &Arc<AtomicU32>) {
+        self.references.register(id, count);
+    }
+
+    fn orphaned_handles(&self, collect: bool) -> OrphanReport {
+        if !self.references.is_enabled() {
+            return OrphanReport::default();
+        }
+
+        // Tensors are created and their references recorded under the server lock, so every
+        // tensor created before the snapshot has its reference in it.
+        let mut server = self.server.lock();
+        let (since, live) = self.references.live();
+        server.orphaned_handles(since, &live, collect)
+    }
+
+    fn memory_pressure_signal(&self) -> MemoryPressureSignal {
+        self.server.lock().memory_pressure_signal()
+    }
+
+    fn relieve_memory_pressure(&self) -> MemoryPressureReport {
+        let mut server = self.server.lock();
+        self.relieve_memory_pressure_locked(&mut server)
+    }
+
+    fn pin_tensor(&self, id: TensorId, stream: StreamId) -> bool {
+        self.server.lock().pin_tensor(id, stream)
+    }
+
+    fn unpin_tensor(&self, id: TensorId) -> bool {
+        self.server.lock().unpin_tensor(id)
+    }
+
+    fn pinned_tensors(&self) -> Vec<TensorId> {
+        self.server.lock().pinned_tensors()
+    }
+
     fn tensor_uninitialized(&self, shape: Vec<usize>, dtype: DType) -> FusionTensor<R> {
-        let id = self.server.lock().create_empty_handle();
+        let mut server = self.server.lock();
+        let id = server.create_empty_handle(shape.clone(), dtype);
 
-        FusionTensor::new(id, shape, dtype, self.clone(), StreamId::current())
+        FusionTensor::new(id, shape, dtype, self.clone(), current_stream())
     }
 
     fn device(&self) -> &FusionDevice<R> {
@@ -69,9 +710,7 @@ where
         dtype: DType,
     ) -> FusionTensor<R> {
         let mut server = self.server.lock();
-        let id = server.create_empty_handle();
-        server.handles.register_handle(id, handle);
-        core::mem::drop(server);
+        let id = server.register_handle(handle, shape.clone(), dtype);
 
         FusionTensor::new(id, shape, dtype, self.clone(), stream)
     }
@@ -120,6 +759,35 @@ where
         self.server.lock().read_quantized::<B>(tensor, stream)
     }
 
+    fn try_read_tensor<B>(
+        self,
+        tensor: TensorIr,
+        kind: TensorKind,
+        stream: StreamId,
+    ) -> impl Future<Output = Result<TensorData, FusionError>> + Send
+    where
+        B: FusionBackend<FusionRuntime = R>,
+    {
+        let read = self.server.lock().try_read::<B>(tensor, kind, stream);
+
+        async move { Ok(read?.await) }
+    }
+
+    fn read_tensors<B>(
+        self,
+        tensors: Vec<(TensorIr, TensorKind)>,
+        stream: StreamId,
+    ) -> impl Future<Output = Vec<TensorData>> + Send
+    where
+        B: FusionBackend<FusionRuntime = R>,
+    {
+        self.server.lock().read_batch::<B>(tensors, stream)
+    }
+
+    fn read_cache_stats(&self) -> ReadCacheStats {
+        self.server.lock().read_cache_stats()
+    }
+
     fn change_client_float<B>(
         &self,
         tensor: TensorIr,
@@ -129,17 +797,7 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        let mut server_other = client.server.lock();
-        let mut server_current = self.server.lock();
-        server_current.drain_stream(stream);
-
-        let id =
-            server_current.change_server_float::<B>(&tensor, &client.device, &mut server_other);
-
-        core::mem::drop(server_other);
-        core::mem::drop(server_current);
-
-        FusionTensor::new(id, tensor.shape, tensor.dtype, client, StreamId::current())
+        self.transfer::<B>(tensor, client, stream, float_to_device::<B>)
     }
 
     fn change_client_int<B>(
@@ -151,16 +809,7 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        let mut server_other = client.server.lock();
-        let mut server_current = self.server.lock();
-        server_current.drain_stream(stream);
-
-        let id = server_current.change_server_int::<B>(&tensor, &client.device, &mut server_other);
-
-        core::mem::drop(server_other);
-        core::mem::drop(server_current);
-
-        FusionTensor::new(id, tensor.shape, tensor.dtype, client, StreamId::current())
+        self.transfer::<B>(tensor, client, stream, int_to_device::<B>)
     }
 
     fn change_client_bool<B>(
@@ -172,16 +821,7 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        let mut server_other = client.server.lock();
-        let mut server_current = self.server.lock();
-        server_current.drain_stream(stream);
-
-        let id = server_current.change_server_bool::<B>(&tensor, &client.device, &mut server_other);
-
-        core::mem::drop(server_other);
-        core::mem::drop(server_current);
-
-        FusionTensor::new(id, tensor.shape, tensor.dtype, client, StreamId::current())
+        self.transfer::<B>(tensor, client, stream, bool_to_device::<B>)
     }
 
     fn change_client_quantized<B>(
@@ -193,17 +833,7 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        let mut server_other = client.server.lock();
-        let mut server_current = self.server.lock();
-        server_current.drain_stream(stream);
-
-        let id =
-            server_current.change_server_quantized::<B>(&tensor, &client.device, &mut server_other);
-
-        core::mem::drop(server_other);
-        core::mem::drop(server_current);
-
-        FusionTensor::new(id, tensor.shape, tensor.dtype, client, StreamId::current())
+        self.transfer::<B>(tensor, client, stream, quantized_to_device::<B>)
     }
 
     fn resolve_tensor_float<B>(&self, tensor: FusionTensor<R>) -> B::FloatTensorPrimitive
@@ -233,3 +863,530 @@ where
         server.resolve_server_bool::<B>(&tensor.into_ir())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{HandleContainer, HandleKind, TensorStatus};
+    use burn_ndarray::NdArrayDevice;
+    use burn_tensor::ops::FloatTensorOps;
+
+    use super::*;
+    use crate::{
+        Fusion, PlanTiming,
+        memory::{DTypeMemory, StreamMemory},
+        stream::TimingSource,
+        test_utils::{
+            self, TestBackend, TestClient, TestRuntime, float_data, float_tensor, with_lazy_streams,
+        },
+    };
+
+    #[derive(Debug)]
+    struct NoOp;
+
+    impl Operation<TestRuntime> for NoOp {
+        fn execute(&self, _handles: &mut HandleContainer<HandleKind<TestBackend>>) {}
+    }
+
+    #[test]
+    fn should_complete_drain_future_from_another_thread() {
+        let future = DrainFuture::default();
+        let state = future.state.clone();
+
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            state.lock().complete();
+        });
+
+        burn_common::future::block_on(future);
+        handle.join().unwrap();
+    }
+
+    fn queue_limited(policy: QueueLimitPolicy) -> (TestClient, FusionTensor<TestRuntime>) {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        client.set_config(FusionConfig {
+            max_queued_ops: Some(2),
+            on_queue_limit: policy,
+            ..Default::default()
+        });
+        let tensor = float_tensor(&client, TensorData::from([0.0f32]));
+
+        (client, tensor)
+    }
+
+    fn num_queued(client: &TestClient) -> usize {
+        client
+            .debug_snapshot(SnapshotOptions::default())
+            .streams
+            .iter()
+            .map(|stream| stream.num_queued)
+            .sum()
+    }
+
+    fn exp(tensor: FusionTensor<TestRuntime>) -> FusionTensor<TestRuntime> {
+        Fusion::<TestBackend>::float_exp(tensor)
+    }
+
+    #[test]
+    fn should_drain_streams_exceeding_the_queue_limits() {
+        let (client, tensor) = queue_limited(QueueLimitPolicy::Flush);
+
+        with_lazy_streams(|| {
+            let tensor = exp(exp(tensor));
+            assert_eq!(num_queued(&client), 2);
+
+            let tensor = exp(tensor);
+            assert_eq!(num_queued(&client), 0);
+
+            float_data(tensor).assert_approx_eq::<f32>(
+                &TensorData::from([1.0f32.exp().exp()]),
+                Default::default(),
+            );
+        });
+    }
+
+    #[test]
+    fn should_panic_before_queuing_past_the_queue_limits() {
+        let (client, tensor) = queue_limited(QueueLimitPolicy::Panic);
+
+        with_lazy_streams(|| {
+            let tensor = exp(exp(tensor));
+            // Without any tensor to drop while unwinding, which would drain the stream.
+            let operation = test_utils::exp(
+                test_utils::tensor(100, TensorStatus::ReadOnly),
+                test_utils::tensor(101, TensorStatus::NotInit),
+            );
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                client.register(OperationStreams::default(), operation, NoOp);
+            }));
+
+            assert!(result.is_err());
+            assert_eq!(num_queued(&client), 2);
+            float_data(tensor)
+                .assert_approx_eq::<f32>(&TensorData::from([1.0f32.exp()]), Default::default());
+        });
+    }
+
+    #[test]
+    fn should_block_until_the_stream_is_drained_by_another_thread() {
+        let (client, tensor) = queue_limited(QueueLimitPolicy::Block(Duration::from_secs(60)));
+        let stream = tensor.stream;
+
+        with_lazy_streams(|| {
+            let tensor = exp(exp(tensor));
+            let start = Instant::now();
+            let drain = std::thread::spawn({
+                let client = client.clone();
+                move || {
+                    std::thread::sleep(Duration::from_millis(20));
+                    client.drain_stream(stream);
+                }
+            });
+
+            let tensor = exp(tensor);
+            drain.join().unwrap();
+
+            assert!(start.elapsed() < Duration::from_secs(30));
+            assert_eq!(num_queued(&client), 0);
+            float_data(tensor).assert_approx_eq::<f32>(
+                &TensorData::from([1.0f32.exp().exp()]),
+                Default::default(),
+            );
+        });
+    }
+
+    #[test]
+    fn should_drain_streams_still_exceeding_the_queue_limits_after_the_timeout() {
+        let (client, tensor) = queue_limited(QueueLimitPolicy::Block(Duration::from_millis(10)));
+
+        with_lazy_streams(|| {
+            let tensor = exp(exp(exp(tensor)));
+
+            assert_eq!(num_queued(&client), 0);
+            float_data(tensor).assert_approx_eq::<f32>(
+                &TensorData::from([1.0f32.exp().exp()]),
+                Default::default(),
+            );
+        });
+    }
+
+    #[test]
+    fn should_drain_idle_streams_in_the_background() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        client.set_config(FusionConfig {
+            idle_timeout: Some(Duration::from_millis(1)),
+            ..Default::default()
+        });
+        let tensor = float_tensor(&client, TensorData::from([0.0f32]));
+
+        with_lazy_streams(|| {
+            let tensor = exp(tensor);
+            let deadline = Instant::now() + Duration::from_secs(30);
+
+            while num_queued(&client) > 0 {
+                assert!(
+                    Instant::now() < deadline,
+                    "The idle stream was never drained"
+                );
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            float_data(tensor).assert_eq(&TensorData::from([1.0f32]), true);
+        });
+    }
+
+    #[test]
+    fn should_send_summaries_periodically() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let tensor = float_tensor(&client, TensorData::from([0.0f32]));
+        let summaries = client.subscribe_summaries(Duration::from_millis(1));
+
+        with_lazy_streams(|| {
+            let tensor = exp(tensor);
+            let timeout = Duration::from_secs(30);
+            let summary = loop {
+                let summary = summaries.recv_timeout(timeout).unwrap();
+
+                if summary.num_queued_operations > 0 {
+                    break summary;
+                }
+            };
+
+            assert_eq!(summary.num_queued_operations, 1);
+            assert_eq!(summary.num_streams, 1);
+
+            // Summaries collected before the drain might still be in the channel.
+            float_data(tensor).assert_eq(&TensorData::from([1.0f32]), true);
+            while summaries
+                .recv_timeout(timeout)
+                .unwrap()
+                .num_queued_operations
+                > 0
+            {}
+        });
+    }
+
+    #[test]
+    fn should_reject_operations_reading_unknown_tensors() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let operation = test_utils::exp(
+            test_utils::tensor(100, TensorStatus::ReadOnly),
+            test_utils::tensor(101, TensorStatus::NotInit),
+        );
+
+        let result = client.try_register(OperationStreams::default(), operation, NoOp);
+
+        assert_eq!(
+            result,
+            Err(FusionError::TensorNotFound {
+                tensor: TensorId::new(100)
+            })
+        );
+        assert_eq!(num_queued(&client), 0);
+    }
+
+    #[test]
+    fn should_try_read_tensors_once_their_stream_is_drained() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let tensor = float_tensor(&client, TensorData::from([0.0f32, 1.0]));
+        let stream = tensor.stream;
+
+        with_lazy_streams(|| {
+            let output = exp(tensor);
+            let unknown = test_utils::tensor(100, TensorStatus::ReadOnly);
+
+            let read = client.clone().try_read_tensor::<TestBackend>(
+                output.into_ir(),
+                TensorKind::Float,
+                stream,
+            );
+            burn_common::future::block_on(read)
+                .unwrap()
+                .assert_approx_eq::<f32>(
+                    &TensorData::from([1.0f32, 1.0f32.exp()]),
+                    Default::default(),
+                );
+
+            let read =
+                client
+                    .clone()
+                    .try_read_tensor::<TestBackend>(unknown, TensorKind::Float, stream);
+            assert_eq!(
+                burn_common::future::block_on(read),
+                Err(FusionError::TensorNotFound {
+                    tensor: TensorId::new(100)
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn should_keep_pinned_buffers_in_place_until_unpinned() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let tensor = float_tensor(&client, TensorData::from([0.0f32]));
+        let other = float_tensor(&client, TensorData::from([2.0f32]));
+
+        with_lazy_streams(|| {
+            let pinned = exp(tensor);
+            assert_eq!(num_queued(&client), 1);
+
+            // The lazy tensor gets its buffer before being pinned.
+            assert!(pinned.pin());
+            assert_eq!(num_queued(&client), 0);
+            assert_eq!(client.pinned_tensors(), vec![pinned.id]);
+            let tracked = client
+                .tracked_tensors()
+                .into_iter()
+                .map(|tensor| (tensor.id, tensor.pinned))
+                .collect::<Vec<_>>();
+            assert_eq!(tracked, vec![(other.id, false), (pinned.id, true)]);
+
+            // Only the other tensor is moved into a fresh allocation.
+            assert_eq!(client.defragment().num_moved, 1);
+
+            let id = pinned.id;
+            float_data(pinned).assert_eq(&TensorData::from([1.0f32]), true);
+            assert_eq!(client.pinned_tensors(), vec![id]);
+            assert!(client.unpin_tensor(id));
+            assert!(!client.unpin_tensor(id));
+            assert!(client.pinned_tensors().is_empty());
+            float_data(other).assert_eq(&TensorData::from([2.0f32]), true);
+        });
+    }
+
+    #[test]
+    fn should_plan_operations_ahead_of_time_without_executing_them() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let operations = [
+            test_utils::exp(
+                test_utils::tensor_with_shape(100, vec![1], TensorStatus::ReadWrite),
+                test_utils::tensor_with_shape(101, vec![1], TensorStatus::NotInit),
+            ),
+            test_utils::exp(
+                test_utils::tensor_with_shape(101, vec![1], TensorStatus::ReadWrite),
+                test_utils::tensor_with_shape(102, vec![1], TensorStatus::NotInit),
+            ),
+        ];
+
+        with_lazy_streams(|| {
+            let report = client.compile_ahead_of_time(&operations);
+            assert_eq!(report.num_operations, 2);
+            assert_eq!(report.num_plans, 1);
+            assert_eq!(report.num_created, 1);
+            assert!(client.tracked_tensors().is_empty());
+
+            // The plans are already in the store.
+            let report = client.compile_ahead_of_time(&operations);
+            assert_eq!(report.num_plans, 1);
+            assert_eq!(report.num_created, 0);
+
+            // The operations are executed with the plan, without being explored again.
+            let before = client.debug_summary();
+            let tensor = float_tensor(&client, TensorData::from([0.0f32]));
+            let tensor = exp(exp(tensor));
+            float_data(tensor)
+                .assert_approx_eq::<f32>(&TensorData::from([1.0f32.exp()]), Default::default());
+            let after = client.debug_summary();
+            assert_eq!(after.num_cache_misses, before.num_cache_misses);
+            assert_eq!(after.num_cache_hits, before.num_cache_hits + 1);
+            assert_eq!(after.plans.num_created, 1);
+        });
+    }
+
+    #[test]
+    fn should_import_the_plans_of_another_client() {
+        let shared = TestClient::new(NdArrayDevice::Cpu);
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let operations = [test_utils::exp(
+            test_utils::tensor_with_shape(100, vec![1], TensorStatus::ReadWrite),
+            test_utils::tensor_with_shape(101, vec![1], TensorStatus::NotInit),
+        )];
+
+        with_lazy_streams(|| {
+            assert_eq!(shared.compile_ahead_of_time(&operations).num_created, 1);
+
+            assert_eq!(client.import_plans_from(&shared), 1);
+            // The plans are already in the store.
+            assert_eq!(client.import_plans_from(&shared), 0);
+            assert_eq!(client.import_plans_from(&client), 0);
+            assert_eq!(client.compile_ahead_of_time(&operations).num_created, 0);
+        });
+    }
+
+    #[test]
+    fn should_report_live_and_queued_memory() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let tensor = float_tensor(&client, TensorData::from([0.0f32, 1.0, 2.0, 3.0]));
+        let other = float_tensor(&client, TensorData::from([0.0f32, 1.0]));
+
+        with_lazy_streams(|| {
+            let output = exp(exp(tensor));
+            let report = client.memory_report();
+
+            assert_eq!(report.num_handles, 2);
+            assert_eq!(
+                report.dtypes,
+                vec![DTypeMemory {
+                    dtype: Some(DType::F32),
+                    num_handles: 2,
+                    bytes: 24,
+                }]
+            );
+            assert_eq!(
+                report.streams,
+                vec![StreamMemory {
+                    stream: output.stream,
+                    num_queued_operations: 2,
+                    num_queued_tensors: 2,
+                    bytes_queued: 32,
+                }]
+            );
+
+            float_data(output);
+            let report = client.memory_report();
+            assert_eq!(report.num_handles, 1);
+            assert_eq!(report.bytes_live(), 8);
+            assert_eq!(report.bytes_queued(), 0);
+            float_data(other);
+        });
+    }
+
+    #[test]
+    fn should_read_batches_of_tensors_with_a_single_drain() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let tensor = float_tensor(&client, TensorData::from([0.0f32, 1.0]));
+
+        with_lazy_streams(|| {
+            let output = exp(tensor.clone());
+            let mask = Fusion::<TestBackend>::float_lower_elem(tensor, 0.5);
+            let stream = output.stream;
+
+            let values = burn_common::future::block_on(Fusion::<TestBackend>::read_batch(vec![
+                (mask, TensorKind::Bool),
+                (output, TensorKind::Float),
+            ]));
+
+            values[0].assert_eq(&TensorData::from([true, false]), true);
+            values[1].assert_approx_eq::<f32>(
+                &TensorData::from([1.0f32, 1.0f32.exp()]),
+                Default::default(),
+            );
+            let summary = client.debug_summary();
+            let stats = summary
+                .streams
+                .iter()
+                .find(|stats| stats.stream == stream)
+                .unwrap();
+            assert_eq!(stats.num_drains, 1);
+            assert_eq!(stats.num_drained_queued, 2);
+        });
+    }
+
+    #[test]
+    fn should_time_plans_on_the_configured_device() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let run = || {
+            let tensor = float_tensor(&client, TensorData::from([0.0f32]));
+            float_data(exp(exp(tensor)));
+        };
+
+        with_lazy_streams(|| {
+            run();
+            let plans = client.debug_detailed_plans();
+            assert_eq!(plans.timing, PlanTiming::Host);
+            assert_eq!(plans.plans.len(), 1);
+            assert_eq!(plans.plans[0].operations.len(), 2);
+            assert_eq!(plans.plans[0].timings.num_samples, 1);
+            assert_eq!(plans.plans[0].timings.source, Some(TimingSource::Host));
+
+            client.set_config(FusionConfig {
+                plan_timing: PlanTiming::Device,
+                ..Default::default()
+            });
+            run();
+            let plans = client.debug_detailed_plans();
+            assert_eq!(plans.timing, PlanTiming::Device);
+            assert_eq!(plans.plans.len(), 1);
+            assert_eq!(plans.plans[0].timings.num_samples, 2);
+            assert_eq!(
+                plans.plans[0].timings.source,
+                Some(TimingSource::DeviceSync)
+            );
+        });
+    }
+
+    /// Consume the tensor without dropping its handle, leaving it orphaned.
+    fn orphan(tensor: FusionTensor<TestRuntime>) -> TensorId {
+        tensor.into_ir().id
+    }
+
+    fn tracked_ids(client: &TestClient) -> Vec<TensorId> {
+        client
+            .tracked_tensors()
+            .into_iter()
+            .map(|tensor| tensor.id)
+            .collect()
+    }
+
+    #[test]
+    fn should_report_orphaned_handles_without_removing_them() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let before = orphan(float_tensor(&client, TensorData::from([1.0f32])));
+        assert!(client.track_tensor_references());
+
+        let kept = float_tensor(&client, TensorData::from([2.0f32]));
+        let orphaned = orphan(float_tensor(&client, TensorData::from([3.0f32])));
+        let report = client.orphaned_handles(false);
+
+        let ids = report
+            .tensors
+            .iter()
+            .map(|tensor| tensor.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![orphaned]);
+        assert!(!report.collected);
+        assert_eq!(tracked_ids(&client), vec![before, kept.id, orphaned]);
+    }
+
+    #[test]
+    fn should_collect_orphaned_handles_only() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        assert!(client.track_tensor_references());
+
+        let kept = float_tensor(&client, TensorData::from([2.0f32]));
+        let orphaned = orphan(float_tensor(&client, TensorData::from([3.0f32])));
+        let report = client.orphaned_handles(true);
+
+        let ids = report
+            .tensors
+            .iter()
+            .map(|tensor| tensor.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![orphaned]);
+        assert!(report.collected);
+        assert_eq!(tracked_ids(&client), vec![kept.id]);
+        assert!(client.orphaned_handles(true).tensors.is_empty());
+        float_data(kept).assert_eq(&TensorData::from([2.0f32]), true);
+    }
+
+    #[test]
+    fn should_never_collect_tensors_created_while_collecting() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        assert!(client.track_tensor_references());
+
+        let create = std::thread::spawn({
+            let client = client.clone();
+            move || {
+                for i in 0..200 {
+                    let value = i as f32;
+                    let tensor = float_tensor(&client, TensorData::from([value]));
+                    float_data(tensor).assert_eq(&TensorData::from([value]), true);
+                }
+            }
+        });
+
+        while !create.is_finished() {
+            let report = client.orphaned_handles(true);
+            assert!(report.tensors.is_empty(), "{report}");
+        }
+        create.join().unwrap();
+    }
+}
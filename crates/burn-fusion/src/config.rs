@@ -0,0 +1,387 @@
+use std::time::Duration;
+
+use burn_ir::{OperationIr, TensorId, TensorStatus};
+use hashbrown::HashSet;
+
+use crate::{
+    debug::operation_kind,
+    stream::{OperationConverter, RelativeOps},
+};
+
+/// The environment variable setting the [fusion filter](FusionFilter) of every device, e.g.
+/// `deny:Gather,Select` or `allow:Float::Exp,NumericFloat::Add`.
+pub const FUSION_FILTER_ENV: &str = "BURN_FUSION_FILTER";
+
+/// The configuration of the fusion server of a device, set with
+/// [Fusion::set_config](crate::Fusion::set_config).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FusionConfig {
+    /// The maximum number of operations queued on a stream before it's drained, unbounded when
+    /// `None`.
+    pub max_queued_ops: Option<usize>,
+    /// The maximum number of bytes written by the operations queued on a stream before it's
+    /// drained, unbounded when `None`.
+    ///
+    /// The outputs of queued operations are allocated once executed, fused intermediates
+    /// included, so it's an upper bound on the memory the queue is going to allocate.
+    pub max_queued_bytes: Option<usize>,
+    /// What happens when a stream exceeds the queue limits.
+    pub on_queue_limit: QueueLimitPolicy,
+    /// How long a stream with queued operations can go without new ones before it's drained,
+    /// never when `None`.
+    ///
+    /// Operations are otherwise only executed once enough of them are queued to be fused, or when
+    /// a tensor is read. For small interactive requests, a timeout in the order of a few hundred
+    /// microseconds starts their execution without waiting for the next read, while bursts of
+    /// operations are still fused. It's ignored on wasm, where no thread can watch the streams.
+    pub idle_timeout: Option<Duration>,
+    /// The limits of the blocks of operations fused together.
+    pub fusion: FusionSettings,
+    /// How the execution times of the plans are measured.
+    pub plan_timing: PlanTiming,
+    /// How the server relieves memory pressure.
+    pub memory_pressure: MemoryPressurePolicy,
+    /// The maximum number of bytes of tensor data kept from reads, so that reading the same
+    /// tensor again is served without draining its stream nor fetching it from the device,
+    /// disabled when `None`.
+    ///
+    /// The data of a tensor is kept until an operation consumes the tensor or the tensor is
+    /// dropped, so it's meant for workloads reading the same tensors repeatedly, e.g. logging
+    /// their values. The least recently read tensors are forgotten first.
+    pub read_cache_bytes: Option<usize>,
+}
+
+/// How the fusion server of a device relieves memory pressure, either
+/// [signaled](crate::memory::MemoryPressureSignal) by the allocator of the backend or detected
+/// from the memory reserved on the device.
+///
+/// The queued operations are always drained, so the tensors they free are released, and the
+/// output pool is emptied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryPressurePolicy {
+    /// The number of bytes reserved on the device above which the pressure is relieved once
+    /// operations are executed, never when `None`.
+    ///
+    /// Ignored by runtimes that don't [report](crate::FusionRuntime::memory_reserved) the memory
+    /// they reserve.
+    pub max_reserved_bytes: Option<u64>,
+    /// Whether the [orphaned handles](crate::Fusion::orphaned_handles) are collected, when the
+    /// tensor references are tracked.
+    ///
+    /// Like [collecting them](crate::Fusion::collect_orphaned_handles) by hand, it's only safe
+    /// when no other thread creates tensors on the device.
+    pub collect_orphans: bool,
+    /// The number of most recently used execution plans kept, evicting the others along with
+    /// their optimizations, or `None` to keep every plan.
+    ///
+    /// Pinned plans and plans about to be executed are always kept.
+    pub keep_plans: Option<usize>,
+}
+
+/// How the [execution times](crate::stream::PlanTimings) of the plans of a device are measured,
+/// as reported by [Fusion::debug_detailed_plans](crate::Fusion::debug_detailed_plans).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlanTiming {
+    /// Time the launch of each plan on the host, which is free but doesn't include the execution
+    /// of its kernels on asynchronous devices.
+    #[default]
+    Host,
+    /// Time the completion of each plan on the device, with timestamp queries where supported and
+    /// by waiting for the device otherwise.
+    ///
+    /// Plans are no longer overlapped with the following ones, so it's only meant for profiling
+    /// sessions. Runtimes that can't measure the device fall back to [host](Self::Host) timing.
+    Device,
+}
+
+/// The limits of the blocks of operations fused together, unbounded when `None`.
+///
+/// Very long fused blocks blow up compile times and register usage on some drivers. Operations
+/// past the limits are left out of the block's optimization and explored again afterward.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FusionSettings {
+    /// The maximum number of operations fused in a block.
+    pub max_ops_per_block: Option<usize>,
+    /// The maximum number of buffers bound to a fused block, counting the tensors read from
+    /// outside the block and the tensors it writes that aren't freed within it.
+    pub max_bound_buffers: Option<usize>,
+    /// The maximum number of scalars passed to a fused block.
+    pub max_scalars: Option<usize>,
+}
+
+/// What the fusion server does when an operation makes its stream exceed the queue limits of the
+/// [configuration](FusionConfig).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueLimitPolicy {
+    /// Drain the stream right away.
+    #[default]
+    Flush,
+    /// Block the registering thread until the stream is drained by another thread, e.g. one
+    /// reading its tensors or draining idle streams, for at most the given duration before
+    /// draining it.
+    ///
+    /// Producers registering operations faster than their consumers read the results are slowed
+    /// down instead of executing partial queues, which keeps the plans explored for full queues.
+    /// In [deterministic mode](crate::stream::set_deterministic), the stream is drained right
+    /// away.
+    Block(Duration),
+    /// Panic before queuing an operation that would exceed the limits, for tests and debugging
+    /// sessions making sure a workload never queues that many operations.
+    Panic,
+}
+
+/// Which kinds of operations can be fused, set per device with
+/// [Fusion::set_fusion_filter](crate::Fusion::set_fusion_filter) or for every device with the
+/// [environment](FUSION_FILTER_ENV).
+///
+/// Kinds are named as in the [operation histogram](crate::debug::OperationHistogram), e.g.
+/// `NumericFloat::Gather`, or only by the operation, e.g. `Gather`, matching every category.
+/// Operations that can't be fused are executed one by one, which is useful to bisect kernel bugs
+/// only appearing in fused execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FusionFilter {
+    /// Every kind of operation can be fused.
+    #[default]
+    All,
+    /// Every kind of operation but the listed ones can be fused.
+    Deny(Vec<String>),
+    /// Only the listed kinds of operations can be fused.
+    Allow(Vec<String>),
+}
+
+impl FusionFilter {
+    /// The filter set by the [environment](FUSION_FILTER_ENV), [all](Self::All) when unset or
+    /// invalid.
+    pub fn from_env() -> Self {
+        std::env::var(FUSION_FILTER_ENV)
+            .ok()
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    /// Parse a filter formatted as `deny:<kinds>` or `allow:<kinds>`, with comma separated
+    /// kinds.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (mode, kinds) = value.split_once(':')?;
+        let kinds = kinds
+            .split(',')
+            .map(str::trim)
+            .filter(|kind| !kind.is_empty())
+            .map(String::from)
+            .collect();
+
+        match mode.trim().to_lowercase().as_str() {
+            "deny" => Some(Self::Deny(kinds)),
+            "allow" => Some(Self::Allow(kinds)),
+            _ => None,
+        }
+    }
+
+    /// Whether the operation can be fused.
+    pub fn can_fuse(&self, operation: &OperationIr) -> bool {
+        let (kinds, listed) = match self {
+            FusionFilter::All => return true,
+            // Dropping tensors is part of every fused block.
+            _ if matches!(operation, OperationIr::Drop(_)) => return true,
+            FusionFilter::Deny(kinds) => (kinds, false),
+            FusionFilter::Allow(kinds) => (kinds, true),
+        };
+        let kind = operation_kind(operation);
+        let name = kind.rsplit("::").next().unwrap_or_default();
+
+        let is_listed = kinds.iter().any(|listed| listed == &kind || listed == name);
+        is_listed == listed
+    }
+}
+
+impl FusionConfig {
+    /// The device measuring the execution of the plans, `None` when they're timed on the host.
+    pub(crate) fn timed_device<'a, D>(&self, device: &'a D) -> Option<&'a D> {
+        match self.plan_timing {
+            PlanTiming::Host => None,
+            PlanTiming::Device => Some(device),
+        }
+    }
+
+    /// Whether the queued operations of a stream, followed by the next one if any, exceed the
+    /// limits, in which case the stream must be drained.
+    pub(crate) fn is_exceeded_by(
+        &self,
+        operations: &[OperationIr],
+        next: Option<&OperationIr>,
+    ) -> bool {
+        let next = next.map(core::slice::from_ref).unwrap_or_default();
+
+        if self
+            .max_queued_ops
+            .is_some_and(|max| operations.len() + next.len() > max)
+        {
+            return true;
+        }
+
+        match self.max_queued_bytes {
+            Some(max) => queued_bytes(operations) + queued_bytes(next) > max,
+            None => false,
+        }
+    }
+}
+
+impl FusionSettings {
+    /// Whether the settings bound the blocks in any way.
+    pub(crate) fn is_bounded(&self) -> bool {
+        self.max_ops_per_block.is_some()
+            || self.max_bound_buffers.is_some()
+            || self.max_scalars.is_some()
+    }
+
+    /// The number of operations, from the start of the block, that can be fused within the
+    /// limits.
+    pub(crate) fn num_fusable(&self, operations: &[OperationIr]) -> usize {
+        let mut produced = HashSet::<TensorId>::new();
+        let mut read = HashSet::<TensorId>::new();
+        let mut num_buffers = 0usize;
+        let mut converter = OperationConverter::default();
+
+        for (index, operation) in operations.iter().enumerate() {
+            if self.max_ops_per_block.is_some_and(|max| index >= max) {
+                return index;
+            }
+
+            for node in operation.nodes() {
+                match node.status {
+                    TensorStatus::NotInit => {
+                        if produced.insert(node.id) {
+                            num_buffers += 1;
+                        }
+                    }
+                    status => {
+                        let is_produced = produced.contains(&node.id);
+                        if !is_produced && read.insert(node.id) {
+                            num_buffers += 1;
+                        }
+                        // Intermediates freed within the block are never written.
+                        if is_produced
+                            && (status == TensorStatus::ReadWrite
+                                || matches!(operation, OperationIr::Drop(_)))
+                        {
+                            produced.remove(&node.id);
+                            num_buffers -= 1;
+                        }
+                    }
+                }
+            }
+
+            if self.max_bound_buffers.is_some_and(|max| num_buffers > max) {
+                return index;
+            }
+
+            if let Some(max) = self.max_scalars {
+                operation.to_relative(&mut converter);
+                if converter.num_scalars() > max {
+                    return index;
+                }
+            }
+        }
+
+        operations.len()
+    }
+}
+
+/// The number of bytes written by the operations.
+fn queued_bytes(operations: &[OperationIr]) -> usize {
+    operations
+        .iter()
+        .flat_map(|operation| operation.nodes())
+        .filter(|node| node.status == TensorStatus::NotInit)
+        .map(|node| node.shape.iter().product::<usize>() * node.dtype.size())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{BinaryOpIr, NumericOperationIr, ScalarOpIr};
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::test_utils::{exp, tensor, tensor_with_shape};
+
+    #[test]
+    fn should_only_be_exceeded_past_the_limits() {
+        let tensor = |id, status| tensor_with_shape(id, vec![4, 8], status);
+        let operations = (0..3)
+            .map(|id| {
+                exp(
+                    tensor(id, TensorStatus::ReadOnly),
+                    tensor(id + 1, TensorStatus::NotInit),
+                )
+            })
+            .collect::<Vec<_>>();
+        let config = |max_queued_ops, max_queued_bytes| FusionConfig {
+            max_queued_ops,
+            max_queued_bytes,
+            ..Default::default()
+        };
+
+        assert!(!config(None, None).is_exceeded_by(&operations, None));
+        assert!(!config(Some(3), None).is_exceeded_by(&operations, None));
+        assert!(config(Some(2), None).is_exceeded_by(&operations, None));
+        // Each output is 128 bytes.
+        assert!(!config(None, Some(384)).is_exceeded_by(&operations, None));
+        assert!(config(None, Some(383)).is_exceeded_by(&operations, None));
+    }
+
+    #[test]
+    fn should_only_fuse_the_operations_within_the_limits() {
+        let add = |lhs, rhs, out| {
+            OperationIr::NumericFloat(
+                DType::F32,
+                NumericOperationIr::Add(BinaryOpIr {
+                    lhs: tensor(lhs, TensorStatus::ReadOnly),
+                    rhs: tensor(rhs, TensorStatus::ReadWrite),
+                    out: tensor(out, TensorStatus::NotInit),
+                }),
+            )
+        };
+        let add_scalar = |input, out| {
+            OperationIr::NumericFloat(
+                DType::F32,
+                NumericOperationIr::AddScalar(ScalarOpIr {
+                    lhs: tensor(input, TensorStatus::ReadWrite),
+                    rhs: 1.0,
+                    out: tensor(out, TensorStatus::NotInit),
+                }),
+            )
+        };
+        // Binds `0`, `1` and `2`, then `3` while `2` is freed, then `4` and `5` while `3` is
+        // freed, so at most 4 buffers are bound.
+        let operations = [add(0, 1, 2), add(0, 2, 3), add(4, 3, 5), add_scalar(5, 6)];
+        let settings = |max_ops_per_block, max_bound_buffers, max_scalars| FusionSettings {
+            max_ops_per_block,
+            max_bound_buffers,
+            max_scalars,
+        };
+
+        assert_eq!(settings(None, None, None).num_fusable(&operations), 4);
+        assert_eq!(settings(Some(2), None, None).num_fusable(&operations), 2);
+        assert_eq!(settings(None, Some(3), None).num_fusable(&operations), 2);
+        assert_eq!(settings(None, Some(4), None).num_fusable(&operations), 4);
+        assert_eq!(settings(None, None, Some(0)).num_fusable(&operations), 3);
+    }
+
+    #[test]
+    fn should_filter_the_kinds_of_fused_operations() {
+        let exp = exp(
+            tensor(0, TensorStatus::ReadOnly),
+            tensor(1, TensorStatus::NotInit),
+        );
+        let filter = |value| FusionFilter::parse(value).unwrap();
+
+        assert_eq!(FusionFilter::parse("Exp"), None);
+        assert!(FusionFilter::All.can_fuse(&exp));
+        assert!(!filter("deny: Exp").can_fuse(&exp));
+        assert!(!filter("deny:Gather,Float::Exp").can_fuse(&exp));
+        assert!(filter("deny:Int::Exp").can_fuse(&exp));
+        assert!(filter("allow:Exp").can_fuse(&exp));
+        assert!(!filter("allow:Log").can_fuse(&exp));
+    }
+}
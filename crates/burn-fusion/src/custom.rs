@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use burn_ir::{CustomOpIr, HandleContainer, TensorIr, TensorMetadata};
+
+use crate::{FusionError, FusionRuntime, stream::Operation};
+
+/// A custom operation registered on a device with
+/// [register_custom_op](crate::Fusion::register_custom_op), so that extensions can add
+/// operations without adding variants to the [intermediate representation](burn_ir::OperationIr).
+///
+/// Custom operations are queued as [custom operations](burn_ir::OperationIr::Custom) named
+/// after the operation, so they can be matched by the [fusion filter](crate::FusionFilter) and by
+/// [rewrite rules](crate::stream::RewriteRule) as `Custom::<name>`. They are never fused.
+pub trait CustomOp<R: FusionRuntime>: Send + Sync {
+    /// The name the operation is registered and called with.
+    fn name(&self) -> &str;
+
+    /// The shape and data type of every output given the inputs, or why the inputs aren't
+    /// supported.
+    fn infer(&self, inputs: &[TensorIr]) -> Result<Vec<TensorMetadata>, String>;
+
+    /// Execute the operation on the inner backend, registering a handle for every output of the
+    /// description.
+    fn execute(&self, desc: &CustomOpIr, handles: &mut HandleContainer<R::FusionHandle>);
+}
+
+/// A registered [custom operation](CustomOp) queued with its description.
+pub(crate) struct CustomOperation<R: FusionRuntime> {
+    pub(crate) desc: CustomOpIr,
+    pub(crate) op: Arc<dyn CustomOp<R>>,
+}
+
+/// The outputs of the custom operation for the given inputs.
+pub(crate) fn infer_outputs<R: FusionRuntime>(
+    op: &dyn CustomOp<R>,
+    inputs: &[TensorIr],
+) -> Result<Vec<TensorMetadata>, FusionError> {
+    op.infer(inputs)
+        .map_err(|reason| FusionError::ShapeInference {
+            operation: format!("Custom::{}", op.name()),
+            reason,
+        })
+}
+
+impl<R: FusionRuntime> Operation<R> for CustomOperation<R> {
+    fn execute(&self, handles: &mut HandleContainer<R::FusionHandle>) {
+        self.op.execute(&self.desc, handles);
+    }
+}
+
+impl<R: FusionRuntime> core::fmt::Debug for CustomOperation<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CustomOperation")
+            .field("desc", &self.desc)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::HandleKind;
+    use burn_ndarray::NdArrayDevice;
+    use burn_tensor::{TensorData, ops::FloatTensorOps};
+
+    use super::*;
+    use crate::{
+        Fusion, FusionTensor,
+        test_utils::{TestBackend, TestRuntime, float_data},
+    };
+
+    /// Concatenate a vector with itself.
+    struct Twice;
+
+    impl CustomOp<TestRuntime> for Twice {
+        fn name(&self) -> &str {
+            "twice"
+        }
+
+        fn infer(&self, inputs: &[TensorIr]) -> Result<Vec<TensorMetadata>, String> {
+            match inputs {
+                [input] if input.shape.len() == 1 => Ok(vec![TensorMetadata {
+                    shape: vec![input.shape[0] * 2],
+                    dtype: input.dtype,
+                }]),
+                _ => Err("expected a single vector".into()),
+            }
+        }
+
+        fn execute(
+            &self,
+            desc: &CustomOpIr,
+            handles: &mut HandleContainer<HandleKind<TestBackend>>,
+        ) {
+            let input = handles.get_float_tensor::<TestBackend>(&desc.inputs[0]);
+            let output = TestBackend::float_cat(vec![input.clone(), input], 0);
+
+            handles.register_float_tensor::<TestBackend>(&desc.outputs[0].id, output);
+        }
+    }
+
+    fn tensor(data: TensorData) -> FusionTensor<TestRuntime> {
+        Fusion::<TestBackend>::float_from_data(data, &NdArrayDevice::Cpu)
+    }
+
+    #[test]
+    fn should_execute_custom_ops_with_inferred_outputs() {
+        let device = NdArrayDevice::Cpu;
+        Fusion::<TestBackend>::register_custom_op(&device, Twice);
+
+        let outputs = Fusion::<TestBackend>::custom_op(
+            &device,
+            "twice",
+            vec![tensor(TensorData::from([1.0f32, 2.0]))],
+        )
+        .unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].shape, vec![4]);
+        let output = outputs.into_iter().next().unwrap();
+        float_data(output).assert_eq(&TensorData::from([1.0f32, 2.0, 1.0, 2.0]), true);
+    }
+
+    #[test]
+    fn should_report_unsupported_custom_op_inputs() {
+        let device = NdArrayDevice::Cpu;
+        Fusion::<TestBackend>::register_custom_op(&device, Twice);
+
+        let result = Fusion::<TestBackend>::custom_op(
+            &device,
+            "twice",
+            vec![tensor(TensorData::from([[1.0f32, 2.0]]))],
+        );
+        assert_eq!(
+            result.err(),
+            Some(FusionError::ShapeInference {
+                operation: "Custom::twice".into(),
+                reason: "expected a single vector".into(),
+            })
+        );
+
+        let result = Fusion::<TestBackend>::custom_op(&device, "unknown", Vec::new());
+        assert!(matches!(
+            result,
+            Err(FusionError::UnsupportedOperation { .. })
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use burn_ir::{IR_SCHEMA_HASH, IR_SCHEMA_VERSION, IrDocument, OperationIr, TensorId, TensorStatus};
+use burn_tensor::backend::Backend;
+use hashbrown::HashMap;
+
+use super::{OperationDisplay, SnapshotOptions, Verbosity};
+use crate::{Fusion, FusionBackend, FusionError, stream::StreamId};
+
+/// Write the debug bundle of the device in the directory, created if needed, returning the paths
+/// of the written files.
+pub(crate) fn write_bundle<B: FusionBackend>(
+    device: &B::Device,
+    directory: &Path,
+) -> Result<Vec<PathBuf>, FusionError> {
+    std::fs::create_dir_all(directory).map_err(FusionError::debug_export)?;
+
+    let mut bundle = Bundle {
+        directory,
+        files: Vec::new(),
+    };
+    let snapshot = Fusion::<B>::debug_snapshot(
+        device,
+        SnapshotOptions {
+            max_queued_operations: usize::MAX,
+            verbosity: Verbosity::Full,
+        },
+    );
+
+    let streams = snapshot
+        .streams
+        .iter()
+        .map(|stream| {
+            serde_json::json!({
+                "stream": stream.stream.value,
+                "info": stream.info.as_ref().map(ToString::to_string),
+                "operations": IrDocument::new(stream.operations.clone()),
+            })
+        })
+        .collect::<Vec<_>>();
+    bundle.write("streams.json", |file| {
+        serde_json::to_writer_pretty(file, &streams).map_err(FusionError::debug_export)
+    })?;
+
+    for stream in snapshot.streams.iter() {
+        let dot = queue_to_dot(stream.stream, &stream.operations);
+        bundle.write(&format!("stream_{}.dot", stream.stream.value), |file| {
+            file.write_all(dot.as_bytes())
+                .map_err(FusionError::debug_export)
+        })?;
+    }
+
+    let plans = directory.join("plans.json");
+    Fusion::<B>::export_plans(device, &plans)?;
+    bundle.files.push(plans);
+
+    let stats = Fusion::<B>::debug_cache_stats(device);
+    bundle.write("plan_stats.csv", |file| {
+        let mut csv =
+            String::from("plan,num_operations,num_executions,total_ns,mean_ns,min_ns,max_ns\n");
+        for plan in stats.plans.iter() {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                plan.plan,
+                plan.num_operations,
+                plan.num_executions,
+                plan.timings.total.as_nanos(),
+                plan.timings.mean().as_nanos(),
+                plan.timings.min.as_nanos(),
+                plan.timings.max.as_nanos()
+            );
+        }
+        file.write_all(csv.as_bytes())
+            .map_err(FusionError::debug_export)
+    })?;
+
+    let reports = [
+        Fusion::<B>::debug_summary(device).to_string(),
+        stats.to_string(),
+        Fusion::<B>::trigger_report(device).to_string(),
+        Fusion::<B>::cross_stream_report(device).to_string(),
+        Fusion::<B>::operation_histogram(device).to_string(),
+        Fusion::<B>::memory_report(device).to_string(),
+        snapshot.to_string(),
+    ];
+    bundle.write("reports.txt", |file| {
+        file.write_all(reports.concat().as_bytes())
+            .map_err(FusionError::debug_export)
+    })?;
+
+    let manifest = serde_json::json!({
+        "backend": Fusion::<B>::name(device),
+        "device": format!("{device:?}"),
+        "burn_version": env!("CARGO_PKG_VERSION"),
+        "ir_schema": IR_SCHEMA_HASH,
+        "ir_schema_version": IR_SCHEMA_VERSION,
+        "config": format!("{:?}", Fusion::<B>::config(device)),
+        "execution_plan_stats": format!("{:?}", Fusion::<B>::execution_plan_stats(device)),
+        "created_at": SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default(),
+    });
+    bundle.write("manifest.json", |file| {
+        serde_json::to_writer_pretty(file, &manifest).map_err(FusionError::debug_export)
+    })?;
+
+    Ok(bundle.files)
+}
+
+struct Bundle<'a> {
+    directory: &'a Path,
+    files: Vec<PathBuf>,
+}
+
+impl Bundle<'_> {
+    fn write<F>(&mut self, name: &str, write: F) -> Result<(), FusionError>
+    where
+        F: FnOnce(&mut File) -> Result<(), FusionError>,
+    {
+        let path = self.directory.join(name);
+        let mut file = File::create(&path).map_err(FusionError::debug_export)?;
+
+        write(&mut file)?;
+        self.files.push(path);
+
+        Ok(())
+    }
+}
+
+/// Render the queued operations of a stream in the DOT format of Graphviz, linked by the tensors
+/// flowing between them.
+fn queue_to_dot(stream: StreamId, operations: &[OperationIr]) -> String {
+    let mut dot = format!(
+        "digraph stream_{} {{\n    node [shape=box];\n",
+        stream.value
+    );
+    let mut producers = HashMap::<TensorId, usize>::new();
+
+    for (index, operation) in operations.iter().enumerate() {
+        let label = OperationDisplay {
+            operation,
+            verbosity: Verbosity::Normal,
+        }
+        .to_string()
+        .replace('"', "\\\"");
+        let _ = writeln!(dot, "    op_{index} [label=\"{index}: {label}\"];");
+
+        for tensor in operation.nodes() {
+            match tensor.status {
+                TensorStatus::NotInit => {
+                    producers.insert(tensor.id, index);
+                }
+                _ => {
+                    if let Some(producer) = producers.get(&tensor.id) {
+                        let _ = writeln!(
+                            dot,
+                            "    op_{producer} -> op_{index} [label=\"t{}\"];",
+                            tensor.id.value()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    dot += "}\n";
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_link_queued_operations_through_their_tensors() {
+        let operations = [
+            exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(2, TensorStatus::ReadOnly),
+                tensor(3, TensorStatus::NotInit),
+            ),
+        ];
+
+        let dot = queue_to_dot(StreamId { value: 3 }, &operations);
+
+        assert!(dot.starts_with("digraph stream_3 {"));
+        assert!(dot.contains("op_0 [label=\"0: Float::Exp(t1 F32[8]) -> (t2 F32[8])\"];"));
+        assert!(dot.contains("op_0 -> op_1 [label=\"t2\"];"));
+    }
+}
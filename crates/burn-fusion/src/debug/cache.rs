@@ -0,0 +1,98 @@
+use core::fmt::Display;
+
+use crate::stream::{ExecutionPlanId, PlanTimings, store::ExecutionPlanStore};
+
+/// How well the [execution plans](ExecutionPlanId) of a device are reused.
+///
+/// A low hit rate means most operations are explored instead of being executed with an existing
+/// plan, which typically happens when shapes keep changing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanCacheStats {
+    /// The number of searches for plans starting with a given operation.
+    pub num_queries: u64,
+    /// The number of searches returning at least one plan.
+    pub num_query_matches: u64,
+    /// The number of executions of a plan found in the store without exploration.
+    pub num_hits: u64,
+    /// The number of explorations, whether or not they created a new plan.
+    pub num_misses: u64,
+    /// The plans currently stored, ordered by id.
+    pub plans: Vec<PlanExecutions>,
+}
+
+/// The number of executions of a plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlanExecutions {
+    /// The id of the plan.
+    pub plan: ExecutionPlanId,
+    /// The number of operations executed by the plan.
+    pub num_operations: usize,
+    /// The number of times the plan was executed.
+    pub num_executions: u64,
+    /// The measured execution times of the plan.
+    pub timings: PlanTimings,
+}
+
+impl PlanCacheStats {
+    pub(crate) fn new<O>(store: &ExecutionPlanStore<O>) -> Self {
+        let cache = store.cache();
+        let mut plans = store
+            .iter()
+            .map(|(id, plan)| PlanExecutions {
+                plan: id,
+                num_operations: plan.operations.len(),
+                num_executions: store.fired(id).iter().sum(),
+                timings: store.timings(id),
+            })
+            .collect::<Vec<_>>();
+        plans.sort_by_key(|plan| plan.plan);
+
+        Self {
+            num_queries: cache.num_queries.get(),
+            num_query_matches: cache.num_query_matches.get(),
+            num_hits: cache.num_hits,
+            num_misses: cache.num_misses,
+            plans,
+        }
+    }
+
+    /// The ratio of executions done without exploration, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.num_hits + self.num_misses;
+
+        match total {
+            0 => 0.0,
+            _ => self.num_hits as f64 / total as f64,
+        }
+    }
+}
+
+impl Display for PlanCacheStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Plan Cache ====\n")?;
+        f.write_fmt(format_args!(
+            " - Queries: {} ({} with matches)\n",
+            self.num_queries, self.num_query_matches
+        ))?;
+        f.write_fmt(format_args!(
+            " - Hits: {} Misses: {} (hit rate: {:.2}%)\n",
+            self.num_hits,
+            self.num_misses,
+            self.hit_rate() * 100.0
+        ))?;
+
+        for plan in self.plans.iter() {
+            f.write_fmt(format_args!(
+                "  - Plan {} ({} operations) => executions: {} mean: {:?} min: {:?} max: {:?}\n",
+                plan.plan,
+                plan.num_operations,
+                plan.num_executions,
+                plan.timings.mean(),
+                plan.timings.min,
+                plan.timings.max
+            ))?;
+        }
+
+        f.write_str("===========================\n")
+    }
+}
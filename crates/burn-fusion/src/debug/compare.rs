@@ -0,0 +1,370 @@
+use core::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+use std::{sync::Arc, time::Duration};
+
+use burn_ir::OperationIr;
+use burn_tensor::{TensorData, backend::Backend};
+use hashbrown::HashMap;
+
+use super::{FusionHook, SyntheticModel};
+use crate::{
+    Fusion, FusionBackend,
+    stream::{ExecutionPlanId, OperationConverter, PlanTimings, RelativeOps, TimingSource},
+};
+
+/// A program that can be executed on any backend, used to [compare backends](compare_backends).
+pub trait BackendProgram {
+    /// Execute the program on the given device, returning its outputs.
+    fn run<B: Backend>(&self, device: &B::Device) -> Vec<TensorData>;
+}
+
+impl BackendProgram for SyntheticModel {
+    fn run<B: Backend>(&self, device: &B::Device) -> Vec<TensorData> {
+        vec![self.forward::<B>(device).into_data()]
+    }
+}
+
+/// The executions of the same program on two backends, side by side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendComparison {
+    /// The names of the compared backends.
+    pub backends: [String; 2],
+    /// The plans executed by either backend, aligned by fingerprint and sorted by decreasing
+    /// total execution time.
+    pub plans: Vec<PlanComparison>,
+    /// The outputs of the program, in the order they were returned.
+    pub outputs: Vec<OutputComparison>,
+}
+
+/// An execution plan found on one or both backends of a [comparison](BackendComparison).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlanComparison {
+    /// The hash of the relative operations of the plan, equal on both backends when the plans
+    /// execute the same operations.
+    pub fingerprint: u64,
+    /// The number of operations executed by the plan.
+    pub num_operations: usize,
+    /// The execution times of the plan on each backend, `None` when the backend didn't execute
+    /// the same operations as a single plan.
+    pub timings: [Option<PlanTimings>; 2],
+}
+
+/// An output of the program on both backends of a [comparison](BackendComparison).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputComparison {
+    /// The sum of the values of the output on each backend.
+    pub checksums: [f64; 2],
+    /// The maximum absolute difference between the values of both backends, `None` when their
+    /// shapes differ.
+    pub max_abs_diff: Option<f64>,
+}
+
+/// Execute the program on the fusion backends of both devices, and compare their execution plans
+/// and outputs.
+///
+/// Both backends fuse the program on their own, so plans are aligned by the
+/// [fingerprint](PlanComparison::fingerprint) of their operations rather than by id. Plans fused
+/// differently only show up on one side.
+///
+/// Both devices are synced before the program is executed, and after it's executed.
+pub fn compare_backends<B1, B2, P>(
+    program: &P,
+    device1: &B1::Device,
+    device2: &B2::Device,
+) -> BackendComparison
+where
+    B1: FusionBackend,
+    B2: FusionBackend,
+    P: BackendProgram,
+{
+    let (profile1, outputs1) = profile::<B1, P>(program, device1);
+    let (profile2, outputs2) = profile::<B2, P>(program, device2);
+
+    BackendComparison::new(
+        [Fusion::<B1>::name(device1), Fusion::<B2>::name(device2)],
+        [profile1, profile2],
+        &outputs1,
+        &outputs2,
+    )
+}
+
+fn profile<B: FusionBackend, P: BackendProgram>(
+    program: &P,
+    device: &B::Device,
+) -> (PlanProfile, Vec<TensorData>) {
+    Fusion::<B>::sync(device);
+
+    let profiler = PlanProfiler::default();
+    Fusion::<B>::register_debug_hook(device, Box::new(profiler.clone()));
+
+    let outputs = program.run::<Fusion<B>>(device);
+    Fusion::<B>::sync(device);
+
+    (profiler.finish(), outputs)
+}
+
+/// The execution times of the plans of a device, by fingerprint.
+#[derive(Default)]
+struct PlanProfile {
+    plans: HashMap<u64, (usize, PlanTimings)>,
+}
+
+/// Collects a [plan profile](PlanProfile) until finished, the hook then staying registered
+/// without recording anything.
+#[derive(Clone)]
+struct PlanProfiler {
+    state: Arc<spin::Mutex<Option<PlanProfilerState>>>,
+}
+
+#[derive(Default)]
+struct PlanProfilerState {
+    fingerprints: HashMap<ExecutionPlanId, (u64, usize)>,
+    profile: PlanProfile,
+}
+
+impl PlanProfiler {
+    fn finish(&self) -> PlanProfile {
+        self.state
+            .lock()
+            .take()
+            .map(|state| state.profile)
+            .unwrap_or_default()
+    }
+}
+
+impl Default for PlanProfiler {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(spin::Mutex::new(Some(PlanProfilerState::default()))),
+        }
+    }
+}
+
+impl FusionHook for PlanProfiler {
+    fn on_plan_execution(&mut self, plan: ExecutionPlanId, operations: &[OperationIr]) {
+        let mut state = self.state.lock();
+        let state = match state.as_mut() {
+            Some(state) => state,
+            None => return,
+        };
+
+        state
+            .fingerprints
+            .entry(plan)
+            .or_insert_with(|| (fingerprint(operations), operations.len()));
+    }
+
+    fn on_plan_executed(&mut self, plan: ExecutionPlanId, duration: Duration) {
+        let mut state = self.state.lock();
+        let state = match state.as_mut() {
+            Some(state) => state,
+            None => return,
+        };
+
+        if let Some(&(fingerprint, num_operations)) = state.fingerprints.get(&plan) {
+            let (_, timings) = state
+                .profile
+                .plans
+                .entry(fingerprint)
+                .or_insert((num_operations, PlanTimings::default()));
+            timings.record(duration, TimingSource::Host);
+        }
+    }
+}
+
+/// Hash the operations in their relative form, so that the same operations registered on
+/// different devices have the same fingerprint.
+fn fingerprint(operations: &[OperationIr]) -> u64 {
+    let mut converter = OperationConverter::default();
+    let mut hasher = std::hash::DefaultHasher::new();
+
+    for operation in operations {
+        operation.to_relative(&mut converter).hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+impl BackendComparison {
+    fn new(
+        backends: [String; 2],
+        profiles: [PlanProfile; 2],
+        outputs1: &[TensorData],
+        outputs2: &[TensorData],
+    ) -> Self {
+        let mut plans = HashMap::<u64, PlanComparison>::new();
+
+        for (index, profile) in profiles.into_iter().enumerate() {
+            for (fingerprint, (num_operations, timings)) in profile.plans {
+                plans
+                    .entry(fingerprint)
+                    .or_insert(PlanComparison {
+                        fingerprint,
+                        num_operations,
+                        timings: [None, None],
+                    })
+                    .timings[index] = Some(timings);
+            }
+        }
+
+        let mut plans = plans.into_values().collect::<Vec<_>>();
+        plans.sort_by_key(|plan| {
+            let total = plan
+                .timings
+                .iter()
+                .flatten()
+                .map(|timings| timings.total)
+                .sum::<Duration>();
+            (core::cmp::Reverse(total), plan.fingerprint)
+        });
+
+        let outputs = outputs1
+            .iter()
+            .zip(outputs2)
+            .map(|(output1, output2)| OutputComparison::new(output1, output2))
+            .collect();
+
+        Self {
+            backends,
+            plans,
+            outputs,
+        }
+    }
+}
+
+impl OutputComparison {
+    fn new(output1: &TensorData, output2: &TensorData) -> Self {
+        let checksum = |output: &TensorData| output.iter::<f64>().sum();
+        let max_abs_diff = (output1.shape == output2.shape).then(|| {
+            output1
+                .iter::<f64>()
+                .zip(output2.iter::<f64>())
+                .map(|(value1, value2)| (value1 - value2).abs())
+                .fold(0.0, f64::max)
+        });
+
+        Self {
+            checksums: [checksum(output1), checksum(output2)],
+            max_abs_diff,
+        }
+    }
+}
+
+impl Display for BackendComparison {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Backend Comparison ====\n")?;
+        f.write_fmt(format_args!(
+            " - Backends: [1] {} [2] {}\n",
+            self.backends[0], self.backends[1]
+        ))?;
+
+        for plan in self.plans.iter() {
+            f.write_fmt(format_args!(
+                " - Plan {:016x} ({} operations)",
+                plan.fingerprint, plan.num_operations
+            ))?;
+
+            for (index, timings) in plan.timings.iter().enumerate() {
+                match timings {
+                    Some(timings) => f.write_fmt(format_args!(
+                        " => [{}] executions: {} mean: {:?}",
+                        index + 1,
+                        timings.num_samples,
+                        timings.mean()
+                    ))?,
+                    None => f.write_fmt(format_args!(" => [{}] not fused", index + 1))?,
+                }
+            }
+            f.write_str("\n")?;
+        }
+
+        for (index, output) in self.outputs.iter().enumerate() {
+            f.write_fmt(format_args!(
+                " - Output {index} => checksums: [1] {} [2] {}",
+                output.checksums[0], output.checksums[1]
+            ))?;
+            match output.max_abs_diff {
+                Some(diff) => f.write_fmt(format_args!(" max abs diff: {diff}\n"))?,
+                None => f.write_str(" shapes differ\n")?,
+            }
+        }
+
+        f.write_str("===================================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::TensorStatus;
+
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_align_plans_by_fingerprint() {
+        let mut profiler1 = PlanProfiler::default();
+        let mut profiler2 = PlanProfiler::default();
+
+        // The same operations on different tensors, with different plan ids.
+        profiler1.on_plan_execution(
+            0,
+            &[exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            )],
+        );
+        profiler1.on_plan_executed(0, Duration::from_millis(2));
+        profiler1.on_plan_execution(
+            1,
+            &[
+                exp(
+                    tensor(2, TensorStatus::ReadOnly),
+                    tensor(3, TensorStatus::NotInit),
+                ),
+                exp(
+                    tensor(3, TensorStatus::ReadOnly),
+                    tensor(4, TensorStatus::NotInit),
+                ),
+            ],
+        );
+        profiler1.on_plan_executed(1, Duration::from_millis(1));
+        profiler2.on_plan_execution(
+            4,
+            &[exp(
+                tensor(10, TensorStatus::ReadOnly),
+                tensor(11, TensorStatus::NotInit),
+            )],
+        );
+        profiler2.on_plan_executed(4, Duration::from_millis(3));
+
+        let comparison = BackendComparison::new(
+            ["a".into(), "b".into()],
+            [profiler1.finish(), profiler2.finish()],
+            &[TensorData::from([1.0f32, 2.0])],
+            &[TensorData::from([1.0f32, 2.5])],
+        );
+
+        assert_eq!(comparison.plans.len(), 2);
+        assert_eq!(comparison.plans[0].num_operations, 1);
+        assert_eq!(
+            comparison.plans[0].timings.map(|t| t.map(|t| t.total)),
+            [
+                Some(Duration::from_millis(2)),
+                Some(Duration::from_millis(3))
+            ]
+        );
+        assert_eq!(
+            comparison.plans[1].timings.map(|t| t.is_some()),
+            [true, false]
+        );
+        assert_eq!(
+            comparison.outputs,
+            [OutputComparison {
+                checksums: [3.0, 3.5],
+                max_abs_diff: Some(0.5),
+            }]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use core::fmt::Display;
+
+use crate::stream::StreamId;
+
+/// The synchronizations between the streams of a device, caused by tensors produced on a stream
+/// and used on another.
+///
+/// Only the operations of the producer up to the last one using the shared tensors are executed,
+/// the others staying queued to be fused with the following operations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrossStreamReport {
+    /// The links between streams, sorted by producer then consumer.
+    pub edges: Vec<CrossStreamEdge>,
+}
+
+/// Tensors produced by a stream and used by another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossStreamEdge {
+    /// The stream producing the tensors.
+    pub producer: StreamId,
+    /// The stream using the tensors.
+    pub consumer: StreamId,
+    /// The number of times the producer was synced for the consumer.
+    pub num_syncs: u64,
+    /// The number of operations of the producer executed by the syncs.
+    pub num_executed: u64,
+    /// The number of operations of the producer left queued by the syncs, which would have been
+    /// executed by draining the whole producer.
+    pub num_deferred: u64,
+}
+
+impl CrossStreamEdge {
+    pub(crate) fn new(producer: StreamId, consumer: StreamId) -> Self {
+        Self {
+            producer,
+            consumer,
+            num_syncs: 0,
+            num_executed: 0,
+            num_deferred: 0,
+        }
+    }
+}
+
+impl Display for CrossStreamReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Cross-Stream Report ====\n")?;
+
+        for edge in self.edges.iter() {
+            f.write_fmt(format_args!(
+                " - {} -> {} => syncs: {} executed: {} deferred: {}\n",
+                edge.producer, edge.consumer, edge.num_syncs, edge.num_executed, edge.num_deferred
+            ))?;
+        }
+
+        f.write_str("====================================\n")
+    }
+}
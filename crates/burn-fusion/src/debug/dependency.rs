@@ -0,0 +1,263 @@
+use core::fmt::Display;
+use std::sync::Arc;
+
+use burn_ir::{OperationIr, OperationMetadata, TensorId, TensorStatus};
+use hashbrown::{HashMap, HashSet};
+
+use super::{FusionHook, metadata_names};
+use crate::stream::ExecutionPlanId;
+
+/// A [hook](FusionHook) linking the execution plans of a device through the tensors flowing
+/// between them, i.e. which plan produced the inputs of which other plan.
+///
+/// The recorder can be cloned before being registered, every clone sharing the same
+/// [graph](PlanGraph).
+#[derive(Clone, Default)]
+pub struct PlanGraphRecorder {
+    state: Arc<spin::Mutex<PlanGraphState>>,
+}
+
+#[derive(Default)]
+struct PlanGraphState {
+    /// The plan that wrote each tensor still alive.
+    producers: HashMap<TensorId, ExecutionPlanId>,
+    plans: HashMap<ExecutionPlanId, PlanNode>,
+    edges: HashMap<(ExecutionPlanId, ExecutionPlanId), u64>,
+}
+
+/// The execution plans of a run, linked by the tensors flowing between them.
+///
+/// It's a coarse view of the whole model as a pipeline of fused kernels.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanGraph {
+    /// The executed plans, sorted by id.
+    pub plans: Vec<PlanNode>,
+    /// The links between plans, sorted by producer then consumer.
+    pub edges: Vec<PlanEdge>,
+}
+
+/// An executed plan in a [plan graph](PlanGraph).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanNode {
+    /// The id of the plan.
+    pub plan: ExecutionPlanId,
+    /// The number of operations executed by the plan.
+    pub num_operations: usize,
+    /// The number of times the plan was executed while recording.
+    pub num_executions: u64,
+    /// The names of the [annotations](crate::debug::FusionAnnotation) or
+    /// [scopes](crate::debug::FusionScope) of the executed operations, over all executions.
+    pub names: Vec<String>,
+}
+
+/// Tensors written by a plan and read by another in a [plan graph](PlanGraph).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlanEdge {
+    /// The plan writing the tensors.
+    pub producer: ExecutionPlanId,
+    /// The plan reading the tensors.
+    pub consumer: ExecutionPlanId,
+    /// The number of tensors read, summed over all executions.
+    pub num_tensors: u64,
+}
+
+impl PlanGraphRecorder {
+    /// The graph of the plans executed since the recorder was registered.
+    pub fn graph(&self) -> PlanGraph {
+        let state = self.state.lock();
+
+        let mut plans = state.plans.values().cloned().collect::<Vec<_>>();
+        plans.sort_by_key(|node| node.plan);
+
+        let mut edges = state
+            .edges
+            .iter()
+            .map(|(&(producer, consumer), &num_tensors)| PlanEdge {
+                producer,
+                consumer,
+                num_tensors,
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|edge| (edge.producer, edge.consumer));
+
+        PlanGraph { plans, edges }
+    }
+}
+
+impl FusionHook for PlanGraphRecorder {
+    fn on_plan_execution(&mut self, plan: ExecutionPlanId, operations: &[OperationIr]) {
+        let mut state = self.state.lock();
+        let node = state.plans.entry(plan).or_insert(PlanNode {
+            plan,
+            num_operations: operations.len(),
+            num_executions: 0,
+            names: Vec::new(),
+        });
+        node.num_executions += 1;
+
+        // Tensors written by the current execution don't link the plan to itself.
+        let mut produced = HashSet::new();
+
+        for operation in operations {
+            let nodes = operation.nodes();
+
+            for tensor in nodes.iter() {
+                if tensor.status == TensorStatus::NotInit || produced.contains(&tensor.id) {
+                    continue;
+                }
+
+                if let Some(producer) = state.producers.get(&tensor.id).copied() {
+                    *state.edges.entry((producer, plan)).or_default() += 1;
+                }
+                if tensor.status == TensorStatus::ReadWrite {
+                    // Last use of the tensor.
+                    state.producers.remove(&tensor.id);
+                }
+            }
+
+            for tensor in nodes.iter() {
+                if tensor.status == TensorStatus::NotInit {
+                    state.producers.insert(tensor.id, plan);
+                    produced.insert(tensor.id);
+                }
+            }
+        }
+    }
+
+    fn on_plan_metadata(
+        &mut self,
+        plan: ExecutionPlanId,
+        metadata: &[Option<Arc<OperationMetadata>>],
+    ) {
+        let mut state = self.state.lock();
+
+        if let Some(node) = state.plans.get_mut(&plan) {
+            for name in metadata_names(metadata) {
+                if !node.names.contains(&name) {
+                    node.names.push(name);
+                }
+            }
+        }
+    }
+}
+
+impl PlanGraph {
+    /// Render the graph in the DOT format of Graphviz.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph fusion_plans {\n    node [shape=box];\n");
+
+        for node in self.plans.iter() {
+            let names = match node.names.is_empty() {
+                true => String::new(),
+                false => format!("\\n{}", node.names.join("\\n").replace('"', "\\\"")),
+            };
+            dot += &format!(
+                "    plan_{} [label=\"Plan {}\\n{} operations\\n{} executions{names}\"];\n",
+                node.plan, node.plan, node.num_operations, node.num_executions
+            );
+        }
+        for edge in self.edges.iter() {
+            dot += &format!(
+                "    plan_{} -> plan_{} [label=\"{}\"];\n",
+                edge.producer, edge.consumer, edge.num_tensors
+            );
+        }
+
+        dot += "}\n";
+        dot
+    }
+}
+
+impl Display for PlanGraph {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Plan Graph ====\n")?;
+
+        for node in self.plans.iter() {
+            f.write_fmt(format_args!(
+                " - Plan {} ({} operations) => executions: {}\n",
+                node.plan, node.num_operations, node.num_executions
+            ))?;
+            if !node.names.is_empty() {
+                f.write_fmt(format_args!("  - from {}\n", node.names.join(", ")))?;
+            }
+
+            for edge in self.edges.iter().filter(|edge| edge.producer == node.plan) {
+                f.write_fmt(format_args!(
+                    "  - feeds plan {} ({} tensors)\n",
+                    edge.consumer, edge.num_tensors
+                ))?;
+            }
+        }
+
+        f.write_str("===========================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_link_plans_through_their_tensors() {
+        let mut recorder = PlanGraphRecorder::default();
+
+        // Plan 0 writes tensors 1 and 2, plan 1 reads both.
+        recorder.on_plan_execution(
+            0,
+            &[
+                exp(
+                    tensor(0, TensorStatus::ReadOnly),
+                    tensor(1, TensorStatus::NotInit),
+                ),
+                exp(
+                    tensor(1, TensorStatus::ReadOnly),
+                    tensor(2, TensorStatus::NotInit),
+                ),
+            ],
+        );
+        recorder.on_plan_execution(
+            1,
+            &[
+                exp(
+                    tensor(1, TensorStatus::ReadWrite),
+                    tensor(3, TensorStatus::NotInit),
+                ),
+                exp(
+                    tensor(2, TensorStatus::ReadWrite),
+                    tensor(4, TensorStatus::NotInit),
+                ),
+            ],
+        );
+        // Tensor 1 isn't alive anymore, so plan 2 only depends on plan 1.
+        recorder.on_plan_execution(
+            2,
+            &[exp(
+                tensor(3, TensorStatus::ReadOnly),
+                tensor(5, TensorStatus::NotInit),
+            )],
+        );
+        recorder.on_plan_metadata(2, &[Some(Arc::new(OperationMetadata::named("head")))]);
+
+        let graph = recorder.graph();
+        assert_eq!(graph.plans.len(), 3);
+        assert_eq!(
+            graph.edges,
+            [
+                PlanEdge {
+                    producer: 0,
+                    consumer: 1,
+                    num_tensors: 2,
+                },
+                PlanEdge {
+                    producer: 1,
+                    consumer: 2,
+                    num_tensors: 1,
+                },
+            ]
+        );
+        assert_eq!(graph.plans[2].names, ["head"]);
+        assert!(graph.to_dot().contains("plan_0 -> plan_1 [label=\"2\"];"));
+        assert!(graph.to_dot().contains("1 executions\\nhead\"];"));
+    }
+}
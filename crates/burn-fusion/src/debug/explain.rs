@@ -0,0 +1,330 @@
+use core::fmt::Display;
+
+use burn_ir::{OperationIr, TensorStatus};
+use burn_tensor::DType;
+
+use super::operation_kind;
+use crate::{
+    NumOperations, OptimizationBuilder, OptimizationStatus,
+    search::ExplorationSettings,
+    stream::{
+        ExecutionMode, ExplorationAction, Explorer, OperationConverter, RelativeOps,
+        ScalarParameterization, store::ExecutionStrategy,
+    },
+};
+
+/// Explains how a sequence of operations is partitioned by the explorer of a device, with the
+/// reason each pair of consecutive operations isn't fused.
+///
+/// The operations are explored the way a synced stream explores them, with the settings of the
+/// device, so the partitioning matches the plans the device would create for them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusionExplanation {
+    /// The kind of each operation, in the order they were given.
+    pub operations: Vec<String>,
+    /// The index of the execution plan of each operation.
+    pub plans: Vec<usize>,
+    /// The decision of the explorer at the boundary between each operation and the next one.
+    pub boundaries: Vec<FusionDecision>,
+}
+
+/// Whether two consecutive operations are executed by the same optimization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FusionDecision {
+    /// Both operations are executed by the same optimization.
+    Fused,
+    /// The operations are executed separately, for the given reason.
+    NotFused(NotFusedReason),
+}
+
+/// Why two consecutive operations aren't executed by the same optimization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotFusedReason {
+    /// The operation of the given kind is excluded by the [fusion filter](crate::FusionFilter)
+    /// of the device.
+    Filtered {
+        /// The kind of the excluded operation.
+        operation: String,
+    },
+    /// Fusing both operations would exceed the [limits](crate::FusionSettings) of the device.
+    LimitReached,
+    /// No optimization builder of the device supports the operation of the given kind.
+    Unsupported {
+        /// The kind of the unsupported operation.
+        operation: String,
+    },
+    /// The operations write tensors of different data types.
+    DTypeMismatch {
+        /// The data type written by the first operation.
+        before: DType,
+        /// The data type written by the second operation.
+        after: DType,
+    },
+    /// Both operations are supported, but no optimization builder fuses them together, e.g.
+    /// because of their shapes or because the first one ends every optimization.
+    Incompatible,
+}
+
+/// The position of an operation in the strategy of its plan.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Placement {
+    plan: usize,
+    /// The leaf of the strategy executing the operation.
+    strategy: usize,
+    /// Whether the leaf is an optimization rather than operations executed one by one.
+    fused: bool,
+}
+
+impl FusionExplanation {
+    pub(crate) fn new<O: NumOperations>(
+        operations: &[OperationIr],
+        builders: Vec<Box<dyn OptimizationBuilder<O>>>,
+        exploration: ExplorationSettings,
+        scalar_parameterization: ScalarParameterization,
+    ) -> Self {
+        let mut probes = builders.iter().map(|b| b.clone_dyn()).collect::<Vec<_>>();
+        let mut explorer = Explorer::new(builders, exploration.clone());
+        let mut converter = OperationConverter::default();
+        converter.scalar_parameterization = scalar_parameterization;
+        let mut placements = Vec::with_capacity(operations.len());
+        let mut num_strategies = 0;
+
+        while placements.len() < operations.len() {
+            let start = placements.len();
+            converter.clear();
+            let relative = operations[start..]
+                .iter()
+                .map(|operation| operation.to_relative(&mut converter))
+                .collect::<Vec<_>>();
+
+            explorer.reset(&relative);
+            let optimization = match explorer.explore(&relative, ExecutionMode::Sync) {
+                ExplorationAction::Completed(optimization) => optimization,
+                ExplorationAction::Continue => unreachable!("Synced explorations always complete"),
+            };
+
+            let plan = placements.last().map_or(0, |p: &Placement| p.plan + 1);
+            let mut placed = vec![None; optimization.ordering.len()];
+            place(
+                &optimization.strategy,
+                plan,
+                &mut num_strategies,
+                &mut placed,
+            );
+            placements.extend(placed.into_iter().map(|p| p.expect("Ordered operation")));
+        }
+
+        let boundaries = placements
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| match pair[0] == pair[1] && pair[0].fused {
+                true => FusionDecision::Fused,
+                false => FusionDecision::NotFused(reason(
+                    &operations[..i + 2],
+                    &placements,
+                    &mut probes,
+                    &exploration,
+                    scalar_parameterization,
+                )),
+            })
+            .collect();
+
+        Self {
+            operations: operations.iter().map(operation_kind).collect(),
+            plans: placements.iter().map(|p| p.plan).collect(),
+            boundaries,
+        }
+    }
+}
+
+/// Place the operations executed by each leaf of the strategy.
+fn place<O>(
+    strategy: &ExecutionStrategy<O>,
+    plan: usize,
+    num_strategies: &mut usize,
+    placed: &mut [Option<Placement>],
+) {
+    let (ordering, fused) = match strategy {
+        ExecutionStrategy::Optimization { ordering, .. } => (ordering, true),
+        ExecutionStrategy::Operations { ordering } => (ordering, false),
+        ExecutionStrategy::Composed(strategies) => {
+            for strategy in strategies {
+                place(strategy, plan, num_strategies, placed);
+            }
+            return;
+        }
+    };
+
+    for index in ordering.iter() {
+        placed[*index] = Some(Placement {
+            plan,
+            strategy: *num_strategies,
+            fused,
+        });
+    }
+    *num_strategies += 1;
+}
+
+/// Why the last two operations aren't fused.
+fn reason<O>(
+    operations: &[OperationIr],
+    placements: &[Placement],
+    probes: &mut [Box<dyn OptimizationBuilder<O>>],
+    exploration: &ExplorationSettings,
+    scalar_parameterization: ScalarParameterization,
+) -> NotFusedReason {
+    let [before, after] = [operations.len() - 2, operations.len() - 1];
+
+    for operation in [&operations[before], &operations[after]] {
+        if !exploration.filter.can_fuse(operation) {
+            return NotFusedReason::Filtered {
+                operation: operation_kind(operation),
+            };
+        }
+    }
+
+    // The operations fused with the first one, starting at the first operation of its plan.
+    let plan = placements[before].plan;
+    let start = placements.iter().position(|p| p.plan == plan).unwrap();
+    if exploration.limits.is_bounded()
+        && exploration.num_fusable(&operations[start..]) < operations.len() - start
+    {
+        return NotFusedReason::LimitReached;
+    }
+
+    // An operation fused with others is obviously supported.
+    let candidates = match placements[before].fused {
+        true => &operations[after..],
+        false => &operations[before..],
+    };
+    for operation in candidates {
+        if !is_supported(operation, probes, scalar_parameterization) {
+            return NotFusedReason::Unsupported {
+                operation: operation_kind(operation),
+            };
+        }
+    }
+
+    match (dtype(&operations[before]), dtype(&operations[after])) {
+        (Some(before), Some(after)) if before != after => {
+            NotFusedReason::DTypeMismatch { before, after }
+        }
+        _ => NotFusedReason::Incompatible,
+    }
+}
+
+/// Whether any builder can start an optimization with the operation.
+fn is_supported<O>(
+    operation: &OperationIr,
+    probes: &mut [Box<dyn OptimizationBuilder<O>>],
+    scalar_parameterization: ScalarParameterization,
+) -> bool {
+    let mut converter = OperationConverter::default();
+    converter.scalar_parameterization = scalar_parameterization;
+    let operation = operation.to_relative(&mut converter);
+
+    probes.iter_mut().any(|probe| {
+        probe.reset();
+        probe.register(&operation);
+        matches!(probe.status(), OptimizationStatus::Open) || probe.properties().ready
+    })
+}
+
+/// The data type of the first tensor written by the operation.
+fn dtype(operation: &OperationIr) -> Option<DType> {
+    operation
+        .nodes()
+        .into_iter()
+        .find(|node| node.status == TensorStatus::NotInit)
+        .map(|node| node.dtype)
+}
+
+impl Display for NotFusedReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Filtered { operation } => {
+                f.write_fmt(format_args!("{operation} is excluded by the fusion filter"))
+            }
+            Self::LimitReached => f.write_str("the fusion limits are reached"),
+            Self::Unsupported { operation } => {
+                f.write_fmt(format_args!("{operation} isn't supported by any builder"))
+            }
+            Self::DTypeMismatch { before, after } => {
+                f.write_fmt(format_args!("data types differ ({before:?} != {after:?})"))
+            }
+            Self::Incompatible => f.write_str("no builder fuses both operations"),
+        }
+    }
+}
+
+impl Display for FusionExplanation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Explanation ====\n")?;
+
+        for (i, operation) in self.operations.iter().enumerate() {
+            f.write_fmt(format_args!(" - [plan {}] {operation}\n", self.plans[i]))?;
+
+            if let Some(FusionDecision::NotFused(reason)) = self.boundaries.get(i) {
+                f.write_fmt(format_args!("   -- not fused: {reason}\n"))?;
+            }
+        }
+
+        f.write_str("============================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        FusionFilter,
+        stream::execution::tests::{
+            TestOptimizationBuilder, operation_1, operation_2, operation_3,
+        },
+    };
+
+    #[test]
+    fn should_explain_why_operations_are_not_fused() {
+        let operations = [operation_1(), operation_2(), operation_3(), operation_1()];
+        let builders = || -> Vec<Box<dyn OptimizationBuilder<_>>> {
+            let mut converter = OperationConverter::default();
+            let fused = [operation_1(), operation_2()]
+                .map(|operation| operation.to_relative(&mut converter));
+            vec![Box::new(TestOptimizationBuilder::new(0, fused.to_vec()))]
+        };
+
+        let explanation = FusionExplanation::new(
+            &operations,
+            builders(),
+            ExplorationSettings::default(),
+            ScalarParameterization::default(),
+        );
+
+        assert_eq!(explanation.plans, vec![0, 0, 1, 2]);
+        assert_eq!(explanation.boundaries[0], FusionDecision::Fused);
+        assert_eq!(
+            explanation.boundaries[1],
+            FusionDecision::NotFused(NotFusedReason::Unsupported {
+                operation: operation_kind(&operation_3()),
+            })
+        );
+
+        // The second operation is excluded.
+        let kind = operation_kind(&operation_2());
+        let exploration = ExplorationSettings {
+            filter: FusionFilter::Deny(vec![kind.clone()]).into(),
+            ..Default::default()
+        };
+        let explanation = FusionExplanation::new(
+            &operations,
+            builders(),
+            exploration,
+            ScalarParameterization::default(),
+        );
+
+        assert_eq!(
+            explanation.boundaries[0],
+            FusionDecision::NotFused(NotFusedReason::Filtered { operation: kind })
+        );
+    }
+}
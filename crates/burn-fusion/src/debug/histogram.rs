@@ -0,0 +1,234 @@
+use core::fmt::Display;
+
+use burn_ir::{OperationIr, TensorIr, TensorStatus};
+use burn_tensor::DType;
+use hashbrown::HashMap;
+
+use crate::stream::store::ExecutionStrategy;
+
+/// Operations grouped by [kind, data type, rank and rounded shape](OperationBucketKey).
+///
+/// Queued operations are counted once per stream queue, while fused and unfused operations are
+/// counted once per [execution plan](crate::stream::ExecutionPlanId), no matter how many times
+/// the plan is executed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationHistogram {
+    /// The buckets, sorted by decreasing number of operations.
+    pub buckets: Vec<OperationBucket>,
+}
+
+/// The key identifying an [operation bucket](OperationBucket).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OperationBucketKey {
+    /// The kind of the operation, e.g. `NumericFloat::Add`.
+    pub kind: String,
+    /// The data type of the output of the operation.
+    pub dtype: DType,
+    /// The rank of the output of the operation.
+    pub rank: usize,
+    /// The shape of the output of the operation, each dimension rounded up to a power of two.
+    pub shape: Vec<usize>,
+}
+
+/// The number of operations sharing the same [key](OperationBucketKey).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationBucket {
+    /// The key of the bucket.
+    pub key: OperationBucketKey,
+    /// The number of operations waiting in a stream queue.
+    pub num_queued: usize,
+    /// The number of operations executed as part of an optimization.
+    pub num_fused: usize,
+    /// The number of operations executed individually.
+    pub num_unfused: usize,
+}
+
+impl OperationBucket {
+    /// The total number of operations in the bucket.
+    pub fn num_operations(&self) -> usize {
+        self.num_queued + self.num_fused + self.num_unfused
+    }
+}
+
+/// Collect the operations of an [operation histogram](OperationHistogram).
+#[derive(Default)]
+pub(crate) struct OperationHistogramBuilder {
+    buckets: HashMap<OperationBucketKey, OperationBucket>,
+}
+
+impl OperationHistogramBuilder {
+    /// Add operations waiting in a stream queue.
+    pub(crate) fn queued(&mut self, operations: &[OperationIr]) {
+        for operation in operations {
+            self.bucket(operation).num_queued += 1;
+        }
+    }
+
+    /// Add the operations of an execution plan, executed with the given strategy.
+    pub(crate) fn plan<O>(&mut self, operations: &[OperationIr], strategy: &ExecutionStrategy<O>) {
+        match strategy {
+            ExecutionStrategy::Optimization { ordering, .. } => {
+                for index in ordering.iter() {
+                    self.bucket(&operations[*index]).num_fused += 1;
+                }
+            }
+            ExecutionStrategy::Operations { ordering } => {
+                for index in ordering.iter() {
+                    self.bucket(&operations[*index]).num_unfused += 1;
+                }
+            }
+            ExecutionStrategy::Composed(items) => {
+                for item in items {
+                    self.plan(operations, item);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn build(self) -> OperationHistogram {
+        let mut buckets = self.buckets.into_values().collect::<Vec<_>>();
+        buckets.sort_by(|a, b| {
+            b.num_operations()
+                .cmp(&a.num_operations())
+                .then_with(|| a.key.kind.cmp(&b.key.kind))
+        });
+
+        OperationHistogram { buckets }
+    }
+
+    fn bucket(&mut self, operation: &OperationIr) -> &mut OperationBucket {
+        let key = OperationBucketKey::new(operation);
+
+        self.buckets
+            .entry(key.clone())
+            .or_insert_with(|| OperationBucket {
+                key,
+                num_queued: 0,
+                num_fused: 0,
+                num_unfused: 0,
+            })
+    }
+}
+
+impl OperationBucketKey {
+    fn new(operation: &OperationIr) -> Self {
+        let nodes = operation.nodes();
+        // Operations without output, like drop, are keyed by their input.
+        let tensor = nodes
+            .iter()
+            .find(|node| node.status == TensorStatus::NotInit)
+            .or(nodes.first())
+            .copied();
+
+        let (dtype, shape) = match tensor {
+            Some(TensorIr { dtype, shape, .. }) => (*dtype, shape.as_slice()),
+            None => (DType::F32, [].as_slice()),
+        };
+
+        Self {
+            kind: operation_kind(operation),
+            dtype,
+            rank: shape.len(),
+            shape: shape.iter().map(|dim| dim.next_power_of_two()).collect(),
+        }
+    }
+}
+
+/// The name of the operation, without its arguments.
+pub(crate) fn operation_kind(operation: &OperationIr) -> String {
+    let (category, repr) = match operation {
+        OperationIr::BaseFloat(repr) => ("BaseFloat", format!("{repr:?}")),
+        OperationIr::BaseInt(repr) => ("BaseInt", format!("{repr:?}")),
+        OperationIr::BaseBool(repr) => ("BaseBool", format!("{repr:?}")),
+        OperationIr::NumericFloat(_, repr) => ("NumericFloat", format!("{repr:?}")),
+        OperationIr::NumericInt(_, repr) => ("NumericInt", format!("{repr:?}")),
+        OperationIr::Bool(repr) => ("Bool", format!("{repr:?}")),
+        OperationIr::Int(repr) => ("Int", format!("{repr:?}")),
+        OperationIr::Float(_, repr) => ("Float", format!("{repr:?}")),
+        OperationIr::Module(repr) => ("Module", format!("{repr:?}")),
+        OperationIr::Init(_) => return "Init".into(),
+        OperationIr::Custom(repr) => return format!("Custom::{}", repr.id),
+        OperationIr::Drop(_) => return "Drop".into(),
+    };
+    let name = repr
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default();
+
+    format!("{category}::{name}")
+}
+
+impl Display for OperationHistogram {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Operation Histogram ====\n")?;
+
+        for bucket in self.buckets.iter() {
+            f.write_fmt(format_args!(
+                " - {} {:?} {:?} => queued: {} fused: {} unfused: {}\n",
+                bucket.key.kind,
+                bucket.key.dtype,
+                bucket.key.shape,
+                bucket.num_queued,
+                bucket.num_fused,
+                bucket.num_unfused
+            ))?;
+        }
+
+        f.write_str("====================================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use burn_ir::{BinaryOpIr, NumericOperationIr};
+
+    use super::*;
+    use crate::test_utils::tensor_with_shape;
+
+    #[test]
+    fn should_bucket_operations_by_rounded_shape() {
+        let mut builder = OperationHistogramBuilder::default();
+        let operations = [add([30, 60]), add([32, 64]), add([33, 64])];
+
+        builder.queued(&operations[0..1]);
+        builder.plan(
+            &operations,
+            &ExecutionStrategy::<()>::Composed(vec![
+                Box::new(ExecutionStrategy::Optimization {
+                    opt: (),
+                    ordering: Arc::new(vec![1]),
+                }),
+                Box::new(ExecutionStrategy::Operations {
+                    ordering: Arc::new(vec![2]),
+                }),
+            ]),
+        );
+
+        let histogram = builder.build();
+
+        assert_eq!(histogram.buckets.len(), 2);
+        let bucket = &histogram.buckets[0];
+        assert_eq!(bucket.key.kind, "NumericFloat::Add");
+        assert_eq!(bucket.key.shape, vec![32, 64]);
+        assert_eq!(bucket.num_queued, 1);
+        assert_eq!(bucket.num_fused, 1);
+        assert_eq!(bucket.num_unfused, 0);
+        assert_eq!(histogram.buckets[1].key.shape, vec![64, 64]);
+        assert_eq!(histogram.buckets[1].num_unfused, 1);
+    }
+
+    fn add(shape: [usize; 2]) -> OperationIr {
+        let tensor = |id, status| tensor_with_shape(id, shape.to_vec(), status);
+
+        OperationIr::NumericFloat(
+            DType::F32,
+            NumericOperationIr::Add(BinaryOpIr {
+                lhs: tensor(0, TensorStatus::ReadOnly),
+                rhs: tensor(1, TensorStatus::ReadOnly),
+                out: tensor(2, TensorStatus::NotInit),
+            }),
+        )
+    }
+}
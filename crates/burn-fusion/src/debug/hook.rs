@@ -0,0 +1,144 @@
+use std::{sync::Arc, time::Duration};
+use web_time::Instant;
+
+use burn_common::id::StreamId;
+use burn_ir::{OperationIr, OperationMetadata};
+
+use crate::stream::ExecutionPlanId;
+
+/// Callbacks invoked by the fusion server, letting tools collect fusion telemetry continuously.
+///
+/// All methods have an empty default implementation, so only the relevant events need to be
+/// implemented.
+///
+/// # Notes
+///
+/// Hooks are called while the server is locked, so they should be fast and must not interact
+/// with tensors on the same device.
+pub trait FusionHook: Send {
+    /// Called when an [operation](OperationIr) is registered on the given stream.
+    fn on_operation_registered(&mut self, _stream: StreamId, _operation: &OperationIr) {}
+    /// Called when a new execution plan is created for the given relative operations.
+    fn on_plan_created(&mut self, _plan: ExecutionPlanId, _operations: &[OperationIr]) {}
+    /// Called before an execution plan is executed, with the operations it executes as they were
+    /// registered, i.e. with the ids of the actual tensors instead of relative ones.
+    fn on_plan_execution(&mut self, _plan: ExecutionPlanId, _operations: &[OperationIr]) {}
+    /// Called before an execution plan is executed, with the [metadata](OperationMetadata) of
+    /// the operations it executes, `None` for the ones registered without any.
+    fn on_plan_metadata(
+        &mut self,
+        _plan: ExecutionPlanId,
+        _metadata: &[Option<Arc<OperationMetadata>>],
+    ) {
+    }
+    /// Called after an execution plan is executed.
+    ///
+    /// The duration only covers the time spent launching the plan, since the underlying backend
+    /// can still be async.
+    fn on_plan_executed(&mut self, _plan: ExecutionPlanId, _duration: Duration) {}
+}
+
+/// The [hooks](FusionHook) registered on a fusion server.
+#[derive(Default)]
+pub(crate) struct FusionHooks {
+    hooks: Vec<Box<dyn FusionHook>>,
+    /// The number of plans already notified as created.
+    num_plans: usize,
+}
+
+impl FusionHooks {
+    /// Register a new hook, `num_plans` being the number of plans already created by the store.
+    pub(crate) fn register(&mut self, hook: Box<dyn FusionHook>, num_plans: usize) {
+        self.hooks.push(hook);
+        self.num_plans = self.num_plans.max(num_plans);
+    }
+
+    pub(crate) fn on_operation_registered(&mut self, stream: StreamId, operation: &OperationIr) {
+        for hook in self.hooks.iter_mut() {
+            hook.on_operation_registered(stream, operation);
+        }
+    }
+
+    /// Notify the hooks that the given plan is about to be executed.
+    ///
+    /// Plan ids are attributed sequentially and new plans are always executed right away, so any
+    /// plan id not seen before is a newly created plan.
+    ///
+    /// Returns the start of the execution when at least one hook is registered.
+    pub(crate) fn before_plan(
+        &mut self,
+        plan: ExecutionPlanId,
+        operations: &[OperationIr],
+        global: &[OperationIr],
+        metadata: &[Option<Arc<OperationMetadata>>],
+    ) -> Option<Instant> {
+        if self.hooks.is_empty() {
+            return None;
+        }
+
+        if plan >= self.num_plans {
+            for hook in self.hooks.iter_mut() {
+                hook.on_plan_created(plan, operations);
+            }
+            self.num_plans = plan + 1;
+        }
+
+        for hook in self.hooks.iter_mut() {
+            hook.on_plan_execution(plan, global);
+            hook.on_plan_metadata(plan, metadata);
+        }
+
+        Some(Instant::now())
+    }
+
+    /// Notify the hooks that the given plan was executed.
+    pub(crate) fn after_plan(&mut self, plan: ExecutionPlanId, start: Option<Instant>) {
+        let duration = match start {
+            Some(start) => start.elapsed(),
+            None => return,
+        };
+
+        for hook in self.hooks.iter_mut() {
+            hook.on_plan_executed(plan, duration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Default, Clone)]
+    struct Recorder {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FusionHook for Recorder {
+        fn on_plan_created(&mut self, plan: ExecutionPlanId, _operations: &[OperationIr]) {
+            self.events.lock().unwrap().push(format!("created {plan}"));
+        }
+
+        fn on_plan_executed(&mut self, plan: ExecutionPlanId, _duration: Duration) {
+            self.events.lock().unwrap().push(format!("executed {plan}"));
+        }
+    }
+
+    #[test]
+    fn should_notify_plan_created_only_once() {
+        let recorder = Recorder::default();
+        let mut hooks = FusionHooks::default();
+        hooks.register(Box::new(recorder.clone()), 1);
+
+        for plan in [0, 1, 1] {
+            let start = hooks.before_plan(plan, &[], &[], &[]);
+            hooks.after_plan(plan, start);
+        }
+
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            ["executed 0", "created 1", "executed 1", "executed 1"]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use core::fmt::Display;
+
+use burn_ir::{OperationIr, TensorId, TensorStatus};
+use hashbrown::HashMap;
+
+/// The estimated memory usage of a list of operations, derived from the lifetime of each tensor.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryEstimate {
+    /// The maximum number of bytes alive while executing a single operation.
+    pub peak_bytes: u64,
+    /// The index of the first operation reaching the peak, if any.
+    pub peak_index: Option<usize>,
+    /// The number of bytes alive while executing each operation.
+    pub live_bytes: Vec<u64>,
+    /// The lifetime of each tensor, in order of first use.
+    pub lifetimes: Vec<TensorLifetime>,
+}
+
+/// The lifetime of a tensor within a list of operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorLifetime {
+    /// The tensor id.
+    pub id: TensorId,
+    /// The number of bytes used by the tensor.
+    pub bytes: u64,
+    /// The index of the operation producing the tensor, `None` when it's an input of the list.
+    pub producer: Option<usize>,
+    /// The index of the last operation using the tensor.
+    pub last_use: usize,
+    /// Whether the tensor is freed by the last operation using it.
+    ///
+    /// Tensors that aren't freed are still referenced after the list of operations.
+    pub freed: bool,
+}
+
+impl TensorLifetime {
+    /// The index of the first operation during which the tensor is alive.
+    pub fn start(&self) -> usize {
+        self.producer.unwrap_or(0)
+    }
+}
+
+/// Estimate the lifetime of each tensor used by the operations, along with the peak number of
+/// bytes alive at once.
+///
+/// A tensor is alive from the operation producing it, or from the start when it's an input, up
+/// to the operation freeing it, or up to the end when it's still referenced afterward. Both the
+/// inputs and the outputs of an operation are considered alive during its execution.
+pub fn estimate_memory(operations: &[OperationIr]) -> MemoryEstimate {
+    let mut lifetimes = Vec::<TensorLifetime>::new();
+    let mut positions = HashMap::<TensorId, usize>::new();
+
+    for (index, operation) in operations.iter().enumerate() {
+        for node in operation.nodes() {
+            let freed =
+                matches!(operation, OperationIr::Drop(_)) || node.status == TensorStatus::ReadWrite;
+
+            match positions.get(&node.id) {
+                Some(position) => {
+                    let lifetime = &mut lifetimes[*position];
+                    lifetime.last_use = index;
+                    lifetime.freed = freed;
+                }
+                None => {
+                    positions.insert(node.id, lifetimes.len());
+                    lifetimes.push(TensorLifetime {
+                        id: node.id,
+                        bytes: (node.shape.iter().product::<usize>() * node.dtype.size()) as u64,
+                        producer: match node.status {
+                            TensorStatus::NotInit => Some(index),
+                            _ => None,
+                        },
+                        last_use: index,
+                        freed,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut live_bytes = vec![0u64; operations.len()];
+
+    for lifetime in lifetimes.iter() {
+        let end = match lifetime.freed {
+            true => lifetime.last_use + 1,
+            false => operations.len(),
+        };
+
+        for bytes in live_bytes[lifetime.start()..end].iter_mut() {
+            *bytes += lifetime.bytes;
+        }
+    }
+
+    let mut estimate = MemoryEstimate {
+        live_bytes,
+        lifetimes,
+        ..Default::default()
+    };
+
+    for (index, bytes) in estimate.live_bytes.iter().enumerate() {
+        if *bytes > estimate.peak_bytes {
+            estimate.peak_bytes = *bytes;
+            estimate.peak_index = Some(index);
+        }
+    }
+
+    estimate
+}
+
+impl Display for MemoryEstimate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Memory Estimate ====\n")?;
+        f.write_fmt(format_args!(" - Tensors: {}\n", self.lifetimes.len()))?;
+
+        match self.peak_index {
+            Some(index) => f.write_fmt(format_args!(
+                " - Peak: {} bytes at operation {index}\n",
+                self.peak_bytes
+            ))?,
+            None => f.write_str(" - Peak: 0 bytes\n")?,
+        }
+
+        f.write_str("================================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::BinaryOpIr;
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_find_peak_when_intermediates_are_alive() {
+        // 0: b = exp(a)       a: input, still referenced afterward.
+        // 1: c = exp(b)
+        // 2: d = b * c        b and c are freed.
+        // 3: drop(d)
+        let operations = vec![
+            exp(
+                tensor(0, TensorStatus::ReadOnly),
+                tensor(1, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            OperationIr::NumericFloat(
+                DType::F32,
+                burn_ir::NumericOperationIr::Mul(BinaryOpIr {
+                    lhs: tensor(1, TensorStatus::ReadWrite),
+                    rhs: tensor(2, TensorStatus::ReadWrite),
+                    out: tensor(3, TensorStatus::NotInit),
+                }),
+            ),
+            OperationIr::Drop(tensor(3, TensorStatus::ReadWrite)),
+        ];
+
+        let estimate = estimate_memory(&operations);
+
+        assert_eq!(estimate.live_bytes, vec![64, 96, 128, 64]);
+        assert_eq!(estimate.peak_bytes, 128);
+        assert_eq!(estimate.peak_index, Some(2));
+        assert_eq!(estimate.lifetimes.len(), 4);
+        assert!(!estimate.lifetimes[0].freed);
+        assert_eq!(estimate.lifetimes[2].producer, Some(1));
+        assert_eq!(estimate.lifetimes[2].last_use, 2);
+    }
+}
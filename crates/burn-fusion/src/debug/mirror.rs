@@ -0,0 +1,311 @@
+use core::fmt::Display;
+use std::sync::Arc;
+
+use burn_common::future::DynFut;
+use burn_ir::{HandleContainer, OperationIr, TensorId, TensorIr, TensorStatus};
+use burn_tensor::{DType, TensorData};
+use hashbrown::HashMap;
+use spin::Mutex;
+
+use crate::{FusionRuntime, stream::execution::Operation};
+
+/// Options of the [mirror](crate::Fusion::enable_mirror) used for differential testing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MirrorOptions {
+    /// The maximum absolute difference allowed between the fused and the reference values.
+    pub tolerance: f64,
+    /// Whether every tensor read is used as a sync point to compare the fused values with the
+    /// reference values.
+    ///
+    /// When disabled, values are only compared on [explicit sync](crate::Fusion::mirror_sync).
+    pub check_reads: bool,
+}
+
+impl Default for MirrorOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-3,
+            check_reads: true,
+        }
+    }
+}
+
+/// The first operation whose output differs between the fusion backend and the reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirrorDivergence {
+    /// The position of the operation in the registration order since the mirror was enabled.
+    pub position: usize,
+    /// The operation producing the divergent tensor.
+    pub operation: OperationIr,
+    /// The divergent tensor.
+    pub tensor: TensorIr,
+    /// The maximum absolute difference between the fused and the reference values.
+    pub max_abs_diff: f64,
+}
+
+impl Display for MirrorDivergence {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!(
+            "Fusion diverged from the reference at operation #{} on tensor {:?} (max abs diff: {}): {:?}",
+            self.position, self.tensor.id, self.max_abs_diff, self.operation
+        ))
+    }
+}
+
+/// Runs every registered operation a second time, unfused, on its own handles.
+///
+/// Both executions use the same device, so the reference values only differ from the fused ones
+/// when an optimization is wrong.
+pub(crate) struct Mirror<R: FusionRuntime> {
+    options: MirrorOptions,
+    handles: HandleContainer<R::FusionHandle>,
+    producers: HashMap<TensorId, Producer>,
+    num_operations: usize,
+    divergence: Arc<Mutex<Option<MirrorDivergence>>>,
+}
+
+#[derive(Clone)]
+struct Producer {
+    position: usize,
+    operation: OperationIr,
+    tensor: TensorIr,
+}
+
+/// A comparison between a fused tensor and its reference, done when the fused value is read.
+pub(crate) struct MirrorCheck {
+    reference: DynFut<TensorData>,
+    producer: Producer,
+    tolerance: f64,
+    divergence: Arc<Mutex<Option<MirrorDivergence>>>,
+}
+
+impl<R: FusionRuntime> Mirror<R> {
+    /// Create a new mirror starting from the given handles.
+    pub(crate) fn new(options: MirrorOptions, handles: &HandleContainer<R::FusionHandle>) -> Self {
+        Self {
+            options,
+            handles: handles.fork(),
+            producers: HashMap::new(),
+            num_operations: 0,
+            divergence: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Track a tensor registered directly from a handle.
+    pub(crate) fn register_handle(&mut self, id: TensorId, handle: &R::FusionHandle) {
+        self.handles.register_handle(id, handle.clone());
+    }
+
+    /// Execute the operation on the reference handles.
+    pub(crate) fn register_operation(&mut self, repr: &OperationIr, operation: &dyn Operation<R>) {
+        let position = self.num_operations;
+        self.num_operations += 1;
+
+        let nodes = repr.nodes();
+        // Tensors coming from another device aren't mirrored, so neither are their dependents.
+        let missing_input = nodes
+            .iter()
+            .filter(|node| node.status != TensorStatus::NotInit)
+            .any(|node| !self.handles.has_handle(&node.id));
+
+        for node in nodes.iter() {
+            match node.status {
+                TensorStatus::ReadWrite => {
+                    self.producers.remove(&node.id);
+                }
+                TensorStatus::NotInit if !missing_input => {
+                    self.producers.insert(
+                        node.id,
+                        Producer {
+                            position,
+                            operation: repr.clone(),
+                            tensor: (*node).clone(),
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        if missing_input {
+            for node in nodes
+                .iter()
+                .filter(|node| node.status == TensorStatus::ReadWrite)
+            {
+                self.handles.remove_handle(node.id);
+            }
+        } else {
+            operation.execute(&mut self.handles);
+        }
+    }
+
+    /// Prepare the comparison of a tensor being read, using `read` to read its reference value.
+    ///
+    /// Returns `None` if the tensor isn't mirrored or if reads aren't sync points.
+    pub(crate) fn check<F>(
+        &mut self,
+        tensor: &TensorIr,
+        read: impl FnOnce(&mut HandleContainer<R::FusionHandle>, &TensorIr) -> F,
+    ) -> Option<MirrorCheck>
+    where
+        F: Future<Output = TensorData> + Send + 'static,
+    {
+        let producer = match self.producers.get(&tensor.id) {
+            Some(producer) if self.options.check_reads => producer.clone(),
+            _ => {
+                if tensor.status == TensorStatus::ReadWrite {
+                    self.producers.remove(&tensor.id);
+                    self.handles.remove_handle(tensor.id);
+                }
+                return None;
+            }
+        };
+
+        if tensor.status == TensorStatus::ReadWrite {
+            self.producers.remove(&tensor.id);
+        }
+
+        Some(MirrorCheck {
+            reference: Box::pin(read(&mut self.handles, tensor)),
+            producer,
+            tolerance: self.options.tolerance,
+            divergence: self.divergence.clone(),
+        })
+    }
+
+    /// Compare every mirrored tensor still alive, using `read` to read the fused and the
+    /// reference values.
+    pub(crate) fn sync(
+        &mut self,
+        handles: &mut HandleContainer<R::FusionHandle>,
+        read: impl Fn(&mut HandleContainer<R::FusionHandle>, &TensorIr) -> Option<TensorData>,
+    ) -> Option<MirrorDivergence> {
+        let mut producers = self.producers.values().cloned().collect::<Vec<_>>();
+        producers.sort_by_key(|producer| producer.position);
+
+        for producer in producers {
+            let tensor = TensorIr {
+                status: TensorStatus::ReadOnly,
+                ..producer.tensor.clone()
+            };
+
+            if !handles.has_handle(&tensor.id) || !self.handles.has_handle(&tensor.id) {
+                continue;
+            }
+
+            let (actual, expected) =
+                match (read(handles, &tensor), read(&mut self.handles, &tensor)) {
+                    (Some(actual), Some(expected)) => (actual, expected),
+                    _ => continue,
+                };
+
+            record(
+                &self.divergence,
+                &producer,
+                &actual,
+                &expected,
+                self.options.tolerance,
+            );
+        }
+
+        self.divergence()
+    }
+
+    /// The first divergence found so far.
+    pub(crate) fn divergence(&self) -> Option<MirrorDivergence> {
+        self.divergence.lock().clone()
+    }
+}
+
+impl MirrorCheck {
+    /// Compare the fused value with the reference value.
+    pub(crate) async fn verify(self, actual: &TensorData) {
+        let expected = self.reference.await;
+        record(
+            &self.divergence,
+            &self.producer,
+            actual,
+            &expected,
+            self.tolerance,
+        );
+    }
+}
+
+/// Keep the divergence of the earliest operation.
+fn record(
+    divergence: &Mutex<Option<MirrorDivergence>>,
+    producer: &Producer,
+    actual: &TensorData,
+    expected: &TensorData,
+    tolerance: f64,
+) {
+    let max_abs_diff = max_abs_diff(actual, expected);
+
+    if max_abs_diff <= tolerance {
+        return;
+    }
+
+    let mut divergence = divergence.lock();
+
+    match divergence.as_ref() {
+        Some(current) if current.position <= producer.position => {}
+        _ => {
+            let found = MirrorDivergence {
+                position: producer.position,
+                operation: producer.operation.clone(),
+                tensor: producer.tensor.clone(),
+                max_abs_diff,
+            };
+            log::warn!("{found}");
+            *divergence = Some(found);
+        }
+    }
+}
+
+/// The maximum absolute difference between two tensors, infinite when they can't be compared.
+fn max_abs_diff(actual: &TensorData, expected: &TensorData) -> f64 {
+    if actual.shape != expected.shape {
+        return f64::INFINITY;
+    }
+
+    // Quantized values are compared as is.
+    if let DType::QFloat(_) = actual.dtype {
+        return match actual.bytes == expected.bytes {
+            true => 0.0,
+            false => f64::INFINITY,
+        };
+    }
+
+    actual
+        .iter::<f64>()
+        .zip(expected.iter::<f64>())
+        .map(|(a, e)| match a.is_nan() && e.is_nan() {
+            true => 0.0,
+            false => (a - e).abs(),
+        })
+        .fold(0.0, |max, diff| match diff.is_nan() {
+            true => f64::INFINITY,
+            false => max.max(diff),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_compute_max_abs_diff() {
+        let actual = TensorData::from([1.0f32, 2.0, f32::NAN]);
+        let expected = TensorData::from([1.0f32, 2.5, f32::NAN]);
+
+        assert_eq!(max_abs_diff(&actual, &expected), 0.5);
+    }
+
+    #[test]
+    fn should_not_compare_tensors_with_different_shapes() {
+        let actual = TensorData::from([1.0f32, 2.0]);
+        let expected = TensorData::from([[1.0f32, 2.0]]);
+
+        assert_eq!(max_abs_diff(&actual, &expected), f64::INFINITY);
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(feature = "plan-export")]
+mod bundle;
+mod cache;
+mod compare;
+mod cross_stream;
+mod dependency;
+mod explain;
+mod histogram;
+mod hook;
+mod liveness;
+mod mirror;
+#[cfg(feature = "evcxr")]
+mod notebook;
+mod ordering;
+mod plans;
+mod recompilation;
+mod render;
+mod scope;
+mod snapshot;
+mod stream_info;
+mod summary;
+mod trigger;
+mod workload;
+
+#[cfg(feature = "plan-export")]
+pub(crate) use bundle::write_bundle;
+pub use cache::*;
+pub use compare::*;
+pub use cross_stream::*;
+pub use dependency::*;
+pub use explain::*;
+pub use histogram::*;
+pub use hook::*;
+pub use liveness::*;
+pub use mirror::*;
+#[cfg(feature = "evcxr")]
+pub use notebook::*;
+pub use ordering::*;
+pub use plans::*;
+pub use recompilation::*;
+pub use render::*;
+pub use scope::*;
+pub use snapshot::*;
+pub use stream_info::*;
+pub use summary::*;
+pub use trigger::*;
+pub use workload::*;
@@ -0,0 +1,327 @@
+use core::fmt::Write;
+
+use super::{
+    FusionDebugSummary, FusionSnapshot, OperationDisplay, OperationHistogram, PlanCacheStats,
+    PlanGraph, TriggerReport,
+};
+
+/// The script used to lay out [plan graphs](PlanGraph) in the browser, since notebooks can't
+/// be expected to have Graphviz installed.
+const VIZ_JS: &str = "https://cdn.jsdelivr.net/npm/@viz-js/viz@3.4.0/lib/viz-standalone.js";
+
+/// A debug report that can be rendered inline by [evcxr](https://github.com/evcxr/evcxr)
+/// notebooks and REPLs.
+///
+/// Evcxr renders the value of a cell with its `evcxr_display` method, which every report
+/// implementing this trait also provides, so the reports show up as tables and graphs without
+/// calling anything. [show](NotebookDisplay::show) renders a report from the middle of a cell.
+pub trait NotebookDisplay {
+    /// The report as an HTML fragment.
+    fn to_html(&self) -> String;
+
+    /// Render the report in the output of the current cell.
+    fn show(&self) {
+        println!(
+            "EVCXR_BEGIN_CONTENT text/html\n{}\nEVCXR_END_CONTENT",
+            self.to_html()
+        );
+    }
+}
+
+macro_rules! evcxr_display {
+    ($($report:ty),*) => {
+        $(
+            impl $report {
+                /// Render the report inline, called by evcxr when the report is the value of a
+                /// cell.
+                pub fn evcxr_display(&self) {
+                    NotebookDisplay::show(self)
+                }
+            }
+        )*
+    };
+}
+
+evcxr_display!(
+    FusionDebugSummary,
+    PlanCacheStats,
+    TriggerReport,
+    OperationHistogram,
+    FusionSnapshot,
+    PlanGraph
+);
+
+/// An HTML table, its cells escaped when added.
+struct HtmlTable {
+    html: String,
+}
+
+impl HtmlTable {
+    fn new(title: &str, headers: &[&str]) -> Self {
+        let mut html = format!(
+            "<table style=\"white-space: pre-line\"><caption>{}</caption><tr>",
+            escape(title)
+        );
+        for header in headers {
+            let _ = write!(html, "<th>{}</th>", escape(header));
+        }
+        html += "</tr>";
+
+        Self { html }
+    }
+
+    fn row<I: IntoIterator<Item = String>>(&mut self, cells: I) {
+        self.html += "<tr>";
+        for cell in cells {
+            let _ = write!(self.html, "<td>{}</td>", escape(&cell));
+        }
+        self.html += "</tr>";
+    }
+
+    fn finish(self) -> String {
+        self.html + "</table>"
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&#39;",
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+impl NotebookDisplay for FusionDebugSummary {
+    fn to_html(&self) -> String {
+        let mut table = HtmlTable::new("Fusion Summary", &["Metric", "Value"]);
+        let rows = [
+            ("Streams", self.num_streams.to_string()),
+            ("Queued operations", self.num_queued_operations.to_string()),
+            ("Stored plans", self.plans.num_plans.to_string()),
+            ("Created plans", self.plans.num_created.to_string()),
+            (
+                "Deduplicated plans",
+                self.plans.num_deduplicated.to_string(),
+            ),
+            ("Evicted plans", self.plans.num_evicted.to_string()),
+            ("Pinned plans", self.plans.num_pinned.to_string()),
+            ("Cache hits", self.num_cache_hits.to_string()),
+            ("Cache misses", self.num_cache_misses.to_string()),
+            ("Hit rate", format!("{:.2}%", self.hit_rate() * 100.0)),
+            ("Pooled buffers", self.output_pool.num_buffers.to_string()),
+        ];
+
+        for (metric, value) in rows {
+            table.row([metric.to_string(), value]);
+        }
+
+        table.finish()
+    }
+}
+
+impl NotebookDisplay for PlanCacheStats {
+    fn to_html(&self) -> String {
+        let mut table = HtmlTable::new(
+            &format!(
+                "Fusion Plan Cache: {} hits, {} misses ({:.2}%)",
+                self.num_hits,
+                self.num_misses,
+                self.hit_rate() * 100.0
+            ),
+            &["Plan", "Operations", "Executions", "Mean", "Min", "Max"],
+        );
+
+        for plan in self.plans.iter() {
+            table.row([
+                plan.plan.to_string(),
+                plan.num_operations.to_string(),
+                plan.num_executions.to_string(),
+                format!("{:?}", plan.timings.mean()),
+                format!("{:?}", plan.timings.min),
+                format!("{:?}", plan.timings.max),
+            ]);
+        }
+
+        table.finish()
+    }
+}
+
+impl NotebookDisplay for TriggerReport {
+    fn to_html(&self) -> String {
+        let mut table = HtmlTable::new(
+            "Fusion Trigger Report",
+            &["Plan", "Operations", "Trigger", "Fired"],
+        );
+
+        for plan in self.plans.iter() {
+            for trigger in plan.triggers.iter() {
+                table.row([
+                    plan.plan.to_string(),
+                    plan.num_operations.to_string(),
+                    trigger.condition.to_string(),
+                    trigger.num_fired.to_string(),
+                ]);
+            }
+        }
+
+        table.finish()
+    }
+}
+
+impl NotebookDisplay for OperationHistogram {
+    fn to_html(&self) -> String {
+        let mut table = HtmlTable::new(
+            "Fusion Operation Histogram",
+            &["Kind", "DType", "Shape", "Queued", "Fused", "Unfused"],
+        );
+
+        for bucket in self.buckets.iter() {
+            table.row([
+                bucket.key.kind.clone(),
+                format!("{:?}", bucket.key.dtype),
+                format!("{:?}", bucket.key.shape),
+                bucket.num_queued.to_string(),
+                bucket.num_fused.to_string(),
+                bucket.num_unfused.to_string(),
+            ]);
+        }
+
+        table.finish()
+    }
+}
+
+impl NotebookDisplay for FusionSnapshot {
+    fn to_html(&self) -> String {
+        let mut streams = HtmlTable::new("Fusion Snapshot", &["Stream", "Queued", "Operations"]);
+
+        for stream in self.streams.iter() {
+            let operations = stream
+                .operations
+                .iter()
+                .map(|operation| {
+                    OperationDisplay {
+                        operation,
+                        verbosity: self.verbosity,
+                    }
+                    .to_string()
+                })
+                .collect::<Vec<_>>();
+
+            streams.row([
+                match &stream.info {
+                    Some(info) => info.to_string(),
+                    None => stream.stream.to_string(),
+                },
+                stream.num_queued.to_string(),
+                operations.join("\n"),
+            ]);
+        }
+
+        let mut plans = HtmlTable::new("Stored Plans", &["Plan", "Operations", "Executions"]);
+
+        for plan in self.plans.iter() {
+            plans.row([
+                match plan.pinned {
+                    true => format!("{} (pinned)", plan.plan),
+                    false => plan.plan.to_string(),
+                },
+                plan.num_operations.to_string(),
+                plan.num_executions.to_string(),
+            ]);
+        }
+
+        streams.finish() + &plans.finish()
+    }
+}
+
+impl NotebookDisplay for PlanGraph {
+    fn to_html(&self) -> String {
+        // Each render needs its own element, since a notebook shows many graphs on one page.
+        static NEXT_ID: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+        let dot = self.to_dot();
+
+        // The DOT source is shown as is when the script can't be loaded, e.g. offline.
+        format!(
+            "<div id=\"fusion-plan-graph-{id}\"><pre>{}</pre></div>\
+             <script src=\"{VIZ_JS}\"></script>\
+             <script>\
+             Viz.instance().then(viz => {{\
+             const element = document.getElementById(\"fusion-plan-graph-{id}\");\
+             element.replaceChildren(viz.renderSVGElement({}));\
+             }});\
+             </script>",
+            escape(&dot),
+            script_string(&dot)
+        )
+    }
+}
+
+/// A string literal for inline scripts, `</script>` included.
+fn script_string(text: &str) -> String {
+    let mut literal = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => literal += "\\\"",
+            '\\' => literal += "\\\\",
+            '\n' => literal += "\\n",
+            '<' => literal += "\\u003c",
+            c => literal.push(c),
+        }
+    }
+
+    literal + "\""
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debug::{PlanEdge, PlanNode};
+
+    #[test]
+    fn should_render_reports_as_escaped_html() {
+        let histogram = OperationHistogram::default();
+        assert_eq!(
+            histogram.to_html(),
+            "<table style=\"white-space: pre-line\"><caption>Fusion Operation Histogram</caption><tr><th>Kind</th>\
+             <th>DType</th><th>Shape</th><th>Queued</th><th>Fused</th><th>Unfused</th></tr>\
+             </table>"
+        );
+
+        let graph = PlanGraph {
+            plans: vec![
+                PlanNode {
+                    plan: 0,
+                    num_operations: 2,
+                    num_executions: 1,
+                    names: Vec::new(),
+                },
+                PlanNode {
+                    plan: 1,
+                    num_operations: 1,
+                    num_executions: 1,
+                    names: Vec::new(),
+                },
+            ],
+            edges: vec![PlanEdge {
+                producer: 0,
+                consumer: 1,
+                num_tensors: 1,
+            }],
+        };
+        let html = graph.to_html();
+
+        assert!(html.contains("plan_0 -&gt; plan_1 [label=&quot;1&quot;];"));
+        assert!(html.contains("plan_0 -> plan_1 [label=\\\"1\\\"];\\n"));
+    }
+}
@@ -0,0 +1,192 @@
+use core::fmt::Display;
+
+use burn_ir::{OperationIr, TensorId, TensorStatus};
+use hashbrown::HashMap;
+
+/// A violation of the ordering guarantees of an execution plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderingViolation {
+    /// The ordering refers to an operation that isn't drained by the plan.
+    OutOfBounds {
+        /// The position of the operation.
+        position: usize,
+    },
+    /// The operation is executed more than once.
+    Duplicated {
+        /// The position of the operation.
+        position: usize,
+    },
+    /// The operation is executed before an operation registered earlier that it depends on.
+    Dependency {
+        /// The position of the operation executed too early.
+        position: usize,
+        /// The position of the operation it depends on.
+        dependency: usize,
+        /// The tensor shared by both operations.
+        tensor: TensorId,
+    },
+}
+
+/// Validate that executing the operations following the given ordering respects the registration
+/// order of dependent operations.
+///
+/// The ordering must be a permutation of the positions of the first `ordering.len()` operations.
+/// Two operations depend on each other when they share a tensor that isn't only read by both,
+/// i.e. when one of them creates, writes or frees it.
+pub fn validate_ordering(
+    operations: &[OperationIr],
+    ordering: &[usize],
+) -> Result<(), OrderingViolation> {
+    let num_operations = ordering.len().min(operations.len());
+    let mut executed_at = vec![None; num_operations];
+
+    for (step, position) in ordering.iter().enumerate() {
+        match executed_at.get_mut(*position) {
+            Some(Some(_)) => {
+                return Err(OrderingViolation::Duplicated {
+                    position: *position,
+                });
+            }
+            Some(slot) => *slot = Some(step),
+            None => {
+                return Err(OrderingViolation::OutOfBounds {
+                    position: *position,
+                });
+            }
+        }
+    }
+
+    let executed_at = executed_at
+        .into_iter()
+        .map(|step| step.expect("Every position to be executed"))
+        .collect::<Vec<_>>();
+    let mut accesses = HashMap::<TensorId, TensorAccesses>::new();
+
+    for (position, operation) in operations[0..num_operations].iter().enumerate() {
+        for node in operation.nodes() {
+            let access = accesses.entry(node.id).or_default();
+            let read_only = node.status == TensorStatus::ReadOnly;
+
+            // An operation using the same tensor multiple times doesn't depend on itself.
+            let dependencies = access
+                .last_write
+                .iter()
+                .chain(match read_only {
+                    true => [].iter(),
+                    false => access.reads.iter(),
+                })
+                .filter(|dependency| **dependency != position);
+
+            for dependency in dependencies {
+                if executed_at[*dependency] > executed_at[position] {
+                    return Err(OrderingViolation::Dependency {
+                        position,
+                        dependency: *dependency,
+                        tensor: node.id,
+                    });
+                }
+            }
+
+            match read_only {
+                true => access.reads.push(position),
+                false => {
+                    access.reads.clear();
+                    access.last_write = Some(position);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct TensorAccesses {
+    last_write: Option<usize>,
+    reads: Vec<usize>,
+}
+
+impl Display for OrderingViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::OutOfBounds { position } => f.write_fmt(format_args!(
+                "operation #{position} isn't drained by the plan"
+            )),
+            Self::Duplicated { position } => {
+                f.write_fmt(format_args!("operation #{position} is executed twice"))
+            }
+            Self::Dependency {
+                position,
+                dependency,
+                tensor,
+            } => f.write_fmt(format_args!(
+                "operation #{position} is executed before operation #{dependency} while both use tensor {tensor:?}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_allow_reordering_independent_operations() {
+        // 0: b = exp(a)
+        // 1: c = exp(a)
+        // 2: d = exp(b)
+        let operations = vec![
+            exp(
+                tensor(0, TensorStatus::ReadOnly),
+                tensor(1, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(0, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(3, TensorStatus::NotInit),
+            ),
+        ];
+
+        assert_eq!(validate_ordering(&operations, &[1, 0, 2]), Ok(()));
+        assert_eq!(validate_ordering(&operations, &[0, 2, 1]), Ok(()));
+    }
+
+    #[test]
+    fn should_detect_dependent_operations_executed_out_of_order() {
+        let operations = vec![
+            exp(
+                tensor(0, TensorStatus::ReadOnly),
+                tensor(1, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(0, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(3, TensorStatus::NotInit),
+            ),
+        ];
+
+        assert_eq!(
+            validate_ordering(&operations, &[2, 0, 1]),
+            Err(OrderingViolation::Dependency {
+                position: 2,
+                dependency: 0,
+                tensor: TensorId::new(1),
+            })
+        );
+        assert_eq!(
+            validate_ordering(&operations, &[0, 0, 1]),
+            Err(OrderingViolation::Duplicated { position: 0 })
+        );
+        assert_eq!(
+            validate_ordering(&operations, &[0, 3, 1]),
+            Err(OrderingViolation::OutOfBounds { position: 3 })
+        );
+    }
+}
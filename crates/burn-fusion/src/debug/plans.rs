@@ -0,0 +1,102 @@
+use core::fmt::Display;
+use std::time::Duration;
+
+use super::{metadata_names, operation_kind};
+use crate::{
+    PlanTiming,
+    stream::{ExecutionPlanId, PlanTimings, store::ExecutionPlanStore},
+};
+
+/// The accumulated execution times of the plans of a device, slowest first.
+///
+/// Unlike the [cache statistics](super::PlanCacheStats), plans are detailed with the operations
+/// they execute, which is enough to find the hot spots of a workload. Times are measured as set
+/// by the [configuration](crate::FusionConfig::plan_timing).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DetailedPlans {
+    /// How the execution times are currently measured.
+    pub timing: PlanTiming,
+    /// The plans executed at least once, by decreasing total execution time.
+    pub plans: Vec<DetailedPlan>,
+}
+
+/// An execution plan with its accumulated execution times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetailedPlan {
+    /// The id of the plan.
+    pub plan: ExecutionPlanId,
+    /// The kind of each operation executed by the plan, named as in the
+    /// [operation histogram](super::OperationHistogram).
+    pub operations: Vec<String>,
+    /// The names of the [annotations](super::FusionAnnotation) or [scopes](super::FusionScope)
+    /// of the operations the plan was first executed with.
+    pub names: Vec<String>,
+    /// The measured execution times of the plan.
+    pub timings: PlanTimings,
+}
+
+impl DetailedPlans {
+    pub(crate) fn new<O>(store: &ExecutionPlanStore<O>, timing: PlanTiming) -> Self {
+        let mut plans = store
+            .iter()
+            .map(|(id, plan)| (id, plan, store.timings(id)))
+            .filter(|(_, _, timings)| timings.num_samples > 0)
+            .map(|(id, plan, timings)| DetailedPlan {
+                plan: id,
+                operations: plan.operations.iter().map(operation_kind).collect(),
+                names: metadata_names(store.metadata(id)),
+                timings,
+            })
+            .collect::<Vec<_>>();
+        plans.sort_by(|a, b| {
+            b.timings
+                .total
+                .cmp(&a.timings.total)
+                .then(a.plan.cmp(&b.plan))
+        });
+
+        Self { timing, plans }
+    }
+
+    /// The time spent executing every plan.
+    pub fn total(&self) -> Duration {
+        self.plans.iter().map(|plan| plan.timings.total).sum()
+    }
+}
+
+impl Display for DetailedPlans {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let total = self.total();
+
+        f.write_str("\n==== Fusion Detailed Plans ====\n")?;
+        f.write_fmt(format_args!(" - Timing: {:?}\n", self.timing))?;
+        f.write_fmt(format_args!(" - Total: {total:?}\n"))?;
+
+        for plan in self.plans.iter() {
+            let share = match total.is_zero() {
+                true => 0.0,
+                false => plan.timings.total.as_secs_f64() / total.as_secs_f64(),
+            };
+
+            f.write_fmt(format_args!(
+                "  - Plan {} ({:.2}%) => executions: {} total: {:?} mean: {:?} min: {:?} max: {:?}\n",
+                plan.plan,
+                share * 100.0,
+                plan.timings.num_samples,
+                plan.timings.total,
+                plan.timings.mean(),
+                plan.timings.min,
+                plan.timings.max
+            ))?;
+            if !plan.names.is_empty() {
+                f.write_fmt(format_args!("    - from {}\n", plan.names.join(", ")))?;
+            }
+            f.write_fmt(format_args!(
+                "    - operations: {}\n",
+                plan.operations.join(", ")
+            ))?;
+        }
+
+        f.write_str("===============================\n")
+    }
+}
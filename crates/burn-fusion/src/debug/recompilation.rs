@@ -0,0 +1,170 @@
+use core::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+use std::sync::Arc;
+
+use burn_ir::OperationIr;
+use hashbrown::{HashMap, HashSet};
+
+use super::{FusionHook, current_scope, operation_kind};
+use crate::stream::ExecutionPlanId;
+
+/// A [hook](FusionHook) telling apart the plans created for new sequences of operations from the
+/// plans created for known sequences with new shapes, by [scope](super::FusionScope).
+///
+/// Relative operations only keep which dimensions are equal, so plans are created again when
+/// that pattern changes, e.g. a sequence length matching the hidden size or becoming `1`. The
+/// sequences of operations are compared regardless of their shapes, only keeping the rank of
+/// their tensors, so that the scopes recompiling the same operations over and over can be
+/// bucketed.
+///
+/// The recorder can be cloned before being registered, every clone sharing the same
+/// [report](RecompilationReport).
+#[derive(Clone, Default)]
+pub struct RecompilationRecorder {
+    state: Arc<spin::Mutex<RecompilationState>>,
+}
+
+#[derive(Default)]
+struct RecompilationState {
+    sequences: HashSet<u64>,
+    scopes: HashMap<Option<String>, ScopeRecompilations>,
+}
+
+/// The plans created while recording, by [scope](super::FusionScope).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecompilationReport {
+    /// The scopes that created plans, sorted by decreasing number of shape changes.
+    pub scopes: Vec<ScopeRecompilations>,
+}
+
+/// The plans created in a [scope](super::FusionScope).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeRecompilations {
+    /// The full name of the scope, `None` for the plans created outside of any scope.
+    pub scope: Option<String>,
+    /// The number of plans created for sequences of operations never seen before.
+    pub num_new_sequences: u64,
+    /// The number of plans created for known sequences of operations with new shapes.
+    pub num_shape_changes: u64,
+}
+
+impl RecompilationRecorder {
+    /// The report of the plans created since the recorder was registered.
+    pub fn report(&self) -> RecompilationReport {
+        let state = self.state.lock();
+
+        let mut scopes = state.scopes.values().cloned().collect::<Vec<_>>();
+        scopes.sort_by(|a, b| {
+            b.num_shape_changes
+                .cmp(&a.num_shape_changes)
+                .then_with(|| a.scope.cmp(&b.scope))
+        });
+
+        RecompilationReport { scopes }
+    }
+}
+
+impl FusionHook for RecompilationRecorder {
+    fn on_plan_created(&mut self, _plan: ExecutionPlanId, operations: &[OperationIr]) {
+        let scope = current_scope();
+        let mut state = self.state.lock();
+        let is_new = state.sequences.insert(shape_erased_fingerprint(operations));
+
+        let recompilations =
+            state
+                .scopes
+                .entry(scope.clone())
+                .or_insert_with(|| ScopeRecompilations {
+                    scope,
+                    ..Default::default()
+                });
+        match is_new {
+            true => recompilations.num_new_sequences += 1,
+            false => recompilations.num_shape_changes += 1,
+        }
+    }
+}
+
+/// Hash the kinds of the operations and how their tensors flow between them, ignoring shapes
+/// but for their rank.
+fn shape_erased_fingerprint(operations: &[OperationIr]) -> u64 {
+    let mut hasher = std::hash::DefaultHasher::new();
+
+    for operation in operations {
+        operation_kind(operation).hash(&mut hasher);
+
+        for tensor in operation.nodes() {
+            tensor.id.hash(&mut hasher);
+            tensor.status.hash(&mut hasher);
+            tensor.dtype.hash(&mut hasher);
+            tensor.shape.len().hash(&mut hasher);
+        }
+    }
+
+    hasher.finish()
+}
+
+impl Display for RecompilationReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Recompilation Report ====\n")?;
+
+        for scope in self.scopes.iter() {
+            f.write_fmt(format_args!(
+                " - {} => new sequences: {} shape changes: {}\n",
+                scope.scope.as_deref().unwrap_or("<no scope>"),
+                scope.num_new_sequences,
+                scope.num_shape_changes
+            ))?;
+        }
+
+        f.write_str("=====================================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::TensorStatus;
+
+    use super::*;
+    use crate::debug::scope;
+    use crate::test_utils::{exp, tensor_with_shape};
+
+    #[test]
+    fn should_tell_shape_changes_from_new_sequences() {
+        let mut recorder = RecompilationRecorder::default();
+        let exp = |shape: Vec<usize>| {
+            exp(
+                tensor_with_shape(0, shape.clone(), TensorStatus::ReadOnly),
+                tensor_with_shape(1, shape, TensorStatus::NotInit),
+            )
+        };
+
+        recorder.on_plan_created(0, &[exp(vec![0, 1])]);
+        {
+            let _scope = scope("attention");
+            // Same sequence, different shapes.
+            recorder.on_plan_created(1, &[exp(vec![0, 0])]);
+            recorder.on_plan_created(2, &[exp(vec![1, 0])]);
+            // Different rank.
+            recorder.on_plan_created(3, &[exp(vec![0])]);
+        }
+
+        assert_eq!(
+            recorder.report().scopes,
+            [
+                ScopeRecompilations {
+                    scope: Some("attention".into()),
+                    num_new_sequences: 1,
+                    num_shape_changes: 2,
+                },
+                ScopeRecompilations {
+                    scope: None,
+                    num_new_sequences: 1,
+                    num_shape_changes: 0,
+                },
+            ]
+        );
+    }
+}
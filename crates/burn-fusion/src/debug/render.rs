@@ -0,0 +1,107 @@
+use core::fmt::Display;
+
+use burn_ir::{OperationIr, TensorIr, TensorStatus};
+
+use super::operation_kind;
+
+/// How much of each operation is written by the debug reports.
+///
+/// Big models queue thousands of operations, so lower levels keep the reports readable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Only the kind of the operation, e.g. `Float::Exp`.
+    Minimal,
+    /// The kind of the operation along with the ids, data types and shapes of its tensors.
+    #[default]
+    Normal,
+    /// The whole intermediate representation of the operation, serialized as JSON with the
+    /// `plan-export` feature and in its debug form otherwise.
+    Full,
+}
+
+/// Writes an operation with the given [verbosity](Verbosity).
+pub(crate) struct OperationDisplay<'a> {
+    pub(crate) operation: &'a OperationIr,
+    pub(crate) verbosity: Verbosity,
+}
+
+impl Display for OperationDisplay<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.verbosity {
+            Verbosity::Minimal => f.write_str(&operation_kind(self.operation)),
+            Verbosity::Normal => {
+                let nodes = self.operation.nodes();
+                let write_tensors = |f: &mut core::fmt::Formatter<'_>, outputs: bool| {
+                    let tensors = nodes
+                        .iter()
+                        .filter(|tensor| (tensor.status == TensorStatus::NotInit) == outputs);
+
+                    for (i, tensor) in tensors.enumerate() {
+                        if i > 0 {
+                            f.write_str(", ")?;
+                        }
+                        write_tensor(f, tensor)?;
+                    }
+
+                    Ok(())
+                };
+
+                f.write_fmt(format_args!("{}(", operation_kind(self.operation)))?;
+                write_tensors(f, false)?;
+                f.write_str(") -> (")?;
+                write_tensors(f, true)?;
+                f.write_str(")")
+            }
+            #[cfg(feature = "plan-export")]
+            Verbosity::Full => match serde_json::to_string(self.operation) {
+                Ok(serialized) => f.write_str(&serialized),
+                Err(_) => f.write_fmt(format_args!("{:?}", self.operation)),
+            },
+            #[cfg(not(feature = "plan-export"))]
+            Verbosity::Full => f.write_fmt(format_args!("{:?}", self.operation)),
+        }
+    }
+}
+
+fn write_tensor(f: &mut core::fmt::Formatter<'_>, tensor: &TensorIr) -> core::fmt::Result {
+    f.write_fmt(format_args!(
+        "t{} {:?}{:?}",
+        tensor.id.value(),
+        tensor.dtype,
+        tensor.shape
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{exp, tensor_with_shape};
+
+    #[test]
+    fn should_write_operations_with_each_verbosity() {
+        let operation = exp(
+            tensor_with_shape(1, vec![2, 3], TensorStatus::ReadOnly),
+            tensor_with_shape(2, vec![2, 3], TensorStatus::NotInit),
+        );
+        let display = |verbosity| OperationDisplay {
+            operation: &operation,
+            verbosity,
+        };
+
+        assert_eq!(display(Verbosity::Minimal).to_string(), "Float::Exp");
+        assert_eq!(
+            display(Verbosity::Normal).to_string(),
+            "Float::Exp(t1 F32[2, 3]) -> (t2 F32[2, 3])"
+        );
+        #[cfg(feature = "plan-export")]
+        assert_eq!(
+            display(Verbosity::Full).to_string(),
+            serde_json::to_string(&operation).unwrap()
+        );
+        #[cfg(not(feature = "plan-export"))]
+        assert_eq!(
+            display(Verbosity::Full).to_string(),
+            format!("{operation:?}")
+        );
+    }
+}
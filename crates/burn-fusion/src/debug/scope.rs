@@ -0,0 +1,144 @@
+use core::{cell::RefCell, marker::PhantomData};
+use std::sync::Arc;
+
+use burn_ir::OperationMetadata;
+
+std::thread_local! {
+    static SCOPES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static ANNOTATIONS: RefCell<Vec<OperationMetadata>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Names the part of the model registering operations on the current thread until dropped,
+/// e.g. a module, so that debug reports can be broken down by scope.
+///
+/// Scopes are nested, the full name of a scope joining the names of the enclosing scopes with
+/// `/`.
+pub struct FusionScope {
+    // The scope is a property of the thread, so the guard can't be sent to another one.
+    _thread: PhantomData<*const ()>,
+}
+
+/// Attaches [metadata](OperationMetadata) to the operations registered on the current thread
+/// until dropped, so that execution plans and debug graphs can relate them to the model.
+///
+/// Annotations are nested, inner annotations taking precedence over the enclosing ones.
+pub struct FusionAnnotation {
+    // The annotation is a property of the thread, so the guard can't be sent to another one.
+    _thread: PhantomData<*const ()>,
+}
+
+/// Enter a new [scope](FusionScope) on the current thread.
+pub fn scope(name: &str) -> FusionScope {
+    SCOPES.with_borrow_mut(|scopes| scopes.push(name.to_string()));
+
+    FusionScope {
+        _thread: PhantomData,
+    }
+}
+
+/// The full name of the innermost scope of the current thread, `None` outside of any scope.
+pub fn current_scope() -> Option<String> {
+    SCOPES.with_borrow(|scopes| match scopes.is_empty() {
+        true => None,
+        false => Some(scopes.join("/")),
+    })
+}
+
+/// Attach the metadata to the operations registered on the current thread until the returned
+/// [annotation](FusionAnnotation) is dropped.
+pub fn annotate(metadata: OperationMetadata) -> FusionAnnotation {
+    ANNOTATIONS.with_borrow_mut(|annotations| annotations.push(metadata));
+
+    FusionAnnotation {
+        _thread: PhantomData,
+    }
+}
+
+/// The metadata of the operations registered on the current thread, merging the enclosing
+/// [annotations](FusionAnnotation) and named after the current [scope](FusionScope) when no
+/// annotation names them. `None` when there is nothing to attach.
+pub fn current_metadata() -> Option<OperationMetadata> {
+    let mut metadata = OperationMetadata::default();
+
+    ANNOTATIONS.with_borrow(|annotations| {
+        for annotation in annotations.iter().rev() {
+            metadata.inherit(annotation);
+        }
+    });
+    if metadata.name.is_none() {
+        metadata.name = current_scope();
+    }
+
+    (!metadata.is_empty()).then_some(metadata)
+}
+
+/// The distinct names of the metadata, in order of appearance.
+pub(crate) fn metadata_names<'a>(
+    metadata: impl IntoIterator<Item = &'a Option<Arc<OperationMetadata>>>,
+) -> Vec<String> {
+    let mut names = Vec::new();
+
+    for name in metadata
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.name.as_ref())
+    {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    names
+}
+
+impl Drop for FusionAnnotation {
+    fn drop(&mut self) {
+        ANNOTATIONS.with_borrow_mut(|annotations| annotations.pop());
+    }
+}
+
+impl Drop for FusionScope {
+    fn drop(&mut self) {
+        SCOPES.with_borrow_mut(|scopes| scopes.pop());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_nest_scopes() {
+        assert_eq!(current_scope(), None);
+
+        let encoder = scope("encoder");
+        {
+            let _layer = scope("layer_0");
+            assert_eq!(current_scope().as_deref(), Some("encoder/layer_0"));
+        }
+        assert_eq!(current_scope().as_deref(), Some("encoder"));
+
+        core::mem::drop(encoder);
+        assert_eq!(current_scope(), None);
+    }
+
+    #[test]
+    fn should_merge_annotations_with_the_current_scope() {
+        assert_eq!(current_metadata(), None);
+
+        let _scope = scope("decoder");
+        let _outer = annotate(OperationMetadata::default().with_tag("phase", "eval"));
+        let metadata = current_metadata().unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("decoder"));
+        assert_eq!(metadata.tags["phase"], "eval");
+
+        {
+            let _inner = annotate(OperationMetadata::named("cross_attention").located());
+            let metadata = current_metadata().unwrap();
+            assert_eq!(metadata.name.as_deref(), Some("cross_attention"));
+            assert!(metadata.location.is_some());
+            assert_eq!(metadata.tags["phase"], "eval");
+        }
+        assert_eq!(current_metadata().unwrap().location, None);
+    }
+}
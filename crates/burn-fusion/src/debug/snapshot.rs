@@ -0,0 +1,188 @@
+use core::fmt::Display;
+
+use burn_ir::OperationIr;
+
+use super::{OperationDisplay, StreamInfo, Verbosity, metadata_names};
+use crate::stream::{ExecutionPlanId, StreamId, store::ExecutionPlanStore};
+
+/// Limit how much of the state of the fusion server is copied in a [snapshot](FusionSnapshot).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotOptions {
+    /// The maximum number of queued operations copied per stream, starting from the most recent.
+    pub max_queued_operations: usize,
+    /// How much of each queued operation is written when the snapshot is displayed.
+    pub verbosity: Verbosity,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            max_queued_operations: 64,
+            verbosity: Verbosity::default(),
+        }
+    }
+}
+
+/// An owned copy of the queues and plans of the fusion server of a device.
+///
+/// The server is only locked while a bounded sample of its state is copied, so snapshots can be
+/// taken on live workloads, and then inspected for as long as needed without blocking the
+/// registration of new operations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FusionSnapshot {
+    /// The streams with queued operations, sorted by id.
+    pub streams: Vec<StreamSnapshot>,
+    /// The plans currently stored, sorted by id.
+    pub plans: Vec<PlanSnapshot>,
+    /// How much of each queued operation is written when the snapshot is displayed.
+    pub verbosity: Verbosity,
+}
+
+/// The queued operations of a stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSnapshot {
+    /// The id of the stream.
+    pub stream: StreamId,
+    /// Where and when the stream was first used.
+    pub info: Option<StreamInfo>,
+    /// The number of operations queued on the stream.
+    pub num_queued: usize,
+    /// The most recent queued operations, in registration order, limited by
+    /// [max_queued_operations](SnapshotOptions::max_queued_operations).
+    pub operations: Vec<OperationIr>,
+}
+
+/// The summary of an execution plan, without its operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanSnapshot {
+    /// The id of the plan.
+    pub plan: ExecutionPlanId,
+    /// The number of operations executed by the plan.
+    pub num_operations: usize,
+    /// The number of times the plan was executed.
+    pub num_executions: u64,
+    /// Whether the plan is pinned.
+    pub pinned: bool,
+    /// The names of the [annotations](super::FusionAnnotation) or [scopes](super::FusionScope)
+    /// of the operations the plan was first executed with.
+    pub names: Vec<String>,
+}
+
+impl FusionSnapshot {
+    pub(crate) fn new<'a, O>(
+        streams: impl Iterator<Item = (StreamId, Option<&'a StreamInfo>, &'a [OperationIr])>,
+        store: &ExecutionPlanStore<O>,
+        options: SnapshotOptions,
+    ) -> Self {
+        let mut streams = streams
+            .map(|(stream, info, operations)| {
+                let start = operations
+                    .len()
+                    .saturating_sub(options.max_queued_operations);
+
+                StreamSnapshot {
+                    stream,
+                    info: info.cloned(),
+                    num_queued: operations.len(),
+                    operations: operations[start..].to_vec(),
+                }
+            })
+            .collect::<Vec<_>>();
+        streams.sort_by_key(|stream| stream.stream);
+
+        let mut plans = store
+            .iter()
+            .map(|(id, plan)| PlanSnapshot {
+                plan: id,
+                num_operations: plan.operations.len(),
+                num_executions: store.fired(id).iter().sum(),
+                pinned: store.is_pinned(id),
+                names: metadata_names(store.metadata(id)),
+            })
+            .collect::<Vec<_>>();
+        plans.sort_by_key(|plan| plan.plan);
+
+        Self {
+            streams,
+            plans,
+            verbosity: options.verbosity,
+        }
+    }
+}
+
+impl Display for FusionSnapshot {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Snapshot ====\n")?;
+
+        for stream in self.streams.iter() {
+            match &stream.info {
+                Some(info) => f.write_fmt(format_args!(" - {info}"))?,
+                None => f.write_fmt(format_args!(" - {}", stream.stream))?,
+            }
+            f.write_fmt(format_args!(
+                " => queued: {} (showing {})\n",
+                stream.num_queued,
+                stream.operations.len()
+            ))?;
+
+            for operation in stream.operations.iter() {
+                let operation = OperationDisplay {
+                    operation,
+                    verbosity: self.verbosity,
+                };
+                f.write_fmt(format_args!("  - {operation}\n"))?;
+            }
+        }
+
+        for plan in self.plans.iter() {
+            f.write_fmt(format_args!(
+                " - Plan {} ({} operations{}) => executions: {}\n",
+                plan.plan,
+                plan.num_operations,
+                if plan.pinned { ", pinned" } else { "" },
+                plan.num_executions
+            ))?;
+            if !plan.names.is_empty() {
+                f.write_fmt(format_args!("  - from {}\n", plan.names.join(", ")))?;
+            }
+        }
+
+        f.write_str("=========================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::TensorStatus;
+
+    use super::*;
+    use crate::test_utils::{exp, tensor_with_shape};
+
+    #[test]
+    fn should_only_copy_the_most_recent_operations() {
+        let operations = (0..4)
+            .map(|id| {
+                exp(
+                    tensor_with_shape(id, vec![32, 32], TensorStatus::ReadOnly),
+                    tensor_with_shape(id + 100, vec![32, 32], TensorStatus::NotInit),
+                )
+            })
+            .collect::<Vec<_>>();
+        let stream = StreamId { value: 1 };
+        let store = ExecutionPlanStore::<()>::new();
+
+        let snapshot = FusionSnapshot::new(
+            [(stream, None, operations.as_slice())].into_iter(),
+            &store,
+            SnapshotOptions {
+                max_queued_operations: 3,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(snapshot.streams.len(), 1);
+        assert_eq!(snapshot.streams[0].num_queued, 4);
+        assert_eq!(snapshot.streams[0].operations, operations[1..]);
+        assert!(snapshot.plans.is_empty());
+    }
+}
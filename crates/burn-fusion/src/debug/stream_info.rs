@@ -0,0 +1,80 @@
+use core::fmt::Display;
+use std::{
+    thread::ThreadId,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::stream::{StreamId, current_stream_name};
+
+/// Where and when a [stream](StreamId) was first used on a device.
+///
+/// Stream ids are derived from the thread registering the operations, so they are opaque on
+/// their own. The name and id of the thread make multi-threaded debug output readable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamInfo {
+    /// The id of the stream.
+    pub stream: StreamId,
+    /// The name of the stream, when it was [created explicitly](crate::stream::FusionStream).
+    pub name: Option<String>,
+    /// The name of the thread that registered the first operation of the stream, if it has one.
+    pub thread_name: Option<String>,
+    /// The id of the thread that registered the first operation of the stream.
+    pub thread_id: ThreadId,
+    /// When the first operation of the stream was registered.
+    pub created_at: SystemTime,
+}
+
+impl StreamInfo {
+    /// The info of a stream used by the current thread.
+    pub(crate) fn current(stream: StreamId) -> Self {
+        let thread = std::thread::current();
+
+        Self {
+            stream,
+            name: current_stream_name(),
+            thread_name: thread.name().map(String::from),
+            thread_id: thread.id(),
+            created_at: SystemTime::now(),
+        }
+    }
+}
+
+impl Display for StreamInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let created_at = self
+            .created_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        match &self.name {
+            Some(name) => f.write_fmt(format_args!("{} ({name})", self.stream))?,
+            None => f.write_fmt(format_args!("{}", self.stream))?,
+        }
+        f.write_fmt(format_args!(
+            " [thread {} {:?}, created at {}.{:03}s]",
+            self.thread_name.as_deref().unwrap_or("<unnamed>"),
+            self.thread_id,
+            created_at.as_secs(),
+            created_at.subsec_millis()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_record_the_thread_of_the_stream() {
+        let info = std::thread::Builder::new()
+            .name("worker-1".into())
+            .spawn(|| StreamInfo::current(StreamId::current()))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert_eq!(info.thread_name.as_deref(), Some("worker-1"));
+        assert_ne!(info.thread_id, std::thread::current().id());
+        assert!(info.to_string().contains("thread worker-1"));
+    }
+}
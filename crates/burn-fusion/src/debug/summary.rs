@@ -0,0 +1,191 @@
+use core::fmt::Display;
+use std::time::Duration;
+
+use crate::stream::{ExecutionPlanStoreStats, OutputPoolStats, StreamId};
+
+/// A cheap snapshot of the state of the fusion server of a device.
+///
+/// Unlike the other reports, it doesn't hold any per-plan or per-operation data, so it can be
+/// [collected periodically](crate::Fusion::subscribe_summaries) by dashboards and metrics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FusionDebugSummary {
+    /// The number of streams with queued operations.
+    pub num_streams: usize,
+    /// The number of operations queued across all streams, waiting to be executed.
+    pub num_queued_operations: usize,
+    /// The number of executions of a plan found in the store without exploration.
+    pub num_cache_hits: u64,
+    /// The number of explorations.
+    pub num_cache_misses: u64,
+    /// The statistics of the execution plan store.
+    pub plans: ExecutionPlanStoreStats,
+    /// The statistics of the output pool.
+    pub output_pool: OutputPoolStats,
+    /// The statistics of every stream used on the device, sorted by id.
+    pub streams: Vec<StreamStats>,
+}
+
+/// How a stream behaved since it was first used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    /// The id of the stream.
+    pub stream: StreamId,
+    /// The number of operations registered on the stream.
+    pub num_registered: u64,
+    /// The number of operations of the stream executed, whether lazily or by a drain.
+    pub num_executed: u64,
+    /// The number of times the stream was drained, fully or partially.
+    pub num_drains: u64,
+    /// The number of operations queued on the stream when it was drained, summed over all
+    /// drains.
+    pub num_drained_queued: u64,
+    /// The number of operations removed from the queue when the stream was drained, their
+    /// results being dropped without being read.
+    pub num_eliminated: u64,
+    /// The number of operations computed from constants when the stream was drained, instead
+    /// of being executed on the device.
+    pub num_folded: u64,
+    /// The number of operations merged with a previous operation computing the same results
+    /// when the stream was drained.
+    pub num_merged: u64,
+    /// The number of operations returning one of their inputs replaced with the input when the
+    /// stream was drained, e.g. `x * 1`.
+    pub num_simplified: u64,
+    /// The number of chains of layout operations restoring the layout of their input replaced
+    /// with the input when the stream was drained, e.g. a permutation followed by its inverse.
+    pub num_cancelled: u64,
+    /// The number of operation patterns rewritten by the [user-defined rules](crate::stream::RewriteRule)
+    /// of the device when the stream was drained.
+    pub num_custom: u64,
+    /// The time spent exploring the operations of the stream for new plans.
+    pub search_time: Duration,
+}
+
+impl StreamStats {
+    pub(crate) fn new(stream: StreamId) -> Self {
+        Self {
+            stream,
+            num_registered: 0,
+            num_executed: 0,
+            num_drains: 0,
+            num_drained_queued: 0,
+            num_eliminated: 0,
+            num_folded: 0,
+            num_merged: 0,
+            num_simplified: 0,
+            num_cancelled: 0,
+            num_custom: 0,
+            search_time: Duration::ZERO,
+        }
+    }
+
+    /// The mean number of operations queued on the stream when it was drained.
+    pub fn mean_queue_depth_at_drain(&self) -> f64 {
+        match self.num_drains {
+            0 => 0.0,
+            num_drains => self.num_drained_queued as f64 / num_drains as f64,
+        }
+    }
+}
+
+impl FusionDebugSummary {
+    /// The ratio of executions done without exploration, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.num_cache_hits + self.num_cache_misses;
+
+        match total {
+            0 => 0.0,
+            _ => self.num_cache_hits as f64 / total as f64,
+        }
+    }
+}
+
+impl Display for FusionDebugSummary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Summary ====\n")?;
+        f.write_fmt(format_args!(
+            " - Queued operations: {} (in {} streams)\n",
+            self.num_queued_operations, self.num_streams
+        ))?;
+        f.write_fmt(format_args!(
+            " - Plans: {} stored, {} created, {} deduplicated, {} evicted, {} pinned\n",
+            self.plans.num_plans,
+            self.plans.num_created,
+            self.plans.num_deduplicated,
+            self.plans.num_evicted,
+            self.plans.num_pinned
+        ))?;
+        f.write_fmt(format_args!(
+            " - Cache hits: {} Misses: {} (hit rate: {:.2}%)\n",
+            self.num_cache_hits,
+            self.num_cache_misses,
+            self.hit_rate() * 100.0
+        ))?;
+        f.write_fmt(format_args!(
+            " - Output pool: {} buffers, {} hits, {} misses\n",
+            self.output_pool.num_buffers, self.output_pool.hits, self.output_pool.misses
+        ))?;
+
+        for stream in self.streams.iter() {
+            f.write_fmt(format_args!(
+                " - {} => registered: {} executed: {} drains: {} (mean depth: {:.1}) search: {:?}\n",
+                stream.stream,
+                stream.num_registered,
+                stream.num_executed,
+                stream.num_drains,
+                stream.mean_queue_depth_at_drain(),
+                stream.search_time
+            ))?;
+
+            let num_rewritten = stream.num_eliminated
+                + stream.num_folded
+                + stream.num_merged
+                + stream.num_simplified
+                + stream.num_cancelled
+                + stream.num_custom;
+
+            if num_rewritten > 0 {
+                f.write_fmt(format_args!(
+                    "   rewrites => eliminated: {} folded: {} merged: {} simplified: {} \
+                     cancelled: {} custom: {}\n",
+                    stream.num_eliminated,
+                    stream.num_folded,
+                    stream.num_merged,
+                    stream.num_simplified,
+                    stream.num_cancelled,
+                    stream.num_custom
+                ))?;
+            }
+        }
+        f.write_str("========================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_write_stream_stats() {
+        let summary = FusionDebugSummary {
+            streams: vec![StreamStats {
+                num_registered: 12,
+                num_executed: 10,
+                num_drains: 2,
+                num_drained_queued: 7,
+                num_merged: 3,
+                ..StreamStats::new(StreamId { value: 4 })
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(summary.streams[0].mean_queue_depth_at_drain(), 3.5);
+        assert!(summary.to_string().contains(
+            " - StreamId(4) => registered: 12 executed: 10 drains: 2 (mean depth: 3.5) search: 0ns\n"
+        ));
+        assert!(summary.to_string().contains(
+            "   rewrites => eliminated: 0 folded: 0 merged: 3 simplified: 0 cancelled: 0 \
+             custom: 0\n"
+        ));
+    }
+}
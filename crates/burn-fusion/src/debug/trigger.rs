@@ -0,0 +1,188 @@
+use core::fmt::Display;
+
+use crate::stream::{
+    ExecutionPlanId,
+    store::{ExecutionPlanStore, ExecutionTrigger},
+};
+
+use super::operation_kind;
+
+/// Explains when each [execution plan](ExecutionPlanId) of a device is executed, along with the
+/// number of times each of its triggers fired.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TriggerReport {
+    /// The plans, ordered by id.
+    pub plans: Vec<PlanTriggers>,
+}
+
+/// The triggers of an execution plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanTriggers {
+    /// The id of the plan.
+    pub plan: ExecutionPlanId,
+    /// The number of operations executed by the plan.
+    pub num_operations: usize,
+    /// The triggers of the plan, in the order they were added.
+    pub triggers: Vec<TriggerStats>,
+}
+
+/// A trigger along with the number of times it caused the execution of its plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerStats {
+    /// The condition of the trigger.
+    pub condition: TriggerCondition,
+    /// The number of times the trigger fired.
+    pub num_fired: u64,
+}
+
+/// The condition under which a plan is executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriggerCondition {
+    /// The plan is executed when its operations are followed by operations of the given kinds.
+    ///
+    /// Those operations couldn't be fused with the plan, so there is no point waiting for more.
+    OnOperations(Vec<String>),
+    /// The plan is executed when the stream is synced, e.g. when a tensor is read.
+    OnSync,
+    /// The plan is executed as soon as its operations are registered, since no other operation
+    /// can be fused with them.
+    Always,
+    /// The plan is executed once the given number of operations are queued on the stream.
+    OnQueueLength(usize),
+    /// The plan is executed once a [user-defined condition](crate::stream::CustomTrigger)
+    /// holds, described by its debug output.
+    Custom(String),
+}
+
+impl TriggerReport {
+    pub(crate) fn new<O>(store: &ExecutionPlanStore<O>) -> Self {
+        let mut plans = store
+            .iter()
+            .map(|(id, plan)| PlanTriggers {
+                plan: id,
+                num_operations: plan.operations.len(),
+                triggers: plan
+                    .triggers
+                    .iter()
+                    .zip(store.fired(id))
+                    .map(|(trigger, num_fired)| TriggerStats {
+                        condition: TriggerCondition::new(trigger),
+                        num_fired: *num_fired,
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        plans.sort_by_key(|plan| plan.plan);
+
+        Self { plans }
+    }
+}
+
+impl TriggerCondition {
+    fn new(trigger: &ExecutionTrigger) -> Self {
+        match trigger {
+            ExecutionTrigger::OnOperations(operations) => {
+                Self::OnOperations(operations.iter().map(operation_kind).collect())
+            }
+            ExecutionTrigger::OnSync => Self::OnSync,
+            ExecutionTrigger::Always => Self::Always,
+            ExecutionTrigger::OnQueueLength(length) => Self::OnQueueLength(*length),
+            ExecutionTrigger::Custom(trigger) => Self::Custom(format!("{:?}", trigger.0)),
+        }
+    }
+}
+
+impl Display for TriggerCondition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::OnOperations(kinds) => f.write_fmt(format_args!(
+                "executes when followed by ops [{}]",
+                kinds.join(", ")
+            )),
+            Self::OnSync => f.write_str("executes on sync"),
+            Self::Always => f.write_str("executes as soon as its operations are registered"),
+            Self::OnQueueLength(length) => {
+                f.write_fmt(format_args!("executes once {length} operations are queued"))
+            }
+            Self::Custom(condition) => f.write_fmt(format_args!("executes when {condition}")),
+        }
+    }
+}
+
+impl Display for TriggerReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Trigger Report ====\n")?;
+
+        for plan in self.plans.iter() {
+            f.write_fmt(format_args!(
+                " - Plan {} ({} operations)\n",
+                plan.plan, plan.num_operations
+            ))?;
+
+            for trigger in plan.triggers.iter() {
+                f.write_fmt(format_args!(
+                    "  - {} => fired: {}\n",
+                    trigger.condition, trigger.num_fired
+                ))?;
+            }
+        }
+
+        f.write_str("===============================\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ndarray::NdArrayDevice;
+    use burn_tensor::{TensorData, ops::FloatTensorOps};
+
+    use super::*;
+    use crate::{
+        Fusion,
+        client::FusionClient,
+        test_utils::{TestBackend, TestClient, float_data, float_tensor},
+    };
+
+    #[test]
+    fn should_count_fired_triggers_of_executed_plans() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        for _ in 0..2 {
+            let tensor = float_tensor(&client, TensorData::from([1.0f32, 2.0]));
+            float_data(Fusion::<TestBackend>::float_exp(tensor));
+        }
+
+        let report = client.trigger_report();
+
+        assert_eq!(
+            report.plans,
+            vec![PlanTriggers {
+                plan: 0,
+                num_operations: 1,
+                triggers: vec![TriggerStats {
+                    condition: TriggerCondition::Always,
+                    num_fired: 2,
+                }],
+            }]
+        );
+        assert!(
+            report
+                .to_string()
+                .contains("executes as soon as its operations are registered => fired: 2")
+        );
+    }
+
+    #[test]
+    fn should_explain_trigger_conditions() {
+        let condition = TriggerCondition::OnOperations(vec!["MulScalar".into(), "Tanh".into()]);
+
+        assert_eq!(
+            condition.to_string(),
+            "executes when followed by ops [MulScalar, Tanh]"
+        );
+        assert_eq!(TriggerCondition::OnSync.to_string(), "executes on sync");
+        assert_eq!(
+            TriggerCondition::OnQueueLength(4).to_string(),
+            "executes once 4 operations are queued"
+        );
+    }
+}
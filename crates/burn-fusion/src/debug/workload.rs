@@ -0,0 +1,332 @@
+use burn_common::rand::{Rng, SeedableRng, StdRng};
+use burn_tensor::{Distribution, Tensor, TensorData, activation, backend::Backend};
+
+/// The relative weights of each category of [operations](SyntheticOp) in a
+/// [synthetic workload](SyntheticWorkload).
+///
+/// A weight of zero disables the category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationMix {
+    /// Element-wise unary operations.
+    pub unary: u32,
+    /// Element-wise operations with a scalar.
+    pub scalar: u32,
+    /// Element-wise operations between two branches.
+    pub binary: u32,
+    /// Reductions broadcasted back to the original shape.
+    pub reduction: u32,
+    /// Matrix multiplications with a random weight.
+    pub matmul: u32,
+    /// Layout changes.
+    pub layout: u32,
+}
+
+impl Default for OperationMix {
+    fn default() -> Self {
+        Self {
+            unary: 4,
+            scalar: 3,
+            binary: 3,
+            reduction: 1,
+            matmul: 1,
+            layout: 1,
+        }
+    }
+}
+
+/// Configuration of a [synthetic workload](SyntheticWorkload).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticWorkloadConfig {
+    /// The seed used to generate the models and their data.
+    pub seed: u64,
+    /// The number of layers of each model.
+    pub depth: usize,
+    /// The number of branches of each model, executed side by side.
+    pub branching: usize,
+    /// The relative weights of each category of operations.
+    pub mix: OperationMix,
+    /// The shape of the tensors of each model before variability is applied.
+    pub shape: [usize; 2],
+    /// How much each dimension can vary between models, as a ratio of the base shape.
+    ///
+    /// Every distinct shape creates distinct execution plans, so higher variability means more
+    /// plans.
+    pub shape_variability: f32,
+}
+
+impl Default for SyntheticWorkloadConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            depth: 8,
+            branching: 2,
+            mix: OperationMix::default(),
+            shape: [32, 32],
+            shape_variability: 0.5,
+        }
+    }
+}
+
+/// An operation applied to a branch of a [synthetic model](SyntheticModel).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyntheticOp {
+    /// Hyperbolic tangent.
+    Tanh,
+    /// Sine.
+    Sin,
+    /// Sigmoid.
+    Sigmoid,
+    /// Addition of a scalar.
+    AddScalar(f32),
+    /// Multiplication by a scalar.
+    MulScalar(f32),
+    /// Addition of the branch at the given index.
+    Add(usize),
+    /// Multiplication by the branch at the given index.
+    Mul(usize),
+    /// Subtraction of the mean of each row.
+    CenterRows,
+    /// Multiplication by a random square matrix.
+    Matmul,
+    /// Reshape to the transposed shape followed by a transposition.
+    Transpose,
+}
+
+/// A model generated by a [synthetic workload](SyntheticWorkload).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticModel {
+    /// The shape of every tensor in the model.
+    pub shape: [usize; 2],
+    /// The operations of each layer, one per branch.
+    pub layers: Vec<Vec<SyntheticOp>>,
+    /// The seed used to generate the data of the model.
+    pub seed: u64,
+}
+
+/// Generates models made of random operations from a seed, used to stress the fusion backend with
+/// many distinct execution plans.
+///
+/// The same configuration always generates the same models.
+pub struct SyntheticWorkload {
+    config: SyntheticWorkloadConfig,
+    rng: StdRng,
+}
+
+impl SyntheticWorkload {
+    /// Create a new workload.
+    pub fn new(config: SyntheticWorkloadConfig) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+        }
+    }
+
+    /// Generate the next model.
+    pub fn next_model(&mut self) -> SyntheticModel {
+        let shape = self.config.shape;
+        let shape = shape.map(|dim| self.vary(dim));
+        let branching = self.config.branching.max(1);
+        let layers = (0..self.config.depth)
+            .map(|_| {
+                (0..branching)
+                    .map(|_| self.next_operation(branching))
+                    .collect()
+            })
+            .collect();
+
+        SyntheticModel {
+            shape,
+            layers,
+            seed: self.rng.random(),
+        }
+    }
+
+    /// Generate and execute `num_models` models on the given device, reading each output.
+    ///
+    /// Returns the number of tensor operations executed.
+    pub fn run<B: Backend>(&mut self, num_models: usize, device: &B::Device) -> usize {
+        let mut num_operations = 0;
+
+        for _ in 0..num_models {
+            let model = self.next_model();
+            num_operations += model.num_operations();
+            model.forward::<B>(device).into_data();
+        }
+
+        num_operations
+    }
+
+    fn vary(&mut self, dim: usize) -> usize {
+        let delta = (dim as f32 * self.config.shape_variability) as usize;
+
+        match delta {
+            0 => dim,
+            _ => self
+                .rng
+                .random_range(dim.saturating_sub(delta).max(1)..=dim + delta),
+        }
+    }
+
+    fn next_operation(&mut self, branching: usize) -> SyntheticOp {
+        let mix = self.config.mix;
+        let weights = [
+            mix.unary,
+            mix.scalar,
+            mix.binary,
+            mix.reduction,
+            mix.matmul,
+            mix.layout,
+        ];
+        let total = weights.iter().sum::<u32>();
+
+        if total == 0 {
+            return SyntheticOp::Tanh;
+        }
+
+        let mut pick = self.rng.random_range(0..total);
+        let category = weights
+            .iter()
+            .position(|weight| match pick < *weight {
+                true => true,
+                false => {
+                    pick -= weight;
+                    false
+                }
+            })
+            .unwrap_or_default();
+
+        match category {
+            0 => match self.rng.random_range(0..3) {
+                0 => SyntheticOp::Tanh,
+                1 => SyntheticOp::Sin,
+                _ => SyntheticOp::Sigmoid,
+            },
+            1 => match self.rng.random_bool(0.5) {
+                true => SyntheticOp::AddScalar(self.rng.random_range(-1.0..1.0)),
+                false => SyntheticOp::MulScalar(self.rng.random_range(0.5..1.5)),
+            },
+            2 => {
+                let other = self.rng.random_range(0..branching);
+                match self.rng.random_bool(0.5) {
+                    true => SyntheticOp::Add(other),
+                    false => SyntheticOp::Mul(other),
+                }
+            }
+            3 => SyntheticOp::CenterRows,
+            4 => SyntheticOp::Matmul,
+            _ => SyntheticOp::Transpose,
+        }
+    }
+}
+
+impl SyntheticModel {
+    /// The number of operations of the model.
+    pub fn num_operations(&self) -> usize {
+        self.layers.iter().map(|layer| layer.len()).sum()
+    }
+
+    /// Execute the model on the given device, returning the sum of all branches.
+    pub fn forward<B: Backend>(&self, device: &B::Device) -> Tensor<B, 2> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let branching = self.layers.first().map(|layer| layer.len()).unwrap_or(1);
+        let mut branches = (0..branching)
+            .map(|_| self.random::<B>(self.shape, &mut rng, device))
+            .collect::<Vec<_>>();
+
+        for layer in self.layers.iter() {
+            let inputs = branches.clone();
+
+            for (branch, operation) in branches.iter_mut().zip(layer) {
+                let tensor = branch.clone();
+
+                *branch = match *operation {
+                    SyntheticOp::Tanh => tensor.tanh(),
+                    SyntheticOp::Sin => tensor.sin(),
+                    SyntheticOp::Sigmoid => activation::sigmoid(tensor),
+                    SyntheticOp::AddScalar(value) => tensor.add_scalar(value),
+                    SyntheticOp::MulScalar(value) => tensor.mul_scalar(value),
+                    SyntheticOp::Add(other) => tensor.add(inputs[other].clone()),
+                    SyntheticOp::Mul(other) => tensor.mul(inputs[other].clone()),
+                    SyntheticOp::CenterRows => tensor.clone().sub(tensor.mean_dim(1)),
+                    SyntheticOp::Matmul => {
+                        let [_, cols] = self.shape;
+                        let weights = self.random::<B>([cols, cols], &mut rng, device);
+                        tensor.matmul(weights.div_scalar(cols as f32))
+                    }
+                    SyntheticOp::Transpose => {
+                        let [rows, cols] = self.shape;
+                        tensor.reshape([cols, rows]).swap_dims(0, 1)
+                    }
+                };
+            }
+        }
+
+        branches
+            .into_iter()
+            .reduce(|acc, branch| acc.add(branch))
+            .expect("At least one branch")
+    }
+
+    fn random<B: Backend>(
+        &self,
+        shape: [usize; 2],
+        rng: &mut StdRng,
+        device: &B::Device,
+    ) -> Tensor<B, 2> {
+        let data = TensorData::random::<f32, _, _>(shape, Distribution::Default, rng);
+        Tensor::from_data(data, device)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_generate_the_same_models_from_the_same_seed() {
+        let config = SyntheticWorkloadConfig {
+            seed: 42,
+            ..Default::default()
+        };
+        let mut workload_1 = SyntheticWorkload::new(config.clone());
+        let mut workload_2 = SyntheticWorkload::new(config);
+
+        for _ in 0..8 {
+            assert_eq!(workload_1.next_model(), workload_2.next_model());
+        }
+    }
+
+    #[test]
+    fn should_respect_the_configuration() {
+        let mut workload = SyntheticWorkload::new(SyntheticWorkloadConfig {
+            depth: 4,
+            branching: 3,
+            mix: OperationMix {
+                unary: 0,
+                scalar: 0,
+                binary: 1,
+                reduction: 0,
+                matmul: 0,
+                layout: 0,
+            },
+            shape: [16, 8],
+            shape_variability: 0.5,
+            ..Default::default()
+        });
+
+        for _ in 0..8 {
+            let model = workload.next_model();
+
+            assert_eq!(model.num_operations(), 12);
+            assert!((8..=24).contains(&model.shape[0]));
+            assert!((4..=12).contains(&model.shape[1]));
+            assert!(
+                model
+                    .layers
+                    .iter()
+                    .flatten()
+                    .all(|op| matches!(op, SyntheticOp::Add(i) | SyntheticOp::Mul(i) if *i < 3))
+            );
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use core::fmt::Display;
+
+use burn_ir::TensorId;
+
+use crate::stream::ExecutionPlanId;
+
+/// The things that can go wrong in the fusion subsystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FusionError {
+    /// The operation isn't supported by the fusion backend.
+    UnsupportedOperation {
+        /// The kind of operation.
+        operation: String,
+        /// Why the operation isn't supported.
+        reason: String,
+    },
+    /// A tensor or a handle belongs to another device than the one it's used on.
+    DeviceMismatch {
+        /// The device the tensor is used on.
+        expected: String,
+        /// The device the tensor belongs to.
+        actual: String,
+    },
+    /// The operation is inconsistent, e.g. its tensors have incompatible shapes.
+    InvalidOperation {
+        /// Why the operation is inconsistent.
+        reason: String,
+    },
+    /// The tensor isn't tracked by the fusion server of its device, e.g. because it was already
+    /// consumed or dropped.
+    TensorNotFound {
+        /// The id of the tensor.
+        tensor: TensorId,
+    },
+    /// The tensor is tracked, but its buffer was never created.
+    TensorNotInitialized {
+        /// The id of the tensor.
+        tensor: TensorId,
+    },
+    /// The shape of the output of an operation couldn't be inferred from its inputs.
+    ShapeInference {
+        /// The kind of operation.
+        operation: String,
+        /// Why the shape couldn't be inferred.
+        reason: String,
+    },
+    /// An execution plan couldn't be created or executed.
+    PlanExecution {
+        /// The id of the plan, when it was already stored.
+        plan: Option<ExecutionPlanId>,
+        /// Why the plan couldn't be created or executed.
+        reason: String,
+    },
+    /// The execution plan isn't in the store, either because it was evicted or because it
+    /// never existed.
+    PlanNotFound {
+        /// The id of the plan.
+        plan: ExecutionPlanId,
+    },
+    /// The store can't hold the pinned execution plans within the requested capacity.
+    StoreCapacityExceeded {
+        /// The requested capacity.
+        capacity: usize,
+        /// The number of pinned plans, which can't be evicted.
+        num_pinned: usize,
+    },
+    /// The execution plans couldn't be written to or read from a file.
+    PlanSerialization {
+        /// Why the plans couldn't be written or read.
+        reason: String,
+    },
+    /// The debug bundle of a device couldn't be written.
+    DebugExport {
+        /// Why the bundle couldn't be written.
+        reason: String,
+    },
+    /// A [captured graph](crate::stream::CapturedGraph) couldn't be captured or replayed.
+    GraphReplay {
+        /// Why the graph couldn't be captured or replayed.
+        reason: String,
+    },
+}
+
+impl FusionError {
+    #[cfg(feature = "plan-export")]
+    pub(crate) fn plan_serialization(err: impl Display) -> Self {
+        Self::PlanSerialization {
+            reason: err.to_string(),
+        }
+    }
+
+    pub(crate) fn invalid_operation(err: impl Display) -> Self {
+        Self::InvalidOperation {
+            reason: err.to_string(),
+        }
+    }
+
+    #[cfg(feature = "plan-export")]
+    pub(crate) fn debug_export(err: impl Display) -> Self {
+        Self::DebugExport {
+            reason: err.to_string(),
+        }
+    }
+}
+
+impl Display for FusionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnsupportedOperation { operation, reason } => f.write_fmt(format_args!(
+                "Unsupported operation {operation}: {reason}"
+            )),
+            Self::DeviceMismatch { expected, actual } => f.write_fmt(format_args!(
+                "Device mismatch: expected {expected}, got {actual}"
+            )),
+            Self::InvalidOperation { reason } => {
+                f.write_fmt(format_args!("Invalid operation: {reason}"))
+            }
+            Self::TensorNotFound { tensor } => f.write_fmt(format_args!(
+                "Tensor {tensor:?} isn't tracked by the fusion server"
+            )),
+            Self::TensorNotInitialized { tensor } => {
+                f.write_fmt(format_args!("Tensor {tensor:?} isn't initialized"))
+            }
+            Self::ShapeInference { operation, reason } => f.write_fmt(format_args!(
+                "Can't infer the output shape of {operation}: {reason}"
+            )),
+            Self::PlanExecution {
+                plan: Some(plan),
+                reason,
+            } => f.write_fmt(format_args!("Execution plan {plan} failed: {reason}")),
+            Self::PlanExecution { plan: None, reason } => {
+                f.write_fmt(format_args!("Execution plan failed: {reason}"))
+            }
+            Self::PlanNotFound { plan } => {
+                f.write_fmt(format_args!("Execution plan {plan} isn't in the store"))
+            }
+            Self::StoreCapacityExceeded {
+                capacity,
+                num_pinned,
+            } => f.write_fmt(format_args!(
+                "The execution plan store can't fit {num_pinned} pinned plans within a capacity of {capacity}"
+            )),
+            Self::PlanSerialization { reason } => {
+                f.write_fmt(format_args!("Can't serialize the execution plans: {reason}"))
+            }
+            Self::DebugExport { reason } => {
+                f.write_fmt(format_args!("Can't write the debug bundle: {reason}"))
+            }
+            Self::GraphReplay { reason } => {
+                f.write_fmt(format_args!("Can't replay the captured graph: {reason}"))
+            }
+        }
+    }
+}
+
+impl core::error::Error for FusionError {}
@@ -1,16 +1,40 @@
 use burn_ir::BackendIr;
 use burn_tensor::backend::{DeviceId, DeviceOps};
 
-use crate::{Client, FusionDevice, FusionRuntime, client::FusionClient};
+use crate::{
+    Client, FusionDevice, FusionRuntime, SessionId, bound_session, client::FusionClient,
+    stream::StreamId,
+};
 
-use std::{any::Any, collections::HashMap, ops::DerefMut};
+use std::{any::Any, collections::HashMap, ops::DerefMut, sync::Arc};
 
 /// Type alias for [representation backend handle](burn_ir::BackendIr::Handle).
 pub type Handle<B> = <B as BackendIr>::Handle;
-type Key = (core::any::TypeId, DeviceId);
+type Key = (core::any::TypeId, DeviceId, SessionId);
 
 pub(crate) struct FusionClientLocator {
-    clients: spin::Mutex<Option<HashMap<Key, Box<dyn core::any::Any + Send>>>>,
+    clients: spin::Mutex<Option<HashMap<Key, LocatedClient>>>,
+}
+
+struct LocatedClient {
+    client: Box<dyn Any + Send>,
+    /// Drains a stream without knowing the runtime of the client.
+    drain_stream: Arc<dyn Fn(StreamId) + Send + Sync>,
+    /// Drains every stream without knowing the runtime of the client.
+    drain: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl LocatedClient {
+    fn new<R: FusionRuntime + 'static>(client: Client<R>) -> Self {
+        let drain_stream = client.clone();
+        let drain = client.clone();
+
+        Self {
+            client: Box::new(client),
+            drain_stream: Arc::new(move |stream| drain_stream.drain_stream(stream)),
+            drain: Arc::new(move || drain.drain()),
+        }
+    }
 }
 
 impl FusionClientLocator {
@@ -21,40 +45,94 @@ impl FusionClientLocator {
         }
     }
 
-    /// Get the fusion client for the given device.
+    /// Get the fusion client for the given device in the [current session](crate::current_session).
     ///
     /// Provide the init function to create a new client if it isn't already initialized.
     pub fn client<R: FusionRuntime + 'static>(&self, device: &FusionDevice<R>) -> Client<R> {
+        let session = match bound_session() {
+            Some(session) => session,
+            None => return self.located::<R>(device, SessionId::DEFAULT).0,
+        };
+        let (client, created) = self.located::<R>(device, session.id);
+
+        if created && session.options.share_plans {
+            // Done without the lock of the locator, since exporting the plans locks the server.
+            let shared = self.located::<R>(device, SessionId::DEFAULT).0;
+            client.import_plans_from(&shared);
+        }
+
+        client
+    }
+
+    /// Get the client of the device in the given session, and whether it was just created.
+    fn located<R: FusionRuntime + 'static>(
+        &self,
+        device: &FusionDevice<R>,
+        session: SessionId,
+    ) -> (Client<R>, bool) {
         let device_id = device.id();
-        let client_id = (core::any::TypeId::of::<R>(), device_id);
+        let client_id = (core::any::TypeId::of::<R>(), device_id, session);
         let mut clients = self.clients.lock();
 
         if clients.is_none() {
             let client = Client::<R>::new(device.clone());
-            Self::register_inner::<R>(client_id, client, &mut clients);
+            Self::register_inner::<R>(client_id, client.clone(), &mut clients);
+            return (client, true);
         }
 
         match clients.deref_mut() {
             Some(clients) => match clients.get(&client_id) {
-                Some(client) => {
-                    let client: &Client<R> = client.downcast_ref().unwrap();
-                    client.clone()
+                Some(located) => {
+                    let client: &Client<R> = located.client.downcast_ref().unwrap();
+                    (client.clone(), false)
                 }
                 None => {
                     let client = Client::<R>::new(device.clone());
-                    let any = Box::new(client.clone());
-                    clients.insert(client_id, any);
-                    client
+                    clients.insert(client_id, LocatedClient::new::<R>(client.clone()));
+                    (client, true)
                 }
             },
             _ => unreachable!(),
         }
     }
 
+    /// Forget the clients of the session on every device, draining them.
+    ///
+    /// Tensors created in the session keep their client alive until dropped.
+    pub fn close_session(&self, session: SessionId) {
+        let drains = match self.clients.lock().as_mut() {
+            Some(clients) => clients
+                .extract_if(|key, _| key.2 == session)
+                .map(|(_, located)| located.drain)
+                .collect::<Vec<_>>(),
+            None => return,
+        };
+
+        for drain in drains {
+            drain();
+        }
+    }
+
+    /// Drain the stream on the devices of every client created so far.
+    pub fn drain_stream(&self, stream: StreamId) {
+        // Draining executes operations, which must not hold the lock of the locator.
+        let drains = match self.clients.lock().as_ref() {
+            Some(clients) => clients
+                .values()
+                .map(|located| located.drain_stream.clone())
+                .collect::<Vec<_>>(),
+            None => return,
+        };
+
+        for drain in drains {
+            drain(stream);
+        }
+    }
+
     fn register_inner<R: FusionRuntime + 'static>(
         key: Key,
         client: Client<R>,
-        clients: &mut Option<HashMap<Key, Box<dyn Any + Send>>>,
+        clients: &mut Option<HashMap<Key, LocatedClient>>,
     ) {
         if clients.is_none() {
             *clients = Some(HashMap::new());
@@ -65,7 +143,7 @@ impl FusionClientLocator {
                 panic!("Client already created for device {key:?}");
             }
 
-            clients.insert(key, Box::new(client));
+            clients.insert(key, LocatedClient::new::<R>(client));
         }
     }
 }
@@ -11,6 +11,10 @@ extern crate derive_new;
 
 /// Client module exposing types to communicate with the fusion server.
 pub mod client;
+/// Debug module exposing tools to inspect the fusion server.
+pub mod debug;
+/// Memory module exposing reports about the memory used by the fusion server.
+pub mod memory;
 /// Stream module exposing all tensor operations that can be optimized.
 pub mod stream;
 
@@ -18,13 +22,35 @@ pub mod stream;
 pub(crate) mod search;
 
 mod backend;
+mod benchmark;
+mod config;
+mod custom;
+mod error;
 mod fusion;
+mod multi_device;
 mod ops;
+mod read_cache;
 mod server;
+mod session;
 mod tensor;
+mod transfer;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
 
 pub(crate) use server::*;
 
 pub use backend::*;
+pub use benchmark::*;
+pub use config::*;
+pub use custom::*;
+pub use error::*;
 pub use fusion::*;
+pub use multi_device::*;
+pub use read_cache::*;
+pub use search::{
+    AlwaysFuse, CostModel, DefaultExplorationPolicy, ExplorationPolicy, ExplorationState,
+    FusionEstimate, ThroughputCostModel,
+};
+pub use session::*;
 pub use tensor::*;
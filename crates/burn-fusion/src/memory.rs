@@ -0,0 +1,588 @@
+use core::fmt::Display;
+use std::sync::{
+    Arc, Weak,
+    atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use burn_ir::{Handle, HandleContainer, TensorId};
+use burn_tensor::DType;
+use hashbrown::{HashMap, HashSet};
+use spin::Mutex;
+
+use crate::{
+    FusionRuntime,
+    stream::{ExecutionPlanId, StreamId},
+};
+
+/// Report describing how the live handles of a device are spread in memory.
+///
+/// The report is derived from the size of each live handle, as reported by the
+/// [runtime](FusionRuntime::handle_size), and from its age, measured in the number of tensors
+/// created since the handle's tensor was created.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FragmentationReport {
+    /// The number of live handles.
+    pub num_handles: usize,
+    /// The number of live handles for which the runtime doesn't know the size.
+    pub num_unknown_size: usize,
+    /// The total number of bytes used by live handles.
+    pub bytes_live: u64,
+    /// The total number of bytes reserved on the device, if known by the runtime.
+    pub bytes_reserved: Option<u64>,
+    /// Live handles grouped by power-of-two size classes, sorted by size.
+    pub size_classes: Vec<SizeClass>,
+}
+
+/// Live handles whose size falls in `(max_bytes / 2, max_bytes]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SizeClass {
+    /// The upper bound of the class in bytes.
+    pub max_bytes: u64,
+    /// The number of handles in the class.
+    pub num_handles: usize,
+    /// The total number of bytes used by the handles in the class.
+    pub bytes: u64,
+    /// The mean age of the handles in the class, in number of tensors created since.
+    pub mean_age: u64,
+}
+
+/// The outcome of a [defragmentation](crate::Fusion::defragment) pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DefragmentationReport {
+    /// The fragmentation before the pass.
+    pub before: FragmentationReport,
+    /// The fragmentation after the pass.
+    pub after: FragmentationReport,
+    /// The number of handles moved into fresh allocations.
+    pub num_moved: usize,
+}
+
+impl FragmentationReport {
+    /// Create the report for the given handles.
+    pub(crate) fn new<R: FusionRuntime>(
+        handles: &HandleContainer<R::FusionHandle>,
+        device: &R::FusionDevice,
+    ) -> Self {
+        let num_created = handles.num_tensors_created();
+        let mut report = Self {
+            bytes_reserved: R::memory_reserved(device),
+            ..Default::default()
+        };
+        let mut ages = Vec::<u64>::new();
+
+        for (id, handle) in handles.existing_handles() {
+            report.num_handles += 1;
+
+            let size = match R::handle_size(handle) {
+                Some(size) => size,
+                None => {
+                    report.num_unknown_size += 1;
+                    continue;
+                }
+            };
+            let age = num_created.saturating_sub(id.value());
+            let max_bytes = size.max(1).next_power_of_two();
+
+            let index = match report
+                .size_classes
+                .binary_search_by_key(&max_bytes, |class| class.max_bytes)
+            {
+                Ok(index) => index,
+                Err(index) => {
+                    report.size_classes.insert(
+                        index,
+                        SizeClass {
+                            max_bytes,
+                            ..Default::default()
+                        },
+                    );
+                    ages.insert(index, 0);
+                    index
+                }
+            };
+
+            let class = &mut report.size_classes[index];
+            class.num_handles += 1;
+            class.bytes += size;
+            ages[index] += age;
+            report.bytes_live += size;
+        }
+
+        for (class, age) in report.size_classes.iter_mut().zip(ages) {
+            class.mean_age = age / class.num_handles as u64;
+        }
+
+        report
+    }
+
+    /// The ratio of reserved memory that isn't used by live handles, between 0 and 1.
+    ///
+    /// Returns `None` when the runtime doesn't report the reserved memory.
+    pub fn fragmentation(&self) -> Option<f64> {
+        let reserved = self.bytes_reserved?;
+
+        if reserved == 0 {
+            return Some(0.0);
+        }
+
+        let unused = reserved.saturating_sub(self.bytes_live);
+        Some(unused as f64 / reserved as f64)
+    }
+}
+
+impl Display for FragmentationReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Fragmentation Report ====\n")?;
+        f.write_fmt(format_args!(
+            " - Handles: {} ({} with unknown size)\n",
+            self.num_handles, self.num_unknown_size
+        ))?;
+        f.write_fmt(format_args!(" - Live bytes: {}\n", self.bytes_live))?;
+
+        match (self.bytes_reserved, self.fragmentation()) {
+            (Some(reserved), Some(fragmentation)) => f.write_fmt(format_args!(
+                " - Reserved bytes: {reserved} (fragmentation: {:.2}%)\n",
+                fragmentation * 100.0
+            ))?,
+            _ => f.write_str(" - Reserved bytes: unknown\n")?,
+        }
+
+        for class in self.size_classes.iter() {
+            f.write_fmt(format_args!(
+                "  - <= {} bytes => handles: {} bytes: {} mean age: {}\n",
+                class.max_bytes, class.num_handles, class.bytes, class.mean_age
+            ))?;
+        }
+
+        f.write_str("=====================================\n")
+    }
+}
+
+/// Report describing where the memory of a device goes, from the point of view of the fusion
+/// server.
+///
+/// Sizes are derived from the shape and data type of the tensors when known, otherwise from the
+/// size reported by the [runtime](FusionRuntime::handle_size), so they don't account for the
+/// padding or the reuse of the allocations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryReport {
+    /// The number of live handles.
+    pub num_handles: usize,
+    /// The memory used by the live handles of each data type, the largest first.
+    pub dtypes: Vec<DTypeMemory>,
+    /// The memory that the operations queued on each stream will allocate once executed, sorted
+    /// by stream.
+    pub streams: Vec<StreamMemory>,
+}
+
+/// The memory used by the live handles of a data type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DTypeMemory {
+    /// The data type, `None` for the handles whose tensor metadata isn't known.
+    pub dtype: Option<DType>,
+    /// The number of live handles.
+    pub num_handles: usize,
+    /// The number of bytes used by the handles, excluding the ones of unknown size.
+    pub bytes: u64,
+}
+
+/// The memory that the operations queued on a stream will allocate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamMemory {
+    /// The stream.
+    pub stream: StreamId,
+    /// The number of queued operations.
+    pub num_queued_operations: usize,
+    /// The number of tensors written by the queued operations.
+    pub num_queued_tensors: usize,
+    /// The number of bytes of the tensors written by the queued operations.
+    pub bytes_queued: u64,
+}
+
+impl MemoryReport {
+    /// Create the report for the given handles and queued streams.
+    pub(crate) fn new<R: FusionRuntime>(
+        handles: &HandleContainer<R::FusionHandle>,
+        streams: Vec<StreamMemory>,
+    ) -> Self {
+        let mut dtypes = Vec::<DTypeMemory>::new();
+        let mut num_handles = 0;
+
+        for entry in handles.iter().filter(|entry| entry.is_initialized()) {
+            num_handles += 1;
+
+            let dtype = entry.metadata.map(|metadata| metadata.dtype);
+            let bytes = entry.num_bytes().or_else(|| match entry.handle {
+                Handle::Existing(handle) => R::handle_size(handle),
+                Handle::NotInit => None,
+            });
+            let index = match dtypes.iter().position(|memory| memory.dtype == dtype) {
+                Some(index) => index,
+                None => {
+                    dtypes.push(DTypeMemory {
+                        dtype,
+                        num_handles: 0,
+                        bytes: 0,
+                    });
+                    dtypes.len() - 1
+                }
+            };
+
+            dtypes[index].num_handles += 1;
+            dtypes[index].bytes += bytes.unwrap_or_default();
+        }
+        dtypes.sort_by_key(|memory| core::cmp::Reverse(memory.bytes));
+
+        Self {
+            num_handles,
+            dtypes,
+            streams,
+        }
+    }
+
+    /// The total number of bytes used by the live handles.
+    pub fn bytes_live(&self) -> u64 {
+        self.dtypes.iter().map(|memory| memory.bytes).sum()
+    }
+
+    /// The total number of bytes that the queued operations will allocate.
+    pub fn bytes_queued(&self) -> u64 {
+        self.streams.iter().map(|memory| memory.bytes_queued).sum()
+    }
+}
+
+impl Display for MemoryReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Memory Report ====\n")?;
+        f.write_fmt(format_args!(
+            " - Live handles: {} ({} bytes)\n",
+            self.num_handles,
+            self.bytes_live()
+        ))?;
+
+        for memory in self.dtypes.iter() {
+            let dtype = match memory.dtype {
+                Some(dtype) => format!("{dtype:?}"),
+                None => "Unknown".to_string(),
+            };
+            f.write_fmt(format_args!(
+                "  - {dtype} => handles: {} bytes: {}\n",
+                memory.num_handles, memory.bytes
+            ))?;
+        }
+
+        f.write_fmt(format_args!(" - Queued: {} bytes\n", self.bytes_queued()))?;
+
+        for memory in self.streams.iter() {
+            f.write_fmt(format_args!(
+                "  - {} => operations: {} tensors: {} bytes: {}\n",
+                memory.stream,
+                memory.num_queued_operations,
+                memory.num_queued_tensors,
+                memory.bytes_queued
+            ))?;
+        }
+
+        f.write_str("==============================\n")
+    }
+}
+
+/// A tensor tracked by the fusion server of a device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedTensor {
+    /// The id of the tensor.
+    pub id: TensorId,
+    /// The shape of the tensor, if known.
+    pub shape: Option<Vec<usize>>,
+    /// The data type of the tensor, if known.
+    pub dtype: Option<DType>,
+    /// Whether the handle of the tensor has been created.
+    ///
+    /// Tensors produced by operations still waiting in a stream aren't initialized yet.
+    pub initialized: bool,
+    /// Whether the tensor is [pinned](crate::FusionTensor::pin).
+    pub pinned: bool,
+    /// The approximate number of bytes used by the tensor.
+    ///
+    /// Derived from the shape and data type when known, otherwise from the size reported by the
+    /// [runtime](FusionRuntime::handle_size).
+    pub bytes: Option<u64>,
+}
+
+/// Collect every tensor tracked by the handles, ordered by id.
+pub(crate) fn tracked_tensors<R: FusionRuntime>(
+    handles: &HandleContainer<R::FusionHandle>,
+) -> Vec<TrackedTensor> {
+    let mut tensors = handles
+        .iter()
+        .map(|entry| TrackedTensor {
+            id: entry.id,
+            shape: entry.metadata.map(|metadata| metadata.shape.clone()),
+            dtype: entry.metadata.map(|metadata| metadata.dtype),
+            initialized: entry.is_initialized(),
+            pinned: entry.pinned,
+            bytes: entry.num_bytes().or_else(|| match entry.handle {
+                Handle::Existing(handle) => R::handle_size(handle),
+                Handle::NotInit => None,
+            }),
+        })
+        .collect::<Vec<_>>();
+    tensors.sort_by_key(|tensor| tensor.id.value());
+
+    tensors
+}
+
+/// Handles tracked by the fusion server of a device that no tensor references anymore.
+///
+/// A tensor dropped without registering its drop operation, e.g. because of a bug in an
+/// operation or a tensor leaked across a panic, leaves its handle in the server forever.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrphanReport {
+    /// The orphaned tensors, ordered by id.
+    pub tensors: Vec<TrackedTensor>,
+    /// Whether the handles of the orphaned tensors were removed.
+    pub collected: bool,
+}
+
+impl OrphanReport {
+    /// The approximate number of bytes used by the orphaned tensors, when known.
+    pub fn bytes(&self) -> u64 {
+        self.tensors.iter().filter_map(|tensor| tensor.bytes).sum()
+    }
+}
+
+impl Display for OrphanReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Orphaned Handles ====\n")?;
+        f.write_fmt(format_args!(
+            " - Orphans: {} ({} bytes)\n",
+            self.tensors.len(),
+            self.bytes()
+        ))?;
+        f.write_fmt(format_args!(" - Collected: {}\n", self.collected))?;
+
+        for tensor in self.tensors.iter() {
+            f.write_fmt(format_args!(
+                "  - {:?} => shape: {:?} dtype: {:?} bytes: {:?}\n",
+                tensor.id, tensor.shape, tensor.dtype, tensor.bytes
+            ))?;
+        }
+
+        f.write_str("=================================\n")
+    }
+}
+
+/// Signals memory pressure to the fusion server of a device, obtained with
+/// [Fusion::memory_pressure_signal](crate::Fusion::memory_pressure_signal).
+///
+/// Meant for the allocator of the backend, e.g. right before an allocation would fail. Signaling
+/// only raises a flag, so it's safe from within the execution of a fused kernel: the server
+/// relieves the pressure as set by its [policy](crate::MemoryPressurePolicy) the next time an
+/// operation is registered, before queuing more work.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryPressureSignal {
+    raised: Arc<AtomicBool>,
+}
+
+impl MemoryPressureSignal {
+    /// Ask the server to relieve memory pressure.
+    pub fn signal(&self) {
+        self.raised.store(true, Ordering::Release);
+    }
+
+    /// Whether the pressure was signaled and not relieved yet.
+    pub fn is_raised(&self) -> bool {
+        self.raised.load(Ordering::Acquire)
+    }
+
+    /// Lower the flag, returning whether it was raised.
+    pub(crate) fn take(&self) -> bool {
+        self.raised.swap(false, Ordering::AcqRel)
+    }
+}
+
+/// The outcome of [relieving memory pressure](crate::Fusion::relieve_memory_pressure).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryPressureReport {
+    /// The number of bytes reserved on the device before, if known by the runtime.
+    pub bytes_reserved_before: Option<u64>,
+    /// The number of bytes reserved on the device after, if known by the runtime.
+    pub bytes_reserved_after: Option<u64>,
+    /// The number of queued operations executed to release the tensors they free.
+    pub num_drained: usize,
+    /// The orphaned handles collected, empty unless the [policy](crate::MemoryPressurePolicy)
+    /// collects them and the tensor references are tracked.
+    pub orphans: OrphanReport,
+    /// The execution plans evicted, ordered by id.
+    pub evicted_plans: Vec<ExecutionPlanId>,
+}
+
+impl MemoryPressureReport {
+    /// The number of bytes released on the device, when known by the runtime.
+    pub fn bytes_released(&self) -> Option<u64> {
+        match (self.bytes_reserved_before, self.bytes_reserved_after) {
+            (Some(before), Some(after)) => Some(before.saturating_sub(after)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for MemoryPressureReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Memory Pressure ====\n")?;
+        f.write_fmt(format_args!(
+            " - Reserved: {:?} => {:?} bytes\n",
+            self.bytes_reserved_before, self.bytes_reserved_after
+        ))?;
+        f.write_fmt(format_args!(
+            " - Drained operations: {}\n",
+            self.num_drained
+        ))?;
+        f.write_fmt(format_args!(
+            " - Collected orphans: {} ({} bytes)\n",
+            self.orphans.tensors.len(),
+            self.orphans.bytes()
+        ))?;
+        f.write_fmt(format_args!(" - Evicted plans: {:?}\n", self.evicted_plans))?;
+        f.write_str("================================\n")
+    }
+}
+
+/// The reference counts of the tensors created by a client, used to find the handles that no
+/// tensor references anymore.
+///
+/// Nothing is recorded until [enabled](Self::enable), so that tensor creation stays cheap when
+/// orphans aren't being tracked.
+#[derive(Default)]
+pub(crate) struct TensorReferences {
+    enabled: AtomicBool,
+    state: Mutex<ReferencesState>,
+}
+
+#[derive(Default)]
+struct ReferencesState {
+    /// The number of tensors created by the server when the tracking was enabled.
+    ///
+    /// Tensors with a smaller id were created before and can't be checked.
+    since: u64,
+    counts: HashMap<TensorId, Weak<AtomicU32>>,
+}
+
+impl TensorReferences {
+    /// Start recording the tensors created from now on, returning `false` if already enabled.
+    pub(crate) fn enable(&self, num_tensors_created: u64) -> bool {
+        let mut state = self.state.lock();
+
+        if self.enabled.swap(true, Ordering::Relaxed) {
+            return false;
+        }
+
+        state.since = num_tensors_created;
+        true
+    }
+
+    /// Whether the tensors created are being recorded.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Record the reference count of a new tensor.
+    ///
+    /// Must be called while the server that created the tensor is locked, so that the tensor is
+    /// never seen without its reference by a [snapshot](Self::live) taken under the same lock.
+    pub(crate) fn register(&self, id: TensorId, count: &Arc<AtomicU32>) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.state.lock().counts.insert(id, Arc::downgrade(count));
+    }
+
+    /// The first tensor id recorded and the ids of the recorded tensors still referenced,
+    /// forgetting the others.
+    pub(crate) fn live(&self) -> (u64, HashSet<TensorId>) {
+        let mut state = self.state.lock();
+        state.counts.retain(|_, count| count.strong_count() > 0);
+
+        (state.since, state.counts.keys().copied().collect())
+    }
+}
+
+/// Move every live handle that the [runtime](FusionRuntime::reallocate) allows into a fresh
+/// allocation, returning the number of handles moved.
+///
+/// Pinned handles are never moved, since their buffers might be referenced outside of burn.
+pub(crate) fn compact_handles<R: FusionRuntime>(
+    handles: &mut HandleContainer<R::FusionHandle>,
+) -> usize {
+    let ids = handles
+        .existing_handles()
+        .filter(|(id, _)| !handles.is_pinned(id))
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    let mut num_moved = 0;
+
+    // Handles are moved one at a time, each old handle being dropped when replaced, so that
+    // compacting never needs more than one extra allocation.
+    for id in ids {
+        let handle = match handles.handle(&id) {
+            Some(Handle::Existing(handle)) => R::reallocate(handle),
+            _ => None,
+        };
+
+        if let Some(handle) = handle {
+            handles.register_handle(id, handle);
+            num_moved += 1;
+        }
+    }
+
+    num_moved
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::HandleKind;
+    use burn_ndarray::NdArrayDevice;
+    use burn_tensor::{TensorData, ops::FloatTensorOps};
+
+    use super::*;
+    use crate::test_utils::{TestBackend, TestRuntime};
+
+    #[test]
+    fn should_compact_handles_keeping_ids_and_data() {
+        let mut handles = HandleContainer::<HandleKind<TestBackend>>::new();
+        let device = NdArrayDevice::Cpu;
+        let data = [
+            TensorData::from([1.0f32, 2.0, 3.0]),
+            TensorData::from([[4.0f32], [5.0]]),
+        ];
+        let ids = data
+            .iter()
+            .map(|data| {
+                let id = handles.create_tensor_uninit();
+                let tensor = TestBackend::float_from_data(data.clone(), &device);
+                handles.register_handle(id, HandleKind::Float(tensor));
+                id
+            })
+            .collect::<Vec<_>>();
+        handles.pin(ids[1]);
+
+        assert_eq!(compact_handles::<TestRuntime>(&mut handles), 1);
+
+        let mut compacted = handles
+            .existing_handles()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        compacted.sort();
+        assert_eq!(compacted, ids);
+
+        for (id, expected) in ids.iter().zip(data) {
+            let tensor = match handles.handle(id) {
+                Some(Handle::Existing(HandleKind::Float(tensor))) => tensor.clone(),
+                _ => panic!("The handle should still exist"),
+            };
+            let data = burn_common::future::block_on(TestBackend::float_into_data(tensor));
+            data.assert_eq(&expected, true);
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use burn_common::future::block_on;
+use burn_tensor::{
+    DType,
+    ops::{BoolTensorOps, FloatTensorOps, IntTensorOps, QTensorOps},
+};
+
+use crate::{
+    Fusion, FusionBackend, FusionRuntime, FusionTensor, client::FusionClient,
+    stream::is_deterministic,
+};
+
+/// Coordinates the transfers of tensors between the devices of a model-parallel setup.
+///
+/// [Moving](FloatTensorOps::float_to_device) a tensor to another device blocks the calling thread
+/// until its stream is drained and the tensor is copied, so pipelines alternating compute and
+/// transfers serialize on them. Instead, each target device gets a worker thread registering the
+/// transfers on a dedicated [stream](crate::stream::FusionStream), so the calling thread keeps queuing compute
+/// while its tensors move.
+///
+/// When the backend [supports peer transfers](FusionBackend::supports_peer_transfer) between two
+/// devices, tensors are copied directly. Otherwise they are staged through the host, reading the
+/// source tensor without locking the target device, so the target keeps computing until the
+/// data is uploaded.
+pub struct MultiDevice<B: FusionBackend> {
+    workers: spin::Mutex<Vec<TransferWorker<B>>>,
+}
+
+/// A tensor being [transferred](MultiDevice::transfer) to another device.
+pub struct PendingTransfer<R: FusionRuntime> {
+    state: PendingState<R>,
+}
+
+enum PendingState<R: FusionRuntime> {
+    Ready(FusionTensor<R>),
+    Running(Receiver<FusionTensor<R>>),
+}
+
+struct TransferWorker<B: FusionBackend> {
+    device: B::Device,
+    sender: Sender<TransferJob<B::FusionRuntime>>,
+}
+
+struct TransferJob<R: FusionRuntime> {
+    tensor: FusionTensor<R>,
+    sender: Sender<FusionTensor<R>>,
+}
+
+impl<B: FusionBackend> Default for MultiDevice<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: FusionBackend> MultiDevice<B> {
+    /// Create a coordinator without any worker, spawned on the first transfer to each device.
+    pub fn new() -> Self {
+        Self {
+            workers: spin::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Transfer the tensor to the device in the background.
+    ///
+    /// In [deterministic mode](crate::stream::set_deterministic), the tensor is transferred
+    /// before returning.
+    pub fn transfer(
+        &self,
+        tensor: FusionTensor<B::FusionRuntime>,
+        device: &B::Device,
+    ) -> PendingTransfer<B::FusionRuntime> {
+        if is_deterministic() {
+            return PendingTransfer {
+                state: PendingState::Ready(transfer::<B>(tensor, device)),
+            };
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let job = TransferJob { tensor, sender };
+
+        let mut workers = self.workers.lock();
+        let worker = match workers.iter().position(|worker| &worker.device == device) {
+            Some(index) => &workers[index],
+            None => {
+                workers.push(TransferWorker::<B>::spawn(device.clone()));
+                workers.last().unwrap()
+            }
+        };
+        worker
+            .sender
+            .send(job)
+            .expect("The transfer worker should be running");
+
+        PendingTransfer {
+            state: PendingState::Running(receiver),
+        }
+    }
+}
+
+impl<R: FusionRuntime> PendingTransfer<R> {
+    /// Whether the tensor is on the target device, in which case [wait](Self::wait) won't block.
+    pub fn is_ready(&mut self) -> bool {
+        if let PendingState::Running(receiver) = &self.state
+            && let Ok(tensor) = receiver.try_recv()
+        {
+            self.state = PendingState::Ready(tensor);
+        }
+
+        matches!(self.state, PendingState::Ready(_))
+    }
+
+    /// Block until the tensor is on the target device.
+    pub fn wait(self) -> FusionTensor<R> {
+        match self.state {
+            PendingState::Ready(tensor) => tensor,
+            PendingState::Running(receiver) => receiver
+                .recv()
+                .expect("The transfer worker should send the transferred tensor"),
+        }
+    }
+}
+
+impl<B: FusionBackend> TransferWorker<B> {
+    fn spawn(device: B::Device) -> Self {
+        let (sender, receiver) = mpsc::channel::<TransferJob<B::FusionRuntime>>();
+        let target = device.clone();
+
+        std::thread::spawn(move || {
+            let stream = Fusion::<B>::stream(&target, "transfer");
+            let _guard = stream.bind();
+
+            for job in receiver.iter() {
+                let tensor = transfer::<B>(job.tensor, &target);
+                // The caller may have dropped the pending transfer.
+                let _ = job.sender.send(tensor);
+            }
+        });
+
+        Self { device, sender }
+    }
+}
+
+/// Transfer the tensor to the device with the operations matching its data type.
+fn transfer<B: FusionBackend>(
+    tensor: FusionTensor<B::FusionRuntime>,
+    device: &B::Device,
+) -> FusionTensor<B::FusionRuntime> {
+    if tensor.client.device() == device {
+        return tensor;
+    }
+
+    if B::supports_peer_transfer(tensor.client.device(), device) {
+        return match tensor.dtype {
+            DType::Bool => Fusion::<B>::bool_to_device(tensor, device),
+            DType::QFloat(_) => Fusion::<B>::q_to_device(tensor, device),
+            dtype if dtype.is_float() => Fusion::<B>::float_to_device(tensor, device),
+            _ => Fusion::<B>::int_to_device(tensor, device),
+        };
+    }
+
+    match tensor.dtype {
+        DType::Bool => {
+            Fusion::<B>::bool_from_data(block_on(Fusion::<B>::bool_into_data(tensor)), device)
+        }
+        DType::QFloat(_) => {
+            Fusion::<B>::q_from_data(block_on(Fusion::<B>::q_into_data(tensor)), device)
+        }
+        dtype if dtype.is_float() => {
+            Fusion::<B>::float_from_data(block_on(Fusion::<B>::float_into_data(tensor)), device)
+        }
+        _ => Fusion::<B>::int_from_data(block_on(Fusion::<B>::int_into_data(tensor)), device),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use burn_ndarray::NdArrayDevice;
+    use burn_tensor::TensorData;
+
+    use super::*;
+    use crate::{
+        debug::SnapshotOptions,
+        test_utils::{TestBackend, TestClient, float_data, float_tensor, with_lazy_streams},
+    };
+
+    fn num_queued(client: &TestClient) -> usize {
+        client
+            .debug_snapshot(SnapshotOptions::default())
+            .streams
+            .iter()
+            .map(|stream| stream.num_queued)
+            .sum()
+    }
+
+    #[test]
+    fn should_keep_queuing_compute_while_tensors_are_transferred() {
+        let client = TestClient::new(NdArrayDevice::Cpu);
+        let multi = MultiDevice::<TestBackend>::new();
+        let tensor = float_tensor(&client, TensorData::from([0.0f32, 1.0]));
+        let other = float_tensor(&client, TensorData::from([0.0f32]));
+
+        with_lazy_streams(|| {
+            let computed = Fusion::<TestBackend>::float_exp(other);
+            let mut pending = multi.transfer(tensor, &NdArrayDevice::Cpu);
+            let computed = Fusion::<TestBackend>::float_exp(computed);
+
+            let deadline = Instant::now() + Duration::from_secs(30);
+            while !pending.is_ready() {
+                assert!(Instant::now() < deadline, "The transfer never completed");
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            let transferred = pending.wait();
+
+            // The compute queued by this thread isn't executed by the transfer.
+            assert_eq!(num_queued(&client), 2);
+            float_data(transferred).assert_eq(&TensorData::from([0.0f32, 1.0]), true);
+            float_data(computed)
+                .assert_approx_eq::<f32>(&TensorData::from([1.0f32.exp()]), Default::default());
+        });
+    }
+}
@@ -1,7 +1,15 @@
-use crate::{FusionBackend, stream::Operation};
+use crate::{
+    FusionBackend,
+    stream::{Constant, Operation},
+};
 use burn_ir::HandleContainer;
+use burn_tensor::{DType, TensorData};
 use std::marker::PhantomData;
 
+/// The maximum number of elements of a tensor initialized from data to be kept as a
+/// [constant](Constant), e.g. scalars built from literals.
+pub(crate) const MAX_CONSTANT_ELEMENTS: usize = 64;
+
 #[derive(new, Clone, Debug)]
 pub struct NoOp<B: FusionBackend> {
     _b: PhantomData<B>,
@@ -10,3 +18,40 @@ pub struct NoOp<B: FusionBackend> {
 impl<B: FusionBackend> Operation<B::FusionRuntime> for NoOp<B> {
     fn execute(&self, _handles: &mut HandleContainer<B::Handle>) {}
 }
+
+/// The operation of a tensor initialized from data, whose buffer is created when registering
+/// the operation, keeping the data of small tensors as a [constant](Constant).
+#[derive(Debug)]
+pub struct InitOp<B: FusionBackend> {
+    constant: Option<(TensorData, B::Device)>,
+}
+
+impl<B: FusionBackend> InitOp<B> {
+    pub fn new(data: &TensorData, device: &B::Device) -> Self {
+        let constant =
+            (data.num_elements() <= MAX_CONSTANT_ELEMENTS).then(|| (data.clone(), device.clone()));
+
+        Self { constant }
+    }
+}
+
+impl<B: FusionBackend> Operation<B::FusionRuntime> for InitOp<B> {
+    fn execute(&self, _handles: &mut HandleContainer<B::Handle>) {}
+
+    fn constant(&self) -> Option<Constant<'_, B::FusionRuntime>> {
+        self.constant.as_ref().map(|(data, device)| Constant {
+            data,
+            device,
+            materialize: materialize::<B>,
+        })
+    }
+}
+
+/// Create the buffer of a tensor from its data with the operations matching its data type.
+fn materialize<B: FusionBackend>(data: TensorData, device: &B::Device) -> B::Handle {
+    match data.dtype {
+        DType::Bool => B::bool_tensor_handle(B::bool_from_data(data, device)),
+        dtype if dtype.is_float() => B::float_tensor_handle(B::float_from_data(data, device)),
+        _ => B::int_tensor_handle(B::int_from_data(data, device)),
+    }
+}
@@ -13,7 +13,7 @@ use crate::{
     Fusion, FusionBackend,
     client::FusionClient,
     get_client,
-    stream::{OperationStreams, StreamId, execution::Operation},
+    stream::{OperationStreams, current_stream, execution::Operation},
 };
 
 use super::NoOp;
@@ -52,7 +52,7 @@ impl<B: FusionBackend> BoolTensorOps<Self> for Fusion<B> {
     }
 
     fn bool_from_data(data: burn_tensor::TensorData, device: &Device<Self>) -> BoolTensor<Self> {
-        let stream = StreamId::current();
+        let stream = current_stream();
         let client = get_client::<B>(&device.clone());
         let tensor = B::bool_from_data(data, device);
         let shape = tensor.shape();
@@ -4,23 +4,25 @@ use crate::{
     get_client,
     ops::binary::check_binary_op_types,
     reduce_float_ops, reduce_float2int_ops, scalar_float_cmp_ops, scalar_float_ops,
-    stream::{OperationStreams, StreamId, execution::Operation},
+    stream::{OperationStreams, current_stream, execution::Operation},
     unary_float_ops,
 };
 use burn_ir::*;
 use burn_tensor::{
-    Device, Distribution, Element, ElementConversion, Shape, TensorData, TensorMetadata,
+    Device, Distribution, Element, ElementConversion, ScatterReduce, Shape, TensorData,
+    TensorMetadata,
     ops::{BoolTensor, FloatElem, FloatTensor, FloatTensorOps, IntTensor, binary_ops_shape},
 };
 use std::{marker::PhantomData, ops::Range};
 
-use super::NoOp;
+use super::InitOp;
 
 impl<B: FusionBackend> FloatTensorOps<Self> for Fusion<B> {
     fn float_from_data(data: TensorData, device: &Device<Self>) -> FloatTensor<Self> {
-        let stream = StreamId::current();
+        let stream = current_stream();
         let client = get_client::<B>(&device.clone());
         let dtype = data.dtype;
+        let init = InitOp::<B>::new(&data, device);
         let tensor = B::float_from_data(data, device);
         let shape = tensor.shape();
 
@@ -31,7 +33,7 @@ impl<B: FusionBackend> FloatTensorOps<Self> for Fusion<B> {
         client.register(
             OperationStreams::default(),
             OperationIr::Init(InitOperationIr { out: desc }),
-            NoOp::<B>::new(),
+            init,
         );
 
         out
@@ -607,7 +609,7 @@ impl<B: FusionBackend> FloatTensorOps<Self> for Fusion<B> {
             OperationIr::BaseFloat(BaseOperationIr::SwapDims(desc.clone())),
             SwapDimsOps::<B>::new(desc),
         );
-        out.stream = StreamId::current();
+        out.stream = current_stream();
 
         out
     }
@@ -740,6 +742,61 @@ impl<B: FusionBackend> FloatTensorOps<Self> for Fusion<B> {
         out
     }
 
+    fn float_scatter_reduce(
+        dim: usize,
+        tensor: FloatTensor<Self>,
+        indices: IntTensor<Self>,
+        value: FloatTensor<Self>,
+        reduce: ScatterReduce,
+    ) -> FloatTensor<Self> {
+        if reduce == ScatterReduce::Sum {
+            return Self::float_scatter(dim, tensor, indices, value);
+        }
+
+        #[derive(new, Debug)]
+        struct ScatterReduceOps<B: FusionBackend> {
+            desc: CustomOpIr,
+            dim: usize,
+            reduce: ScatterReduce,
+            _b: PhantomData<B>,
+        }
+
+        impl<B: FusionBackend> Operation<B::FusionRuntime> for ScatterReduceOps<B> {
+            fn execute(&self, handles: &mut HandleContainer<B::Handle>) {
+                let ([tensor, indices, value], [out]) = self.desc.as_fixed();
+                let tensor = handles.get_float_tensor::<B>(tensor);
+                let indices = handles.get_int_tensor::<B>(indices);
+                let value = handles.get_float_tensor::<B>(value);
+
+                let output = B::float_scatter_reduce(self.dim, tensor, indices, value, self.reduce);
+
+                handles.register_float_tensor::<B>(&out.id, output);
+            }
+        }
+
+        let mut streams = OperationStreams::default();
+        streams.tensor(&tensor);
+        streams.tensor(&indices);
+        streams.tensor(&value);
+
+        let out = tensor
+            .client
+            .tensor_uninitialized(tensor.shape.clone(), tensor.dtype);
+
+        let desc = CustomOpIr::new(
+            "scatter_reduce",
+            &[tensor.into_ir(), indices.into_ir(), value.into_ir()],
+            &[out.to_ir_out()],
+        );
+        out.client.register(
+            streams,
+            OperationIr::Custom(desc.clone()),
+            ScatterReduceOps::<B>::new(desc, dim, reduce),
+        );
+
+        out
+    }
+
     fn float_select(
         tensor: FloatTensor<Self>,
         dim: usize,
@@ -2161,6 +2218,116 @@ impl<B: FusionBackend> FloatTensorOps<Self> for Fusion<B> {
         out
     }
 
+    fn float_fft(tensor: FloatTensor<Self>, dim: usize, inverse: bool) -> FloatTensor<Self> {
+        #[derive(new, Debug)]
+        struct FftOps<B: FusionBackend> {
+            desc: CustomOpIr,
+            dim: usize,
+            inverse: bool,
+            _b: PhantomData<B>,
+        }
+
+        impl<B: FusionBackend> Operation<B::FusionRuntime> for FftOps<B> {
+            fn execute(&self, handles: &mut HandleContainer<B::Handle>) {
+                let ([input], [out]) = self.desc.as_fixed();
+                let input = handles.get_float_tensor::<B>(input);
+                let output = B::float_fft(input, self.dim, self.inverse);
+                handles.register_float_tensor::<B>(&out.id, output);
+            }
+        }
+
+        let mut streams = OperationStreams::default();
+        streams.tensor(&tensor);
+        let out = tensor
+            .client
+            .tensor_uninitialized(tensor.shape.clone(), tensor.dtype);
+
+        let name = match inverse {
+            true => "ifft",
+            false => "fft",
+        };
+        let desc = CustomOpIr::new(name, &[tensor.into_ir()], &[out.to_ir_out()]);
+
+        out.client.register(
+            streams,
+            OperationIr::Custom(desc.clone()),
+            FftOps::<B>::new(desc, dim, inverse),
+        );
+
+        out
+    }
+
+    fn float_rfft(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
+        #[derive(new, Debug)]
+        struct RfftOps<B: FusionBackend> {
+            desc: CustomOpIr,
+            dim: usize,
+            _b: PhantomData<B>,
+        }
+
+        impl<B: FusionBackend> Operation<B::FusionRuntime> for RfftOps<B> {
+            fn execute(&self, handles: &mut HandleContainer<B::Handle>) {
+                let ([input], [out]) = self.desc.as_fixed();
+                let input = handles.get_float_tensor::<B>(input);
+                let output = B::float_rfft(input, self.dim);
+                handles.register_float_tensor::<B>(&out.id, output);
+            }
+        }
+
+        let mut streams = OperationStreams::default();
+        streams.tensor(&tensor);
+        let mut shape = tensor.shape.clone();
+        shape[dim] = shape[dim] / 2 + 1;
+        shape.push(2);
+        let out = tensor.client.tensor_uninitialized(shape, tensor.dtype);
+
+        let desc = CustomOpIr::new("rfft", &[tensor.into_ir()], &[out.to_ir_out()]);
+
+        out.client.register(
+            streams,
+            OperationIr::Custom(desc.clone()),
+            RfftOps::<B>::new(desc, dim),
+        );
+
+        out
+    }
+
+    fn float_irfft(tensor: FloatTensor<Self>, dim: usize, n: usize) -> FloatTensor<Self> {
+        #[derive(new, Debug)]
+        struct IrfftOps<B: FusionBackend> {
+            desc: CustomOpIr,
+            dim: usize,
+            n: usize,
+            _b: PhantomData<B>,
+        }
+
+        impl<B: FusionBackend> Operation<B::FusionRuntime> for IrfftOps<B> {
+            fn execute(&self, handles: &mut HandleContainer<B::Handle>) {
+                let ([input], [out]) = self.desc.as_fixed();
+                let input = handles.get_float_tensor::<B>(input);
+                let output = B::float_irfft(input, self.dim, self.n);
+                handles.register_float_tensor::<B>(&out.id, output);
+            }
+        }
+
+        let mut streams = OperationStreams::default();
+        streams.tensor(&tensor);
+        let mut shape = tensor.shape.clone();
+        shape.pop();
+        shape[dim] = n;
+        let out = tensor.client.tensor_uninitialized(shape, tensor.dtype);
+
+        let desc = CustomOpIr::new("irfft", &[tensor.into_ir()], &[out.to_ir_out()]);
+
+        out.client.register(
+            streams,
+            OperationIr::Custom(desc.clone()),
+            IrfftOps::<B>::new(desc, dim, n),
+        );
+
+        out
+    }
+
     fn float_round(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
         unary_float_ops!(RoundOps, B::float_round);
 
@@ -2,18 +2,19 @@ use crate::{
     Fusion, FusionBackend, binary_int_cmp_ops, binary_int_ops,
     client::FusionClient,
     get_client, reduce_int_ops, scalar_int_cmp_ops, scalar_int_ops,
-    stream::{OperationStreams, StreamId, execution::Operation},
+    stream::{OperationStreams, current_stream, execution::Operation},
     unary_int_ops,
 };
 use burn_ir::*;
 use burn_tensor::{
-    Device, Distribution, Element, ElementConversion, Shape, TensorData, TensorMetadata,
+    Device, Distribution, Element, ElementConversion, ScatterReduce, Shape, TensorData,
+    TensorMetadata,
     ops::{BoolTensor, FloatTensor, IntElem, IntTensor, IntTensorOps, binary_ops_shape},
 };
 use core::ops::Range;
 use std::marker::PhantomData;
 
-use super::NoOp;
+use super::InitOp;
 
 impl<B: FusionBackend> IntTensorOps<Self> for Fusion<B> {
     fn int_empty(shape: Shape, device: &Device<Self>) -> IntTensor<Self> {
@@ -49,9 +50,10 @@ impl<B: FusionBackend> IntTensorOps<Self> for Fusion<B> {
     }
 
     fn int_from_data(data: TensorData, device: &Device<Self>) -> IntTensor<Self> {
-        let stream = StreamId::current();
+        let stream = current_stream();
         let client = get_client::<B>(&device.clone());
         let dtype = data.dtype;
+        let init = InitOp::<B>::new(&data, device);
         let tensor = B::int_from_data(data, device);
         let shape = tensor.shape();
 
@@ -62,7 +64,7 @@ impl<B: FusionBackend> IntTensorOps<Self> for Fusion<B> {
         client.register(
             OperationStreams::default(),
             OperationIr::Init(InitOperationIr { out: desc }),
-            NoOp::<B>::new(),
+            init,
         );
 
         out
@@ -410,6 +412,61 @@ impl<B: FusionBackend> IntTensorOps<Self> for Fusion<B> {
         out
     }
 
+    fn int_scatter_reduce(
+        dim: usize,
+        tensor: IntTensor<Self>,
+        indices: IntTensor<Self>,
+        value: IntTensor<Self>,
+        reduce: ScatterReduce,
+    ) -> IntTensor<Self> {
+        if reduce == ScatterReduce::Sum {
+            return Self::int_scatter(dim, tensor, indices, value);
+        }
+
+        #[derive(new, Debug)]
+        struct ScatterReduceOps<B: FusionBackend> {
+            desc: CustomOpIr,
+            dim: usize,
+            reduce: ScatterReduce,
+            _b: PhantomData<B>,
+        }
+
+        impl<B: FusionBackend> Operation<B::FusionRuntime> for ScatterReduceOps<B> {
+            fn execute(&self, handles: &mut HandleContainer<B::Handle>) {
+                let ([tensor, indices, value], [out]) = self.desc.as_fixed();
+                let tensor = handles.get_int_tensor::<B>(tensor);
+                let indices = handles.get_int_tensor::<B>(indices);
+                let value = handles.get_int_tensor::<B>(value);
+
+                let output = B::int_scatter_reduce(self.dim, tensor, indices, value, self.reduce);
+
+                handles.register_int_tensor::<B>(&out.id, output);
+            }
+        }
+
+        let mut streams = OperationStreams::default();
+        streams.tensor(&tensor);
+        streams.tensor(&indices);
+        streams.tensor(&value);
+
+        let out = tensor
+            .client
+            .tensor_uninitialized(tensor.shape.clone(), tensor.dtype);
+
+        let desc = CustomOpIr::new(
+            "scatter_reduce",
+            &[tensor.into_ir(), indices.into_ir(), value.into_ir()],
+            &[out.to_ir_out()],
+        );
+        out.client.register(
+            streams,
+            OperationIr::Custom(desc.clone()),
+            ScatterReduceOps::<B>::new(desc, dim, reduce),
+        );
+
+        out
+    }
+
     fn int_select(
         tensor: IntTensor<Self>,
         dim: usize,
@@ -15,14 +15,14 @@ use crate::{
     Fusion, FusionBackend,
     client::FusionClient,
     get_client,
-    stream::{OperationStreams, StreamId, execution::Operation},
+    stream::{OperationStreams, current_stream, execution::Operation},
 };
 
 use super::NoOp;
 
 impl<B: FusionBackend> QTensorOps<Self> for Fusion<B> {
     fn q_from_data(data: TensorData, device: &Device<Self>) -> QuantizedTensor<Self> {
-        let stream = StreamId::current();
+        let stream = current_stream();
         let client = get_client::<B>(&device.clone());
         let dtype = data.dtype;
         let tensor = B::q_from_data(data, device);
@@ -236,7 +236,7 @@ impl<B: FusionBackend> QTensorOps<Self> for Fusion<B> {
             OperationIr::BaseFloat(BaseOperationIr::SwapDims(desc.clone())),
             SwapDimsOps::<B>::new(desc),
         );
-        out.stream = StreamId::current();
+        out.stream = current_stream();
 
         out
     }
@@ -0,0 +1,343 @@
+use core::fmt::Display;
+use std::sync::Arc;
+
+use burn_ir::{TensorId, TensorIr, TensorStatus};
+use burn_tensor::TensorData;
+use hashbrown::HashMap;
+use spin::Mutex;
+
+/// How often the reads of a device were served by the data of a previous read, enabled by the
+/// [configuration](crate::FusionConfig::read_cache_bytes).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadCacheStats {
+    /// The number of reads served from the cache, without draining the stream.
+    pub num_hits: u64,
+    /// The number of reads of the device while the cache was enabled.
+    pub num_misses: u64,
+    /// The number of tensors whose data is currently cached.
+    pub num_entries: usize,
+    /// The number of bytes of data currently cached.
+    pub bytes: usize,
+}
+
+impl ReadCacheStats {
+    /// The ratio of reads served from the cache, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.num_hits + self.num_misses;
+
+        match total {
+            0 => 0.0,
+            _ => self.num_hits as f64 / total as f64,
+        }
+    }
+}
+
+impl Display for ReadCacheStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Read Cache ====\n")?;
+        f.write_fmt(format_args!(
+            " - Hits: {} Misses: {} (hit rate: {:.2}%)\n",
+            self.num_hits,
+            self.num_misses,
+            self.hit_rate() * 100.0
+        ))?;
+        f.write_fmt(format_args!(
+            " - Entries: {} ({} bytes)\n",
+            self.num_entries, self.bytes
+        ))?;
+        f.write_str("===========================\n")
+    }
+}
+
+/// The data of the last read of each tensor, reused by the next reads of the same tensor until
+/// it's written or dropped.
+///
+/// Tensors are immutable while they're only read, so the data can't change until an operation
+/// consumes the tensor, which is when its entry is invalidated. The least recently read tensors
+/// are forgotten once the cache exceeds its capacity.
+#[derive(Clone, Default)]
+pub(crate) struct ReadCache {
+    state: Arc<Mutex<ReadCacheState>>,
+    /// Whether the cache has a capacity, checked without locking it.
+    enabled: bool,
+}
+
+#[derive(Default)]
+struct ReadCacheState {
+    /// The maximum number of bytes cached, disabled when `None`.
+    capacity: Option<usize>,
+    entries: HashMap<TensorId, CachedRead>,
+    /// The reads being resolved, completed only if not invalidated in the meantime.
+    pending: HashMap<TensorId, u64>,
+    bytes: usize,
+    clock: u64,
+    num_hits: u64,
+    num_misses: u64,
+}
+
+struct CachedRead {
+    data: TensorData,
+    last_used: u64,
+}
+
+/// A read of the device whose data is cached once resolved.
+pub(crate) struct PendingRead {
+    cache: ReadCache,
+    tensor: TensorId,
+    token: u64,
+}
+
+impl ReadCache {
+    /// Update the capacity, forgetting every entry when disabled.
+    pub(crate) fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.enabled = capacity.is_some();
+        let mut state = self.state.lock();
+        state.capacity = capacity;
+
+        match capacity {
+            Some(capacity) => state.evict(capacity),
+            None => state.clear(),
+        }
+    }
+
+    /// Forget every entry, e.g. when tensor ids are reused.
+    pub(crate) fn clear(&self) {
+        self.state.lock().clear();
+    }
+
+    /// Forget the tensors that the operation writes or consumes.
+    pub(crate) fn invalidate<'a>(&self, tensors: impl IntoIterator<Item = &'a TensorIr>) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut state = self.state.lock();
+        for tensor in tensors {
+            if tensor.status != TensorStatus::ReadOnly {
+                state.remove(tensor.id);
+            }
+        }
+    }
+
+    /// The data of the last read of the tensor, if cached.
+    ///
+    /// A read consuming the tensor is never served from the cache, since the operations queued
+    /// before it must be executed before its handle is freed, but it forgets the tensor.
+    pub(crate) fn get(&self, tensor: &TensorIr) -> Option<TensorData> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut state = self.state.lock();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let data = match tensor.status {
+            TensorStatus::ReadWrite => {
+                state.remove(tensor.id);
+                None
+            }
+            _ => state.entries.get_mut(&tensor.id).map(|entry| {
+                entry.last_used = clock;
+                entry.data.clone()
+            }),
+        };
+
+        match data.is_some() {
+            true => state.num_hits += 1,
+            false => state.num_misses += 1,
+        }
+
+        data
+    }
+
+    /// Start reading the tensor from the device, returning `None` when its data won't be cached.
+    pub(crate) fn begin(&self, tensor: &TensorIr) -> Option<PendingRead> {
+        if !self.enabled || tensor.status == TensorStatus::ReadWrite {
+            return None;
+        }
+
+        let mut state = self.state.lock();
+        state.clock += 1;
+        let token = state.clock;
+        state.pending.insert(tensor.id, token);
+
+        Some(PendingRead {
+            cache: self.clone(),
+            tensor: tensor.id,
+            token,
+        })
+    }
+
+    pub(crate) fn stats(&self) -> ReadCacheStats {
+        let state = self.state.lock();
+
+        ReadCacheStats {
+            num_hits: state.num_hits,
+            num_misses: state.num_misses,
+            num_entries: state.entries.len(),
+            bytes: state.bytes,
+        }
+    }
+}
+
+/// Where the data of a read comes from.
+pub(crate) enum ReadSource<F> {
+    /// The data of a previous read.
+    Cached(TensorData),
+    /// A read of the device, cached once resolved when pending.
+    Device(Option<PendingRead>, F),
+}
+
+impl<F: Future<Output = TensorData>> ReadSource<F> {
+    pub(crate) async fn resolve(self) -> TensorData {
+        match self {
+            Self::Cached(data) => data,
+            Self::Device(pending, read) => {
+                let data = read.await;
+                if let Some(pending) = pending {
+                    pending.complete(&data);
+                }
+
+                data
+            }
+        }
+    }
+}
+
+impl PendingRead {
+    /// Cache the data read, unless the tensor was invalidated since the read started.
+    pub(crate) fn complete(self, data: &TensorData) {
+        let mut state = self.cache.state.lock();
+        if state.pending.get(&self.tensor) != Some(&self.token) {
+            return;
+        }
+        state.pending.remove(&self.tensor);
+
+        let capacity = match state.capacity {
+            Some(capacity) => capacity,
+            None => return,
+        };
+        let bytes = data.as_bytes().len();
+        if bytes > capacity {
+            return;
+        }
+
+        state.remove(self.tensor);
+        state.bytes += bytes;
+        state.entries.insert(
+            self.tensor,
+            CachedRead {
+                data: data.clone(),
+                last_used: self.token,
+            },
+        );
+        state.evict(capacity);
+    }
+}
+
+impl ReadCacheState {
+    fn remove(&mut self, tensor: TensorId) {
+        self.pending.remove(&tensor);
+
+        if let Some(entry) = self.entries.remove(&tensor) {
+            self.bytes -= entry.data.as_bytes().len();
+        }
+    }
+
+    /// Forget the least recently read tensors until the cache fits its capacity.
+    fn evict(&mut self, capacity: usize) {
+        if self.bytes <= capacity {
+            return;
+        }
+
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|(id, entry)| (entry.last_used, *id))
+            .collect::<Vec<_>>();
+        entries.sort_unstable();
+
+        for (_, id) in entries {
+            if self.bytes <= capacity {
+                break;
+            }
+            self.remove(id);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.pending.clear();
+        self.bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::tensor;
+
+    fn read(cache: &ReadCache, tensor: &TensorIr, values: [f32; 2]) {
+        let data = TensorData::from(values);
+        if cache.get(tensor).is_none()
+            && let Some(pending) = cache.begin(tensor)
+        {
+            pending.complete(&data);
+        }
+    }
+
+    #[test]
+    fn should_serve_repeated_reads_until_invalidated() {
+        let mut cache = ReadCache::default();
+        cache.set_capacity(Some(1024));
+        let read_only = tensor(1, TensorStatus::ReadOnly);
+
+        read(&cache, &read_only, [1.0, 2.0]);
+        assert_eq!(cache.get(&read_only), Some(TensorData::from([1.0f32, 2.0])));
+
+        cache.invalidate([&tensor(1, TensorStatus::ReadWrite)]);
+        assert_eq!(cache.get(&read_only), None);
+
+        let stats = cache.stats();
+        assert_eq!(stats.num_hits, 1);
+        assert_eq!(stats.num_misses, 2);
+        assert_eq!(stats.num_entries, 0);
+        assert_eq!(stats.bytes, 0);
+    }
+
+    #[test]
+    fn should_not_cache_reads_invalidated_before_completion() {
+        let mut cache = ReadCache::default();
+        cache.set_capacity(Some(1024));
+        let read_only = tensor(1, TensorStatus::ReadOnly);
+
+        let pending = cache.begin(&read_only).unwrap();
+        cache.invalidate([&tensor(1, TensorStatus::ReadWrite)]);
+        pending.complete(&TensorData::from([1.0f32, 2.0]));
+
+        assert_eq!(cache.get(&read_only), None);
+    }
+
+    #[test]
+    fn should_evict_least_recently_read_tensors() {
+        let mut cache = ReadCache::default();
+        // Two tensors of 8 bytes each.
+        cache.set_capacity(Some(16));
+        let (tensor_1, tensor_2, tensor_3) = (
+            tensor(1, TensorStatus::ReadOnly),
+            tensor(2, TensorStatus::ReadOnly),
+            tensor(3, TensorStatus::ReadOnly),
+        );
+
+        read(&cache, &tensor_1, [1.0, 2.0]);
+        read(&cache, &tensor_2, [3.0, 4.0]);
+        read(&cache, &tensor_1, [1.0, 2.0]);
+        read(&cache, &tensor_3, [5.0, 6.0]);
+
+        assert!(cache.get(&tensor_1).is_some());
+        assert!(cache.get(&tensor_2).is_none());
+        assert!(cache.get(&tensor_3).is_some());
+        assert_eq!(cache.stats().bytes, 16);
+    }
+}
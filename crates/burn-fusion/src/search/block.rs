@@ -1,7 +1,9 @@
 use crate::{
-    NumOperations, OptimizationBuilder, OptimizationStatus, stream::store::ExecutionStrategy,
+    NumOperations, OptimizationBuilder, OptimizationStatus,
+    search::{ExplorationSettings, FusionEstimate},
+    stream::store::ExecutionStrategy,
 };
-use burn_ir::{OperationIr, TensorId, TensorIr};
+use burn_ir::{FloatOperationIr, NumericOperationIr, OperationIr, TensorId, TensorIr};
 use std::{collections::HashSet, sync::Arc};
 
 /// A block represents a list of operations, not necessarily in the same order as the execution
@@ -14,6 +16,15 @@ pub struct Block<O> {
     operations: Vec<OperationIr>,
     ids: HashSet<TensorId>,
     ordering: Vec<usize>,
+    /// The shape of every operation of the block when they are all element wise, which allows
+    /// [horizontal fusion](Self::accepts_horizontally).
+    elementwise_shape: Option<Vec<usize>>,
+    is_elementwise: bool,
+    /// Whether the operations following a reduction or a matrix multiplication closing every
+    /// builder are explored in an [epilogue](Self::with_epilogues).
+    epilogues: bool,
+    has_anchor: bool,
+    epilogue: Option<Box<Block<O>>>,
     /// The start position in the relative execution stream.
     pub start_pos: usize,
     /// The end position in the relative execution stream.
@@ -47,22 +58,75 @@ impl<O: NumOperations> Block<O> {
             operations: Vec::new(),
             ids: HashSet::new(),
             ordering: Vec::new(),
+            elementwise_shape: None,
+            is_elementwise: true,
+            epilogues: false,
+            has_anchor: false,
+            epilogue: None,
             start_pos: usize::MAX,
             end_pos: usize::MIN,
         }
     }
 
+    /// Explore the operations following a reduction or a matrix multiplication closing every
+    /// builder of the block, e.g. the element wise operations of a softmax or the bias and the
+    /// activation of a linear layer, with fresh builders instead of ending the exploration.
+    ///
+    /// The operations of the epilogue aren't part of the block's optimization, they are
+    /// optimized separately and composed with it in the same execution plan.
+    pub fn with_epilogues(mut self, epilogues: bool) -> Self {
+        self.epilogues = epilogues;
+        self
+    }
+
     /// Sort the [blocks](Block) based on the start position.
     pub fn sort(blocks: &mut [Self]) {
         blocks.sort_by(|a, b| a.start_pos.cmp(&b.start_pos));
     }
 
-    /// Optimize the block.
-    pub fn optimize(mut self) -> BlockOptimization<O> {
-        match find_best_optimization_index(&mut self.builders) {
+    /// Optimize the block with the candidate selected by the [policy](crate::ExplorationPolicy),
+    /// unless the [cost model](crate::CostModel) predicts it's slower than executing the
+    /// operations one by one.
+    ///
+    /// Only the operations within the [limits](crate::FusionSettings) and allowed by the
+    /// [filter](crate::FusionFilter) are fused, the others being left out of the optimization.
+    pub fn optimize(mut self, exploration: &ExplorationSettings) -> BlockOptimization<O> {
+        let num_fusable = exploration.num_fusable(&self.operations);
+        if num_fusable == 0 && !self.operations.is_empty() {
+            // The first operation can't be fused, so it's executed alone.
+            self.ordering.truncate(1);
+            let strategy = ExecutionStrategy::Operations {
+                ordering: Arc::new(self.ordering.clone()),
+            };
+            return BlockOptimization::new(strategy, self.ordering);
+        }
+        self.limit(num_fusable);
+
+        let candidates = self
+            .builders
+            .iter()
+            .map(|builder| builder.properties())
+            .collect::<Vec<_>>();
+        let selected = exploration
+            .policy
+            .select_candidate(&candidates)
+            .filter(|index| {
+                let builder = &self.builders[*index];
+                let num_fused = usize::min(builder.len(), self.operations.len());
+                let estimate =
+                    FusionEstimate::new(&self.operations[..num_fused], builder.cost_hint());
+
+                exploration.cost_model.should_fuse(&estimate)
+            });
+
+        match selected {
             Some(index) => {
                 let opt = self.builders[index].build();
                 let opt_len = opt.len();
+                // The epilogue follows the optimization only when it fuses every other operation.
+                let epilogue = self.epilogue.take().filter(|epilogue| {
+                    epilogue.len() > 0 && opt_len + epilogue.len() == self.operations.len()
+                });
                 if opt_len < self.operations.len() {
                     self.ordering.drain(opt_len..);
                 }
@@ -71,7 +135,19 @@ impl<O: NumOperations> Block<O> {
                     ordering: Arc::new(self.ordering.clone()),
                     opt,
                 };
-                BlockOptimization::new(strategy, self.ordering)
+
+                match epilogue {
+                    Some(epilogue) => {
+                        let mut epilogue = epilogue.optimize(exploration);
+                        let strategy = ExecutionStrategy::Composed(vec![
+                            Box::new(strategy),
+                            Box::new(epilogue.strategy),
+                        ]);
+                        self.ordering.append(&mut epilogue.ordering);
+                        BlockOptimization::new(strategy, self.ordering)
+                    }
+                    None => BlockOptimization::new(strategy, self.ordering),
+                }
             }
             None => {
                 let strategy = ExecutionStrategy::Operations {
@@ -82,6 +158,28 @@ impl<O: NumOperations> Block<O> {
         }
     }
 
+    /// The number of operations registered in the block.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Register the first operations of the block in new builders, leaving out the others.
+    fn limit(&mut self, num_operations: usize) {
+        if num_operations >= self.operations.len() {
+            return;
+        }
+
+        // The epilogue may include operations left out.
+        self.epilogue = None;
+
+        for builder in self.builders.iter_mut() {
+            builder.reset();
+            for operation in self.operations[..num_operations].iter() {
+                builder.register(operation);
+            }
+        }
+    }
+
     /// Returns if the block contains any of the provided [tensors](TensorIr).
     pub fn contains_tensors(&self, tensors: &[&TensorIr]) -> bool {
         for node in tensors {
@@ -143,8 +241,22 @@ impl<O: NumOperations> Block<O> {
         RegistrationResult::Accepted
     }
 
+    /// Whether the operation, independent of the block, can be fused with it horizontally: both
+    /// are element wise with the same shape, e.g. the updates of different parameters by an
+    /// optimizer.
+    pub fn accepts_horizontally(&self, operation: &OperationIr) -> bool {
+        self.is_elementwise
+            && self.elementwise_shape.is_some()
+            && elementwise_shape(operation) == self.elementwise_shape.as_deref()
+            && self.still_optimizing()
+    }
+
     /// If the block can still be optimized further.
     pub fn still_optimizing(&self) -> bool {
+        if let Some(epilogue) = &self.epilogue {
+            return epilogue.still_optimizing();
+        }
+
         let mut num_stopped = 0;
 
         for optimization in self.builders.iter() {
@@ -160,6 +272,17 @@ impl<O: NumOperations> Block<O> {
         self.operations.push(operation.clone());
         self.ordering.push(pos);
 
+        if self.is_elementwise && !matches!(operation, OperationIr::Drop(_)) {
+            match (elementwise_shape(operation), &self.elementwise_shape) {
+                (Some(shape), None) => self.elementwise_shape = Some(shape.to_vec()),
+                (Some(shape), Some(current)) if shape == current.as_slice() => {}
+                _ => {
+                    self.is_elementwise = false;
+                    self.elementwise_shape = None;
+                }
+            }
+        }
+
         if pos < self.start_pos {
             self.start_pos = pos;
         }
@@ -167,16 +290,108 @@ impl<O: NumOperations> Block<O> {
             self.end_pos = pos + 1;
         }
 
+        for node in operation.nodes() {
+            self.ids.insert(node.id);
+        }
+
+        if let Some(epilogue) = &mut self.epilogue {
+            epilogue.register_op(operation, pos);
+            return;
+        }
+
+        self.has_anchor |= is_anchor(operation);
         for builder in self.builders.iter_mut() {
             builder.register(operation);
         }
 
-        for node in operation.nodes() {
-            self.ids.insert(node.id);
+        if self.epilogues && self.has_anchor && !self.still_optimizing() {
+            self.open_epilogue();
+        }
+    }
+
+    /// Start exploring the operations left out of the optimizations of the block in an epilogue.
+    fn open_epilogue(&mut self) {
+        let num_fused = self
+            .builders
+            .iter()
+            .filter(|builder| builder.properties().ready)
+            .map(|builder| builder.len())
+            .max()
+            .unwrap_or(0);
+
+        // Nothing to compose the epilogue with.
+        if num_fused == 0 {
+            return;
         }
+
+        let mut epilogue = Block::new(&self.builders).with_epilogues(true);
+        epilogue
+            .builders
+            .iter_mut()
+            .for_each(|builder| builder.reset());
+
+        for (operation, pos) in self.operations.iter().zip(&self.ordering).skip(num_fused) {
+            epilogue.register_op(operation, *pos);
+        }
+
+        self.epilogue = Some(Box::new(epilogue));
+    }
+}
+
+/// Whether the operation is a reduction or a matrix multiplication, which often end the
+/// optimizations of a block.
+fn is_anchor(operation: &OperationIr) -> bool {
+    match operation {
+        OperationIr::Float(_, FloatOperationIr::Matmul(_)) => true,
+        OperationIr::NumericFloat(_, operation) => is_numeric_reduction(operation),
+        OperationIr::NumericInt(_, operation) => is_numeric_reduction(operation),
+        _ => false,
     }
 }
 
+fn is_numeric_reduction<E>(operation: &NumericOperationIr<E>) -> bool {
+    matches!(
+        operation,
+        NumericOperationIr::Sum(_)
+            | NumericOperationIr::SumDim(_)
+            | NumericOperationIr::Mean(_)
+            | NumericOperationIr::MeanDim(_)
+            | NumericOperationIr::Prod(_)
+            | NumericOperationIr::ProdDim(_)
+            | NumericOperationIr::Max(_)
+            | NumericOperationIr::MaxDim(_)
+            | NumericOperationIr::MaxDimWithIndices(_)
+            | NumericOperationIr::Min(_)
+            | NumericOperationIr::MinDim(_)
+            | NumericOperationIr::MinDimWithIndices(_)
+            | NumericOperationIr::MaxAbs(_)
+            | NumericOperationIr::MaxAbsDim(_)
+            | NumericOperationIr::ArgMax(_)
+            | NumericOperationIr::ArgMin(_)
+    )
+}
+
+/// The shape of every tensor of the operation if it's element wise.
+fn elementwise_shape(operation: &OperationIr) -> Option<&[usize]> {
+    match operation {
+        OperationIr::Float(_, FloatOperationIr::Matmul(_)) => return None,
+        OperationIr::Float(..)
+        | OperationIr::NumericFloat(..)
+        | OperationIr::NumericInt(..)
+        | OperationIr::Int(_)
+        | OperationIr::Bool(_) => {}
+        _ => return None,
+    }
+
+    let nodes = operation.nodes();
+    let shape = nodes.first()?.shape.as_slice();
+
+    nodes
+        .iter()
+        .all(|node| node.shape.as_slice() == shape)
+        .then_some(shape)
+}
+
 impl<O> BlockOptimization<O> {
     /// Maps the ordering of the current block optimization using the given mapping.
     pub fn map_ordering(&mut self, mapping: &[usize]) {
@@ -217,24 +432,6 @@ impl<O> ExecutionStrategy<O> {
     }
 }
 
-fn find_best_optimization_index<O>(
-    optimizations: &mut [Box<dyn OptimizationBuilder<O>>],
-) -> Option<usize> {
-    let mut best_index = None;
-    let mut best_score = 0;
-
-    for (i, optimization) in optimizations.iter().enumerate() {
-        let properties = optimization.properties();
-
-        if properties.ready && properties.score >= best_score {
-            best_index = Some(i);
-            best_score = properties.score;
-        }
-    }
-
-    best_index
-}
-
 impl<O> PartialEq for Block<O> {
     fn eq(&self, other: &Self) -> bool {
         // Since the ordering can be seen as operation ids, we can use it to compare
@@ -266,6 +463,11 @@ impl<O> Clone for Block<O> {
             operations: self.operations.clone(),
             ids: self.ids.clone(),
             ordering: self.ordering.clone(),
+            elementwise_shape: self.elementwise_shape.clone(),
+            is_elementwise: self.is_elementwise,
+            epilogues: self.epilogues,
+            has_anchor: self.has_anchor,
+            epilogue: self.epilogue.clone(),
             start_pos: self.start_pos,
             end_pos: self.end_pos,
         }
@@ -0,0 +1,185 @@
+use core::time::Duration;
+
+use burn_ir::{OperationIr, TensorId, TensorStatus};
+use hashbrown::HashSet;
+
+use crate::OptimizationCostHint;
+
+/// Predicts whether fusing a block of operations is faster than executing them one by one, set
+/// per device with [Fusion::set_cost_model](crate::Fusion::set_cost_model).
+///
+/// The model is consulted once per block when a plan is explored, so a rejected optimization is
+/// never built and the plan keeps executing the operations one by one.
+pub trait CostModel: Send + Sync {
+    /// Whether the operations should be executed with the optimization.
+    fn should_fuse(&self, estimate: &FusionEstimate) -> bool;
+}
+
+/// The [cost model](CostModel) used unless another one is set, always fusing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysFuse;
+
+impl CostModel for AlwaysFuse {
+    fn should_fuse(&self, _estimate: &FusionEstimate) -> bool {
+        true
+    }
+}
+
+/// A [cost model](CostModel) comparing the time saved by fusing operations, from the kernel
+/// launches and the memory traffic avoided, with the time it must save to be worth compiling a
+/// new kernel.
+///
+/// Blocks of tiny tensors save close to nothing on memory traffic, so they are only fused when
+/// enough launches are saved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThroughputCostModel {
+    /// The time it takes to launch a kernel.
+    pub launch_overhead: Duration,
+    /// The memory bandwidth of the device, in bytes per second.
+    pub bandwidth: f64,
+    /// The minimum time an optimization must save to be used.
+    pub min_saving: Duration,
+    /// The highest [register pressure](OptimizationCostHint::register_pressure) of the fused
+    /// kernels, unbounded when `None`.
+    pub max_register_pressure: Option<u32>,
+}
+
+/// The estimated costs of fusing a block of operations, given to the [cost model](CostModel).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FusionEstimate {
+    /// The number of operations fused.
+    pub num_operations: usize,
+    /// The number of bytes read and written when executing the operations one by one.
+    pub bytes_unfused: u64,
+    /// The number of bytes read and written by the fused kernels, which keep the intermediate
+    /// tensors freed by the operations out of global memory.
+    pub bytes_fused: u64,
+    /// The number of kernel launches avoided by fusing the operations.
+    pub launches_saved: usize,
+    /// The register pressure of the fused kernels, hinted by the
+    /// [builder](crate::OptimizationBuilder::cost_hint).
+    pub register_pressure: Option<u32>,
+}
+
+impl ThroughputCostModel {
+    /// The time saved by fusing the operations, zero when fusing is slower.
+    pub fn saving(&self, estimate: &FusionEstimate) -> Duration {
+        let bytes_saved = estimate.bytes_unfused.saturating_sub(estimate.bytes_fused);
+        let transfers = Duration::from_secs_f64(bytes_saved as f64 / self.bandwidth);
+
+        self.launch_overhead * estimate.launches_saved as u32 + transfers
+    }
+}
+
+impl CostModel for ThroughputCostModel {
+    fn should_fuse(&self, estimate: &FusionEstimate) -> bool {
+        if let (Some(max), Some(pressure)) =
+            (self.max_register_pressure, estimate.register_pressure)
+            && pressure > max
+        {
+            return false;
+        }
+
+        self.saving(estimate) >= self.min_saving
+    }
+}
+
+impl FusionEstimate {
+    /// Estimate the costs of fusing the operations with the hint of the builder fusing them.
+    pub(crate) fn new(operations: &[OperationIr], hint: OptimizationCostHint) -> Self {
+        let mut produced = HashSet::<TensorId>::new();
+        let mut freed = HashSet::<TensorId>::new();
+        let mut read = HashSet::<TensorId>::new();
+        let mut bytes_unfused = 0;
+        let mut bytes_read = 0;
+        let mut num_launches = 0usize;
+
+        for operation in operations {
+            if let OperationIr::Drop(tensor) = operation {
+                freed.insert(tensor.id);
+                continue;
+            }
+
+            num_launches += 1;
+
+            for node in operation.nodes() {
+                let bytes = (node.shape.iter().product::<usize>() * node.dtype.size()) as u64;
+                bytes_unfused += bytes;
+
+                match node.status {
+                    TensorStatus::NotInit => {
+                        produced.insert(node.id);
+                    }
+                    status => {
+                        if status == TensorStatus::ReadWrite {
+                            freed.insert(node.id);
+                        }
+                        if !produced.contains(&node.id) && read.insert(node.id) {
+                            bytes_read += bytes;
+                        }
+                    }
+                }
+            }
+        }
+
+        let bytes_written = operations
+            .iter()
+            .flat_map(|operation| operation.nodes())
+            .filter(|node| node.status == TensorStatus::NotInit && !freed.contains(&node.id))
+            .map(|node| (node.shape.iter().product::<usize>() * node.dtype.size()) as u64)
+            .sum::<u64>();
+
+        Self {
+            num_operations: operations.len(),
+            bytes_unfused,
+            bytes_fused: bytes_read + bytes_written,
+            launches_saved: num_launches.saturating_sub(hint.num_launches),
+            register_pressure: hint.register_pressure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_only_fuse_when_the_saving_is_worth_it() {
+        let operations = [
+            exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(2, TensorStatus::ReadWrite),
+                tensor(3, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(3, TensorStatus::ReadOnly),
+                tensor(4, TensorStatus::NotInit),
+            ),
+        ];
+
+        let estimate = FusionEstimate::new(&operations, OptimizationCostHint::default());
+
+        // Each tensor is 32 bytes, the fused kernel reads `1` and writes `3` and `4`.
+        assert_eq!(estimate.bytes_unfused, 192);
+        assert_eq!(estimate.bytes_fused, 96);
+        assert_eq!(estimate.launches_saved, 2);
+
+        let model = |min_saving| ThroughputCostModel {
+            launch_overhead: Duration::from_micros(5),
+            bandwidth: 1e9,
+            min_saving,
+            max_register_pressure: Some(8),
+        };
+        assert!(model(Duration::from_micros(10)).should_fuse(&estimate));
+        assert!(!model(Duration::from_micros(11)).should_fuse(&estimate));
+        assert!(!model(Duration::ZERO).should_fuse(&FusionEstimate {
+            register_pressure: Some(9),
+            ..estimate
+        }));
+        assert!(AlwaysFuse.should_fuse(&estimate));
+    }
+}
@@ -1,7 +1,11 @@
 mod block;
+mod cost;
 mod optimization;
+mod policy;
 
 pub(super) mod merging;
 pub(super) use block::*;
 
+pub use cost::*;
 pub use optimization::*;
+pub use policy::*;
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::{
     NumOperations,
     search::{
-        Block, BlockOptimization,
+        Block, BlockOptimization, ExplorationSettings,
         merging::{MergeBlocksResult, merge_blocks},
     },
     stream::store::ExecutionStrategy,
@@ -23,6 +23,7 @@ pub struct BlocksOptimizer<O> {
     blocks: Vec<Block<O>>,
     resolved: Vec<bool>,
     last_checked: usize,
+    exploration: ExplorationSettings,
 }
 
 /// When we can't find a proper optimization for the provided list of [blocks](Block).
@@ -54,13 +55,14 @@ enum BlockOptimizationStep<O> {
 
 impl<O: NumOperations> BlocksOptimizer<O> {
     /// Create a new optimizer with the given blocks.
-    pub fn new(blocks: Vec<Block<O>>) -> Self {
+    pub fn new(blocks: Vec<Block<O>>, exploration: ExplorationSettings) -> Self {
         let num_ops: usize = blocks.iter().map(|g| g.end_pos).max().unwrap();
 
         Self {
             blocks,
             resolved: vec![false; num_ops],
             last_checked: 0,
+            exploration,
         }
     }
 
@@ -122,7 +124,7 @@ impl<O: NumOperations> BlocksOptimizer<O> {
         ordering: &mut Vec<usize>,
     ) -> BlockOptimizationStep<O> {
         let last_index = block.end_pos;
-        let mut block_optimization = block.optimize();
+        let mut block_optimization = block.optimize(&self.exploration);
         let opt_size = block_optimization.ordering.len();
 
         for pos in block_optimization.ordering.iter() {
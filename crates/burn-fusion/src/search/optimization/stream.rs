@@ -2,7 +2,7 @@ use super::blocks::BlocksOptimizer;
 use crate::{
     NumOperations, OptimizationBuilder,
     search::{
-        Block, BlockOptimization, RegistrationResult,
+        Block, BlockOptimization, ExplorationSettings, RegistrationResult,
         merging::{MergeBlocksResult, merge_blocks},
         optimization::blocks::BlocksOptimizerResult,
     },
@@ -16,22 +16,29 @@ pub struct StreamOptimizer<O> {
     blocks: Vec<Block<O>>,
     length: usize,
     stopped: bool,
-    max_blocks: Option<usize>,
+    exploration: ExplorationSettings,
 }
 
 impl<O: NumOperations> StreamOptimizer<O> {
     /// Create a new stream optimizer.
-    pub fn new(builders: Vec<Box<dyn OptimizationBuilder<O>>>) -> Self {
+    pub fn new(
+        builders: Vec<Box<dyn OptimizationBuilder<O>>>,
+        exploration: ExplorationSettings,
+    ) -> Self {
         Self {
             builders,
             blocks: Vec::new(),
             length: 0,
             stopped: false,
-            // Too high and it may breaks the fusion cache always retriggering explorations.
-            max_blocks: Some(5),
+            exploration,
         }
     }
 
+    /// Update the [settings](ExplorationSettings) deciding how the operations are optimized.
+    pub(crate) fn set_exploration(&mut self, exploration: ExplorationSettings) {
+        self.exploration = exploration;
+    }
+
     /// Register a new [operation](OperationIr) in the optimizer.
     ///
     /// You can use the function [Self::still_optimizing] to know if the operations are actually
@@ -41,6 +48,16 @@ impl<O: NumOperations> StreamOptimizer<O> {
             return;
         }
 
+        // Operations that can't be fused end the exploration, executed alone once first.
+        if !self.exploration.filter.can_fuse(operation) {
+            if self.blocks.is_empty() {
+                self.on_new_block(operation);
+                self.length += 1;
+            }
+            self.stopped = true;
+            return;
+        }
+
         if self.blocks.is_empty() {
             self.on_new_block(operation);
             self.length += 1;
@@ -56,7 +73,7 @@ impl<O: NumOperations> StreamOptimizer<O> {
             }
         }
 
-        if let Some(max_blocks) = self.max_blocks {
+        if let Some(max_blocks) = self.exploration.policy.max_blocks() {
             if self.register_max_block(operation, max_blocks) {
                 self.length += 1;
             } else {
@@ -66,7 +83,7 @@ impl<O: NumOperations> StreamOptimizer<O> {
         }
 
         let added_count = self.register_inner(operation, false);
-        if added_count == 0 {
+        if added_count == 0 && !self.register_horizontal(operation) {
             self.on_new_block(operation);
         }
 
@@ -81,7 +98,15 @@ impl<O: NumOperations> StreamOptimizer<O> {
     /// method, this simply remove the need for the current type to also keep track of the list of
     /// operations.
     pub fn optimize(&self, operations: &[OperationIr]) -> BlockOptimization<O> {
-        let result = BlocksOptimizer::new(self.blocks.clone()).optimize();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "fusion.search",
+            num_operations = operations.len(),
+            num_blocks = self.blocks.len()
+        )
+        .entered();
+
+        let result = BlocksOptimizer::new(self.blocks.clone(), self.exploration.clone()).optimize();
 
         match result {
             BlocksOptimizerResult::Full(block_optimization) => block_optimization,
@@ -137,9 +162,11 @@ impl<O: NumOperations> StreamOptimizer<O> {
         }
 
         let mut num_stopped = 0;
+        let max_ops = self.exploration.limits.max_ops_per_block;
 
         for block in self.blocks.iter() {
-            if !block.still_optimizing() {
+            // Operations past the limit would be left out of the block's optimization.
+            if !block.still_optimizing() || max_ops.is_some_and(|max| block.len() >= max) {
                 num_stopped += 1
             }
         }
@@ -155,7 +182,7 @@ impl<O: NumOperations> StreamOptimizer<O> {
         }
         let added_count = self.register_inner(operation, false);
 
-        if added_count > 0 {
+        if added_count > 0 || self.register_horizontal(operation) {
             return true;
         }
 
@@ -193,6 +220,26 @@ impl<O: NumOperations> StreamOptimizer<O> {
         added_count
     }
 
+    /// Register an operation independent of every block in a block it can be fused with
+    /// horizontally, returning whether one was found.
+    fn register_horizontal(&mut self, operation: &OperationIr) -> bool {
+        if !self.exploration.policy.horizontal_fusion() {
+            return false;
+        }
+
+        match self
+            .blocks
+            .iter_mut()
+            .find(|block| block.accepts_horizontally(operation))
+        {
+            Some(block) => {
+                block.register(operation, self.length, true);
+                true
+            }
+            None => false,
+        }
+    }
+
     fn new_empty_search(&self) -> Self {
         Self::new(
             self.builders
@@ -203,6 +250,7 @@ impl<O: NumOperations> StreamOptimizer<O> {
                     b
                 })
                 .collect(),
+            self.exploration.clone(),
         )
     }
 
@@ -263,7 +311,8 @@ impl<O: NumOperations> StreamOptimizer<O> {
     }
 
     fn on_new_block(&mut self, operation: &OperationIr) {
-        let mut block = Block::new(&self.builders);
+        let mut block =
+            Block::new(&self.builders).with_epilogues(self.exploration.policy.epilogues());
         block.register(operation, self.length, true);
         self.blocks.push(block);
     }
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use burn_ir::OperationIr;
+
+use crate::{
+    FusionFilter, FusionSettings, OptimizationProperties,
+    search::{AlwaysFuse, CostModel},
+};
+
+/// Decides how the operations of a stream are explored to find optimizations, set per device
+/// with [Fusion::set_exploration_policy](crate::Fusion::set_exploration_policy).
+///
+/// Every method has a default matching the [default policy](DefaultExplorationPolicy), so an
+/// implementation only overrides the decisions it cares about.
+pub trait ExplorationPolicy: Send + Sync {
+    /// The maximum number of independent blocks of operations explored at once, unbounded when
+    /// `None`.
+    ///
+    /// The exploration stops when an operation would start a new block past the limit.
+    fn max_blocks(&self) -> Option<usize> {
+        // Too high and it may breaks the fusion cache always retriggering explorations.
+        Some(5)
+    }
+
+    /// Whether element wise operations independent of the explored blocks can join a block with
+    /// the same shape, fusing independent chains into a single kernel launch.
+    ///
+    /// Without horizontal fusion, independent chains are explored as separate blocks, up to
+    /// the [maximum](Self::max_blocks), so workloads updating thousands of small tensors, like
+    /// optimizers, launch a kernel or more per tensor.
+    fn horizontal_fusion(&self) -> bool {
+        true
+    }
+
+    /// Whether the operations following a reduction or a matrix multiplication that ends every
+    /// optimization of a block, like the element wise operations of a softmax or the bias and
+    /// the activation of a linear layer, are explored with the block, their optimization being
+    /// composed with the block's optimization in a single execution plan.
+    ///
+    /// Otherwise, the reduction or the matrix multiplication ends the exploration and the
+    /// following operations are explored again for another plan.
+    fn epilogues(&self) -> bool {
+        true
+    }
+
+    /// Whether new sequences of operations are explored on a background thread while they are
+    /// executed one by one, the optimized plan being used the next time the sequence is seen.
+    ///
+    /// It removes the latency of the exploration from the first iteration of a workload, at the
+    /// cost of executing it without fusion, which suits serving. The plans explored in the
+    /// background are used from the next operation registered after they are found.
+    fn background_exploration(&self) -> bool {
+        false
+    }
+
+    /// Whether to stop registering new operations in the optimization builders.
+    fn should_stop(&self, state: &ExplorationState) -> bool {
+        !state.still_optimizing
+    }
+
+    /// Whether to build the optimization of the explored operations instead of waiting for more
+    /// operations.
+    ///
+    /// Only called when the stream isn't synced, an optimization always being built otherwise.
+    fn should_commit(&self, state: &ExplorationState) -> bool {
+        self.should_stop(state)
+    }
+
+    /// The index of the candidate optimization built for a block of operations, falling back on
+    /// executing the operations one by one when `None`.
+    ///
+    /// Candidates are given in the order of the
+    /// [optimization builders](crate::FusionRuntime::optimizations) of the runtime. By default,
+    /// the ready candidate with the highest score is selected, the last one breaking ties.
+    fn select_candidate(&self, candidates: &[OptimizationProperties]) -> Option<usize> {
+        let mut best_index = None;
+        let mut best_score = 0;
+
+        for (i, properties) in candidates.iter().enumerate() {
+            if properties.ready && properties.score >= best_score {
+                best_index = Some(i);
+                best_score = properties.score;
+            }
+        }
+
+        best_index
+    }
+}
+
+/// The [exploration policy](ExplorationPolicy) used unless another one is set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultExplorationPolicy;
+
+impl ExplorationPolicy for DefaultExplorationPolicy {}
+
+/// The state of the exploration of a stream, given to the [policy](ExplorationPolicy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExplorationState {
+    /// The number of operations registered in the optimization builders.
+    pub num_explored: usize,
+    /// The number of operations queued on the stream.
+    pub num_queued: usize,
+    /// Whether the optimization builders could still fuse more operations.
+    pub still_optimizing: bool,
+}
+
+/// The [policy](ExplorationPolicy), [cost model](CostModel), [limits](FusionSettings) and
+/// [filter](FusionFilter) used by the explorers of a device.
+#[derive(Clone)]
+pub(crate) struct ExplorationSettings {
+    pub(crate) policy: Arc<dyn ExplorationPolicy>,
+    pub(crate) cost_model: Arc<dyn CostModel>,
+    pub(crate) limits: FusionSettings,
+    pub(crate) filter: Arc<FusionFilter>,
+}
+
+impl ExplorationSettings {
+    /// The number of operations, from the start of a block, that can be fused.
+    pub(crate) fn num_fusable(&self, operations: &[OperationIr]) -> usize {
+        let num_allowed = operations
+            .iter()
+            .position(|operation| !self.filter.can_fuse(operation))
+            .unwrap_or(operations.len());
+
+        match self.limits.is_bounded() {
+            true => self.limits.num_fusable(&operations[..num_allowed]),
+            false => num_allowed,
+        }
+    }
+}
+
+impl Default for ExplorationSettings {
+    fn default() -> Self {
+        Self {
+            policy: Arc::new(DefaultExplorationPolicy),
+            cost_model: Arc::new(AlwaysFuse),
+            limits: FusionSettings::default(),
+            filter: Arc::new(FusionFilter::from_env()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_select_the_last_best_ready_candidate_by_default() {
+        let candidate = |score, ready| OptimizationProperties { score, ready };
+        let policy = DefaultExplorationPolicy;
+
+        assert_eq!(policy.select_candidate(&[]), None);
+        assert_eq!(policy.select_candidate(&[candidate(10, false)]), None);
+        assert_eq!(
+            policy.select_candidate(&[
+                candidate(2, true),
+                candidate(10, false),
+                candidate(2, true),
+                candidate(1, true),
+            ]),
+            Some(2)
+        );
+    }
+}
@@ -1,15 +1,50 @@
-use std::sync::Arc;
+#[cfg(feature = "plan-export")]
+use std::{io::Write, path::Path};
+use std::{sync::Arc, task::Poll, time::Duration};
+use web_time::Instant;
 
 use crate::{
-    FusionBackend, FusionRuntime,
-    stream::{MultiStream, OperationStreams, StreamId, execution::Operation},
+    CostModel, CustomOp, ExplorationPolicy, FusionBackend, FusionConfig, FusionError, FusionFilter,
+    FusionRuntime, ReadCache, ReadCacheStats, ReadSource, TensorKind,
+    debug::{
+        CrossStreamReport, DetailedPlans, FusionDebugSummary, FusionExplanation, FusionHook,
+        FusionSnapshot, Mirror, MirrorCheck, MirrorDivergence, MirrorOptions, OperationHistogram,
+        PlanCacheStats, SnapshotOptions, StreamInfo, TriggerReport,
+    },
+    memory::{
+        DefragmentationReport, FragmentationReport, MemoryPressureReport, MemoryPressureSignal,
+        MemoryReport, OrphanReport, TrackedTensor, compact_handles, tracked_tensors,
+    },
+    stream::{
+        AheadOfTimeReport, EventId, ExecutionPlanId, ExecutionPlanStoreStats, ExecutionSignal,
+        FusionCheckpoint, MultiStream, OperationStreams, OutputPoolStats, PlanTrigger, RewriteRule,
+        ScalarParameterization, StreamId, StreamPriority, capture::GraphCapture,
+        execution::Operation, is_deterministic, store::ExecutionPlanStoreState,
+    },
 };
-use burn_ir::{HandleContainer, OperationIr, TensorId, TensorIr};
-use burn_tensor::TensorData;
+use burn_common::{future::DynFut, reader::try_read_sync};
+use burn_ir::{
+    Dim, Handle, HandleContainer, OperationIr, TensorId, TensorIr, TensorMetadata, TensorStatus,
+};
+use burn_tensor::{DType, TensorData};
+use hashbrown::{HashMap, HashSet};
+
+#[cfg(feature = "plan-export")]
+use crate::stream::PlanExportMode;
 
 pub struct FusionServer<R: FusionRuntime> {
     streams: MultiStream<R>,
     pub(crate) handles: HandleContainer<R::FusionHandle>,
+    device: R::FusionDevice,
+    mirror: Option<Mirror<R>>,
+    /// Whether a thread is draining the idle streams.
+    idle_watcher: bool,
+    /// The [custom operations](CustomOp) registered on the device, by name.
+    custom_ops: HashMap<String, Arc<dyn CustomOp<R>>>,
+    memory_pressure: MemoryPressureSignal,
+    /// The number of executed operations when the reserved memory was last checked.
+    memory_checked_at: u64,
+    read_cache: ReadCache,
 }
 
 impl<R> FusionServer<R>
@@ -20,6 +55,13 @@ where
         Self {
             streams: MultiStream::new(device.clone()),
             handles: HandleContainer::new(),
+            device,
+            mirror: None,
+            idle_watcher: false,
+            custom_ops: HashMap::new(),
+            memory_pressure: MemoryPressureSignal::default(),
+            memory_checked_at: 0,
+            read_cache: ReadCache::default(),
         }
     }
 
@@ -29,16 +71,558 @@ where
         repr: OperationIr,
         operation: Arc<dyn Operation<R>>,
     ) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("fusion.register", stream = %streams.current).entered();
+
+        self.streams.check_queue_limits(streams.current, &repr);
+        self.streams.on_operation_registered(streams.current, &repr);
+        self.read_cache.invalidate(repr.nodes());
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.register_operation(&repr, operation.as_ref());
+        }
         self.streams
             .register(streams, repr, operation, &mut self.handles)
     }
 
+    /// Register the operation once checked, instead of panicking later when it's executed.
+    ///
+    /// The operation must be consistent, and the tensors it reads must be tracked.
+    pub fn try_register(
+        &mut self,
+        streams: OperationStreams,
+        repr: OperationIr,
+        operation: Arc<dyn Operation<R>>,
+    ) -> Result<(), FusionError> {
+        burn_ir::validate(core::slice::from_ref(&repr)).map_err(FusionError::invalid_operation)?;
+
+        if let Some(tensor) = repr
+            .nodes()
+            .into_iter()
+            .filter(|tensor| tensor.status != TensorStatus::NotInit)
+            .find(|tensor| self.handles.handle(&tensor.id).is_none())
+        {
+            return Err(FusionError::TensorNotFound { tensor: tensor.id });
+        }
+
+        self.register(streams, repr, operation);
+        Ok(())
+    }
+
+    pub fn exceeds_queue_limits(&self, id: StreamId) -> bool {
+        self.streams.exceeds_queue_limits(id)
+    }
+
+    pub(crate) fn execution_signal(&self) -> Arc<ExecutionSignal> {
+        self.streams.execution_signal()
+    }
+
+    pub fn config(&self) -> FusionConfig {
+        self.streams.config()
+    }
+
+    pub fn set_config(&mut self, config: FusionConfig) {
+        self.streams.set_config(config);
+        self.read_cache.set_capacity(config.read_cache_bytes);
+    }
+
+    /// Whether a thread must be started to [drain the idle streams](Self::drain_idle_streams),
+    /// which is only the case while operations are queued with an idle timeout and no thread is
+    /// already draining them.
+    pub fn arm_idle_watcher(&mut self) -> bool {
+        let start = !self.idle_watcher
+            && !is_deterministic()
+            && self.config().idle_timeout.is_some()
+            && self.streams.num_queued_operations() > 0;
+        self.idle_watcher |= start;
+        start
+    }
+
+    pub fn set_stream_priority(&mut self, id: StreamId, priority: StreamPriority) {
+        self.streams.set_stream_priority(id, priority);
+    }
+
+    pub fn set_scalar_parameterization(&mut self, parameterization: ScalarParameterization) {
+        self.streams.set_scalar_parameterization(parameterization);
+    }
+
+    pub fn register_custom_op(&mut self, op: Arc<dyn CustomOp<R>>) {
+        self.custom_ops.insert(op.name().to_string(), op);
+    }
+
+    pub fn custom_op(&self, name: &str) -> Option<Arc<dyn CustomOp<R>>> {
+        self.custom_ops.get(name).cloned()
+    }
+
+    pub fn register_rewrite(&mut self, rule: Box<dyn RewriteRule<R>>) {
+        self.streams.register_rewrite(rule);
+    }
+
+    pub fn set_exploration_policy(&mut self, policy: Arc<dyn ExplorationPolicy>) {
+        self.streams.set_exploration_policy(policy);
+    }
+
+    pub fn set_cost_model(&mut self, cost_model: Arc<dyn CostModel>) {
+        self.streams.set_cost_model(cost_model);
+    }
+
+    pub fn set_fusion_filter(&mut self, filter: FusionFilter) {
+        self.streams.set_fusion_filter(filter);
+    }
+
+    /// Drain the streams idle at the given instant, returning how long to wait before the next
+    /// check, or `None` when the idle timeout was disabled or every queue is empty, in which case
+    /// the thread draining them should stop until it's [armed](Self::arm_idle_watcher) again.
+    pub fn drain_idle_streams(&mut self, now: Instant) -> Option<Duration> {
+        let next = self.streams.drain_idle_streams(&mut self.handles, now);
+        self.idle_watcher = next.is_some();
+
+        next
+    }
+
     pub fn drain_stream(&mut self, id: StreamId) {
         self.streams.drain(&mut self.handles, id)
     }
 
-    pub fn create_empty_handle(&mut self) -> TensorId {
-        self.handles.create_tensor_uninit()
+    /// Bind the buffers of the inputs to new tensors and start capturing the stream, returning
+    /// the ids of the new tensors.
+    pub fn begin_capture(
+        &mut self,
+        id: StreamId,
+        inputs: Vec<(TensorIr, StreamId)>,
+    ) -> Vec<TensorId> {
+        self.drain_stream(id);
+
+        let placeholders = inputs
+            .into_iter()
+            .map(|(input, stream)| {
+                self.drain_stream(stream);
+                let handle = self.handles.get_handle(&input.id, &input.status);
+                self.streams.mark_read(stream, &input, &self.handles);
+                self.register_handle(handle, input.shape, input.dtype)
+            })
+            .collect();
+        self.streams.begin_capture(id);
+
+        placeholders
+    }
+
+    pub fn end_capture(
+        &mut self,
+        id: StreamId,
+        inputs: Vec<TensorIr>,
+        outputs: Vec<TensorIr>,
+    ) -> Result<GraphCapture<R>, FusionError> {
+        self.drain_stream(id);
+
+        let steps = self
+            .streams
+            .end_capture(id)
+            .ok_or_else(|| FusionError::GraphReplay {
+                reason: format!("{id} isn't being captured"),
+            })?;
+
+        Ok(GraphCapture::new(steps, inputs, outputs))
+    }
+
+    /// Execute the captured graph with the inputs, returning the ids of its outputs.
+    pub fn replay_graph(
+        &mut self,
+        graph: &GraphCapture<R>,
+        inputs: Vec<(TensorIr, StreamId)>,
+    ) -> Result<Vec<TensorId>, FusionError> {
+        for (input, stream) in inputs.iter() {
+            self.drain_stream(*stream);
+            self.streams.mark_read(*stream, input, &self.handles);
+        }
+        let (inputs, _): (Vec<_>, Vec<_>) = inputs.into_iter().unzip();
+
+        if let Err(err) = graph.tensors.check_inputs(&inputs) {
+            for input in inputs.iter() {
+                if input.status == TensorStatus::ReadWrite {
+                    self.handles.remove_handle(input.id);
+                }
+            }
+            return Err(err);
+        }
+
+        let stashed = graph.tensors.stash(&mut self.handles);
+        graph.tensors.bind_inputs(&mut self.handles, &inputs);
+        let outputs = self
+            .streams
+            .replay(&graph.steps, &mut self.handles)
+            .map(|_| graph.tensors.outputs_handles(&mut self.handles));
+        graph.tensors.restore(&mut self.handles, stashed);
+
+        Ok(outputs?
+            .into_iter()
+            .zip(graph.tensors.outputs())
+            .map(|(handle, output)| {
+                self.register_handle(handle, output.shape.clone(), output.dtype)
+            })
+            .collect())
+    }
+
+    pub fn record_event(&mut self, id: StreamId) -> EventId {
+        self.streams.record_event(id)
+    }
+
+    pub fn wait_event(&mut self, id: StreamId, event: EventId) {
+        self.streams.wait_event(&mut self.handles, id, event)
+    }
+
+    pub fn output_pool_stats(&self) -> OutputPoolStats {
+        self.streams.output_pool_stats()
+    }
+
+    pub fn set_output_pool_capacity(&mut self, capacity: usize) {
+        self.streams.set_output_pool_capacity(capacity)
+    }
+
+    pub fn execution_plan_stats(&self) -> ExecutionPlanStoreStats {
+        self.streams.execution_plan_stats()
+    }
+
+    pub fn set_execution_plan_capacity(
+        &mut self,
+        capacity: Option<usize>,
+    ) -> Result<(), FusionError> {
+        self.streams.set_execution_plan_capacity(capacity)
+    }
+
+    pub fn pin_plan(&mut self, id: ExecutionPlanId) -> Result<(), FusionError> {
+        self.streams.pin_plan(id)
+    }
+
+    pub fn add_plan_trigger(
+        &mut self,
+        id: ExecutionPlanId,
+        trigger: PlanTrigger,
+    ) -> Result<(), FusionError> {
+        self.streams.add_plan_trigger(id, trigger)
+    }
+
+    pub fn unpin_plan(&mut self, id: ExecutionPlanId) -> bool {
+        self.streams.unpin_plan(id)
+    }
+
+    pub fn pinned_plans(&self) -> Vec<ExecutionPlanId> {
+        self.streams.pinned_plans()
+    }
+
+    pub fn pin_executed_plans(&mut self, enabled: bool) -> bool {
+        self.streams.pin_executed_plans(enabled)
+    }
+
+    #[cfg(feature = "plan-export")]
+    pub fn export_plans(&self, path: &Path) -> Result<usize, FusionError> {
+        self.streams.export_plans(path)
+    }
+
+    #[cfg(feature = "plan-export")]
+    pub fn export_plans_ndjson(
+        &mut self,
+        writer: &mut dyn Write,
+        mode: PlanExportMode,
+    ) -> Result<usize, FusionError> {
+        self.streams.export_plans_ndjson(writer, mode)
+    }
+
+    #[cfg(feature = "plan-export")]
+    pub fn import_plans(&mut self, path: &Path) -> Result<usize, FusionError> {
+        self.streams.import_plans(path)
+    }
+
+    #[cfg(feature = "plan-export")]
+    pub fn export_plans_to_bytes(&self) -> Result<Vec<u8>, FusionError> {
+        self.streams.export_plans_to_bytes()
+    }
+
+    #[cfg(feature = "plan-export")]
+    pub fn import_plans_from_bytes(&mut self, bytes: &[u8]) -> Result<usize, FusionError> {
+        self.streams.import_plans_from_bytes(bytes)
+    }
+
+    pub fn plans_state(&self) -> ExecutionPlanStoreState<R::OptimizationState> {
+        self.streams.plans_state()
+    }
+
+    pub fn import_plans_state(
+        &mut self,
+        state: ExecutionPlanStoreState<R::OptimizationState>,
+    ) -> usize {
+        self.streams.import_plans_state(state)
+    }
+
+    pub fn compile_ahead_of_time(&mut self, operations: &[OperationIr]) -> AheadOfTimeReport {
+        self.streams.compile_ahead_of_time(operations)
+    }
+
+    pub fn collect_finished_stream_plans(&mut self) -> Vec<ExecutionPlanId> {
+        self.streams.collect_finished_stream_plans()
+    }
+
+    pub fn register_debug_hook(&mut self, hook: Box<dyn FusionHook>) {
+        self.streams.register_hook(hook);
+    }
+
+    pub fn operation_histogram(&self) -> OperationHistogram {
+        self.streams.operation_histogram()
+    }
+
+    pub fn trigger_report(&self) -> TriggerReport {
+        self.streams.trigger_report()
+    }
+
+    pub fn explain_fusion(&self, operations: &[OperationIr]) -> FusionExplanation {
+        self.streams.explain_fusion(operations)
+    }
+
+    pub fn debug_cache_stats(&self) -> PlanCacheStats {
+        self.streams.cache_stats()
+    }
+
+    pub fn debug_detailed_plans(&self) -> DetailedPlans {
+        self.streams.detailed_plans()
+    }
+
+    pub fn cross_stream_report(&self) -> CrossStreamReport {
+        self.streams.cross_stream_report()
+    }
+
+    pub fn tensor_fingerprint(&self, id: TensorId) -> u64 {
+        self.streams.tensor_fingerprint(id)
+    }
+
+    pub fn bind_symbolic_dim(&mut self, tensor: &TensorIr, axis: usize, name: &str) {
+        self.streams.bind_symbolic_dim(tensor, axis, name);
+    }
+
+    pub fn symbolic_dims(&self, tensor: &TensorIr) -> Vec<Dim> {
+        self.streams.symbolic_dims(tensor)
+    }
+
+    pub fn debug_stream_info(&self) -> Vec<StreamInfo> {
+        self.streams.stream_info()
+    }
+
+    pub fn debug_summary(&self) -> FusionDebugSummary {
+        self.streams.debug_summary()
+    }
+
+    pub fn debug_snapshot(&self, options: SnapshotOptions) -> FusionSnapshot {
+        self.streams.debug_snapshot(options)
+    }
+
+    pub fn memory_report(&self) -> MemoryReport {
+        MemoryReport::new::<R>(&self.handles, self.streams.queued_memory())
+    }
+
+    pub fn fragmentation_report(&self) -> FragmentationReport {
+        FragmentationReport::new::<R>(&self.handles, &self.device)
+    }
+
+    pub fn tracked_tensors(&self) -> Vec<TrackedTensor> {
+        tracked_tensors::<R>(&self.handles)
+    }
+
+    pub fn checkpoint(&self) -> FusionCheckpoint<R> {
+        FusionCheckpoint {
+            handles: self.handles.fork(),
+            streams: self.streams.checkpoint(),
+        }
+    }
+
+    pub fn restore(&mut self, checkpoint: &FusionCheckpoint<R>) {
+        self.handles = checkpoint.handles.fork();
+        self.streams.restore(&checkpoint.streams, &mut self.handles);
+        // Tensor ids are handed out again, so the cached reads could belong to other tensors.
+        self.read_cache.clear();
+    }
+
+    pub fn num_tensors_created(&self) -> u64 {
+        self.handles.num_tensors_created()
+    }
+
+    pub fn orphaned_handles(
+        &mut self,
+        since: u64,
+        live: &HashSet<TensorId>,
+        collect: bool,
+    ) -> OrphanReport {
+        let queued = self.streams.queued_tensors();
+        let tensors = tracked_tensors::<R>(&self.handles)
+            .into_iter()
+            .filter(|tensor| tensor.id.value() >= since)
+            .filter(|tensor| !tensor.pinned)
+            .filter(|tensor| !live.contains(&tensor.id) && !queued.contains(&tensor.id))
+            .collect::<Vec<_>>();
+
+        if collect && !tensors.is_empty() {
+            for tensor in tensors.iter() {
+                self.handles.remove_handle(tensor.id);
+            }
+            R::memory_cleanup(&self.device);
+        }
+
+        OrphanReport {
+            tensors,
+            collected: collect,
+        }
+    }
+
+    pub fn memory_pressure_signal(&self) -> MemoryPressureSignal {
+        self.memory_pressure.clone()
+    }
+
+    /// Whether the memory pressure was signaled, or the memory reserved exceeds the limit of the
+    /// [policy](crate::MemoryPressurePolicy) since operations were last executed.
+    pub fn take_memory_pressure(&mut self) -> bool {
+        if self.memory_pressure.take() {
+            return true;
+        }
+
+        let max_reserved_bytes = match self.config().memory_pressure.max_reserved_bytes {
+            Some(max) => max,
+            None => return false,
+        };
+        let num_executed = self.streams.num_executed();
+        if num_executed == self.memory_checked_at {
+            return false;
+        }
+        self.memory_checked_at = num_executed;
+
+        R::memory_reserved(&self.device).is_some_and(|reserved| reserved > max_reserved_bytes)
+    }
+
+    /// Drain every stream and release what the [policy](crate::MemoryPressurePolicy) allows.
+    ///
+    /// The `references` are the tensors still referenced, used to collect the orphaned handles
+    /// when tracked.
+    pub fn relieve_memory_pressure(
+        &mut self,
+        references: Option<(u64, &HashSet<TensorId>)>,
+    ) -> MemoryPressureReport {
+        let policy = self.config().memory_pressure;
+        let bytes_reserved_before = R::memory_reserved(&self.device);
+
+        let num_drained = self.streams.num_queued_operations();
+        self.streams.drain_all(&mut self.handles);
+        self.streams.clear_output_pool();
+
+        let orphans = match references {
+            Some((since, live)) if policy.collect_orphans => {
+                self.orphaned_handles(since, live, true)
+            }
+            _ => OrphanReport::default(),
+        };
+        let mut evicted_plans = policy
+            .keep_plans
+            .map(|num_plans| self.streams.evict_plans_down_to(num_plans))
+            .unwrap_or_default();
+        evicted_plans.sort_unstable();
+
+        R::memory_cleanup(&self.device);
+        self.memory_pressure.take();
+        self.memory_checked_at = self.streams.num_executed();
+
+        MemoryPressureReport {
+            bytes_reserved_before,
+            bytes_reserved_after: R::memory_reserved(&self.device),
+            num_drained,
+            orphans,
+            evicted_plans,
+        }
+    }
+
+    pub fn defragment(&mut self) -> DefragmentationReport {
+        self.streams.drain_all(&mut self.handles);
+        // Pooled buffers would keep the old allocations alive.
+        self.streams.clear_output_pool();
+
+        let before = self.fragmentation_report();
+        let num_moved = compact_handles::<R>(&mut self.handles);
+        R::memory_cleanup(&self.device);
+        let after = self.fragmentation_report();
+
+        DefragmentationReport {
+            before,
+            after,
+            num_moved,
+        }
+    }
+
+    pub fn enable_mirror(&mut self, options: MirrorOptions) {
+        // Operations already queued wouldn't be mirrored.
+        self.streams.drain_all(&mut self.handles);
+        self.mirror = Some(Mirror::new(options, &self.handles));
+    }
+
+    pub fn mirror_sync<B>(&mut self) -> Option<MirrorDivergence>
+    where
+        B: FusionBackend<FusionRuntime = R>,
+    {
+        self.streams.drain_all(&mut self.handles);
+
+        let mirror = self.mirror.as_mut()?;
+        mirror.sync(&mut self.handles, |handles, tensor| {
+            try_read_sync(read_tensor::<B>(
+                handles,
+                tensor,
+                TensorKind::of(tensor.dtype),
+            ))
+        })
+    }
+
+    pub fn mirror_divergence(&self) -> Option<MirrorDivergence> {
+        self.mirror.as_ref().and_then(|mirror| mirror.divergence())
+    }
+
+    pub fn pin_tensor(&mut self, id: TensorId, stream: StreamId) -> bool {
+        // The tensor might not be initialized yet.
+        self.drain_stream(stream);
+        self.handles.pin(id)
+    }
+
+    pub fn unpin_tensor(&mut self, id: TensorId) -> bool {
+        self.handles.unpin(id)
+    }
+
+    pub fn pinned_tensors(&self) -> Vec<TensorId> {
+        let mut ids = self.handles.pinned().collect::<Vec<_>>();
+        ids.sort_by_key(|id| id.value());
+        ids
+    }
+
+    pub fn create_empty_handle(&mut self, shape: Vec<usize>, dtype: DType) -> TensorId {
+        let id = self.handles.create_tensor_uninit();
+        self.handles
+            .register_metadata(id, TensorMetadata { shape, dtype });
+        id
+    }
+
+    pub fn register_handle(
+        &mut self,
+        handle: R::FusionHandle,
+        shape: Vec<usize>,
+        dtype: DType,
+    ) -> TensorId {
+        let id = self.create_empty_handle(shape, dtype);
+
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.register_handle(id, &handle);
+        }
+
+        self.handles.register_handle(id, handle);
+        id
+    }
+
+    fn mirror_check<B>(&mut self, tensor: &TensorIr) -> Option<MirrorCheck>
+    where
+        B: FusionBackend<FusionRuntime = R>,
+    {
+        self.mirror.as_mut()?.check(tensor, |handles, tensor| {
+            read_tensor::<B>(handles, tensor, TensorKind::of(tensor.dtype))
+        })
     }
 
     pub fn read_float<B>(
@@ -49,12 +633,9 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        // Make sure all registered operations are executed.
-        // The underlying backend can still be async.
-        self.drain_stream(id);
-        let tensor_float = self.handles.get_float_tensor::<B>(&tensor);
-        self.streams.mark_read(id, &tensor, &self.handles);
-        B::float_into_data(tensor_float)
+        self.read_with::<B, _, _>(tensor, id, |handles, tensor| {
+            B::float_into_data(handles.get_float_tensor::<B>(tensor))
+        })
     }
 
     pub fn read_int<B>(
@@ -65,12 +646,9 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        // Make sure all registered operations are executed.
-        // The underlying backend can still be async.
-        self.drain_stream(id);
-        let tensor_int = self.handles.get_int_tensor::<B>(&tensor);
-        self.streams.mark_read(id, &tensor, &self.handles);
-        B::int_into_data(tensor_int)
+        self.read_with::<B, _, _>(tensor, id, |handles, tensor| {
+            B::int_into_data(handles.get_int_tensor::<B>(tensor))
+        })
     }
 
     pub fn read_bool<B>(
@@ -81,12 +659,9 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        // Make sure all registered operations are executed.
-        // The underlying backend can still be async.
-        self.drain_stream(id);
-        let tensor_bool = self.handles.get_bool_tensor::<B>(&tensor);
-        self.streams.mark_read(id, &tensor, &self.handles);
-        B::bool_into_data(tensor_bool)
+        self.read_with::<B, _, _>(tensor, id, |handles, tensor| {
+            B::bool_into_data(handles.get_bool_tensor::<B>(tensor))
+        })
     }
 
     pub fn read_quantized<B>(
@@ -97,35 +672,114 @@ where
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        // Make sure all registered operations are executed.
-        // The underlying backend can still be async.
+        self.read_with::<B, _, _>(tensor, id, |handles, tensor| {
+            B::q_into_data(handles.get_quantized_tensor::<B>(tensor))
+        })
+    }
+
+    /// Read the tensor with the given function, unless its data is in the
+    /// [read cache](ReadCache).
+    fn read_with<B, F, Read>(
+        &mut self,
+        tensor: TensorIr,
+        id: StreamId,
+        read: Read,
+    ) -> impl Future<Output = TensorData> + Send + use<R, B, F, Read>
+    where
+        B: FusionBackend<FusionRuntime = R>,
+        F: Future<Output = TensorData> + Send,
+        Read: FnOnce(&mut HandleContainer<R::FusionHandle>, &TensorIr) -> F,
+    {
+        let source = match self.read_cache.get(&tensor) {
+            Some(data) => ReadSource::Cached(data),
+            None => {
+                // Make sure all registered operations are executed.
+                // The underlying backend can still be async.
+                self.drain_stream(id);
+                let check = self.mirror_check::<B>(&tensor);
+                let data = read(&mut self.handles, &tensor);
+                self.streams.mark_read(id, &tensor, &self.handles);
+                ReadSource::Device(self.read_cache.begin(&tensor), verify(data, check))
+            }
+        };
+
+        source.resolve()
+    }
+
+    /// Read a tensor of the given kind, returning an error instead of panicking when the tensor
+    /// has no buffer once the stream is drained.
+    pub fn try_read<B>(
+        &mut self,
+        tensor: TensorIr,
+        kind: TensorKind,
+        id: StreamId,
+    ) -> Result<DynFut<TensorData>, FusionError>
+    where
+        B: FusionBackend<FusionRuntime = R>,
+    {
+        if let Some(data) = self.read_cache.get(&tensor) {
+            return Ok(Box::pin(async move { data }));
+        }
         self.drain_stream(id);
-        let tensor_q = self.handles.get_quantized_tensor::<B>(&tensor);
+
+        match self.handles.handle(&tensor.id) {
+            Some(Handle::Existing(_)) if tensor.status != TensorStatus::NotInit => {}
+            Some(_) => return Err(FusionError::TensorNotInitialized { tensor: tensor.id }),
+            None => return Err(FusionError::TensorNotFound { tensor: tensor.id }),
+        }
+
+        let check = self.mirror_check::<B>(&tensor);
+        let data = read_tensor::<B>(&mut self.handles, &tensor, kind);
         self.streams.mark_read(id, &tensor, &self.handles);
-        B::q_into_data(tensor_q)
+        let source = ReadSource::Device(self.read_cache.begin(&tensor), verify(data, check));
+
+        Ok(Box::pin(source.resolve()))
     }
 
-    pub fn change_server_float<B>(
+    /// Read multiple tensors of the stream at once, draining it only once.
+    ///
+    /// The tensors can have different kinds and data types, and are read concurrently once the
+    /// returned future is polled. The stream isn't drained when every tensor is in the
+    /// [read cache](ReadCache).
+    pub fn read_batch<B>(
         &mut self,
-        tensor: &TensorIr,
-        device: &R::FusionDevice,
-        server_device: &mut Self,
-    ) -> TensorId
+        tensors: Vec<(TensorIr, TensorKind)>,
+        id: StreamId,
+    ) -> impl Future<Output = Vec<TensorData>> + Send + use<R, B>
     where
         B: FusionBackend<FusionRuntime = R>,
     {
-        let tensor_float = self.handles.get_float_tensor::<B>(tensor);
-        self.streams
-            .mark_read(StreamId::current(), tensor, &self.handles);
+        let cached = tensors
+            .iter()
+            .map(|(tensor, _)| self.read_cache.get(tensor))
+            .collect::<Vec<_>>();
+        if cached.iter().any(Option::is_none) {
+            self.drain_stream(id);
+        }
 
-        let tensor = B::float_to_device(tensor_float, device);
-        let id = server_device.create_empty_handle();
+        let reads = tensors
+            .iter()
+            .zip(cached)
+            .map(|((tensor, kind), cached)| {
+                let source = match cached {
+                    Some(data) => ReadSource::Cached(data),
+                    None => {
+                        let check = self.mirror_check::<B>(tensor);
+                        let data = read_tensor::<B>(&mut self.handles, tensor, *kind);
+                        self.streams.mark_read(id, tensor, &self.handles);
+                        ReadSource::Device(self.read_cache.begin(tensor), verify(data, check))
+                    }
+                };
 
-        server_device
-            .handles
-            .register_float_tensor::<B>(&id, tensor.clone());
+                Box::pin(source.resolve()) as DynFut<TensorData>
+            })
+            .collect();
 
-        id
+        join_all(reads)
+    }
+
+    pub fn read_cache_stats(&self) -> ReadCacheStats {
+        self.read_cache.stats()
     }
 
     pub fn resolve_server_float<B>(&mut self, tensor: &TensorIr) -> B::FloatTensorPrimitive
@@ -148,68 +802,190 @@ where
     {
         self.handles.get_bool_tensor::<B>(tensor)
     }
+}
 
-    pub fn change_server_int<B>(
-        &mut self,
-        tensor: &TensorIr,
-        device: &R::FusionDevice,
-        server_device: &mut Self,
-    ) -> TensorId
-    where
-        B: FusionBackend<FusionRuntime = R>,
-    {
-        let tensor_int = self.handles.get_int_tensor::<B>(tensor);
-        self.streams
-            .mark_read(StreamId::current(), tensor, &self.handles);
-        let tensor = B::int_to_device(tensor_int, device);
-        let id = server_device.create_empty_handle();
+/// Read the tensor with the [backend](FusionBackend) method of its kind.
+fn read_tensor<B: FusionBackend>(
+    handles: &mut HandleContainer<B::Handle>,
+    tensor: &TensorIr,
+    kind: TensorKind,
+) -> DynFut<TensorData> {
+    match kind {
+        TensorKind::Float => Box::pin(B::float_into_data(handles.get_float_tensor::<B>(tensor))),
+        TensorKind::Int => Box::pin(B::int_into_data(handles.get_int_tensor::<B>(tensor))),
+        TensorKind::Bool => Box::pin(B::bool_into_data(handles.get_bool_tensor::<B>(tensor))),
+        TensorKind::Quantized => {
+            Box::pin(B::q_into_data(handles.get_quantized_tensor::<B>(tensor)))
+        }
+    }
+}
 
-        server_device
-            .handles
-            .register_int_tensor::<B>(&id, tensor.clone());
+/// Await the futures concurrently, so that the reads submitted when first polled overlap.
+async fn join_all<T>(futures: Vec<DynFut<T>>) -> Vec<T> {
+    let mut futures = futures
+        .into_iter()
+        .map(|future| (future, None))
+        .collect::<Vec<_>>();
 
-        id
+    core::future::poll_fn(|cx| {
+        let mut pending = false;
+
+        for (future, output) in futures.iter_mut().filter(|(_, output)| output.is_none()) {
+            match future.as_mut().poll(cx) {
+                Poll::Ready(value) => *output = Some(value),
+                Poll::Pending => pending = true,
+            }
+        }
+
+        match pending {
+            true => Poll::Pending,
+            false => Poll::Ready(
+                futures
+                    .iter_mut()
+                    .map(|(_, output)| output.take().expect("Should be ready"))
+                    .collect(),
+            ),
+        }
+    })
+    .await
+}
+
+/// Compare the data with its mirrored reference, if any, once it is read.
+async fn verify(
+    data: impl Future<Output = TensorData> + Send,
+    check: Option<MirrorCheck>,
+) -> TensorData {
+    let data = data.await;
+
+    if let Some(check) = check {
+        check.verify(&data).await;
     }
 
-    pub fn change_server_bool<B>(
-        &mut self,
-        tensor: &TensorIr,
-        device: &R::FusionDevice,
-        server_device: &mut Self,
-    ) -> TensorId
-    where
-        B: FusionBackend<FusionRuntime = R>,
-    {
-        let tensor_bool = self.handles.get_bool_tensor::<B>(tensor);
-        self.streams
-            .mark_read(StreamId::current(), tensor, &self.handles);
-        let tensor = B::bool_to_device(tensor_bool, device);
-        let id = server_device.create_empty_handle();
+    data
+}
 
-        server_device
-            .handles
-            .register_bool_tensor::<B>(&id, tensor.clone());
+#[cfg(test)]
+mod tests {
+    use burn_common::future::block_on;
 
-        id
+    use super::*;
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn should_trace_registration_exploration_and_execution() {
+        use crate::{
+            Fusion,
+            client::FusionClient,
+            test_utils::{TestBackend, TestClient, float_data, float_tensor},
+        };
+        use burn_ndarray::NdArrayDevice;
+        use burn_tensor::{TensorData, ops::FloatTensorOps};
+        use std::sync::Mutex;
+        use tracing::{
+            Event, Metadata, Subscriber,
+            span::{Attributes, Id, Record},
+        };
+
+        /// Records the name of every span created.
+        #[derive(Default)]
+        struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+
+        impl Subscriber for SpanNames {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut names = self.0.lock().unwrap();
+                names.push(span.metadata().name());
+                Id::from_u64(names.len() as u64)
+            }
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+            fn event(&self, _event: &Event<'_>) {}
+            fn enter(&self, _span: &Id) {}
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = SpanNames(names.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let client = TestClient::new(NdArrayDevice::Cpu);
+            let tensor = float_tensor(&client, TensorData::from([0.0f32, 1.0]));
+            let output = Fusion::<TestBackend>::float_exp(tensor);
+
+            float_data(output);
+        });
+
+        let names = names.lock().unwrap();
+        for name in [
+            "fusion.register",
+            "fusion.explore",
+            "fusion.drain",
+            "fusion.execute_plan",
+        ] {
+            assert!(names.contains(&name), "Missing span {name} in {names:?}");
+        }
     }
 
-    pub fn change_server_quantized<B>(
-        &mut self,
-        tensor: &TensorIr,
-        device: &R::FusionDevice,
-        server_device: &mut Self,
-    ) -> TensorId
-    where
-        B: FusionBackend<FusionRuntime = R>,
-    {
-        let tensor = self.handles.get_quantized_tensor::<B>(tensor);
-        let tensor = B::q_to_device(tensor, device);
-        let id = server_device.create_empty_handle();
+    #[test]
+    fn should_drain_idle_streams_only_while_operations_are_queued() {
+        use crate::test_utils::{TestBackend, TestRuntime, exp, tensor, with_lazy_streams};
+        use burn_ir::HandleKind;
+        use burn_ndarray::NdArrayDevice;
 
-        server_device
-            .handles
-            .register_quantized_tensor::<B>(&id, tensor);
+        #[derive(Debug)]
+        struct NoOp;
 
-        id
+        impl Operation<TestRuntime> for NoOp {
+            fn execute(&self, _handles: &mut HandleContainer<HandleKind<TestBackend>>) {}
+        }
+
+        with_lazy_streams(|| {
+            let mut server = FusionServer::<TestRuntime>::new(NdArrayDevice::Cpu);
+            let timeout = Duration::from_millis(1);
+            server.set_config(FusionConfig {
+                idle_timeout: Some(timeout),
+                ..Default::default()
+            });
+
+            assert!(!server.arm_idle_watcher());
+            assert_eq!(server.drain_idle_streams(Instant::now() + timeout), None);
+
+            let operation = exp(
+                tensor(100, TensorStatus::ReadOnly),
+                tensor(101, TensorStatus::NotInit),
+            );
+            server.register(OperationStreams::default(), operation, Arc::new(NoOp));
+            let registered = Instant::now();
+
+            assert!(server.arm_idle_watcher());
+            assert!(!server.arm_idle_watcher());
+
+            let next = server.drain_idle_streams(registered);
+            assert!(next.is_some_and(|next| next <= timeout));
+            assert_eq!(server.streams.num_queued_operations(), 1);
+
+            assert_eq!(server.drain_idle_streams(registered + timeout), None);
+            assert_eq!(server.streams.num_queued_operations(), 0);
+            assert!(!server.arm_idle_watcher());
+        });
+    }
+
+    #[test]
+    fn should_join_futures_in_order() {
+        let mut polled = false;
+        // Pending on its first poll.
+        let late = core::future::poll_fn(move |cx| match polled {
+            true => Poll::Ready(1),
+            false => {
+                polled = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        });
+        let futures: Vec<DynFut<u32>> = vec![Box::pin(late), Box::pin(async { 2 })];
+
+        assert_eq!(block_on(join_all(futures)), [1, 2]);
     }
 }
@@ -0,0 +1,148 @@
+use core::{cell::Cell, marker::PhantomData};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::backend::CLIENTS;
+
+std::thread_local! {
+    /// The [fusion session](FusionSession) bound to the thread, if any.
+    static BOUND_SESSION: Cell<Option<BoundSession>> = const { Cell::new(None) };
+}
+
+/// Identifies a [fusion session](FusionSession).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SessionId {
+    value: u64,
+}
+
+impl SessionId {
+    /// The session of the operations registered while no session is bound.
+    pub const DEFAULT: Self = Self { value: 0 };
+
+    /// The value of the id, `0` for the [default session](Self::DEFAULT).
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+/// Options of a [fusion session](FusionSession).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionOptions {
+    /// Whether the session starts with the execution plans of the default session, imported
+    /// when the session first uses a device.
+    ///
+    /// The plans found by the session are never shared back. Kernels compiled by the runtime
+    /// aren't owned by the fusion servers, so they're reused by every session regardless.
+    pub share_plans: bool,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct BoundSession {
+    pub(crate) id: SessionId,
+    pub(crate) options: SessionOptions,
+}
+
+/// An isolated workload sharing devices with others, e.g. one of multiple models served by the
+/// same process.
+///
+/// Operations registered while the session is [bound](Self::bind) go to fusion servers of their
+/// own on every device, so the session has its own streams, execution plans and debug views,
+/// such as the [summary](crate::Fusion::debug_summary) or the
+/// [plan statistics](crate::Fusion::execution_plan_stats).
+///
+/// Tensors remember the server that created them, so they can be used after the session is
+/// unbound or dropped. However tensors of different sessions can't be used by the same
+/// operation, just like tensors of different devices.
+///
+/// Dropping the session drains its operations on every device.
+pub struct FusionSession {
+    bound: BoundSession,
+}
+
+/// Binds a [fusion session](FusionSession) to the current thread until dropped, restoring the
+/// previously bound session.
+pub struct FusionSessionGuard<'a> {
+    previous: Option<BoundSession>,
+    // The session is bound to the thread, so the guard can't be sent to another one.
+    _session: PhantomData<(&'a (), *const ())>,
+}
+
+/// The session of the operations registered by the current thread.
+pub fn current_session() -> SessionId {
+    bound_session().map_or(SessionId::DEFAULT, |bound| bound.id)
+}
+
+pub(crate) fn bound_session() -> Option<BoundSession> {
+    BOUND_SESSION.get()
+}
+
+impl FusionSession {
+    /// Create a new session with the given options.
+    pub fn new(options: SessionOptions) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        let id = SessionId {
+            value: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        };
+
+        Self {
+            bound: BoundSession { id, options },
+        }
+    }
+
+    /// The id of the session.
+    pub fn id(&self) -> SessionId {
+        self.bound.id
+    }
+
+    /// The options of the session.
+    pub fn options(&self) -> SessionOptions {
+        self.bound.options
+    }
+
+    /// Register the operations of the current thread in the session until the guard is dropped.
+    pub fn bind(&self) -> FusionSessionGuard<'_> {
+        FusionSessionGuard {
+            previous: BOUND_SESSION.replace(Some(self.bound)),
+            _session: PhantomData,
+        }
+    }
+
+    /// Run the function with the session [bound](Self::bind) to the current thread.
+    pub fn run<T>(&self, func: impl FnOnce() -> T) -> T {
+        let _guard = self.bind();
+        func()
+    }
+}
+
+impl Drop for FusionSession {
+    fn drop(&mut self) {
+        CLIENTS.close_session(self.bound.id);
+    }
+}
+
+impl Drop for FusionSessionGuard<'_> {
+    fn drop(&mut self) {
+        BOUND_SESSION.set(self.previous.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_restore_the_previous_session_when_unbound() {
+        let outer = FusionSession::new(SessionOptions::default());
+        let inner = FusionSession::new(SessionOptions { share_plans: true });
+
+        let (bound, nested, restored) = outer.run(|| {
+            let bound = current_session();
+            let nested = inner.run(current_session);
+            (bound, nested, current_session())
+        });
+
+        assert_eq!(bound, outer.id());
+        assert_eq!(nested, inner.id());
+        assert_eq!(restored, outer.id());
+        assert_eq!(current_session(), SessionId::DEFAULT);
+    }
+}
@@ -0,0 +1,127 @@
+use std::{marker::PhantomData, time::Duration};
+
+use burn_ir::OperationIr;
+use hashbrown::HashSet;
+
+use crate::{
+    FusionError, FusionRuntime, Optimization,
+    stream::{
+        OperationConverter, RelativeOps, ScalarParameterization,
+        execution::StreamSegment,
+        store::{ExecutionPlanId, ExecutionPlanStore, ExecutionStrategy},
+    },
+};
+
+/// What was planned and compiled by
+/// [compile_ahead_of_time](crate::Fusion::compile_ahead_of_time).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AheadOfTimeReport {
+    /// The number of operations planned.
+    pub num_operations: usize,
+    /// The number of distinct execution plans covering the operations.
+    pub num_plans: usize,
+    /// The number of execution plans created, the others already being in the store.
+    pub num_created: usize,
+    /// The time spent planning and compiling.
+    pub duration: Duration,
+}
+
+/// Plans the operations of a stream without executing them, compiling the optimizations of
+/// every plan used once.
+pub(crate) struct PlanningSegment<R: FusionRuntime> {
+    global: Vec<OperationIr>,
+    relative: Vec<OperationIr>,
+    converter: OperationConverter,
+    /// The plans used so far.
+    pub(crate) planned: HashSet<ExecutionPlanId>,
+    _runtime: PhantomData<R>,
+}
+
+impl<R: FusionRuntime> PlanningSegment<R> {
+    pub(crate) fn new(scalar_parameterization: ScalarParameterization) -> Self {
+        let mut converter = OperationConverter::default();
+        converter.scalar_parameterization = scalar_parameterization;
+
+        Self {
+            global: Vec::new(),
+            relative: Vec::new(),
+            converter,
+            planned: HashSet::new(),
+            _runtime: PhantomData,
+        }
+    }
+
+    /// Add an operation to the end of the segment.
+    pub(crate) fn push(&mut self, operation: OperationIr) {
+        self.relative
+            .push(operation.to_relative(&mut self.converter));
+        self.global.push(operation);
+    }
+
+    fn reset_relative(&mut self) {
+        self.relative.clear();
+        self.converter.clear();
+
+        for operation in self.global.iter() {
+            self.relative
+                .push(operation.to_relative(&mut self.converter));
+        }
+    }
+}
+
+impl<R: FusionRuntime> StreamSegment<R::Optimization> for &mut PlanningSegment<R> {
+    fn operations(&self) -> &[OperationIr] {
+        &self.relative
+    }
+
+    fn global_operations(&self) -> &[OperationIr] {
+        &self.global
+    }
+
+    fn scalar_parameterization(&self) -> ScalarParameterization {
+        self.converter.scalar_parameterization
+    }
+
+    fn execute(
+        &mut self,
+        id: ExecutionPlanId,
+        store: &mut ExecutionPlanStore<R::Optimization>,
+    ) -> Result<(), FusionError> {
+        let plan = store
+            .get_mut(id)
+            .ok_or(FusionError::PlanNotFound { plan: id })?;
+
+        if self.planned.insert(id) {
+            compile::<R>(&mut plan.optimization.strategy);
+        }
+        let num_planned = plan.operations.len();
+        self.global.drain(0..num_planned);
+        self.reset_relative();
+
+        Ok(())
+    }
+}
+
+fn compile<R: FusionRuntime>(strategy: &mut ExecutionStrategy<R::Optimization>) {
+    match strategy {
+        ExecutionStrategy::Optimization { opt, .. } => opt.compile(),
+        ExecutionStrategy::Operations { .. } => {}
+        ExecutionStrategy::Composed(strategies) => {
+            for strategy in strategies.iter_mut() {
+                compile::<R>(strategy);
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for AheadOfTimeReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\n==== Fusion Ahead Of Time ====\n")?;
+        f.write_fmt(format_args!(" - Operations: {}\n", self.num_operations))?;
+        f.write_fmt(format_args!(
+            " - Plans: {} used, {} created\n",
+            self.num_plans, self.num_created
+        ))?;
+        f.write_fmt(format_args!(" - Duration: {:?}\n", self.duration))
+    }
+}
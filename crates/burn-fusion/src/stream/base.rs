@@ -1 +1,413 @@
+use core::{cell::RefCell, cmp::Reverse, marker::PhantomData};
+use std::sync::{
+    Arc, Weak,
+    atomic::{AtomicU8, AtomicU64, Ordering},
+};
+
 pub use burn_common::id::StreamId;
+use hashbrown::HashMap;
+
+use super::EventId;
+use crate::{Client, FusionRuntime, backend::CLIENTS, client::FusionClient};
+
+std::thread_local! {
+    /// Dropped when the thread exits.
+    static THREAD_TOKEN: Arc<()> = Arc::new(());
+    /// The [fusion stream](FusionStream) bound to the thread, if any.
+    static BOUND_STREAM: RefCell<Option<BoundStream>> = const { RefCell::new(None) };
+}
+
+/// The environment variable enabling [deterministic mode](set_deterministic) when set to `1` or
+/// `true`.
+pub const DETERMINISTIC_ENV: &str = "BURN_FUSION_DETERMINISTIC";
+
+/// The single stream of every operation in [deterministic mode](set_deterministic).
+const DETERMINISTIC_STREAM: StreamId = StreamId { value: 0 };
+
+/// Whether deterministic mode is enabled, `0` until read from the [environment](DETERMINISTIC_ENV).
+static DETERMINISTIC: AtomicU8 = AtomicU8::new(0);
+const DETERMINISTIC_OFF: u8 = 1;
+const DETERMINISTIC_ON: u8 = 2;
+
+/// Enable or disable deterministic mode for every device, overriding the
+/// [environment](DETERMINISTIC_ENV).
+///
+/// In deterministic mode, every operation is registered on the same stream, whatever the thread
+/// or the [fusion stream](FusionStream) registering it, so streams are never synced partially
+/// because of tensors shared between threads. Decisions depending on timing are disabled too:
+/// the idle timeout is ignored, and [flushes](crate::Fusion::flush) drain the stream right away
+/// on the calling thread. Given the same operations in the same order, the same plans are then
+/// explored and executed on every run.
+///
+/// It's meant for debugging numerical differences between runs, e.g. with multi-threaded data
+/// loaders, since operations of independent threads can't overlap anymore.
+pub fn set_deterministic(enabled: bool) {
+    let value = match enabled {
+        true => DETERMINISTIC_ON,
+        false => DETERMINISTIC_OFF,
+    };
+    DETERMINISTIC.store(value, Ordering::Relaxed);
+}
+
+/// Whether [deterministic mode](set_deterministic) is enabled.
+pub fn is_deterministic() -> bool {
+    match DETERMINISTIC.load(Ordering::Relaxed) {
+        0 => {
+            let enabled =
+                std::env::var(DETERMINISTIC_ENV).is_ok_and(|value| parse_deterministic(&value));
+            let value = match enabled {
+                true => DETERMINISTIC_ON,
+                false => DETERMINISTIC_OFF,
+            };
+            // An explicit call to `set_deterministic` wins over the environment.
+            let _ = DETERMINISTIC.compare_exchange(0, value, Ordering::Relaxed, Ordering::Relaxed);
+
+            DETERMINISTIC.load(Ordering::Relaxed) == DETERMINISTIC_ON
+        }
+        value => value == DETERMINISTIC_ON,
+    }
+}
+
+fn parse_deterministic(value: &str) -> bool {
+    matches!(value.trim().to_lowercase().as_str(), "1" | "true")
+}
+
+/// The stream of the operations registered by the current thread.
+///
+/// It's the [fusion stream](FusionStream) bound to the thread, or the stream of the thread
+/// itself. In [deterministic mode](set_deterministic), it's the same stream for every thread.
+pub fn current_stream() -> StreamId {
+    if is_deterministic() {
+        return DETERMINISTIC_STREAM;
+    }
+
+    BOUND_STREAM
+        .with_borrow(|bound| bound.as_ref().map(|bound| bound.id))
+        .unwrap_or_else(StreamId::current)
+}
+
+/// The name of the [fusion stream](FusionStream) bound to the current thread, if any.
+pub(crate) fn current_stream_name() -> Option<String> {
+    BOUND_STREAM.with_borrow(|bound| bound.as_ref().map(|bound| bound.name.to_string()))
+}
+
+#[derive(Clone)]
+struct BoundStream {
+    id: StreamId,
+    name: Arc<str>,
+    token: Weak<()>,
+}
+
+/// A stream created explicitly, instead of being derived from the thread registering the
+/// operations.
+///
+/// Operations registered while the stream is [bound](Self::bind) are queued on it, so a single
+/// thread can interleave independent pipelines, e.g. one for compute and one for transfers.
+/// Tensors remember the stream that created them, and reading them from another stream syncs
+/// their stream first, just like tensors shared between threads.
+///
+/// Dropping the stream drains its queued operations.
+pub struct FusionStream<R: FusionRuntime> {
+    id: StreamId,
+    name: Arc<str>,
+    token: Arc<()>,
+    client: Client<R>,
+}
+
+/// Binds a [fusion stream](FusionStream) to the current thread until dropped, restoring the
+/// previously bound stream.
+pub struct FusionStreamGuard<'a> {
+    previous: Option<BoundStream>,
+    // The stream is bound to the thread, so the guard can't be sent to another one.
+    _stream: PhantomData<(&'a (), *const ())>,
+}
+
+/// Run the function on a new stream, draining it on every device once the function returns.
+///
+/// Operations registered by the function, on any device, are queued on a stream of their own
+/// instead of the stream of the current thread, so requests served one after the other by the
+/// same thread don't share their queues, plans or syncs. The stream is finished once the scope
+/// ends, so its plans can be [collected](crate::Fusion::collect_finished_stream_plans).
+///
+/// Scopes can be nested, each having its own stream. The stream isn't drained when the function
+/// panics.
+pub fn scope<T>(func: impl FnOnce() -> T) -> T {
+    let token = Arc::new(());
+    let bound = BoundStream {
+        id: next_stream_id(),
+        name: "scope".into(),
+        token: Arc::downgrade(&token),
+    };
+
+    let guard = FusionStreamGuard::new(bound);
+    let output = func();
+    // The stream operations are registered on, which is shared in deterministic mode.
+    let stream = current_stream();
+    core::mem::drop(guard);
+
+    CLIENTS.drain_stream(stream);
+    core::mem::drop(token);
+
+    output
+}
+
+/// A new id for a stream that isn't derived from a thread.
+fn next_stream_id() -> StreamId {
+    // Thread streams are hashes of their thread id, so the top bit keeps created streams
+    // apart in practice.
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    let value = NEXT_ID.fetch_add(1, Ordering::Relaxed) | (1 << 63);
+
+    StreamId { value }
+}
+
+impl<R: FusionRuntime> FusionStream<R> {
+    pub(crate) fn new(name: &str, client: Client<R>) -> Self {
+        Self {
+            id: next_stream_id(),
+            name: name.into(),
+            token: Arc::new(()),
+            client,
+        }
+    }
+
+    /// The id of the stream.
+    pub fn id(&self) -> StreamId {
+        self.id
+    }
+
+    /// The name of the stream.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Register the operations of the current thread on the stream until the guard is dropped.
+    pub fn bind(&self) -> FusionStreamGuard<'_> {
+        let bound = BoundStream {
+            id: self.id,
+            name: self.name.clone(),
+            token: Arc::downgrade(&self.token),
+        };
+
+        FusionStreamGuard::new(bound)
+    }
+
+    /// The stream the operations are actually registered on, which is shared by every thread in
+    /// [deterministic mode](set_deterministic).
+    fn registered_id(&self) -> StreamId {
+        match is_deterministic() {
+            true => DETERMINISTIC_STREAM,
+            false => self.id,
+        }
+    }
+
+    /// Run the function with the stream [bound](Self::bind) to the current thread.
+    pub fn run<T>(&self, func: impl FnOnce() -> T) -> T {
+        let _guard = self.bind();
+        func()
+    }
+
+    /// Update the [priority](StreamPriority) of the stream.
+    pub fn set_priority(&self, priority: StreamPriority) {
+        self.client
+            .set_stream_priority(self.registered_id(), priority);
+    }
+
+    /// Record an event after the operations currently queued on the stream.
+    pub fn record_event(&self) -> EventId {
+        self.client.record_event(self.registered_id())
+    }
+
+    /// Wait on an event recorded on another stream, see [wait_event](crate::Fusion::wait_event).
+    pub fn wait_event(&self, event: EventId) {
+        self.client.wait_event(self.registered_id(), event);
+    }
+
+    /// Execute the operations queued on the stream.
+    pub fn sync(&self) {
+        self.client.drain_stream(self.registered_id());
+    }
+}
+
+impl<R: FusionRuntime> Drop for FusionStream<R> {
+    fn drop(&mut self) {
+        self.sync();
+    }
+}
+
+impl FusionStreamGuard<'_> {
+    fn new(bound: BoundStream) -> Self {
+        Self {
+            previous: BOUND_STREAM.with_borrow_mut(|current| current.replace(bound)),
+            _stream: PhantomData,
+        }
+    }
+}
+
+impl Drop for FusionStreamGuard<'_> {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        BOUND_STREAM.with_borrow_mut(|current| *current = previous);
+    }
+}
+
+/// How urgently the operations of a stream are executed when multiple streams must be drained.
+///
+/// Streams are drained one after the other on the same device, so draining latency-critical
+/// streams first, e.g. the path serving inference requests, lets them complete before
+/// best-effort work such as prefetching or metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StreamPriority {
+    /// Drained after every other stream.
+    Low,
+    /// The priority of every stream unless set otherwise.
+    #[default]
+    Normal,
+    /// Drained before every other stream.
+    High,
+}
+
+/// The [priorities](StreamPriority) set for the streams of a device.
+#[derive(Default)]
+pub(crate) struct StreamPriorities {
+    priorities: HashMap<StreamId, StreamPriority>,
+}
+
+impl StreamPriorities {
+    pub(crate) fn set(&mut self, stream: StreamId, priority: StreamPriority) {
+        match priority {
+            StreamPriority::Normal => self.priorities.remove(&stream),
+            priority => self.priorities.insert(stream, priority),
+        };
+    }
+
+    pub(crate) fn get(&self, stream: StreamId) -> StreamPriority {
+        self.priorities.get(&stream).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn forget(&mut self, stream: StreamId) {
+        self.priorities.remove(&stream);
+    }
+
+    /// Sort the streams in the order they should be drained, the most urgent first.
+    ///
+    /// Streams with the same priority are sorted by id, so the order is deterministic.
+    pub(crate) fn sort(&self, streams: &mut [StreamId]) {
+        streams.sort_by_key(|stream| (Reverse(self.get(*stream)), *stream));
+    }
+}
+
+/// Tells whether the thread or the [fusion stream](FusionStream) behind a [stream](StreamId) is
+/// still running.
+#[derive(Debug, Clone)]
+pub(crate) struct StreamLiveness {
+    /// `None` for the stream of [deterministic mode](set_deterministic), which never finishes.
+    token: Option<Weak<()>>,
+}
+
+impl StreamLiveness {
+    /// The liveness of the [current stream](current_stream).
+    ///
+    /// A [fusion stream](FusionStream) is alive until dropped, no matter which thread used it.
+    pub(crate) fn current() -> Self {
+        if is_deterministic() {
+            return Self { token: None };
+        }
+
+        if let Some(token) =
+            BOUND_STREAM.with_borrow(|bound| bound.as_ref().map(|b| b.token.clone()))
+        {
+            return Self { token: Some(token) };
+        }
+
+        THREAD_TOKEN.with(|token| Self {
+            token: Some(Arc::downgrade(token)),
+        })
+    }
+
+    /// Whether the thread or the [fusion stream](FusionStream) of the stream is still running.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.token
+            .as_ref()
+            .is_none_or(|token| token.strong_count() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_finished_threads() {
+        let liveness = std::thread::spawn(StreamLiveness::current).join().unwrap();
+
+        assert!(!liveness.is_alive());
+        assert!(StreamLiveness::current().is_alive());
+    }
+
+    #[test]
+    fn should_run_scopes_on_their_own_stream() {
+        let thread = current_stream();
+
+        let (outer, inner, restored) = scope(|| {
+            let outer = current_stream();
+            let inner = scope(current_stream);
+            (outer, inner, current_stream())
+        });
+
+        assert_ne!(outer, thread);
+        assert_ne!(inner, outer);
+        assert_eq!(restored, outer);
+        assert_eq!(current_stream(), thread);
+    }
+
+    #[test]
+    fn should_parse_deterministic_env() {
+        assert!(parse_deterministic("1"));
+        assert!(parse_deterministic("True "));
+        assert!(!parse_deterministic("0"));
+        assert!(!parse_deterministic(""));
+    }
+
+    #[test]
+    fn should_sort_streams_by_priority() {
+        let stream = |value| StreamId { value };
+        let mut priorities = StreamPriorities::default();
+        priorities.set(stream(3), StreamPriority::High);
+        priorities.set(stream(1), StreamPriority::Low);
+        priorities.set(stream(4), StreamPriority::High);
+        priorities.set(stream(4), StreamPriority::Normal);
+
+        let mut streams = [1, 2, 3, 4].map(stream);
+        priorities.sort(&mut streams);
+
+        assert_eq!(streams.map(|stream| stream.value), [3, 2, 4, 1]);
+    }
+
+    #[test]
+    fn should_bind_streams_until_the_guard_is_dropped() {
+        let thread = current_stream();
+        let token = Arc::new(());
+        let bound = |value| BoundStream {
+            id: StreamId { value },
+            name: "compute".into(),
+            token: Arc::downgrade(&token),
+        };
+
+        let outer = FusionStreamGuard::new(bound(1 << 63));
+        {
+            let _inner = FusionStreamGuard::new(bound((1 << 63) | 1));
+            assert_eq!(current_stream().value, (1 << 63) | 1);
+        }
+        assert_eq!(current_stream().value, 1 << 63);
+        assert_eq!(current_stream_name().as_deref(), Some("compute"));
+
+        // The stream outlives the guard, not the thread.
+        let liveness = StreamLiveness::current();
+        core::mem::drop(outer);
+        assert_eq!(current_stream(), thread);
+        assert!(liveness.is_alive());
+        core::mem::drop(token);
+        assert!(!liveness.is_alive());
+    }
+}
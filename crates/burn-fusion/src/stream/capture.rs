@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use burn_ir::{Handle, HandleContainer, IrDocument, OperationIr, TensorId, TensorIr, TensorStatus};
+use hashbrown::HashSet;
+
+use crate::{
+    Client, FusionError, FusionRuntime, FusionTensor,
+    client::FusionClient,
+    stream::{ExecutionPlanId, OperationConverter, execution::Operation},
+};
+
+/// The operations executed on a stream while capturing, replayed on new inputs without being
+/// registered, explored and dispatched again.
+///
+/// Created by [Fusion::capture](crate::Fusion::capture). Each step of the graph is executed with
+/// the execution plan and the tensors of the captured iteration:
+///
+/// - The inputs of the graph are bound to the tensors passed to [replay](Self::replay), which must
+///   have the shapes and data types of the captured inputs.
+/// - The other tensors read by the graph, e.g. the weights of a model, are read as they are when
+///   replayed, so they must stay alive as long as the graph.
+/// - Scalars keep their captured values.
+///
+/// The execution plans of the graph must stay in the store, so devices with a
+/// [capacity](crate::Fusion::set_execution_plan_capacity) should
+/// [pin](crate::Fusion::pin_plan) them.
+pub struct CapturedGraph<R: FusionRuntime> {
+    pub(crate) client: Client<R>,
+    pub(crate) capture: Arc<GraphCapture<R>>,
+}
+
+/// The operations captured on a stream, with the tensors the graph reads and writes.
+pub(crate) struct GraphCapture<R: FusionRuntime> {
+    pub(crate) steps: Vec<CapturedStep<R>>,
+    pub(crate) tensors: GraphTensors,
+}
+
+/// The tensors bound to a captured graph when it's replayed.
+pub(crate) struct GraphTensors {
+    inputs: Vec<TensorIr>,
+    outputs: Vec<TensorIr>,
+    /// The tensors written by the graph, including its inputs.
+    written: Vec<TensorId>,
+}
+
+/// The operations executed by a plan while capturing.
+pub(crate) struct CapturedStep<R: FusionRuntime> {
+    pub(crate) plan: ExecutionPlanId,
+    /// The conversion between relative and global tensors when the plan was executed.
+    pub(crate) converter: OperationConverter,
+    pub(crate) operations: Vec<Arc<dyn Operation<R>>>,
+    pub(crate) global: Vec<OperationIr>,
+}
+
+/// The handles of the tensors of a graph alive before it's replayed.
+pub(crate) struct StashedHandles<H> {
+    handles: Vec<(TensorId, H)>,
+}
+
+impl<R: FusionRuntime> CapturedGraph<R> {
+    /// Execute the graph again with new inputs, returning its new outputs.
+    ///
+    /// Fails with [FusionError::GraphReplay] when the inputs don't match the captured ones, or
+    /// when an execution plan of the graph was evicted.
+    pub fn replay(
+        &self,
+        inputs: Vec<FusionTensor<R>>,
+    ) -> Result<Vec<FusionTensor<R>>, FusionError> {
+        self.client.replay_graph(self, inputs)
+    }
+
+    /// The operations of the graph as they were registered, to be serialized and
+    /// [compiled ahead of time](crate::Fusion::compile_ahead_of_time).
+    pub fn document(&self) -> IrDocument {
+        let operations = self
+            .capture
+            .steps
+            .iter()
+            .flat_map(|step| step.global.iter().cloned())
+            .collect();
+
+        IrDocument::new(operations)
+    }
+
+    /// The number of execution plans executed by a replay.
+    pub fn num_plans(&self) -> usize {
+        self.capture.steps.len()
+    }
+
+    /// The number of operations executed by a replay.
+    pub fn num_operations(&self) -> usize {
+        self.capture
+            .steps
+            .iter()
+            .map(|step| step.operations.len())
+            .sum()
+    }
+}
+
+impl<R: FusionRuntime> GraphCapture<R> {
+    pub(crate) fn new(
+        steps: Vec<CapturedStep<R>>,
+        inputs: Vec<TensorIr>,
+        outputs: Vec<TensorIr>,
+    ) -> Self {
+        let global = steps
+            .iter()
+            .flat_map(|step| step.global.iter())
+            .collect::<Vec<_>>();
+        let tensors = GraphTensors::new(inputs, outputs, &global);
+
+        Self { steps, tensors }
+    }
+}
+
+impl GraphTensors {
+    fn new(inputs: Vec<TensorIr>, outputs: Vec<TensorIr>, operations: &[&OperationIr]) -> Self {
+        let mut written = HashSet::new();
+        let written = inputs
+            .iter()
+            .map(|input| input.id)
+            .chain(
+                operations
+                    .iter()
+                    .flat_map(|operation| operation.nodes())
+                    .filter(|node| node.status == TensorStatus::NotInit)
+                    .map(|node| node.id),
+            )
+            .filter(|id| written.insert(*id))
+            .collect();
+
+        Self {
+            inputs,
+            outputs,
+            written,
+        }
+    }
+
+    /// The shapes and data types of the outputs of the graph.
+    pub(crate) fn outputs(&self) -> &[TensorIr] {
+        &self.outputs
+    }
+
+    /// Check that the tensors can be bound to the inputs of the graph.
+    pub(crate) fn check_inputs(&self, inputs: &[TensorIr]) -> Result<(), FusionError> {
+        if inputs.len() != self.inputs.len() {
+            return Err(FusionError::GraphReplay {
+                reason: format!(
+                    "expected {} inputs, got {}",
+                    self.inputs.len(),
+                    inputs.len()
+                ),
+            });
+        }
+
+        for (index, (input, captured)) in inputs.iter().zip(self.inputs.iter()).enumerate() {
+            if input.shape != captured.shape || input.dtype != captured.dtype {
+                return Err(FusionError::GraphReplay {
+                    reason: format!(
+                        "input {index} is {:?}{:?}, captured as {:?}{:?}",
+                        input.dtype, input.shape, captured.dtype, captured.shape
+                    ),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the handles of the tensors written by the graph that are still alive, e.g. the
+    /// outputs of the captured iteration, so that the replay doesn't overwrite them.
+    pub(crate) fn stash<H: Clone>(&self, handles: &mut HandleContainer<H>) -> StashedHandles<H> {
+        let handles = self
+            .written
+            .iter()
+            .filter_map(|id| match handles.remove_handle(*id) {
+                Some(Handle::Existing(handle)) => Some((*id, handle)),
+                _ => None,
+            })
+            .collect();
+
+        StashedHandles { handles }
+    }
+
+    /// Bind the buffers of the tensors to the inputs of the graph, moving them when the tensors
+    /// are consumed.
+    pub(crate) fn bind_inputs<H: Clone>(
+        &self,
+        handles: &mut HandleContainer<H>,
+        inputs: &[TensorIr],
+    ) {
+        for (input, captured) in inputs.iter().zip(self.inputs.iter()) {
+            let handle = handles.get_handle(&input.id, &input.status);
+            handles.register_handle(captured.id, handle);
+        }
+    }
+
+    /// The handles of the outputs of the replay, in the order of the captured outputs.
+    ///
+    /// Outputs can be returned more than once or not be written by the graph, so their handles
+    /// are only removed when [restoring](Self::restore) the stashed ones.
+    pub(crate) fn outputs_handles<H: Clone>(&self, handles: &mut HandleContainer<H>) -> Vec<H> {
+        self.outputs
+            .iter()
+            .map(|output| handles.get_handle(&output.id, &TensorStatus::ReadOnly))
+            .collect()
+    }
+
+    /// Remove the handles left by the replay and restore the [stashed](Self::stash) ones.
+    pub(crate) fn restore<H: Clone>(
+        &self,
+        handles: &mut HandleContainer<H>,
+        stashed: StashedHandles<H>,
+    ) {
+        for id in self.written.iter() {
+            handles.remove_handle(*id);
+        }
+        for (id, handle) in stashed.handles {
+            handles.register_handle(id, handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_replay_without_overwriting_captured_tensors() {
+        let exp = |input, out| {
+            exp(
+                tensor(input, TensorStatus::ReadOnly),
+                tensor(out, TensorStatus::NotInit),
+            )
+        };
+        let capture = GraphTensors::new(
+            vec![tensor(1, TensorStatus::ReadOnly)],
+            vec![tensor(3, TensorStatus::ReadOnly)],
+            &[&exp(1, 2), &exp(2, 3)],
+        );
+        let mut handles = HandleContainer::<u32>::new();
+        // The output of the captured iteration is still alive.
+        handles.register_handle(TensorId::new(3), 30);
+        handles.register_handle(TensorId::new(10), 100);
+
+        let mismatch = [TensorIr {
+            shape: vec![4],
+            ..tensor(10, TensorStatus::ReadOnly)
+        }];
+        assert!(capture.check_inputs(&mismatch).is_err());
+
+        let inputs = [tensor(10, TensorStatus::ReadOnly)];
+        capture.check_inputs(&inputs).unwrap();
+        let stashed = capture.stash(&mut handles);
+        capture.bind_inputs(&mut handles, &inputs);
+        assert_eq!(
+            handles.get_handle(&TensorId::new(1), &TensorStatus::ReadOnly),
+            100
+        );
+
+        // Executing the graph.
+        handles.register_handle(TensorId::new(2), 20);
+        handles.register_handle(TensorId::new(3), 31);
+
+        assert_eq!(capture.outputs_handles(&mut handles), [31]);
+        capture.restore(&mut handles, stashed);
+        assert!(!handles.has_handle(&TensorId::new(1)));
+        assert!(!handles.has_handle(&TensorId::new(2)));
+        assert_eq!(
+            handles.get_handle(&TensorId::new(3), &TensorStatus::ReadOnly),
+            30
+        );
+        assert!(handles.has_handle(&TensorId::new(10)));
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use burn_ir::{HandleContainer, OperationIr, TensorId};
+use hashbrown::HashMap;
+
+use crate::{
+    FusionRuntime,
+    memory::{TrackedTensor, tracked_tensors},
+    stream::{StreamId, execution::Operation},
+};
+
+/// The state of the fusion server of a device, taken by [checkpoint](crate::Fusion::checkpoint)
+/// and brought back by [restore](crate::Fusion::restore).
+///
+/// The checkpoint holds the handles of the tensors and the operations queued on every stream,
+/// but not the content of the device buffers: a buffer written in place after the checkpoint
+/// keeps its new values once restored.
+pub struct FusionCheckpoint<R: FusionRuntime> {
+    pub(crate) handles: HandleContainer<R::FusionHandle>,
+    /// The streams with queued operations, sorted by id.
+    pub(crate) streams: Vec<StreamCheckpoint<R>>,
+}
+
+/// The operations queued on a stream when the [checkpoint](FusionCheckpoint) was taken.
+pub(crate) struct StreamCheckpoint<R: FusionRuntime> {
+    pub(crate) stream: StreamId,
+    pub(crate) global: Vec<OperationIr>,
+    pub(crate) operations: Vec<Arc<dyn Operation<R>>>,
+    /// The stream of each tensor read by the operations.
+    pub(crate) variables: HashMap<TensorId, StreamId>,
+}
+
+impl<R: FusionRuntime> FusionCheckpoint<R> {
+    /// Every tensor tracked by the server when the checkpoint was taken, ordered by id.
+    pub fn tensors(&self) -> Vec<TrackedTensor> {
+        tracked_tensors::<R>(&self.handles)
+    }
+
+    /// The operations queued on each stream when the checkpoint was taken, sorted by stream.
+    pub fn queued_operations(&self) -> impl Iterator<Item = (StreamId, &[OperationIr])> {
+        self.streams
+            .iter()
+            .map(|stream| (stream.stream, stream.global.as_slice()))
+    }
+
+    /// The number of operations queued on every stream when the checkpoint was taken.
+    pub fn num_queued_operations(&self) -> usize {
+        self.streams.iter().map(|stream| stream.global.len()).sum()
+    }
+}
+
+impl<R: FusionRuntime> Clone for FusionCheckpoint<R> {
+    fn clone(&self) -> Self {
+        Self {
+            handles: self.handles.fork(),
+            streams: self.streams.clone(),
+        }
+    }
+}
+
+impl<R: FusionRuntime> Clone for StreamCheckpoint<R> {
+    fn clone(&self) -> Self {
+        Self {
+            stream: self.stream,
+            global: self.global.clone(),
+            operations: self.operations.clone(),
+            variables: self.variables.clone(),
+        }
+    }
+}
+
+impl<R: FusionRuntime> core::fmt::Debug for FusionCheckpoint<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FusionCheckpoint")
+            .field("num_tensors", &self.handles.iter().count())
+            .field("num_queued_operations", &self.num_queued_operations())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_common::future::block_on;
+    use burn_ir::{TensorIr, TensorStatus};
+    use burn_ndarray::NdArrayDevice;
+    use burn_tensor::{DType, TensorData, ops::FloatTensorOps};
+
+    use crate::{
+        Fusion, FusionServer, FusionTensor,
+        client::FusionClient,
+        test_utils::{
+            TestBackend, TestClient, TestRuntime, float_data, float_tensor, with_lazy_streams,
+        },
+    };
+
+    fn read_ir(tensor: &FusionTensor<TestRuntime>) -> TensorIr {
+        TensorIr {
+            id: tensor.id,
+            shape: tensor.shape.clone(),
+            status: TensorStatus::ReadOnly,
+            dtype: DType::F32,
+        }
+    }
+
+    #[test]
+    fn should_execute_the_queued_operations_once_restored_on_another_server() {
+        with_lazy_streams(|| {
+            let client = TestClient::new(NdArrayDevice::Cpu);
+            let input = float_tensor(&client, TensorData::from([0.0f32, 1.0]));
+            let stream = input.stream;
+            let output = Fusion::<TestBackend>::float_exp(Fusion::<TestBackend>::float_exp(input));
+
+            let checkpoint = client.checkpoint();
+            assert_eq!(checkpoint.num_queued_operations(), 2);
+
+            let mut server = FusionServer::<TestRuntime>::new(NdArrayDevice::Cpu);
+            server.restore(&checkpoint);
+            assert_eq!(server.tracked_tensors(), checkpoint.tensors());
+
+            server.drain_stream(stream);
+            client.drain_stream(stream);
+            assert_eq!(server.tracked_tensors(), client.tracked_tensors());
+
+            let restored = block_on(server.read_float::<TestBackend>(read_ir(&output), stream));
+            let expected = float_data(output);
+            restored.assert_eq(&expected, true);
+            expected.assert_approx_eq::<f32>(
+                &TensorData::from([1.0f32.exp(), 1.0f32.exp().exp()]),
+                Default::default(),
+            );
+        });
+    }
+}
@@ -1,8 +1,10 @@
 use burn_ir::*;
-use burn_tensor::{DType, Element, ElementConversion};
+use burn_tensor::{DType, Distribution, Element, ElementConversion};
 use half::{bf16, f16};
 use hashbrown::HashMap;
 
+use super::OutputPool;
+
 /// The context contains the relative graph tensor mapping so that a relative tensor id can be
 /// mapped to an existing tensor that can be fetched and updated with the
 /// [handle container](HandleContainer).
@@ -18,6 +20,8 @@ pub struct Context<'a, H> {
     pub handles: &'a mut HandleContainer<H>,
     /// Scalars found in the graph in the order they appeared.
     pub scalars: &'a mut HashMap<ScalarId, ScalarValue>,
+    /// Buffers released by previous executions of the current plan that can hold its outputs.
+    pub pool: &'a mut OutputPool<H>,
 }
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -45,11 +49,79 @@ pub enum ScalarValue {
     U8(u8),
 }
 
+impl core::hash::Hash for ScalarValue {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+
+        // Floats are hashed from their bits, so `-0.0` and `0.0` differ.
+        match self {
+            ScalarValue::F64(value) => value.to_bits().hash(state),
+            ScalarValue::F32(value) => value.to_bits().hash(state),
+            ScalarValue::F16(value) => value.to_bits().hash(state),
+            ScalarValue::BF16(value) => value.to_bits().hash(state),
+            ScalarValue::I64(value) => value.hash(state),
+            ScalarValue::I32(value) => value.hash(state),
+            ScalarValue::I16(value) => value.hash(state),
+            ScalarValue::I8(value) => value.hash(state),
+            ScalarValue::U64(value) => value.hash(state),
+            ScalarValue::U32(value) => value.hash(state),
+            ScalarValue::U16(value) => value.hash(state),
+            ScalarValue::U8(value) => value.hash(state),
+        }
+    }
+}
+
+impl PartialEq for ScalarValue {
+    fn eq(&self, other: &Self) -> bool {
+        // Floats are compared from their bits, like they are hashed.
+        match (self, other) {
+            (ScalarValue::F64(lhs), ScalarValue::F64(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            (ScalarValue::F32(lhs), ScalarValue::F32(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            (ScalarValue::F16(lhs), ScalarValue::F16(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            (ScalarValue::BF16(lhs), ScalarValue::BF16(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            (ScalarValue::I64(lhs), ScalarValue::I64(rhs)) => lhs == rhs,
+            (ScalarValue::I32(lhs), ScalarValue::I32(rhs)) => lhs == rhs,
+            (ScalarValue::I16(lhs), ScalarValue::I16(rhs)) => lhs == rhs,
+            (ScalarValue::I8(lhs), ScalarValue::I8(rhs)) => lhs == rhs,
+            (ScalarValue::U64(lhs), ScalarValue::U64(rhs)) => lhs == rhs,
+            (ScalarValue::U32(lhs), ScalarValue::U32(rhs)) => lhs == rhs,
+            (ScalarValue::U16(lhs), ScalarValue::U16(rhs)) => lhs == rhs,
+            (ScalarValue::U8(lhs), ScalarValue::U8(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ScalarValue {}
+
+/// Which scalars are replaced by parameters when operations are converted to their relative
+/// form.
+///
+/// Plans only differing by parameterized scalars are the same plan, so a single optimization
+/// serves every value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScalarParameterization {
+    /// Only the scalars passed to fused kernels at execution time, e.g. the rhs of `x * 2.0`.
+    ///
+    /// The other scalars, such as the parameters of random distributions, are constants of the
+    /// plans.
+    #[default]
+    Fused,
+    /// Every scalar, including the parameters of random distributions, e.g. the probability of
+    /// a dropout.
+    ///
+    /// Optimizations can't rely on the value of scalars that aren't passed at execution time,
+    /// so custom optimizations fusing random operations need [fused](Self::Fused) scalars.
+    All,
+}
+
+#[derive(Clone)]
 pub(crate) struct OperationConverter {
     tensors_relative2global: HashMap<TensorId, TensorIr>,
     tensors_global2relative: HashMap<TensorId, TensorIr>,
     shapes_global2relative: HashMap<usize, usize>,
     scalars: HashMap<ScalarId, ScalarValue>,
+    pub(crate) scalar_parameterization: ScalarParameterization,
 }
 
 impl Default for OperationConverter {
@@ -59,6 +131,7 @@ impl Default for OperationConverter {
             tensors_global2relative: Default::default(),
             shapes_global2relative: Default::default(),
             scalars: Default::default(),
+            scalar_parameterization: Default::default(),
         };
 
         // global 1 is always shape id 0.
@@ -73,6 +146,7 @@ pub struct ContextOwned<H> {
     tensors: HashMap<TensorId, TensorIr>,
     handles: HandleContainer<H>,
     scalars: HashMap<ScalarId, ScalarValue>,
+    pool: OutputPool<H>,
 }
 
 impl<H: Clone> ContextOwned<H> {
@@ -82,6 +156,7 @@ impl<H: Clone> ContextOwned<H> {
             tensors: &mut self.tensors,
             handles: &mut self.handles,
             scalars: &mut self.scalars,
+            pool: &mut self.pool,
         }
     }
 
@@ -91,6 +166,8 @@ impl<H: Clone> ContextOwned<H> {
             tensors: self.tensors.clone(),
             handles: self.handles.fork(),
             scalars: self.scalars.clone(),
+            // Pooled buffers can't be shared between forks.
+            pool: OutputPool::new(0),
         }
     }
 }
@@ -102,6 +179,8 @@ impl<H: Clone> Context<'_, H> {
             tensors: self.tensors.clone(),
             handles: self.handles.fork(),
             scalars: self.scalars.clone(),
+            // Pooled buffers can't be shared between forks.
+            pool: OutputPool::new(0),
         }
     }
 }
@@ -128,11 +207,13 @@ impl OperationConverter {
     pub(crate) fn context<'a, H>(
         &'a mut self,
         handles: &'a mut HandleContainer<H>,
+        pool: &'a mut OutputPool<H>,
     ) -> Context<'a, H> {
         Context {
             handles,
             tensors: &mut self.tensors_relative2global,
             scalars: &mut self.scalars,
+            pool,
         }
     }
 
@@ -147,6 +228,25 @@ impl OperationConverter {
         self.scalars.clear();
     }
 
+    /// The number of scalars converted so far.
+    pub(crate) fn num_scalars(&self) -> usize {
+        self.scalars.len()
+    }
+
+    /// The values of the scalars converted so far, in the order they were converted.
+    pub(crate) fn scalar_values(&self) -> Vec<ScalarValue> {
+        (0..self.scalars.len() as u64)
+            .filter_map(|value| self.scalars.get(&ScalarId { value }).cloned())
+            .collect()
+    }
+
+    /// Hash the values of the scalars converted so far, in the order they were converted.
+    pub(crate) fn hash_scalars<H: core::hash::Hasher>(&self, state: &mut H) {
+        for value in 0..self.scalars.len() as u64 {
+            core::hash::Hash::hash(&self.scalars.get(&ScalarId { value }), state);
+        }
+    }
+
     pub(crate) fn relative_float<E: Element>(&mut self, elem: &E, dtype: &DType) -> E {
         let id = ScalarId {
             value: self.scalars.len() as u64,
@@ -163,6 +263,20 @@ impl OperationConverter {
         id.value.elem()
     }
 
+    pub(crate) fn relative_distribution(&self, distribution: &Distribution) -> Distribution {
+        match self.scalar_parameterization {
+            ScalarParameterization::Fused => *distribution,
+            // Random operations are executed with their global representation, so the values
+            // are only lost for matching.
+            ScalarParameterization::All => match distribution {
+                Distribution::Default => Distribution::Default,
+                Distribution::Bernoulli(_) => Distribution::Bernoulli(0.0),
+                Distribution::Uniform(..) => Distribution::Uniform(0.0, 0.0),
+                Distribution::Normal(..) => Distribution::Normal(0.0, 0.0),
+            },
+        }
+    }
+
     pub(crate) fn relative_int<E: Element>(&mut self, elem: &E, dtype: &DType) -> E {
         let id = ScalarId {
             value: self.scalars.len() as u64,
@@ -507,7 +621,7 @@ impl RelativeOpsScalar<f32> for FloatOperationIr {
             }),
             FloatOperationIr::Random(desc) => FloatOperationIr::Random(RandomOpIr {
                 out: desc.out.to_relative(converter),
-                distribution: desc.distribution,
+                distribution: converter.relative_distribution(&desc.distribution),
             }),
             FloatOperationIr::Recip(desc) => FloatOperationIr::Recip(UnaryOpIr {
                 input: desc.input.to_relative(converter),
@@ -921,7 +1035,7 @@ impl<E: Element> RelativeOpsScalar<E> for NumericOperationIr<E> {
             }),
             NumericOperationIr::IntRandom(desc) => NumericOperationIr::IntRandom(RandomOpIr {
                 out: desc.out.to_relative(converter),
-                distribution: desc.distribution,
+                distribution: converter.relative_distribution(&desc.distribution),
             }),
             NumericOperationIr::Powf(desc) => NumericOperationIr::Powf(BinaryOpIr {
                 lhs: desc.lhs.to_relative(converter),
@@ -1106,4 +1220,34 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn distribution_to_relative() {
+        let random = |prob| {
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::Random(RandomOpIr {
+                    out: TensorIr {
+                        id: TensorId::new(500),
+                        shape: vec![32],
+                        status: TensorStatus::NotInit,
+                        dtype: DType::F32,
+                    },
+                    distribution: Distribution::Bernoulli(prob),
+                }),
+            )
+        };
+        let mut converter = OperationConverter::default();
+
+        assert_ne!(
+            random(0.1).to_relative(&mut converter),
+            random(0.2).to_relative(&mut converter)
+        );
+
+        converter.scalar_parameterization = ScalarParameterization::All;
+        assert_eq!(
+            random(0.1).to_relative(&mut converter),
+            random(0.2).to_relative(&mut converter)
+        );
+    }
 }
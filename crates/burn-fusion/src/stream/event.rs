@@ -0,0 +1,147 @@
+use core::fmt::Display;
+use std::sync::{Condvar, Mutex};
+use web_time::Instant;
+
+use hashbrown::HashMap;
+
+use super::StreamId;
+
+/// A point in the operations of a stream, recorded with
+/// [record_event](crate::Fusion::record_event).
+///
+/// Another stream can [wait](crate::Fusion::wait_event) on the event, executing the operations of
+/// the recording stream up to that point without syncing the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventId {
+    /// The value of the id.
+    pub value: u64,
+}
+
+impl Display for EventId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("Event({})", self.value))
+    }
+}
+
+/// Where an event was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StreamPosition {
+    pub(crate) stream: StreamId,
+    /// Streams are removed once all their operations are executed, and created again on their
+    /// next operation, so the position is only meaningful for the same instance of the stream.
+    pub(crate) epoch: u64,
+    /// The number of operations of the stream up to the event, executed ones included.
+    pub(crate) position: u64,
+}
+
+impl StreamPosition {
+    /// The number of operations left to execute before reaching the position, given the current
+    /// instance of the stream.
+    pub(crate) fn num_pending(&self, epoch: u64, cursor: u64) -> u64 {
+        match epoch == self.epoch {
+            true => self.position.saturating_sub(cursor),
+            false => 0,
+        }
+    }
+}
+
+/// The events recorded on the streams of a device that aren't complete yet.
+#[derive(Default)]
+pub(crate) struct StreamEvents {
+    num_recorded: u64,
+    pending: HashMap<EventId, StreamPosition>,
+}
+
+impl StreamEvents {
+    /// Record a new event, `None` when it's already complete.
+    pub(crate) fn record(&mut self, position: Option<StreamPosition>) -> EventId {
+        let id = EventId {
+            value: self.num_recorded,
+        };
+        self.num_recorded += 1;
+
+        if let Some(position) = position {
+            self.pending.insert(id, position);
+        }
+
+        id
+    }
+
+    /// Remove the event, returning its position if it might not be complete.
+    pub(crate) fn take(&mut self, id: EventId) -> Option<StreamPosition> {
+        self.pending.remove(&id)
+    }
+
+    /// Forget the events that are complete.
+    pub(crate) fn retain_pending<F: Fn(&StreamPosition) -> bool>(&mut self, is_pending: F) {
+        self.pending.retain(|_, position| is_pending(position));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_only_wait_on_pending_events() {
+        let mut events = StreamEvents::default();
+        let position = StreamPosition {
+            stream: StreamId { value: 1 },
+            epoch: 2,
+            position: 10,
+        };
+
+        let complete = events.record(None);
+        let pending = events.record(Some(position));
+        assert_ne!(complete, pending);
+        assert_eq!(events.take(complete), None);
+
+        assert_eq!(position.num_pending(2, 4), 6);
+        assert_eq!(position.num_pending(2, 12), 0);
+        // The stream was recreated, so all operations up to the event were executed.
+        assert_eq!(position.num_pending(3, 0), 0);
+
+        events.retain_pending(|position| position.num_pending(2, 10) > 0);
+        assert_eq!(events.take(pending), None);
+    }
+}
+
+/// Notified every time operations are executed on a device, so that threads can wait for a
+/// stream to be drained without holding the server.
+#[derive(Default)]
+pub(crate) struct ExecutionSignal {
+    num_notified: Mutex<u64>,
+    condvar: Condvar,
+}
+
+impl ExecutionSignal {
+    pub(crate) fn notify(&self) {
+        *self.num_notified.lock().unwrap() += 1;
+        self.condvar.notify_all();
+    }
+
+    /// The number of times the signal was notified.
+    pub(crate) fn num_notified(&self) -> u64 {
+        *self.num_notified.lock().unwrap()
+    }
+
+    /// Wait for the signal to be notified more than `num_notified` times, returning `false` when
+    /// the deadline is reached first.
+    pub(crate) fn wait(&self, num_notified: u64, deadline: Instant) -> bool {
+        let mut current = self.num_notified.lock().unwrap();
+
+        while *current == num_notified {
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            current = self
+                .condvar
+                .wait_timeout(current, deadline - now)
+                .unwrap()
+                .0;
+        }
+
+        true
+    }
+}
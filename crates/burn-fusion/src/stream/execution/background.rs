@@ -0,0 +1,195 @@
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+use burn_ir::OperationIr;
+
+use super::{ExecutionMode, ExplorationAction, Explorer};
+use crate::{
+    NumOperations, OptimizationBuilder,
+    search::{BlockOptimization, ExplorationSettings},
+    stream::{OperationConverter, RelativeOps, ScalarParameterization, store::ExecutionTrigger},
+};
+
+/// Explores the operations executed eagerly by a stream on a background thread, sending back
+/// the plans found so that the next occurrences of the operations are optimized.
+///
+/// Used when the [policy](crate::ExplorationPolicy::background_exploration) moves the
+/// exploration off the hot path.
+pub(crate) struct BackgroundExplorer<O> {
+    tasks: Sender<BackgroundTask>,
+    explored: Receiver<ExploredPlan<O>>,
+}
+
+/// A plan explored in the background, to be added to the store of the stream.
+pub(crate) struct ExploredPlan<O> {
+    pub(crate) operations: Vec<OperationIr>,
+    pub(crate) trigger: ExecutionTrigger,
+    pub(crate) optimization: BlockOptimization<O>,
+}
+
+enum BackgroundTask {
+    /// An operation executed eagerly, with the conversion of its scalars.
+    Operation(Box<OperationIr>, ScalarParameterization),
+    /// The next operations don't follow the queued ones, which are explored without waiting
+    /// for more operations.
+    Flush,
+}
+
+/// The state of the background thread, mirroring the queue of a stream.
+struct Worker<O> {
+    explorer: Explorer<O>,
+    converter: OperationConverter,
+    global: Vec<OperationIr>,
+    relative: Vec<OperationIr>,
+    explored: Sender<ExploredPlan<O>>,
+}
+
+impl<O: NumOperations + Send + 'static> BackgroundExplorer<O> {
+    /// Start exploring in the background with the given builders and settings.
+    pub(crate) fn new(
+        builders: Vec<Box<dyn OptimizationBuilder<O>>>,
+        exploration: ExplorationSettings,
+    ) -> Self {
+        let (tasks, tasks_receiver) = channel();
+        let (explored_sender, explored) = channel();
+
+        std::thread::spawn(move || {
+            let mut worker = Worker {
+                explorer: Explorer::new(builders, exploration),
+                converter: OperationConverter::default(),
+                global: Vec::new(),
+                relative: Vec::new(),
+                explored: explored_sender,
+            };
+
+            // Stops when the stream is dropped.
+            while let Ok(task) = tasks_receiver.recv() {
+                if !worker.on_task(task) {
+                    break;
+                }
+            }
+        });
+
+        Self { tasks, explored }
+    }
+
+    /// Explore an operation executed eagerly, in its registered form.
+    pub(crate) fn register(&self, operation: &OperationIr, scalars: ScalarParameterization) {
+        // The thread only stops when this explorer is dropped.
+        self.tasks
+            .send(BackgroundTask::Operation(
+                Box::new(operation.clone()),
+                scalars,
+            ))
+            .ok();
+    }
+
+    /// Explore the registered operations without waiting for more, the next ones being
+    /// executed with a plan or synced.
+    pub(crate) fn flush(&self) {
+        self.tasks.send(BackgroundTask::Flush).ok();
+    }
+
+    /// The plans explored since the last call.
+    pub(crate) fn explored(&self) -> impl Iterator<Item = ExploredPlan<O>> + '_ {
+        self.explored.try_iter()
+    }
+}
+
+impl<O: NumOperations> Worker<O> {
+    /// Handle the task, returning whether the stream still listens for explored plans.
+    fn on_task(&mut self, task: BackgroundTask) -> bool {
+        let mode = match task {
+            BackgroundTask::Operation(operation, scalars) => {
+                self.converter.scalar_parameterization = scalars;
+                self.relative
+                    .push(operation.to_relative(&mut self.converter));
+                self.global.push(*operation);
+                self.explorer.on_new_operation();
+                ExecutionMode::Lazy
+            }
+            BackgroundTask::Flush => ExecutionMode::Sync,
+        };
+
+        while !self.relative.is_empty() {
+            let optimization = match self.explorer.explore(&self.relative, mode) {
+                ExplorationAction::Completed(optimization) => optimization,
+                ExplorationAction::Continue => break,
+            };
+
+            if !self.commit(optimization) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Send the explored plan, removing its operations from the queue.
+    fn commit(&mut self, optimization: BlockOptimization<O>) -> bool {
+        let num_optimized = optimization.ordering.len();
+        let next_operations = &self.relative[num_optimized..];
+        // Like the plans explored on the hot path, except that the operations following a flush
+        // are unknown.
+        let trigger = match next_operations.is_empty() {
+            true => ExecutionTrigger::Always,
+            false => ExecutionTrigger::OnOperations(next_operations.to_vec()),
+        };
+        let plan = ExploredPlan {
+            operations: self.relative[..num_optimized].to_vec(),
+            trigger,
+            optimization,
+        };
+
+        self.global.drain(..num_optimized);
+        self.converter.clear();
+        self.relative = self
+            .global
+            .iter()
+            .map(|operation| operation.to_relative(&mut self.converter))
+            .collect();
+        self.explorer.reset(&self.relative);
+
+        self.explored.send(plan).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::stream::execution::tests::{
+        TestOptimization, TestOptimizationBuilder, operation_1, operation_2,
+    };
+
+    #[test]
+    fn should_explore_eagerly_executed_operations_in_the_background() {
+        let mut converter = OperationConverter::default();
+        let relative =
+            [operation_1(), operation_2()].map(|operation| operation.to_relative(&mut converter));
+        let builder = TestOptimizationBuilder::new(0, relative[..2].to_vec());
+        let background = BackgroundExplorer::<TestOptimization>::new(
+            vec![Box::new(builder)],
+            ExplorationSettings::default(),
+        );
+        let scalars = ScalarParameterization::default();
+
+        background.register(&operation_1(), scalars);
+        background.register(&operation_2(), scalars);
+        background.register(&operation_1(), scalars);
+        background.flush();
+
+        let explored = background.explored.recv_timeout(Duration::from_secs(10));
+        let plan = explored.expect("The fused operations are explored");
+        assert_eq!(plan.operations, relative);
+        // The optimization can't continue with more operations.
+        assert_eq!(plan.trigger, ExecutionTrigger::Always);
+        assert_eq!(plan.optimization.ordering, vec![0, 1]);
+
+        // The remaining operation starts a new sequence.
+        let explored = background.explored.recv_timeout(Duration::from_secs(10));
+        let plan = explored.expect("The remaining operation is explored when flushed");
+        assert_eq!(plan.operations, relative[..1]);
+        assert_eq!(plan.trigger, ExecutionTrigger::Always);
+    }
+}
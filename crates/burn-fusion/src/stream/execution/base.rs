@@ -1,4 +1,5 @@
 use burn_ir::HandleContainer;
+use burn_tensor::TensorData;
 
 use crate::FusionRuntime;
 
@@ -13,4 +14,25 @@ pub(crate) enum ExecutionMode {
 pub trait Operation<R: FusionRuntime>: Send + Sync + core::fmt::Debug {
     /// Execute the operation.
     fn execute(&self, handles: &mut HandleContainer<R::FusionHandle>);
+
+    /// The data of the tensor initialized by the operation, when it's a small constant that the
+    /// operations reading it can be folded with before the stream is drained.
+    fn constant(&self) -> Option<Constant<'_, R>> {
+        None
+    }
+}
+
+/// Creates the buffer of a tensor on the device from its data.
+pub type MaterializeFn<R> =
+    fn(TensorData, &<R as FusionRuntime>::FusionDevice) -> <R as FusionRuntime>::FusionHandle;
+
+/// A tensor [initialized](burn_ir::OperationIr::Init) from data known when its operation is
+/// registered.
+pub struct Constant<'a, R: FusionRuntime> {
+    /// The data of the tensor.
+    pub data: &'a TensorData,
+    /// The device of the tensor.
+    pub device: &'a R::FusionDevice,
+    /// Creates the buffers of the tensors folded from the constant.
+    pub materialize: MaterializeFn<R>,
 }
@@ -3,12 +3,13 @@ use burn_ir::OperationIr;
 use super::ExecutionMode;
 use crate::{
     NumOperations, OptimizationBuilder,
-    search::{BlockOptimization, StreamOptimizer},
+    search::{BlockOptimization, ExplorationSettings, ExplorationState, StreamOptimizer},
 };
 
 /// Explore and create new optimization.
 pub struct Explorer<O> {
     optimizer: StreamOptimizer<O>,
+    exploration: ExplorationSettings,
     num_deferred: usize,
     num_explored: usize,
     is_still_optimizing: bool,
@@ -24,15 +25,25 @@ pub enum ExplorationAction<O> {
 
 impl<O: NumOperations> Explorer<O> {
     /// Create a new explorer.
-    pub(crate) fn new(optimizations: Vec<Box<dyn OptimizationBuilder<O>>>) -> Self {
+    pub(crate) fn new(
+        optimizations: Vec<Box<dyn OptimizationBuilder<O>>>,
+        exploration: ExplorationSettings,
+    ) -> Self {
         Self {
-            optimizer: StreamOptimizer::new(optimizations),
+            optimizer: StreamOptimizer::new(optimizations, exploration.clone()),
+            exploration,
             num_deferred: 0,
             num_explored: 0,
             is_still_optimizing: true,
         }
     }
 
+    /// Update the [settings](ExplorationSettings) used from the next explored operation.
+    pub(crate) fn set_exploration(&mut self, exploration: ExplorationSettings) {
+        self.optimizer.set_exploration(exploration.clone());
+        self.exploration = exploration;
+    }
+
     /// Indicate that a new operation is added.
     pub(crate) fn on_new_operation(&mut self) {
         self.num_deferred += 1;
@@ -49,13 +60,24 @@ impl<O: NumOperations> Explorer<O> {
         operations: &[OperationIr],
         mode: ExecutionMode,
     ) -> ExplorationAction<O> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "fusion.explore",
+            num_operations = operations.len(),
+            mode = ?mode
+        )
+        .entered();
+
         self.update(operations);
 
         // Can only continue exploration when not sync.
-        if let ExecutionMode::Lazy = mode {
-            if self.is_still_optimizing {
-                return ExplorationAction::Continue;
-            }
+        if let ExecutionMode::Lazy = mode
+            && !self
+                .exploration
+                .policy
+                .should_commit(&self.state(operations))
+        {
+            return ExplorationAction::Continue;
         }
 
         let optimization = self.optimizer.optimize(operations);
@@ -83,9 +105,18 @@ impl<O: NumOperations> Explorer<O> {
             self.optimizer.register(relative);
             self.num_explored += 1;
 
-            self.is_still_optimizing = self.optimizer.still_optimizing();
+            self.is_still_optimizing =
+                !self.exploration.policy.should_stop(&self.state(operations));
         }
 
         self.num_deferred = 0;
     }
+
+    fn state(&self, operations: &[OperationIr]) -> ExplorationState {
+        ExplorationState {
+            num_explored: self.num_explored,
+            num_queued: operations.len(),
+            still_optimizing: self.optimizer.still_optimizing(),
+        }
+    }
 }
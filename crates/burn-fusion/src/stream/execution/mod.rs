@@ -1,5 +1,6 @@
 pub(crate) mod validator;
 
+mod background;
 mod base;
 mod explorer;
 mod ordering;
@@ -9,6 +10,7 @@ mod processor;
 pub use base::*;
 pub use ordering::*;
 
+pub(crate) use background::*;
 pub(crate) use explorer::*;
 pub(crate) use policy::*;
 pub(crate) use processor::*;
@@ -1,12 +1,11 @@
 use burn_ir::OperationIr;
 
 use super::ExecutionMode;
-use super::validator::{
-    ExecutionPlanOperationsStore, TriggerOperationsStore, TriggerProgress, TriggerValidator,
-    ValidatorState,
-};
+use super::validator::{TriggerOperationsStore, TriggerProgress, TriggerValidator, ValidatorState};
 use crate::stream::execution::validator::OperationsValidator;
-use crate::stream::store::{ExecutionPlanId, ExecutionPlanStore, ExecutionTrigger, SearchQuery};
+use crate::stream::store::{
+    ExecutionPlanId, ExecutionPlanStore, ExecutionTrigger, IndexCursor, IndexStep, TriggerContext,
+};
 use std::marker::PhantomData;
 
 /// The policy keeps track of all possible execution plans for the current operations.
@@ -16,17 +15,19 @@ use std::marker::PhantomData;
 /// We keep track of each new operation added and invalidate potential execution plans
 /// when we see a different operation is added.
 ///
-/// Therefore, the overhead is very minimal, since the time-complexity of checking for existing
-/// execution plans scales with the number of concurrent potential plans for the current operations,
-/// which isn't supposed to be big at any time.
+/// Therefore, the overhead is very minimal: the potential execution plans are tracked with a
+/// single [cursor](IndexCursor) in the index of the store, so checking a new operation costs one
+/// lookup no matter how many plans share the current operations.
 pub(crate) struct Policy<O> {
-    /// List of potential execution plans that are compatible with current stream segment
-    candidates: Vec<OperationsValidator<ExecutionPlanId>>,
+    /// The position in the index of the potential execution plans that are compatible with the
+    /// current stream segment, but need more operations to be found. `None` when there are no
+    /// such plans.
+    candidates: Option<IndexCursor>,
     /// List of candidate execution plans that have been found; we can still keep searching
     /// to potentially find a better one.
     availables: Vec<AvailableItem>,
-    /// The found execution plan that should be executed, along with the number of operations
-    /// in the plan.
+    /// The found execution plan that should be executed, along with the index of the trigger
+    /// that fired.
     found: Option<(ExecutionPlanId, usize)>,
     /// The number of operations that have been analyzed
     num_operations: usize,
@@ -60,7 +61,7 @@ impl<O> Policy<O> {
     /// Create a new policy.
     pub(crate) fn new() -> Self {
         Self {
-            candidates: Vec::new(),
+            candidates: None,
             availables: Vec::new(),
             found: None,
             num_operations: 0,
@@ -69,41 +70,49 @@ impl<O> Policy<O> {
     }
 
     /// Returns the [action](Action) that should be taken given the state of the policy.
-    pub fn action(
-        &self,
-        store: &ExecutionPlanStore<O>,
-        operations: &[OperationIr],
-        mode: ExecutionMode,
-    ) -> Action {
+    pub fn action(&self, operations: &[OperationIr], mode: ExecutionMode) -> Action {
         if self.num_operations < operations.len() {
             panic!(
                 "Internal Error: Can't retrieve the policy action on a list of operations bigger than what is analyzed."
             );
         }
 
-        if let Some((id, _length)) = self.found {
+        if let Some((id, _trigger)) = self.found {
             return Action::Execute(id);
         }
 
         match mode {
             ExecutionMode::Lazy => self.action_lazy(operations),
-            ExecutionMode::Sync => self.action_sync(operations, store),
+            ExecutionMode::Sync => self.action_sync(operations),
         }
     }
 
+    /// The index of the [trigger](ExecutionTrigger) of the found plan that fired, if any.
+    pub fn fired_trigger(&self) -> Option<usize> {
+        self.found.map(|(_id, trigger)| trigger)
+    }
+
+    /// The plans the policy is still considering for the current operations.
+    pub fn referenced_plans(&self, store: &ExecutionPlanStore<O>) -> Vec<ExecutionPlanId> {
+        let mut plans = match self.candidates {
+            Some(cursor) => store.pending_plans(cursor),
+            None => Vec::new(),
+        };
+
+        plans.extend(self.availables.iter().map(|available| available.id));
+        plans.extend(self.found.map(|(id, _trigger)| id));
+        plans
+    }
+
     /// Update the policy state.
     pub fn update(&mut self, store: &ExecutionPlanStore<O>, operation: &OperationIr) {
-        // reset the candidates to contain all execution plans starting with the operation.
-        if self.num_operations == 0 {
-            self.candidates = store
-                .find(SearchQuery::PlansStartingWith(operation))
-                .into_iter()
-                .map(OperationsValidator::new)
-                .collect();
-        }
-
-        self.update_candidates(store, operation);
-        self.check_candidates(store);
+        // Start from all execution plans starting with the first operation.
+        let step = match (self.num_operations, self.candidates) {
+            (0, _) => store.start_search(operation),
+            (_, Some(cursor)) => store.continue_search(cursor, operation),
+            (_, None) => None,
+        };
+        self.update_candidates(store, step);
 
         self.update_availables(store, operation);
         self.check_availables();
@@ -112,58 +121,58 @@ impl<O> Policy<O> {
 
     // Reset the state of the policy.
     pub fn reset(&mut self) {
-        self.candidates.clear();
+        self.candidates = None;
         self.availables.clear();
 
         self.num_operations = 0;
         self.found = None;
     }
 
-    /// Check which candidates can be removed, and which one can go from
-    /// 'candidate' to 'available'
-    fn check_candidates(&mut self, store: &ExecutionPlanStore<O>) {
-        let mut candidates_to_remove = Vec::new();
-
-        for candidate in self.candidates.iter() {
-            match candidate.state {
-                ValidatorState::Found { size } => {
-                    let item = store.get_unchecked(candidate.id);
-                    let mut triggers = Vec::with_capacity(item.triggers.len());
-
-                    for (index, trigger) in item.triggers.iter().enumerate() {
-                        triggers.push(match trigger {
-                            ExecutionTrigger::OnOperations(_) => TriggerValidator::OnOperations {
-                                matching: OperationsValidator::new(index),
-                                progress: TriggerProgress::NotInit,
-                            },
-                            ExecutionTrigger::OnSync => TriggerValidator::OnSync,
-                            ExecutionTrigger::Always => TriggerValidator::Always,
-                        });
+    /// Move the plans found with the new operation from 'candidate' to 'available', and keep
+    /// the cursor only if other plans can still be found with more operations.
+    fn update_candidates(&mut self, store: &ExecutionPlanStore<O>, step: Option<IndexStep>) {
+        self.candidates = None;
+
+        let step = match step {
+            Some(step) => step,
+            None => return,
+        };
+
+        for id in step.ended {
+            let item = store.get_unchecked(id);
+            let mut triggers = Vec::with_capacity(item.triggers.len());
+
+            for (index, trigger) in item.triggers.iter().enumerate() {
+                triggers.push(match trigger {
+                    ExecutionTrigger::OnOperations(_) => TriggerValidator::OnOperations {
+                        matching: OperationsValidator::new(index),
+                        progress: TriggerProgress::NotInit,
+                    },
+                    ExecutionTrigger::OnSync => TriggerValidator::OnSync,
+                    ExecutionTrigger::Always => TriggerValidator::Always,
+                    ExecutionTrigger::OnQueueLength(length) => {
+                        TriggerValidator::OnQueueLength(*length)
                     }
+                    ExecutionTrigger::Custom(trigger) => TriggerValidator::Custom {
+                        trigger: trigger.0.clone(),
+                        progress: TriggerProgress::NotInit,
+                        fired: false,
+                    },
+                });
+            }
 
-                    self.availables
-                        .push(AvailableItem::new(candidate.id, size, triggers));
-                    candidates_to_remove.push(candidate.id);
-                }
-                ValidatorState::Invalidated => {
-                    candidates_to_remove.push(candidate.id);
-                }
-                ValidatorState::Validating => {}
-            };
+            self.availables
+                .push(AvailableItem::new(id, self.num_operations + 1, triggers));
         }
 
-        let mut updated_candidates = Vec::new();
-        core::mem::swap(&mut updated_candidates, &mut self.candidates);
-
-        self.candidates = updated_candidates
-            .into_iter()
-            .filter(|candidate| !candidates_to_remove.iter().any(|id| id == &candidate.id))
-            .collect();
+        if step.num_pending > 0 {
+            self.candidates = Some(step.cursor);
+        }
     }
 
     fn check_availables(&mut self) {
         for available in self.availables.iter() {
-            for trigger in available.triggers.iter() {
+            for (index, trigger) in available.triggers.iter().enumerate() {
                 match trigger {
                     TriggerValidator::OnOperations {
                         matching,
@@ -173,15 +182,26 @@ impl<O> Policy<O> {
                             size: _size_of_trigger,
                         } = matching.state
                         {
-                            self.found = Some((available.id, available.size));
+                            self.found = Some((available.id, index));
                             return;
                         }
                     }
                     TriggerValidator::Always => {
-                        self.found = Some((available.id, available.size));
+                        self.found = Some((available.id, index));
                         return;
                     }
-                    TriggerValidator::OnSync => {
+                    TriggerValidator::OnQueueLength(length) => {
+                        // The current operation isn't counted yet.
+                        if self.num_operations + 1 >= *length {
+                            self.found = Some((available.id, index));
+                            return;
+                        }
+                    }
+                    TriggerValidator::Custom { fired: true, .. } => {
+                        self.found = Some((available.id, index));
+                        return;
+                    }
+                    TriggerValidator::Custom { fired: false, .. } | TriggerValidator::OnSync => {
                         // Does nothing during an update.
                     }
                 }
@@ -189,36 +209,43 @@ impl<O> Policy<O> {
         }
     }
 
-    fn update_candidates(&mut self, store: &ExecutionPlanStore<O>, operation: &OperationIr) {
-        let main_store = ExecutionPlanOperationsStore::new(store);
-
-        self.candidates
-            .iter_mut()
-            .for_each(|candidate| candidate.update(operation, self.num_operations, &main_store));
-    }
-
     fn update_availables(&mut self, store: &ExecutionPlanStore<O>, operation: &OperationIr) {
+        let num_queued = self.num_operations + 1;
+
         self.availables.iter_mut().for_each(|available| {
             let store_trigger = TriggerOperationsStore::new(available.id, store);
 
-            available.triggers.iter_mut().for_each(|trigger| {
-                if let TriggerValidator::OnOperations { matching, progress } = trigger {
-                    match progress {
-                        TriggerProgress::NotInit => {
-                            *progress = TriggerProgress::NumChecked(0);
+            available
+                .triggers
+                .iter_mut()
+                .for_each(|trigger| match trigger {
+                    TriggerValidator::OnOperations { matching, progress } => {
+                        if let Some(position) = progress.next() {
+                            matching.update(operation, position, &store_trigger);
                         }
-                        TriggerProgress::NumChecked(num_check) => {
-                            matching.update(operation, *num_check, &store_trigger);
-                            *num_check += 1;
+                    }
+                    TriggerValidator::Custom {
+                        trigger,
+                        progress,
+                        fired,
+                    } => {
+                        if let Some(position) = progress.next() {
+                            *fired |= trigger.should_execute(&TriggerContext {
+                                operation,
+                                position,
+                                num_queued,
+                            });
                         }
                     }
-                }
-            });
+                    TriggerValidator::OnQueueLength(_)
+                    | TriggerValidator::Always
+                    | TriggerValidator::OnSync => {}
+                });
         });
     }
 
     fn action_lazy(&self, operations: &[OperationIr]) -> Action {
-        if !self.candidates.is_empty() {
+        if self.candidates.is_some() {
             return Action::Defer;
         }
 
@@ -228,14 +255,20 @@ impl<O> Policy<O> {
             }
 
             for trigger in available.triggers.iter() {
-                if let TriggerValidator::OnOperations {
-                    matching,
-                    progress: _,
-                } = trigger
-                {
-                    if let ValidatorState::Validating = matching.state {
+                match trigger {
+                    TriggerValidator::OnOperations {
+                        matching,
+                        progress: _,
+                    } => {
+                        if let ValidatorState::Validating = matching.state {
+                            return Action::Defer;
+                        }
+                    }
+                    // Those triggers wait for more operations until they fire.
+                    TriggerValidator::OnQueueLength(_) | TriggerValidator::Custom { .. } => {
                         return Action::Defer;
                     }
+                    TriggerValidator::Always | TriggerValidator::OnSync => {}
                 }
             }
         }
@@ -243,21 +276,13 @@ impl<O> Policy<O> {
         Action::Explore
     }
 
-    fn action_sync(&self, operations: &[OperationIr], store: &ExecutionPlanStore<O>) -> Action {
+    fn action_sync(&self, operations: &[OperationIr]) -> Action {
         for available in self.availables.iter() {
             if available.size == operations.len() {
                 return Action::Execute(available.id);
             }
         }
 
-        for candidate in self.candidates.iter() {
-            let item = store.get_unchecked(candidate.id);
-
-            if item.operations.len() == operations.len() {
-                return Action::Execute(candidate.id);
-            }
-        }
-
         Action::Explore
     }
 }
@@ -294,16 +319,20 @@ mod tests {
         let mut policy = Policy::new();
         let stream = TestStream::new(3);
 
-        let id_1 = store.add(ExecutionPlan {
-            operations: stream.operations[0..2].to_vec(),
-            triggers: Vec::new(),
-            optimization: BlockOptimization::new(ExecutionStrategy::operations(2), Vec::new()),
-        });
-        let _id_2 = store.add(ExecutionPlan {
-            operations: stream.operations[0..3].to_vec(),
-            triggers: Vec::new(),
-            optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
-        });
+        let id_1 = store
+            .add(ExecutionPlan {
+                operations: stream.operations[0..2].to_vec(),
+                triggers: Vec::new(),
+                optimization: BlockOptimization::new(ExecutionStrategy::operations(2), Vec::new()),
+            })
+            .unwrap();
+        let _id_2 = store
+            .add(ExecutionPlan {
+                operations: stream.operations[0..3].to_vec(),
+                triggers: Vec::new(),
+                optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
+            })
+            .unwrap();
 
         stream.assert_updates(
             &store,
@@ -312,7 +341,7 @@ mod tests {
             Action::Defer,
         );
 
-        let action = policy.action(&store, &stream.operations[0..2], ExecutionMode::Sync);
+        let action = policy.action(&stream.operations[0..2], ExecutionMode::Sync);
         assert_eq!(action, Action::Execute(id_1));
     }
 
@@ -322,14 +351,16 @@ mod tests {
         let mut policy = Policy::new();
 
         let stream = TestStream::new(3);
-        let id = store.add(ExecutionPlan {
-            operations: stream.operations[0..2].to_vec(),
-            triggers: stream.operations[2..3]
-                .iter()
-                .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
-                .collect(),
-            optimization: BlockOptimization::new(ExecutionStrategy::operations(2), Vec::new()),
-        });
+        let id = store
+            .add(ExecutionPlan {
+                operations: stream.operations[0..2].to_vec(),
+                triggers: stream.operations[2..3]
+                    .iter()
+                    .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
+                    .collect(),
+                optimization: BlockOptimization::new(ExecutionStrategy::operations(2), Vec::new()),
+            })
+            .unwrap();
 
         stream.assert_updates(
             &store,
@@ -360,14 +391,16 @@ mod tests {
         stream_1.new_ops(trigger_id_1);
         stream_2.new_ops(trigger_id_2);
 
-        let id = store.add(ExecutionPlan {
-            operations: stream_1.operations[0..2].to_vec(),
-            triggers: vec![
-                ExecutionTrigger::OnOperations(vec![stream_1.operations[2].clone()]),
-                ExecutionTrigger::OnOperations(vec![stream_2.operations[2].clone()]),
-            ],
-            optimization: BlockOptimization::new(ExecutionStrategy::operations(2), Vec::new()),
-        });
+        let id = store
+            .add(ExecutionPlan {
+                operations: stream_1.operations[0..2].to_vec(),
+                triggers: vec![
+                    ExecutionTrigger::OnOperations(vec![stream_1.operations[2].clone()]),
+                    ExecutionTrigger::OnOperations(vec![stream_2.operations[2].clone()]),
+                ],
+                optimization: BlockOptimization::new(ExecutionStrategy::operations(2), Vec::new()),
+            })
+            .unwrap();
 
         stream_1.assert_updates(
             &store,
@@ -412,22 +445,26 @@ mod tests {
         stream_2.new_ops(5);
         stream_2.new_ops(6);
 
-        let optimization_stream_1 = store.add(ExecutionPlan {
-            operations: stream_1.operations[0..3].to_vec(),
-            triggers: stream_1.operations[3..4]
-                .iter()
-                .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
-                .collect(),
-            optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
-        });
-        let optimization_stream_2 = store.add(ExecutionPlan {
-            operations: stream_2.operations[0..3].to_vec(),
-            triggers: stream_2.operations[3..4]
-                .iter()
-                .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
-                .collect(),
-            optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
-        });
+        let optimization_stream_1 = store
+            .add(ExecutionPlan {
+                operations: stream_1.operations[0..3].to_vec(),
+                triggers: stream_1.operations[3..4]
+                    .iter()
+                    .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
+                    .collect(),
+                optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
+            })
+            .unwrap();
+        let optimization_stream_2 = store
+            .add(ExecutionPlan {
+                operations: stream_2.operations[0..3].to_vec(),
+                triggers: stream_2.operations[3..4]
+                    .iter()
+                    .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
+                    .collect(),
+                optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
+            })
+            .unwrap();
         assert_ne!(optimization_stream_1, optimization_stream_2);
 
         stream_1.assert_updates(
@@ -465,14 +502,16 @@ mod tests {
         stream_2.new_ops(6);
         stream_2.new_ops(7);
 
-        store.add(ExecutionPlan {
-            operations: stream_1.operations[0..3].to_vec(),
-            triggers: stream_1.operations[3..4]
-                .iter()
-                .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
-                .collect(),
-            optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
-        });
+        store
+            .add(ExecutionPlan {
+                operations: stream_1.operations[0..3].to_vec(),
+                triggers: stream_1.operations[3..4]
+                    .iter()
+                    .map(|desc| ExecutionTrigger::OnOperations(vec![desc.clone()]))
+                    .collect(),
+                optimization: BlockOptimization::new(ExecutionStrategy::operations(3), Vec::new()),
+            })
+            .unwrap();
 
         let mut policy = Policy::new();
         // Same path as stream 1
@@ -528,7 +567,7 @@ mod tests {
                         let stream = &self.operations[0..i];
                         let next_ops = &self.operations[i];
                         policy.update(optimizations, next_ops);
-                        let result = policy.action(optimizations, stream, ExecutionMode::Lazy);
+                        let result = policy.action(stream, ExecutionMode::Lazy);
 
                         assert_eq!(result, action);
                     }
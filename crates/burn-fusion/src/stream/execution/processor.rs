@@ -1,34 +1,112 @@
+use std::sync::Arc;
+use std::time::Duration;
+use web_time::Instant;
+
 use burn_ir::OperationIr;
 
-use super::{ExecutionMode, ExplorationAction, Explorer};
-use crate::search::BlockOptimization;
+use super::{BackgroundExplorer, ExecutionMode, ExplorationAction, Explorer};
+use crate::search::{BlockOptimization, ExplorationSettings};
+use crate::stream::ScalarParameterization;
 use crate::stream::execution::{Action, Policy};
-use crate::stream::store::{ExecutionPlan, ExecutionPlanId, ExecutionPlanStore, ExecutionTrigger};
-use crate::{NumOperations, OptimizationBuilder};
+use crate::stream::store::{
+    ExecutionPlan, ExecutionPlanId, ExecutionPlanStore, ExecutionStrategy, ExecutionTrigger,
+};
+use crate::{FusionError, NumOperations, OptimizationBuilder};
 
 /// Process a [stream segment](StreamSegment) following a [policy](Policy).
 pub(crate) struct Processor<O> {
     policy: Policy<O>,
     explorer: Explorer<O>,
+    /// The builders used to start a [background explorer](BackgroundExplorer).
+    builders: Vec<Box<dyn OptimizationBuilder<O>>>,
+    /// Explores the operations executed eagerly when the policy enables
+    /// [background exploration](crate::ExplorationPolicy::background_exploration).
+    background: Option<BackgroundExplorer<O>>,
+    /// The time spent exploring since it was last [taken](Self::take_search_time).
+    search_time: Duration,
 }
 
 /// A part of a stream that can be executed partially using [execution plan](ExecutionPlan).
 pub(crate) trait StreamSegment<O> {
     /// The operations in the segment.
     fn operations(&self) -> &[OperationIr];
+    /// The operations in the segment, as they were registered.
+    fn global_operations(&self) -> &[OperationIr];
+    /// How the scalars of the operations are converted to their relative form.
+    fn scalar_parameterization(&self) -> ScalarParameterization;
     /// Execute part of the segment using the given plan id.
-    fn execute(&mut self, id: ExecutionPlanId, store: &mut ExecutionPlanStore<O>);
+    ///
+    /// Nothing is executed when an error is returned.
+    fn execute(
+        &mut self,
+        id: ExecutionPlanId,
+        store: &mut ExecutionPlanStore<O>,
+    ) -> Result<(), FusionError>;
 }
 
-impl<O: NumOperations> Processor<O> {
+impl<O: NumOperations + Send + 'static> Processor<O> {
     /// Create a new stream processor.
-    pub fn new(optimizations: Vec<Box<dyn OptimizationBuilder<O>>>) -> Self {
+    pub fn new(
+        optimizations: Vec<Box<dyn OptimizationBuilder<O>>>,
+        exploration: ExplorationSettings,
+    ) -> Self {
+        let builders = optimizations.iter().map(|o| o.clone_dyn()).collect();
+        let background = Self::background(&optimizations, &exploration);
+
+        Self {
+            policy: Policy::new(),
+            explorer: Explorer::new(optimizations, exploration),
+            builders,
+            background,
+            search_time: Duration::ZERO,
+        }
+    }
+
+    /// Create a stream processor exploring every sequence of operations before it's executed,
+    /// even when the policy enables
+    /// [background exploration](crate::ExplorationPolicy::background_exploration).
+    pub fn foreground(
+        optimizations: Vec<Box<dyn OptimizationBuilder<O>>>,
+        exploration: ExplorationSettings,
+    ) -> Self {
+        let builders = optimizations.iter().map(|o| o.clone_dyn()).collect();
+
         Self {
             policy: Policy::new(),
-            explorer: Explorer::new(optimizations),
+            explorer: Explorer::new(optimizations, exploration),
+            builders,
+            background: None,
+            search_time: Duration::ZERO,
         }
     }
 
+    /// Update the [settings](ExplorationSettings) used to explore new optimizations.
+    pub fn set_exploration(&mut self, exploration: ExplorationSettings) {
+        self.background = Self::background(&self.builders, &exploration);
+        self.explorer.set_exploration(exploration);
+    }
+
+    fn background(
+        builders: &[Box<dyn OptimizationBuilder<O>>],
+        exploration: &ExplorationSettings,
+    ) -> Option<BackgroundExplorer<O>> {
+        exploration.policy.background_exploration().then(|| {
+            let builders = builders.iter().map(|o| o.clone_dyn()).collect();
+            BackgroundExplorer::new(builders, exploration.clone())
+        })
+    }
+
+    /// The plans that might be executed on the next call to [process](Self::process).
+    pub fn referenced_plans(&self, store: &ExecutionPlanStore<O>) -> Vec<ExecutionPlanId> {
+        self.policy.referenced_plans(store)
+    }
+
+    /// The time spent exploring since the last call, excluding the execution of the plans it
+    /// created.
+    pub fn take_search_time(&mut self) -> Duration {
+        core::mem::take(&mut self.search_time)
+    }
+
     /// Process the [stream segment](StreamSegment) with the provided [mode](ExecutionMode).
     pub fn process<Segment>(
         &mut self,
@@ -42,15 +120,19 @@ impl<O: NumOperations> Processor<O> {
         if let ExecutionMode::Lazy = mode {
             self.on_new_operation(&segment, store);
         }
+        self.commit_explored(&segment, store);
 
         loop {
             if segment.operations().is_empty() {
                 break;
             }
 
-            let action = self.policy.action(store, segment.operations(), mode);
+            let action = self.policy.action(segment.operations(), mode);
 
             match action {
+                Action::Explore if self.background.is_some() => {
+                    self.execute_eagerly(&mut segment, store);
+                }
                 Action::Explore => {
                     self.explore(&mut segment, store, mode);
 
@@ -65,15 +147,113 @@ impl<O: NumOperations> Processor<O> {
                     };
                 }
                 Action::Execute(id) => {
-                    if let ExecutionMode::Sync = mode {
-                        store.add_trigger(id, ExecutionTrigger::OnSync);
+                    if store.get(id).is_none() {
+                        // The plan was removed since the policy found it, so the operations are
+                        // explored again.
+                        self.reset(store, segment.operations());
+                        continue;
                     }
 
-                    segment.execute(id, store);
+                    let sync_trigger = match mode {
+                        ExecutionMode::Sync => {
+                            Some(store.add_trigger(id, ExecutionTrigger::OnSync))
+                        }
+                        ExecutionMode::Lazy => None,
+                    };
+
+                    if let Some(trigger) = self.policy.fired_trigger().or(sync_trigger) {
+                        store.trigger_fired(id, trigger);
+                    }
+                    store.cache_hit();
+
+                    // The operations executed eagerly before don't continue with the plan.
+                    if let Some(background) = &self.background {
+                        background.flush();
+                    }
+                    Self::execute_segment(&mut segment, id, store);
                     self.reset(store, segment.operations());
                 }
             };
         }
+
+        if let (ExecutionMode::Sync, Some(background)) = (mode, &self.background) {
+            background.flush();
+        }
+    }
+
+    /// Execute the first operation of the segment on its own, exploring it in the background
+    /// with the operations following it.
+    ///
+    /// The operation is executed with a plan only fired when syncing, so that the plans explored
+    /// in the background take precedence the next time it is seen.
+    fn execute_eagerly<Segment>(&mut self, segment: &mut Segment, store: &mut ExecutionPlanStore<O>)
+    where
+        Segment: StreamSegment<O>,
+    {
+        let id = store
+            .add(ExecutionPlan {
+                operations: segment.operations()[..1].to_vec(),
+                triggers: Vec::new(),
+                optimization: BlockOptimization::new(
+                    ExecutionStrategy::Operations {
+                        ordering: Arc::new(vec![0]),
+                    },
+                    vec![0],
+                ),
+            })
+            .expect("The segment isn't empty");
+        let trigger = store.add_trigger(id, ExecutionTrigger::OnSync);
+        store.trigger_fired(id, trigger);
+        store.cache_miss();
+
+        if let Some(background) = &self.background {
+            background.register(
+                &segment.global_operations()[0],
+                segment.scalar_parameterization(),
+            );
+        }
+        Self::execute_segment(segment, id, store);
+        self.reset(store, segment.operations());
+    }
+
+    /// Add the plans explored in the background to the store.
+    fn commit_explored<Segment>(&mut self, segment: &Segment, store: &mut ExecutionPlanStore<O>)
+    where
+        Segment: StreamSegment<O>,
+    {
+        let Some(background) = &self.background else {
+            return;
+        };
+        let mut committed = false;
+
+        for plan in background.explored() {
+            // The plan may be merged into a stored plan with the same operations.
+            let id = store
+                .add(ExecutionPlan {
+                    operations: plan.operations,
+                    triggers: Vec::new(),
+                    optimization: plan.optimization,
+                })
+                .expect("An exploration always optimizes at least one operation");
+            store.add_trigger(id, plan.trigger);
+            committed = true;
+        }
+
+        if committed && !segment.operations().is_empty() {
+            self.reset(store, segment.operations());
+        }
+    }
+
+    fn execute_segment<Segment>(
+        segment: &mut Segment,
+        id: ExecutionPlanId,
+        store: &mut ExecutionPlanStore<O>,
+    ) where
+        Segment: StreamSegment<O>,
+    {
+        if let Err(err) = segment.execute(id, store) {
+            panic!("{err}");
+        }
     }
 
     fn on_new_operation<Segment>(&mut self, segment: &Segment, store: &mut ExecutionPlanStore<O>)
@@ -96,16 +276,22 @@ impl<O: NumOperations> Processor<O> {
         store: &mut ExecutionPlanStore<O>,
         mode: ExecutionMode,
     ) {
-        match self.explorer.explore(item.operations(), mode) {
+        let started_at = Instant::now();
+        let action = self.explorer.explore(item.operations(), mode);
+        self.search_time += started_at.elapsed();
+
+        match action {
             ExplorationAction::Completed(optim) => {
-                let id = Self::on_exploration_completed(
+                let (id, trigger) = Self::on_exploration_completed(
                     &self.policy,
                     item.operations(),
                     store,
                     optim,
                     mode,
                 );
-                item.execute(id, store);
+                store.trigger_fired(id, trigger);
+                store.cache_miss();
+                Self::execute_segment(item, id, store);
                 self.reset(store, item.operations());
             }
             ExplorationAction::Continue => {
@@ -116,7 +302,8 @@ impl<O: NumOperations> Processor<O> {
         }
     }
 
-    fn reset(&mut self, store: &mut ExecutionPlanStore<O>, operations: &[OperationIr]) {
+    /// Forget the current exploration and start again from the given operations.
+    pub fn reset(&mut self, store: &mut ExecutionPlanStore<O>, operations: &[OperationIr]) {
         self.explorer.reset(operations);
         self.policy.reset();
 
@@ -128,16 +315,26 @@ impl<O: NumOperations> Processor<O> {
 
     /// We found an optimization (i.e. a new execution plan).
     /// Cache it in the store.
+    ///
+    /// Returns the plan id along with the index of the trigger causing its execution.
     fn on_exploration_completed(
         policy: &Policy<O>,
         operations: &[OperationIr],
         store: &mut ExecutionPlanStore<O>,
         optimization: BlockOptimization<O>,
         mode: ExecutionMode,
-    ) -> ExecutionPlanId {
+    ) -> (ExecutionPlanId, usize) {
         let num_optimized = optimization.ordering.len();
         let relative = &operations[0..num_optimized];
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            num_operations = operations.len(),
+            num_optimized,
+            mode = ?mode,
+            "fusion exploration completed"
+        );
+
         match mode {
             ExecutionMode::Lazy => {
                 let next_ops = &operations[num_optimized..operations.len()];
@@ -150,28 +347,33 @@ impl<O: NumOperations> Processor<O> {
                     ExecutionTrigger::OnOperations(next_ops.to_vec())
                 };
 
-                match policy.action(store, relative, ExecutionMode::Sync) {
-                    Action::Execute(id) => {
-                        store.add_trigger(id, trigger);
-                        id
+                match policy.action(relative, ExecutionMode::Sync) {
+                    Action::Execute(id) => (id, store.add_trigger(id, trigger)),
+                    _ => {
+                        // The plan may be merged into a stored plan with the same operations.
+                        let id = store
+                            .add(ExecutionPlan {
+                                operations: relative.to_vec(),
+                                triggers: Vec::new(),
+                                optimization,
+                            })
+                            .expect("An exploration always optimizes at least one operation");
+                        (id, store.add_trigger(id, trigger))
                     }
-                    _ => store.add(ExecutionPlan {
-                        operations: relative.to_vec(),
-                        triggers: vec![trigger],
-                        optimization,
-                    }),
                 }
             }
-            ExecutionMode::Sync => match policy.action(store, relative, ExecutionMode::Sync) {
-                Action::Execute(id) => {
-                    store.add_trigger(id, ExecutionTrigger::OnSync);
-                    id
+            ExecutionMode::Sync => match policy.action(relative, ExecutionMode::Sync) {
+                Action::Execute(id) => (id, store.add_trigger(id, ExecutionTrigger::OnSync)),
+                _ => {
+                    let id = store
+                        .add(ExecutionPlan {
+                            operations: relative.to_vec(),
+                            triggers: Vec::new(),
+                            optimization,
+                        })
+                        .expect("An exploration always optimizes at least one operation");
+                    (id, store.add_trigger(id, ExecutionTrigger::OnSync))
                 }
-                _ => store.add(ExecutionPlan {
-                    operations: relative.to_vec(),
-                    triggers: vec![ExecutionTrigger::OnSync],
-                    optimization,
-                }),
             },
         }
     }
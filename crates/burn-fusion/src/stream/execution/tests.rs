@@ -6,22 +6,31 @@
 //! To test these components effectively, we create mock types for the stream, optimization,
 //! optimization builder, and stream segment. These mock types aid in comprehensively
 //! understanding the process of optimizing streams.
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use burn_ir::{
-    BinaryOpIr, FloatOperationIr, NumericOperationIr, OperationIr, ScalarOpIr, TensorId, TensorIr,
-    TensorStatus, UnaryOpIr,
+    BinaryOpIr, FloatOperationIr, NumericOperationIr, OperationIr, ReduceDimOpIr, ScalarOpIr,
+    TensorId, TensorIr, TensorStatus, UnaryOpIr,
 };
 use burn_tensor::DType;
+use hashbrown::HashSet;
 
 use crate::{
-    NumOperations, OptimizationBuilder, OptimizationProperties, OptimizationStatus,
-    search::BlockOptimization,
-    stream::store::{
-        ExecutionPlan, ExecutionPlanId, ExecutionPlanStore, ExecutionStrategy, ExecutionTrigger,
+    FusionError, NumOperations, OptimizationBuilder, OptimizationProperties, OptimizationStatus,
+    debug::validate_ordering,
+    search::{BlockOptimization, ExplorationSettings},
+    stream::{
+        ScalarParameterization, StreamId,
+        store::{
+            CustomTrigger, ExecutionPlan, ExecutionPlanId, ExecutionPlanStore, ExecutionStrategy,
+            ExecutionTrigger, PlanTimings, PlanTrigger, TimingSource, TriggerContext,
+        },
     },
 };
 
+#[cfg(feature = "plan-export")]
+use crate::stream::store::{ExecutionPlanState, ExecutionPlanStoreState, PlanCacheHeader};
+
 use super::*;
 
 /// A fake stream of operations for testing purpose.
@@ -29,6 +38,7 @@ pub struct TestStream {
     processor: Processor<TestOptimization>,
     store: ExecutionPlanStore<TestOptimization>,
     executed: Vec<ExecutionPlanId>,
+    orderings: Vec<Vec<usize>>,
     operations: Vec<OperationIr>,
 }
 
@@ -61,6 +71,7 @@ impl NumOperations for TestOptimization {
 pub struct TestSegment<'i> {
     operations: &'i mut Vec<OperationIr>,
     executed: &'i mut Vec<ExecutionPlanId>,
+    orderings: &'i mut Vec<Vec<usize>>,
 }
 
 impl<O> ExecutionStrategy<O> {
@@ -195,6 +206,11 @@ fn should_support_complex_stream() {
     stream.assert_number_of_operations(0);
     stream.assert_number_of_executions(5);
     stream.assert_last_executed(plan_id_3);
+
+    // Each plan was always executed because of its only trigger.
+    stream.assert_triggers_fired(plan_id_1, &[1]);
+    stream.assert_triggers_fired(plan_id_2, &[2]);
+    stream.assert_triggers_fired(plan_id_3, &[2]);
 }
 
 /// In this scenario we will never use an optimization, but we check that we reuse the execution plan stored.
@@ -258,6 +274,723 @@ fn should_reuse_basic_operations() {
     stream.assert_last_executed(plan_id_2);
 }
 
+/// In this scenario we validate that a plan retrieved from the store executes its operations in
+/// the same order as when it was explored.
+#[test]
+fn should_replay_the_same_ordering_on_cache_hit() {
+    let builder_id_1 = 0;
+    let plan_id_1 = 0;
+
+    let builder_1 = TestOptimizationBuilder::new(builder_id_1, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    // Cold exploration.
+    stream.add(operation_1());
+    stream.add(operation_2());
+    stream.assert_number_of_executions(1);
+    stream.assert_last_executed(plan_id_1);
+    stream.assert_last_ordering(&[0, 1]);
+    stream.assert_cache(0, 1);
+
+    // Cache hits.
+    for num_executions in 2..5 {
+        stream.add(operation_1());
+        stream.add(operation_2());
+        stream.assert_number_of_executions(num_executions);
+        stream.assert_last_executed(plan_id_1);
+        stream.assert_last_ordering(&[0, 1]);
+    }
+
+    stream.assert_triggers_fired(plan_id_1, &[4]);
+    stream.assert_cache(3, 1);
+}
+
+/// In this scenario we validate that a plan with the same operations as a stored plan is merged
+/// into it instead of being stored twice.
+#[test]
+fn should_deduplicate_plans_with_the_same_operations() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+    let plan = |trigger| ExecutionPlan {
+        operations: vec![operation_1(), operation_2()],
+        triggers: vec![trigger],
+        optimization: BlockOptimization::new(
+            ExecutionStrategy::optimization(TestOptimization::new(0, 2)),
+            vec![0, 1],
+        ),
+    };
+
+    let id = stream.store.add(plan(ExecutionTrigger::Always)).unwrap();
+    let duplicate = stream.store.add(plan(ExecutionTrigger::OnSync)).unwrap();
+    let again = stream.store.add(plan(ExecutionTrigger::Always)).unwrap();
+
+    assert_eq!(id, duplicate);
+    assert_eq!(id, again);
+    assert_eq!(stream.store.len(), 1);
+    assert_eq!(stream.store.stats().num_deduplicated, 2);
+    stream.assert_triggers_fired(id, &[0, 0]);
+    assert_eq!(
+        stream.store.get_unchecked(id).triggers,
+        vec![ExecutionTrigger::Always, ExecutionTrigger::OnSync]
+    );
+}
+
+/// In this scenario we validate that plans are collected once all the streams that executed them
+/// are finished, unless they are pinned.
+#[test]
+fn should_collect_plans_of_finished_streams() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+    let (stream_1, stream_2) = (StreamId { value: 1 }, StreamId { value: 2 });
+
+    stream.add(operation_3());
+    stream.add(operation_1());
+    stream.add(operation_2());
+    stream.add(operation_2());
+    stream.add(operation_3());
+    assert_eq!(stream.store.len(), 3);
+
+    stream.store.record_stream(0, stream_1);
+    stream.store.record_stream(1, stream_1);
+    stream.store.record_stream(1, stream_2);
+    stream.store.record_stream(2, stream_1);
+    stream.store.pin(2).unwrap();
+
+    let finished = HashSet::from([stream_1]);
+    let collected = stream
+        .store
+        .collect_stream_plans(&finished, &HashSet::new());
+    assert_eq!(collected, vec![0]);
+
+    let finished = HashSet::from([stream_2]);
+    let collected = stream
+        .store
+        .collect_stream_plans(&finished, &HashSet::new());
+    assert_eq!(collected, vec![1]);
+    assert_eq!(stream.store.stats().num_collected, 2);
+    assert_eq!(stream.store.len(), 1);
+}
+
+/// In this scenario we validate that the execution times of a plan are accumulated.
+#[test]
+fn should_record_plan_timings() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+    let plan_id_1 = 0;
+
+    stream.add(operation_1());
+    stream.add(operation_2());
+    assert_eq!(stream.store.timings(plan_id_1), PlanTimings::default());
+
+    for millis in [4, 2, 6] {
+        stream
+            .store
+            .record_timing(plan_id_1, Duration::from_millis(millis), TimingSource::Host);
+    }
+    // Plans that were evicted are ignored.
+    stream
+        .store
+        .record_timing(42, Duration::from_millis(1), TimingSource::Host);
+
+    let timings = stream.store.timings(plan_id_1);
+    assert_eq!(timings.num_samples, 3);
+    assert_eq!(timings.mean(), Duration::from_millis(4));
+    assert_eq!(timings.min, Duration::from_millis(2));
+    assert_eq!(timings.max, Duration::from_millis(6));
+    assert_eq!(timings.last, Duration::from_millis(6));
+    assert_eq!(timings.source, Some(TimingSource::Host));
+}
+
+/// In this scenario we validate that the least recently used plans are evicted, except the ones
+/// still considered by the policy.
+#[test]
+fn should_evict_least_recently_used_plans() {
+    let builder_id_1 = 0;
+    let plan_id_1 = 0;
+    let plan_id_2 = 1;
+    let plan_id_3 = 2;
+
+    let builder_1 = TestOptimizationBuilder::new(builder_id_1, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    stream.add(operation_3());
+    stream.assert_last_executed(plan_id_1);
+    stream.add(operation_1());
+    stream.add(operation_2());
+    stream.assert_last_executed(plan_id_2);
+
+    assert_eq!(stream.evict(1), vec![plan_id_1]);
+
+    // The evicted plan is explored again under a new id.
+    stream.add(operation_3());
+    stream.assert_last_executed(plan_id_3);
+
+    // The second plan is a candidate for the queued operation, so it can't be evicted.
+    stream.add(operation_1());
+    assert_eq!(stream.evict(0), vec![plan_id_3]);
+    stream.add(operation_2());
+    stream.assert_last_executed(plan_id_2);
+
+    let stats = stream.store.stats();
+    assert_eq!(stats.num_plans, 1);
+    assert_eq!(stats.num_created, 3);
+    assert_eq!(stats.num_evicted, 2);
+}
+
+/// In this scenario we validate that plans can be evicted down to a number of plans, e.g. under
+/// memory pressure, without changing the capacity of the store.
+#[test]
+fn should_evict_plans_down_to_a_number_of_plans() {
+    let plan_id_1 = 0;
+    let plan_id_2 = 1;
+
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    stream.add(operation_3());
+    stream.add(operation_1());
+    stream.add(operation_2());
+    stream.assert_last_executed(plan_id_2);
+
+    let referenced = HashSet::new();
+    assert!(stream.store.evict_down_to(2, &referenced).is_empty());
+    assert_eq!(stream.store.evict_down_to(1, &referenced), vec![plan_id_1]);
+    assert!(stream.store.evict(&referenced).is_empty());
+
+    let stats = stream.store.stats();
+    assert_eq!(stats.num_plans, 1);
+    assert_eq!(stats.num_evicted, 1);
+    assert_eq!(stats.capacity, None);
+}
+
+/// In this scenario we validate that evicted plans can't be retrieved nor executed anymore.
+#[test]
+fn should_not_execute_evicted_plans() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+    let plan_id_1 = 0;
+    let plan_id_2 = 1;
+
+    stream.add(operation_3());
+    assert!(stream.store.get(plan_id_1).is_some());
+    assert_eq!(stream.evict(0), vec![plan_id_1]);
+    assert!(stream.store.get(plan_id_1).is_none());
+    assert_eq!(stream.store.len(), 0);
+    assert_eq!(stream.store.iter().count(), 0);
+
+    let mut operations = vec![operation_3()];
+    let mut segment =
+        TestSegment::new(&mut operations, &mut stream.executed, &mut stream.orderings);
+    assert_eq!(
+        segment.execute(plan_id_1, &mut stream.store),
+        Err(FusionError::PlanNotFound { plan: plan_id_1 })
+    );
+    assert_eq!(operations.len(), 1);
+    stream.assert_number_of_executions(1);
+
+    // The operations are explored again.
+    stream.add(operation_3());
+    stream.assert_last_executed(plan_id_2);
+}
+
+/// In this scenario we validate that pinned plans are never evicted, whether they are pinned
+/// explicitly or executed while pinning is enabled.
+#[test]
+fn should_not_evict_pinned_plans() {
+    let builder_id_1 = 0;
+    let plan_id_1 = 0;
+    let plan_id_2 = 1;
+    let plan_id_3 = 2;
+
+    let builder_1 = TestOptimizationBuilder::new(builder_id_1, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    stream.add(operation_3());
+    stream.assert_last_executed(plan_id_1);
+    assert_eq!(stream.store.pin(plan_id_1), Ok(()));
+
+    stream.store.set_pin_executed(true);
+    stream.add(operation_1());
+    stream.add(operation_2());
+    stream.assert_last_executed(plan_id_2);
+    assert!(stream.store.set_pin_executed(false));
+
+    stream.add(operation_1());
+    stream.add(operation_3());
+    stream.assert_last_executed(plan_id_3);
+
+    assert_eq!(stream.evict(2), vec![plan_id_3]);
+    assert_eq!(stream.store.stats().num_pinned, 2);
+
+    assert!(stream.store.unpin(plan_id_1));
+    assert!(!stream.store.unpin(plan_id_1));
+    assert_eq!(stream.evict(1), vec![plan_id_1]);
+}
+
+/// In this scenario we validate that invalid plan ids and capacities are reported as errors.
+#[test]
+fn should_report_plan_store_errors() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    stream.add(operation_3());
+    stream.add(operation_1());
+    stream.add(operation_2());
+
+    assert_eq!(
+        stream.store.pin(42),
+        Err(FusionError::PlanNotFound { plan: 42 })
+    );
+    assert_eq!(stream.store.pin(0), Ok(()));
+    assert_eq!(
+        stream.store.set_capacity(Some(0)),
+        Err(FusionError::StoreCapacityExceeded {
+            capacity: 0,
+            num_pinned: 1,
+        })
+    );
+    assert_eq!(stream.store.set_capacity(Some(1)), Ok(()));
+    assert_eq!(
+        stream.store.pin(1),
+        Err(FusionError::StoreCapacityExceeded {
+            capacity: 1,
+            num_pinned: 2,
+        })
+    );
+    assert_eq!(stream.store.stats().capacity, Some(1));
+}
+
+/// In this scenario we validate that plans exported from a stream are executed without any
+/// exploration once imported in another stream.
+#[cfg(feature = "plan-export")]
+#[test]
+fn should_execute_imported_plans() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1.clone())]);
+
+    stream.add(operation_3());
+    stream.add(operation_1());
+    stream.add(operation_2());
+    stream.assert_cache(0, 2);
+    stream.store.pin(1).unwrap();
+
+    let state = stream.store.to_state(|opt| (opt.builder_id, opt.size));
+    let header = PlanCacheHeader::new("test");
+    let mut json = Vec::new();
+    state.write_json(&mut json, &header).unwrap();
+
+    // Plans exported by another backend are ignored.
+    let other = PlanCacheHeader::new("other");
+    let ignored = ExecutionPlanStoreState::<(usize, usize)>::read_json(json.as_slice(), &other);
+    assert!(matches!(ignored, Ok(None)));
+
+    let state = ExecutionPlanStoreState::<(usize, usize)>::read_json(json.as_slice(), &header)
+        .unwrap()
+        .unwrap();
+
+    let mut imported = TestStream::new(vec![Box::new(builder_1)]);
+    let added = imported.store.load_state(state, |(builder_id, size)| {
+        TestOptimization::new(builder_id, size)
+    });
+    assert_eq!(added, vec![0, 1]);
+    assert_eq!(imported.store.pinned().collect::<Vec<_>>(), vec![1]);
+
+    imported.add(operation_3());
+    imported.add(operation_1());
+    imported.add(operation_2());
+    imported.assert_cache(2, 0);
+    imported.assert_last_executed(1);
+    imported.assert_last_ordering(&[0, 1]);
+
+    // Plans already stored aren't added twice.
+    let state = stream.store.to_state(|opt| (opt.builder_id, opt.size));
+    let added = imported.store.load_state(state, |(builder_id, size)| {
+        TestOptimization::new(builder_id, size)
+    });
+    assert!(added.is_empty());
+    assert_eq!(imported.store.len(), 2);
+}
+
+/// In this scenario we validate that plan caches exported by another build, or before caches
+/// were versioned, are ignored without parsing their plans.
+#[cfg(feature = "plan-export")]
+#[test]
+fn should_ignore_stale_plan_caches() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    stream.add(operation_3());
+    stream.add(operation_1());
+    stream.add(operation_2());
+
+    let state = stream.store.to_state(|opt| (opt.builder_id, opt.size));
+    let header = PlanCacheHeader::new("test");
+    let read = |json: &[u8]| ExecutionPlanStoreState::<(usize, usize)>::read_json(json, &header);
+
+    // Caches exported before they were versioned don't have any header.
+    let legacy = serde_json::to_vec(&state).unwrap();
+    assert!(matches!(read(&legacy), Ok(None)));
+
+    let stale = [
+        PlanCacheHeader {
+            format: header.format + 1,
+            ..header.clone()
+        },
+        PlanCacheHeader {
+            burn_version: "0.1.0".to_string(),
+            ..header.clone()
+        },
+        PlanCacheHeader {
+            ir_schema: header.ir_schema ^ 1,
+            ..header.clone()
+        },
+    ];
+    for stale in stale.iter() {
+        let mut json = Vec::new();
+        state.write_json(&mut json, stale).unwrap();
+        assert!(matches!(read(&json), Ok(None)));
+    }
+
+    // The plans of a stale cache might not be readable with the current schema.
+    let unreadable = serde_json::json!({ "header": stale[2], "plans": "unreadable" });
+    assert!(matches!(read(unreadable.to_string().as_bytes()), Ok(None)));
+
+    let mut json = Vec::new();
+    state.write_json(&mut json, &header).unwrap();
+    assert_eq!(read(&json).unwrap().unwrap().plans.len(), 2);
+
+    // NDJSON exports start with the header.
+    let mut bytes = Vec::new();
+    stream
+        .store
+        .write_ndjson(&mut bytes, Some(&header), 0, |opt| {
+            (opt.builder_id, opt.size)
+        })
+        .unwrap();
+    let first = String::from_utf8(bytes)
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .to_string();
+    assert_eq!(
+        serde_json::from_str::<PlanCacheHeader>(&first).unwrap(),
+        header
+    );
+}
+
+/// In this scenario we validate that user triggers control when a stored plan is executed,
+/// without waiting for the operations that triggered it during exploration.
+#[test]
+fn should_execute_plans_on_user_triggers() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+    let plan_id_1 = 0;
+
+    stream.add(operation_1());
+    stream.sync();
+    stream.assert_plan(
+        plan_id_1,
+        ExecutionPlan {
+            operations: vec![operation_1()],
+            triggers: vec![ExecutionTrigger::OnSync],
+            optimization: BlockOptimization::new(ExecutionStrategy::operations(1), vec![0]),
+        },
+    );
+
+    // The plan is executed once followed by `operation_3` instead of waiting for a sync.
+    let custom = PlanTrigger::Custom(Arc::new(FollowedBy(operation_3())));
+    stream.store.add_plan_trigger(plan_id_1, custom).unwrap();
+    stream.add(operation_1());
+    stream.assert_number_of_operations(1);
+    stream.add(operation_3());
+    stream.assert_number_of_operations(0);
+    stream.assert_triggers_fired(plan_id_1, &[1, 1]);
+
+    // The plan is executed as soon as its operations are queued.
+    let on_length = PlanTrigger::OnQueueLength(1);
+    stream.store.add_plan_trigger(plan_id_1, on_length).unwrap();
+    stream.add(operation_1());
+    stream.assert_last_executed(plan_id_1);
+    stream.assert_number_of_operations(0);
+    stream.assert_triggers_fired(plan_id_1, &[1, 1, 1]);
+
+    // Custom triggers aren't exported.
+    let state = stream.store.to_state(|opt| (opt.builder_id, opt.size));
+    assert_eq!(
+        state.plans[plan_id_1].triggers,
+        vec![ExecutionTrigger::OnSync, ExecutionTrigger::OnQueueLength(1)]
+    );
+
+    assert!(matches!(
+        stream
+            .store
+            .add_plan_trigger(42, PlanTrigger::OnQueueLength(1)),
+        Err(FusionError::PlanNotFound { plan: 42 })
+    ));
+}
+
+#[derive(Debug)]
+struct FollowedBy(OperationIr);
+
+impl CustomTrigger for FollowedBy {
+    fn should_execute(&self, context: &TriggerContext<'_>) -> bool {
+        context.operation == &self.0
+    }
+}
+
+/// In this scenario we validate that plans encoded in the binary format are loaded like JSON
+/// ones, and that the format is detected when reading.
+#[cfg(feature = "plan-export")]
+#[test]
+fn should_read_binary_plan_caches() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    stream.add(operation_3());
+    stream.add(operation_1());
+    stream.add(operation_2());
+
+    let state = stream.store.to_state(|opt| (opt.builder_id, opt.size));
+    let header = PlanCacheHeader::new("test");
+    let (mut json, mut bytes) = (Vec::new(), Vec::new());
+    state.write_json(&mut json, &header).unwrap();
+    state.write_bytes(&mut bytes, &header).unwrap();
+    assert!(bytes.len() < json.len());
+
+    let ids = |state: ExecutionPlanStoreState<(usize, usize)>| {
+        state
+            .plans
+            .into_iter()
+            .map(|plan| (plan.id, plan.operations))
+            .collect::<Vec<_>>()
+    };
+    let expected = ids(stream.store.to_state(|opt| (opt.builder_id, opt.size)));
+    for encoded in [&json, &bytes] {
+        let state = ExecutionPlanStoreState::<(usize, usize)>::read(encoded.as_slice(), &header)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ids(state), expected);
+    }
+
+    // Plans exported by another backend are ignored.
+    let other = PlanCacheHeader::new("other");
+    let ignored = ExecutionPlanStoreState::<(usize, usize)>::read_bytes(bytes.as_slice(), &other);
+    assert!(matches!(ignored, Ok(None)));
+
+    // JSON isn't mistaken for the binary format.
+    let invalid = ExecutionPlanStoreState::<(usize, usize)>::read_bytes(json.as_slice(), &header);
+    assert!(matches!(
+        invalid,
+        Err(FusionError::PlanSerialization { .. })
+    ));
+}
+
+/// In this scenario we validate that plans are written one per line, and that only the plans
+/// created after a given id are written.
+#[cfg(feature = "plan-export")]
+#[test]
+fn should_write_plans_as_ndjson() {
+    let builder_1 = TestOptimizationBuilder::new(0, vec![operation_1(), operation_2()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+    let to_state = |opt: &TestOptimization| (opt.builder_id, opt.size);
+    let ids = |bytes: &[u8]| {
+        String::from_utf8(bytes.to_vec())
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<ExecutionPlanState<(usize, usize)>>(line)
+                    .unwrap()
+                    .id
+            })
+            .collect::<Vec<_>>()
+    };
+
+    stream.add(operation_3());
+    stream.add(operation_1());
+    stream.add(operation_2());
+
+    let mut bytes = Vec::new();
+    assert_eq!(
+        stream.store.write_ndjson(&mut bytes, None, 0, to_state),
+        Ok(2)
+    );
+    assert_eq!(ids(&bytes), vec![0, 1]);
+
+    let since = stream.store.num_created();
+    stream.add(operation_2());
+    stream.add(operation_2());
+
+    let mut bytes = Vec::new();
+    assert_eq!(
+        stream.store.write_ndjson(&mut bytes, None, since, to_state),
+        Ok(1)
+    );
+    assert_eq!(ids(&bytes), vec![2]);
+}
+
+/// In this scenario we validate that independent element wise operations with the same shape,
+/// e.g. the updates of different parameters, are fused horizontally in a single plan.
+#[test]
+fn should_fuse_independent_operations_horizontally() {
+    let exp = |input: u64, out: u64| {
+        let tensor = |id, status| TensorIr {
+            id: TensorId::new(id),
+            shape: vec![32, 32],
+            status,
+            dtype: DType::F32,
+        };
+        OperationIr::Float(
+            DType::F32,
+            FloatOperationIr::Exp(UnaryOpIr {
+                input: tensor(input, TensorStatus::ReadOnly),
+                out: tensor(out, TensorStatus::NotInit),
+            }),
+        )
+    };
+    let builder_1 = TestOptimizationBuilder::new(0, vec![exp(10, 11), exp(20, 21)]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1)]);
+
+    stream.add(exp(10, 11));
+    stream.add(exp(20, 21));
+    stream.assert_number_of_executions(1);
+    stream.assert_number_of_operations(0);
+    stream.assert_plan(
+        0,
+        ExecutionPlan {
+            operations: vec![exp(10, 11), exp(20, 21)],
+            triggers: vec![ExecutionTrigger::Always],
+            optimization: BlockOptimization {
+                strategy: ExecutionStrategy::optimization(TestOptimization::new(0, 2)),
+                ordering: vec![0, 1],
+            },
+        },
+    );
+}
+
+/// In this scenario we validate that the element wise operations following a reduction ending
+/// every optimization, e.g. in a softmax, are explored in the same plan as the reduction.
+#[test]
+fn should_compose_reductions_with_their_epilogue() {
+    let tensor = |id, shape: [usize; 2], status| TensorIr {
+        id: TensorId::new(id),
+        shape: shape.to_vec(),
+        status,
+        dtype: DType::F32,
+    };
+    let sum = OperationIr::NumericFloat(
+        DType::F32,
+        NumericOperationIr::SumDim(ReduceDimOpIr {
+            input: tensor(1, [32, 32], TensorStatus::ReadOnly),
+            out: tensor(2, [32, 1], TensorStatus::NotInit),
+            axis: 1,
+        }),
+    );
+    let exp = |input: u64, out: u64| {
+        OperationIr::Float(
+            DType::F32,
+            FloatOperationIr::Exp(UnaryOpIr {
+                input: tensor(input, [32, 1], TensorStatus::ReadOnly),
+                out: tensor(out, [32, 1], TensorStatus::NotInit),
+            }),
+        )
+    };
+    let builder_1 = TestOptimizationBuilder::new(0, vec![sum.clone()]);
+    let builder_2 = TestOptimizationBuilder::new(1, vec![exp(2, 3), exp(3, 4)]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1), Box::new(builder_2)]);
+
+    stream.add(sum.clone());
+    // The reduction closed every builder, but its epilogue is still explored.
+    stream.assert_number_of_executions(0);
+
+    stream.add(exp(2, 3));
+    stream.add(exp(3, 4));
+    stream.assert_number_of_executions(1);
+    stream.assert_number_of_operations(0);
+    stream.assert_plan(
+        0,
+        ExecutionPlan {
+            operations: vec![sum, exp(2, 3), exp(3, 4)],
+            triggers: vec![ExecutionTrigger::Always],
+            optimization: BlockOptimization {
+                strategy: ExecutionStrategy::Composed(vec![
+                    Box::new(ExecutionStrategy::optimization(TestOptimization::new(0, 1))),
+                    Box::new(ExecutionStrategy::Optimization {
+                        opt: TestOptimization::new(1, 2),
+                        ordering: Arc::new(vec![1, 2]),
+                    }),
+                ]),
+                ordering: vec![0, 1, 2],
+            },
+        },
+    );
+}
+
+/// In this scenario we validate that the bias and the activation following a matrix
+/// multiplication ending every optimization, e.g. in a linear layer, are explored in the same
+/// plan as the matrix multiplication.
+#[test]
+fn should_compose_matmuls_with_their_epilogue() {
+    let tensor = |id, shape: [usize; 2], status| TensorIr {
+        id: TensorId::new(id),
+        shape: shape.to_vec(),
+        status,
+        dtype: DType::F32,
+    };
+    let matmul = OperationIr::Float(
+        DType::F32,
+        FloatOperationIr::Matmul(BinaryOpIr {
+            lhs: tensor(1, [8, 16], TensorStatus::ReadOnly),
+            rhs: tensor(2, [16, 32], TensorStatus::ReadOnly),
+            out: tensor(3, [8, 32], TensorStatus::NotInit),
+        }),
+    );
+    let bias = OperationIr::NumericFloat(
+        DType::F32,
+        NumericOperationIr::Add(BinaryOpIr {
+            lhs: tensor(3, [8, 32], TensorStatus::ReadOnly),
+            rhs: tensor(4, [1, 32], TensorStatus::ReadOnly),
+            out: tensor(5, [8, 32], TensorStatus::NotInit),
+        }),
+    );
+    let activation = OperationIr::Float(
+        DType::F32,
+        FloatOperationIr::Tanh(UnaryOpIr {
+            input: tensor(5, [8, 32], TensorStatus::ReadOnly),
+            out: tensor(6, [8, 32], TensorStatus::NotInit),
+        }),
+    );
+    let builder_1 = TestOptimizationBuilder::new(0, vec![matmul.clone()]);
+    let builder_2 = TestOptimizationBuilder::new(1, vec![bias.clone(), activation.clone()]);
+    let mut stream = TestStream::new(vec![Box::new(builder_1), Box::new(builder_2)]);
+
+    stream.add(matmul.clone());
+    stream.add(bias.clone());
+    stream.assert_number_of_executions(0);
+
+    stream.add(activation.clone());
+    stream.assert_number_of_executions(1);
+    stream.assert_number_of_operations(0);
+    stream.assert_last_ordering(&[0, 1, 2]);
+    stream.assert_plan(
+        0,
+        ExecutionPlan {
+            operations: vec![matmul, bias, activation],
+            triggers: vec![ExecutionTrigger::Always],
+            optimization: BlockOptimization {
+                strategy: ExecutionStrategy::Composed(vec![
+                    Box::new(ExecutionStrategy::optimization(TestOptimization::new(0, 1))),
+                    Box::new(ExecutionStrategy::Optimization {
+                        opt: TestOptimization::new(1, 2),
+                        ordering: Arc::new(vec![1, 2]),
+                    }),
+                ]),
+                ordering: vec![0, 1, 2],
+            },
+        },
+    );
+}
+
 // In this scenario we validate that we support multiple optimization builders with overlapping
 // operations.
 //
@@ -412,6 +1145,10 @@ fn should_support_overlapping_optimizations() {
         },
     );
 
+    // The first plan was executed once because of each of its triggers.
+    stream.assert_triggers_fired(plan_id_1, &[1, 1, 1]);
+    stream.assert_triggers_fired(plan_id_4, &[1]);
+
     stream.add(operation_3());
     stream.assert_last_executed(plan_id_5);
     stream.assert_plan(
@@ -434,9 +1171,13 @@ impl TestStream {
     /// Create a new stream with the given optimization builders.
     fn new(optimizations: Vec<Box<dyn OptimizationBuilder<TestOptimization>>>) -> Self {
         Self {
-            processor: Processor::<TestOptimization>::new(optimizations),
+            processor: Processor::<TestOptimization>::new(
+                optimizations,
+                ExplorationSettings::default(),
+            ),
             store: ExecutionPlanStore::<TestOptimization>::new(),
             executed: Vec::new(),
+            orderings: Vec::new(),
             operations: Vec::new(),
         }
     }
@@ -445,7 +1186,11 @@ impl TestStream {
     fn add(&mut self, operation: OperationIr) {
         self.operations.push(operation);
         self.processor.process(
-            TestSegment::new(&mut self.operations, &mut self.executed),
+            TestSegment::new(
+                &mut self.operations,
+                &mut self.executed,
+                &mut self.orderings,
+            ),
             &mut self.store,
             ExecutionMode::Lazy,
         );
@@ -454,12 +1199,30 @@ impl TestStream {
     /// Sync the stream.
     fn sync(&mut self) {
         self.processor.process(
-            TestSegment::new(&mut self.operations, &mut self.executed),
+            TestSegment::new(
+                &mut self.operations,
+                &mut self.executed,
+                &mut self.orderings,
+            ),
             &mut self.store,
             ExecutionMode::Sync,
         );
     }
 
+    /// Evict plans until the store holds at most `capacity` plans.
+    fn evict(&mut self, capacity: usize) -> Vec<ExecutionPlanId> {
+        let referenced = self
+            .processor
+            .referenced_plans(&self.store)
+            .into_iter()
+            .collect();
+
+        self.store
+            .set_capacity(Some(capacity))
+            .expect("The capacity should fit the pinned plans");
+        self.store.evict(&referenced)
+    }
+
     /// Assert that the plan has been executed as provided.
     fn assert_plan(&self, id: ExecutionPlanId, expected: ExecutionPlan<TestOptimization>) {
         let actual = self.store.get_unchecked(id);
@@ -475,6 +1238,26 @@ impl TestStream {
         }
     }
 
+    /// Assert the order in which the operations of the last executed plan were executed.
+    fn assert_last_ordering(&self, expected: &[usize]) {
+        match self.orderings.last() {
+            Some(ordering) => assert_eq!(ordering, expected),
+            None => panic!("No plan has been executed"),
+        }
+    }
+
+    /// Assert the number of times each trigger of the plan fired.
+    fn assert_triggers_fired(&self, id: ExecutionPlanId, expected: &[u64]) {
+        assert_eq!(self.store.fired(id), expected);
+    }
+
+    /// Assert the number of plans executed with and without exploration.
+    fn assert_cache(&self, num_hits: u64, num_misses: u64) {
+        let cache = self.store.cache();
+        assert_eq!(cache.num_hits, num_hits, "Same cache hits");
+        assert_eq!(cache.num_misses, num_misses, "Same cache misses");
+    }
+
     /// Assert the number of executions since the start of the stream.
     fn assert_number_of_executions(&self, number: usize) {
         assert_eq!(self.executed.len(), number);
@@ -572,13 +1355,36 @@ impl StreamSegment<TestOptimization> for TestSegment<'_> {
         self.operations
     }
 
+    // The test operations are registered in their relative form.
+    fn global_operations(&self) -> &[OperationIr] {
+        self.operations
+    }
+
+    fn scalar_parameterization(&self) -> ScalarParameterization {
+        ScalarParameterization::default()
+    }
+
     // Execute the process.
-    fn execute(&mut self, id: ExecutionPlanId, store: &mut ExecutionPlanStore<TestOptimization>) {
-        let execution_plan = store.get_unchecked(id);
+    fn execute(
+        &mut self,
+        id: ExecutionPlanId,
+        store: &mut ExecutionPlanStore<TestOptimization>,
+    ) -> Result<(), FusionError> {
+        let execution_plan = store
+            .get(id)
+            .ok_or(FusionError::PlanNotFound { plan: id })?;
+        let ordering = execution_plan.optimization.strategy.ordering();
+
+        if let Err(violation) = validate_ordering(self.operations, &ordering) {
+            panic!("Invalid ordering for execution plan {id}: {violation}");
+        }
 
         self.execute_strategy(&execution_plan.optimization.strategy);
 
         self.executed.push(id);
+        self.orderings.push(ordering);
+
+        Ok(())
     }
 }
 
@@ -1,12 +1,14 @@
+use std::sync::Arc;
+
 use burn_ir::OperationIr;
 
-use crate::stream::store::{ExecutionPlanId, ExecutionPlanStore, ExecutionTrigger};
+use crate::stream::store::{CustomTrigger, ExecutionPlanId, ExecutionPlanStore, ExecutionTrigger};
 
 /// Compare each operation in the list of operations provided by the [store](OperationsStore)
 /// to verify if the newly added operations match the original list.
 ///
-/// It is used by the [policy](crate::stream::execution::Policy) to check each candidate as well
-/// as to verify if a list of operations is optimal to execute based on their triggers.
+/// It is used by the [policy](crate::stream::execution::Policy) to verify if a list of operations
+/// is optimal to execute based on their triggers.
 #[derive(Debug)]
 pub(crate) struct OperationsValidator<ID> {
     /// The ID used to retrieve the operation list.
@@ -93,6 +95,12 @@ pub(crate) enum TriggerValidator {
     },
     Always,
     OnSync,
+    OnQueueLength(usize),
+    Custom {
+        trigger: Arc<dyn CustomTrigger>,
+        progress: TriggerProgress,
+        fired: bool,
+    },
 }
 
 /// The progress made into the trigger validation process.
@@ -104,6 +112,24 @@ pub(crate) enum TriggerProgress {
     NumChecked(usize),
 }
 
+impl TriggerProgress {
+    /// Move to the next operation, returning the position of the operation to check after the
+    /// plan, or `None` for the last operation of the plan itself.
+    pub(crate) fn next(&mut self) -> Option<usize> {
+        match self {
+            Self::NotInit => {
+                *self = Self::NumChecked(0);
+                None
+            }
+            Self::NumChecked(num_check) => {
+                let position = *num_check;
+                *num_check += 1;
+                Some(position)
+            }
+        }
+    }
+}
+
 /// An execution plan can have many triggers, so we use the position in the list to identify a
 /// trigger.
 pub(crate) type TriggerId = usize;
@@ -114,23 +140,10 @@ impl<O> OperationsStore for TriggerOperationsStore<'_, O> {
     fn get(&self, id: Self::Id) -> &[OperationIr] {
         match &self.store.get_unchecked(self.id).triggers[id] {
             ExecutionTrigger::OnOperations(operations) => operations,
-            ExecutionTrigger::OnSync => &[],
-            ExecutionTrigger::Always => &[],
+            ExecutionTrigger::OnSync
+            | ExecutionTrigger::Always
+            | ExecutionTrigger::OnQueueLength(_)
+            | ExecutionTrigger::Custom(_) => &[],
         }
     }
 }
-
-/// [Operations store](OperationsStore) used to retrieve the list of operations for an
-/// [execution plan](crate::stream::store::ExecutionPlan).
-#[derive(new)]
-pub(crate) struct ExecutionPlanOperationsStore<'a, O> {
-    store: &'a ExecutionPlanStore<O>,
-}
-
-impl<O> OperationsStore for ExecutionPlanOperationsStore<'_, O> {
-    type Id = ExecutionPlanId;
-
-    fn get(&self, id: Self::Id) -> &[OperationIr] {
-        &self.store.get_unchecked(id).operations
-    }
-}
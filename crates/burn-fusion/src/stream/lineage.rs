@@ -0,0 +1,229 @@
+use core::hash::{Hash, Hasher};
+
+use burn_ir::{BaseOperationIr, Dim, OperationIr, ShapeSymbols, TensorId, TensorIr, TensorStatus};
+use hashbrown::HashMap;
+
+use super::{OperationConverter, RelativeOps};
+
+/// Tracks the fingerprint of the computation that produced each tensor alive on a device.
+///
+/// The fingerprint of a tensor hashes the operation that wrote it, with its exact shapes and
+/// scalars, along with the fingerprints of its inputs. Tensors computed the same way from the same
+/// tensors have the same fingerprint, whatever their ids and whenever they were computed.
+///
+/// Dimensions [bound to a symbol](Self::bind_symbol) are hashed as their symbol instead of their
+/// size, so that computations only differing by e.g. their batch size share their fingerprint.
+#[derive(Default)]
+pub(crate) struct TensorLineage {
+    fingerprints: HashMap<TensorId, u64>,
+    symbols: ShapeSymbols,
+}
+
+impl TensorLineage {
+    /// Update the fingerprints with a newly registered operation.
+    pub(crate) fn register(&mut self, operation: &OperationIr) {
+        if let OperationIr::Drop(tensor) = operation {
+            self.fingerprints.remove(&tensor.id);
+            self.symbols.register(operation);
+            return;
+        }
+
+        let nodes = operation.nodes();
+        if nodes.is_empty() {
+            return;
+        }
+
+        let mut hasher = std::hash::DefaultHasher::new();
+        let mut converter = OperationConverter::default();
+        operation.to_relative(&mut converter).hash(&mut hasher);
+        converter.hash_scalars(&mut hasher);
+        hash_ranges(operation, &mut hasher);
+
+        // Consumed inputs are forgotten by the symbols, while outputs are only bound to symbols
+        // once the operation is registered.
+        let mut dims = nodes
+            .iter()
+            .map(|tensor| self.symbols.dims(tensor))
+            .collect::<Vec<_>>();
+        self.symbols.register(operation);
+
+        for (tensor, dims) in nodes.iter().zip(dims.iter_mut()) {
+            if tensor.status == TensorStatus::NotInit {
+                *dims = self.symbols.dims(tensor);
+            }
+            dims.hash(&mut hasher);
+            if tensor.status != TensorStatus::NotInit {
+                self.fingerprint(tensor.id).hash(&mut hasher);
+            }
+        }
+
+        let fingerprint = hasher.finish();
+        let mut outputs = 0u64;
+
+        for tensor in nodes.into_iter() {
+            match tensor.status {
+                TensorStatus::NotInit => {
+                    self.fingerprints
+                        .insert(tensor.id, output_fingerprint(fingerprint, outputs));
+                    outputs += 1;
+                }
+                // Last use of the tensor.
+                TensorStatus::ReadWrite => {
+                    self.fingerprints.remove(&tensor.id);
+                }
+                TensorStatus::ReadOnly => {}
+            }
+        }
+    }
+
+    /// Bind a dimension of a tensor to the symbol with the given name.
+    ///
+    /// The tensor is considered an input of a dynamic workload, e.g. a batch of requests, so its
+    /// fingerprint is derived from its symbolic shape and its data type, regardless of how it
+    /// was computed.
+    pub(crate) fn bind_symbol(&mut self, tensor: &TensorIr, axis: usize, name: &str) {
+        let symbol = self.symbols.symbol(name);
+        self.symbols.bind(tensor, axis, symbol);
+
+        let mut hasher = std::hash::DefaultHasher::new();
+        (self.symbols.dims(tensor), tensor.dtype).hash(&mut hasher);
+        self.fingerprints.insert(tensor.id, hasher.finish());
+    }
+
+    /// The dimensions of a tensor, symbolic where bound to a symbol.
+    pub(crate) fn dims(&self, tensor: &TensorIr) -> Vec<Dim> {
+        self.symbols.dims(tensor)
+    }
+
+    /// The fingerprint of a tensor.
+    ///
+    /// Tensors that weren't written by an operation, e.g. created from data, have no lineage, so
+    /// their fingerprint is derived from their id.
+    pub(crate) fn fingerprint(&self, tensor: TensorId) -> u64 {
+        match self.fingerprints.get(&tensor) {
+            Some(fingerprint) => *fingerprint,
+            None => {
+                let mut hasher = std::hash::DefaultHasher::new();
+                tensor.hash(&mut hasher);
+                hasher.finish()
+            }
+        }
+    }
+}
+
+fn output_fingerprint(operation: u64, output: u64) -> u64 {
+    let mut hasher = std::hash::DefaultHasher::new();
+    (operation, output).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Slice ranges aren't kept by the relative form, but slices with the same shapes over different
+/// ranges compute different tensors.
+fn hash_ranges<H: Hasher>(operation: &OperationIr, state: &mut H) {
+    let base = match operation {
+        OperationIr::BaseFloat(ops) | OperationIr::BaseInt(ops) | OperationIr::BaseBool(ops) => ops,
+        _ => return,
+    };
+
+    match base {
+        BaseOperationIr::Slice(desc) => desc.ranges.hash(state),
+        BaseOperationIr::SliceAssign(desc) => desc.ranges.hash(state),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{InitOperationIr, NumericOperationIr, ScalarOpIr};
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::test_utils::{exp, tensor, tensor_with_shape};
+
+    #[test]
+    fn should_fingerprint_tensors_from_their_computation() {
+        let mut lineage = TensorLineage::default();
+
+        // The same computation over the same input, on different tensors.
+        lineage.register(&exp(
+            tensor(1, TensorStatus::ReadOnly),
+            tensor(2, TensorStatus::NotInit),
+        ));
+        lineage.register(&mul(2, TensorStatus::ReadOnly, 2.0, 3));
+        lineage.register(&exp(
+            tensor(1, TensorStatus::ReadOnly),
+            tensor(4, TensorStatus::NotInit),
+        ));
+        lineage.register(&mul(4, TensorStatus::ReadOnly, 2.0, 5));
+        assert_eq!(
+            lineage.fingerprint(TensorId::new(3)),
+            lineage.fingerprint(TensorId::new(5))
+        );
+        assert_ne!(
+            lineage.fingerprint(TensorId::new(2)),
+            lineage.fingerprint(TensorId::new(3))
+        );
+
+        // A different scalar.
+        lineage.register(&mul(4, TensorStatus::ReadOnly, 3.0, 6));
+        assert_ne!(
+            lineage.fingerprint(TensorId::new(5)),
+            lineage.fingerprint(TensorId::new(6))
+        );
+
+        // A different input.
+        lineage.register(&exp(
+            tensor(7, TensorStatus::ReadOnly),
+            tensor(8, TensorStatus::NotInit),
+        ));
+        lineage.register(&mul(8, TensorStatus::ReadWrite, 2.0, 9));
+        assert_ne!(
+            lineage.fingerprint(TensorId::new(5)),
+            lineage.fingerprint(TensorId::new(9))
+        );
+
+        // Consumed tensors are forgotten.
+        assert!(!lineage.fingerprints.contains_key(&TensorId::new(8)));
+        lineage.register(&OperationIr::Drop(tensor(5, TensorStatus::ReadWrite)));
+        assert!(!lineage.fingerprints.contains_key(&TensorId::new(5)));
+    }
+
+    #[test]
+    fn should_fingerprint_symbolic_dims_as_symbols() {
+        let mut lineage = TensorLineage::default();
+        let tensor = |id, batch, status| tensor_with_shape(id, vec![batch, 4], status);
+        let mut exp_with_batch = |input, out, batch| {
+            lineage.register(&OperationIr::Init(InitOperationIr {
+                out: tensor(input, batch, TensorStatus::NotInit),
+            }));
+            lineage.bind_symbol(&tensor(input, batch, TensorStatus::ReadOnly), 0, "batch");
+            lineage.register(&exp(
+                tensor(input, batch, TensorStatus::ReadWrite),
+                tensor(out, batch, TensorStatus::NotInit),
+            ));
+        };
+
+        exp_with_batch(1, 2, 8);
+        exp_with_batch(3, 4, 16);
+
+        assert_eq!(
+            lineage.fingerprint(TensorId::new(2)),
+            lineage.fingerprint(TensorId::new(4))
+        );
+        assert!(matches!(
+            lineage.dims(&tensor(4, 16, TensorStatus::ReadOnly))[..],
+            [Dim::Dynamic(_), Dim::Static(4)]
+        ));
+    }
+
+    fn mul(lhs: u64, status: TensorStatus, rhs: f32, out: u64) -> OperationIr {
+        OperationIr::NumericFloat(
+            DType::F32,
+            NumericOperationIr::MulScalar(ScalarOpIr {
+                lhs: tensor(lhs, status),
+                rhs,
+                out: tensor(out, TensorStatus::NotInit),
+            }),
+        )
+    }
+}
@@ -1,5 +1,9 @@
+pub(crate) mod ahead_of_time;
+pub(crate) mod capture;
+pub(crate) mod checkpoint;
 pub(crate) mod execution;
 pub(crate) mod queue;
+pub(crate) mod rewrite;
 pub(crate) mod shared_tensors;
 pub(crate) mod store;
 
@@ -25,9 +29,23 @@ macro_rules! memory_checks {
 
 mod base;
 mod context;
+mod event;
+mod lineage;
 mod multi;
+mod pool;
 
+pub use ahead_of_time::AheadOfTimeReport;
 pub use base::*;
+pub use capture::CapturedGraph;
+pub use checkpoint::FusionCheckpoint;
 pub use context::*;
+pub use event::EventId;
+pub(crate) use event::ExecutionSignal;
 pub use execution::*;
 pub use multi::*;
+pub use pool::*;
+pub use rewrite::{OperationPattern, RewriteRule, RewriteStep};
+pub use store::{
+    CustomTrigger, ExecutionPlanId, ExecutionPlanStoreStats, PlanExportMode, PlanTimings,
+    PlanTrigger, TimingSource, TriggerContext,
+};
@@ -1,26 +1,88 @@
-use std::sync::Arc;
+#[cfg(feature = "plan-export")]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+use std::{sync::Arc, time::Duration};
+use web_time::Instant;
 
-use burn_ir::{HandleContainer, OperationIr, TensorId, TensorIr, TensorStatus};
+use burn_ir::{Dim, HandleContainer, OperationIr, TensorId, TensorIr, TensorStatus};
 use hashbrown::{HashMap, HashSet};
 
+#[cfg(feature = "plan-export")]
+use super::{PlanExportMode, store::PlanCacheHeader};
+
 use super::{
-    StreamId,
+    AheadOfTimeReport, OutputPool, OutputPoolStats, PlanTrigger, ScalarParameterization, StreamId,
+    StreamLiveness, StreamPriorities, StreamPriority,
+    ahead_of_time::PlanningSegment,
+    capture::CapturedStep,
+    checkpoint::StreamCheckpoint,
+    current_stream,
+    event::{EventId, ExecutionSignal, StreamEvents, StreamPosition},
     execution::{ExecutionMode, Operation, Processor, StreamSegment},
-    queue::OperationQueue,
+    is_deterministic,
+    lineage::TensorLineage,
+    queue::{OperationQueue, last_use},
     shared_tensors::SharedTensors,
-    store::{ExecutionPlanId, ExecutionPlanStore},
+    store::{
+        ExecutionPlanId, ExecutionPlanStore, ExecutionPlanStoreState, ExecutionPlanStoreStats,
+        TimingSource,
+    },
 };
 use crate::{
-    DropOp, FusionRuntime,
-    stream::shared_tensors::{SharedTensorAnalysis, SharedTensorDropAction},
+    DropOp, FusionConfig, FusionError, FusionFilter, FusionRuntime, Optimization, QueueLimitPolicy,
+    debug::{
+        CrossStreamEdge, CrossStreamReport, DetailedPlans, FusionDebugSummary, FusionExplanation,
+        FusionHook, FusionHooks, FusionSnapshot, OperationHistogram, OperationHistogramBuilder,
+        PlanCacheStats, SnapshotOptions, StreamInfo, StreamStats, TriggerReport, current_metadata,
+    },
+    memory::StreamMemory,
+    search::{CostModel, ExplorationPolicy, ExplorationSettings},
+    stream::{
+        RewriteRule,
+        rewrite::{PatternRewrite, RewritePipeline},
+        shared_tensors::{SharedTensorAnalysis, SharedTensorDropAction},
+    },
 };
 
 /// Keep track of multiple concurrent lazy streams of operations.
 pub struct MultiStream<R: FusionRuntime> {
     streams: HashMap<StreamId, Stream<R>>,
     optimizations: ExecutionPlanStore<R::Optimization>,
+    pool: OutputPool<R::FusionHandle>,
+    hooks: FusionHooks,
     shared_tensors: SharedTensors,
     device: R::FusionDevice,
+    /// Where and when each stream was first used, kept after the streams are drained.
+    stream_info: HashMap<StreamId, StreamInfo>,
+    /// Whether the thread of each stream is still running, until its plans are collected.
+    stream_liveness: HashMap<StreamId, StreamLiveness>,
+    stream_stats: HashMap<StreamId, StreamStats>,
+    /// The id of the first plan not written by an incremental export.
+    #[cfg(feature = "plan-export")]
+    num_exported_plans: ExecutionPlanId,
+    /// The syncs between streams caused by shared tensors, by producer and consumer.
+    cross_stream_edges: HashMap<(StreamId, StreamId), CrossStreamEdge>,
+    /// The order in which streams are drained when multiple streams must be drained at once.
+    priorities: StreamPriorities,
+    /// Which scalars are replaced by parameters in the relative operations of new streams.
+    scalar_parameterization: ScalarParameterization,
+    /// How new optimizations are explored on every stream.
+    exploration: ExplorationSettings,
+    /// The passes rewriting the operations of a stream when it's drained.
+    rewrites: RewritePipeline<R>,
+    lineage: TensorLineage,
+    config: FusionConfig,
+    events: StreamEvents,
+    /// The plans executed on the streams being captured.
+    captures: HashMap<StreamId, Vec<CapturedStep<R>>>,
+    /// The number of streams created, giving each instance of a stream its epoch.
+    num_created_streams: u64,
+    /// The number of operations executed on every stream.
+    num_executed: u64,
+    executed: Arc<ExecutionSignal>,
     #[cfg(feature = "memory-checks")]
     memory_checks: super::memory_checks::MemoryChecks,
 }
@@ -37,8 +99,27 @@ impl<R: FusionRuntime> MultiStream<R> {
         Self {
             streams: HashMap::new(),
             optimizations: ExecutionPlanStore::new(),
+            pool: OutputPool::default(),
+            hooks: FusionHooks::default(),
             shared_tensors: SharedTensors::default(),
             device,
+            stream_info: HashMap::new(),
+            stream_liveness: HashMap::new(),
+            stream_stats: HashMap::new(),
+            #[cfg(feature = "plan-export")]
+            num_exported_plans: 0,
+            cross_stream_edges: HashMap::new(),
+            priorities: StreamPriorities::default(),
+            scalar_parameterization: ScalarParameterization::default(),
+            exploration: ExplorationSettings::default(),
+            rewrites: RewritePipeline::default(),
+            lineage: TensorLineage::default(),
+            config: FusionConfig::default(),
+            events: StreamEvents::default(),
+            captures: HashMap::new(),
+            num_created_streams: 0,
+            num_executed: 0,
+            executed: Arc::new(ExecutionSignal::default()),
             #[cfg(feature = "memory-checks")]
             memory_checks: super::memory_checks::MemoryChecks::default(),
         }
@@ -52,6 +133,18 @@ impl<R: FusionRuntime> MultiStream<R> {
         operation: Arc<dyn Operation<R>>,
         handles: &mut HandleContainer<R::FusionHandle>,
     ) {
+        self.stream_info
+            .entry(streams.current)
+            .or_insert_with(|| StreamInfo::current(streams.current));
+        self.stream_liveness
+            .entry(streams.current)
+            .or_insert_with(StreamLiveness::current);
+        self.stream_stats
+            .entry(streams.current)
+            .or_insert_with(|| StreamStats::new(streams.current))
+            .num_registered += 1;
+        self.lineage.register(&repr);
+
         let id = self.resolve_streams(&streams, handles, &mut repr);
 
         let drop_action = match &mut repr {
@@ -87,6 +180,16 @@ impl<R: FusionRuntime> MultiStream<R> {
             }
         }
 
+        // The client waits once the server is unlocked when blocking, and the operation was
+        // checked before being queued when panicking.
+        if !sync
+            && self.config.on_queue_limit == QueueLimitPolicy::Flush
+            && self.exceeds_queue_limits(id)
+        {
+            log::debug!("Draining stream {id} exceeding the queue limits");
+            self.drain(handles, id);
+        }
+
         let stream = match self.streams.get(&id) {
             Some(val) => val,
             None => {
@@ -129,6 +232,41 @@ impl<R: FusionRuntime> MultiStream<R> {
         }
     }
 
+    /// Whether the operations queued on the stream exceed the limits of the
+    /// [configuration](FusionConfig).
+    pub(crate) fn exceeds_queue_limits(&self, id: StreamId) -> bool {
+        match self.streams.get(&id) {
+            Some(stream) => self.config.is_exceeded_by(&stream.queue.global, None),
+            None => false,
+        }
+    }
+
+    /// Panic before queuing an operation that would make its stream exceed the limits of the
+    /// [configuration](FusionConfig), when its policy is to [panic](QueueLimitPolicy::Panic).
+    pub(crate) fn check_queue_limits(&self, id: StreamId, repr: &OperationIr) {
+        if self.config.on_queue_limit != QueueLimitPolicy::Panic
+            || matches!(repr, OperationIr::Drop(_))
+        {
+            return;
+        }
+
+        let queued = match self.streams.get(&id) {
+            Some(stream) => stream.queue.global.as_slice(),
+            None => &[],
+        };
+        if self.config.is_exceeded_by(queued, Some(repr)) {
+            panic!(
+                "Stream {id} would exceed the queue limits of the fusion configuration: {:?}",
+                self.config
+            );
+        }
+    }
+
+    /// The signal notified every time operations are executed.
+    pub(crate) fn execution_signal(&self) -> Arc<ExecutionSignal> {
+        self.executed.clone()
+    }
+
     /// Enqueue an operation on the queue.
     fn enqueue_operation(
         &mut self,
@@ -141,7 +279,13 @@ impl<R: FusionRuntime> MultiStream<R> {
         let stream = match self.streams.get_mut(&id) {
             Some(stream) => stream,
             None => {
-                let stream = Stream::new(self.device.clone());
+                let mut stream = Stream::new(
+                    self.device.clone(),
+                    self.exploration.clone(),
+                    self.num_created_streams,
+                );
+                self.num_created_streams += 1;
+                stream.queue.converter.scalar_parameterization = self.scalar_parameterization;
                 self.streams.insert(id, stream);
                 self.streams
                     .get_mut(&id)
@@ -149,11 +293,21 @@ impl<R: FusionRuntime> MultiStream<R> {
             }
         };
 
-        stream.queue.add(repr, operation, streams, id);
+        let metadata = current_metadata().map(Arc::new);
+        stream.queue.add(repr, operation, metadata, streams, id);
+        stream.last_registered = Instant::now();
 
         let len_before = stream.queue.global.len();
         stream.processor.process(
-            Segment::new(&mut stream.queue, handles),
+            Segment::new(
+                &mut stream.queue,
+                handles,
+                &mut self.pool,
+                &mut self.hooks,
+                id,
+                self.captures.get_mut(&id),
+                self.config.timed_device(&self.device),
+            ),
             &mut self.optimizations,
             ExecutionMode::Lazy,
         );
@@ -161,6 +315,9 @@ impl<R: FusionRuntime> MultiStream<R> {
         let num_executed = len_before - len_after;
 
         stream.cursor += num_executed as u64;
+        let search_time = stream.processor.take_search_time();
+        self.on_operations_executed(id, num_executed, search_time, None);
+        self.evict_plans();
 
         num_executed
     }
@@ -192,23 +349,761 @@ impl<R: FusionRuntime> MultiStream<R> {
         self.memory_checks.check(&self.streams, handles);
     }
 
+    /// The statistics of the [output pool](OutputPool).
+    pub(crate) fn output_pool_stats(&self) -> OutputPoolStats {
+        self.pool.stats()
+    }
+
+    /// Update the maximum number of buffers kept alive by the [output pool](OutputPool).
+    pub(crate) fn set_output_pool_capacity(&mut self, capacity: usize) {
+        self.pool.set_capacity(capacity);
+    }
+
+    /// The statistics of the [execution plan store](ExecutionPlanStore).
+    pub(crate) fn execution_plan_stats(&self) -> ExecutionPlanStoreStats {
+        self.optimizations.stats()
+    }
+
+    /// Update the maximum number of execution plans kept alive, `None` meaning unbounded.
+    pub(crate) fn set_execution_plan_capacity(
+        &mut self,
+        capacity: Option<usize>,
+    ) -> Result<(), FusionError> {
+        self.optimizations.set_capacity(capacity)?;
+        self.evict_plans();
+        Ok(())
+    }
+
+    /// Pin the execution plan so that it is never evicted.
+    pub(crate) fn pin_plan(&mut self, id: ExecutionPlanId) -> Result<(), FusionError> {
+        self.optimizations.pin(id)
+    }
+
+    /// Add a user trigger to the execution plan.
+    pub(crate) fn add_plan_trigger(
+        &mut self,
+        id: ExecutionPlanId,
+        trigger: PlanTrigger,
+    ) -> Result<(), FusionError> {
+        self.optimizations.add_plan_trigger(id, trigger).map(|_| ())
+    }
+
+    /// Unpin the execution plan, returning whether it was pinned.
+    pub(crate) fn unpin_plan(&mut self, id: ExecutionPlanId) -> bool {
+        self.optimizations.unpin(id)
+    }
+
+    /// The ids of the pinned execution plans, sorted.
+    pub(crate) fn pinned_plans(&self) -> Vec<ExecutionPlanId> {
+        let mut ids = self.optimizations.pinned().collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Pin every execution plan executed from now on, returning the previous state.
+    pub(crate) fn pin_executed_plans(&mut self, enabled: bool) -> bool {
+        self.optimizations.set_pin_executed(enabled)
+    }
+
+    /// Write every execution plan to the file at `path`, returning the number of plans written.
+    #[cfg(feature = "plan-export")]
+    pub(crate) fn export_plans(&self, path: &Path) -> Result<usize, FusionError> {
+        let state = self.optimizations.to_state(|opt| opt.to_state());
+        let file = File::create(path).map_err(FusionError::plan_serialization)?;
+
+        state.write_json(BufWriter::new(file), &Self::plan_cache_header())?;
+
+        Ok(state.plans.len())
+    }
+
+    /// Encode every execution plan in the compact binary format.
+    #[cfg(feature = "plan-export")]
+    pub(crate) fn export_plans_to_bytes(&self) -> Result<Vec<u8>, FusionError> {
+        let state = self.optimizations.to_state(|opt| opt.to_state());
+        let mut bytes = Vec::new();
+
+        state.write_bytes(&mut bytes, &Self::plan_cache_header())?;
+
+        Ok(bytes)
+    }
+
+    /// Write the execution plans to `writer` as newline-delimited JSON, returning the number of
+    /// plans written.
+    #[cfg(feature = "plan-export")]
+    pub(crate) fn export_plans_ndjson(
+        &mut self,
+        writer: &mut dyn Write,
+        mode: PlanExportMode,
+    ) -> Result<usize, FusionError> {
+        let since = match mode {
+            PlanExportMode::Full => 0,
+            PlanExportMode::Incremental => self.num_exported_plans,
+        };
+        // The header is only written once at the start of the file, which incremental exports
+        // keep appending to.
+        let header = match since {
+            0 => Some(Self::plan_cache_header()),
+            _ => None,
+        };
+        let num_plans = self
+            .optimizations
+            .write_ndjson(writer, header.as_ref(), since, |opt| opt.to_state())?;
+        self.num_exported_plans = self.optimizations.num_created();
+
+        Ok(num_plans)
+    }
+
+    /// Load the execution plans of the file at `path`, written either as JSON or in the binary
+    /// format, returning the number of plans added.
+    ///
+    /// Plans already in the store are skipped, and so are all plans when the file was exported by
+    /// another backend, burn version or IR schema.
+    #[cfg(feature = "plan-export")]
+    pub(crate) fn import_plans(&mut self, path: &Path) -> Result<usize, FusionError> {
+        let file = File::open(path).map_err(FusionError::plan_serialization)?;
+        let state = ExecutionPlanStoreState::<R::OptimizationState>::read(
+            BufReader::new(file),
+            &Self::plan_cache_header(),
+        )?;
+
+        Ok(self.load_plans(state))
+    }
+
+    /// Load the execution plans encoded by [export_plans_to_bytes](Self::export_plans_to_bytes),
+    /// returning the number of plans added.
+    #[cfg(feature = "plan-export")]
+    pub(crate) fn import_plans_from_bytes(&mut self, bytes: &[u8]) -> Result<usize, FusionError> {
+        let state = ExecutionPlanStoreState::<R::OptimizationState>::read_bytes(
+            bytes,
+            &Self::plan_cache_header(),
+        )?;
+
+        Ok(self.load_plans(state))
+    }
+
+    /// The state of every execution plan, which a server of the same runtime can load with
+    /// [import_plans_state](Self::import_plans_state).
+    pub(crate) fn plans_state(&self) -> ExecutionPlanStoreState<R::OptimizationState> {
+        self.optimizations.to_state(|opt| opt.to_state())
+    }
+
+    /// Load the execution plans of the state, returning the number of plans added.
+    pub(crate) fn import_plans_state(
+        &mut self,
+        state: ExecutionPlanStoreState<R::OptimizationState>,
+    ) -> usize {
+        self.load_plans(Some(state))
+    }
+
+    /// Plan the operations as if they were registered on a new stream and synced, without
+    /// executing them, compiling the optimizations of the plans used.
+    pub(crate) fn compile_ahead_of_time(
+        &mut self,
+        operations: &[OperationIr],
+    ) -> AheadOfTimeReport {
+        let started_at = Instant::now();
+        let num_created_before = self.optimizations.num_created();
+        let mut processor = Processor::foreground(
+            R::optimizations(self.device.clone()),
+            self.exploration.clone(),
+        );
+        let mut segment = PlanningSegment::<R>::new(self.scalar_parameterization);
+
+        for operation in operations {
+            segment.push(operation.clone());
+            processor.process(&mut segment, &mut self.optimizations, ExecutionMode::Lazy);
+        }
+        processor.process(&mut segment, &mut self.optimizations, ExecutionMode::Sync);
+        self.evict_plans();
+
+        AheadOfTimeReport {
+            num_operations: operations.len(),
+            num_plans: segment.planned.len(),
+            num_created: self.optimizations.num_created() - num_created_before,
+            duration: started_at.elapsed(),
+        }
+    }
+
+    fn load_plans(
+        &mut self,
+        state: Option<ExecutionPlanStoreState<R::OptimizationState>>,
+    ) -> usize {
+        let state = match state {
+            Some(state) => state,
+            None => return 0,
+        };
+
+        let device = &self.device;
+        let added = self
+            .optimizations
+            .load_state(state, |state| R::Optimization::from_state(device, state));
+        self.evict_plans();
+
+        added.len()
+    }
+
+    #[cfg(feature = "plan-export")]
+    fn plan_cache_header() -> PlanCacheHeader {
+        PlanCacheHeader::new(core::any::type_name::<R>())
+    }
+
+    /// Evict the least recently used execution plans when the store is over capacity.
+    ///
+    /// Plans still considered by a stream are kept, since they might be executed next.
+    fn evict_plans(&mut self) {
+        if !self.optimizations.is_over_capacity() {
+            return;
+        }
+
+        let referenced = self.referenced_plans();
+
+        for id in self.optimizations.evict(&referenced) {
+            self.pool.forget_plan(id);
+        }
+    }
+
+    /// Evict the least recently used plans until at most `num_plans` are kept, returning the ids
+    /// of the evicted plans.
+    pub(crate) fn evict_plans_down_to(&mut self, num_plans: usize) -> Vec<ExecutionPlanId> {
+        let referenced = self.referenced_plans();
+        let evicted = self.optimizations.evict_down_to(num_plans, &referenced);
+
+        for id in evicted.iter() {
+            self.pool.forget_plan(*id);
+        }
+
+        evicted
+    }
+
+    /// The plans that a stream might be about to execute.
+    fn referenced_plans(&self) -> HashSet<ExecutionPlanId> {
+        self.streams
+            .values()
+            .flat_map(|stream| stream.processor.referenced_plans(&self.optimizations))
+            .collect()
+    }
+
+    /// Remove the execution plans only executed by streams whose thread is finished, returning
+    /// the ids of the removed plans.
+    ///
+    /// Streams with queued operations are never considered finished, since their operations are
+    /// still to be executed.
+    pub(crate) fn collect_finished_stream_plans(&mut self) -> Vec<ExecutionPlanId> {
+        let finished = self
+            .stream_liveness
+            .iter()
+            .filter(|(id, liveness)| !liveness.is_alive() && !self.streams.contains_key(*id))
+            .map(|(id, _)| *id)
+            .collect::<HashSet<_>>();
+
+        if finished.is_empty() {
+            return Vec::new();
+        }
+
+        self.stream_liveness.retain(|id, _| !finished.contains(id));
+        self.stream_stats.retain(|id, _| !finished.contains(id));
+        for id in finished.iter() {
+            self.priorities.forget(*id);
+        }
+
+        let referenced = self.referenced_plans();
+        let collected = self
+            .optimizations
+            .collect_stream_plans(&finished, &referenced);
+
+        for id in collected.iter() {
+            self.pool.forget_plan(*id);
+        }
+
+        collected
+    }
+
+    /// Register a new [debug hook](FusionHook).
+    pub(crate) fn register_hook(&mut self, hook: Box<dyn FusionHook>) {
+        self.hooks.register(hook, self.optimizations.num_created());
+    }
+
+    /// Notify the [debug hooks](FusionHook) that an operation is registered.
+    pub(crate) fn on_operation_registered(&mut self, stream: StreamId, operation: &OperationIr) {
+        self.hooks.on_operation_registered(stream, operation);
+    }
+
+    /// Group the queued operations and the operations of every plan in a histogram.
+    pub(crate) fn operation_histogram(&self) -> OperationHistogram {
+        let mut builder = OperationHistogramBuilder::default();
+
+        for stream in self.streams.values() {
+            builder.queued(&stream.queue.global);
+        }
+        for (_id, plan) in self.optimizations.iter() {
+            builder.plan(&plan.operations, &plan.optimization.strategy);
+        }
+
+        builder.build()
+    }
+
+    /// Explain the triggers of every plan, along with the number of times they fired.
+    pub(crate) fn trigger_report(&self) -> TriggerReport {
+        TriggerReport::new(&self.optimizations)
+    }
+
+    /// Explain how the given operations are partitioned into plans by the explorer.
+    pub(crate) fn explain_fusion(&self, operations: &[OperationIr]) -> FusionExplanation {
+        FusionExplanation::new(
+            operations,
+            R::optimizations(self.device.clone()),
+            self.exploration.clone(),
+            self.scalar_parameterization,
+        )
+    }
+
+    /// Collect the cache counters of the plan store, along with the executions of every plan.
+    pub(crate) fn cache_stats(&self) -> PlanCacheStats {
+        PlanCacheStats::new(&self.optimizations)
+    }
+
+    pub(crate) fn detailed_plans(&self) -> DetailedPlans {
+        DetailedPlans::new(&self.optimizations, self.config.plan_timing)
+    }
+
+    /// Copy a bounded [snapshot](FusionSnapshot) of the queues and plans.
+    pub(crate) fn debug_snapshot(&self, options: SnapshotOptions) -> FusionSnapshot {
+        FusionSnapshot::new(
+            self.streams.iter().map(|(id, stream)| {
+                (
+                    *id,
+                    self.stream_info.get(id),
+                    stream.queue.global.as_slice(),
+                )
+            }),
+            &self.optimizations,
+            options,
+        )
+    }
+
+    /// The syncs between streams caused by tensors shared between them.
+    pub(crate) fn cross_stream_report(&self) -> CrossStreamReport {
+        let mut edges = self
+            .cross_stream_edges
+            .values()
+            .copied()
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|edge| (edge.producer, edge.consumer));
+
+        CrossStreamReport { edges }
+    }
+
+    /// The [info](StreamInfo) of every stream used so far, sorted by creation time.
+    pub(crate) fn tensor_fingerprint(&self, id: TensorId) -> u64 {
+        self.lineage.fingerprint(id)
+    }
+
+    /// Bind a dimension of a tensor to the symbol with the given name.
+    pub(crate) fn bind_symbolic_dim(&mut self, tensor: &TensorIr, axis: usize, name: &str) {
+        self.lineage.bind_symbol(tensor, axis, name);
+    }
+
+    /// The dimensions of a tensor, symbolic where bound to a symbol.
+    pub(crate) fn symbolic_dims(&self, tensor: &TensorIr) -> Vec<Dim> {
+        self.lineage.dims(tensor)
+    }
+
+    pub(crate) fn stream_info(&self) -> Vec<StreamInfo> {
+        let mut info = self.stream_info.values().cloned().collect::<Vec<_>>();
+        info.sort_by_key(|info| (info.created_at, info.stream));
+        info
+    }
+
+    /// The number of operations queued across all streams.
+    pub(crate) fn num_queued_operations(&self) -> usize {
+        self.streams
+            .values()
+            .map(|stream| stream.queue.global.len())
+            .sum()
+    }
+
+    /// The number of operations executed on every stream since the start of the device.
+    pub(crate) fn num_executed(&self) -> u64 {
+        self.num_executed
+    }
+
+    /// Collect a [summary](FusionDebugSummary) of the streams.
+    pub(crate) fn debug_summary(&self) -> FusionDebugSummary {
+        let cache = self.optimizations.cache();
+        let mut streams = self.stream_stats.values().copied().collect::<Vec<_>>();
+        streams.sort_by_key(|stats| stats.stream);
+
+        FusionDebugSummary {
+            num_streams: self.streams.len(),
+            num_queued_operations: self.num_queued_operations(),
+            num_cache_hits: cache.num_hits,
+            num_cache_misses: cache.num_misses,
+            plans: self.optimizations.stats(),
+            output_pool: self.pool.stats(),
+            streams,
+        }
+    }
+
+    /// Copy the operations queued on every stream, sorted by stream.
+    pub(crate) fn checkpoint(&self) -> Vec<StreamCheckpoint<R>> {
+        let mut streams = self
+            .streams
+            .iter()
+            .map(|(id, stream)| StreamCheckpoint {
+                stream: *id,
+                global: stream.queue.global.clone(),
+                operations: stream.queue.operations.clone(),
+                variables: stream
+                    .queue
+                    .variables
+                    .iter()
+                    .map(|(tensor, (stream, _))| (*tensor, *stream))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        streams.sort_by_key(|stream| stream.stream);
+
+        streams
+    }
+
+    /// Discard the operations queued on every stream without executing them, then register the
+    /// operations of the checkpoint again.
+    ///
+    /// The handles must already be restored, since registering an operation can execute it.
+    pub(crate) fn restore(
+        &mut self,
+        streams: &[StreamCheckpoint<R>],
+        handles: &mut HandleContainer<R::FusionHandle>,
+    ) {
+        self.streams.clear();
+        self.shared_tensors = SharedTensors::default();
+
+        for checkpoint in streams {
+            for (repr, operation) in checkpoint.global.iter().zip(checkpoint.operations.iter()) {
+                let mut operation_streams = OperationStreams {
+                    streams: HashMap::new(),
+                    current: checkpoint.stream,
+                };
+
+                for tensor in repr.nodes() {
+                    if tensor.status != TensorStatus::NotInit {
+                        let stream = checkpoint.variables.get(&tensor.id);
+                        operation_streams
+                            .streams
+                            .insert(tensor.id, *stream.unwrap_or(&checkpoint.stream));
+                    }
+                }
+
+                self.register(operation_streams, repr.clone(), operation.clone(), handles);
+            }
+        }
+    }
+
+    /// The tensors read or written by the operations queued on every stream.
+    pub(crate) fn queued_tensors(&self) -> HashSet<TensorId> {
+        self.streams
+            .values()
+            .flat_map(|stream| stream.queue.global.iter())
+            .flat_map(|operation| operation.nodes())
+            .map(|tensor| tensor.id)
+            .collect()
+    }
+
+    /// The memory that the operations queued on each stream will allocate, sorted by stream.
+    pub(crate) fn queued_memory(&self) -> Vec<StreamMemory> {
+        let mut memory = self
+            .streams
+            .iter()
+            .map(|(id, stream)| {
+                let mut written = HashSet::new();
+                let tensors = stream
+                    .queue
+                    .global
+                    .iter()
+                    .flat_map(|operation| operation.nodes())
+                    .filter(|tensor| tensor.status == TensorStatus::NotInit)
+                    .filter(|tensor| written.insert(tensor.id))
+                    .collect::<Vec<_>>();
+
+                StreamMemory {
+                    stream: *id,
+                    num_queued_operations: stream.queue.global.len(),
+                    num_queued_tensors: tensors.len(),
+                    bytes_queued: tensors
+                        .iter()
+                        .map(|tensor| {
+                            (tensor.shape.iter().product::<usize>() * tensor.dtype.size()) as u64
+                        })
+                        .sum(),
+                }
+            })
+            .collect::<Vec<_>>();
+        memory.sort_by_key(|memory| memory.stream);
+
+        memory
+    }
+
+    /// Drop all buffers kept by the [output pool](OutputPool).
+    pub(crate) fn clear_output_pool(&mut self) {
+        self.pool.clear();
+    }
+
+    /// Drain all streams, the ones with the highest [priority](StreamPriority) first.
+    pub(crate) fn drain_all(&mut self, handles: &mut HandleContainer<R::FusionHandle>) {
+        let mut ids = self.streams.keys().copied().collect::<Vec<_>>();
+        self.priorities.sort(&mut ids);
+
+        for id in ids {
+            self.drain(handles, id);
+        }
+    }
+
+    /// Update which scalars are replaced by parameters when operations are registered.
+    ///
+    /// Operations already queued keep their relative form.
+    pub(crate) fn set_scalar_parameterization(&mut self, parameterization: ScalarParameterization) {
+        self.scalar_parameterization = parameterization;
+
+        for stream in self.streams.values_mut() {
+            stream.queue.converter.scalar_parameterization = parameterization;
+        }
+    }
+
+    /// Run the [rule](RewriteRule) on the operations of every stream when it's drained.
+    pub(crate) fn register_rewrite(&mut self, rule: Box<dyn RewriteRule<R>>) {
+        self.rewrites.register(Box::new(PatternRewrite { rule }));
+    }
+
+    /// Update the [policy](ExplorationPolicy) used to explore new optimizations on every stream.
+    pub(crate) fn set_exploration_policy(&mut self, policy: Arc<dyn ExplorationPolicy>) {
+        self.exploration.policy = policy;
+        self.update_exploration();
+    }
+
+    /// Update which kinds of operations can be fused on every stream.
+    pub(crate) fn set_fusion_filter(&mut self, filter: FusionFilter) {
+        self.exploration.filter = Arc::new(filter);
+        self.update_exploration();
+    }
+
+    /// Update the [cost model](CostModel) deciding whether the optimizations explored on every
+    /// stream are used.
+    pub(crate) fn set_cost_model(&mut self, cost_model: Arc<dyn CostModel>) {
+        self.exploration.cost_model = cost_model;
+        self.update_exploration();
+    }
+
+    fn update_exploration(&mut self) {
+        for stream in self.streams.values_mut() {
+            stream.processor.set_exploration(self.exploration.clone());
+        }
+    }
+
+    pub(crate) fn config(&self) -> FusionConfig {
+        self.config
+    }
+
+    pub(crate) fn set_config(&mut self, config: FusionConfig) {
+        self.config = config;
+
+        if self.exploration.limits != config.fusion {
+            self.exploration.limits = config.fusion;
+            self.update_exploration();
+        }
+    }
+
+    /// Record the plans executed on the stream until the capture [ends](Self::end_capture).
+    pub(crate) fn begin_capture(&mut self, id: StreamId) {
+        self.captures.insert(id, Vec::new());
+    }
+
+    /// The plans executed on the stream since the capture began, `None` if it never did.
+    pub(crate) fn end_capture(&mut self, id: StreamId) -> Option<Vec<CapturedStep<R>>> {
+        self.captures.remove(&id)
+    }
+
+    /// Execute the captured plans again, bypassing the streams.
+    pub(crate) fn replay(
+        &mut self,
+        steps: &[CapturedStep<R>],
+        handles: &mut HandleContainer<R::FusionHandle>,
+    ) -> Result<(), FusionError> {
+        for step in steps {
+            step.replay(handles, &mut self.optimizations, &mut self.pool)?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn set_stream_priority(&mut self, id: StreamId, priority: StreamPriority) {
+        self.priorities.set(id, priority);
+    }
+
+    /// Drain the streams with queued operations that didn't get a new operation for the
+    /// [idle timeout](FusionConfig::idle_timeout).
+    ///
+    /// Returns how long until the next stream can become idle, or `None` when there is no
+    /// idle timeout or no stream left with queued operations.
+    pub(crate) fn drain_idle_streams(
+        &mut self,
+        handles: &mut HandleContainer<R::FusionHandle>,
+        now: Instant,
+    ) -> Option<Duration> {
+        if is_deterministic() {
+            return None;
+        }
+
+        let timeout = self.config.idle_timeout?;
+        let mut next = None;
+        let mut idle = Vec::new();
+
+        for (id, stream) in self.streams.iter() {
+            if stream.queue.global.is_empty() {
+                continue;
+            }
+
+            let elapsed = now.saturating_duration_since(stream.last_registered);
+            match timeout.checked_sub(elapsed) {
+                Some(remaining) if !remaining.is_zero() => {
+                    next = Some(next.map_or(remaining, |next: Duration| next.min(remaining)))
+                }
+                _ => idle.push(*id),
+            }
+        }
+
+        self.priorities.sort(&mut idle);
+        for id in idle {
+            log::debug!("Draining stream {id} after {timeout:?} without new operations");
+            self.drain(handles, id);
+        }
+
+        next
+    }
+
     /// Drain a stream
     pub fn drain(&mut self, handles: &mut HandleContainer<R::FusionHandle>, id: StreamId) {
         if let Some(stream) = self.streams.get_mut(&id) {
+            let num_queued = stream.queue.global.len();
+            let stats = self
+                .stream_stats
+                .entry(id)
+                .or_insert_with(|| StreamStats::new(id));
+            self.rewrites.run(&mut stream.queue, stats);
             let num_executed = stream.queue.global.len();
+
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!(
+                "fusion.drain",
+                stream = %id,
+                num_operations = num_executed
+            )
+            .entered();
+
             stream.processor.process(
-                Segment::new(&mut stream.queue, handles),
+                Segment::new(
+                    &mut stream.queue,
+                    handles,
+                    &mut self.pool,
+                    &mut self.hooks,
+                    id,
+                    self.captures.get_mut(&id),
+                    self.config.timed_device(&self.device),
+                ),
                 &mut self.optimizations,
                 ExecutionMode::Sync,
             );
-            stream.cursor += num_executed as u64;
+            // Events are positioned among the registered operations, rewritten ones included.
+            stream.cursor += num_queued as u64;
+
+            let search_time = stream.processor.take_search_time();
+            // Syncs usually drain streams with nothing queued, which aren't counted as drains.
+            let num_drained = (num_executed > 0).then_some(num_executed);
+            self.on_operations_executed(id, num_executed, search_time, num_drained);
+            self.on_stream_executed(handles, id);
+        }
+    }
+
+    /// Update the [statistics](StreamStats) of the stream, `num_queued` being the number of
+    /// queued operations when the stream was drained.
+    fn on_operations_executed(
+        &mut self,
+        id: StreamId,
+        num_executed: usize,
+        search_time: Duration,
+        num_queued: Option<usize>,
+    ) {
+        let stats = self
+            .stream_stats
+            .entry(id)
+            .or_insert_with(|| StreamStats::new(id));
+        stats.num_executed += num_executed as u64;
+        stats.search_time += search_time;
+        self.num_executed += num_executed as u64;
+        self.executed.notify();
 
+        if let Some(num_queued) = num_queued {
+            stats.num_drains += 1;
+            stats.num_drained_queued += num_queued as u64;
+        }
+    }
+
+    /// Release the shared tensors no longer needed after operations of the stream were executed.
+    fn on_stream_executed(&mut self, handles: &mut HandleContainer<R::FusionHandle>, id: StreamId) {
+        if let Some(stream) = self.streams.get_mut(&id) {
             let cleared = self.shared_tensors.on_executed_ops(id, stream);
             self.clear_shared_tensors(&cleared, id);
             let to_drop = self.shared_tensors.clear_tensors(cleared);
 
             self.drop_shared_tensors(to_drop, handles, id);
         }
+
+        let streams = &self.streams;
+        self.events
+            .retain_pending(|position| match streams.get(&position.stream) {
+                Some(stream) => position.num_pending(stream.epoch, stream.cursor) > 0,
+                None => false,
+            });
+        self.evict_plans();
+    }
+
+    /// Record an event after the operations currently queued on the stream.
+    pub(crate) fn record_event(&mut self, id: StreamId) -> EventId {
+        let position = self
+            .streams
+            .get(&id)
+            .filter(|stream| !stream.queue.global.is_empty())
+            .map(|stream| StreamPosition {
+                stream: id,
+                epoch: stream.epoch,
+                position: stream.cursor + stream.queue.global.len() as u64,
+            });
+
+        self.events.record(position)
+    }
+
+    /// Execute the operations of the stream that recorded the event up to the event, so that
+    /// the operations registered next on the waiting stream are ordered after them.
+    pub(crate) fn wait_event(
+        &mut self,
+        handles: &mut HandleContainer<R::FusionHandle>,
+        id: StreamId,
+        event: EventId,
+    ) {
+        let position = match self.events.take(event) {
+            Some(position) => position,
+            None => return,
+        };
+        let stream = match self.streams.get(&position.stream) {
+            Some(stream) => stream,
+            None => return,
+        };
+        let num_queued = stream.queue.global.len();
+        let num_operations =
+            (position.num_pending(stream.epoch, stream.cursor) as usize).min(num_queued);
+
+        if num_operations > 0 {
+            self.execute_for(handles, position.stream, id, num_operations);
+        }
     }
 
     /// When one of the provided streams is different from the current stream, we drain them.
@@ -231,24 +1126,119 @@ impl<R: FusionRuntime> MultiStream<R> {
         current
     }
 
-    /// Drain the stream only if one of the tensor in the given nodes is also included in the
-    /// stream queue.
+    /// Execute the operations of the stream up to the last one using a tensor in the given
+    /// nodes, so that the current stream can use them.
     fn resolve_stream(
         &mut self,
         handles: &mut HandleContainer<R::FusionHandle>,
         id: StreamId,
+        current: StreamId,
         nodes: &[&TensorIr],
     ) {
-        if let Some(stream) = self.streams.get(&id) {
-            for node in nodes {
-                if stream.queue.variables.contains_key(&node.id) {
-                    self.drain(handles, id);
-                    return;
-                }
-            }
+        let stream = match self.streams.get(&id) {
+            Some(stream) => stream,
+            None => return,
+        };
+        let num_operations = match last_use(&stream.queue.global, nodes) {
+            Some(position) => position + 1,
+            None => return,
+        };
+
+        self.execute_for(handles, id, current, num_operations);
+    }
+
+    /// Execute the first operations of the stream on behalf of the consumer stream.
+    fn execute_for(
+        &mut self,
+        handles: &mut HandleContainer<R::FusionHandle>,
+        id: StreamId,
+        consumer: StreamId,
+        num_operations: usize,
+    ) {
+        let num_queued = match self.streams.get(&id) {
+            Some(stream) => stream.queue.global.len(),
+            None => return,
+        };
+
+        let edge = self
+            .cross_stream_edges
+            .entry((id, consumer))
+            .or_insert_with(|| CrossStreamEdge::new(id, consumer));
+        edge.num_syncs += 1;
+        edge.num_executed += num_operations as u64;
+        edge.num_deferred += (num_queued - num_operations) as u64;
+
+        match num_operations == num_queued {
+            true => self.drain(handles, id),
+            false => self.drain_prefix(handles, id, num_operations),
         }
     }
 
+    /// Execute the first operations of a stream, the following operations being queued again as
+    /// if they were just registered.
+    fn drain_prefix(
+        &mut self,
+        handles: &mut HandleContainer<R::FusionHandle>,
+        id: StreamId,
+        num_operations: usize,
+    ) {
+        let stream = match self.streams.get_mut(&id) {
+            Some(stream) => stream,
+            None => return,
+        };
+        let num_queued = stream.queue.global.len();
+        let remaining = stream.queue.split_off(num_operations);
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "fusion.drain_prefix",
+            stream = %id,
+            num_operations,
+            num_queued
+        )
+        .entered();
+
+        stream
+            .processor
+            .reset(&mut self.optimizations, &stream.queue.relative);
+        stream.processor.process(
+            Segment::new(
+                &mut stream.queue,
+                handles,
+                &mut self.pool,
+                &mut self.hooks,
+                id,
+                self.captures.get_mut(&id),
+                self.config.timed_device(&self.device),
+            ),
+            &mut self.optimizations,
+            ExecutionMode::Sync,
+        );
+
+        for (global, operation, metadata) in remaining {
+            stream.queue.requeue(global, operation, metadata);
+            stream.processor.process(
+                Segment::new(
+                    &mut stream.queue,
+                    handles,
+                    &mut self.pool,
+                    &mut self.hooks,
+                    id,
+                    self.captures.get_mut(&id),
+                    self.config.timed_device(&self.device),
+                ),
+                &mut self.optimizations,
+                ExecutionMode::Lazy,
+            );
+        }
+        let num_executed = num_queued - stream.queue.global.len();
+        stream.cursor += num_executed as u64;
+
+        let search_time = stream.processor.take_search_time();
+        self.on_operations_executed(id, num_executed, search_time, Some(num_queued));
+        self.on_stream_executed(handles, id);
+    }
+
     fn analyse_shared_tensors(
         &mut self,
         nodes: &[&TensorIr],
@@ -310,8 +1300,11 @@ impl<R: FusionRuntime> MultiStream<R> {
             }
         }
 
-        for id in streams_to_sync.drain() {
-            self.resolve_stream(handles, id, nodes);
+        let mut streams_to_sync = streams_to_sync.into_iter().collect::<Vec<_>>();
+        self.priorities.sort(&mut streams_to_sync);
+
+        for id in streams_to_sync {
+            self.resolve_stream(handles, id, current, nodes);
         }
     }
 
@@ -383,12 +1376,22 @@ pub(crate) struct Stream<R: FusionRuntime> {
     pub(crate) queue: OperationQueue<R>,
     processor: Processor<R::Optimization>,
     pub(crate) cursor: u64,
+    /// The instance of the stream, the same id being reused once the stream is removed.
+    epoch: u64,
+    /// When the last operation was registered on the stream.
+    last_registered: Instant,
 }
 
 #[derive(new)]
 struct Segment<'a, R: FusionRuntime> {
     queue: &'a mut OperationQueue<R>,
     handles: &'a mut HandleContainer<R::FusionHandle>,
+    pool: &'a mut OutputPool<R::FusionHandle>,
+    hooks: &'a mut FusionHooks,
+    stream: StreamId,
+    capture: Option<&'a mut Vec<CapturedStep<R>>>,
+    /// The device measuring the execution of the plans, which are timed on the host when `None`.
+    timed_device: Option<&'a R::FusionDevice>,
 }
 
 impl<R: FusionRuntime> StreamSegment<R::Optimization> for Segment<'_, R> {
@@ -396,17 +1399,76 @@ impl<R: FusionRuntime> StreamSegment<R::Optimization> for Segment<'_, R> {
         &self.queue.relative
     }
 
-    fn execute(&mut self, id: ExecutionPlanId, store: &mut ExecutionPlanStore<R::Optimization>) {
-        self.queue.execute(id, self.handles, store)
+    fn global_operations(&self) -> &[OperationIr] {
+        &self.queue.global
+    }
+
+    fn scalar_parameterization(&self) -> ScalarParameterization {
+        self.queue.converter.scalar_parameterization
+    }
+
+    fn execute(
+        &mut self,
+        id: ExecutionPlanId,
+        store: &mut ExecutionPlanStore<R::Optimization>,
+    ) -> Result<(), FusionError> {
+        let plan = store
+            .get(id)
+            .ok_or(FusionError::PlanNotFound { plan: id })?;
+        let num_operations = plan.operations.len();
+        let global = &self.queue.global[..num_operations];
+        let metadata = &self.queue.metadata[..num_operations];
+        let start = self
+            .hooks
+            .before_plan(id, &plan.operations, global, metadata);
+        store.record_metadata(id, metadata);
+        let captured = self.capture.is_some().then(|| {
+            (
+                self.queue.converter.clone(),
+                self.queue.operations.clone(),
+                self.queue.global.clone(),
+            )
+        });
+        let started_at = Instant::now();
+        let (result, measured) = match self.timed_device {
+            Some(device) => R::profile(device, || {
+                self.queue.execute(id, self.handles, store, self.pool)
+            }),
+            None => (self.queue.execute(id, self.handles, store, self.pool), None),
+        };
+        result?;
+        let (duration, source) =
+            measured.unwrap_or_else(|| (started_at.elapsed(), TimingSource::Host));
+        store.record_timing(id, duration, source);
+
+        if let (Some(steps), Some((converter, mut operations, mut global))) =
+            (self.capture.as_mut(), captured)
+        {
+            let num_executed = global.len() - self.queue.global.len();
+            operations.truncate(num_executed);
+            global.truncate(num_executed);
+            steps.push(CapturedStep {
+                plan: id,
+                converter,
+                operations,
+                global,
+            });
+        }
+        store.record_stream(id, self.stream);
+        self.hooks.after_plan(id, start);
+
+        Ok(())
     }
 }
 
 impl<R: FusionRuntime> Stream<R> {
-    fn new(device: R::FusionDevice) -> Self {
+    fn new(device: R::FusionDevice, exploration: ExplorationSettings, epoch: u64) -> Self {
         Self {
-            processor: Processor::new(R::optimizations(device)),
+            processor: Processor::new(R::optimizations(device), exploration),
             queue: OperationQueue::new(),
             cursor: 0,
+            epoch,
+            last_registered: Instant::now(),
         }
     }
 }
@@ -422,7 +1484,7 @@ impl Default for OperationStreams {
     fn default() -> Self {
         Self {
             streams: HashMap::new(),
-            current: StreamId::current(),
+            current: current_stream(),
         }
     }
 }
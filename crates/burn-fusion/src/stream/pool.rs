@@ -0,0 +1,344 @@
+use std::collections::VecDeque;
+
+use burn_ir::TensorIr;
+use burn_tensor::DType;
+use hashbrown::HashMap;
+
+use super::store::ExecutionPlanId;
+
+/// The default maximum number of buffers kept alive by an [output pool](OutputPool).
+pub const DEFAULT_OUTPUT_POOL_CAPACITY: usize = 64;
+
+/// Keeps the buffers released by an [execution plan](crate::stream::store::ExecutionPlan) so that
+/// the next execution of the same plan can write its outputs into them instead of allocating new
+/// ones.
+///
+/// # Notes
+///
+/// Plans with stable output shapes tend to free and allocate buffers of the same size every time
+/// they are executed. Buffers are recycled only between executions of the same plan and only
+/// when the [runtime](crate::FusionRuntime::can_recycle) confirms that no other handle shares
+/// the buffer.
+pub struct OutputPool<H> {
+    /// The pooled buffers of each key, from the oldest to the most recently released.
+    buffers: HashMap<PoolKey, VecDeque<H>>,
+    /// Insertion order of the pooled buffers, used to evict the oldest ones first.
+    ///
+    /// The occurrences of a key follow the same order as its buffers, so the most recently
+    /// released buffer of a key always matches the last occurrence of the key.
+    order: VecDeque<PoolKey>,
+    current: Option<ExecutionPlanId>,
+    capacity: usize,
+    stats: OutputPoolStats,
+}
+
+#[derive(Hash, PartialEq, Eq, Clone, Debug)]
+struct PoolKey {
+    plan: ExecutionPlanId,
+    shape: Vec<usize>,
+    dtype: DType,
+}
+
+/// Statistics collected by an [output pool](OutputPool).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputPoolStats {
+    /// The number of output allocations served by a pooled buffer.
+    pub hits: u64,
+    /// The number of output allocations that had to allocate a new buffer.
+    pub misses: u64,
+    /// The number of buffers released into the pool.
+    pub recycled: u64,
+    /// The number of buffers dropped because the pool was full.
+    pub evicted: u64,
+    /// The number of buffers currently kept in the pool.
+    pub num_buffers: usize,
+}
+
+impl OutputPoolStats {
+    /// The ratio of output allocations served by the pool, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+
+        match total {
+            0 => 0.0,
+            _ => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl<H> Default for OutputPool<H> {
+    fn default() -> Self {
+        Self::new(DEFAULT_OUTPUT_POOL_CAPACITY)
+    }
+}
+
+impl<H> OutputPool<H> {
+    /// Create a new pool keeping at most `capacity` buffers alive.
+    ///
+    /// A capacity of zero disables the pool.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffers: HashMap::new(),
+            order: VecDeque::new(),
+            current: None,
+            capacity,
+            stats: OutputPoolStats::default(),
+        }
+    }
+
+    /// Take a buffer previously released by the plan being executed that can hold the given
+    /// output tensor.
+    ///
+    /// Returns `None` when no plan is being executed or when no buffer is available, in which
+    /// case the caller should allocate a new one.
+    pub fn take(&mut self, output: &TensorIr) -> Option<H> {
+        self.take_if(output, |_| true)
+    }
+
+    /// Take the most recently released buffer that can hold the given output tensor, only if it
+    /// satisfies the predicate.
+    ///
+    /// A buffer rejected by the predicate stays in the pool and the allocation is counted as a
+    /// miss.
+    pub fn take_if(&mut self, output: &TensorIr, predicate: impl FnOnce(&H) -> bool) -> Option<H> {
+        let plan = self.current?;
+        let key = PoolKey {
+            plan,
+            shape: output.shape.clone(),
+            dtype: output.dtype,
+        };
+
+        let buffers = match self.buffers.get_mut(&key) {
+            Some(buffers) if buffers.back().is_some_and(predicate) => buffers,
+            _ => {
+                self.stats.misses += 1;
+                return None;
+            }
+        };
+        let buffer = buffers.pop_back();
+        if buffers.is_empty() {
+            self.buffers.remove(&key);
+        }
+        if let Some(pos) = self.order.iter().rposition(|k| k == &key) {
+            self.order.remove(pos);
+        }
+        self.stats.hits += 1;
+        self.stats.num_buffers -= 1;
+
+        buffer
+    }
+
+    /// The statistics of the pool.
+    pub fn stats(&self) -> OutputPoolStats {
+        self.stats
+    }
+
+    /// The maximum number of buffers kept alive by the pool.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Update the maximum number of buffers kept alive, evicting the oldest buffers if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    /// Drop all pooled buffers.
+    pub fn clear(&mut self) {
+        self.stats.evicted += self.stats.num_buffers as u64;
+        self.stats.num_buffers = 0;
+        self.buffers.clear();
+        self.order.clear();
+    }
+
+    /// Drop the buffers released by a plan that will never be executed again.
+    pub(crate) fn forget_plan(&mut self, plan: ExecutionPlanId) {
+        let num_buffers = self
+            .buffers
+            .extract_if(|key, _| key.plan == plan)
+            .map(|(_, buffers)| buffers.len())
+            .sum::<usize>();
+
+        self.order.retain(|key| key.plan != plan);
+        self.stats.num_buffers -= num_buffers;
+        self.stats.evicted += num_buffers as u64;
+    }
+
+    /// Mark the start of the execution of the given plan.
+    pub(crate) fn begin(&mut self, plan: ExecutionPlanId) {
+        self.current = Some(plan);
+    }
+
+    /// Mark the end of the execution of the current plan.
+    pub(crate) fn end(&mut self) {
+        self.current = None;
+    }
+
+    /// Release the buffer of a tensor freed by the given plan so that it can be reused.
+    pub(crate) fn release(&mut self, plan: ExecutionPlanId, tensor: &TensorIr, buffer: H) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let key = PoolKey {
+            plan,
+            shape: tensor.shape.clone(),
+            dtype: tensor.dtype,
+        };
+
+        self.buffers
+            .entry(key.clone())
+            .or_default()
+            .push_back(buffer);
+        self.order.push_back(key);
+        self.stats.recycled += 1;
+        self.stats.num_buffers += 1;
+
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.stats.num_buffers > self.capacity {
+            let key = match self.order.pop_front() {
+                Some(key) => key,
+                None => break,
+            };
+
+            if let Some(buffers) = self.buffers.get_mut(&key) {
+                if buffers.pop_front().is_some() {
+                    self.stats.num_buffers -= 1;
+                    self.stats.evicted += 1;
+                }
+                if buffers.is_empty() {
+                    self.buffers.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{TensorId, TensorStatus};
+
+    use super::*;
+
+    #[test]
+    fn should_reuse_buffer_released_by_the_same_plan() {
+        let mut pool = OutputPool::new(4);
+        let tensor = tensor(&[32, 32]);
+
+        pool.release(0, &tensor, 1u32);
+        pool.begin(0);
+
+        assert_eq!(pool.take(&tensor), Some(1));
+        assert_eq!(pool.take(&tensor), None);
+
+        let stats = pool.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn should_not_reuse_buffer_from_another_plan_or_shape() {
+        let mut pool = OutputPool::new(4);
+
+        pool.release(0, &tensor(&[32, 32]), 1u32);
+        pool.begin(1);
+        assert_eq!(pool.take(&tensor(&[32, 32])), None);
+
+        pool.begin(0);
+        assert_eq!(pool.take(&tensor(&[16, 32])), None);
+        assert_eq!(pool.take(&tensor(&[32, 32])), Some(1));
+    }
+
+    #[test]
+    fn should_evict_oldest_buffers_when_full() {
+        let mut pool = OutputPool::new(2);
+        let tensor = tensor(&[8]);
+
+        pool.release(0, &tensor, 1u32);
+        pool.release(0, &tensor, 2u32);
+        pool.release(0, &tensor, 3u32);
+
+        let stats = pool.stats();
+        assert_eq!(stats.evicted, 1);
+        assert_eq!(stats.num_buffers, 2);
+
+        pool.begin(0);
+        assert_eq!(pool.take(&tensor), Some(3));
+        assert_eq!(pool.take(&tensor), Some(2));
+        assert_eq!(pool.take(&tensor), None);
+    }
+
+    #[test]
+    fn should_evict_oldest_buffer_after_taking_from_the_same_key() {
+        let mut pool = OutputPool::new(2);
+        let small = tensor(&[8]);
+        let large = tensor(&[16]);
+
+        pool.release(0, &small, 1u32);
+        pool.release(0, &large, 2u32);
+        pool.release(0, &small, 3u32);
+
+        pool.begin(0);
+        assert_eq!(pool.take(&small), Some(3));
+        pool.end();
+
+        // Buffer 1 is now the oldest one in the pool.
+        pool.release(0, &large, 4u32);
+        assert_eq!(pool.stats().evicted, 1);
+
+        pool.begin(0);
+        assert_eq!(pool.take(&small), None);
+        assert_eq!(pool.take(&large), Some(4));
+        assert_eq!(pool.take(&large), Some(2));
+    }
+
+    #[test]
+    fn should_keep_buffer_rejected_by_predicate() {
+        let mut pool = OutputPool::new(2);
+        let tensor = tensor(&[8]);
+
+        pool.release(0, &tensor, 1u32);
+        pool.begin(0);
+
+        assert_eq!(pool.take_if(&tensor, |buffer| *buffer > 1), None);
+        assert_eq!(pool.stats().num_buffers, 1);
+        assert_eq!(pool.take(&tensor), Some(1));
+
+        let stats = pool.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[test]
+    fn should_drop_buffers_of_forgotten_plans() {
+        let mut pool = OutputPool::new(4);
+        let tensor = tensor(&[8]);
+
+        pool.release(0, &tensor, 1u32);
+        pool.release(1, &tensor, 2u32);
+        pool.forget_plan(0);
+
+        let stats = pool.stats();
+        assert_eq!(stats.evicted, 1);
+        assert_eq!(stats.num_buffers, 1);
+
+        pool.begin(0);
+        assert_eq!(pool.take(&tensor), None);
+        pool.begin(1);
+        assert_eq!(pool.take(&tensor), Some(2));
+    }
+
+    fn tensor(shape: &[usize]) -> TensorIr {
+        TensorIr {
+            id: TensorId::new(0),
+            shape: shape.to_vec(),
+            status: TensorStatus::ReadWrite,
+            dtype: DType::F32,
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
 use crate::FusionRuntime;
-use crate::stream::{OperationConverter, OperationStreams, RelativeOps, execution::Operation};
+use crate::stream::{
+    OperationConverter, OperationStreams, RelativeOps, execution::Operation, rewrite::Replacement,
+};
 use burn_common::id::StreamId;
-use burn_ir::{OperationIr, TensorId, TensorStatus};
+use burn_ir::{OperationIr, OperationMetadata, TensorId, TensorIr, TensorStatus};
 
 use hashbrown::HashMap;
 
@@ -20,6 +22,8 @@ pub struct OperationQueue<R: FusionRuntime> {
     pub(crate) relative: Vec<OperationIr>,
     pub(crate) converter: OperationConverter,
     pub(crate) operations: Vec<Arc<dyn Operation<R>>>,
+    /// The [metadata](OperationMetadata) attached to each operation when it was registered.
+    pub(crate) metadata: Vec<Option<Arc<OperationMetadata>>>,
     pub(crate) variables: HashMap<TensorId, (StreamId, TensorStatus)>,
 }
 
@@ -37,6 +41,7 @@ impl<R: FusionRuntime> OperationQueue<R> {
             relative: Vec::new(),
             converter: OperationConverter::default(),
             operations: Vec::new(),
+            metadata: Vec::new(),
             variables: HashMap::new(),
         }
     }
@@ -50,6 +55,7 @@ impl<R: FusionRuntime> OperationQueue<R> {
         &mut self,
         global: OperationIr,
         operation: Arc<dyn Operation<R>>,
+        metadata: Option<Arc<OperationMetadata>>,
         streams: &OperationStreams,
         current: StreamId,
     ) {
@@ -64,12 +70,106 @@ impl<R: FusionRuntime> OperationQueue<R> {
         self.relative.push(relative);
         self.global.push(global);
         self.operations.push(operation);
+        self.metadata.push(metadata);
+    }
+
+    /// Remove the operations starting at the given position, so that the operations before it can
+    /// be executed on their own.
+    ///
+    /// The removed operations must be [requeued](Self::requeue) once the others are executed.
+    pub(crate) fn split_off(&mut self, position: usize) -> Vec<QueuedOperation<R>> {
+        self.relative.truncate(position);
+        self.global
+            .split_off(position)
+            .into_iter()
+            .zip(self.operations.split_off(position))
+            .zip(self.metadata.split_off(position))
+            .map(|((global, operation), metadata)| (global, operation, metadata))
+            .collect()
+    }
+
+    /// Replace every queued operation, given its position, with the returned operations.
+    ///
+    /// The tensors of the operations are tracked by the queue when they are registered, so the
+    /// replacements must only use tensors of the queue. They keep the metadata of the operation
+    /// they replace.
+    pub(crate) fn rewrite<F>(&mut self, mut func: F)
+    where
+        F: FnMut(usize, OperationIr, Arc<dyn Operation<R>>) -> Vec<Replacement<R>>,
+    {
+        let global = core::mem::take(&mut self.global);
+        let operations = core::mem::take(&mut self.operations);
+        let metadata = core::mem::take(&mut self.metadata);
+        self.relative.clear();
+        self.converter.clear();
+
+        let queued = global.into_iter().zip(operations).zip(metadata);
+        for (position, ((global, operation), metadata)) in queued.enumerate() {
+            for (global, operation) in func(position, global, operation) {
+                self.requeue(global, operation, metadata.clone());
+            }
+        }
+    }
+
+    /// Add back an operation removed by [split_off](Self::split_off).
+    ///
+    /// The tensors of the operation are still tracked by the queue, so only its relative form is
+    /// computed again.
+    pub(crate) fn requeue(
+        &mut self,
+        global: OperationIr,
+        operation: Arc<dyn Operation<R>>,
+        metadata: Option<Arc<OperationMetadata>>,
+    ) {
+        let relative = global.to_relative(&mut self.converter);
+        self.relative.push(relative);
+        self.global.push(global);
+        self.operations.push(operation);
+        self.metadata.push(metadata);
     }
 }
 
+/// An operation removed from the queue, with how it's executed and its metadata.
+pub(crate) type QueuedOperation<R> = (
+    OperationIr,
+    Arc<dyn Operation<R>>,
+    Option<Arc<OperationMetadata>>,
+);
+
+/// The position of the last operation using any of the given tensors.
+pub(crate) fn last_use(operations: &[OperationIr], tensors: &[&TensorIr]) -> Option<usize> {
+    operations.iter().rposition(|operation| {
+        operation
+            .nodes()
+            .iter()
+            .any(|node| tensors.iter().any(|tensor| tensor.id == node.id))
+    })
+}
+
 #[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_find_the_last_use_of_tensors() {
+        let exp = |input, out| {
+            exp(
+                tensor(input, TensorStatus::ReadOnly),
+                tensor(out, TensorStatus::NotInit),
+            )
+        };
+        let operations = [exp(1, 2), exp(2, 3), exp(3, 4)];
+
+        assert_eq!(
+            last_use(&operations, &[&tensor(2, TensorStatus::ReadOnly)]),
+            Some(1)
+        );
+        assert_eq!(
+            last_use(&operations, &[&tensor(5, TensorStatus::ReadOnly)]),
+            None
+        );
+    }
 
     #[test]
     fn stream_id_from_different_threads() {
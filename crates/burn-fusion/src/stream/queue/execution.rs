@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
-use burn_ir::{HandleContainer, TensorStatus};
+use burn_ir::{Handle, HandleContainer, OperationIr, TensorStatus};
 
 use crate::{
-    FusionRuntime,
+    FusionError, FusionRuntime,
     search::BlockOptimization,
     stream::{
-        Context, Operation, OperationConverter, OrderedExecution, RelativeOps,
+        Context, Operation, OperationConverter, OrderedExecution, OutputPool, RelativeOps,
+        capture::CapturedStep,
         store::{ExecutionPlanId, ExecutionPlanStore, ExecutionStrategy},
     },
 };
@@ -15,43 +16,82 @@ use super::OperationQueue;
 
 impl<R: FusionRuntime> OperationQueue<R> {
     /// Execute the queue partially following the execution strategy from the plan.
+    ///
+    /// Nothing is executed when the plan isn't in the store.
     pub(crate) fn execute(
         &mut self,
         id: ExecutionPlanId,
         handles: &mut HandleContainer<R::FusionHandle>,
         store: &mut ExecutionPlanStore<R::Optimization>,
-    ) {
-        let plan = store.get_mut_unchecked(id);
-        self.execute_block_optimization(&mut plan.optimization, handles);
+        pool: &mut OutputPool<R::FusionHandle>,
+    ) -> Result<(), FusionError> {
+        let plan = store
+            .get_mut(id)
+            .ok_or(FusionError::PlanNotFound { plan: id })?;
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "fusion.execute_plan",
+            plan = id,
+            num_operations = plan.operations.len(),
+            num_queued = self.global.len()
+        )
+        .entered();
+
+        #[cfg(feature = "ordering-checks")]
+        if let Err(violation) =
+            crate::debug::validate_ordering(&self.global, &plan.optimization.strategy.ordering())
+        {
+            return Err(FusionError::PlanExecution {
+                plan: Some(id),
+                reason: format!("invalid ordering, {violation}"),
+            });
+        }
+
+        pool.begin(id);
+        let num_drained = self.execute_block_optimization(&mut plan.optimization, handles, pool);
+        pool.end();
+        self.drain_queue(id, num_drained, handles, pool);
+
+        Ok(())
     }
 
     fn execute_block_optimization(
         &mut self,
         step: &mut BlockOptimization<R::Optimization>,
         handles: &mut HandleContainer<R::FusionHandle>,
-    ) {
+        pool: &mut OutputPool<R::FusionHandle>,
+    ) -> usize {
         let mut operations = Vec::new();
         core::mem::swap(&mut operations, &mut self.operations);
         let (operations, num_drained) =
-            QueueExecution::run(step, &mut self.converter, handles, operations);
+            QueueExecution::run(step, &mut self.converter, handles, pool, operations);
 
         self.operations = operations;
-        self.drain_queue(num_drained, handles);
+        num_drained
     }
 
-    /// Bookkeeping after executing `num_drained` operations from the queue.
-    fn drain_queue(&mut self, num_drained: usize, handles: &mut HandleContainer<R::FusionHandle>) {
-        self.global[0..num_drained]
+    /// Bookkeeping after executing `num_drained` operations from the queue with the given plan.
+    ///
+    /// The buffers of the tensors freed by the plan are released into the pool.
+    fn drain_queue(
+        &mut self,
+        id: ExecutionPlanId,
+        num_drained: usize,
+        handles: &mut HandleContainer<R::FusionHandle>,
+        pool: &mut OutputPool<R::FusionHandle>,
+    ) {
+        for tensor in self.global[0..num_drained]
             .iter()
             .flat_map(|desc| desc.nodes())
-            .for_each(|tensor| {
-                if tensor.status == TensorStatus::ReadWrite {
-                    self.variables.remove(&tensor.id);
-                };
-                handles.free(tensor)
-            });
+            .filter(|tensor| tensor.status == TensorStatus::ReadWrite)
+        {
+            self.variables.remove(&tensor.id);
+        }
+        release_consumed::<R>(id, &self.global[0..num_drained], handles, pool);
 
         self.global.drain(0..num_drained);
+        self.metadata.drain(0..num_drained);
 
         self.reset_relative();
     }
@@ -67,12 +107,64 @@ impl<R: FusionRuntime> OperationQueue<R> {
     }
 }
 
+impl<R: FusionRuntime> CapturedStep<R> {
+    /// Execute the captured operations again with the plan they were executed with.
+    pub(crate) fn replay(
+        &self,
+        handles: &mut HandleContainer<R::FusionHandle>,
+        store: &mut ExecutionPlanStore<R::Optimization>,
+        pool: &mut OutputPool<R::FusionHandle>,
+    ) -> Result<(), FusionError> {
+        let plan = store
+            .get_mut(self.plan)
+            .ok_or(FusionError::PlanNotFound { plan: self.plan })?;
+        // Executing a plan updates the conversion with the tensors it creates.
+        let mut converter = self.converter.clone();
+
+        pool.begin(self.plan);
+        QueueExecution::run(
+            &mut plan.optimization,
+            &mut converter,
+            handles,
+            pool,
+            self.operations.clone(),
+        );
+        pool.end();
+        release_consumed::<R>(self.plan, &self.global, handles, pool);
+
+        Ok(())
+    }
+}
+
+/// Release the buffers of the tensors consumed by the operations into the pool.
+fn release_consumed<R: FusionRuntime>(
+    id: ExecutionPlanId,
+    operations: &[OperationIr],
+    handles: &mut HandleContainer<R::FusionHandle>,
+    pool: &mut OutputPool<R::FusionHandle>,
+) {
+    operations
+        .iter()
+        .flat_map(|desc| desc.nodes())
+        .filter(|tensor| tensor.status == TensorStatus::ReadWrite)
+        .for_each(|tensor| match handles.remove_handle(tensor.id) {
+            // Pinned buffers might still be referenced outside of burn.
+            Some(Handle::Existing(handle))
+                if R::can_recycle(&handle) && !handles.is_pinned(&tensor.id) =>
+            {
+                pool.release(id, tensor, handle)
+            }
+            _ => {}
+        });
+}
+
 /// A queue execution has the responsability to run the provided
 /// [optimization](FusionRuntime::Optimization) without holes.
 enum QueueExecution<'a, R: FusionRuntime> {
     Single {
         handles: &'a mut HandleContainer<R::FusionHandle>,
         converter: &'a mut OperationConverter,
+        pool: &'a mut OutputPool<R::FusionHandle>,
         execution: OrderedExecution<R>,
     },
     Multiple {
@@ -86,12 +178,13 @@ impl<'a, R: FusionRuntime> QueueExecution<'a, R> {
         optimization: &mut BlockOptimization<R::Optimization>,
         converter: &'a mut OperationConverter,
         handles: &'a mut HandleContainer<R::FusionHandle>,
+        pool: &'a mut OutputPool<R::FusionHandle>,
         operations: Vec<Arc<dyn Operation<R>>>,
     ) -> (Vec<Arc<dyn Operation<R>>>, usize) {
         let execution = OrderedExecution::new(operations);
 
         if matches!(&optimization.strategy, ExecutionStrategy::Composed(..)) {
-            let mut context = converter.context(handles);
+            let mut context = converter.context(handles, pool);
             let mut this = QueueExecution::Multiple {
                 context: &mut context,
                 execution,
@@ -107,6 +200,7 @@ impl<'a, R: FusionRuntime> QueueExecution<'a, R> {
             let mut this = QueueExecution::Single {
                 handles,
                 converter,
+                pool,
                 execution,
             };
             this = this.execute_strategy(&mut optimization.strategy);
@@ -123,10 +217,11 @@ impl<'a, R: FusionRuntime> QueueExecution<'a, R> {
             QueueExecution::Single {
                 handles,
                 converter,
+                pool,
                 execution,
             } => match strategy {
                 ExecutionStrategy::Optimization { ordering, opt } => {
-                    let mut context = converter.context(handles);
+                    let mut context = converter.context(handles, pool);
                     execution.execute_optimization(opt, &mut context, ordering.clone())
                 }
                 ExecutionStrategy::Operations { ordering } => {
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use burn_ir::{
+    BaseOperationIr, FloatOperationIr, HandleContainer, NumericOperationIr, OperationIr, TensorId,
+    TensorIr, TensorStatus, UnaryOpIr,
+};
+use burn_tensor::DType;
+
+use crate::{
+    FusionRuntime,
+    debug::StreamStats,
+    stream::{execution::Operation, queue::OperationQueue},
+    tensor::DropOp,
+};
+
+use super::{
+    AlgebraicSimplification, CommonSubexpressionElimination, ConstantFolding, DeadCodeElimination,
+    LayoutPropagation,
+};
+
+/// A pass rewriting the operations queued on a stream before it's drained.
+///
+/// Passes must keep the results read after the drain, and release the tensors consumed by the
+/// operations they remove.
+pub(crate) trait RewritePass<R: FusionRuntime>: Send {
+    /// Rewrite the queued operations, recording what was rewritten in the statistics of the
+    /// stream.
+    fn rewrite(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats);
+}
+
+/// The [passes](RewritePass) run in order on every stream when it's drained.
+pub(crate) struct RewritePipeline<R: FusionRuntime> {
+    passes: Vec<Box<dyn RewritePass<R>>>,
+    /// The passes merging and removing the operations left by the other passes, run last.
+    cleanup: Vec<Box<dyn RewritePass<R>>>,
+}
+
+impl<R: FusionRuntime> RewritePipeline<R> {
+    /// Run every pass on the queued operations.
+    pub(crate) fn run(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats) {
+        if queue.global.is_empty() {
+            return;
+        }
+
+        for pass in self.passes.iter().chain(self.cleanup.iter()) {
+            pass.rewrite(queue, stats);
+        }
+    }
+
+    /// Run the pass after the other passes, before the cleanup.
+    pub(crate) fn register(&mut self, pass: Box<dyn RewritePass<R>>) {
+        self.passes.push(pass);
+    }
+}
+
+impl<R: FusionRuntime> Default for RewritePipeline<R> {
+    fn default() -> Self {
+        Self {
+            // Folded, simplified, cancelled and merged operations consume their inputs, which can
+            // leave them unread.
+            passes: vec![
+                Box::new(ConstantFolding),
+                Box::new(AlgebraicSimplification),
+                Box::new(LayoutPropagation),
+            ],
+            cleanup: vec![
+                Box::new(CommonSubexpressionElimination),
+                Box::new(DeadCodeElimination),
+            ],
+        }
+    }
+}
+
+/// Whether the operation has no effect other than writing its outputs, so that it can be
+/// removed or merged with another one.
+///
+/// Random operations advance the generator of the device, transfers and custom operations may
+/// have effects outside of the stream.
+pub(crate) fn is_pure(operation: &OperationIr) -> bool {
+    match operation {
+        OperationIr::BaseFloat(BaseOperationIr::ToDevice(_))
+        | OperationIr::BaseInt(BaseOperationIr::ToDevice(_))
+        | OperationIr::BaseBool(BaseOperationIr::ToDevice(_))
+        | OperationIr::Float(_, FloatOperationIr::Random(_))
+        | OperationIr::NumericFloat(_, NumericOperationIr::IntRandom(_))
+        | OperationIr::NumericInt(_, NumericOperationIr::IntRandom(_)) => false,
+        OperationIr::BaseFloat(_)
+        | OperationIr::BaseInt(_)
+        | OperationIr::BaseBool(_)
+        | OperationIr::NumericFloat(..)
+        | OperationIr::NumericInt(..)
+        | OperationIr::Bool(_)
+        | OperationIr::Int(_)
+        | OperationIr::Float(..)
+        | OperationIr::Module(_) => true,
+        OperationIr::Init(_) | OperationIr::Custom(_) | OperationIr::Drop(_) => false,
+    }
+}
+
+/// Whether one of the operations consumes the tensor, after which it can't be read anymore.
+pub(crate) fn is_consumed(operations: &[OperationIr], tensor: TensorId) -> bool {
+    operations.iter().any(|operation| {
+        operation
+            .nodes()
+            .iter()
+            .any(|node| node.id == tensor && node.status == TensorStatus::ReadWrite)
+    })
+}
+
+/// An operation replacing queued operations, with its executable.
+pub(crate) type Replacement<R> = (OperationIr, Arc<dyn Operation<R>>);
+
+/// Drop the tensor consumed by a removed operation, so that its buffer is still released.
+pub(crate) fn drop_tensor<R: FusionRuntime>(tensor: &TensorIr) -> Replacement<R> {
+    (
+        OperationIr::Drop(tensor.clone()),
+        Arc::new(DropOp { id: tensor.id }),
+    )
+}
+
+/// Use the buffer of the input as the buffer of the output, moving it when the input is
+/// consumed and sharing it otherwise.
+///
+/// It's represented as an identity reshape, which optimizations can fuse.
+pub(crate) fn alias_tensor<R: FusionRuntime>(input: &TensorIr, out: &TensorIr) -> Replacement<R> {
+    let reshape = BaseOperationIr::Reshape(UnaryOpIr {
+        input: input.clone(),
+        out: out.clone(),
+    });
+    let operation = match input.dtype {
+        DType::Bool => OperationIr::BaseBool(reshape),
+        DType::QFloat(_) => OperationIr::BaseFloat(reshape),
+        dtype if dtype.is_float() => OperationIr::BaseFloat(reshape),
+        _ => OperationIr::BaseInt(reshape),
+    };
+    let alias = AliasOp {
+        input: input.id,
+        status: input.status,
+        out: out.id,
+    };
+
+    (operation, Arc::new(alias))
+}
+
+/// Registers the handle of a tensor as the handle of another one.
+#[derive(Debug)]
+struct AliasOp {
+    input: TensorId,
+    status: TensorStatus,
+    out: TensorId,
+}
+
+impl<R: FusionRuntime> Operation<R> for AliasOp {
+    fn execute(&self, handles: &mut HandleContainer<R::FusionHandle>) {
+        let handle = handles.get_handle(&self.input, &self.status);
+        handles.register_handle(self.out, handle);
+    }
+}
@@ -0,0 +1,234 @@
+use burn_ir::{OperationIr, TensorId, TensorIr, TensorStatus};
+use burn_tensor::DType;
+use hashbrown::HashMap;
+
+use crate::{
+    FusionRuntime,
+    debug::StreamStats,
+    stream::{
+        OperationConverter, RelativeOps, ScalarValue,
+        queue::OperationQueue,
+        rewrite::{RewritePass, alias_tensor, drop_tensor, is_pure},
+    },
+};
+
+/// Merges the queued operations computing the same results as a previous operation from the
+/// same inputs, e.g. masks recomputed by every layer of a model.
+///
+/// The outputs of a merged operation share the buffers of the outputs of the first operation,
+/// which are never written in place since they are shared.
+pub(crate) struct CommonSubexpressionElimination;
+
+/// The tensors read and the tensors written by an operation, telling which operations could
+/// compute the same results without comparing them.
+#[derive(PartialEq, Eq, Hash)]
+struct Signature {
+    inputs: Vec<TensorId>,
+    outputs: Vec<(Vec<usize>, DType)>,
+}
+
+impl<R: FusionRuntime> RewritePass<R> for CommonSubexpressionElimination {
+    fn rewrite(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats) {
+        let merged = merged_operations(&queue.global);
+
+        if merged.is_empty() {
+            return;
+        }
+
+        stats.num_merged += merged.len() as u64;
+        let global = queue.global.clone();
+        queue.rewrite(|position, operation, executable| {
+            let Some(first) = merged.get(&position) else {
+                return vec![(operation, executable)];
+            };
+            let mut operations = Vec::new();
+
+            for (input, out) in outputs(&global[*first]).zip(outputs(&operation)) {
+                let input = TensorIr {
+                    status: TensorStatus::ReadOnly,
+                    ..input.clone()
+                };
+                operations.push(alias_tensor(&input, out));
+            }
+            // The inputs consumed by the operation are still released.
+            for node in operation.nodes() {
+                if node.status == TensorStatus::ReadWrite {
+                    operations.push(drop_tensor(node));
+                }
+            }
+
+            operations
+        });
+    }
+}
+
+/// The position of the operations computing the same results as a previous operation, with the
+/// position of the previous operation.
+fn merged_operations(operations: &[OperationIr]) -> HashMap<usize, usize> {
+    let mut seen = HashMap::<Signature, Vec<usize>>::new();
+    let mut merged = HashMap::new();
+
+    for (position, operation) in operations.iter().enumerate() {
+        if !is_pure(operation) || outputs(operation).next().is_none() {
+            continue;
+        }
+
+        let signature = Signature {
+            inputs: operation
+                .nodes()
+                .into_iter()
+                .filter(|node| node.status != TensorStatus::NotInit)
+                .map(|node| node.id)
+                .collect(),
+            outputs: outputs(operation)
+                .map(|node| (node.shape.clone(), node.dtype))
+                .collect(),
+        };
+
+        if let Some(candidates) = seen.get(&signature) {
+            let computation = Computation::new(operation);
+            let first = candidates.iter().find(|first| {
+                Computation::new(&operations[**first]) == computation
+                    && is_alive(operations, **first, position)
+            });
+
+            if let Some(first) = first {
+                merged.insert(position, *first);
+                continue;
+            }
+        }
+
+        seen.entry(signature).or_default().push(position);
+    }
+
+    merged
+}
+
+/// The computation done by an operation, the same for operations computing the same results
+/// from the same inputs.
+///
+/// It's only compared for operations with the same [signature](Signature), which already read
+/// the same tensors and write outputs of the same shapes. The relative form of the operation
+/// then tells apart the kinds and the attributes of operations, and the scalars tell apart
+/// their values.
+#[derive(PartialEq)]
+struct Computation {
+    operation: OperationIr,
+    scalars: Vec<ScalarValue>,
+}
+
+impl Computation {
+    fn new(operation: &OperationIr) -> Self {
+        let mut operation = operation.clone();
+        let ids = operation
+            .nodes()
+            .into_iter()
+            .map(|node| node.id)
+            .collect::<Vec<_>>();
+        // Whether an input is consumed doesn't change what is computed.
+        operation.mark_read_only(&ids);
+
+        let mut converter = OperationConverter::default();
+        let operation = operation.to_relative(&mut converter);
+
+        Self {
+            operation,
+            scalars: converter.scalar_values(),
+        }
+    }
+}
+
+/// Whether the outputs of the operation at `first` are still alive at `position`.
+fn is_alive(operations: &[OperationIr], first: usize, position: usize) -> bool {
+    let outputs = outputs(&operations[first])
+        .map(|node| node.id)
+        .collect::<Vec<_>>();
+
+    !operations[first + 1..position].iter().any(|operation| {
+        operation
+            .nodes()
+            .iter()
+            .any(|node| node.status == TensorStatus::ReadWrite && outputs.contains(&node.id))
+    })
+}
+
+fn outputs(operation: &OperationIr) -> impl Iterator<Item = &TensorIr> {
+    operation
+        .nodes()
+        .into_iter()
+        .filter(|node| node.status == TensorStatus::NotInit)
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{BaseOperationIr, NumericOperationIr, ScalarOpIr, SwapDimsOpIr};
+
+    use super::*;
+    use crate::test_utils::{exp, tensor, tensor_with_shape};
+
+    #[test]
+    fn should_merge_identical_operations_on_the_same_inputs() {
+        let mul = |input, status, rhs, out| {
+            OperationIr::NumericFloat(
+                DType::F32,
+                NumericOperationIr::MulScalar(ScalarOpIr {
+                    lhs: tensor(input, status),
+                    rhs,
+                    out: tensor(out, TensorStatus::NotInit),
+                }),
+            )
+        };
+        let operations = [
+            mul(1, TensorStatus::ReadOnly, 2.0, 2),
+            // Another scalar.
+            mul(1, TensorStatus::ReadOnly, 3.0, 3),
+            exp(
+                tensor(2, TensorStatus::ReadOnly),
+                tensor(4, TensorStatus::NotInit),
+            ),
+            mul(1, TensorStatus::ReadWrite, 2.0, 5),
+            exp(
+                tensor(2, TensorStatus::ReadWrite),
+                tensor(6, TensorStatus::NotInit),
+            ),
+        ];
+
+        let merged = merged_operations(&operations);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[&3], 0);
+        assert_eq!(merged[&4], 2);
+
+        // The output of the first operation is dropped before.
+        let operations = [
+            exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            OperationIr::Drop(tensor(2, TensorStatus::ReadWrite)),
+            exp(
+                tensor(1, TensorStatus::ReadWrite),
+                tensor(3, TensorStatus::NotInit),
+            ),
+        ];
+        assert!(merged_operations(&operations).is_empty());
+    }
+
+    #[test]
+    fn should_not_merge_operations_with_other_attributes() {
+        let swap_dims = |dim1, dim2, out| {
+            OperationIr::BaseFloat(BaseOperationIr::SwapDims(SwapDimsOpIr {
+                input: tensor_with_shape(1, vec![2, 2, 2], TensorStatus::ReadOnly),
+                out: tensor_with_shape(out, vec![2, 2, 2], TensorStatus::NotInit),
+                dim1,
+                dim2,
+            }))
+        };
+        let operations = [swap_dims(0, 1, 2), swap_dims(1, 2, 3), swap_dims(0, 1, 4)];
+
+        let merged = merged_operations(&operations);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[&2], 0);
+    }
+}
@@ -0,0 +1,140 @@
+use burn_ir::{OperationIr, TensorId, TensorIr, TensorStatus};
+use hashbrown::{HashMap, HashSet};
+
+use crate::{
+    FusionRuntime,
+    debug::StreamStats,
+    stream::{
+        queue::OperationQueue,
+        rewrite::{RewritePass, drop_tensor, is_pure},
+    },
+};
+
+/// Removes the queued operations whose outputs are all dropped without being read.
+///
+/// The tensors consumed by an eliminated operation are dropped in its place, so that their
+/// buffers are still released.
+pub(crate) struct DeadCodeElimination;
+
+impl<R: FusionRuntime> RewritePass<R> for DeadCodeElimination {
+    fn rewrite(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats) {
+        let dead = dead_operations(&queue.global);
+
+        if dead.is_empty() {
+            return;
+        }
+
+        stats.num_eliminated += dead.len() as u64;
+        queue.rewrite(|position, global, operation| match dead.get(&position) {
+            Some(consumed) => consumed.iter().map(drop_tensor).collect(),
+            None => vec![(global, operation)],
+        });
+    }
+}
+
+/// The positions of the operations whose outputs are all dropped without being read, with the
+/// tensors each of them consumes.
+///
+/// Operations are visited from the last one, so that eliminating an operation can make the
+/// operations producing its inputs dead too.
+fn dead_operations(operations: &[OperationIr]) -> HashMap<usize, Vec<TensorIr>> {
+    let mut dropped = HashSet::<TensorId>::new();
+    let mut read = HashSet::<TensorId>::new();
+    let mut dead = HashMap::new();
+
+    for (position, operation) in operations.iter().enumerate().rev() {
+        if let OperationIr::Drop(tensor) = operation {
+            if tensor.status == TensorStatus::ReadWrite {
+                dropped.insert(tensor.id);
+            }
+            continue;
+        }
+
+        let nodes = operation.nodes();
+        let mut outputs = nodes
+            .iter()
+            .filter(|node| node.status == TensorStatus::NotInit)
+            .peekable();
+        let is_dead = outputs.peek().is_some()
+            && outputs.all(|node| dropped.contains(&node.id) && !read.contains(&node.id))
+            && is_pure(operation);
+
+        if !is_dead {
+            read.extend(
+                nodes
+                    .iter()
+                    .filter(|node| node.status != TensorStatus::NotInit)
+                    .map(|node| node.id),
+            );
+            continue;
+        }
+
+        let mut consumed = Vec::new();
+        for node in nodes {
+            if node.status == TensorStatus::ReadWrite && dropped.insert(node.id) {
+                consumed.push(node.clone());
+            }
+        }
+        dead.insert(position, consumed);
+    }
+
+    dead
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{BinaryOpIr, NumericOperationIr};
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::test_utils::{exp, tensor};
+
+    #[test]
+    fn should_eliminate_subgraphs_with_dropped_outputs() {
+        let drop = |id| OperationIr::Drop(tensor(id, TensorStatus::ReadWrite));
+        let add = OperationIr::NumericFloat(
+            DType::F32,
+            NumericOperationIr::Add(BinaryOpIr {
+                lhs: tensor(1, TensorStatus::ReadOnly),
+                rhs: tensor(5, TensorStatus::ReadWrite),
+                out: tensor(6, TensorStatus::NotInit),
+            }),
+        );
+        let operations = [
+            // `2` is only read by `3`, which is dropped.
+            exp(
+                tensor(1, TensorStatus::ReadOnly),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            exp(
+                tensor(2, TensorStatus::ReadWrite),
+                tensor(3, TensorStatus::NotInit),
+            ),
+            drop(3),
+            // `5` is read by `6`, which is returned.
+            exp(
+                tensor(4, TensorStatus::ReadWrite),
+                tensor(5, TensorStatus::NotInit),
+            ),
+            add,
+        ];
+
+        let dead = dead_operations(&operations);
+
+        // The buffer of `2` is never created, but its handle must still be dropped.
+        assert_eq!(dead.len(), 2);
+        assert_eq!(dead[&0], []);
+        assert_eq!(dead[&1], [tensor(2, TensorStatus::ReadWrite)]);
+
+        // The input `1` is consumed, so it's dropped in place of the eliminated operation.
+        let operations = [
+            exp(
+                tensor(1, TensorStatus::ReadWrite),
+                tensor(2, TensorStatus::NotInit),
+            ),
+            drop(2),
+        ];
+        let dead = dead_operations(&operations);
+        assert_eq!(dead[&0], [tensor(1, TensorStatus::ReadWrite)]);
+    }
+}
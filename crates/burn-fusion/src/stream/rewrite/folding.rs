@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use burn_ir::{
+    FloatOperationIr, HandleContainer, InitOperationIr, NumericOperationIr, OperationIr, TensorId,
+    TensorIr, TensorStatus,
+};
+use burn_tensor::TensorData;
+use hashbrown::HashMap;
+
+use crate::{
+    FusionRuntime,
+    debug::StreamStats,
+    stream::{
+        execution::{MaterializeFn, Operation},
+        queue::OperationQueue,
+        rewrite::{RewritePass, drop_tensor},
+    },
+};
+
+/// Computes the queued operations reading only [constants](crate::stream::Constant) when the
+/// stream is drained, replacing them with the initialization of their result.
+///
+/// Only element wise operations on float tensors are folded, e.g. the scalars of a model derived
+/// from its configuration. The results are constants too, so chains of such operations are
+/// folded at once.
+pub(crate) struct ConstantFolding;
+
+/// A tensor whose data is known before the stream is executed.
+struct FoldedConstant<R: FusionRuntime> {
+    data: TensorData,
+    device: R::FusionDevice,
+    materialize: MaterializeFn<R>,
+}
+
+/// Creates the buffer of a folded tensor from its data.
+#[derive(Debug)]
+struct FoldedOp<R: FusionRuntime> {
+    out: TensorId,
+    data: TensorData,
+    device: R::FusionDevice,
+    materialize: MaterializeFn<R>,
+}
+
+impl<R: FusionRuntime> RewritePass<R> for ConstantFolding {
+    fn rewrite(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats) {
+        let mut constants = HashMap::<TensorId, FoldedConstant<R>>::new();
+        let mut folded = HashMap::new();
+
+        for (position, (global, operation)) in
+            queue.global.iter().zip(queue.operations.iter()).enumerate()
+        {
+            if let OperationIr::Init(init) = global {
+                if let Some(constant) = operation.constant() {
+                    constants.insert(
+                        init.out.id,
+                        FoldedConstant {
+                            data: constant.data.clone(),
+                            device: constant.device.clone(),
+                            materialize: constant.materialize,
+                        },
+                    );
+                }
+                continue;
+            }
+
+            let Some((out, data)) = evaluate(global, |id| {
+                constants.get(&id).map(|constant| &constant.data)
+            }) else {
+                continue;
+            };
+            let Some(input) = global
+                .nodes()
+                .into_iter()
+                .find_map(|node| constants.get(&node.id))
+            else {
+                continue;
+            };
+
+            let constant = FoldedConstant {
+                data,
+                device: input.device.clone(),
+                materialize: input.materialize,
+            };
+            folded.insert(position, out.clone());
+            constants.insert(out.id, constant);
+        }
+
+        if folded.is_empty() {
+            return;
+        }
+
+        stats.num_folded += folded.len() as u64;
+        queue.rewrite(|position, global, operation| {
+            let Some(out) = folded.get(&position) else {
+                return vec![(global, operation)];
+            };
+            let constant = &constants[&out.id];
+            let init: Arc<dyn Operation<R>> = Arc::new(FoldedOp {
+                out: out.id,
+                data: constant.data.clone(),
+                device: constant.device.clone(),
+                materialize: constant.materialize,
+            });
+            let mut operations = vec![(
+                OperationIr::Init(InitOperationIr { out: out.clone() }),
+                init,
+            )];
+
+            // The inputs consumed by the operation are still released.
+            for node in global.nodes() {
+                if node.status == TensorStatus::ReadWrite {
+                    operations.push(drop_tensor(node));
+                }
+            }
+
+            operations
+        });
+    }
+}
+
+impl<R: FusionRuntime> Operation<R> for FoldedOp<R> {
+    fn execute(&self, handles: &mut HandleContainer<R::FusionHandle>) {
+        let handle = (self.materialize)(self.data.clone(), &self.device);
+        handles.register_handle(self.out, handle);
+    }
+}
+
+/// The output of the operation and its data, when it can be folded with the data of its inputs.
+fn evaluate<'a>(
+    operation: &OperationIr,
+    constant: impl Fn(TensorId) -> Option<&'a TensorData>,
+) -> Option<(TensorIr, TensorData)> {
+    let unary = |input: &TensorIr, out: &TensorIr, func: fn(f64) -> f64| {
+        let values = constant(input.id)?.iter::<f64>().map(func).collect();
+        Some((out.clone(), values))
+    };
+    let binary = |lhs: &TensorIr, rhs: &TensorIr, out: &TensorIr, func: fn(f64, f64) -> f64| {
+        // Broadcasted operations aren't folded.
+        if lhs.shape != out.shape || rhs.shape != out.shape {
+            return None;
+        }
+        let rhs = constant(rhs.id)?.iter::<f64>();
+        let values = constant(lhs.id)?
+            .iter::<f64>()
+            .zip(rhs)
+            .map(|(lhs, rhs)| func(lhs, rhs))
+            .collect();
+        Some((out.clone(), values))
+    };
+    let scalar = |lhs: &TensorIr, rhs: f32, out: &TensorIr, func: fn(f64, f64) -> f64| {
+        let rhs = rhs as f64;
+        let values = constant(lhs.id)?
+            .iter::<f64>()
+            .map(|lhs| func(lhs, rhs))
+            .collect();
+        Some((out.clone(), values))
+    };
+
+    let (out, values): (TensorIr, Vec<f64>) = match operation {
+        OperationIr::Float(_, operation) => match operation {
+            FloatOperationIr::Exp(op) => unary(&op.input, &op.out, f64::exp),
+            FloatOperationIr::Log(op) => unary(&op.input, &op.out, f64::ln),
+            FloatOperationIr::Log1p(op) => unary(&op.input, &op.out, f64::ln_1p),
+            FloatOperationIr::Sqrt(op) => unary(&op.input, &op.out, f64::sqrt),
+            FloatOperationIr::Cos(op) => unary(&op.input, &op.out, f64::cos),
+            FloatOperationIr::Sin(op) => unary(&op.input, &op.out, f64::sin),
+            FloatOperationIr::Tanh(op) => unary(&op.input, &op.out, f64::tanh),
+            FloatOperationIr::Recip(op) => unary(&op.input, &op.out, f64::recip),
+            FloatOperationIr::Round(op) => unary(&op.input, &op.out, f64::round_ties_even),
+            FloatOperationIr::Floor(op) => unary(&op.input, &op.out, f64::floor),
+            FloatOperationIr::Ceil(op) => unary(&op.input, &op.out, f64::ceil),
+            _ => None,
+        },
+        OperationIr::NumericFloat(_, operation) => match operation {
+            NumericOperationIr::Add(op) => binary(&op.lhs, &op.rhs, &op.out, |a, b| a + b),
+            NumericOperationIr::Sub(op) => binary(&op.lhs, &op.rhs, &op.out, |a, b| a - b),
+            NumericOperationIr::Mul(op) => binary(&op.lhs, &op.rhs, &op.out, |a, b| a * b),
+            NumericOperationIr::Div(op) => binary(&op.lhs, &op.rhs, &op.out, |a, b| a / b),
+            NumericOperationIr::AddScalar(op) => scalar(&op.lhs, op.rhs, &op.out, |a, b| a + b),
+            NumericOperationIr::SubScalar(op) => scalar(&op.lhs, op.rhs, &op.out, |a, b| a - b),
+            NumericOperationIr::MulScalar(op) => scalar(&op.lhs, op.rhs, &op.out, |a, b| a * b),
+            NumericOperationIr::DivScalar(op) => scalar(&op.lhs, op.rhs, &op.out, |a, b| a / b),
+            NumericOperationIr::Abs(op) => unary(&op.input, &op.out, f64::abs),
+            _ => None,
+        },
+        _ => None,
+    }?;
+
+    if !out.dtype.is_float() {
+        return None;
+    }
+
+    let data = TensorData::new(values, out.shape.clone()).convert_dtype(out.dtype);
+    Some((out, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{ScalarOpIr, UnaryOpIr};
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::test_utils::tensor_with_shape;
+
+    #[test]
+    fn should_evaluate_operations_on_constants() {
+        let tensor = |id, status| tensor_with_shape(id, vec![2], status);
+        let data = TensorData::new(vec![1.0f32, 4.0], [2]);
+        let constant = |id: TensorId| (id == TensorId::new(1)).then_some(&data);
+
+        let sqrt = OperationIr::Float(
+            DType::F32,
+            FloatOperationIr::Sqrt(UnaryOpIr {
+                input: tensor(1, TensorStatus::ReadOnly),
+                out: tensor(2, TensorStatus::NotInit),
+            }),
+        );
+        let (out, folded) = evaluate(&sqrt, constant).unwrap();
+        assert_eq!(out.id, TensorId::new(2));
+        assert_eq!(folded, TensorData::new(vec![1.0f32, 2.0], [2]));
+
+        let mul = |lhs| {
+            OperationIr::NumericFloat(
+                DType::F32,
+                NumericOperationIr::MulScalar(ScalarOpIr {
+                    lhs: tensor(lhs, TensorStatus::ReadOnly),
+                    rhs: 0.5,
+                    out: tensor(3, TensorStatus::NotInit),
+                }),
+            )
+        };
+        let (_, folded) = evaluate(&mul(1), constant).unwrap();
+        assert_eq!(folded, TensorData::new(vec![0.5f32, 2.0], [2]));
+
+        // The input isn't a constant.
+        assert!(evaluate(&mul(4), constant).is_none());
+    }
+}
@@ -0,0 +1,291 @@
+use burn_ir::{BaseOperationIr, OperationIr, TensorId, TensorIr, TensorStatus};
+use hashbrown::HashMap;
+
+use crate::{
+    FusionRuntime,
+    debug::StreamStats,
+    stream::{
+        queue::OperationQueue,
+        rewrite::{RewritePass, alias_tensor, drop_tensor, is_consumed},
+    },
+};
+
+/// Cancels the queued chains of layout operations restoring the layout of their input, e.g. a
+/// permutation followed by its inverse, which otherwise produce real data movement when an
+/// element wise operation reads the permuted tensor.
+///
+/// The output of a cancelled chain shares the buffer of its input. When the input is consumed
+/// by the chain and no other operation reads the tensors in between, the whole chain is removed
+/// and the buffer is moved instead.
+pub(crate) struct LayoutPropagation;
+
+/// How a tensor is laid out relative to the input of a chain of layout operations.
+#[derive(Clone, Debug, PartialEq)]
+enum Layout {
+    /// The dimensions of the input in a new order, swapped dimensions included.
+    Permuted(Vec<usize>),
+    /// The input with a new shape.
+    Reshaped,
+}
+
+/// A tensor produced by a chain of layout operations.
+#[derive(Clone)]
+struct View {
+    /// The input of the chain, with its status in the first operation.
+    input: TensorIr,
+    layout: Layout,
+    /// The position of the operations of the chain.
+    chain: Vec<usize>,
+}
+
+/// How a queued operation is rewritten.
+#[derive(Debug, PartialEq)]
+enum Cancellation {
+    /// The operation ends a chain, its output becoming an alias of the input of the chain.
+    Alias(TensorIr),
+    /// The operation is part of a removed chain whose input is moved.
+    Removed(TensorId),
+}
+
+impl<R: FusionRuntime> RewritePass<R> for LayoutPropagation {
+    fn rewrite(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats) {
+        let cancelled = cancelled_chains(&queue.global);
+
+        if cancelled.is_empty() {
+            return;
+        }
+
+        stats.num_cancelled += cancelled
+            .values()
+            .filter(|cancellation| matches!(cancellation, Cancellation::Alias(_)))
+            .count() as u64;
+        queue.rewrite(|position, operation, executable| {
+            let mut operations = Vec::new();
+            let input = match cancelled.get(&position) {
+                Some(Cancellation::Alias(input)) => {
+                    let (_, out, _) = layout(&operation).expect("Cancelled layout operation");
+                    operations.push(alias_tensor(input, out));
+                    input.id
+                }
+                Some(Cancellation::Removed(input)) => *input,
+                None => return vec![(operation, executable)],
+            };
+
+            // The tensors in between are still released.
+            for node in operation.nodes() {
+                if node.status == TensorStatus::ReadWrite && node.id != input {
+                    operations.push(drop_tensor(node));
+                }
+            }
+
+            operations
+        });
+    }
+}
+
+/// The position of the operations of the cancelled chains, with how they are rewritten.
+fn cancelled_chains(operations: &[OperationIr]) -> HashMap<usize, Cancellation> {
+    let mut views = HashMap::<TensorId, View>::new();
+    let mut cancelled = HashMap::new();
+
+    for (position, operation) in operations.iter().enumerate() {
+        let Some((input, out, layout)) = layout(operation) else {
+            continue;
+        };
+
+        let view = match views.get(&input.id) {
+            Some(view) => match (&view.layout, &layout) {
+                (Layout::Permuted(first), Layout::Permuted(axes)) => Some(View {
+                    input: view.input.clone(),
+                    layout: Layout::Permuted(axes.iter().map(|axis| first[*axis]).collect()),
+                    chain: [view.chain.as_slice(), &[position]].concat(),
+                }),
+                (Layout::Reshaped, Layout::Reshaped) => Some(View {
+                    input: view.input.clone(),
+                    layout: Layout::Reshaped,
+                    chain: [view.chain.as_slice(), &[position]].concat(),
+                }),
+                _ => None,
+            },
+            None => None,
+        };
+        let view = view.unwrap_or_else(|| View {
+            input: input.clone(),
+            layout,
+            chain: vec![position],
+        });
+
+        let is_identity = match &view.layout {
+            Layout::Permuted(axes) => axes.iter().enumerate().all(|(i, axis)| i == *axis),
+            Layout::Reshaped => view.input.shape == out.shape,
+        };
+
+        // A single identity operation is already an alias.
+        if !is_identity || view.chain.len() < 2 || view.input.dtype != out.dtype {
+            views.insert(out.id, view);
+            continue;
+        }
+
+        let first = view.chain[0];
+        if !is_consumed(&operations[first..position], view.input.id) {
+            let input = TensorIr {
+                status: TensorStatus::ReadOnly,
+                ..view.input
+            };
+            cancelled.insert(position, Cancellation::Alias(input));
+        } else if view.input.status == TensorStatus::ReadWrite
+            && is_private(operations, &view.chain)
+        {
+            for position in &view.chain[..view.chain.len() - 1] {
+                cancelled.insert(*position, Cancellation::Removed(view.input.id));
+            }
+            cancelled.insert(position, Cancellation::Alias(view.input));
+        }
+    }
+
+    cancelled
+}
+
+/// Whether the tensors in between the operations of the chain are only read by the chain.
+fn is_private(operations: &[OperationIr], chain: &[usize]) -> bool {
+    chain.windows(2).all(|positions| {
+        let (_, tensor, _) = layout(&operations[positions[0]]).expect("Layout operation");
+        let readers = operations
+            .iter()
+            .enumerate()
+            .filter(|(_, operation)| {
+                operation
+                    .nodes()
+                    .iter()
+                    .any(|node| node.id == tensor.id && node.status != TensorStatus::NotInit)
+            })
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+
+        readers == [positions[1]]
+    })
+}
+
+/// The input, the output and the layout of the layout operation.
+fn layout(operation: &OperationIr) -> Option<(&TensorIr, &TensorIr, Layout)> {
+    let operation = match operation {
+        OperationIr::BaseFloat(operation)
+        | OperationIr::BaseInt(operation)
+        | OperationIr::BaseBool(operation) => operation,
+        _ => return None,
+    };
+
+    match operation {
+        BaseOperationIr::Permute(op) => {
+            Some((&op.input, &op.out, Layout::Permuted(op.axes.clone())))
+        }
+        BaseOperationIr::SwapDims(op) => {
+            let mut axes = (0..op.input.shape.len()).collect::<Vec<_>>();
+            axes.swap(op.dim1, op.dim2);
+            Some((&op.input, &op.out, Layout::Permuted(axes)))
+        }
+        BaseOperationIr::Reshape(op) => Some((&op.input, &op.out, Layout::Reshaped)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{FloatOperationIr, PermuteOpIr, SwapDimsOpIr, UnaryOpIr};
+    use burn_tensor::DType;
+
+    use super::*;
+
+    fn tensor(id: u64, shape: [usize; 3], status: TensorStatus) -> TensorIr {
+        TensorIr {
+            id: TensorId::new(id),
+            shape: shape.to_vec(),
+            status,
+            dtype: DType::F32,
+        }
+    }
+
+    fn permute(input: TensorIr, out: TensorIr, axes: [usize; 3]) -> OperationIr {
+        OperationIr::BaseFloat(BaseOperationIr::Permute(PermuteOpIr {
+            input,
+            out,
+            axes: axes.to_vec(),
+        }))
+    }
+
+    #[test]
+    fn should_cancel_inverse_permutations() {
+        let operations = [
+            permute(
+                tensor(1, [2, 3, 4], TensorStatus::ReadOnly),
+                tensor(2, [4, 2, 3], TensorStatus::NotInit),
+                [2, 0, 1],
+            ),
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::Exp(UnaryOpIr {
+                    input: tensor(2, [4, 2, 3], TensorStatus::ReadOnly),
+                    out: tensor(3, [4, 2, 3], TensorStatus::NotInit),
+                }),
+            ),
+            OperationIr::BaseFloat(BaseOperationIr::SwapDims(SwapDimsOpIr {
+                input: tensor(2, [4, 2, 3], TensorStatus::ReadWrite),
+                out: tensor(4, [4, 3, 2], TensorStatus::NotInit),
+                dim1: 1,
+                dim2: 2,
+            })),
+            permute(
+                tensor(4, [4, 3, 2], TensorStatus::ReadWrite),
+                tensor(5, [2, 3, 4], TensorStatus::NotInit),
+                [2, 1, 0],
+            ),
+        ];
+
+        let cancelled = cancelled_chains(&operations);
+
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(
+            cancelled[&3],
+            Cancellation::Alias(tensor(1, [2, 3, 4], TensorStatus::ReadOnly))
+        );
+    }
+
+    #[test]
+    fn should_remove_chains_consuming_their_input() {
+        let operations = [
+            permute(
+                tensor(1, [2, 3, 4], TensorStatus::ReadWrite),
+                tensor(2, [4, 2, 3], TensorStatus::NotInit),
+                [2, 0, 1],
+            ),
+            permute(
+                tensor(2, [4, 2, 3], TensorStatus::ReadWrite),
+                tensor(3, [2, 3, 4], TensorStatus::NotInit),
+                [1, 2, 0],
+            ),
+        ];
+
+        let cancelled = cancelled_chains(&operations);
+
+        assert_eq!(cancelled.len(), 2);
+        assert_eq!(cancelled[&0], Cancellation::Removed(TensorId::new(1)));
+        assert_eq!(
+            cancelled[&1],
+            Cancellation::Alias(tensor(1, [2, 3, 4], TensorStatus::ReadWrite))
+        );
+
+        // The permuted tensor is read by another operation.
+        let mut operations = operations.to_vec();
+        operations.insert(
+            1,
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::Exp(UnaryOpIr {
+                    input: tensor(2, [4, 2, 3], TensorStatus::ReadOnly),
+                    out: tensor(4, [4, 2, 3], TensorStatus::NotInit),
+                }),
+            ),
+        );
+        assert!(cancelled_chains(&operations).is_empty());
+    }
+}
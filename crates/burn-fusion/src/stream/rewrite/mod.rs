@@ -0,0 +1,16 @@
+mod base;
+mod cse;
+mod dce;
+mod folding;
+mod layout;
+mod pattern;
+mod simplify;
+
+pub(crate) use base::*;
+pub(crate) use cse::*;
+pub(crate) use dce::*;
+pub(crate) use folding::*;
+pub(crate) use layout::*;
+pub(crate) use pattern::PatternRewrite;
+pub use pattern::{OperationPattern, RewriteRule, RewriteStep};
+pub(crate) use simplify::*;
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use burn_ir::{OperationIr, TensorIr};
+use hashbrown::HashMap;
+
+use crate::{
+    FusionRuntime,
+    debug::{StreamStats, operation_kind},
+    stream::{
+        execution::Operation,
+        queue::OperationQueue,
+        rewrite::{Replacement, RewritePass, alias_tensor, drop_tensor},
+    },
+};
+
+/// A user-defined rewrite of consecutive queued operations, registered on a device with
+/// [register_rewrite](crate::Fusion::register_rewrite).
+///
+/// Rules run when a stream is drained, after the built-in passes folding constants and
+/// simplifying operations and before the passes merging and removing operations, which clean up
+/// after them. They must keep the results read after the drain, and only use the tensors of the
+/// matched operations in their replacements.
+pub trait RewriteRule<R: FusionRuntime>: Send {
+    /// The kinds of the consecutive operations matched by the rule.
+    fn pattern(&self) -> Vec<OperationPattern>;
+
+    /// The operations replacing the matched ones, or `None` to keep them, e.g. when they don't
+    /// use each other's outputs.
+    fn rewrite(&self, matched: &[OperationIr]) -> Option<Vec<RewriteStep<R>>>;
+}
+
+/// Matches an operation by kind, the way the [fusion filter](crate::FusionFilter) does, e.g.
+/// `Float::Exp` or `Exp`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationPattern {
+    kind: String,
+}
+
+/// An operation replacing the operations matched by a [rule](RewriteRule).
+pub enum RewriteStep<R: FusionRuntime> {
+    /// Keep the matched operation at the given index.
+    Keep(usize),
+    /// Use the buffer of the input as the buffer of the output, moving it when the input is
+    /// consumed and sharing it otherwise.
+    Alias {
+        /// The tensor whose buffer is used.
+        input: TensorIr,
+        /// The tensor written by a removed operation.
+        out: TensorIr,
+    },
+    /// Release a tensor consumed by a removed operation.
+    Drop(TensorIr),
+    /// A new operation, with how it's executed.
+    Operation(OperationIr, Arc<dyn Operation<R>>),
+}
+
+/// Runs a [rule](RewriteRule) on every non-overlapping match of its pattern.
+pub(crate) struct PatternRewrite<R: FusionRuntime> {
+    pub(crate) rule: Box<dyn RewriteRule<R>>,
+}
+
+impl OperationPattern {
+    /// Match the operations of the given kind.
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self { kind: kind.into() }
+    }
+
+    /// Whether the operation is of the kind of the pattern.
+    pub fn matches(&self, operation: &OperationIr) -> bool {
+        let kind = operation_kind(operation);
+        let name = kind.rsplit("::").next().unwrap_or_default();
+
+        self.kind == kind || self.kind == name
+    }
+}
+
+impl<R: FusionRuntime> RewritePass<R> for PatternRewrite<R> {
+    fn rewrite(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats) {
+        let pattern = self.rule.pattern();
+        let mut rewritten = matches(&queue.global, &pattern, |matched| {
+            self.rule.rewrite(matched)
+        });
+
+        if rewritten.is_empty() {
+            return;
+        }
+
+        stats.num_custom += rewritten.len() as u64;
+        let mut matched = Vec::with_capacity(pattern.len());
+        // The steps of the match being visited.
+        let mut steps = None;
+        queue.rewrite(|position, operation, executable| {
+            if let Some(found) = rewritten.remove(&position) {
+                steps = Some(found);
+            }
+            if steps.is_none() {
+                return vec![(operation, executable)];
+            }
+
+            matched.push((operation, executable));
+            if matched.len() < pattern.len() {
+                return Vec::new();
+            }
+
+            let replacements = steps
+                .take()
+                .into_iter()
+                .flatten()
+                .map(|step| replacement(step, &matched))
+                .collect();
+            matched.clear();
+            replacements
+        });
+    }
+}
+
+fn replacement<R: FusionRuntime>(
+    step: RewriteStep<R>,
+    matched: &[(OperationIr, Arc<dyn Operation<R>>)],
+) -> Replacement<R> {
+    match step {
+        RewriteStep::Keep(index) => matched
+            .get(index)
+            .cloned()
+            .expect("Kept operations are part of the pattern"),
+        RewriteStep::Alias { input, out } => alias_tensor(&input, &out),
+        RewriteStep::Drop(tensor) => drop_tensor(&tensor),
+        RewriteStep::Operation(operation, executable) => (operation, executable),
+    }
+}
+
+/// The first position of every non-overlapping match of the pattern, with its rewrite.
+fn matches<S>(
+    operations: &[OperationIr],
+    pattern: &[OperationPattern],
+    mut rewrite: impl FnMut(&[OperationIr]) -> Option<Vec<S>>,
+) -> HashMap<usize, Vec<S>> {
+    let mut rewritten = HashMap::new();
+    let mut position = 0;
+
+    if pattern.is_empty() {
+        return rewritten;
+    }
+
+    while position + pattern.len() <= operations.len() {
+        let window = &operations[position..position + pattern.len()];
+        let is_match = window
+            .iter()
+            .zip(pattern)
+            .all(|(operation, pattern)| pattern.matches(operation));
+
+        match is_match.then(|| rewrite(window)).flatten() {
+            Some(steps) => {
+                rewritten.insert(position, steps);
+                position += pattern.len();
+            }
+            None => position += 1,
+        }
+    }
+
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{FloatOperationIr, TensorId, TensorStatus, UnaryOpIr};
+    use burn_tensor::DType;
+
+    use super::*;
+
+    #[test]
+    fn should_match_consecutive_operations_by_kind() {
+        let unary = |input, out, exp| {
+            let op = UnaryOpIr {
+                input: TensorIr {
+                    id: TensorId::new(input),
+                    shape: vec![8],
+                    status: TensorStatus::ReadOnly,
+                    dtype: DType::F32,
+                },
+                out: TensorIr {
+                    id: TensorId::new(out),
+                    shape: vec![8],
+                    status: TensorStatus::NotInit,
+                    dtype: DType::F32,
+                },
+            };
+            match exp {
+                true => OperationIr::Float(DType::F32, FloatOperationIr::Exp(op)),
+                false => OperationIr::Float(DType::F32, FloatOperationIr::Log(op)),
+            }
+        };
+        let operations = [
+            unary(1, 2, true),
+            unary(2, 3, false),
+            unary(3, 4, false),
+            unary(4, 5, true),
+            unary(5, 6, false),
+            // Doesn't read the output of the previous operation.
+            unary(1, 7, true),
+            unary(2, 8, false),
+        ];
+        let pattern = [
+            OperationPattern::new("Float::Exp"),
+            OperationPattern::new("Log"),
+        ];
+
+        let rewritten = matches(&operations, &pattern, |matched| {
+            let (first, second) = (&matched[0].nodes()[1], &matched[1].nodes()[0]);
+            (first.id == second.id).then(|| vec![first.id])
+        });
+
+        assert_eq!(rewritten.len(), 2);
+        assert_eq!(rewritten[&0], vec![TensorId::new(2)]);
+        assert_eq!(rewritten[&3], vec![TensorId::new(5)]);
+    }
+}
@@ -0,0 +1,201 @@
+use burn_ir::{NumericOperationIr, OperationIr, TensorId, TensorIr, TensorStatus};
+use hashbrown::HashMap;
+
+use crate::{
+    FusionRuntime,
+    debug::StreamStats,
+    stream::{
+        queue::OperationQueue,
+        rewrite::{RewritePass, alias_tensor, drop_tensor, is_consumed},
+    },
+};
+
+/// Replaces the queued operations returning one of their inputs with an alias of the input,
+/// e.g. `x * 1`, `x + 0` or `-(-x)`, which show up in generically written model code.
+///
+/// Only operations whose output has the shape and the data type of the input are simplified,
+/// so broadcasting and casting operations are kept.
+pub(crate) struct AlgebraicSimplification;
+
+/// A tensor known to hold the same value everywhere.
+#[derive(Clone, Copy, PartialEq)]
+enum Filled {
+    Zeros,
+    Ones,
+}
+
+impl<R: FusionRuntime> RewritePass<R> for AlgebraicSimplification {
+    fn rewrite(&self, queue: &mut OperationQueue<R>, stats: &mut StreamStats) {
+        let identities = identities(&queue.global);
+
+        if identities.is_empty() {
+            return;
+        }
+
+        stats.num_simplified += identities.len() as u64;
+        queue.rewrite(|position, operation, executable| {
+            let Some(input) = identities.get(&position) else {
+                return vec![(operation, executable)];
+            };
+            let nodes = operation.nodes();
+            let out = nodes
+                .iter()
+                .find(|node| node.status == TensorStatus::NotInit)
+                .expect("Simplified operations have an output");
+            let mut operations = vec![alias_tensor(input, out)];
+
+            // The other inputs consumed by the operation are still released.
+            for node in nodes.iter() {
+                if node.status == TensorStatus::ReadWrite && node.id != input.id {
+                    operations.push(drop_tensor(node));
+                }
+            }
+
+            operations
+        });
+    }
+}
+
+/// The position of the operations returning one of their inputs, with the input returned.
+fn identities(operations: &[OperationIr]) -> HashMap<usize, TensorIr> {
+    let mut filled = HashMap::<TensorId, Filled>::new();
+    // The negated tensors with the position of their negation.
+    let mut negated = HashMap::<TensorId, (TensorIr, usize)>::new();
+    let mut identities = HashMap::new();
+
+    for (position, operation) in operations.iter().enumerate() {
+        let identity = match operation {
+            OperationIr::NumericFloat(_, operation) => {
+                numeric_identity(operation, 0.0, 1.0, &mut filled, &mut negated, position)
+            }
+            OperationIr::NumericInt(_, operation) => {
+                numeric_identity(operation, 0, 1, &mut filled, &mut negated, position)
+            }
+            _ => None,
+        };
+
+        let Some(input) = identity else {
+            continue;
+        };
+
+        // The input of a double negation is read by the first negation only, so it must still
+        // be alive.
+        if let Some((_, first)) = negated.values().find(|(tensor, _)| tensor.id == input.id)
+            && is_consumed(&operations[*first..position], input.id)
+        {
+            continue;
+        }
+
+        identities.insert(position, input);
+    }
+
+    identities
+}
+
+/// The input returned by the numeric operation, if any.
+fn numeric_identity<E: PartialEq + core::ops::Neg<Output = E> + Copy>(
+    operation: &NumericOperationIr<E>,
+    zero: E,
+    one: E,
+    filled: &mut HashMap<TensorId, Filled>,
+    negated: &mut HashMap<TensorId, (TensorIr, usize)>,
+    position: usize,
+) -> Option<TensorIr> {
+    let same = |input: &TensorIr, out: &TensorIr| {
+        (input.shape == out.shape && input.dtype == out.dtype).then(|| input.clone())
+    };
+    let is = |tensor: &TensorIr, value| filled.get(&tensor.id) == Some(&value);
+
+    match operation {
+        NumericOperationIr::Zeros(out) => {
+            filled.insert(out.id, Filled::Zeros);
+            None
+        }
+        NumericOperationIr::Ones(out) => {
+            filled.insert(out.id, Filled::Ones);
+            None
+        }
+        NumericOperationIr::Full((out, value)) => {
+            if *value == zero {
+                filled.insert(out.id, Filled::Zeros);
+            } else if *value == one {
+                filled.insert(out.id, Filled::Ones);
+            }
+            None
+        }
+        NumericOperationIr::AddScalar(op) | NumericOperationIr::SubScalar(op) => {
+            (op.rhs == zero).then(|| same(&op.lhs, &op.out)).flatten()
+        }
+        NumericOperationIr::DivScalar(op) => {
+            (op.rhs == one).then(|| same(&op.lhs, &op.out)).flatten()
+        }
+        NumericOperationIr::MulScalar(op) if op.rhs == one => same(&op.lhs, &op.out),
+        NumericOperationIr::MulScalar(op) if op.rhs == -one => {
+            if let Some((input, _)) = negated.get(&op.lhs.id) {
+                let input = TensorIr {
+                    status: TensorStatus::ReadOnly,
+                    ..input.clone()
+                };
+                return same(&input, &op.out);
+            }
+
+            negated.insert(op.out.id, (op.lhs.clone(), position));
+            None
+        }
+        NumericOperationIr::Add(op) if is(&op.rhs, Filled::Zeros) => same(&op.lhs, &op.out),
+        NumericOperationIr::Add(op) if is(&op.lhs, Filled::Zeros) => same(&op.rhs, &op.out),
+        NumericOperationIr::Sub(op) if is(&op.rhs, Filled::Zeros) => same(&op.lhs, &op.out),
+        NumericOperationIr::Mul(op) if is(&op.rhs, Filled::Ones) => same(&op.lhs, &op.out),
+        NumericOperationIr::Mul(op) if is(&op.lhs, Filled::Ones) => same(&op.rhs, &op.out),
+        NumericOperationIr::Div(op) if is(&op.rhs, Filled::Ones) => same(&op.lhs, &op.out),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ir::{BinaryOpIr, ScalarOpIr};
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::test_utils::tensor;
+
+    #[test]
+    fn should_find_operations_returning_their_input() {
+        let scalar = |input, rhs, out| ScalarOpIr {
+            lhs: tensor(input, TensorStatus::ReadOnly),
+            rhs,
+            out: tensor(out, TensorStatus::NotInit),
+        };
+        let float = |operation| OperationIr::NumericFloat(DType::F32, operation);
+        let operations = [
+            float(NumericOperationIr::MulScalar(scalar(1, 1.0, 2))),
+            float(NumericOperationIr::AddScalar(scalar(1, 2.0, 3))),
+            float(NumericOperationIr::Zeros(tensor(4, TensorStatus::NotInit))),
+            float(NumericOperationIr::Add(BinaryOpIr {
+                lhs: tensor(4, TensorStatus::ReadWrite),
+                rhs: tensor(3, TensorStatus::ReadOnly),
+                out: tensor(5, TensorStatus::NotInit),
+            })),
+            // Double negation.
+            float(NumericOperationIr::MulScalar(scalar(5, -1.0, 6))),
+            float(NumericOperationIr::MulScalar(scalar(6, -1.0, 7))),
+        ];
+
+        let simplified = identities(&operations);
+
+        assert_eq!(simplified.len(), 3);
+        assert_eq!(simplified[&0], tensor(1, TensorStatus::ReadOnly));
+        assert_eq!(simplified[&3], tensor(3, TensorStatus::ReadOnly));
+        assert_eq!(simplified[&5], tensor(5, TensorStatus::ReadOnly));
+
+        // The negated tensor is consumed by the first negation.
+        let mut consumed = scalar(5, -1.0, 6);
+        consumed.lhs.status = TensorStatus::ReadWrite;
+        let operations = [
+            float(NumericOperationIr::MulScalar(consumed)),
+            float(NumericOperationIr::MulScalar(scalar(6, -1.0, 7))),
+        ];
+        assert!(identities(&operations).is_empty());
+    }
+}
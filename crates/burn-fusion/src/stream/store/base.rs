@@ -1,19 +1,162 @@
-use std::sync::Arc;
+use core::cell::Cell;
+use std::{sync::Arc, time::Duration};
 
-use crate::search::BlockOptimization;
+use crate::{FusionError, search::BlockOptimization, stream::StreamId};
 
-use super::{ExecutionPlanIndex, InsertQuery, SearchQuery};
-use burn_ir::OperationIr;
+use super::{
+    CustomTriggerRef, ExecutionPlanIndex, IndexCursor, IndexStep, InsertQuery, PlanTrigger,
+    RemoveQuery, SearchQuery,
+};
+use burn_ir::{OperationIr, OperationMetadata};
+use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 
 /// The store that contains all explorations done on a device.
+///
+/// # Notes
+///
+/// Every new sequence of operations creates a new plan, so workloads with highly dynamic shapes
+/// can create plans indefinitely. When a [capacity](ExecutionPlanStore::set_capacity) is set, the
+/// least recently executed plans are evicted once the store is full. Plan ids are never reused,
+/// so an evicted plan is simply explored again under a new id if its operations come back.
+///
+/// [Pinned](ExecutionPlanStore::pin) plans are never evicted, which keeps latency-critical paths
+/// from being explored again.
 #[derive(Default)]
 pub(crate) struct ExecutionPlanStore<O> {
-    plans: Vec<ExecutionPlan<O>>,
+    plans: HashMap<ExecutionPlanId, StoredPlan<O>>,
     index: ExecutionPlanIndex,
+    capacity: Option<usize>,
+    /// Logical clock incremented on every execution, used to find the least recently used plan.
+    clock: u64,
+    stats: ExecutionPlanStoreStats,
+    cache: CacheCounters,
+    /// Whether every executed plan is pinned.
+    pin_executed: bool,
+}
+
+/// Counters of the lookups of plans in the store.
+#[derive(Default, Clone)]
+pub(crate) struct CacheCounters {
+    /// The number of searches in the index, counted from shared references.
+    pub(crate) num_queries: Cell<u64>,
+    /// The number of searches returning at least one plan.
+    pub(crate) num_query_matches: Cell<u64>,
+    /// The number of executions of a plan retrieved from the store without exploration.
+    pub(crate) num_hits: u64,
+    /// The number of explorations.
+    pub(crate) num_misses: u64,
+}
+
+struct StoredPlan<O> {
+    plan: ExecutionPlan<O>,
+    /// The number of times each trigger fired.
+    fired: Vec<u64>,
+    last_used: u64,
+    pinned: bool,
+    timings: PlanTimings,
+    /// The streams that executed the plan.
+    streams: HashSet<StreamId>,
+    /// The metadata of the operations the plan was first executed with.
+    metadata: Vec<Option<Arc<OperationMetadata>>>,
+}
+
+/// Statistics collected by the store of execution plans of a device.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionPlanStoreStats {
+    /// The number of plans currently stored.
+    pub num_plans: usize,
+    /// The number of plans created since the start of the device.
+    pub num_created: usize,
+    /// The number of plans evicted because the store was full.
+    pub num_evicted: u64,
+    /// The number of plans added with the same operations as a stored plan, which were merged
+    /// into it.
+    pub num_deduplicated: u64,
+    /// The number of plans removed because all the streams that executed them are finished.
+    pub num_collected: u64,
+    /// The number of plans currently pinned, which are never evicted.
+    pub num_pinned: usize,
+    /// The maximum number of plans kept by the store, unbounded when `None`.
+    pub capacity: Option<usize>,
+}
+
+/// The measured execution times of an execution plan.
+///
+/// By default, times are measured on the host around the execution of the plan, which includes
+/// the launch of its kernels but not necessarily their completion on asynchronous devices. With
+/// [device timing](crate::PlanTiming::Device), they measure the completion of the plan on the
+/// device instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlanTimings {
+    /// The number of measured executions.
+    pub num_samples: u64,
+    /// The sum of the measured execution times.
+    pub total: Duration,
+    /// The fastest execution.
+    pub min: Duration,
+    /// The slowest execution.
+    pub max: Duration,
+    /// The most recent execution.
+    pub last: Duration,
+    /// How the most recent execution was measured, `None` before the first one.
+    pub source: Option<TimingSource>,
+}
+
+/// How the execution time of a plan was measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingSource {
+    /// A host timer around the launch of the plan, not waiting for the device.
+    Host,
+    /// A host timer around the plan, waiting for the device to complete it.
+    DeviceSync,
+    /// Timestamps queried from the device around the plan.
+    DeviceTimestamps,
+}
+
+impl PlanTimings {
+    /// The mean execution time, zero when no execution was measured.
+    pub fn mean(&self) -> Duration {
+        match self.num_samples {
+            0 => Duration::ZERO,
+            num_samples => self.total / num_samples as u32,
+        }
+    }
+
+    pub(crate) fn record(&mut self, duration: Duration, source: TimingSource) {
+        self.min = match self.num_samples {
+            0 => duration,
+            _ => self.min.min(duration),
+        };
+        self.max = self.max.max(duration);
+        self.last = duration;
+        self.source = Some(source);
+        self.total += duration;
+        self.num_samples += 1;
+    }
 }
 
 /// How a list of operations should be executed.
+///
+/// # Ordering
+///
+/// Each ordering holds the positions of the operations to execute, relative to the start of the
+/// plan, in the order they are executed. A [composed](ExecutionStrategy::Composed) strategy
+/// executes its items one after the other, so the [execution order](ExecutionStrategy::ordering)
+/// of a plan is the concatenation of the orderings of its items.
+///
+/// The following guarantees hold for every stored strategy:
+///
+/// - The execution order is a permutation of the positions of the operations drained by the plan,
+///   so each operation is executed exactly once.
+/// - Operations sharing a tensor that is written or freed by one of them are executed in their
+///   registration order. Only independent operations, or operations only reading the same
+///   tensors, can be reordered.
+/// - The strategy is computed once, when the plan is explored, and replayed as is on every cache
+///   hit. The same operations are therefore always executed in the same order, no matter if the
+///   plan was just explored or retrieved from the store.
+///
+/// Those guarantees are checked on every execution when the `ordering-checks` feature is enabled.
 #[derive(PartialEq, Debug, Clone)]
 pub(crate) enum ExecutionStrategy<O> {
     /// An optimization was found, and therefore should be executed.
@@ -25,15 +168,18 @@ pub(crate) enum ExecutionStrategy<O> {
 }
 
 /// The trigger that indicates when to stop exploring.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum ExecutionTrigger {
     OnOperations(Vec<OperationIr>),
     OnSync,
     Always,
+    OnQueueLength(usize),
+    #[serde(skip)]
+    Custom(CustomTriggerRef),
 }
 
 /// The unique identifier for an exploration that was executed.
-pub(crate) type ExecutionPlanId = usize;
+pub type ExecutionPlanId = usize;
 
 /// The outcome of an exploration that can be stored.
 #[derive(Debug)]
@@ -46,49 +192,451 @@ pub(crate) struct ExecutionPlan<O> {
     pub(crate) optimization: BlockOptimization<O>,
 }
 
+impl<O> ExecutionStrategy<O> {
+    /// The positions of the operations in the order they are executed.
+    #[cfg(any(test, feature = "ordering-checks"))]
+    pub fn ordering(&self) -> Vec<usize> {
+        match self {
+            Self::Optimization { ordering, .. } | Self::Operations { ordering } => {
+                ordering.as_ref().clone()
+            }
+            Self::Composed(items) => items.iter().flat_map(|item| item.ordering()).collect(),
+        }
+    }
+}
+
 impl<O> ExecutionPlanStore<O> {
     pub fn new() -> Self {
         Self {
-            plans: Vec::new(),
+            plans: HashMap::new(),
             index: ExecutionPlanIndex::default(),
+            capacity: None,
+            clock: 0,
+            stats: ExecutionPlanStoreStats::default(),
+            cache: CacheCounters::default(),
+            pin_executed: false,
         }
     }
 
+    /// All plans in the store, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (ExecutionPlanId, &ExecutionPlan<O>)> {
+        self.plans.iter().map(|(id, stored)| (*id, &stored.plan))
+    }
+
+    /// The number of plans in the store.
+    pub fn len(&self) -> usize {
+        self.plans.len()
+    }
+
+    /// The number of plans created so far, which is also the id of the next plan.
+    pub fn num_created(&self) -> usize {
+        self.stats.num_created
+    }
+
     pub fn find(&self, query: SearchQuery<'_>) -> Vec<ExecutionPlanId> {
-        self.index.find(query)
+        let found = self.index.find(query);
+        self.count_query(!found.is_empty());
+
+        found
+    }
+
+    /// Start matching the plans with the first operation of a sequence, see
+    /// [ExecutionPlanIndex::start].
+    pub fn start_search(&self, operation: &OperationIr) -> Option<IndexStep> {
+        let step = self.index.start(operation);
+        self.count_query(step.is_some());
+
+        step
+    }
+
+    /// Continue matching the plans with the next operation of a sequence, see
+    /// [ExecutionPlanIndex::advance].
+    pub fn continue_search(
+        &self,
+        cursor: IndexCursor,
+        operation: &OperationIr,
+    ) -> Option<IndexStep> {
+        self.index.advance(cursor, operation)
     }
 
-    pub fn add(&mut self, exploration: ExecutionPlan<O>) -> ExecutionPlanId {
+    /// The plans that can still be matched from the cursor with more operations.
+    pub fn pending_plans(&self, cursor: IndexCursor) -> Vec<ExecutionPlanId> {
+        self.index.pending(cursor)
+    }
+
+    fn count_query(&self, matched: bool) {
+        let cache = &self.cache;
+        cache.num_queries.set(cache.num_queries.get() + 1);
+        if matched {
+            cache
+                .num_query_matches
+                .set(cache.num_query_matches.get() + 1);
+        }
+    }
+
+    /// Add a plan to the store, returning its id.
+    ///
+    /// Operations are relative to the start of their stream segment, with tensor ids, shapes and
+    /// scalars relabeled in order of first use, so the same subgraph registered by different
+    /// streams has the same operations. When a plan with the same operations is already stored,
+    /// the triggers of the new plan are added to it and its id is returned, so both share the
+    /// same optimization.
+    pub fn add(&mut self, exploration: ExecutionPlan<O>) -> Result<ExecutionPlanId, FusionError> {
         if exploration.operations.is_empty() {
-            panic!("Can't add an empty optimization.");
+            return Err(FusionError::PlanExecution {
+                plan: None,
+                reason: "Can't add an empty optimization".into(),
+            });
         }
 
-        let id = self.plans.len();
+        let existing = self
+            .index
+            .find(SearchQuery::PlansWithOperations(&exploration.operations));
+        if let Some(id) = existing.first().copied() {
+            for trigger in exploration.triggers {
+                self.add_trigger(id, trigger);
+            }
+            self.stats.num_deduplicated += 1;
+
+            return Ok(id);
+        }
 
+        let id = self.stats.num_created;
         self.index.insert(InsertQuery::NewPlan {
             operations: &exploration.operations,
             id,
         });
 
-        self.plans.push(exploration);
+        self.clock += 1;
+        self.plans.insert(
+            id,
+            StoredPlan {
+                fired: vec![0; exploration.triggers.len()],
+                plan: exploration,
+                last_used: self.clock,
+                pinned: self.pin_executed,
+                timings: PlanTimings::default(),
+                streams: HashSet::new(),
+                metadata: Vec::new(),
+            },
+        );
+        self.stats.num_created += 1;
+
+        Ok(id)
+    }
 
-        id
+    /// The plan with the given id, `None` when it isn't in the store.
+    pub fn get(&self, id: ExecutionPlanId) -> Option<&ExecutionPlan<O>> {
+        self.plans.get(&id).map(|stored| &stored.plan)
     }
 
-    pub fn get_mut_unchecked(&mut self, id: ExecutionPlanId) -> &mut ExecutionPlan<O> {
-        &mut self.plans[id]
+    /// The plan with the given id, `None` when it isn't in the store.
+    pub fn get_mut(&mut self, id: ExecutionPlanId) -> Option<&mut ExecutionPlan<O>> {
+        self.plans.get_mut(&id).map(|stored| &mut stored.plan)
     }
 
+    /// The plan with the given id, which must be in the store.
+    ///
+    /// Only use it with ids just returned by the store, e.g. by [find](Self::find).
     pub fn get_unchecked(&self, id: ExecutionPlanId) -> &ExecutionPlan<O> {
-        &self.plans[id]
+        &self.stored(id).plan
+    }
+
+    /// Add a new end condition for an optimization, returning its index.
+    pub fn add_trigger(&mut self, id: ExecutionPlanId, trigger: ExecutionTrigger) -> usize {
+        let stored = self.stored_mut(id);
+        let criteria = &mut stored.plan.triggers;
+
+        match criteria.iter().position(|item| item == &trigger) {
+            Some(index) => index,
+            None => {
+                criteria.push(trigger);
+                stored.fired.push(0);
+                criteria.len() - 1
+            }
+        }
+    }
+
+    /// Add a [user trigger](PlanTrigger) to the plan, returning its index.
+    pub fn add_plan_trigger(
+        &mut self,
+        id: ExecutionPlanId,
+        trigger: PlanTrigger,
+    ) -> Result<usize, FusionError> {
+        self.try_stored_mut(id)?;
+
+        Ok(self.add_trigger(id, trigger.into()))
+    }
+
+    /// Record that the trigger at the given index caused the execution of the plan.
+    pub fn trigger_fired(&mut self, id: ExecutionPlanId, trigger: usize) {
+        self.clock += 1;
+        let clock = self.clock;
+        let pin_executed = self.pin_executed;
+        let stored = self.stored_mut(id);
+
+        stored.fired[trigger] += 1;
+        stored.last_used = clock;
+        stored.pinned |= pin_executed;
     }
 
-    /// Add a new end condition for an optimization.
-    pub fn add_trigger(&mut self, id: ExecutionPlanId, trigger: ExecutionTrigger) {
-        let criteria = &mut self.plans[id].triggers;
+    /// Pin the plan so that it is never evicted.
+    ///
+    /// Fails when the plan isn't in the store, or when pinning it would leave more pinned plans
+    /// than the capacity of the store.
+    pub fn pin(&mut self, id: ExecutionPlanId) -> Result<(), FusionError> {
+        let num_pinned = self.pinned().count();
+        let capacity = self.capacity;
+        let stored = self.try_stored_mut(id)?;
+
+        if stored.pinned {
+            return Ok(());
+        }
+
+        match capacity {
+            Some(capacity) if num_pinned >= capacity => {
+                return Err(FusionError::StoreCapacityExceeded {
+                    capacity,
+                    num_pinned: num_pinned + 1,
+                });
+            }
+            _ => {}
+        }
+
+        stored.pinned = true;
+        Ok(())
+    }
 
-        if !criteria.contains(&trigger) {
-            criteria.push(trigger);
+    /// Unpin the plan so that it can be evicted again, returning whether it was pinned.
+    pub fn unpin(&mut self, id: ExecutionPlanId) -> bool {
+        match self.plans.get_mut(&id) {
+            Some(stored) => core::mem::replace(&mut stored.pinned, false),
+            None => false,
         }
     }
+
+    /// The ids of the pinned plans, in no particular order.
+    pub fn pinned(&self) -> impl Iterator<Item = ExecutionPlanId> + '_ {
+        self.plans
+            .iter()
+            .filter(|(_, stored)| stored.pinned)
+            .map(|(id, _)| *id)
+    }
+
+    /// Whether the plan is pinned.
+    pub fn is_pinned(&self, id: ExecutionPlanId) -> bool {
+        self.plans.get(&id).is_some_and(|stored| stored.pinned)
+    }
+
+    /// Pin every plan executed from now on, either created or retrieved from the store, until
+    /// disabled. Returns the previous state so that regions can be nested.
+    pub fn set_pin_executed(&mut self, enabled: bool) -> bool {
+        core::mem::replace(&mut self.pin_executed, enabled)
+    }
+
+    /// Record an execution of a plan found in the store without exploration.
+    pub fn cache_hit(&mut self) {
+        self.cache.num_hits += 1;
+    }
+
+    /// Record an exploration, no matter if it ends up creating a new plan.
+    pub fn cache_miss(&mut self) {
+        self.cache.num_misses += 1;
+    }
+
+    /// The lookup counters of the store.
+    pub fn cache(&self) -> &CacheCounters {
+        &self.cache
+    }
+
+    /// Record the time taken by an execution of the plan, ignored when it was evicted.
+    pub fn record_timing(&mut self, id: ExecutionPlanId, duration: Duration, source: TimingSource) {
+        if let Some(stored) = self.plans.get_mut(&id) {
+            stored.timings.record(duration, source);
+        }
+    }
+
+    /// Record that the plan was executed by the given stream, ignored when it was evicted.
+    pub fn record_stream(&mut self, id: ExecutionPlanId, stream: StreamId) {
+        if let Some(stored) = self.plans.get_mut(&id) {
+            stored.streams.insert(stream);
+        }
+    }
+
+    /// Record the metadata of the operations executed by the plan, ignored when it was evicted or
+    /// when the metadata of a previous execution was already recorded.
+    pub fn record_metadata(
+        &mut self,
+        id: ExecutionPlanId,
+        metadata: &[Option<Arc<OperationMetadata>>],
+    ) {
+        if let Some(stored) = self.plans.get_mut(&id)
+            && stored.metadata.is_empty()
+        {
+            stored.metadata = metadata.to_vec();
+        }
+    }
+
+    /// The metadata of the operations the plan was first executed with, empty before its first
+    /// execution.
+    pub fn metadata(&self, id: ExecutionPlanId) -> &[Option<Arc<OperationMetadata>>] {
+        &self.stored(id).metadata
+    }
+
+    /// The measured execution times of the plan.
+    pub fn timings(&self, id: ExecutionPlanId) -> PlanTimings {
+        self.stored(id).timings
+    }
+
+    /// The number of times each trigger of the plan fired.
+    pub fn fired(&self, id: ExecutionPlanId) -> &[u64] {
+        &self.stored(id).fired
+    }
+
+    /// The statistics of the store.
+    pub fn stats(&self) -> ExecutionPlanStoreStats {
+        ExecutionPlanStoreStats {
+            num_plans: self.len(),
+            num_pinned: self.pinned().count(),
+            capacity: self.capacity,
+            ..self.stats
+        }
+    }
+
+    /// Update the maximum number of plans kept by the store.
+    ///
+    /// Plans aren't evicted right away, but on the next call to [evict](Self::evict). The capacity
+    /// is left unchanged when it can't fit the pinned plans.
+    pub fn set_capacity(&mut self, capacity: Option<usize>) -> Result<(), FusionError> {
+        if let Some(capacity) = capacity {
+            let num_pinned = self.pinned().count();
+
+            if num_pinned > capacity {
+                return Err(FusionError::StoreCapacityExceeded {
+                    capacity,
+                    num_pinned,
+                });
+            }
+        }
+
+        self.capacity = capacity;
+        Ok(())
+    }
+
+    /// Whether the store holds more plans than its capacity.
+    pub fn is_over_capacity(&self) -> bool {
+        match self.capacity {
+            Some(capacity) => self.len() > capacity,
+            None => false,
+        }
+    }
+
+    /// Evict the least recently used plans until the store fits its capacity, returning the ids
+    /// of the evicted plans.
+    ///
+    /// The `referenced` plans are never evicted, since a stream might be about to execute them,
+    /// and neither are the pinned plans.
+    pub fn evict(&mut self, referenced: &HashSet<ExecutionPlanId>) -> Vec<ExecutionPlanId> {
+        match self.capacity {
+            Some(capacity) => self.evict_down_to(capacity, referenced),
+            None => Vec::new(),
+        }
+    }
+
+    /// Evict the least recently used plans until the store holds at most `num_plans` plans,
+    /// returning the ids of the evicted plans.
+    ///
+    /// Like with [eviction](Self::evict), the `referenced` and pinned plans are kept, so more
+    /// plans might remain.
+    pub fn evict_down_to(
+        &mut self,
+        num_plans: usize,
+        referenced: &HashSet<ExecutionPlanId>,
+    ) -> Vec<ExecutionPlanId> {
+        if self.plans.len() <= num_plans {
+            return Vec::new();
+        }
+
+        let mut evictables = self
+            .plans
+            .iter()
+            .filter(|(id, stored)| !stored.pinned && !referenced.contains(*id))
+            .map(|(id, stored)| (stored.last_used, *id))
+            .collect::<Vec<_>>();
+        evictables.sort_unstable();
+
+        let num_evicted = (self.plans.len() - num_plans).min(evictables.len());
+        let evicted = evictables[0..num_evicted]
+            .iter()
+            .map(|(_, id)| *id)
+            .collect::<Vec<_>>();
+
+        for id in evicted.iter() {
+            self.remove(*id);
+        }
+
+        self.stats.num_evicted += evicted.len() as u64;
+
+        evicted
+    }
+
+    /// Forget the `finished` streams, removing the plans only executed by them, and returning the
+    /// ids of the removed plans.
+    ///
+    /// Like with [eviction](Self::evict), the `referenced` and pinned plans are kept, and so are
+    /// the plans never executed, e.g. imported ones.
+    pub fn collect_stream_plans(
+        &mut self,
+        finished: &HashSet<StreamId>,
+        referenced: &HashSet<ExecutionPlanId>,
+    ) -> Vec<ExecutionPlanId> {
+        let mut collected = Vec::new();
+
+        for (id, stored) in self.plans.iter_mut() {
+            if stored.streams.is_empty() {
+                continue;
+            }
+
+            stored.streams.retain(|stream| !finished.contains(stream));
+
+            if stored.streams.is_empty() && !stored.pinned && !referenced.contains(id) {
+                collected.push(*id);
+            }
+        }
+        collected.sort_unstable();
+
+        for id in collected.iter() {
+            self.remove(*id);
+        }
+
+        self.stats.num_collected += collected.len() as u64;
+
+        collected
+    }
+
+    fn remove(&mut self, id: ExecutionPlanId) {
+        if let Some(stored) = self.plans.remove(&id) {
+            self.index.remove(RemoveQuery::Plan {
+                operations: &stored.plan.operations,
+                id,
+            });
+        }
+    }
+
+    fn stored(&self, id: ExecutionPlanId) -> &StoredPlan<O> {
+        self.plans
+            .get(&id)
+            .unwrap_or_else(|| panic!("{}", FusionError::PlanNotFound { plan: id }))
+    }
+
+    fn stored_mut(&mut self, id: ExecutionPlanId) -> &mut StoredPlan<O> {
+        self.try_stored_mut(id)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn try_stored_mut(&mut self, id: ExecutionPlanId) -> Result<&mut StoredPlan<O>, FusionError> {
+        self.plans
+            .get_mut(&id)
+            .ok_or(FusionError::PlanNotFound { plan: id })
+    }
 }
@@ -1,16 +1,47 @@
 use crate::stream::store::ExecutionPlanId;
 use burn_ir::OperationIr;
-use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, hash_map::DefaultHasher},
     hash::{Hash, Hasher},
+    sync::RwLock,
 };
 
+/// The default number of shards of the [index](ExecutionPlanIndex).
+const DEFAULT_NUM_SHARDS: usize = 16;
+
+/// The position of the root of the trie of each shard.
+const ROOT: usize = 0;
+
 /// Index used to search optimizations.
-#[derive(Default, Serialize, Deserialize, Clone)]
+///
+/// Plans are stored in a prefix trie over their operations, so matching a sequence of operations
+/// against the stored plans costs one lookup per operation, no matter how many plans share the
+/// same prefix. Searches [start](ExecutionPlanIndex::start) with the first operation and
+/// [advance](ExecutionPlanIndex::advance) one operation at a time from the returned
+/// [cursor](IndexCursor).
+///
+/// The index is split into shards, selected by the hash of the first operation of each plan.
+/// Every shard is behind its own lock, so searches only contend with insertions and removals of
+/// plans starting with operations of the same shard, and the index can be searched from many
+/// threads at once.
 pub struct ExecutionPlanIndex {
-    /// We can't use `HashMap<OperationIr, Vec<ExecutionPlanId>>` since `OperationIr`
-    /// doesn't implement [`Eq`](core::cmp::Eq).
+    shards: Vec<RwLock<IndexShard>>,
+}
+
+/// The trie of the plans starting with the operations of a shard.
+///
+/// Nodes are kept in an arena and refer to each other by position. Removed nodes are recycled,
+/// and their generation is bumped so that outdated [cursors](IndexCursor) are detected.
+struct IndexShard {
+    nodes: Vec<TrieNode>,
+    free: Vec<usize>,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    generation: u64,
+    /// We can't use `HashMap<OperationIr, usize>` since `OperationIr` doesn't implement
+    /// [`Eq`](core::cmp::Eq).
     ///
     /// `OperationIr` can't implement `Eq` since float types don't implement it.
     ///
@@ -18,15 +49,36 @@ pub struct ExecutionPlanIndex {
     /// This is OK because we use `relative` operations where any scalar values are set to zeros,
     /// see [`RelativeStreamConverter`](crate::stream::RelativeStreamConverter).
     ///
-    /// Map from the hash of the `OperationIr` to a list of `(OperationIr, index)` pairs,
-    /// where `index` is the index of all the execution plans that start with the `OperationIr`
-    /// in the `starters` list.
-    mapping: HashMap<u64, Vec<(OperationIr, usize)>>,
-    starters: Vec<Vec<ExecutionPlanId>>,
+    /// Map from the hash of the `OperationIr` to a list of `(OperationIr, node)` pairs, where
+    /// `node` is the position of the child reached with the `OperationIr`.
+    children: HashMap<u64, Vec<(OperationIr, usize)>>,
+    /// The plans whose operations end at this node.
+    plans: Vec<ExecutionPlanId>,
+    /// The number of plans ending at this node or below it.
+    num_plans: usize,
+}
+
+/// A position in the [index](ExecutionPlanIndex), reached by matching a sequence of operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexCursor {
+    shard: usize,
+    node: usize,
+    generation: u64,
+}
+
+/// The plans matching a sequence of operations.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IndexStep {
+    /// The position reached by the sequence of operations.
+    pub cursor: IndexCursor,
+    /// The plans with exactly the sequence of operations.
+    pub ended: Vec<ExecutionPlanId>,
+    /// The number of plans starting with the sequence of operations, followed by more operations.
+    pub num_pending: usize,
 }
 
 pub enum SearchQuery<'a> {
-    PlansStartingWith(&'a OperationIr),
+    PlansWithOperations(&'a [OperationIr]),
 }
 
 pub enum InsertQuery<'a> {
@@ -36,81 +88,142 @@ pub enum InsertQuery<'a> {
     },
 }
 
+pub enum RemoveQuery<'a> {
+    Plan {
+        operations: &'a [OperationIr],
+        id: ExecutionPlanId,
+    },
+}
+
+impl Default for ExecutionPlanIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_NUM_SHARDS)
+    }
+}
+
 impl ExecutionPlanIndex {
-    /// Search optimizations with the given [query](SearchQuery).
-    pub fn find(&self, query: SearchQuery<'_>) -> Vec<ExecutionPlanId> {
-        match query {
-            SearchQuery::PlansStartingWith(ops) => self.find_starting_with(ops),
+    /// Create a new index with the given number of shards.
+    pub fn new(num_shards: usize) -> Self {
+        Self {
+            shards: (0..num_shards.max(1))
+                .map(|_| RwLock::new(IndexShard::default()))
+                .collect(),
         }
     }
 
-    /// Register a new optimization with the given [query](InsertQuery).
-    pub fn insert(&mut self, query: InsertQuery<'_>) {
+    /// Search optimizations with the given [query](SearchQuery).
+    pub fn find(&self, query: SearchQuery<'_>) -> Vec<ExecutionPlanId> {
         match query {
-            InsertQuery::NewPlan { operations, id } => {
-                if let Some(operation) = operations.first() {
-                    self.insert_new_operation(operation, id)
+            SearchQuery::PlansWithOperations(operations) => {
+                let mut operations = operations.iter();
+                let mut step = operations.next().and_then(|op| self.start(op));
+
+                for operation in operations {
+                    step = step.and_then(|step| self.advance(step.cursor, operation));
                 }
+
+                step.map(|step| step.ended).unwrap_or_default()
             }
         }
     }
 
-    /// Find execution plans starting with the `OperationIr`
-    fn find_starting_with(&self, operation: &OperationIr) -> Vec<ExecutionPlanId> {
+    /// Start matching the plans with the first operation of a sequence.
+    ///
+    /// Returns `None` when no plan starts with the operation.
+    pub fn start(&self, operation: &OperationIr) -> Option<IndexStep> {
         let key = self.operation_key(operation);
-        let values = match self.mapping.get(&key) {
-            Some(val) => val,
-            None => return Vec::new(),
-        };
+        let shard_index = self.shard_index(key);
+        let shard = self.read(shard_index);
+        let node = shard.child(ROOT, key, operation)?;
 
-        if values.is_empty() {
-            return Vec::new();
+        Some(shard.step(shard_index, node))
+    }
+
+    /// Continue matching the plans from the cursor with the next operation of the sequence.
+    ///
+    /// Returns `None` when no plan continues with the operation, or when the plans of the cursor
+    /// were all removed since it was returned.
+    pub fn advance(&self, cursor: IndexCursor, operation: &OperationIr) -> Option<IndexStep> {
+        let key = self.operation_key(operation);
+        let shard = self.read(cursor.shard);
+
+        if !shard.is_valid(cursor) {
+            return None;
         }
 
-        let (_, index) = match values.iter().find(|value| &value.0 == operation) {
-            Some(val) => val,
-            None => return Vec::new(),
-        };
+        let node = shard.child(cursor.node, key, operation)?;
+        Some(shard.step(cursor.shard, node))
+    }
 
-        match self.starters.get(*index) {
-            Some(value) => value.clone(),
-            None => Vec::new(),
+    /// The plans starting with the sequence of operations of the cursor, followed by more
+    /// operations.
+    pub fn pending(&self, cursor: IndexCursor) -> Vec<ExecutionPlanId> {
+        let shard = self.read(cursor.shard);
+
+        if !shard.is_valid(cursor) {
+            return Vec::new();
         }
+
+        let ended = &shard.nodes[cursor.node].plans;
+        let mut plans = shard.plans_below(cursor.node);
+        plans.retain(|id| !ended.contains(id));
+        plans
     }
 
-    /// Update the index for an execution plan starting with operation `ops`
-    fn insert_new_operation(&mut self, ops: &OperationIr, new_id: ExecutionPlanId) {
-        let key = self.operation_key(ops);
-        let values = match self.mapping.get_mut(&key) {
-            Some(val) => val,
-            None => {
-                // New starter ops.
-                let index = self.starters.len();
-                self.starters.push(vec![new_id]);
-                self.mapping.insert(key, vec![(ops.clone(), index)]);
+    /// Register a new optimization with the given [query](InsertQuery).
+    pub fn insert(&self, query: InsertQuery<'_>) {
+        match query {
+            InsertQuery::NewPlan { operations, id } => {
+                if operations.is_empty() {
+                    return;
+                }
 
-                return;
+                let keys = self.operation_keys(operations);
+                self.write(self.shard_index(keys[0]))
+                    .insert(operations, &keys, id);
             }
-        };
-        let (_, index) = match values.iter_mut().find(|value| &value.0 == ops) {
-            Some(val) => val,
-            None => {
-                // New with hash collision.
-                let index = self.starters.len();
-                self.starters.push(vec![new_id]);
-                values.push((ops.clone(), index));
-                return;
+        }
+    }
+
+    /// Unregister an optimization with the given [query](RemoveQuery).
+    pub fn remove(&self, query: RemoveQuery<'_>) {
+        match query {
+            RemoveQuery::Plan { operations, id } => {
+                if operations.is_empty() {
+                    return;
+                }
+
+                let keys = self.operation_keys(operations);
+                self.write(self.shard_index(keys[0]))
+                    .remove(operations, &keys, id);
             }
-        };
+        }
+    }
 
-        // New optimization for an existing starter.
-        self.starters
-            .get_mut(*index)
-            .expect("Should exist")
-            .push(new_id);
+    fn read(&self, shard: usize) -> std::sync::RwLockReadGuard<'_, IndexShard> {
+        self.shards[shard]
+            .read()
+            .expect("Index shard lock poisoned")
     }
 
-    // Hash the value of the first operation in a list.
+    fn write(&self, shard: usize) -> std::sync::RwLockWriteGuard<'_, IndexShard> {
+        self.shards[shard]
+            .write()
+            .expect("Index shard lock poisoned")
+    }
+
+    fn shard_index(&self, key: u64) -> usize {
+        (key % self.shards.len() as u64) as usize
+    }
+
+    fn operation_keys(&self, operations: &[OperationIr]) -> Vec<u64> {
+        operations
+            .iter()
+            .map(|operation| self.operation_key(operation))
+            .collect()
+    }
+
+    // Hash the value of an operation.
     fn operation_key(&self, ops: &OperationIr) -> u64 {
         let mut hasher = DefaultHasher::new();
         ops.hash(&mut hasher);
@@ -118,6 +231,156 @@ impl ExecutionPlanIndex {
     }
 }
 
+impl Default for IndexShard {
+    fn default() -> Self {
+        Self {
+            nodes: vec![TrieNode::default()],
+            free: Vec::new(),
+        }
+    }
+}
+
+impl IndexShard {
+    fn child(&self, node: usize, key: u64, operation: &OperationIr) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .get(&key)?
+            .iter()
+            .find(|(child_operation, _)| child_operation == operation)
+            .map(|(_, child)| *child)
+    }
+
+    fn step(&self, shard: usize, node: usize) -> IndexStep {
+        let trie_node = &self.nodes[node];
+
+        IndexStep {
+            cursor: IndexCursor {
+                shard,
+                node,
+                generation: trie_node.generation,
+            },
+            ended: trie_node.plans.clone(),
+            num_pending: trie_node.num_plans - trie_node.plans.len(),
+        }
+    }
+
+    fn is_valid(&self, cursor: IndexCursor) -> bool {
+        self.nodes
+            .get(cursor.node)
+            .is_some_and(|node| node.generation == cursor.generation && node.num_plans > 0)
+    }
+
+    /// The plans ending at the node or below it, sorted.
+    fn plans_below(&self, node: usize) -> Vec<ExecutionPlanId> {
+        let mut plans = Vec::new();
+        let mut nodes = vec![node];
+
+        while let Some(node) = nodes.pop() {
+            let node = &self.nodes[node];
+            plans.extend_from_slice(&node.plans);
+            nodes.extend(
+                node.children
+                    .values()
+                    .flat_map(|children| children.iter().map(|(_, child)| *child)),
+            );
+        }
+
+        plans.sort_unstable();
+        plans
+    }
+
+    fn insert(&mut self, operations: &[OperationIr], keys: &[u64], id: ExecutionPlanId) {
+        let mut node = ROOT;
+        self.nodes[ROOT].num_plans += 1;
+
+        for (operation, key) in operations.iter().zip(keys) {
+            node = match self.child(node, *key, operation) {
+                Some(child) => child,
+                None => {
+                    let child = self.new_node();
+                    self.nodes[node]
+                        .children
+                        .entry(*key)
+                        .or_default()
+                        .push((operation.clone(), child));
+                    child
+                }
+            };
+            self.nodes[node].num_plans += 1;
+        }
+
+        self.nodes[node].plans.push(id);
+    }
+
+    fn remove(&mut self, operations: &[OperationIr], keys: &[u64], id: ExecutionPlanId) {
+        // The `(parent, key, child)` edges from the root to the last operation of the plan.
+        let mut path = Vec::with_capacity(operations.len());
+        let mut node = ROOT;
+
+        for (operation, key) in operations.iter().zip(keys) {
+            let child = match self.child(node, *key, operation) {
+                Some(child) => child,
+                None => return,
+            };
+            path.push((node, *key, child));
+            node = child;
+        }
+
+        let plans = &mut self.nodes[node].plans;
+        match plans.iter().position(|plan| *plan == id) {
+            Some(position) => plans.remove(position),
+            None => return,
+        };
+
+        self.nodes[ROOT].num_plans -= 1;
+        for (_, _, child) in path.iter() {
+            self.nodes[*child].num_plans -= 1;
+        }
+
+        // Nodes without plans are pruned, starting from the first one on the path since all the
+        // following nodes are empty as well.
+        let Some(first_empty) = path
+            .iter()
+            .position(|(_, _, child)| self.nodes[*child].num_plans == 0)
+        else {
+            return;
+        };
+
+        let (parent, key, child) = path[first_empty];
+        let siblings = self.nodes[parent]
+            .children
+            .get_mut(&key)
+            .expect("The edge was just traversed");
+        siblings.retain(|(_, sibling)| *sibling != child);
+        if siblings.is_empty() {
+            self.nodes[parent].children.remove(&key);
+        }
+
+        for (_, _, child) in path[first_empty..].iter() {
+            self.free_node(*child);
+        }
+    }
+
+    fn new_node(&mut self) -> usize {
+        match self.free.pop() {
+            Some(node) => node,
+            None => {
+                self.nodes.push(TrieNode::default());
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn free_node(&mut self, node: usize) {
+        let trie_node = &mut self.nodes[node];
+        trie_node.generation += 1;
+        trie_node.children.clear();
+        trie_node.plans.clear();
+        trie_node.num_plans = 0;
+        self.free.push(node);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use burn_ir::{BinaryOpIr, NumericOperationIr, ScalarOpIr, TensorId, TensorIr, TensorStatus};
@@ -127,7 +390,7 @@ mod tests {
 
     #[test]
     fn should_find_optimization_id_based_on_tensor_ops() {
-        let mut index = ExecutionPlanIndex::default();
+        let index = ExecutionPlanIndex::default();
         let stream_1 = [ops_1()];
         let optimization_id_1 = 0;
 
@@ -136,14 +399,14 @@ mod tests {
             id: optimization_id_1,
         });
 
-        let found = index.find(SearchQuery::PlansStartingWith(&stream_1[0]));
+        let found = starting_with(&index, &stream_1[0]);
 
         assert_eq!(found, vec![optimization_id_1]);
     }
 
     #[test]
     fn should_support_multiple_optimization_ids_with_same_starting_ops() {
-        let mut index = ExecutionPlanIndex::default();
+        let index = ExecutionPlanIndex::default();
         let stream_1 = [ops_1(), ops_2(), ops_1()];
         let stream_2 = [ops_1(), ops_1(), ops_2()];
         let optimization_id_1 = 0;
@@ -158,14 +421,40 @@ mod tests {
             id: optimization_id_2,
         });
 
-        let found = index.find(SearchQuery::PlansStartingWith(&stream_1[0]));
+        let found = starting_with(&index, &stream_1[0]);
 
         assert_eq!(found, vec![optimization_id_1, optimization_id_2]);
     }
 
+    #[test]
+    fn should_not_find_removed_optimization_ids() {
+        let index = ExecutionPlanIndex::default();
+        let stream_1 = [ops_1(), ops_2(), ops_1()];
+        let stream_2 = [ops_1(), ops_1(), ops_2()];
+        let optimization_id_1 = 0;
+        let optimization_id_2 = 1;
+
+        index.insert(InsertQuery::NewPlan {
+            operations: &stream_1,
+            id: optimization_id_1,
+        });
+        index.insert(InsertQuery::NewPlan {
+            operations: &stream_2,
+            id: optimization_id_2,
+        });
+        index.remove(RemoveQuery::Plan {
+            operations: &stream_1,
+            id: optimization_id_1,
+        });
+
+        let found = starting_with(&index, &stream_1[0]);
+
+        assert_eq!(found, vec![optimization_id_2]);
+    }
+
     #[test]
     fn should_only_find_optimization_with_correct_starting_ops() {
-        let mut index = ExecutionPlanIndex::default();
+        let index = ExecutionPlanIndex::default();
         let stream_1 = [ops_1(), ops_1()];
         let stream_2 = [ops_2(), ops_1()];
         let optimization_id_1 = 0;
@@ -180,14 +469,14 @@ mod tests {
             id: optimization_id_2,
         });
 
-        let found = index.find(SearchQuery::PlansStartingWith(&stream_1[0]));
+        let found = starting_with(&index, &stream_1[0]);
 
         assert_eq!(found, vec![optimization_id_1]);
     }
 
     #[test]
     fn should_handle_hash_collisions() {
-        let mut index = ExecutionPlanIndex::default();
+        let index = ExecutionPlanIndex::default();
         let stream_1 = [ops_1(), ops_1()];
         let stream_2 = [ops_3(), ops_1()];
         let optimization_id_1 = 0;
@@ -211,11 +500,227 @@ mod tests {
             id: optimization_id_2,
         });
 
-        let found = index.find(SearchQuery::PlansStartingWith(&stream_1[0]));
+        let found = starting_with(&index, &stream_1[0]);
 
         assert_eq!(found, vec![optimization_id_1]);
     }
 
+    #[test]
+    fn should_support_concurrent_searches_and_insertions() {
+        let index = ExecutionPlanIndex::new(4);
+        let operations = (0..32).map(scalar_ops).collect::<Vec<_>>();
+
+        std::thread::scope(|scope| {
+            for (id, operation) in operations.iter().enumerate() {
+                let index = &index;
+                scope.spawn(move || {
+                    index.insert(InsertQuery::NewPlan {
+                        operations: core::slice::from_ref(operation),
+                        id,
+                    });
+                    starting_with(index, operation)
+                });
+            }
+        });
+
+        for (id, operation) in operations.iter().enumerate() {
+            let found = starting_with(&index, operation);
+            assert_eq!(found, vec![id]);
+        }
+    }
+
+    #[test]
+    fn should_match_plans_one_operation_at_a_time() {
+        let index = ExecutionPlanIndex::default();
+        let plans = [
+            vec![ops_1(), ops_2(), ops_3()],
+            vec![ops_1(), ops_2()],
+            vec![ops_1(), ops_3()],
+        ];
+
+        for (id, operations) in plans.iter().enumerate() {
+            index.insert(InsertQuery::NewPlan { operations, id });
+        }
+
+        let step = index.start(&ops_1()).unwrap();
+        assert_eq!((step.ended, step.num_pending), (vec![], 3));
+
+        let step = index.advance(step.cursor, &ops_2()).unwrap();
+        assert_eq!((step.ended, step.num_pending), (vec![1], 1));
+        assert_eq!(index.pending(step.cursor), vec![0]);
+
+        let step = index.advance(step.cursor, &ops_3()).unwrap();
+        assert_eq!((step.ended, step.num_pending), (vec![0], 0));
+        assert_eq!(index.advance(step.cursor, &ops_1()), None);
+
+        assert_eq!(
+            index.find(SearchQuery::PlansWithOperations(&plans[2])),
+            vec![2]
+        );
+        assert!(
+            index
+                .find(SearchQuery::PlansWithOperations(&plans[2][..1]))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn should_invalidate_cursors_of_removed_plans() {
+        let index = ExecutionPlanIndex::default();
+        let stream_1 = [ops_1(), ops_2()];
+        let stream_2 = [ops_1(), ops_3()];
+
+        index.insert(InsertQuery::NewPlan {
+            operations: &stream_1,
+            id: 0,
+        });
+        let cursor = index.start(&ops_1()).unwrap().cursor;
+
+        index.remove(RemoveQuery::Plan {
+            operations: &stream_1,
+            id: 0,
+        });
+        assert_eq!(index.advance(cursor, &ops_2()), None);
+        assert_eq!(index.start(&ops_1()), None);
+
+        // The removed nodes are recycled, but the old cursor still doesn't match.
+        index.insert(InsertQuery::NewPlan {
+            operations: &stream_2,
+            id: 1,
+        });
+        assert_eq!(index.advance(cursor, &ops_3()), None);
+        assert!(index.pending(cursor).is_empty());
+
+        let step = index.start(&ops_1()).unwrap();
+        assert_eq!(index.advance(step.cursor, &ops_3()).unwrap().ended, vec![1]);
+    }
+
+    /// Compares searches from many threads on the sharded index with searches serialized behind
+    /// a single lock.
+    ///
+    /// Run with `cargo test -p burn-fusion --release -- --ignored --nocapture bench_`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_concurrent_search() {
+        let num_threads = 8;
+        let num_searches = 200_000;
+        let operations = (0..256).map(scalar_ops).collect::<Vec<_>>();
+        let index = ExecutionPlanIndex::default();
+
+        for (id, operation) in operations.iter().enumerate() {
+            index.insert(InsertQuery::NewPlan {
+                operations: core::slice::from_ref(operation),
+                id,
+            });
+        }
+
+        let run = |search: &(dyn Fn(&OperationIr) -> usize + Sync)| {
+            let start = std::time::Instant::now();
+            std::thread::scope(|scope| {
+                for thread in 0..num_threads {
+                    let operations = &operations;
+                    scope.spawn(move || {
+                        (0..num_searches)
+                            .map(|i| search(&operations[(i * 7 + thread) % operations.len()]))
+                            .sum::<usize>()
+                    });
+                }
+            });
+            start.elapsed()
+        };
+
+        let sharded = run(&|operation| {
+            index
+                .start(operation)
+                .map_or(0, |step| step.ended.len() + step.num_pending)
+        });
+        let locked = std::sync::Mutex::new(&index);
+        let serialized = run(&|operation| {
+            locked
+                .lock()
+                .unwrap()
+                .start(operation)
+                .map_or(0, |step| step.ended.len() + step.num_pending)
+        });
+
+        println!(
+            "{num_threads} threads x {num_searches} searches: sharded {sharded:?}, single lock {serialized:?}"
+        );
+    }
+
+    /// Matches a long sequence of operations against thousands of plans sharing most of it.
+    ///
+    /// Run with `cargo test -p burn-fusion --release -- --ignored --nocapture bench_`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_long_sequence_search() {
+        let num_plans = 4_000;
+        let sequence_length = 200;
+        let operations = (0..sequence_length + num_plans)
+            .map(scalar_ops)
+            .collect::<Vec<_>>();
+        let index = ExecutionPlanIndex::default();
+
+        // Every plan shares the first `sequence_length - 1` operations, and ends with its own.
+        for id in 0..num_plans {
+            let mut plan = operations[0..sequence_length - 1].to_vec();
+            plan.push(operations[sequence_length - 1 + id].clone());
+            index.insert(InsertQuery::NewPlan {
+                operations: &plan,
+                id,
+            });
+        }
+
+        let num_searches = 1_000;
+        let start = std::time::Instant::now();
+        for _ in 0..num_searches {
+            let mut step = index.start(&operations[0]).unwrap();
+            for operation in operations[1..sequence_length].iter() {
+                step = index.advance(step.cursor, operation).unwrap();
+            }
+            assert_eq!(step.ended, vec![0]);
+        }
+
+        println!(
+            "{num_searches} searches of {sequence_length} operations among {num_plans} plans: {:?}",
+            start.elapsed()
+        );
+    }
+
+    /// The plans starting with the operation.
+    fn starting_with(index: &ExecutionPlanIndex, operation: &OperationIr) -> Vec<ExecutionPlanId> {
+        match index.start(operation) {
+            Some(step) => {
+                let mut plans = step.ended;
+                plans.extend(index.pending(step.cursor));
+                plans.sort_unstable();
+                plans
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn scalar_ops(dim: usize) -> OperationIr {
+        OperationIr::NumericFloat(
+            DType::F32,
+            NumericOperationIr::AddScalar(ScalarOpIr {
+                lhs: TensorIr {
+                    id: TensorId::new(0),
+                    shape: vec![dim + 1],
+                    status: TensorStatus::ReadOnly,
+                    dtype: DType::F32,
+                },
+                rhs: 0.0,
+                out: TensorIr {
+                    id: TensorId::new(1),
+                    shape: vec![dim + 1],
+                    status: TensorStatus::NotInit,
+                    dtype: DType::F32,
+                },
+            }),
+        )
+    }
+
     fn ops_1() -> OperationIr {
         OperationIr::NumericFloat(
             DType::F32,
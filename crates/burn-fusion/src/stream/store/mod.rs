@@ -1,5 +1,11 @@
 mod base;
 mod index;
+mod state;
+mod trigger;
 
 pub(crate) use base::*;
+pub use base::{ExecutionPlanId, ExecutionPlanStoreStats, PlanTimings, TimingSource};
 pub(super) use index::*;
+pub use state::PlanExportMode;
+pub(crate) use state::*;
+pub use trigger::*;
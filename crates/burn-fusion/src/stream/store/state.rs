@@ -0,0 +1,384 @@
+#[cfg(feature = "plan-export")]
+use std::io::{BufRead, Read, Write};
+use std::sync::Arc;
+
+use burn_ir::OperationIr;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ExecutionPlan, ExecutionPlanId, ExecutionPlanStore, ExecutionStrategy, ExecutionTrigger,
+    SearchQuery,
+};
+use crate::search::BlockOptimization;
+
+#[cfg(feature = "plan-export")]
+use crate::FusionError;
+#[cfg(feature = "plan-export")]
+use burn_ir::IR_SCHEMA_HASH;
+#[cfg(feature = "plan-export")]
+use serde::de::DeserializeOwned;
+
+/// The serializable state of an [execution plan store](ExecutionPlanStore), where `S` is the
+/// serializable state of the optimizations.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExecutionPlanStoreState<S> {
+    /// The plans, ordered by id.
+    pub(crate) plans: Vec<ExecutionPlanState<S>>,
+}
+
+/// Identifies the build that exported a plan cache.
+///
+/// Plans are only valid for the backend, burn version and IR schema that explored them, so a
+/// cache with another header is ignored and its operations are explored again.
+#[cfg(feature = "plan-export")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlanCacheHeader {
+    /// The version of the layout of the cache itself.
+    pub(crate) format: u32,
+    /// The name of the backend that explored the plans.
+    pub(crate) backend: String,
+    /// The version of burn that explored the plans.
+    pub(crate) burn_version: String,
+    /// The [hash of the IR schema](IR_SCHEMA_HASH) the operations were serialized with.
+    pub(crate) ir_schema: u64,
+}
+
+/// The bytes starting a binary plan cache, telling it apart from a JSON one.
+#[cfg(feature = "plan-export")]
+const BINARY_MAGIC: &[u8; 4] = b"BFPC";
+
+/// A plan cache as written to a file, with the header first.
+#[cfg(feature = "plan-export")]
+#[derive(Serialize)]
+struct PlanCache<'a, S> {
+    header: &'a PlanCacheHeader,
+    plans: &'a [ExecutionPlanState<S>],
+}
+
+/// The serializable state of an [execution plan](ExecutionPlan).
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExecutionPlanState<S> {
+    /// The id of the plan when it was exported, ignored when imported.
+    pub(crate) id: ExecutionPlanId,
+    pub(crate) operations: Vec<OperationIr>,
+    pub(crate) triggers: Vec<ExecutionTrigger>,
+    pub(crate) strategy: ExecutionStrategyState<S>,
+    pub(crate) ordering: Vec<usize>,
+    pub(crate) pinned: bool,
+}
+
+/// The serializable state of an [execution strategy](ExecutionStrategy).
+#[derive(Serialize, Deserialize)]
+pub(crate) enum ExecutionStrategyState<S> {
+    Optimization { opt: S, ordering: Vec<usize> },
+    Operations { ordering: Vec<usize> },
+    Composed(Vec<Self>),
+}
+
+#[cfg(feature = "plan-export")]
+impl PlanCacheHeader {
+    const FORMAT_VERSION: u32 = 1;
+
+    /// The header of the plans explored by the current build for the given backend.
+    pub fn new(backend: &str) -> Self {
+        Self {
+            format: Self::FORMAT_VERSION,
+            backend: backend.to_string(),
+            burn_version: env!("CARGO_PKG_VERSION").to_string(),
+            ir_schema: IR_SCHEMA_HASH,
+        }
+    }
+}
+
+#[cfg(feature = "plan-export")]
+impl<S: Serialize> ExecutionPlanStoreState<S> {
+    /// Write the state as JSON, preceded by the header.
+    pub fn write_json(
+        &self,
+        writer: impl Write,
+        header: &PlanCacheHeader,
+    ) -> Result<(), FusionError> {
+        let cache = PlanCache {
+            header,
+            plans: &self.plans,
+        };
+
+        serde_json::to_writer(writer, &cache).map_err(FusionError::plan_serialization)
+    }
+
+    /// Write the state with a compact binary encoding, preceded by a magic number and the
+    /// header.
+    ///
+    /// Binary caches are a fraction of the size of JSON ones and much faster to read, which
+    /// matters for huge plan stores.
+    pub fn write_bytes(
+        &self,
+        mut writer: impl Write,
+        header: &PlanCacheHeader,
+    ) -> Result<(), FusionError> {
+        let config = bincode::config::standard();
+
+        writer
+            .write_all(BINARY_MAGIC)
+            .map_err(FusionError::plan_serialization)?;
+        bincode::serde::encode_into_std_write(header, &mut writer, config)
+            .map_err(FusionError::plan_serialization)?;
+        bincode::serde::encode_into_std_write(&self.plans, &mut writer, config)
+            .map_err(FusionError::plan_serialization)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "plan-export")]
+impl<S: DeserializeOwned> ExecutionPlanStoreState<S> {
+    /// Read a state written by [write_json](Self::write_json) or
+    /// [write_bytes](Self::write_bytes), detecting the format from the first bytes.
+    pub fn read(
+        mut reader: impl BufRead,
+        expected: &PlanCacheHeader,
+    ) -> Result<Option<Self>, FusionError> {
+        let buffer = reader.fill_buf().map_err(FusionError::plan_serialization)?;
+
+        match buffer.starts_with(BINARY_MAGIC) {
+            true => Self::read_bytes(reader, expected),
+            false => Self::read_json(reader, expected),
+        }
+    }
+
+    /// Read a state written by [write_bytes](Self::write_bytes).
+    ///
+    /// Returns `None` when the header differs from the `expected` one, without decoding the
+    /// plans.
+    pub fn read_bytes(
+        mut reader: impl Read,
+        expected: &PlanCacheHeader,
+    ) -> Result<Option<Self>, FusionError> {
+        let config = bincode::config::standard();
+        let mut magic = [0; BINARY_MAGIC.len()];
+
+        reader
+            .read_exact(&mut magic)
+            .map_err(FusionError::plan_serialization)?;
+        if &magic != BINARY_MAGIC {
+            return Err(FusionError::plan_serialization(
+                "Not a binary execution plan cache",
+            ));
+        }
+
+        let header =
+            bincode::serde::decode_from_std_read::<PlanCacheHeader, _, _>(&mut reader, config).ok();
+        if header.as_ref() != Some(expected) {
+            log::info!(
+                "Ignoring the execution plans exported by {header:?}, which don't match {expected:?}"
+            );
+            return Ok(None);
+        }
+
+        let plans = bincode::serde::decode_from_std_read(&mut reader, config)
+            .map_err(FusionError::plan_serialization)?;
+
+        Ok(Some(Self { plans }))
+    }
+
+    /// Read a state written by [write_json](Self::write_json).
+    ///
+    /// Returns `None` when the header is missing or differs from the `expected` one, without
+    /// parsing the plans, since they might not even be readable with the current schema.
+    pub fn read_json(
+        reader: impl Read,
+        expected: &PlanCacheHeader,
+    ) -> Result<Option<Self>, FusionError> {
+        let mut cache: serde_json::Value =
+            serde_json::from_reader(reader).map_err(FusionError::plan_serialization)?;
+        let header = cache
+            .get_mut("header")
+            .map(serde_json::Value::take)
+            .and_then(|header| serde_json::from_value::<PlanCacheHeader>(header).ok());
+
+        if header.as_ref() != Some(expected) {
+            log::info!(
+                "Ignoring the execution plans exported by {header:?}, which don't match {expected:?}"
+            );
+            return Ok(None);
+        }
+
+        let plans = match cache.get_mut("plans") {
+            Some(plans) => {
+                serde_json::from_value(plans.take()).map_err(FusionError::plan_serialization)?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Some(Self { plans }))
+    }
+}
+
+/// Which plans are written when exporting a [plan store](ExecutionPlanStore).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanExportMode {
+    /// Every plan currently stored.
+    Full,
+    /// Only the plans created since the last export, so that long-running services can append
+    /// to the same file without writing the same plans again.
+    ///
+    /// Plans created and evicted between two exports are never written.
+    Incremental,
+}
+
+impl<O> ExecutionPlanStore<O> {
+    /// Collect the state of every plan, converting each optimization with `to_state`.
+    pub fn to_state<S>(&self, to_state: impl Fn(&O) -> S + Copy) -> ExecutionPlanStoreState<S> {
+        let plans = self
+            .sorted_ids(0)
+            .into_iter()
+            .map(|id| self.plan_state(id, to_state))
+            .collect();
+
+        ExecutionPlanStoreState { plans }
+    }
+
+    /// Write the state of the plans with an id greater or equal to `since` as newline-delimited
+    /// JSON, one plan per line, returning the number of plans written.
+    ///
+    /// The header, when provided, is written on the first line. Plans are serialized one at a
+    /// time, so the whole store is never buffered in memory.
+    #[cfg(feature = "plan-export")]
+    pub fn write_ndjson<S: Serialize>(
+        &self,
+        writer: &mut dyn Write,
+        header: Option<&PlanCacheHeader>,
+        since: ExecutionPlanId,
+        to_state: impl Fn(&O) -> S + Copy,
+    ) -> Result<usize, FusionError> {
+        let ids = self.sorted_ids(since);
+
+        if let Some(header) = header {
+            serde_json::to_writer(&mut *writer, header).map_err(FusionError::plan_serialization)?;
+            writer
+                .write_all(b"\n")
+                .map_err(FusionError::plan_serialization)?;
+        }
+
+        for id in ids.iter() {
+            serde_json::to_writer(&mut *writer, &self.plan_state(*id, to_state))
+                .map_err(FusionError::plan_serialization)?;
+            writer
+                .write_all(b"\n")
+                .map_err(FusionError::plan_serialization)?;
+        }
+        writer.flush().map_err(FusionError::plan_serialization)?;
+
+        Ok(ids.len())
+    }
+
+    fn sorted_ids(&self, since: ExecutionPlanId) -> Vec<ExecutionPlanId> {
+        let mut ids = self
+            .iter()
+            .map(|(id, _)| id)
+            .filter(|id| *id >= since)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn plan_state<S>(
+        &self,
+        id: ExecutionPlanId,
+        to_state: impl Fn(&O) -> S + Copy,
+    ) -> ExecutionPlanState<S> {
+        let plan = self.get_unchecked(id);
+
+        ExecutionPlanState {
+            id,
+            operations: plan.operations.clone(),
+            triggers: plan
+                .triggers
+                .iter()
+                .filter(|trigger| !matches!(trigger, ExecutionTrigger::Custom(_)))
+                .cloned()
+                .collect(),
+            strategy: ExecutionStrategyState::new(&plan.optimization.strategy, to_state),
+            ordering: plan.optimization.ordering.clone(),
+            pinned: self.is_pinned(id),
+        }
+    }
+
+    /// Add the plans of the state to the store, converting each optimization with `from_state`.
+    ///
+    /// Plans get new ids, and plans with the same operations as a plan already in the store are
+    /// skipped. Returns the ids of the added plans.
+    pub fn load_state<S>(
+        &mut self,
+        state: ExecutionPlanStoreState<S>,
+        from_state: impl Fn(S) -> O + Copy,
+    ) -> Vec<ExecutionPlanId> {
+        let mut added = Vec::new();
+
+        for plan in state.plans {
+            if plan.operations.is_empty() || self.contains_operations(&plan.operations) {
+                continue;
+            }
+
+            let pinned = plan.pinned;
+            let plan = ExecutionPlan {
+                operations: plan.operations,
+                triggers: plan.triggers,
+                optimization: BlockOptimization::new(
+                    plan.strategy.into_strategy(from_state),
+                    plan.ordering,
+                ),
+            };
+            let id = self.add(plan).expect("Plans with operations can be added");
+
+            if pinned {
+                // Pinned plans exceeding the capacity are simply kept unpinned.
+                let _ = self.pin(id);
+            }
+            added.push(id);
+        }
+
+        added
+    }
+
+    fn contains_operations(&self, operations: &[OperationIr]) -> bool {
+        !self
+            .find(SearchQuery::PlansWithOperations(operations))
+            .is_empty()
+    }
+}
+
+impl<S> ExecutionStrategyState<S> {
+    fn new<O>(strategy: &ExecutionStrategy<O>, to_state: impl Fn(&O) -> S + Copy) -> Self {
+        match strategy {
+            ExecutionStrategy::Optimization { opt, ordering } => Self::Optimization {
+                opt: to_state(opt),
+                ordering: ordering.as_ref().clone(),
+            },
+            ExecutionStrategy::Operations { ordering } => Self::Operations {
+                ordering: ordering.as_ref().clone(),
+            },
+            ExecutionStrategy::Composed(items) => {
+                Self::Composed(items.iter().map(|item| Self::new(item, to_state)).collect())
+            }
+        }
+    }
+
+    fn into_strategy<O>(self, from_state: impl Fn(S) -> O + Copy) -> ExecutionStrategy<O> {
+        match self {
+            Self::Optimization { opt, ordering } => ExecutionStrategy::Optimization {
+                opt: from_state(opt),
+                ordering: Arc::new(ordering),
+            },
+            Self::Operations { ordering } => ExecutionStrategy::Operations {
+                ordering: Arc::new(ordering),
+            },
+            Self::Composed(items) => ExecutionStrategy::Composed(
+                items
+                    .into_iter()
+                    .map(|item| Box::new(item.into_strategy(from_state)))
+                    .collect(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use core::fmt::Debug;
+use std::sync::Arc;
+
+use burn_ir::OperationIr;
+
+use super::ExecutionTrigger;
+
+/// A user-defined condition deciding when a stored execution plan is executed.
+///
+/// The condition is checked for every operation registered after the operations of the plan,
+/// until it holds or the plan is executed by another trigger. While it doesn't hold, the plan
+/// keeps waiting for more operations, so a condition that never holds delays the plan until the
+/// stream is synced.
+pub trait CustomTrigger: Debug + Send + Sync {
+    /// Whether the plan should be executed, given the operation just registered after it.
+    fn should_execute(&self, context: &TriggerContext<'_>) -> bool;
+}
+
+/// What a [custom trigger](CustomTrigger) knows about the stream when it's checked.
+#[derive(Debug)]
+pub struct TriggerContext<'a> {
+    /// The operation registered after the operations of the plan, with tensor ids relative to
+    /// the stream.
+    pub operation: &'a OperationIr,
+    /// The position of the operation after the plan, starting at 0 for the first operation
+    /// following it.
+    pub position: usize,
+    /// The number of operations queued on the stream, including the operation.
+    pub num_queued: usize,
+}
+
+/// A trigger that can be added to a stored execution plan.
+#[derive(Debug, Clone)]
+pub enum PlanTrigger {
+    /// The plan is executed once the given number of operations are queued on the stream, the
+    /// operations of the plan included.
+    OnQueueLength(usize),
+    /// The plan is executed once the [condition](CustomTrigger) holds.
+    ///
+    /// Custom triggers aren't exported with the plans.
+    Custom(Arc<dyn CustomTrigger>),
+}
+
+/// A [custom trigger](CustomTrigger) stored with a plan, compared by address.
+#[derive(Debug, Clone)]
+pub(crate) struct CustomTriggerRef(pub(crate) Arc<dyn CustomTrigger>);
+
+impl PartialEq for CustomTriggerRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl From<PlanTrigger> for ExecutionTrigger {
+    fn from(trigger: PlanTrigger) -> Self {
+        match trigger {
+            PlanTrigger::OnQueueLength(length) => Self::OnQueueLength(length),
+            PlanTrigger::Custom(trigger) => Self::Custom(CustomTriggerRef(trigger)),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     client::FusionClient,
     stream::{Operation, OperationStreams, StreamId},
 };
-use burn_ir::{OperationIr, TensorId, TensorIr, TensorStatus};
+use burn_ir::{Dim, OperationIr, TensorId, TensorIr, TensorStatus};
 use burn_tensor::{
     DType, Shape, TensorData, TensorMetadata,
     quantization::{QTensorPrimitive, QuantScheme},
@@ -13,6 +13,35 @@ use std::sync::{
     atomic::{AtomicU32, Ordering},
 };
 
+/// The kind of a [fusion tensor](FusionTensor), deciding how its values are read.
+///
+/// The data type doesn't always tell the kind, e.g. bool tensors are stored with the data type
+/// of the [bool element](burn_tensor::backend::Backend::BoolElem), which can be an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TensorKind {
+    /// A float tensor.
+    Float,
+    /// An int tensor.
+    Int,
+    /// A bool tensor.
+    Bool,
+    /// A quantized tensor.
+    Quantized,
+}
+
+impl TensorKind {
+    /// The kind of a tensor guessed from its data type, a bool tensor stored as integers being
+    /// taken for an int tensor.
+    pub fn of(dtype: DType) -> Self {
+        match dtype {
+            DType::Bool => Self::Bool,
+            DType::QFloat(_) => Self::Quantized,
+            dtype if dtype.is_float() => Self::Float,
+            _ => Self::Int,
+        }
+    }
+}
+
 /// Tensor primitive for the [fusion backend](crate::FusionBackend) for all kind.
 pub struct FusionTensor<R: FusionRuntime> {
     /// Tensor id.
@@ -75,16 +104,63 @@ impl<R: FusionRuntime> FusionTensor<R> {
         client: Client<R>,
         stream: StreamId,
     ) -> Self {
+        let count = Arc::new(AtomicU32::new(1));
+        client.track_tensor(id, &count);
+
         Self {
             id,
             shape,
             client,
             dtype,
             stream,
-            count: Arc::new(AtomicU32::new(1)),
+            count,
         }
     }
 
+    /// Pin the buffer of the tensor, returning `false` if the tensor has no buffer.
+    ///
+    /// A pinned buffer is never recycled, moved or written in place by the fusion passes, so raw
+    /// device pointers to it stay valid across steps. The buffer stays alive until
+    /// [unpinned](Self::unpin), even after the tensor is dropped or consumed; in which case
+    /// [Fusion::unpin](crate::Fusion::unpin) can release it from its id.
+    ///
+    /// Pending operations of the tensor stream are executed first, since the buffer of a lazy
+    /// tensor doesn't exist yet.
+    pub fn pin(&self) -> bool {
+        self.client.pin_tensor(self.id, self.stream)
+    }
+
+    /// Release the buffer [pinned](Self::pin) for this tensor, returning `false` if it wasn't
+    /// pinned.
+    pub fn unpin(&self) -> bool {
+        self.client.unpin_tensor(self.id)
+    }
+
+    /// The fingerprint of the computation that produced this tensor, hashing its operations,
+    /// with their shapes and scalars, back to the tensors created from data.
+    ///
+    /// Tensors computed the same way from the same inputs share their fingerprint even when they
+    /// are still lazy, so user code can key caches of results on it. Tensors created from data
+    /// are identified by their id, so their fingerprint doesn't depend on their values.
+    pub fn fingerprint(&self) -> u64 {
+        self.client.tensor_fingerprint(self.id)
+    }
+
+    /// Mark a dimension of this tensor as symbolic, e.g. the batch or the sequence length of the
+    /// inputs of a model, using the given name for the symbol.
+    ///
+    /// The symbol is propagated to the tensors computed from this one, which are then
+    /// [fingerprinted](Self::fingerprint) the same way whatever the size of the dimension.
+    pub fn bind_symbolic_dim(&self, axis: usize, name: &str) {
+        self.client.bind_symbolic_dim(&self.to_ir_out(), axis, name);
+    }
+
+    /// The dimensions of this tensor, [symbolic](Self::bind_symbolic_dim) where bound to a
+    /// symbol.
+    pub fn symbolic_dims(&self) -> Vec<Dim> {
+        self.client.symbolic_dims(&self.to_ir_out())
+    }
+
     fn status(&self, count: u32) -> TensorStatus {
         if count <= 1 {
             TensorStatus::ReadWrite
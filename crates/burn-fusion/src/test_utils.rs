@@ -0,0 +1,219 @@
+//! Types shared by the tests of the fusion server.
+//!
+//! The [test runtime](TestRuntime) executes every operation eagerly with the ndarray backend,
+//! without any optimization, so the tests can check the behavior of the fusion server and clients
+//! on real data.
+
+use std::{cell::Cell, time::Instant};
+
+use burn_common::future::block_on;
+use burn_ir::{
+    BackendIr, FloatOperationIr, HandleKind, OperationIr, TensorId, TensorIr, TensorStatus,
+    UnaryOpIr,
+};
+use burn_ndarray::{NdArray, NdArrayDevice};
+use burn_tensor::{
+    DType, TensorData,
+    ops::{BoolTensorOps, FloatTensor, FloatTensorOps, IntTensorOps},
+};
+
+use crate::{
+    FusionBackend, FusionRuntime, FusionTensor, NumOperations, Optimization, OptimizationBuilder,
+    OptimizationProperties, OptimizationStatus,
+    client::{FusionClient, MutexFusionClient},
+    stream::{Context, OrderedExecution, TimingSource, current_stream},
+};
+
+/// The backend executing the operations of the [test runtime](TestRuntime).
+pub(crate) type TestBackend = NdArray<f32, i64, i8>;
+/// A client with its own fusion server, so that tests don't share state.
+pub(crate) type TestClient = MutexFusionClient<TestRuntime>;
+
+/// A fusion runtime that never fuses operations.
+#[derive(Debug)]
+pub struct TestRuntime;
+
+thread_local! {
+    static LAZY_STREAMS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run the function with the streams it creates keeping their operations queued until they're
+/// drained, instead of executing them as soon as they're registered.
+pub(crate) fn with_lazy_streams<T>(func: impl FnOnce() -> T) -> T {
+    LAZY_STREAMS.set(true);
+    let output = func();
+    LAZY_STREAMS.set(false);
+
+    output
+}
+
+/// The optimization of the [test runtime](TestRuntime), which is never built.
+#[derive(Debug)]
+pub struct TestOptimization;
+
+impl NumOperations for TestOptimization {
+    fn len(&self) -> usize {
+        0
+    }
+}
+
+impl Optimization<TestRuntime> for TestOptimization {
+    fn execute(
+        &mut self,
+        _context: &mut Context<'_, HandleKind<TestBackend>>,
+        _execution: &OrderedExecution<TestRuntime>,
+    ) {
+        unreachable!("The test runtime doesn't have any optimization builder")
+    }
+
+    fn to_state(&self) {}
+
+    fn from_state(_device: &NdArrayDevice, _state: ()) -> Self {
+        Self
+    }
+}
+
+impl FusionRuntime for TestRuntime {
+    type OptimizationState = ();
+    type Optimization = TestOptimization;
+    type FusionHandle = HandleKind<TestBackend>;
+    type FusionDevice = NdArrayDevice;
+    type FusionClient = TestClient;
+    type BoolRepr = bool;
+
+    fn optimizations(
+        _device: NdArrayDevice,
+    ) -> Vec<Box<dyn OptimizationBuilder<Self::Optimization>>> {
+        match LAZY_STREAMS.get() {
+            true => vec![Box::new(LazyBuilder::default())],
+            false => Vec::new(),
+        }
+    }
+
+    fn profile<O>(
+        _device: &NdArrayDevice,
+        func: impl FnOnce() -> O,
+    ) -> (O, Option<(core::time::Duration, TimingSource)>) {
+        let started_at = Instant::now();
+        let output = func();
+
+        // The ndarray backend executes the operations synchronously.
+        (
+            output,
+            Some((started_at.elapsed(), TimingSource::DeviceSync)),
+        )
+    }
+
+    fn reallocate(handle: &Self::FusionHandle) -> Option<Self::FusionHandle> {
+        let data = read_handle(handle)?;
+        let device = NdArrayDevice::Cpu;
+
+        Some(match handle {
+            HandleKind::Float(_) => HandleKind::Float(TestBackend::float_from_data(data, &device)),
+            HandleKind::Int(_) => HandleKind::Int(TestBackend::int_from_data(data, &device)),
+            HandleKind::Bool(_) => HandleKind::Bool(TestBackend::bool_from_data(data, &device)),
+            _ => return None,
+        })
+    }
+}
+
+/// A builder accepting every operation without ever being ready, so the operations are only
+/// executed one by one when their stream is drained.
+#[derive(Clone, Default)]
+struct LazyBuilder {
+    len: usize,
+}
+
+impl OptimizationBuilder<TestOptimization> for LazyBuilder {
+    fn register(&mut self, _operation: &OperationIr) {
+        self.len += 1;
+    }
+
+    fn build(&self) -> TestOptimization {
+        unreachable!("The lazy builder is never ready")
+    }
+
+    fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    fn status(&self) -> OptimizationStatus {
+        OptimizationStatus::Open
+    }
+
+    fn properties(&self) -> OptimizationProperties {
+        OptimizationProperties {
+            score: 0,
+            ready: false,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clone_dyn(&self) -> Box<dyn OptimizationBuilder<TestOptimization>> {
+        Box::new(self.clone())
+    }
+}
+
+impl FusionBackend for TestBackend {
+    type FusionRuntime = TestRuntime;
+    type FullPrecisionBackend = TestBackend;
+
+    fn cast_float(tensor: FloatTensor<Self>, dtype: DType) -> Self::Handle {
+        TestBackend::float_tensor_handle(TestBackend::float_cast(tensor, dtype.into()))
+    }
+}
+
+/// Create a float tensor with the given data on the client.
+pub(crate) fn float_tensor(client: &TestClient, data: TensorData) -> FusionTensor<TestRuntime> {
+    let shape = data.shape.clone();
+    let handle = TestBackend::float_from_data(data, &NdArrayDevice::Cpu);
+
+    client.register_tensor(
+        HandleKind::Float(handle),
+        shape,
+        current_stream(),
+        DType::F32,
+    )
+}
+
+/// Read the data of a float tensor.
+pub(crate) fn float_data(tensor: FusionTensor<TestRuntime>) -> TensorData {
+    let client = tensor.client.clone();
+    let stream = tensor.stream;
+
+    block_on(client.read_tensor_float::<TestBackend>(tensor.into_ir(), stream))
+}
+
+fn read_handle(handle: &HandleKind<TestBackend>) -> Option<TensorData> {
+    let data = match handle {
+        HandleKind::Float(tensor) => block_on(TestBackend::float_into_data(tensor.clone())),
+        HandleKind::Int(tensor) => block_on(TestBackend::int_into_data(tensor.clone())),
+        HandleKind::Bool(tensor) => block_on(TestBackend::bool_into_data(tensor.clone())),
+        _ => return None,
+    };
+
+    Some(data)
+}
+
+/// A float tensor of 8 elements.
+pub(crate) fn tensor(id: u64, status: TensorStatus) -> TensorIr {
+    tensor_with_shape(id, vec![8], status)
+}
+
+/// A float tensor of the given shape.
+pub(crate) fn tensor_with_shape(id: u64, shape: Vec<usize>, status: TensorStatus) -> TensorIr {
+    TensorIr {
+        id: TensorId::new(id),
+        shape,
+        status,
+        dtype: DType::F32,
+    }
+}
+
+/// The operation `out = exp(input)` on float tensors.
+pub(crate) fn exp(input: TensorIr, out: TensorIr) -> OperationIr {
+    OperationIr::Float(DType::F32, FloatOperationIr::Exp(UnaryOpIr { input, out }))
+}
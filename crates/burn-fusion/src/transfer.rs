@@ -0,0 +1,215 @@
+use std::sync::{
+    Mutex,
+    mpsc::{Receiver, SyncSender},
+};
+
+use burn_ir::{BackendIr, HandleContainer, TensorId, TensorIr};
+use burn_tensor::backend::Backend;
+
+use crate::{FusionBackend, FusionRuntime, stream::execution::Operation};
+
+/// Moves the tensor of a given kind to another device, returning its new handle.
+pub(crate) type ToDeviceFn<B> = fn(
+    &mut HandleContainer<<B as BackendIr>::Handle>,
+    &TensorIr,
+    &<B as Backend>::Device,
+) -> <B as BackendIr>::Handle;
+
+pub(crate) fn float_to_device<B: FusionBackend>(
+    handles: &mut HandleContainer<B::Handle>,
+    tensor: &TensorIr,
+    device: &B::Device,
+) -> B::Handle {
+    let tensor = handles.get_float_tensor::<B>(tensor);
+    B::float_tensor_handle(B::float_to_device(tensor, device))
+}
+
+pub(crate) fn int_to_device<B: FusionBackend>(
+    handles: &mut HandleContainer<B::Handle>,
+    tensor: &TensorIr,
+    device: &B::Device,
+) -> B::Handle {
+    let tensor = handles.get_int_tensor::<B>(tensor);
+    B::int_tensor_handle(B::int_to_device(tensor, device))
+}
+
+pub(crate) fn bool_to_device<B: FusionBackend>(
+    handles: &mut HandleContainer<B::Handle>,
+    tensor: &TensorIr,
+    device: &B::Device,
+) -> B::Handle {
+    let tensor = handles.get_bool_tensor::<B>(tensor);
+    B::bool_tensor_handle(B::bool_to_device(tensor, device))
+}
+
+pub(crate) fn quantized_to_device<B: FusionBackend>(
+    handles: &mut HandleContainer<B::Handle>,
+    tensor: &TensorIr,
+    device: &B::Device,
+) -> B::Handle {
+    let tensor = handles.get_quantized_tensor::<B>(tensor);
+    B::quantized_tensor_handle(B::q_to_device(tensor, device))
+}
+
+/// Queued on the stream of the source device, moving the tensor to the target device once the
+/// operations producing it are executed.
+///
+/// The source stream is drained right after queuing it, so the tensor is always sent before
+/// the [receiving operation](ReceiveOp) is queued.
+pub(crate) struct SendOp<B: FusionBackend> {
+    tensor: TensorIr,
+    device: B::Device,
+    to_device: ToDeviceFn<B>,
+    sender: SyncSender<B::Handle>,
+}
+
+/// Queued on the stream of the target device, waiting for the tensor [sent](SendOp) by the
+/// source device.
+pub(crate) struct ReceiveOp<H> {
+    out: TensorId,
+    receiver: Mutex<Receiver<H>>,
+}
+
+impl<B: FusionBackend> SendOp<B> {
+    pub(crate) fn new(
+        tensor: TensorIr,
+        device: B::Device,
+        to_device: ToDeviceFn<B>,
+        sender: SyncSender<B::Handle>,
+    ) -> Self {
+        Self {
+            tensor,
+            device,
+            to_device,
+            sender,
+        }
+    }
+}
+
+impl<H> ReceiveOp<H> {
+    pub(crate) fn new(out: TensorId, receiver: Receiver<H>) -> Self {
+        Self {
+            out,
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+impl<B: FusionBackend> Operation<B::FusionRuntime> for SendOp<B> {
+    fn execute(&self, handles: &mut HandleContainer<B::Handle>) {
+        let handle = (self.to_device)(handles, &self.tensor, &self.device);
+        // The receiver is gone when the target tensor was dropped without being used.
+        let _ = self.sender.try_send(handle);
+    }
+}
+
+impl<R: FusionRuntime> Operation<R> for ReceiveOp<R::FusionHandle> {
+    fn execute(&self, handles: &mut HandleContainer<R::FusionHandle>) {
+        let handle = self
+            .receiver
+            .lock()
+            .unwrap()
+            .try_recv()
+            .expect("The transfer should be sent before it's received");
+
+        handles.register_handle(self.out, handle);
+    }
+}
+
+impl<B: FusionBackend> core::fmt::Debug for SendOp<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SendOp")
+            .field("tensor", &self.tensor)
+            .field("device", &self.device)
+            .finish()
+    }
+}
+
+impl<H> core::fmt::Debug for ReceiveOp<H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReceiveOp").field("out", &self.out).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_ndarray::NdArrayDevice;
+    use burn_tensor::{TensorData, ops::FloatTensorOps};
+
+    use crate::{
+        Fusion, FusionTensor,
+        client::FusionClient,
+        test_utils::{TestBackend, TestClient, TestRuntime, float_data, float_tensor},
+    };
+
+    fn transfer(
+        tensor: FusionTensor<TestRuntime>,
+        client: &TestClient,
+    ) -> FusionTensor<TestRuntime> {
+        let source = tensor.client.clone();
+        let stream = tensor.stream;
+
+        source.change_client_float::<TestBackend>(tensor.into_ir(), client.clone(), stream)
+    }
+
+    #[test]
+    fn should_transfer_tensors_back_and_forth() {
+        let client_1 = TestClient::new(NdArrayDevice::Cpu);
+        let client_2 = TestClient::new(NdArrayDevice::Cpu);
+        let tensor = float_tensor(&client_1, TensorData::from([0.0f32, 1.0]));
+
+        let tensor = Fusion::<TestBackend>::float_exp(tensor);
+        let tensor = transfer(tensor, &client_2);
+        let tensor = Fusion::<TestBackend>::float_log(tensor);
+        let tensor = transfer(tensor, &client_1);
+
+        float_data(tensor)
+            .assert_approx_eq::<f32>(&TensorData::from([0.0f32, 1.0]), Default::default());
+    }
+
+    #[test]
+    fn should_transfer_in_both_directions_concurrently() {
+        let client_1 = TestClient::new(NdArrayDevice::Cpu);
+        let client_2 = TestClient::new(NdArrayDevice::Cpu);
+
+        let spawn = |source: TestClient, target: TestClient, value: f32| {
+            std::thread::spawn(move || {
+                (0..32)
+                    .map(|_| {
+                        let tensor = float_tensor(&source, TensorData::from([value]));
+                        float_data(transfer(tensor, &target))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        let forward = spawn(client_1.clone(), client_2.clone(), 1.0);
+        let backward = spawn(client_2, client_1, 2.0);
+
+        for data in forward.join().unwrap() {
+            data.assert_eq(&TensorData::from([1.0f32]), false);
+        }
+        for data in backward.join().unwrap() {
+            data.assert_eq(&TensorData::from([2.0f32]), false);
+        }
+    }
+
+    #[test]
+    fn should_receive_tensors_sent_from_a_finished_stream() {
+        let client_1 = TestClient::new(NdArrayDevice::Cpu);
+        let client_2 = TestClient::new(NdArrayDevice::Cpu);
+
+        // The stream of the thread is gone before the target stream is drained.
+        let tensor = std::thread::spawn({
+            let client_2 = client_2.clone();
+            move || {
+                let tensor = float_tensor(&client_1, TensorData::from([1.0f32, 2.0]));
+                let tensor = Fusion::<TestBackend>::float_neg(tensor);
+                transfer(tensor, &client_2)
+            }
+        })
+        .join()
+        .unwrap();
+
+        float_data(tensor).assert_eq(&TensorData::from([-1.0f32, -2.0]), false);
+    }
+}
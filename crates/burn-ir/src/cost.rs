@@ -0,0 +1,349 @@
+//! Estimation of the arithmetic and memory traffic of [operations](OperationIr).
+//!
+//! The estimates only depend on the shapes and data types of the tensors, so they can be
+//! computed without executing anything. They are meant to compare operations and groups of
+//! operations, e.g. the execution plans of a fusion backend, rather than to predict execution
+//! times.
+
+use alloc::{vec, vec::Vec};
+use burn_tensor::DType;
+
+use crate::{
+    BaseOperationIr, BaseTensorKind, BoolOperationIr, CustomOpIr, FloatOperationIr,
+    InitOperationIr, IntOperationIr, ModuleOperationIr, NumericOperationIr, OperationIr,
+    OperationVisitor, TensorIr, TensorStatus,
+};
+
+/// The estimated work of an operation, or of a group of operations when summed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OperationCost {
+    /// The number of floating point or integer operations, multiply-adds counting as two.
+    pub flops: u64,
+    /// The number of bytes read from the inputs.
+    pub bytes_read: u64,
+    /// The number of bytes written to the outputs.
+    pub bytes_written: u64,
+}
+
+/// The estimated work of each operation of a sequence, along with the total.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CostReport {
+    /// The cost of each operation, in the order they were given.
+    pub operations: Vec<OperationCost>,
+    /// The cost of all operations.
+    pub total: OperationCost,
+}
+
+/// Estimate the cost of every operation of the sequence.
+pub fn estimate(operations: &[OperationIr]) -> CostReport {
+    let operations = operations.iter().map(operation_cost).collect::<Vec<_>>();
+    let total = operations.iter().copied().sum();
+
+    CostReport { operations, total }
+}
+
+/// Estimate the cost of a single operation.
+///
+/// Layout changes that are views on most backends, like reshapes and permutations, as well as
+/// tensor initializations and drops, cost nothing. Every other operation reads its inputs and
+/// writes its outputs once.
+pub fn operation_cost(operation: &OperationIr) -> OperationCost {
+    let mut work = Work::Outputs;
+    operation.accept(&mut work);
+
+    if let Work::View = work {
+        return OperationCost::default();
+    }
+
+    let nodes = operation.nodes();
+    let (outputs, inputs): (Vec<_>, Vec<_>) = nodes
+        .into_iter()
+        .partition(|tensor| tensor.status == TensorStatus::NotInit);
+    let flops = match work {
+        Work::View | Work::None => 0,
+        Work::Outputs => outputs.iter().map(|tensor| num_elements(tensor)).sum(),
+        Work::Inputs => inputs.iter().map(|tensor| num_elements(tensor)).sum(),
+        Work::Exact(flops) => flops,
+    };
+
+    OperationCost {
+        flops,
+        bytes_read: inputs.iter().map(|tensor| num_bytes(tensor)).sum(),
+        bytes_written: outputs.iter().map(|tensor| num_bytes(tensor)).sum(),
+    }
+}
+
+impl OperationCost {
+    /// The number of operations per byte moved, zero when no byte is moved.
+    ///
+    /// Operations with a low intensity are bound by the memory bandwidth, which is what fusing
+    /// them saves.
+    pub fn arithmetic_intensity(&self) -> f64 {
+        match self.bytes_read + self.bytes_written {
+            0 => 0.0,
+            bytes => self.flops as f64 / bytes as f64,
+        }
+    }
+}
+
+impl CostReport {
+    /// The cost of each plan, given the index of the plan executing each operation, e.g. the
+    /// plans of a fusion explanation.
+    ///
+    /// Plans are indexed from zero, the length of the result being the highest index plus one.
+    pub fn plans(&self, plans: &[usize]) -> Vec<OperationCost> {
+        let num_plans = plans.iter().max().map_or(0, |max| max + 1);
+        let mut costs = vec![OperationCost::default(); num_plans];
+
+        for (cost, plan) in self.operations.iter().zip(plans) {
+            costs[*plan] += *cost;
+        }
+
+        costs
+    }
+}
+
+impl core::ops::Add for OperationCost {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            flops: self.flops + rhs.flops,
+            bytes_read: self.bytes_read + rhs.bytes_read,
+            bytes_written: self.bytes_written + rhs.bytes_written,
+        }
+    }
+}
+
+impl core::ops::AddAssign for OperationCost {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl core::iter::Sum for OperationCost {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, cost| total + cost)
+    }
+}
+
+/// How the arithmetic of an operation is counted.
+enum Work {
+    /// No arithmetic nor memory traffic.
+    View,
+    /// Memory traffic only.
+    None,
+    /// One operation per output element.
+    Outputs,
+    /// One operation per input element, e.g. for reductions.
+    Inputs,
+    /// A known number of operations.
+    Exact(u64),
+}
+
+impl OperationVisitor<'_> for Work {
+    fn visit_base(&mut self, _kind: BaseTensorKind, op: &BaseOperationIr) {
+        *self = match op {
+            BaseOperationIr::Reshape(_)
+            | BaseOperationIr::SwapDims(_)
+            | BaseOperationIr::Permute(_)
+            | BaseOperationIr::Expand(_)
+            | BaseOperationIr::Slice(_)
+            | BaseOperationIr::Empty(_) => Work::View,
+            BaseOperationIr::Equal(_) => Work::Outputs,
+            _ => Work::None,
+        };
+    }
+
+    fn visit_numeric_float(&mut self, _dtype: DType, op: &NumericOperationIr<f32>) {
+        *self = numeric_work(op);
+    }
+
+    fn visit_numeric_int(&mut self, _dtype: DType, op: &NumericOperationIr<i32>) {
+        *self = numeric_work(op);
+    }
+
+    fn visit_float(&mut self, _dtype: DType, op: &FloatOperationIr) {
+        *self = match op {
+            FloatOperationIr::Matmul(op) => {
+                let k = op.lhs.shape.last().copied().unwrap_or_default() as u64;
+                Work::Exact(2 * k * num_elements(&op.out))
+            }
+            FloatOperationIr::IntoInt(_) => Work::None,
+            _ => Work::Outputs,
+        };
+    }
+
+    fn visit_int(&mut self, op: &IntOperationIr) {
+        *self = match op {
+            IntOperationIr::IntoFloat(_) => Work::None,
+            _ => Work::Outputs,
+        };
+    }
+
+    fn visit_bool(&mut self, op: &BoolOperationIr) {
+        *self = match op {
+            BoolOperationIr::IntoFloat(_) | BoolOperationIr::IntoInt(_) => Work::None,
+            _ => Work::Outputs,
+        };
+    }
+
+    fn visit_module(&mut self, op: &ModuleOperationIr) {
+        // Every output element of a convolution accumulates a kernel over the input channels
+        // of its group, which is the weight without its output channels.
+        let kernel = |weight: &TensorIr| weight.shape.iter().skip(1).product::<usize>() as u64;
+
+        *self = match op {
+            ModuleOperationIr::Conv1d(op) => {
+                Work::Exact(2 * num_elements(&op.out) * kernel(&op.weight))
+            }
+            ModuleOperationIr::Conv2d(op) => {
+                Work::Exact(2 * num_elements(&op.out) * kernel(&op.weight))
+            }
+            ModuleOperationIr::Conv3d(op) => {
+                Work::Exact(2 * num_elements(&op.out) * kernel(&op.weight))
+            }
+            ModuleOperationIr::DeformableConv2d(op) => {
+                Work::Exact(2 * num_elements(&op.out) * kernel(&op.weight))
+            }
+            // Every input element of a transposed convolution is spread over a kernel.
+            ModuleOperationIr::ConvTranspose1d(op) => {
+                Work::Exact(2 * num_elements(&op.x) * kernel(&op.weight))
+            }
+            ModuleOperationIr::ConvTranspose2d(op) => {
+                Work::Exact(2 * num_elements(&op.x) * kernel(&op.weight))
+            }
+            ModuleOperationIr::ConvTranspose3d(op) => {
+                Work::Exact(2 * num_elements(&op.x) * kernel(&op.weight))
+            }
+            ModuleOperationIr::AvgPool1d(op) => {
+                Work::Exact(num_elements(&op.out) * op.kernel_size as u64)
+            }
+            ModuleOperationIr::AvgPool2d(op) => {
+                Work::Exact(num_elements(&op.out) * op.kernel_size.iter().product::<usize>() as u64)
+            }
+            ModuleOperationIr::MaxPool1d(op) => {
+                Work::Exact(num_elements(&op.out) * op.kernel_size as u64)
+            }
+            ModuleOperationIr::MaxPool1dWithIndices(op) => {
+                Work::Exact(num_elements(&op.out) * op.kernel_size as u64)
+            }
+            ModuleOperationIr::MaxPool2d(op) => {
+                Work::Exact(num_elements(&op.out) * op.kernel_size.iter().product::<usize>() as u64)
+            }
+            ModuleOperationIr::MaxPool2dWithIndices(op) => {
+                Work::Exact(num_elements(&op.out) * op.kernel_size.iter().product::<usize>() as u64)
+            }
+            ModuleOperationIr::AdaptiveAvgPool1d(_) | ModuleOperationIr::AdaptiveAvgPool2d(_) => {
+                Work::Inputs
+            }
+            ModuleOperationIr::Embedding(_) => Work::None,
+            _ => Work::Outputs,
+        };
+    }
+
+    fn visit_init(&mut self, _op: &InitOperationIr) {
+        *self = Work::View;
+    }
+
+    fn visit_custom(&mut self, _op: &CustomOpIr) {
+        *self = Work::None;
+    }
+
+    fn visit_drop(&mut self, _tensor: &TensorIr) {
+        *self = Work::View;
+    }
+}
+
+fn numeric_work<E>(op: &NumericOperationIr<E>) -> Work {
+    match op {
+        NumericOperationIr::Mean(_)
+        | NumericOperationIr::MeanDim(_)
+        | NumericOperationIr::Sum(_)
+        | NumericOperationIr::SumDim(_)
+        | NumericOperationIr::Prod(_)
+        | NumericOperationIr::ProdDim(_)
+        | NumericOperationIr::ArgMax(_)
+        | NumericOperationIr::ArgMin(_)
+        | NumericOperationIr::Max(_)
+        | NumericOperationIr::MaxDim(_)
+        | NumericOperationIr::MaxDimWithIndices(_)
+        | NumericOperationIr::Min(_)
+        | NumericOperationIr::MinDim(_)
+        | NumericOperationIr::MinDimWithIndices(_)
+        | NumericOperationIr::MaxAbs(_)
+        | NumericOperationIr::MaxAbsDim(_) => Work::Inputs,
+        NumericOperationIr::Scatter(op) => Work::Exact(num_elements(&op.value)),
+        NumericOperationIr::SelectAssign(op) => Work::Exact(num_elements(&op.value)),
+        NumericOperationIr::Ones(_)
+        | NumericOperationIr::Zeros(_)
+        | NumericOperationIr::Full(_)
+        | NumericOperationIr::Gather(_)
+        | NumericOperationIr::Select(_) => Work::None,
+        _ => Work::Outputs,
+    }
+}
+
+fn num_elements(tensor: &TensorIr) -> u64 {
+    tensor.shape.iter().product::<usize>() as u64
+}
+
+fn num_bytes(tensor: &TensorIr) -> u64 {
+    num_elements(tensor) * tensor.dtype.size() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinaryOpIr, TensorId, UnaryOpIr};
+
+    #[test]
+    fn should_estimate_matmul_and_element_wise_costs() {
+        let tensor = |id, shape: &[usize], status| TensorIr {
+            id: TensorId::new(id),
+            shape: shape.to_vec(),
+            status,
+            dtype: DType::F32,
+        };
+        let operations = [
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::Matmul(BinaryOpIr {
+                    lhs: tensor(1, &[2, 8, 16], TensorStatus::ReadOnly),
+                    rhs: tensor(2, &[2, 16, 4], TensorStatus::ReadOnly),
+                    out: tensor(3, &[2, 8, 4], TensorStatus::NotInit),
+                }),
+            ),
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::Exp(UnaryOpIr {
+                    input: tensor(3, &[2, 8, 4], TensorStatus::ReadWrite),
+                    out: tensor(4, &[2, 8, 4], TensorStatus::NotInit),
+                }),
+            ),
+            OperationIr::BaseFloat(BaseOperationIr::Reshape(UnaryOpIr {
+                input: tensor(4, &[2, 8, 4], TensorStatus::ReadWrite),
+                out: tensor(5, &[16, 4], TensorStatus::NotInit),
+            })),
+        ];
+
+        let report = estimate(&operations);
+
+        assert_eq!(
+            report.operations[0],
+            OperationCost {
+                flops: 2 * 16 * 64,
+                bytes_read: (256 + 128) * 4,
+                bytes_written: 64 * 4,
+            }
+        );
+        assert_eq!(report.operations[1].flops, 64);
+        assert_eq!(report.operations[1].arithmetic_intensity(), 64.0 / 512.0);
+        assert_eq!(report.operations[2], OperationCost::default());
+        assert_eq!(report.total.flops, 2 * 16 * 64 + 64);
+        assert_eq!(
+            report.plans(&[0, 1, 1]),
+            [report.operations[0], report.operations[1]]
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use burn_tensor::Shape;
+use alloc::vec::Vec;
+use burn_tensor::{DType, Shape};
 use hashbrown::HashMap;
 
 use crate::{BackendIr, TensorHandle, TensorId, TensorIr, TensorStatus};
@@ -8,9 +9,34 @@ use crate::{BackendIr, TensorHandle, TensorId, TensorIr, TensorStatus};
 #[derive(Default)]
 pub struct HandleContainer<H> {
     handles: HashMap<TensorId, Handle<H>>,
+    metadata: HashMap<TensorId, TensorMetadata>,
+    /// Extra references to pinned handles, kept until they are unpinned.
+    pinned: HashMap<TensorId, H>,
     counter: u64,
 }
 
+/// The shape and data type of a tensor tracked by a [handle container](HandleContainer).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TensorMetadata {
+    /// The shape of the tensor.
+    pub shape: Vec<usize>,
+    /// The data type of the tensor.
+    pub dtype: DType,
+}
+
+/// A read-only view of a tensor tracked by a [handle container](HandleContainer).
+#[derive(Debug)]
+pub struct HandleEntry<'a, H> {
+    /// The id of the tensor.
+    pub id: TensorId,
+    /// The handle of the tensor.
+    pub handle: &'a Handle<H>,
+    /// The shape and data type of the tensor, if [registered](HandleContainer::register_metadata).
+    pub metadata: Option<&'a TensorMetadata>,
+    /// Whether the handle is [pinned](HandleContainer::pin).
+    pub pinned: bool,
+}
+
 impl<H: Clone> HandleContainer<H> {
     /// Fork the container, useful for autotune.
     pub fn fork(&self) -> Self {
@@ -22,6 +48,8 @@ impl<H: Clone> HandleContainer<H> {
 
         Self {
             handles,
+            metadata: self.metadata.clone(),
+            pinned: self.pinned.clone(),
             counter: self.counter,
         }
     }
@@ -37,7 +65,7 @@ impl<H> core::fmt::Debug for HandleContainer<H> {
 }
 
 /// Backend [tensor handle](BackendIr::Handle) wrapper tracking their creation state
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Handle<H> {
     /// No [tensor handle](BackendIr::Handle) has been created yet
     NotInit,
@@ -50,6 +78,8 @@ impl<H: Clone> HandleContainer<H> {
     pub fn new() -> Self {
         Self {
             handles: HashMap::new(),
+            metadata: HashMap::new(),
+            pinned: HashMap::new(),
             counter: 0,
         }
     }
@@ -64,6 +94,11 @@ impl<H: Clone> HandleContainer<H> {
         self.handles.contains_key(id)
     }
 
+    /// The handle of the given [tensor id](TensorId), without removing it.
+    pub fn handle(&self, id: &TensorId) -> Option<&Handle<H>> {
+        self.handles.get(id)
+    }
+
     /// Get the handle for the given [tensor id](TensorId). The status is used to determine if the
     /// tensor should be popped out of the current tensor map, necessary for inplace operations.
     ///
@@ -83,7 +118,10 @@ impl<H: Clone> HandleContainer<H> {
                     self.handles.insert(id, Handle::Existing(handle.clone()));
                     handle
                 }
-                TensorStatus::ReadWrite => handle,
+                TensorStatus::ReadWrite => {
+                    self.metadata.remove(&id);
+                    handle
+                }
                 TensorStatus::NotInit => panic!(
                     "Cannot get uninitialized tensor {id:?}. Tensor exist but with wrong status"
                 ),
@@ -185,6 +223,7 @@ impl<H: Clone> HandleContainer<H> {
 
     /// Remove tensor handle from container.
     pub fn remove_handle(&mut self, id: TensorId) -> Option<Handle<H>> {
+        self.metadata.remove(&id);
         self.handles.remove(&id)
     }
 
@@ -195,6 +234,7 @@ impl<H: Clone> HandleContainer<H> {
             TensorStatus::NotInit => (),
             TensorStatus::ReadWrite => {
                 self.handles.remove(&tensor.id);
+                self.metadata.remove(&tensor.id);
             }
         };
     }
@@ -203,4 +243,85 @@ impl<H: Clone> HandleContainer<H> {
     pub fn num_handles(&self) -> usize {
         self.handles.len()
     }
+
+    /// Returns the number of [tensor ids](TensorId) created by the container so far.
+    pub fn num_tensors_created(&self) -> u64 {
+        self.counter
+    }
+
+    /// Register the shape and data type of the tensor with the given [tensor id](TensorId).
+    ///
+    /// The metadata is forgotten when the handle is removed from the container.
+    pub fn register_metadata(&mut self, id: TensorId, metadata: TensorMetadata) {
+        self.metadata.insert(id, metadata);
+    }
+
+    /// Pin the handle of the given [tensor id](TensorId), returning `false` if the tensor has no
+    /// existing handle.
+    ///
+    /// The container keeps an extra reference to a pinned handle until it is
+    /// [unpinned](Self::unpin), even after the tensor is removed. The buffer therefore stays
+    /// alive and isn't considered exclusively owned by the tensor, so it can't be reused in place.
+    pub fn pin(&mut self, id: TensorId) -> bool {
+        match self.handles.get(&id) {
+            Some(Handle::Existing(handle)) => {
+                self.pinned.insert(id, handle.clone());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Release the handle pinned for the given [tensor id](TensorId), returning `false` if it
+    /// wasn't pinned.
+    pub fn unpin(&mut self, id: TensorId) -> bool {
+        self.pinned.remove(&id).is_some()
+    }
+
+    /// Whether the handle of the given [tensor id](TensorId) is pinned.
+    pub fn is_pinned(&self, id: &TensorId) -> bool {
+        self.pinned.contains_key(id)
+    }
+
+    /// Iterate over the ids of all pinned tensors.
+    pub fn pinned(&self) -> impl Iterator<Item = TensorId> + '_ {
+        self.pinned.keys().copied()
+    }
+
+    /// Iterate over all tensors tracked by the container, initialized or not.
+    pub fn iter(&self) -> impl Iterator<Item = HandleEntry<'_, H>> {
+        self.handles.iter().map(|(id, handle)| HandleEntry {
+            id: *id,
+            handle,
+            metadata: self.metadata.get(id),
+            pinned: self.pinned.contains_key(id),
+        })
+    }
+
+    /// Iterate over all tensors that have an existing handle.
+    pub fn existing_handles(&self) -> impl Iterator<Item = (TensorId, &H)> {
+        self.handles.iter().filter_map(|(id, handle)| match handle {
+            Handle::Existing(handle) => Some((*id, handle)),
+            Handle::NotInit => None,
+        })
+    }
+}
+
+impl TensorMetadata {
+    /// The number of bytes used by the tensor when stored contiguously.
+    pub fn num_bytes(&self) -> u64 {
+        (self.shape.iter().product::<usize>() * self.dtype.size()) as u64
+    }
+}
+
+impl<H> HandleEntry<'_, H> {
+    /// Whether the handle of the tensor has been created.
+    pub fn is_initialized(&self) -> bool {
+        matches!(self.handle, Handle::Existing(_))
+    }
+
+    /// The approximate number of bytes used by the tensor, if its metadata is known.
+    pub fn num_bytes(&self) -> Option<u64> {
+        self.metadata.map(TensorMetadata::num_bytes)
+    }
 }
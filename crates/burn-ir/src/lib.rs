@@ -6,12 +6,26 @@
 
 extern crate alloc;
 
+pub mod cost;
+
 mod backend;
 mod handle;
+mod metadata;
 mod operation;
+mod schema;
+mod symbolic;
 mod tensor;
+mod text;
+mod validate;
+mod visitor;
 
 pub use backend::*;
 pub use handle::*;
+pub use metadata::*;
 pub use operation::*;
+pub use schema::*;
+pub use symbolic::*;
 pub use tensor::*;
+pub use text::*;
+pub use validate::*;
+pub use visitor::*;
@@ -0,0 +1,91 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+};
+use serde::{Deserialize, Serialize};
+
+/// Describes where an [operation](crate::OperationIr) comes from, e.g. the module creating it,
+/// so that the tools consuming the operations can relate them to the model.
+///
+/// The metadata doesn't take part in the equality of operations, so operations with different
+/// metadata can still be executed by the same plans.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OperationMetadata {
+    /// The name of what created the operation, e.g. `encoder/layer_0/attention`.
+    pub name: Option<String>,
+    /// The source location that created the operation, as `file:line:column`.
+    pub location: Option<String>,
+    /// Additional tags attached by the user.
+    pub tags: BTreeMap<String, String>,
+}
+
+impl OperationMetadata {
+    /// Metadata with the given name.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set the location to the caller of this function.
+    #[track_caller]
+    pub fn located(mut self) -> Self {
+        let caller = core::panic::Location::caller();
+        self.location = Some(format!(
+            "{}:{}:{}",
+            caller.file(),
+            caller.line(),
+            caller.column()
+        ));
+        self
+    }
+
+    /// Attach a tag, replacing any previous value for the same key.
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.tags.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// Whether the metadata doesn't hold anything.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.location.is_none() && self.tags.is_empty()
+    }
+
+    /// Complete the metadata with the metadata of an enclosing context, the values already set
+    /// taking precedence.
+    pub fn inherit(&mut self, outer: &Self) {
+        if self.name.is_none() {
+            self.name.clone_from(&outer.name);
+        }
+        if self.location.is_none() {
+            self.location.clone_from(&outer.location);
+        }
+        for (key, value) in outer.tags.iter() {
+            self.tags
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_inherit_missing_values() {
+        let outer = OperationMetadata::named("encoder")
+            .with_tag("phase", "train")
+            .with_tag("layer", 0);
+        let mut inner = OperationMetadata::default().located().with_tag("layer", 1);
+
+        inner.inherit(&outer);
+
+        assert_eq!(inner.name.as_deref(), Some("encoder"));
+        assert!(inner.location.unwrap().contains("metadata.rs"));
+        assert_eq!(inner.tags["phase"], "train");
+        assert_eq!(inner.tags["layer"], "1");
+    }
+}
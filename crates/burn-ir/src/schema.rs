@@ -0,0 +1,149 @@
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::OperationIr;
+
+/// The version of the serialized form of the intermediate representation.
+///
+/// Unlike the [schema hash](IR_SCHEMA_HASH), it only changes when a change to the
+/// [operations](OperationIr) or the [tensors](crate::TensorIr) breaks their serialized form,
+/// e.g. a renamed variant or a new field, so external tools can tell which version they read.
+pub const IR_SCHEMA_VERSION: u32 = 1;
+
+/// A hash of the definitions of the intermediate representation.
+///
+/// It changes whenever the files defining the IR change, even for cosmetic edits, so anything
+/// serialized from the IR and persisted across builds, such as exported fusion plans, can be
+/// invalidated conservatively when the schema might differ.
+pub const IR_SCHEMA_HASH: u64 = {
+    let hash = fnv1a(FNV_OFFSET_BASIS, include_str!("operation.rs").as_bytes());
+    fnv1a(hash, include_str!("tensor.rs").as_bytes())
+};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// The 64-bit FNV-1a hash, which is stable across platforms and compiler versions.
+const fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+
+    hash
+}
+
+/// Operations in their serialized form, tagged with the [schema version](IR_SCHEMA_VERSION)
+/// they were serialized with.
+///
+/// Used for graph dumps, recorded workloads and any tool reading the operations outside of
+/// the process, which can check the version before reading the operations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrDocument {
+    /// The [schema version](IR_SCHEMA_VERSION) of the operations.
+    pub schema_version: u32,
+    /// The operations, in the order they were registered.
+    pub operations: Vec<OperationIr>,
+}
+
+/// The error returned when reading a [document](IrDocument) serialized with another
+/// [schema version](IR_SCHEMA_VERSION).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaVersionError {
+    /// The version of the document.
+    pub found: u32,
+    /// The version of the current build.
+    pub expected: u32,
+}
+
+impl IrDocument {
+    /// Tag the operations with the current [schema version](IR_SCHEMA_VERSION).
+    pub fn new(operations: Vec<OperationIr>) -> Self {
+        Self {
+            schema_version: IR_SCHEMA_VERSION,
+            operations,
+        }
+    }
+
+    /// The operations of the document, if it was serialized with the current
+    /// [schema version](IR_SCHEMA_VERSION).
+    pub fn into_operations(self) -> Result<Vec<OperationIr>, SchemaVersionError> {
+        match self.schema_version == IR_SCHEMA_VERSION {
+            true => Ok(self.operations),
+            false => Err(SchemaVersionError {
+                found: self.schema_version,
+                expected: IR_SCHEMA_VERSION,
+            }),
+        }
+    }
+}
+
+impl core::fmt::Display for SchemaVersionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!(
+            "IR schema version {} isn't supported, expected version {}",
+            self.found, self.expected
+        ))
+    }
+}
+
+impl core::error::Error for SchemaVersionError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::{FloatOperationIr, TensorId, TensorIr, TensorStatus, UnaryOpIr};
+
+    fn operations() -> Vec<OperationIr> {
+        let tensor = |id, status| TensorIr {
+            id: TensorId::new(id),
+            shape: vec![8, 8],
+            status,
+            dtype: DType::F32,
+        };
+
+        vec![OperationIr::Float(
+            DType::F32,
+            FloatOperationIr::Exp(UnaryOpIr {
+                input: tensor(1, TensorStatus::ReadOnly),
+                out: tensor(2, TensorStatus::NotInit),
+            }),
+        )]
+    }
+
+    #[test]
+    fn should_read_documents_of_the_current_version() {
+        let json = serde_json::to_string(&IrDocument::new(operations())).unwrap();
+        let document: IrDocument = serde_json::from_str(&json).unwrap();
+
+        assert!(json.starts_with(&alloc::format!("{{\"schema_version\":{IR_SCHEMA_VERSION},")));
+        assert_eq!(document.into_operations(), Ok(operations()));
+    }
+
+    #[test]
+    fn should_reject_documents_of_another_version() {
+        let document = IrDocument {
+            schema_version: IR_SCHEMA_VERSION + 1,
+            operations: operations(),
+        };
+        let error = document.into_operations().unwrap_err();
+
+        assert_eq!(
+            error,
+            SchemaVersionError {
+                found: IR_SCHEMA_VERSION + 1,
+                expected: IR_SCHEMA_VERSION,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "IR schema version 2 isn't supported, expected version 1"
+        );
+    }
+}
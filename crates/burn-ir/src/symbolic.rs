@@ -0,0 +1,191 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{OperationIr, TensorId, TensorIr, TensorStatus};
+
+/// Identifies a [symbolic dimension](Dim::Dynamic) declared in [shape symbols](ShapeSymbols).
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub struct SymbolId {
+    value: u32,
+}
+
+/// A dimension of a tensor shape, either known or standing for a symbol, e.g. the batch size.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Dim {
+    /// A dimension always having the same size.
+    Static(usize),
+    /// A dimension whose size varies between executions, e.g. the batch or the sequence length.
+    Dynamic(SymbolId),
+}
+
+/// Tracks which dimensions of the tensors of an operation sequence are symbolic, so that the tools
+/// consuming the operations can treat them as symbols instead of sizes.
+///
+/// Dimensions are bound to a symbol with [bind](Self::bind), usually on the inputs of a model,
+/// and [propagated](Self::register) to the outputs of the operations reading them.
+#[derive(Clone, Debug, Default)]
+pub struct ShapeSymbols {
+    names: Vec<String>,
+    bindings: HashMap<TensorId, Vec<Option<SymbolId>>>,
+}
+
+impl SymbolId {
+    /// The index of the symbol, in the order the symbols were declared.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+impl ShapeSymbols {
+    /// The symbol with the given name, declared on first use.
+    pub fn symbol(&mut self, name: &str) -> SymbolId {
+        let index = match self.names.iter().position(|declared| declared == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.names.len() - 1
+            }
+        };
+
+        SymbolId {
+            value: index as u32,
+        }
+    }
+
+    /// The name of a symbol.
+    pub fn name(&self, symbol: SymbolId) -> &str {
+        &self.names[symbol.value as usize]
+    }
+
+    /// Whether no dimension is bound to a symbol.
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Bind a dimension of a tensor to a symbol.
+    pub fn bind(&mut self, tensor: &TensorIr, axis: usize, symbol: SymbolId) {
+        let bindings = self
+            .bindings
+            .entry(tensor.id)
+            .or_insert_with(|| vec![None; tensor.shape.len()]);
+
+        if let Some(binding) = bindings.get_mut(axis) {
+            *binding = Some(symbol);
+        }
+    }
+
+    /// The dimensions of the tensor, symbolic where bound to a symbol.
+    pub fn dims(&self, tensor: &TensorIr) -> Vec<Dim> {
+        let bindings = self.bindings.get(&tensor.id);
+
+        tensor
+            .shape
+            .iter()
+            .enumerate()
+            .map(|(axis, size)| {
+                match bindings.and_then(|bindings| bindings.get(axis).copied().flatten()) {
+                    Some(symbol) => Dim::Dynamic(symbol),
+                    None => Dim::Static(*size),
+                }
+            })
+            .collect()
+    }
+
+    /// Propagate the symbols of the inputs of an operation to its outputs, forgetting the tensors
+    /// it consumes.
+    ///
+    /// An output dimension is bound to the symbol of an input dimension with the same size, so a
+    /// static dimension that happens to have the size of a symbolic one is bound to it as well.
+    pub fn register(&mut self, operation: &OperationIr) {
+        if self.bindings.is_empty() {
+            return;
+        }
+        if let OperationIr::Drop(tensor) = operation {
+            self.bindings.remove(&tensor.id);
+            return;
+        }
+
+        let nodes = operation.nodes();
+        let mut sizes = HashMap::new();
+
+        for tensor in nodes
+            .iter()
+            .filter(|node| node.status != TensorStatus::NotInit)
+        {
+            for (size, dim) in tensor.shape.iter().zip(self.dims(tensor)) {
+                if let Dim::Dynamic(symbol) = dim {
+                    sizes.entry(*size).or_insert(symbol);
+                }
+            }
+        }
+
+        for tensor in nodes {
+            match tensor.status {
+                TensorStatus::NotInit => {
+                    for (axis, size) in tensor.shape.iter().enumerate() {
+                        if let Some(symbol) = sizes.get(size) {
+                            self.bind(tensor, axis, *symbol);
+                        }
+                    }
+                }
+                TensorStatus::ReadWrite => {
+                    self.bindings.remove(&tensor.id);
+                }
+                TensorStatus::ReadOnly => {}
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for Dim {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Dim::Static(size) => f.write_fmt(format_args!("{size}")),
+            Dim::Dynamic(symbol) => f.write_fmt(format_args!("?{}", symbol.value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::{FloatOperationIr, UnaryOpIr};
+
+    #[test]
+    fn should_propagate_symbols_to_outputs() {
+        let tensor = |id, shape: [usize; 2], status| TensorIr {
+            id: TensorId::new(id),
+            shape: shape.to_vec(),
+            status,
+            dtype: DType::F32,
+        };
+        let mut symbols = ShapeSymbols::default();
+        let batch = symbols.symbol("batch");
+        symbols.bind(&tensor(1, [8, 16], TensorStatus::ReadOnly), 0, batch);
+
+        symbols.register(&OperationIr::Float(
+            DType::F32,
+            FloatOperationIr::Exp(UnaryOpIr {
+                input: tensor(1, [8, 16], TensorStatus::ReadWrite),
+                out: tensor(2, [8, 16], TensorStatus::NotInit),
+            }),
+        ));
+
+        assert_eq!(symbols.name(batch), "batch");
+        assert_eq!(
+            symbols.dims(&tensor(2, [8, 16], TensorStatus::ReadOnly)),
+            [Dim::Dynamic(batch), Dim::Static(16)]
+        );
+        assert_eq!(
+            symbols.dims(&tensor(1, [8, 16], TensorStatus::ReadOnly)),
+            [Dim::Static(8), Dim::Static(16)]
+        );
+    }
+}
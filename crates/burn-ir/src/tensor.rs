@@ -54,4 +54,9 @@ impl TensorId {
     pub fn new(value: u64) -> Self {
         Self { value }
     }
+
+    /// The raw value of the tensor id.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
 }
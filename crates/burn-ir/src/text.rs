@@ -0,0 +1,1004 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{Display, Write};
+
+use serde::{
+    Deserialize, Serialize, de,
+    de::{
+        DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+        value::{MapDeserializer, StrDeserializer},
+    },
+    ser,
+};
+
+use crate::OperationIr;
+
+/// Render the operations in their textual form, one operation per line.
+///
+/// The textual form follows the structure of the [operations](OperationIr), with the names of
+/// the variants but without the names of the structs, e.g.
+///
+/// ```text
+/// Float(F32, Exp {input: %1 F32[8, 8] ro, out: %2 F32[8, 8] new})
+/// ```
+///
+/// Tensors are written as `%<id> <dtype><shape> <status>`, the status being `ro` for read-only
+/// tensors, `rw` for consumed tensors and `new` for written tensors. The text can be read back
+/// with [parse_text], so it's suited for test fixtures and minimal reproductions.
+pub fn to_text(operations: &[OperationIr]) -> String {
+    let mut text = String::new();
+
+    for operation in operations {
+        operation
+            .serialize(TextSerializer { out: &mut text })
+            .expect("Operations can be written as text");
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Parse operations written in the [textual form](to_text), one operation per line.
+///
+/// Blank lines and lines starting with `#` are ignored.
+pub fn parse_text(text: &str) -> Result<Vec<OperationIr>, TextError> {
+    let mut operations = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let mut de = TextDeserializer {
+            input: line,
+            position: 0,
+        };
+        let parsed = OperationIr::deserialize(&mut de).and_then(|operation| {
+            de.skip_whitespace();
+            match de.position == line.len() {
+                true => Ok(operation),
+                false => Err(de::Error::custom(
+                    "unexpected characters after the operation",
+                )),
+            }
+        });
+
+        match parsed {
+            Ok(operation) => operations.push(operation),
+            Err(err) => {
+                return Err(TextError {
+                    line: index + 1,
+                    column: de.position + 1,
+                    message: err.message,
+                });
+            }
+        }
+    }
+
+    Ok(operations)
+}
+
+/// The error returned when operations can't be [parsed](parse_text) from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// The column of the error in bytes, starting at 1.
+    pub column: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl Display for TextError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!(
+            "Invalid operation at {}:{}: {}",
+            self.line, self.column, self.message
+        ))
+    }
+}
+
+impl core::error::Error for TextError {}
+
+impl ser::Error for TextError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self {
+            line: 0,
+            column: 0,
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl de::Error for TextError {
+    fn custom<T: Display>(msg: T) -> Self {
+        <Self as ser::Error>::custom(msg)
+    }
+}
+
+/// The statuses of tensors, as written in the textual form.
+const STATUSES: [(&str, &str); 3] = [("ReadOnly", "ro"), ("ReadWrite", "rw"), ("NotInit", "new")];
+
+struct TextSerializer<'a> {
+    out: &'a mut String,
+}
+
+/// Writes the elements of a sequence, a tuple, a map or a struct between delimiters.
+struct Compound<'a> {
+    out: &'a mut String,
+    first: bool,
+    close: &'static str,
+}
+
+/// Writes structs, tensors and their ids having a dedicated syntax.
+enum StructWriter<'a> {
+    Plain(Compound<'a>),
+    Special {
+        out: &'a mut String,
+        name: &'static str,
+        fields: Vec<(&'static str, String)>,
+    },
+}
+
+impl<'a> Compound<'a> {
+    fn new(out: &'a mut String, open: &str, close: &'static str) -> Self {
+        out.push_str(open);
+
+        Self {
+            out,
+            first: true,
+            close,
+        }
+    }
+
+    fn separate(&mut self) {
+        if !self.first {
+            self.out.push_str(", ");
+        }
+        self.first = false;
+    }
+
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TextError> {
+        self.separate();
+        value.serialize(TextSerializer { out: self.out })
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<(), TextError> {
+        self.separate();
+        self.out.push_str(key);
+        self.out.push_str(": ");
+        value.serialize(TextSerializer { out: self.out })
+    }
+
+    fn finish(self) -> Result<(), TextError> {
+        self.out.push_str(self.close);
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for TextSerializer<'a> {
+    type Ok = ();
+    type Error = TextError;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = StructWriter<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), TextError> {
+        self.out.push_str(if v { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), TextError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), TextError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), TextError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), TextError> {
+        write!(self.out, "{v}").map_err(ser::Error::custom)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), TextError> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), TextError> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), TextError> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), TextError> {
+        write!(self.out, "{v}").map_err(ser::Error::custom)
+    }
+
+    // The debug form is the shortest one parsed back to the same value.
+    fn serialize_f32(self, v: f32) -> Result<(), TextError> {
+        write!(self.out, "{v:?}").map_err(ser::Error::custom)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), TextError> {
+        write!(self.out, "{v:?}").map_err(ser::Error::custom)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), TextError> {
+        let mut buffer = [0; 4];
+        self.serialize_str(v.encode_utf8(&mut buffer))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), TextError> {
+        write!(self.out, "{v:?}").map_err(ser::Error::custom)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), TextError> {
+        ser::Serialize::serialize(v, self)
+    }
+
+    fn serialize_none(self) -> Result<(), TextError> {
+        self.out.push_str("None");
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), TextError> {
+        self.out.push_str("Some(");
+        value.serialize(TextSerializer { out: self.out })?;
+        self.out.push(')');
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), TextError> {
+        self.out.push_str("()");
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), TextError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), TextError> {
+        self.out.push_str(variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), TextError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), TextError> {
+        let mut inner = String::new();
+        value.serialize(TextSerializer { out: &mut inner })?;
+
+        // Structs follow the name of the variant directly, like struct variants.
+        match inner.starts_with('{') {
+            true => write!(self.out, "{variant} {inner}"),
+            false => write!(self.out, "{variant}({inner})"),
+        }
+        .map_err(ser::Error::custom)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>, TextError> {
+        Ok(Compound::new(self.out, "[", "]"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a>, TextError> {
+        Ok(Compound::new(self.out, "(", ")"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, TextError> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, TextError> {
+        self.out.push_str(variant);
+        Ok(Compound::new(self.out, "(", ")"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>, TextError> {
+        Ok(Compound::new(self.out, "{", "}"))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<StructWriter<'a>, TextError> {
+        Ok(match name {
+            "TensorIr" | "TensorId" => StructWriter::Special {
+                out: self.out,
+                name,
+                fields: Vec::new(),
+            },
+            _ => StructWriter::Plain(Compound::new(self.out, "{", "}")),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, TextError> {
+        self.out.push_str(variant);
+        Ok(Compound::new(self.out, " {", "}"))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = TextError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TextError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), TextError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = TextError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TextError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), TextError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = TextError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TextError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), TextError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = TextError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TextError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), TextError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = TextError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), TextError> {
+        self.element(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TextError> {
+        self.out.push_str(": ");
+        value.serialize(TextSerializer { out: self.out })
+    }
+
+    fn end(self) -> Result<(), TextError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = TextError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), TextError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), TextError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for StructWriter<'_> {
+    type Ok = ();
+    type Error = TextError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), TextError> {
+        match self {
+            Self::Plain(compound) => compound.field(key, value),
+            Self::Special { fields, .. } => {
+                let mut text = String::new();
+                value.serialize(TextSerializer { out: &mut text })?;
+                fields.push((key, text));
+                Ok(())
+            }
+        }
+    }
+
+    fn end(self) -> Result<(), TextError> {
+        let (out, name, fields) = match self {
+            Self::Plain(compound) => return compound.finish(),
+            Self::Special { out, name, fields } => (out, name, fields),
+        };
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, text)| text.as_str())
+                .ok_or_else(|| <TextError as ser::Error>::custom(format!("missing field {key}")))
+        };
+
+        match name {
+            "TensorId" => write!(out, "%{}", field("value")?),
+            _ => {
+                let status = field("status")?;
+                let status = STATUSES
+                    .iter()
+                    .find(|(variant, _)| *variant == status)
+                    .map(|(_, short)| *short)
+                    .ok_or_else(|| {
+                        <TextError as ser::Error>::custom(format!("unknown status {status}"))
+                    })?;
+
+                write!(
+                    out,
+                    "{} {}{} {status}",
+                    field("id")?,
+                    field("dtype")?,
+                    field("shape")?
+                )
+            }
+        }
+        .map_err(ser::Error::custom)
+    }
+}
+
+struct TextDeserializer<'de> {
+    input: &'de str,
+    /// The position of the next character to read, in bytes.
+    position: usize,
+}
+
+impl<'de> TextDeserializer<'de> {
+    fn rest(&self) -> &'de str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), TextError> {
+        match self.peek() {
+            Some(found) if found == expected => {
+                self.position += found.len_utf8();
+                Ok(())
+            }
+            Some(found) => Err(de::Error::custom(format!(
+                "expected '{expected}', found '{found}'"
+            ))),
+            None => Err(de::Error::custom(format!(
+                "expected '{expected}', found the end of the line"
+            ))),
+        }
+    }
+
+    /// The next identifier, number or keyword.
+    fn token(&mut self) -> Result<&'de str, TextError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '+' | '-')))
+            .unwrap_or(rest.len());
+
+        match len {
+            0 => Err(de::Error::custom(match rest.chars().next() {
+                Some(found) => format!("unexpected '{found}'"),
+                None => "unexpected end of the line".to_string(),
+            })),
+            _ => {
+                self.position += len;
+                Ok(&rest[..len])
+            }
+        }
+    }
+
+    fn parse<T: core::str::FromStr>(&mut self, kind: &str) -> Result<T, TextError> {
+        let token = self.token()?;
+        token
+            .parse()
+            .map_err(|_| de::Error::custom(format!("expected {kind}, found '{token}'")))
+    }
+
+    fn string(&mut self) -> Result<String, TextError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+
+        while let Some((index, c)) = chars.next() {
+            let c = match c {
+                '"' => {
+                    self.position += index + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('0') => '\0',
+                    Some('u') => {
+                        let code = chars
+                            .by_ref()
+                            .map(|(_, c)| c)
+                            .skip_while(|c| *c == '{')
+                            .take_while(|c| *c != '}')
+                            .collect::<String>();
+                        u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| de::Error::custom("invalid unicode escape"))?
+                    }
+                    Some(c) => c,
+                    None => break,
+                },
+                c => c,
+            };
+            value.push(c);
+        }
+
+        Err(de::Error::custom("unterminated string"))
+    }
+}
+
+/// The elements of a sequence or a tuple, or the entries of a map or a struct.
+struct Separated<'a, 'de> {
+    de: &'a mut TextDeserializer<'de>,
+    close: char,
+    first: bool,
+}
+
+impl<'a, 'de> Separated<'a, 'de> {
+    fn new(de: &'a mut TextDeserializer<'de>, close: char) -> Self {
+        Self {
+            de,
+            close,
+            first: true,
+        }
+    }
+
+    /// Whether another element follows, consuming its separator.
+    fn has_next(&mut self) -> Result<bool, TextError> {
+        if self.de.peek() == Some(self.close) {
+            return Ok(false);
+        }
+        if !self.first {
+            self.de.expect(',')?;
+        }
+        self.first = false;
+
+        // Trailing separators are allowed.
+        Ok(self.de.peek() != Some(self.close))
+    }
+}
+
+impl<'de> SeqAccess<'de> for Separated<'_, 'de> {
+    type Error = TextError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, TextError> {
+        match self.has_next()? {
+            true => seed.deserialize(&mut *self.de).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Separated<'_, 'de> {
+    type Error = TextError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, TextError> {
+        match self.has_next()? {
+            true => seed.deserialize(&mut *self.de).map(Some),
+            false => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, TextError> {
+        self.de.expect(':')?;
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+/// The fields of a tensor written as `%<id> <dtype><shape> <status>`.
+struct TensorFields<'a, 'de> {
+    de: &'a mut TextDeserializer<'de>,
+    next: usize,
+}
+
+const TENSOR_FIELDS: [&str; 4] = ["id", "dtype", "shape", "status"];
+
+impl<'de> MapAccess<'de> for TensorFields<'_, 'de> {
+    type Error = TextError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, TextError> {
+        match TENSOR_FIELDS.get(self.next) {
+            Some(key) => seed
+                .deserialize::<StrDeserializer<'_, TextError>>(key.into_deserializer())
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, TextError> {
+        self.next += 1;
+
+        if TENSOR_FIELDS[self.next - 1] != "status" {
+            return seed.deserialize(&mut *self.de);
+        }
+
+        self.de.skip_whitespace();
+        let start = self.de.position;
+        let status = self.de.token()?;
+        let variant = STATUSES
+            .iter()
+            .find(|(_, short)| *short == status)
+            .map(|(variant, _)| *variant);
+        let Some(variant) = variant else {
+            // Errors point at the unknown status.
+            self.de.position = start;
+            return Err(de::Error::custom(format!(
+                "unknown tensor status '{status}'"
+            )));
+        };
+        seed.deserialize::<StrDeserializer<'_, TextError>>(variant.into_deserializer())
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut TextDeserializer<'de> {
+    type Error = TextError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, TextError> {
+        Err(de::Error::custom(
+            "the textual form can only be read into known types",
+        ))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        visitor.visit_bool(self.parse("a boolean")?)
+    }
+
+    deserialize_number!(
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    );
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        let value = self.string()?;
+        let mut chars = value.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(de::Error::custom("expected a single character")),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        visitor.visit_byte_buf(Vec::<u8>::deserialize(self)?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        match self.token()? {
+            "None" => visitor.visit_none(),
+            "Some" => {
+                self.expect('(')?;
+                let value = visitor.visit_some(&mut *self)?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            token => Err(de::Error::custom(format!(
+                "expected None or Some, found '{token}'"
+            ))),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        self.expect('(')?;
+        self.expect(')')?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TextError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TextError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        self.expect('[')?;
+        let value = visitor.visit_seq(Separated::new(self, ']'))?;
+        self.expect(']')?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TextError> {
+        self.expect('(')?;
+        let value = visitor.visit_seq(Separated::new(self, ')'))?;
+        self.expect(')')?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TextError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        self.expect('{')?;
+        let value = visitor.visit_map(Separated::new(self, '}'))?;
+        self.expect('}')?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TextError> {
+        match name {
+            "TensorId" => {
+                self.expect('%')?;
+                let value: u64 = self.parse("a tensor id")?;
+                visitor.visit_map(MapDeserializer::new(core::iter::once(("value", value))))
+            }
+            "TensorIr" => visitor.visit_map(TensorFields { de: self, next: 0 }),
+            _ => self.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TextError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        visitor.visit_borrowed_str(self.token()?)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TextError> {
+        self.deserialize_any(visitor)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut TextDeserializer<'de> {
+    type Error = TextError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), TextError> {
+        let variant = seed.deserialize(&mut *self)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut TextDeserializer<'de> {
+    type Error = TextError;
+
+    fn unit_variant(self) -> Result<(), TextError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, TextError> {
+        // Structs follow the name of the variant directly.
+        if self.peek() != Some('(') {
+            return seed.deserialize(self);
+        }
+
+        self.expect('(')?;
+        let value = seed.deserialize(&mut *self)?;
+        self.expect(')')?;
+        Ok(value)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, TextError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TextError> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use burn_tensor::DType;
+
+    use super::*;
+    use crate::{
+        BaseOperationIr, CustomOpIr, FloatOperationIr, ScalarOpIr, SliceOpIr, TensorId, TensorIr,
+        TensorStatus, UnaryOpIr,
+    };
+
+    #[test]
+    fn should_write_and_parse_operations() {
+        let tensor = |id, shape: &[usize], status| TensorIr {
+            id: TensorId::new(id),
+            shape: shape.to_vec(),
+            status,
+            dtype: DType::F32,
+        };
+        let operations = vec![
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::Exp(UnaryOpIr {
+                    input: tensor(1, &[8, 8], TensorStatus::ReadOnly),
+                    out: tensor(2, &[8, 8], TensorStatus::NotInit),
+                }),
+            ),
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::PowfScalar(ScalarOpIr {
+                    lhs: tensor(2, &[8, 8], TensorStatus::ReadWrite),
+                    rhs: -0.1,
+                    out: tensor(3, &[8, 8], TensorStatus::NotInit),
+                }),
+            ),
+            OperationIr::BaseFloat(BaseOperationIr::Slice(SliceOpIr {
+                tensor: tensor(3, &[8, 8], TensorStatus::ReadWrite),
+                ranges: vec![0..4, 2..8],
+                out: tensor(4, &[4, 6], TensorStatus::NotInit),
+            })),
+            OperationIr::Custom(CustomOpIr::new(
+                "top \"k\"",
+                &[tensor(4, &[4, 6], TensorStatus::ReadOnly)],
+                &[],
+            )),
+        ];
+
+        let text = to_text(&operations);
+
+        assert_eq!(
+            text.lines().next(),
+            Some("Float(F32, Exp {input: %1 F32[8, 8] ro, out: %2 F32[8, 8] new})")
+        );
+        assert_eq!(parse_text(&text), Ok(operations));
+    }
+
+    #[test]
+    fn should_locate_parse_errors() {
+        let text = "# Comment\n\nFloat(F32, Exp {input: %1 F32[8] ro, out: %2 F32[8] old})\n";
+
+        let err = parse_text(text).unwrap_err();
+
+        assert_eq!(err.line, 3);
+        assert_eq!(err.column, 53);
+        assert_eq!(err.message, "unknown tensor status 'old'");
+    }
+}
@@ -0,0 +1,482 @@
+use alloc::vec::Vec;
+use burn_tensor::DType;
+use hashbrown::HashMap;
+
+use crate::{
+    BaseOperationIr, BaseTensorKind, BinaryOpIr, BoolOperationIr, FloatOperationIr, IntOperationIr,
+    NumericOperationIr, OperationIr, OperationVisitor, ScalarOpIr, TensorId, TensorStatus,
+    UnaryOpIr,
+};
+
+/// The error returned by [validate] for the first inconsistent operation of a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The position of the operation in the sequence.
+    pub position: usize,
+    /// What is inconsistent.
+    pub kind: ValidationErrorKind,
+}
+
+/// What makes an operation inconsistent with the operations before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// The tensor is read after being consumed or dropped by a previous operation.
+    NotAlive {
+        /// The tensor read.
+        tensor: TensorId,
+    },
+    /// The tensor is written while it's still alive.
+    Redefined {
+        /// The tensor written.
+        tensor: TensorId,
+    },
+    /// The tensor is used with another shape than the one it was created with.
+    ShapeChanged {
+        /// The tensor used.
+        tensor: TensorId,
+        /// The shape of the tensor when it was created or first used.
+        expected: Vec<usize>,
+        /// The shape of the tensor in the operation.
+        found: Vec<usize>,
+    },
+    /// The tensor is used with another data type than the one it was created with.
+    DTypeChanged {
+        /// The tensor used.
+        tensor: TensorId,
+        /// The data type of the tensor when it was created or first used.
+        expected: DType,
+        /// The data type of the tensor in the operation.
+        found: DType,
+    },
+    /// The tensors of the operation have incompatible shapes, e.g. operands that can't be
+    /// broadcast together or an output that doesn't have the shape computed from the inputs.
+    ShapeMismatch {
+        /// The shape required by the other tensors of the operation.
+        expected: Vec<usize>,
+        /// The shape of the tensor.
+        found: Vec<usize>,
+    },
+    /// The tensors of the operation have incompatible data types.
+    DTypeMismatch {
+        /// The data type required by the other tensors of the operation.
+        expected: DType,
+        /// The data type of the tensor.
+        found: DType,
+    },
+}
+
+/// The state of a tensor used by the validated operations.
+struct Tracked {
+    shape: Vec<usize>,
+    dtype: DType,
+    alive: bool,
+}
+
+/// Check that a sequence of operations, e.g. captured from a stream, is consistent.
+///
+/// Every tensor must be read while alive and keep the shape and the data type it was created
+/// with, and element wise operations, matrix multiplications and reshapes must agree on the
+/// shapes, broadcasting included, and the data types of their tensors. Tensors read before
+/// being written are assumed to be created before the sequence.
+///
+/// The other operations are only checked for the consistency of their tensors.
+pub fn validate(operations: &[OperationIr]) -> Result<(), ValidationError> {
+    let mut tensors = HashMap::<TensorId, Tracked>::new();
+
+    for (position, operation) in operations.iter().enumerate() {
+        let error = |kind| ValidationError { position, kind };
+        let nodes = operation.nodes();
+
+        for node in nodes.iter() {
+            let tracked = match (tensors.get_mut(&node.id), node.status) {
+                (Some(tracked), TensorStatus::NotInit) if tracked.alive => {
+                    return Err(error(ValidationErrorKind::Redefined { tensor: node.id }));
+                }
+                (Some(tracked), TensorStatus::NotInit) => {
+                    tracked.shape.clone_from(&node.shape);
+                    tracked.dtype = node.dtype;
+                    continue;
+                }
+                (Some(tracked), _) if !tracked.alive => {
+                    return Err(error(ValidationErrorKind::NotAlive { tensor: node.id }));
+                }
+                (Some(tracked), _) => tracked,
+                (None, _) => {
+                    tensors.insert(
+                        node.id,
+                        Tracked {
+                            shape: node.shape.clone(),
+                            dtype: node.dtype,
+                            alive: node.status != TensorStatus::NotInit,
+                        },
+                    );
+                    continue;
+                }
+            };
+
+            if tracked.shape != node.shape {
+                return Err(error(ValidationErrorKind::ShapeChanged {
+                    tensor: node.id,
+                    expected: tracked.shape.clone(),
+                    found: node.shape.clone(),
+                }));
+            }
+            if tracked.dtype != node.dtype {
+                return Err(error(ValidationErrorKind::DTypeChanged {
+                    tensor: node.id,
+                    expected: tracked.dtype,
+                    found: node.dtype,
+                }));
+            }
+        }
+
+        check_operation(operation).map_err(error)?;
+
+        // Inputs are consumed and outputs created once the whole operation is checked, since a
+        // tensor can be used more than once by the same operation.
+        for node in nodes.iter() {
+            let tracked = tensors.get_mut(&node.id).expect("Tracked tensor");
+            match node.status {
+                TensorStatus::ReadWrite => tracked.alive = false,
+                TensorStatus::NotInit => tracked.alive = true,
+                TensorStatus::ReadOnly => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// How the output of an element wise operation relates to its inputs.
+#[derive(Clone, Copy, PartialEq)]
+enum Output {
+    /// The output has the data type of the inputs.
+    SameDType,
+    /// The output has another data type, e.g. for comparisons and casts.
+    OtherDType,
+}
+
+fn check_operation(operation: &OperationIr) -> Result<(), ValidationErrorKind> {
+    let mut checker = OperationChecker { result: Ok(()) };
+    operation.accept(&mut checker);
+    checker.result
+}
+
+/// Checks the shapes and data types of the operation it visits. Module, init, custom and drop
+/// operations are accepted as is.
+struct OperationChecker {
+    result: Result<(), ValidationErrorKind>,
+}
+
+impl OperationVisitor<'_> for OperationChecker {
+    fn visit_base(&mut self, _kind: BaseTensorKind, op: &BaseOperationIr) {
+        self.result = match op {
+            BaseOperationIr::Equal(op) => check_binary(op, Output::OtherDType),
+            BaseOperationIr::Cast(op) => check_unary(op, Output::OtherDType),
+            BaseOperationIr::Reshape(op) => {
+                let num_elements = |shape: &[usize]| shape.iter().product::<usize>();
+                expect_dtype(op.input.dtype, op.out.dtype).and_then(|_| {
+                    match num_elements(&op.input.shape) == num_elements(&op.out.shape) {
+                        true => Ok(()),
+                        false => Err(ValidationErrorKind::ShapeMismatch {
+                            expected: op.input.shape.clone(),
+                            found: op.out.shape.clone(),
+                        }),
+                    }
+                })
+            }
+            _ => Ok(()),
+        };
+    }
+
+    fn visit_numeric_float(&mut self, _dtype: DType, op: &NumericOperationIr<f32>) {
+        self.result = check_numeric(op);
+    }
+
+    fn visit_numeric_int(&mut self, _dtype: DType, op: &NumericOperationIr<i32>) {
+        self.result = check_numeric(op);
+    }
+
+    fn visit_float(&mut self, _dtype: DType, op: &FloatOperationIr) {
+        self.result = match op {
+            FloatOperationIr::Matmul(op) => check_matmul(op),
+            FloatOperationIr::PowfScalar(op) => check_scalar(op, Output::SameDType),
+            FloatOperationIr::IntoInt(op) => check_unary(op, Output::OtherDType),
+            FloatOperationIr::Exp(op)
+            | FloatOperationIr::Log(op)
+            | FloatOperationIr::Erf(op)
+            | FloatOperationIr::Sqrt(op)
+            | FloatOperationIr::Cos(op)
+            | FloatOperationIr::Sin(op)
+            | FloatOperationIr::Tanh(op)
+            | FloatOperationIr::Round(op)
+            | FloatOperationIr::Floor(op)
+            | FloatOperationIr::Ceil(op)
+            | FloatOperationIr::Recip(op) => check_unary(op, Output::SameDType),
+            _ => Ok(()),
+        };
+    }
+
+    fn visit_int(&mut self, op: &IntOperationIr) {
+        self.result = match op {
+            IntOperationIr::BitwiseAnd(op)
+            | IntOperationIr::BitwiseOr(op)
+            | IntOperationIr::BitwiseXor(op)
+            | IntOperationIr::BitwiseLeftShift(op)
+            | IntOperationIr::BitwiseRightShift(op) => check_binary(op, Output::SameDType),
+            IntOperationIr::BitwiseAndScalar(op)
+            | IntOperationIr::BitwiseOrScalar(op)
+            | IntOperationIr::BitwiseXorScalar(op)
+            | IntOperationIr::BitwiseLeftShiftScalar(op)
+            | IntOperationIr::BitwiseRightShiftScalar(op) => check_scalar(op, Output::SameDType),
+            IntOperationIr::BitwiseNot(op) => check_unary(op, Output::SameDType),
+            IntOperationIr::IntoFloat(op) => check_unary(op, Output::OtherDType),
+        };
+    }
+
+    fn visit_bool(&mut self, op: &BoolOperationIr) {
+        self.result = match op {
+            BoolOperationIr::And(op) | BoolOperationIr::Or(op) => {
+                check_binary(op, Output::SameDType)
+            }
+            BoolOperationIr::Not(op) => check_unary(op, Output::SameDType),
+            BoolOperationIr::IntoFloat(op) | BoolOperationIr::IntoInt(op) => {
+                check_unary(op, Output::OtherDType)
+            }
+        };
+    }
+}
+
+fn check_numeric<E>(op: &NumericOperationIr<E>) -> Result<(), ValidationErrorKind> {
+    match op {
+        NumericOperationIr::Add(op)
+        | NumericOperationIr::Sub(op)
+        | NumericOperationIr::Div(op)
+        | NumericOperationIr::Rem(op)
+        | NumericOperationIr::Mul(op)
+        | NumericOperationIr::Powf(op) => check_binary(op, Output::SameDType),
+        NumericOperationIr::Greater(op)
+        | NumericOperationIr::GreaterEqual(op)
+        | NumericOperationIr::Lower(op)
+        | NumericOperationIr::LowerEqual(op) => check_binary(op, Output::OtherDType),
+        NumericOperationIr::AddScalar(op)
+        | NumericOperationIr::SubScalar(op)
+        | NumericOperationIr::DivScalar(op)
+        | NumericOperationIr::RemScalar(op)
+        | NumericOperationIr::MulScalar(op) => check_scalar(op, Output::SameDType),
+        NumericOperationIr::EqualElem(op)
+        | NumericOperationIr::GreaterElem(op)
+        | NumericOperationIr::GreaterEqualElem(op)
+        | NumericOperationIr::LowerElem(op)
+        | NumericOperationIr::LowerEqualElem(op) => check_scalar(op, Output::OtherDType),
+        NumericOperationIr::Abs(op) => check_unary(op, Output::SameDType),
+        _ => Ok(()),
+    }
+}
+
+fn check_binary(op: &BinaryOpIr, output: Output) -> Result<(), ValidationErrorKind> {
+    expect_dtype(op.lhs.dtype, op.rhs.dtype)?;
+    if output == Output::SameDType {
+        expect_dtype(op.lhs.dtype, op.out.dtype)?;
+    }
+
+    let shape = broadcast(&op.lhs.shape, &op.rhs.shape)?;
+    expect_shape(&shape, &op.out.shape)
+}
+
+fn check_unary(op: &UnaryOpIr, output: Output) -> Result<(), ValidationErrorKind> {
+    if output == Output::SameDType {
+        expect_dtype(op.input.dtype, op.out.dtype)?;
+    }
+
+    expect_shape(&op.input.shape, &op.out.shape)
+}
+
+fn check_scalar<E>(op: &ScalarOpIr<E>, output: Output) -> Result<(), ValidationErrorKind> {
+    if output == Output::SameDType {
+        expect_dtype(op.lhs.dtype, op.out.dtype)?;
+    }
+
+    expect_shape(&op.lhs.shape, &op.out.shape)
+}
+
+fn check_matmul(op: &BinaryOpIr) -> Result<(), ValidationErrorKind> {
+    let (lhs, rhs) = (&op.lhs.shape, &op.rhs.shape);
+    expect_dtype(op.lhs.dtype, op.rhs.dtype)?;
+
+    let mismatch = || ValidationErrorKind::ShapeMismatch {
+        expected: lhs.clone(),
+        found: rhs.clone(),
+    };
+    let rank = lhs.len();
+    if rank < 2 || rhs.len() != rank || lhs[rank - 1] != rhs[rank - 2] {
+        return Err(mismatch());
+    }
+
+    let mut shape = broadcast(&lhs[..rank - 2], &rhs[..rank - 2]).map_err(|_| mismatch())?;
+    shape.extend([lhs[rank - 2], rhs[rank - 1]]);
+    expect_shape(&shape, &op.out.shape)
+}
+
+/// The shape of two operands broadcast together.
+fn broadcast(lhs: &[usize], rhs: &[usize]) -> Result<Vec<usize>, ValidationErrorKind> {
+    let mismatch = || ValidationErrorKind::ShapeMismatch {
+        expected: lhs.to_vec(),
+        found: rhs.to_vec(),
+    };
+
+    if lhs.len() != rhs.len() {
+        return Err(mismatch());
+    }
+
+    lhs.iter()
+        .zip(rhs)
+        .map(|(lhs, rhs)| match (lhs, rhs) {
+            (lhs, rhs) if lhs == rhs => Ok(*lhs),
+            (1, dim) | (dim, 1) => Ok(*dim),
+            _ => Err(mismatch()),
+        })
+        .collect()
+}
+
+fn expect_shape(expected: &[usize], found: &[usize]) -> Result<(), ValidationErrorKind> {
+    match expected == found {
+        true => Ok(()),
+        false => Err(ValidationErrorKind::ShapeMismatch {
+            expected: expected.to_vec(),
+            found: found.to_vec(),
+        }),
+    }
+}
+
+fn expect_dtype(expected: DType, found: DType) -> Result<(), ValidationErrorKind> {
+    match expected == found {
+        true => Ok(()),
+        false => Err(ValidationErrorKind::DTypeMismatch { expected, found }),
+    }
+}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("Invalid operation #{}: ", self.position))?;
+
+        match &self.kind {
+            ValidationErrorKind::NotAlive { tensor } => {
+                f.write_fmt(format_args!("{tensor} is read after being consumed"))
+            }
+            ValidationErrorKind::Redefined { tensor } => {
+                f.write_fmt(format_args!("{tensor} is written while still alive"))
+            }
+            ValidationErrorKind::ShapeChanged {
+                tensor,
+                expected,
+                found,
+            } => f.write_fmt(format_args!(
+                "{tensor} has shape {found:?} instead of {expected:?}"
+            )),
+            ValidationErrorKind::DTypeChanged {
+                tensor,
+                expected,
+                found,
+            } => f.write_fmt(format_args!(
+                "{tensor} has data type {found:?} instead of {expected:?}"
+            )),
+            ValidationErrorKind::ShapeMismatch { expected, found } => f.write_fmt(format_args!(
+                "shape {found:?} is incompatible with shape {expected:?}"
+            )),
+            ValidationErrorKind::DTypeMismatch { expected, found } => f.write_fmt(format_args!(
+                "data type {found:?} is incompatible with data type {expected:?}"
+            )),
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::TensorIr;
+
+    fn tensor(id: u64, shape: &[usize], status: TensorStatus) -> TensorIr {
+        TensorIr {
+            id: TensorId::new(id),
+            shape: shape.to_vec(),
+            status,
+            dtype: DType::F32,
+        }
+    }
+
+    fn add(lhs: TensorIr, rhs: TensorIr, out: TensorIr) -> OperationIr {
+        OperationIr::NumericFloat(
+            DType::F32,
+            NumericOperationIr::Add(BinaryOpIr { lhs, rhs, out }),
+        )
+    }
+
+    #[test]
+    fn should_accept_broadcast_operations() {
+        let operations = [
+            add(
+                tensor(1, &[4, 8], TensorStatus::ReadOnly),
+                tensor(2, &[1, 8], TensorStatus::ReadWrite),
+                tensor(3, &[4, 8], TensorStatus::NotInit),
+            ),
+            OperationIr::Drop(tensor(3, &[4, 8], TensorStatus::ReadWrite)),
+        ];
+
+        assert_eq!(validate(&operations), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_inconsistent_operations() {
+        let consumed = [
+            add(
+                tensor(1, &[4, 8], TensorStatus::ReadWrite),
+                tensor(2, &[4, 8], TensorStatus::ReadOnly),
+                tensor(3, &[4, 8], TensorStatus::NotInit),
+            ),
+            add(
+                tensor(1, &[4, 8], TensorStatus::ReadOnly),
+                tensor(3, &[4, 8], TensorStatus::ReadOnly),
+                tensor(4, &[4, 8], TensorStatus::NotInit),
+            ),
+        ];
+        let not_broadcast = [add(
+            tensor(1, &[4, 8], TensorStatus::ReadOnly),
+            tensor(2, &[2, 8], TensorStatus::ReadOnly),
+            tensor(3, &[4, 8], TensorStatus::NotInit),
+        )];
+        let mut mixed = not_broadcast.clone();
+        if let OperationIr::NumericFloat(_, NumericOperationIr::Add(op)) = &mut mixed[0] {
+            op.rhs.shape = vec![4, 8];
+            op.rhs.dtype = DType::F16;
+        }
+
+        assert_eq!(
+            validate(&consumed).unwrap_err(),
+            ValidationError {
+                position: 1,
+                kind: ValidationErrorKind::NotAlive {
+                    tensor: TensorId::new(1)
+                },
+            }
+        );
+        assert_eq!(
+            validate(&not_broadcast).unwrap_err().kind,
+            ValidationErrorKind::ShapeMismatch {
+                expected: vec![4, 8],
+                found: vec![2, 8],
+            }
+        );
+        assert_eq!(
+            validate(&mixed).unwrap_err().kind,
+            ValidationErrorKind::DTypeMismatch {
+                expected: DType::F32,
+                found: DType::F16,
+            }
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use burn_tensor::DType;
+
+use crate::{
+    BaseOperationIr, BoolOperationIr, CustomOpIr, FloatOperationIr, InitOperationIr,
+    IntOperationIr, ModuleOperationIr, NumericOperationIr, OperationIr, TensorIr,
+};
+
+/// The kind of tensor a [base operation](BaseOperationIr) is applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaseTensorKind {
+    /// A float tensor.
+    Float,
+    /// An int tensor.
+    Int,
+    /// A bool tensor.
+    Bool,
+}
+
+/// Visits [operations](OperationIr) by category, so that consumers of the IR only match the
+/// categories they handle instead of the whole operation enum.
+///
+/// Every method does nothing by default. The operation enum is only matched by
+/// [accept](OperationIr::accept), so a new category is a compile error there and nowhere else.
+pub trait OperationVisitor<'a> {
+    /// Visit an operation applied to any kind of tensor.
+    fn visit_base(&mut self, _kind: BaseTensorKind, _op: &'a BaseOperationIr) {}
+    /// Visit a numeric operation on a float tensor of the given data type.
+    fn visit_numeric_float(&mut self, _dtype: DType, _op: &'a NumericOperationIr<f32>) {}
+    /// Visit a numeric operation on an int tensor of the given data type.
+    fn visit_numeric_int(&mut self, _dtype: DType, _op: &'a NumericOperationIr<i32>) {}
+    /// Visit an operation specific to float tensors of the given data type.
+    fn visit_float(&mut self, _dtype: DType, _op: &'a FloatOperationIr) {}
+    /// Visit an operation specific to int tensors.
+    fn visit_int(&mut self, _op: &'a IntOperationIr) {}
+    /// Visit an operation specific to bool tensors.
+    fn visit_bool(&mut self, _op: &'a BoolOperationIr) {}
+    /// Visit a module operation.
+    fn visit_module(&mut self, _op: &'a ModuleOperationIr) {}
+    /// Visit the initialization of a tensor.
+    fn visit_init(&mut self, _op: &'a InitOperationIr) {}
+    /// Visit a custom operation.
+    fn visit_custom(&mut self, _op: &'a CustomOpIr) {}
+    /// Visit the drop of a tensor.
+    fn visit_drop(&mut self, _tensor: &'a TensorIr) {}
+}
+
+impl OperationIr {
+    /// Call the method of the visitor matching the category of the operation.
+    pub fn accept<'a, V: OperationVisitor<'a> + ?Sized>(&'a self, visitor: &mut V) {
+        match self {
+            OperationIr::BaseFloat(op) => visitor.visit_base(BaseTensorKind::Float, op),
+            OperationIr::BaseInt(op) => visitor.visit_base(BaseTensorKind::Int, op),
+            OperationIr::BaseBool(op) => visitor.visit_base(BaseTensorKind::Bool, op),
+            OperationIr::NumericFloat(dtype, op) => visitor.visit_numeric_float(*dtype, op),
+            OperationIr::NumericInt(dtype, op) => visitor.visit_numeric_int(*dtype, op),
+            OperationIr::Float(dtype, op) => visitor.visit_float(*dtype, op),
+            OperationIr::Int(op) => visitor.visit_int(op),
+            OperationIr::Bool(op) => visitor.visit_bool(op),
+            OperationIr::Module(op) => visitor.visit_module(op),
+            OperationIr::Init(op) => visitor.visit_init(op),
+            OperationIr::Custom(op) => visitor.visit_custom(op),
+            OperationIr::Drop(tensor) => visitor.visit_drop(tensor),
+        }
+    }
+}
+
+/// Visit the operations in order, returning the visitor with the state it accumulated.
+pub fn fold<'a, V: OperationVisitor<'a>>(
+    operations: impl IntoIterator<Item = &'a OperationIr>,
+    mut visitor: V,
+) -> V {
+    for operation in operations {
+        operation.accept(&mut visitor);
+    }
+
+    visitor
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+    use crate::{TensorId, TensorStatus, UnaryOpIr};
+
+    /// Collects the tensors dropped or initialized by the operations.
+    #[derive(Default)]
+    struct Lifetimes<'a> {
+        created: Vec<&'a TensorIr>,
+        dropped: Vec<&'a TensorIr>,
+    }
+
+    impl<'a> OperationVisitor<'a> for Lifetimes<'a> {
+        fn visit_init(&mut self, op: &'a InitOperationIr) {
+            self.created.push(&op.out);
+        }
+
+        fn visit_drop(&mut self, tensor: &'a TensorIr) {
+            self.dropped.push(tensor);
+        }
+    }
+
+    #[test]
+    fn should_only_visit_the_implemented_categories() {
+        let tensor = |id, status| TensorIr {
+            id: TensorId::new(id),
+            shape: vec![4],
+            status,
+            dtype: DType::F32,
+        };
+        let operations = [
+            OperationIr::Init(InitOperationIr {
+                out: tensor(1, TensorStatus::NotInit),
+            }),
+            OperationIr::Float(
+                DType::F32,
+                FloatOperationIr::Exp(UnaryOpIr {
+                    input: tensor(1, TensorStatus::ReadWrite),
+                    out: tensor(2, TensorStatus::NotInit),
+                }),
+            ),
+            OperationIr::Drop(tensor(2, TensorStatus::ReadWrite)),
+        ];
+
+        let lifetimes = fold(&operations, Lifetimes::default());
+
+        assert_eq!(lifetimes.created, [&tensor(1, TensorStatus::NotInit)]);
+        assert_eq!(lifetimes.dropped, [&tensor(2, TensorStatus::ReadWrite)]);
+    }
+}
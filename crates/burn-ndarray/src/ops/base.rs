@@ -1,5 +1,6 @@
 use alloc::{vec, vec::Vec};
 use burn_tensor::ElementConversion;
+use burn_tensor::ScatterReduce;
 use burn_tensor::TensorData;
 use burn_tensor::TensorMetadata;
 #[cfg(feature = "simd")]
@@ -552,10 +553,20 @@ where
     }
 
     pub fn scatter<I: NdArrayElement>(
+        dim: usize,
+        tensor: NdArrayTensor<E>,
+        indices: NdArrayTensor<I>,
+        value: NdArrayTensor<E>,
+    ) -> NdArrayTensor<E> {
+        Self::scatter_reduce(dim, tensor, indices, value, ScatterReduce::Sum)
+    }
+
+    pub fn scatter_reduce<I: NdArrayElement>(
         dim: usize,
         mut tensor: NdArrayTensor<E>,
         mut indices: NdArrayTensor<I>,
         mut value: NdArrayTensor<E>,
+        reduce: ScatterReduce,
     ) -> NdArrayTensor<E> {
         let ndims = tensor.shape().num_dims();
         if dim != ndims - 1 {
@@ -584,16 +595,35 @@ where
         let indices = NdArrayOps::reshape(indices, Shape::new([batch_size, size_index])).array;
         let value = NdArrayOps::reshape(value, Shape::new([batch_size, size_value])).array;
         let mut tensor = NdArrayOps::reshape(tensor, Shape::new([batch_size, size_tensor])).array;
+        // Each element of the tensor counts as one element of the mean. Only the mean needs
+        // the counts, so other reductions skip allocating them.
+        let mut counts =
+            (reduce == ScatterReduce::Mean).then(|| Array2::<E>::ones((batch_size, size_tensor)));
 
         for b in 0..batch_size {
             let indices = indices.slice(s!(b, ..));
 
             for (i, index) in indices.iter().enumerate() {
                 let index = index.elem::<i64>() as usize;
-                tensor[[b, index]] += value[[b, i]];
+                let (current, value) = (tensor[[b, index]], value[[b, i]]);
+
+                tensor[[b, index]] = match reduce {
+                    ScatterReduce::Sum | ScatterReduce::Mean => current + value,
+                    ScatterReduce::Prod => current * value,
+                    ScatterReduce::Min if value < current => value,
+                    ScatterReduce::Max if value > current => value,
+                    ScatterReduce::Min | ScatterReduce::Max => current,
+                };
+                if let Some(counts) = counts.as_mut() {
+                    counts[[b, index]] += E::one();
+                }
             }
         }
 
+        if let Some(counts) = counts {
+            tensor = tensor / counts;
+        }
+
         let mut output = NdArrayOps::reshape(
             NdArrayTensor::<E>::new(tensor.into_shared().into_dyn()),
             shape_tensor,
@@ -0,0 +1,155 @@
+use alloc::vec::Vec;
+use burn_tensor::ElementConversion;
+use ndarray::{ArcArray, Array, IxDyn};
+use rustfft::{FftNum, FftPlanner, num_complex::Complex};
+
+use crate::{FloatNdArrayElement, NdArrayTensor};
+
+/// Computes the discrete Fourier transform along `dim` of a complex tensor, whose last dimension
+/// holds the real and imaginary parts.
+pub(crate) fn fft<E: FloatNdArrayElement + FftNum>(
+    tensor: NdArrayTensor<E>,
+    dim: usize,
+    inverse: bool,
+) -> NdArrayTensor<E> {
+    let rank = tensor.array.ndim();
+    let n = tensor.array.shape()[dim];
+    let axes = lane_axes(rank - 1, dim, Some(rank - 1));
+    let (values, shape) = into_lanes(tensor.array, &axes);
+
+    if n == 0 {
+        return from_lanes(values, shape, &axes);
+    }
+
+    let mut buffer = to_complex(&values);
+    let mut planner = FftPlanner::new();
+    let plan = match inverse {
+        true => planner.plan_fft_inverse(n),
+        false => planner.plan_fft_forward(n),
+    };
+    plan.process(&mut buffer);
+
+    if inverse {
+        let scale: E = (1.0 / n as f64).elem();
+        buffer.iter_mut().for_each(|value| *value = *value * scale);
+    }
+
+    from_lanes(from_complex(&buffer), shape, &axes)
+}
+
+/// Computes the discrete Fourier transform along `dim` of a real tensor, keeping only the
+/// `n / 2 + 1` non-redundant frequencies.
+pub(crate) fn rfft<E: FloatNdArrayElement + FftNum>(
+    tensor: NdArrayTensor<E>,
+    dim: usize,
+) -> NdArrayTensor<E> {
+    let rank = tensor.array.ndim();
+    let n = tensor.array.shape()[dim];
+    let num_freqs = n / 2 + 1;
+    let (values, mut shape) = into_lanes(tensor.array, &lane_axes(rank, dim, None));
+
+    let mut buffer = values
+        .iter()
+        .map(|value| Complex::new(*value, 0.elem()))
+        .collect::<Vec<_>>();
+    if n > 0 {
+        FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+    }
+
+    let output = buffer
+        .chunks(n.max(1))
+        .flat_map(|lane| lane.iter().take(num_freqs))
+        .flat_map(|value| [value.re, value.im])
+        .collect::<Vec<_>>();
+
+    *shape.last_mut().unwrap() = num_freqs;
+    shape.push(2);
+
+    from_lanes(output, shape, &lane_axes(rank, dim, Some(rank)))
+}
+
+/// Computes the inverse of [rfft], recovering a real signal of size `n` along `dim` from its
+/// `n / 2 + 1` non-redundant frequencies.
+pub(crate) fn irfft<E: FloatNdArrayElement + FftNum>(
+    tensor: NdArrayTensor<E>,
+    dim: usize,
+    n: usize,
+) -> NdArrayTensor<E> {
+    let rank = tensor.array.ndim();
+    let num_freqs = tensor.array.shape()[dim];
+    let (values, mut shape) = into_lanes(tensor.array, &lane_axes(rank - 1, dim, Some(rank - 1)));
+    let spectrum = to_complex(&values);
+
+    // The other half of the spectrum of a real signal is the conjugate of the first one.
+    let mut buffer = Vec::with_capacity(spectrum.len() / num_freqs.max(1) * n);
+    for lane in spectrum.chunks(num_freqs.max(1)) {
+        buffer.extend((0..n).map(|k| match k < num_freqs {
+            true => lane[k],
+            false => lane[n - k].conj(),
+        }));
+    }
+    if n > 0 {
+        FftPlanner::new().plan_fft_inverse(n).process(&mut buffer);
+    }
+
+    let scale: E = (1.0 / n.max(1) as f64).elem();
+    let output = buffer
+        .iter()
+        .map(|value| value.re * scale)
+        .collect::<Vec<_>>();
+
+    shape.pop();
+    *shape.last_mut().unwrap() = n;
+
+    from_lanes(output, shape, &lane_axes(rank - 1, dim, None))
+}
+
+/// The axes of a tensor of the given rank, excluding the complex one if any, ordered so that the
+/// lanes along `dim` are contiguous.
+fn lane_axes(rank: usize, dim: usize, complex: Option<usize>) -> Vec<usize> {
+    (0..rank)
+        .filter(|axis| *axis != dim)
+        .chain([dim])
+        .chain(complex)
+        .collect()
+}
+
+/// The values of the array in the order of the permuted axes, with the permuted shape.
+fn into_lanes<E: Copy>(array: ArcArray<E, IxDyn>, axes: &[usize]) -> (Vec<E>, Vec<usize>) {
+    let array = array.permuted_axes(axes);
+    let shape = array.shape().to_vec();
+
+    (array.iter().copied().collect(), shape)
+}
+
+/// The tensor of the values in the order of the permuted axes, with its axes restored.
+fn from_lanes<E: FloatNdArrayElement>(
+    values: Vec<E>,
+    shape: Vec<usize>,
+    axes: &[usize],
+) -> NdArrayTensor<E> {
+    let mut inverse = alloc::vec![0; axes.len()];
+    for (i, axis) in axes.iter().enumerate() {
+        inverse[*axis] = i;
+    }
+
+    let array = Array::from_shape_vec(IxDyn(&shape), values)
+        .unwrap()
+        .permuted_axes(inverse);
+
+    NdArrayTensor::new(array.into_shared())
+}
+
+fn to_complex<E: FftNum>(values: &[E]) -> Vec<Complex<E>> {
+    values
+        .chunks(2)
+        .map(|pair| Complex::new(pair[0], pair[1]))
+        .collect()
+}
+
+fn from_complex<E: FftNum>(values: &[Complex<E>]) -> Vec<E> {
+    values
+        .iter()
+        .flat_map(|value| [value.re, value.im])
+        .collect()
+}
@@ -19,7 +19,7 @@ use crate::{NdArray, tensor::NdArrayTensor};
 use crate::{NdArrayDevice, SEED};
 
 // Workspace crates
-use burn_tensor::{DType, Shape, TensorData, backend::Backend};
+use burn_tensor::{DType, ScatterReduce, Shape, TensorData, backend::Backend};
 
 use super::{NdArrayBitOps, NdArrayMathOps, NdArrayOps};
 
@@ -226,6 +226,16 @@ impl<E: FloatNdArrayElement, I: IntNdArrayElement, Q: QuantElement> IntTensorOps
         NdArrayMathOps::scatter(dim, tensor, indices, value)
     }
 
+    fn int_scatter_reduce(
+        dim: usize,
+        tensor: NdArrayTensor<I>,
+        indices: NdArrayTensor<I>,
+        value: NdArrayTensor<I>,
+        reduce: ScatterReduce,
+    ) -> NdArrayTensor<I> {
+        NdArrayMathOps::scatter_reduce(dim, tensor, indices, value, reduce)
+    }
+
     fn int_select(
         tensor: NdArrayTensor<I>,
         dim: usize,
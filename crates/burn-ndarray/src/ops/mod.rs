@@ -13,6 +13,8 @@ pub(crate) mod adaptive_avgpool;
 pub(crate) mod avgpool;
 pub(crate) mod conv;
 pub(crate) mod deform_conv;
+#[cfg(feature = "std")]
+pub(crate) mod fft;
 pub(crate) mod interpolate;
 pub(crate) mod macros;
 pub(crate) mod matmul;
@@ -12,7 +12,7 @@ use crate::{NdArrayDevice, NdArrayTensorFloat, SEED, execute_with_float_dtype};
 
 // Workspace crates
 use burn_common::rand::get_seeded_rng;
-use burn_tensor::{DType, Distribution, FloatDType};
+use burn_tensor::{DType, Distribution, FloatDType, ScatterReduce};
 use burn_tensor::{ElementConversion, Shape, TensorData, backend::Backend, ops::FloatTensorOps};
 
 #[cfg(not(feature = "std"))]
@@ -130,6 +130,27 @@ impl<E: FloatNdArrayElement, I: IntNdArrayElement, Q: QuantElement> FloatTensorO
         execute_with_float_dtype!((lhs, rhs), matmul)
     }
 
+    #[cfg(feature = "std")]
+    fn float_fft(tensor: FloatTensor<Self>, dim: usize, inverse: bool) -> FloatTensor<Self> {
+        execute_with_float_dtype!(tensor, E, |tensor: NdArrayTensor<E>| {
+            super::fft::fft::<E>(tensor, dim, inverse)
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn float_rfft(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
+        execute_with_float_dtype!(tensor, E, |tensor: NdArrayTensor<E>| {
+            super::fft::rfft::<E>(tensor, dim)
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn float_irfft(tensor: FloatTensor<Self>, dim: usize, n: usize) -> FloatTensor<Self> {
+        execute_with_float_dtype!(tensor, E, |tensor: NdArrayTensor<E>| {
+            super::fft::irfft::<E>(tensor, dim, n)
+        })
+    }
+
     fn float_neg(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
         Self::float_mul_scalar(tensor, (-1f32).elem::<E>())
     }
@@ -167,6 +188,18 @@ impl<E: FloatNdArrayElement, I: IntNdArrayElement, Q: QuantElement> FloatTensorO
         ))
     }
 
+    fn float_scatter_reduce(
+        dim: usize,
+        tensor: FloatTensor<Self>,
+        indices: NdArrayTensor<I>,
+        value: FloatTensor<Self>,
+        reduce: ScatterReduce,
+    ) -> FloatTensor<Self> {
+        execute_with_float_dtype!((tensor, value), |tensor, value| {
+            NdArrayMathOps::scatter_reduce(dim, tensor, indices, value, reduce)
+        })
+    }
+
     fn float_select(
         tensor: FloatTensor<Self>,
         dim: usize,
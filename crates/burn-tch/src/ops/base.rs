@@ -419,21 +419,22 @@ impl TchOps {
         TchTensor::from_existing(broadcasted_tensor, storage)
     }
 
-    pub fn sort(tensor: TchTensor, dim: usize, descending: bool) -> TchTensor {
-        TchTensor::new(tensor.tensor.sort(dim as i64, descending).0)
+    pub fn sort(tensor: TchTensor, dim: usize, descending: bool, stable: bool) -> TchTensor {
+        TchTensor::new(tensor.tensor.sort_stable(stable, dim as i64, descending).0)
     }
 
     pub fn sort_with_indices(
         tensor: TchTensor,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (TchTensor, TchTensor) {
-        let sorted = tensor.tensor.sort(dim as i64, descending);
+        let sorted = tensor.tensor.sort_stable(stable, dim as i64, descending);
         (TchTensor::new(sorted.0), TchTensor::new(sorted.1))
     }
 
-    pub fn argsort(tensor: TchTensor, dim: usize, descending: bool) -> TchTensor {
-        TchTensor::new(tensor.tensor.argsort(dim as i64, descending))
+    pub fn argsort(tensor: TchTensor, dim: usize, descending: bool, stable: bool) -> TchTensor {
+        TchTensor::new(tensor.tensor.argsort_stable(stable, dim as i64, descending))
     }
 
     pub fn bitwise_and(lhs: TchTensor, rhs: TchTensor) -> TchTensor {
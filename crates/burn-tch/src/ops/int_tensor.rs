@@ -392,12 +392,22 @@ impl<E: TchElement, Q: QuantElement> IntTensorOps<Self> for LibTorch<E, Q> {
         TchOps::expand(tensor, shape)
     }
 
-    fn int_sort(tensor: IntTensor<Self>, dim: usize, descending: bool) -> IntTensor<Self> {
-        TchOps::sort(tensor, dim, descending)
+    fn int_sort(
+        tensor: IntTensor<Self>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<Self> {
+        TchOps::sort(tensor, dim, descending, stable)
     }
 
-    fn int_argsort(tensor: IntTensor<Self>, dim: usize, descending: bool) -> IntTensor<Self> {
-        TchOps::argsort(tensor, dim, descending)
+    fn int_argsort(
+        tensor: IntTensor<Self>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<Self> {
+        TchOps::argsort(tensor, dim, descending, stable)
     }
 
     fn bitwise_and(lhs: IntTensor<Self>, rhs: IntTensor<Self>) -> IntTensor<Self> {
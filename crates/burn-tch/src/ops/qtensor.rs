@@ -260,9 +260,10 @@ impl<E: TchElement, Q: QuantElement> QTensorOps<Self> for LibTorch<E, Q> {
         tensor: QuantizedTensor<Self>,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> QuantizedTensor<Self> {
         TchQTensor {
-            qtensor: TchOps::sort(tensor.qtensor, dim, descending),
+            qtensor: TchOps::sort(tensor.qtensor, dim, descending, stable),
             scheme: tensor.scheme,
         }
     }
@@ -271,8 +272,9 @@ impl<E: TchElement, Q: QuantElement> QTensorOps<Self> for LibTorch<E, Q> {
         tensor: QuantizedTensor<Self>,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (QuantizedTensor<Self>, IntTensor<Self>) {
-        let (qtensor, indices) = TchOps::sort_with_indices(tensor.qtensor, dim, descending);
+        let (qtensor, indices) = TchOps::sort_with_indices(tensor.qtensor, dim, descending, stable);
         let tensor = TchQTensor {
             qtensor,
             scheme: tensor.scheme,
@@ -280,7 +282,12 @@ impl<E: TchElement, Q: QuantElement> QTensorOps<Self> for LibTorch<E, Q> {
         (tensor, indices)
     }
 
-    fn q_argsort(tensor: QuantizedTensor<Self>, dim: usize, descending: bool) -> IntTensor<Self> {
-        TchOps::argsort(tensor.qtensor, dim, descending)
+    fn q_argsort(
+        tensor: QuantizedTensor<Self>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<Self> {
+        TchOps::argsort(tensor.qtensor, dim, descending, stable)
     }
 }
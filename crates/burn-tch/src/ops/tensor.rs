@@ -431,20 +431,26 @@ impl<E: TchElement, Q: QuantElement> FloatTensorOps<Self> for LibTorch<E, Q> {
         TchOps::expand(tensor, shape)
     }
 
-    fn float_sort(tensor: TchTensor, dim: usize, descending: bool) -> TchTensor {
-        TchOps::sort(tensor, dim, descending)
+    fn float_sort(tensor: TchTensor, dim: usize, descending: bool, stable: bool) -> TchTensor {
+        TchOps::sort(tensor, dim, descending, stable)
     }
 
     fn float_sort_with_indices(
         tensor: TchTensor,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (TchTensor, TchTensor) {
-        TchOps::sort_with_indices(tensor, dim, descending)
+        TchOps::sort_with_indices(tensor, dim, descending, stable)
     }
 
-    fn float_argsort(tensor: TchTensor, dim: usize, descending: bool) -> IntTensor<Self> {
-        TchOps::argsort(tensor, dim, descending)
+    fn float_argsort(
+        tensor: TchTensor,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<Self> {
+        TchOps::argsort(tensor, dim, descending, stable)
     }
 
     fn float_cast(tensor: TchTensor, dtype: FloatDType) -> TchTensor {
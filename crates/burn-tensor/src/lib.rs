@@ -52,6 +52,9 @@ mod cube {
                 crate::DType::U8 => Elem::UInt(UIntKind::U8),
                 crate::DType::Bool => Elem::Bool,
                 crate::DType::QFloat(_) => panic!("quantized type is not supported yet."),
+                crate::DType::Complex64 | crate::DType::Complex32 => {
+                    unreachable!("Backends never store complex data")
+                }
             }
         }
     }
@@ -1256,6 +1256,8 @@ where
     }
 
     /// Create a tensor from the given data on the given device.
+    ///
+    /// Complex data can only be converted into a [complex tensor](crate::ComplexTensor).
     pub fn from_data<T>(data: T, device: &B::Device) -> Self
     where
         T: Into<TensorData>,
@@ -1265,6 +1267,7 @@ where
             "From Data",
             data.shape.as_slice()
         ));
+        check!(TensorCheck::creation_dtype("From Data", data.dtype));
         Self::new(K::from_data(data, device))
     }
 
@@ -1278,6 +1281,8 @@ where
             "From Data",
             data.shape.as_slice()
         ));
+        check!(TensorCheck::creation_dtype("From Data", data.dtype));
+        check!(TensorCheck::creation_dtype("From Data", dtype));
         Self::new(K::from_data_dtype(data, device, dtype))
     }
 
@@ -1,4 +1,5 @@
-use crate::{BasicOps, Numeric, Shape, Tensor, backend::Backend, cast::ToElement};
+use super::einsum::EinsumEquation;
+use crate::{BasicOps, DType, Numeric, Shape, Tensor, backend::Backend, cast::ToElement};
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec;
@@ -102,6 +103,22 @@ impl TensorCheck {
         check
     }
 
+    pub(crate) fn creation_dtype(ops: &str, dtype: DType) -> Self {
+        let mut check = Self::Ok;
+
+        if dtype.is_complex() {
+            check = check.register(
+                ops,
+                TensorError::new("Complex data can only be converted into a complex tensor.")
+                    .details(format!(
+                        "Data type: '{dtype:?}'. Use `ComplexTensor::from_data` instead."
+                    )),
+            );
+        }
+
+        check
+    }
+
     pub(crate) fn narrow<B: Backend, const D: usize, K: BasicOps<B>>(
         tensor: &Tensor<B, D, K>,
         dim: usize,
@@ -596,6 +613,152 @@ impl TensorCheck {
         check
     }
 
+    pub(crate) fn einsum<B: Backend, const D1: usize, const D2: usize, const D3: usize>(
+        equation: &str,
+        lhs: &Tensor<B, D1>,
+        rhs: &Tensor<B, D2>,
+    ) -> Self {
+        let mut check = Self::Ok;
+
+        check = check.binary_ops_device("Einsum", &lhs.device(), &rhs.device());
+
+        let shape_lhs = lhs.shape();
+        let shape_rhs = rhs.shape();
+
+        match EinsumEquation::parse(equation, &[&shape_lhs.dims, &shape_rhs.dims]) {
+            Ok(parsed) => {
+                let rank = parsed.output.len().max(1);
+
+                if rank != D3 {
+                    check = check.register(
+                        "Einsum",
+                        TensorError::new(format!(
+                            "The output of the equation has rank {rank}, but the output tensor \
+                             has rank {D3}."
+                        ))
+                        .details(format!(
+                            "Equation '{equation}', output shape {:?}.",
+                            parsed.output_shape()
+                        )),
+                    );
+                }
+            }
+            Err(err) => {
+                check = check.register(
+                    "Einsum",
+                    TensorError::new(err).details(format!(
+                        "Equation '{equation}', lhs shape {:?}, rhs shape {:?}.",
+                        shape_lhs.dims, shape_rhs.dims
+                    )),
+                );
+            }
+        }
+
+        check
+    }
+
+    pub(crate) fn fft<const D1: usize, const D2: usize>(
+        ops: &str,
+        shape: &Shape,
+        dim: usize,
+        complex_input: bool,
+        complex_output: bool,
+    ) -> Self {
+        let mut check = Self::Ok;
+        let expected = match (complex_input, complex_output) {
+            (false, true) => D1 + 1,
+            (true, false) => D1.saturating_sub(1),
+            _ => D1,
+        };
+
+        if expected != D2 {
+            check = check.register(
+                ops,
+                TensorError::new(format!(
+                    "The output tensor should have rank {expected}, but got {D2}."
+                ))
+                .details(format!("Input shape {:?}.", shape.dims)),
+            );
+        }
+
+        if !complex_input {
+            if dim >= D1 {
+                check = check.register(
+                    ops,
+                    TensorError::new("Given dimension is higher than the tensor rank.")
+                        .details(format!("Tensor rank: '{D1}', given dimension: '{dim}'.")),
+                );
+            }
+
+            return check;
+        }
+
+        if D1 < 2 || shape.dims[D1 - 1] != 2 {
+            check = check.register(
+                ops,
+                TensorError::new(
+                    "The input tensor should be complex, with a last dimension of size 2 holding \
+                     the real and imaginary parts.",
+                )
+                .details(format!("Input shape {:?}.", shape.dims)),
+            );
+        } else if dim >= D1 - 1 {
+            check = check.register(
+                ops,
+                TensorError::new("The transformed dimension can't be the complex dimension.")
+                    .details(format!("Input shape {:?}, dim {dim}.", shape.dims)),
+            );
+        }
+
+        check
+    }
+
+    pub(crate) fn complex_parts(ops: &str, shape_real: &Shape, shape_imag: &Shape) -> Self {
+        let mut check = Self::Ok;
+
+        if shape_real != shape_imag {
+            check = check.register(
+                ops,
+                TensorError::new("The real and imaginary parts should have the same shape.")
+                    .details(format!(
+                        "Real shape {:?}, imaginary shape {:?}.",
+                        shape_real.dims, shape_imag.dims
+                    )),
+            );
+        }
+
+        check
+    }
+
+    pub(crate) fn complex_interleaved<const D: usize, const D2: usize>(
+        ops: &str,
+        shape: &Shape,
+    ) -> Self {
+        let mut check = Self::Ok;
+
+        if D2 != D + 1 {
+            check = check.register(
+                ops,
+                TensorError::new(format!(
+                    "The interleaved tensor should have rank {}, but got {D2}.",
+                    D + 1
+                ))
+                .details(format!("Complex tensor rank {D}.")),
+            );
+        } else if shape.dims[D] != 2 {
+            check = check.register(
+                ops,
+                TensorError::new(
+                    "The interleaved tensor should have a last dimension of size 2 holding the \
+                     real and imaginary parts.",
+                )
+                .details(format!("Interleaved shape {:?}.", shape.dims)),
+            );
+        }
+
+        check
+    }
+
     pub(crate) fn stack<B: Backend, const D1: usize, K: BasicOps<B>, const D2: usize>(
         tensors: &[Tensor<B, D1, K>],
         dim: usize,
@@ -891,6 +1054,43 @@ impl TensorCheck {
         check
     }
 
+    pub(crate) fn mask(ops: &str, shape: &Shape, shape_mask: &Shape) -> Self {
+        let mut check = Self::Ok;
+
+        if shape != shape_mask {
+            check = check.register(
+                ops,
+                TensorError::new("The mask shape should be the same as the tensor shape.").details(
+                    format!(
+                        "The shape differs: {:?} != {:?}",
+                        shape.dims, shape_mask.dims
+                    ),
+                ),
+            );
+        }
+
+        check
+    }
+
+    pub(crate) fn mask_assign(shape: &Shape, shape_mask: &Shape, shape_values: &Shape) -> Self {
+        let ops = "Mask Assign";
+        let mut check = Self::mask(ops, shape, shape_mask);
+        let num_elements = shape.num_elements();
+        let num_values = shape_values.num_elements();
+
+        if num_values > num_elements {
+            check = check.register(
+                ops,
+                TensorError::new("There can't be more values than elements in the tensor.")
+                    .details(format!(
+                        "The tensor has {num_elements} elements, but there are {num_values} values."
+                    )),
+            );
+        }
+
+        check
+    }
+
     pub(crate) fn select<const D: usize>(dim: usize) -> Self {
         Self::check_select_basic::<D>(Self::Ok, "select", dim)
     }
@@ -1239,6 +1439,101 @@ impl TensorCheck {
     }
 }
 
+#[cfg(feature = "experimental-named-tensor")]
+impl TensorCheck {
+    /// Checks that the names of the dimensions are unique.
+    pub(crate) fn dim_names(ops: &str, names: &[&str]) -> Self {
+        let mut check = TensorCheck::Ok;
+
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                check = check.register(
+                    ops,
+                    TensorError::new(format!("Dimension '{name}' is named more than once."))
+                        .details(format!("The dimensions are {names:?}.")),
+                );
+            }
+        }
+
+        check
+    }
+
+    /// Checks that a dimension with the given name exists.
+    pub(crate) fn dim_name(ops: &str, names: &[String], name: &str) -> Self {
+        match names.iter().any(|n| n == name) {
+            true => TensorCheck::Ok,
+            false => TensorCheck::Ok.register(
+                ops,
+                TensorError::new(format!("Dimension '{name}' doesn't exist."))
+                    .details(format!("The dimensions are {names:?}.")),
+            ),
+        }
+    }
+
+    /// Checks that the target names are unique and include all the names of the dimensions.
+    pub(crate) fn dim_names_target(ops: &str, names: &[String], target: &[&str]) -> Self {
+        let mut check = Self::dim_names(ops, target);
+
+        for name in names {
+            if !target.contains(&name.as_str()) {
+                check = check.register(
+                    ops,
+                    TensorError::new(format!("Dimension '{name}' is missing from the target."))
+                        .details(format!(
+                            "The dimensions are {names:?}, the target dimensions are {target:?}."
+                        )),
+                );
+            }
+        }
+
+        check
+    }
+
+    /// Checks that the dimensions of the right hand side can be broadcasted to the dimensions with
+    /// the same names on the left hand side.
+    pub(crate) fn dim_names_broadcast(
+        ops: &str,
+        lhs: &[String],
+        lhs_shape: &Shape,
+        rhs: &[String],
+        rhs_shape: &Shape,
+    ) -> Self {
+        let mut check = TensorCheck::Ok;
+
+        for (name, size_rhs) in rhs.iter().zip(rhs_shape.dims.iter()) {
+            let Some(dim) = lhs.iter().position(|n| n == name) else {
+                check = check.register(
+                    ops,
+                    TensorError::new(format!(
+                        "Dimension '{name}' of the rhs tensor doesn't exist in the lhs tensor."
+                    ))
+                    .details(format!(
+                        "Lhs tensor dimensions {lhs:?}, Rhs tensor dimensions {rhs:?}."
+                    )),
+                );
+                continue;
+            };
+            let size_lhs = lhs_shape.dims[dim];
+
+            if size_lhs != *size_rhs && size_lhs != 1 && *size_rhs != 1 {
+                check = check.register(
+                    ops,
+                    TensorError::new("The provided tensors have incompatible shapes.").details(
+                        format!(
+                            "Incompatible size at dimension '{name}' => '{size_lhs} != {size_rhs}', \
+                             which can't be broadcasted. Lhs tensor shape {:?}, Rhs tensor shape \
+                             {:?}.",
+                            lhs_shape.dims, rhs_shape.dims,
+                        ),
+                    ),
+                );
+            }
+        }
+
+        check
+    }
+}
+
 pub(crate) struct FailedTensorCheck {
     ops: String,
     errors: Vec<TensorError>,
@@ -1326,6 +1621,17 @@ mod tests {
         ));
     }
 
+    #[test]
+    #[should_panic]
+    fn creation_complex_dtype() {
+        check!(TensorCheck::creation_dtype("From Data", DType::Complex32));
+    }
+
+    #[test]
+    fn creation_float_dtype() {
+        check!(TensorCheck::creation_dtype("From Data", DType::F32));
+    }
+
     #[test]
     #[should_panic]
     fn index_range_exceed_dimension() {
@@ -1413,4 +1719,63 @@ mod tests {
             &vec![0, 1, 2]
         ));
     }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "experimental-named-tensor")]
+    fn dim_names_duplicates() {
+        check!(TensorCheck::dim_names(
+            "With Dims",
+            &["batch", "h", "batch"]
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "experimental-named-tensor")]
+    fn dim_name_missing() {
+        let names = ["batch".to_string(), "h".to_string()];
+        check!(TensorCheck::dim_name("Sum Dim", &names, "w"));
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "experimental-named-tensor")]
+    fn dim_names_target_missing() {
+        let names = ["batch".to_string(), "h".to_string()];
+        check!(TensorCheck::dim_names_target(
+            "Permute",
+            &names,
+            &["h", "w"]
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "experimental-named-tensor")]
+    fn dim_names_broadcast_incompatible() {
+        let lhs = ["batch".to_string(), "h".to_string()];
+        let rhs = ["h".to_string()];
+        check!(TensorCheck::dim_names_broadcast(
+            "Add",
+            &lhs,
+            &Shape::new([2, 3]),
+            &rhs,
+            &Shape::new([4]),
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "experimental-named-tensor")]
+    fn dim_names_broadcast() {
+        let lhs = ["batch".to_string(), "h".to_string()];
+        let rhs = ["h".to_string(), "batch".to_string()];
+        check!(TensorCheck::dim_names_broadcast(
+            "Add",
+            &lhs,
+            &Shape::new([2, 3]),
+            &rhs,
+            &Shape::new([3, 1]),
+        ));
+    }
 }
@@ -0,0 +1,417 @@
+use alloc::format;
+use alloc::vec::Vec;
+use burn_common::reader::try_read_sync;
+
+use super::fft::{merge_complex, split_complex};
+use crate::backend::Backend;
+use crate::check;
+use crate::check::TensorCheck;
+use crate::{DType, Shape, Tensor, TensorData, TensorPrimitive};
+
+/// A tensor of complex numbers.
+///
+/// This is an API-level wrapper: the real and imaginary parts are stored as two float tensors of
+/// the same shape, and every operation is composed of float operations on them. Backends and the
+/// intermediate representation never see complex data. Only [data](TensorData) uses the
+/// [complex64](DType::Complex64) and [complex32](DType::Complex32) data types, depending on the
+/// float precision of the backend.
+#[derive(Debug, Clone)]
+pub struct ComplexTensor<B: Backend, const D: usize> {
+    real: Tensor<B, D>,
+    imag: Tensor<B, D>,
+}
+
+impl<B: Backend, const D: usize> ComplexTensor<B, D> {
+    /// Create a complex tensor from its real and imaginary parts.
+    ///
+    /// # Panics
+    ///
+    /// If the parts don't have the same shape.
+    pub fn new(real: Tensor<B, D>, imag: Tensor<B, D>) -> Self {
+        check!(TensorCheck::complex_parts(
+            "Complex",
+            &real.shape(),
+            &imag.shape()
+        ));
+
+        Self { real, imag }
+    }
+
+    /// Create a complex tensor with the given real part and an imaginary part of zeros.
+    pub fn from_real(real: Tensor<B, D>) -> Self {
+        let imag = real.zeros_like();
+
+        Self { real, imag }
+    }
+
+    /// Create a complex tensor filled with zeros.
+    pub fn zeros<S: Into<Shape>>(shape: S, device: &B::Device) -> Self {
+        let real = Tensor::zeros(shape, device);
+        Self::from_real(real)
+    }
+
+    /// Create a complex tensor from complex data.
+    ///
+    /// # Panics
+    ///
+    /// If the data isn't [complex](DType::is_complex) or doesn't have `D` dimensions.
+    pub fn from_data<T: Into<TensorData>>(data: T, device: &B::Device) -> Self {
+        let data = data.into();
+        check!(TensorCheck::creation_ops::<D>(
+            "Complex From Data",
+            &data.shape
+        ));
+
+        let parts = data.into_complex_parts().convert::<B::FloatElem>();
+        let (real, imag) = split_complex::<B>(B::float_from_data(parts, device));
+
+        Self::from_primitives(real, imag)
+    }
+
+    /// Create a complex tensor from a float tensor whose last dimension has size 2, holding the
+    /// real and imaginary parts of each element, as returned by [rfft](Tensor::rfft).
+    ///
+    /// # Panics
+    ///
+    /// If `D2` isn't `D + 1` or the last dimension doesn't have size 2.
+    pub fn from_interleaved<const D2: usize>(tensor: Tensor<B, D2>) -> Self {
+        check!(TensorCheck::complex_interleaved::<D, D2>(
+            "Complex From Interleaved",
+            &tensor.shape()
+        ));
+
+        let (real, imag) = split_complex::<B>(tensor.into_primitive().tensor());
+
+        Self::from_primitives(real, imag)
+    }
+
+    /// Convert the complex tensor into a float tensor whose last dimension has size 2, holding
+    /// the real and imaginary parts of each element, as expected by [fft](Tensor::fft).
+    ///
+    /// # Panics
+    ///
+    /// If `D2` isn't `D + 1`.
+    pub fn into_interleaved<const D2: usize>(self) -> Tensor<B, D2> {
+        check!(TensorCheck::complex_interleaved::<D, D2>(
+            "Complex Into Interleaved",
+            &Shape::from([self.dims().as_slice(), &[2]].concat())
+        ));
+
+        Tensor::new(TensorPrimitive::Float(self.into_merged()))
+    }
+
+    /// Returns the data of the complex tensor.
+    ///
+    /// Half precision parts are converted to `f32`, so the data is always
+    /// [complex64](DType::Complex64) or [complex32](DType::Complex32).
+    pub fn into_data(self) -> TensorData {
+        try_read_sync(self.into_data_async()).expect(
+            "Failed to read tensor data synchronously.
+        This can happen on platforms that don't support blocking futures like WASM.
+        If possible, try using into_data_async instead.",
+        )
+    }
+
+    /// Returns the data of the complex tensor.
+    pub async fn into_data_async(self) -> TensorData {
+        let data = B::float_into_data(self.into_merged()).await;
+        let data = match data.dtype.complex() {
+            Some(_) => data,
+            None => data.convert_dtype(DType::F32),
+        };
+
+        TensorData::from_complex_parts(data)
+    }
+
+    /// Returns the real part.
+    pub fn real(&self) -> Tensor<B, D> {
+        self.real.clone()
+    }
+
+    /// Returns the imaginary part.
+    pub fn imag(&self) -> Tensor<B, D> {
+        self.imag.clone()
+    }
+
+    /// Returns the real and imaginary parts.
+    pub fn into_parts(self) -> (Tensor<B, D>, Tensor<B, D>) {
+        (self.real, self.imag)
+    }
+
+    /// Returns the shape of the complex tensor.
+    pub fn shape(&self) -> Shape {
+        self.real.shape()
+    }
+
+    /// Returns the dimensions of the complex tensor.
+    pub fn dims(&self) -> [usize; D] {
+        self.real.dims()
+    }
+
+    /// Returns the device of the complex tensor.
+    pub fn device(&self) -> B::Device {
+        self.real.device()
+    }
+
+    /// Returns the data type of the complex tensor, which is the complex counterpart of the data
+    /// type of its parts.
+    pub fn dtype(&self) -> DType {
+        self.real.dtype().complex().unwrap_or(DType::Complex32)
+    }
+
+    /// Returns the complex conjugate, negating the imaginary part.
+    pub fn conj(self) -> Self {
+        Self {
+            real: self.real,
+            imag: self.imag.neg(),
+        }
+    }
+
+    /// Returns the magnitude of each element, `sqrt(re^2 + im^2)`.
+    pub fn abs(self) -> Tensor<B, D> {
+        self.abs_squared().sqrt()
+    }
+
+    /// Returns the squared magnitude of each element, `re^2 + im^2`.
+    pub fn abs_squared(self) -> Tensor<B, D> {
+        let real = self.real.clone() * self.real;
+        let imag = self.imag.clone() * self.imag;
+
+        real + imag
+    }
+
+    /// Applies element wise negation.
+    #[allow(clippy::should_implement_trait)]
+    pub fn neg(self) -> Self {
+        Self {
+            real: self.real.neg(),
+            imag: self.imag.neg(),
+        }
+    }
+
+    /// Applies element wise addition, with broadcasting like [Tensor::add].
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(self, rhs: Self) -> Self {
+        Self {
+            real: self.real + rhs.real,
+            imag: self.imag + rhs.imag,
+        }
+    }
+
+    /// Applies element wise subtraction, with broadcasting like [Tensor::sub].
+    #[allow(clippy::should_implement_trait)]
+    pub fn sub(self, rhs: Self) -> Self {
+        Self {
+            real: self.real - rhs.real,
+            imag: self.imag - rhs.imag,
+        }
+    }
+
+    /// Applies element wise multiplication, with broadcasting like [Tensor::mul].
+    #[allow(clippy::should_implement_trait)]
+    pub fn mul(self, rhs: Self) -> Self {
+        let (a, b) = (self.real, self.imag);
+        let (c, d) = (rhs.real, rhs.imag);
+
+        Self {
+            real: a.clone() * c.clone() - b.clone() * d.clone(),
+            imag: a * d + b * c,
+        }
+    }
+
+    /// Applies element wise division, with broadcasting like [Tensor::div].
+    #[allow(clippy::should_implement_trait)]
+    pub fn div(self, rhs: Self) -> Self {
+        let (a, b) = (self.real, self.imag);
+        let (c, d) = (rhs.real, rhs.imag);
+        let denominator = c.clone() * c.clone() + d.clone() * d.clone();
+
+        Self {
+            real: (a.clone() * c.clone() + b.clone() * d.clone()) / denominator.clone(),
+            imag: (b * c - a * d) / denominator,
+        }
+    }
+
+    /// Multiplies each element by a real tensor, with broadcasting like [Tensor::mul].
+    pub fn mul_real(self, rhs: Tensor<B, D>) -> Self {
+        Self {
+            real: self.real * rhs.clone(),
+            imag: self.imag * rhs,
+        }
+    }
+
+    /// Applies the matrix multiplication, with the same shape requirements as [Tensor::matmul].
+    pub fn matmul(self, rhs: Self) -> Self {
+        let (a, b) = (self.real, self.imag);
+        let (c, d) = (rhs.real, rhs.imag);
+
+        Self {
+            real: a.clone().matmul(c.clone()) - b.clone().matmul(d.clone()),
+            imag: a.matmul(d) + b.matmul(c),
+        }
+    }
+
+    /// Computes the discrete Fourier transform along the given dimension.
+    ///
+    /// See [Tensor::fft] for the transform on interleaved tensors.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension is higher than the tensor rank.
+    pub fn fft(self, dim: usize) -> Self {
+        check!(TensorCheck::dim_ops::<D>("Complex Fft", dim));
+
+        self.transform(dim, false)
+    }
+
+    /// Computes the inverse discrete Fourier transform along the given dimension, normalized by
+    /// the size of the dimension.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension is higher than the tensor rank.
+    pub fn ifft(self, dim: usize) -> Self {
+        check!(TensorCheck::dim_ops::<D>("Complex Ifft", dim));
+
+        self.transform(dim, true)
+    }
+
+    /// Computes the discrete Fourier transform of a real tensor along the given dimension,
+    /// keeping only the `n / 2 + 1` non-redundant frequencies.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension is higher than the tensor rank.
+    pub fn rfft(tensor: Tensor<B, D>, dim: usize) -> Self {
+        check!(TensorCheck::dim_ops::<D>("Complex Rfft", dim));
+
+        let (real, imag) = split_complex::<B>(B::float_rfft(tensor.into_primitive().tensor(), dim));
+
+        Self::from_primitives(real, imag)
+    }
+
+    /// Computes the inverse of [rfft](Self::rfft) along the given dimension, returning a real
+    /// tensor whose dimension has size `n`.
+    ///
+    /// When `n` is `None`, it defaults to `2 * (size - 1)`. The frequencies are truncated or
+    /// padded with zeros to `n / 2 + 1`.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension is higher than the tensor rank.
+    pub fn irfft(self, dim: usize, n: Option<usize>) -> Tensor<B, D> {
+        check!(TensorCheck::dim_ops::<D>("Complex Irfft", dim));
+
+        let size = self.dims()[dim];
+        let n = n.unwrap_or(2 * size.saturating_sub(1)).max(1);
+        let num_freqs = n / 2 + 1;
+
+        let tensor = match size.cmp(&num_freqs) {
+            core::cmp::Ordering::Equal => self,
+            core::cmp::Ordering::Greater => Self {
+                real: self.real.narrow(dim, 0, num_freqs),
+                imag: self.imag.narrow(dim, 0, num_freqs),
+            },
+            core::cmp::Ordering::Less => {
+                let mut shape = self.dims();
+                shape[dim] = num_freqs - size;
+                let padding = Self::zeros(shape, &self.device());
+
+                Self::cat(alloc::vec![self, padding], dim)
+            }
+        };
+
+        Tensor::new(TensorPrimitive::Float(B::float_irfft(
+            tensor.into_merged(),
+            dim,
+            n,
+        )))
+    }
+
+    /// Concatenates all complex tensors into a new one along the given dimension.
+    ///
+    /// # Panics
+    ///
+    /// If the tensors don't have the same shape, except along the given dimension.
+    pub fn cat(tensors: Vec<Self>, dim: usize) -> Self {
+        let (real, imag) = tensors
+            .into_iter()
+            .map(|tensor| (tensor.real, tensor.imag))
+            .unzip();
+
+        Self {
+            real: Tensor::cat(real, dim),
+            imag: Tensor::cat(imag, dim),
+        }
+    }
+
+    fn transform(self, dim: usize, inverse: bool) -> Self {
+        let (real, imag) = split_complex::<B>(B::float_fft(self.into_merged(), dim, inverse));
+
+        Self::from_primitives(real, imag)
+    }
+
+    fn into_merged(self) -> B::FloatTensorPrimitive {
+        merge_complex::<B>(
+            self.real.into_primitive().tensor(),
+            self.imag.into_primitive().tensor(),
+        )
+    }
+
+    fn from_primitives(real: B::FloatTensorPrimitive, imag: B::FloatTensorPrimitive) -> Self {
+        Self {
+            real: Tensor::new(TensorPrimitive::Float(real)),
+            imag: Tensor::new(TensorPrimitive::Float(imag)),
+        }
+    }
+}
+
+impl<B: Backend, const D: usize> core::ops::Add<Self> for ComplexTensor<B, D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        ComplexTensor::add(self, rhs)
+    }
+}
+
+impl<B: Backend, const D: usize> core::ops::Sub<Self> for ComplexTensor<B, D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        ComplexTensor::sub(self, rhs)
+    }
+}
+
+impl<B: Backend, const D: usize> core::ops::Mul<Self> for ComplexTensor<B, D> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        ComplexTensor::mul(self, rhs)
+    }
+}
+
+impl<B: Backend, const D: usize> core::ops::Div<Self> for ComplexTensor<B, D> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        ComplexTensor::div(self, rhs)
+    }
+}
+
+impl<B: Backend, const D: usize> core::ops::Neg for ComplexTensor<B, D> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        ComplexTensor::neg(self)
+    }
+}
+
+impl<B: Backend, const D: usize> core::fmt::Display for ComplexTensor<B, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&format!(
+            "ComplexTensor[shape={:?}, dtype={:?}]",
+            self.shape().dims,
+            self.dtype(),
+        ))
+    }
+}
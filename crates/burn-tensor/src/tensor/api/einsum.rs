@@ -0,0 +1,513 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{Shape, TensorMetadata, backend::Backend, ops::FloatTensor};
+
+/// The first label assigned to the dimensions covered by an ellipsis (`...`).
+const ELLIPSIS_LABEL: u32 = 0xE000;
+/// The label of the size-one dimension of an operand without any label left.
+const UNIT_LABEL: char = '\0';
+
+/// A parsed einsum equation, where each dimension of each operand is given a label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EinsumEquation {
+    /// The labels of each operand.
+    pub(crate) inputs: Vec<Vec<char>>,
+    /// The labels of the output, in order.
+    pub(crate) output: Vec<char>,
+    /// The size of each label, broadcast across the operands.
+    sizes: Vec<(char, usize)>,
+}
+
+impl EinsumEquation {
+    /// Parse the equation for operands of the given shapes.
+    ///
+    /// Labels are ASCII letters, and an ellipsis covers the leading dimensions not labeled,
+    /// broadcast across the operands. Without an explicit output (`->`), the output has the
+    /// dimensions of the ellipsis followed by the labels appearing once, in alphabetical order.
+    pub(crate) fn parse(equation: &str, shapes: &[&[usize]]) -> Result<Self, String> {
+        let equation = equation
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        let (lhs, output) = match equation.split_once("->") {
+            Some((lhs, output)) => (lhs, Some(output)),
+            None => (equation.as_str(), None),
+        };
+        let terms = lhs.split(',').collect::<Vec<_>>();
+
+        if terms.len() != shapes.len() {
+            return Err(format!(
+                "The equation has {} operands, but {} tensors were given.",
+                terms.len(),
+                shapes.len()
+            ));
+        }
+
+        let mut inputs = Vec::with_capacity(terms.len());
+        let mut num_ellipsis_dims = 0;
+
+        for (term, shape) in terms.iter().zip(shapes) {
+            let (labels, ellipsis) = parse_term(term)?;
+            let num_labels = labels.len();
+
+            let labels = match ellipsis {
+                Some(position) => {
+                    let num_dims = shape.len().checked_sub(num_labels).ok_or_else(|| {
+                        format!(
+                            "Term '{term}' has {num_labels} labels, but the tensor has rank {}.",
+                            shape.len()
+                        )
+                    })?;
+                    num_ellipsis_dims = num_ellipsis_dims.max(num_dims);
+                    // Ellipsis dimensions are aligned from the right, like broadcasting.
+                    let mut labels = labels;
+                    labels.splice(position..position, (0..num_dims).map(|_| UNIT_LABEL));
+                    (labels, Some((position, num_dims)))
+                }
+                None if num_labels != shape.len() => {
+                    return Err(format!(
+                        "Term '{term}' has {num_labels} labels, but the tensor has rank {}.",
+                        shape.len()
+                    ));
+                }
+                None => (labels, None),
+            };
+            inputs.push(labels);
+        }
+
+        let ellipsis_labels = (0..num_ellipsis_dims)
+            .map(|i| char::from_u32(ELLIPSIS_LABEL + i as u32).unwrap())
+            .collect::<Vec<_>>();
+
+        let inputs = inputs
+            .into_iter()
+            .map(|(mut labels, ellipsis)| {
+                if let Some((position, num_dims)) = ellipsis {
+                    let offset = num_ellipsis_dims - num_dims;
+                    labels[position..position + num_dims]
+                        .copy_from_slice(&ellipsis_labels[offset..]);
+                }
+                labels
+            })
+            .collect::<Vec<_>>();
+
+        let mut sizes: Vec<(char, usize)> = Vec::new();
+
+        for (labels, shape) in inputs.iter().zip(shapes) {
+            for (i, (label, size)) in labels.iter().zip(shape.iter()).enumerate() {
+                if let Some(j) = labels[..i].iter().position(|l| l == label)
+                    && shape[j] != *size
+                {
+                    return Err(format!(
+                        "Label '{}' is repeated with different sizes {} and {size} in the same \
+                         operand.",
+                        display_label(*label),
+                        shape[j]
+                    ));
+                }
+
+                match sizes.iter_mut().find(|(l, _)| l == label) {
+                    Some((_, current)) if *current == *size || *size == 1 => {}
+                    Some((_, current)) if *current == 1 => *current = *size,
+                    Some((_, current)) => {
+                        return Err(format!(
+                            "Label '{}' has incompatible sizes {current} and {size}.",
+                            display_label(*label)
+                        ));
+                    }
+                    None => sizes.push((*label, *size)),
+                }
+            }
+        }
+
+        let output = match output {
+            Some(term) => {
+                let (mut labels, ellipsis) = parse_term(term)?;
+                if let Some(position) = ellipsis {
+                    labels.splice(position..position, ellipsis_labels.iter().copied());
+                } else if num_ellipsis_dims > 0 {
+                    return Err(format!(
+                        "Output '{term}' must have an ellipsis since the operands have one."
+                    ));
+                }
+
+                for (i, label) in labels.iter().enumerate() {
+                    if labels[..i].contains(label) {
+                        return Err(format!(
+                            "Output label '{}' is repeated.",
+                            display_label(*label)
+                        ));
+                    }
+                    if !sizes.iter().any(|(l, _)| l == label) {
+                        return Err(format!(
+                            "Output label '{}' isn't a label of the operands.",
+                            display_label(*label)
+                        ));
+                    }
+                }
+
+                labels
+            }
+            None => {
+                let mut labels = sizes
+                    .iter()
+                    .map(|(label, _)| *label)
+                    .filter(|label| !ellipsis_labels.contains(label))
+                    .filter(|label| inputs.iter().flatten().filter(|l| *l == label).count() == 1)
+                    .collect::<Vec<_>>();
+                labels.sort_unstable();
+
+                ellipsis_labels.into_iter().chain(labels).collect()
+            }
+        };
+
+        Ok(Self {
+            inputs,
+            output,
+            sizes,
+        })
+    }
+
+    /// The size of the label, broadcast across the operands.
+    fn size(&self, label: char) -> usize {
+        self.sizes
+            .iter()
+            .find(|(l, _)| *l == label)
+            .map(|(_, size)| *size)
+            .unwrap_or(1)
+    }
+
+    /// The shape of the output.
+    pub(crate) fn output_shape(&self) -> Vec<usize> {
+        self.output.iter().map(|label| self.size(*label)).collect()
+    }
+}
+
+/// Parse the labels of a term, returning the position of its ellipsis if any.
+fn parse_term(term: &str) -> Result<(Vec<char>, Option<usize>), String> {
+    let mut labels = Vec::with_capacity(term.len());
+    let mut ellipsis = None;
+    let mut rest = term;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("...") {
+            if ellipsis.is_some() {
+                return Err(format!("Term '{term}' has more than one ellipsis."));
+            }
+            ellipsis = Some(labels.len());
+            rest = after;
+            continue;
+        }
+
+        if !c.is_ascii_alphabetic() {
+            return Err(format!(
+                "Term '{term}' has an invalid label '{c}', labels should be ASCII letters."
+            ));
+        }
+        labels.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    Ok((labels, ellipsis))
+}
+
+fn display_label(label: char) -> String {
+    match label as u32 >= ELLIPSIS_LABEL {
+        true => String::from("..."),
+        false => format!("{label}"),
+    }
+}
+
+/// Sums the product of the operands over the labels of an einsum equation, such as `bij,bjk->bik`
+/// for a batched matrix multiplication.
+///
+/// # Arguments
+///
+/// * `equation` - The labels of the dimensions of each operand, separated by commas, optionally
+///   followed by `->` and the labels of the output.
+/// * `operands` - The tensors to multiply.
+///
+/// # Returns
+///
+/// The tensor with the output dimensions of the equation, or a tensor of shape `[1]` when the
+/// output has none.
+///
+/// # Remarks
+///
+/// This is a fallback solution that used only when the backend doesn't have the corresponding implementation.
+/// Ideally, it is supposed to be implemented by the backend and the backend implementation will be resolved
+/// by static dispatch. It is not designed for direct usage by users, and not recommended to import
+/// or use this function directly.
+///
+/// The equation is lowered to diagonal selections, sums, permutations and batched matrix
+/// multiplications, contracting the operands from left to right.
+pub fn einsum<B: Backend>(equation: &str, operands: Vec<FloatTensor<B>>) -> FloatTensor<B> {
+    let shapes = operands
+        .iter()
+        .map(|tensor| tensor.shape().dims)
+        .collect::<Vec<_>>();
+    let shapes = shapes.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let equation = EinsumEquation::parse(equation, &shapes)
+        .unwrap_or_else(|err| panic!("Invalid einsum equation: {err}"));
+
+    let mut operands = operands
+        .into_iter()
+        .zip(equation.inputs.iter())
+        .map(|(tensor, labels)| {
+            Operand::<B> {
+                tensor,
+                labels: labels.clone(),
+            }
+            .diagonal()
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    let mut result = operands
+        .next()
+        .expect("The equation should have at least one operand");
+    let remaining = operands.collect::<Vec<_>>();
+
+    for (i, operand) in remaining.iter().enumerate() {
+        // The labels still needed after contracting the operand.
+        let needed = equation
+            .output
+            .iter()
+            .chain(remaining[i + 1..].iter().flat_map(|o| o.labels.iter()))
+            .copied()
+            .collect::<Vec<_>>();
+        result = result.contract(operand.clone(), &needed, &equation);
+    }
+
+    let result = result.sum_labels(|label| equation.output.contains(&label));
+
+    if equation.output.is_empty() {
+        return result.tensor;
+    }
+
+    let result = result.expand(&equation);
+    let axes = equation
+        .output
+        .iter()
+        .map(|label| result.dim(*label))
+        .collect::<Vec<_>>();
+
+    B::float_permute(result.tensor, &axes)
+}
+
+/// A tensor whose dimensions are labeled.
+struct Operand<B: Backend> {
+    tensor: FloatTensor<B>,
+    labels: Vec<char>,
+}
+
+impl<B: Backend> Clone for Operand<B> {
+    fn clone(&self) -> Self {
+        Self {
+            tensor: self.tensor.clone(),
+            labels: self.labels.clone(),
+        }
+    }
+}
+
+impl<B: Backend> Operand<B> {
+    fn dim(&self, label: char) -> usize {
+        self.labels.iter().position(|l| *l == label).unwrap()
+    }
+
+    /// Take the diagonal of the dimensions sharing a label, until every label is unique.
+    fn diagonal(mut self) -> Self {
+        while let Some((first, second)) = self.repeated() {
+            let shape = self.tensor.shape().dims;
+            let size = shape[first];
+
+            // Move the repeated dimensions last, flatten them and select their diagonal.
+            let mut axes = (0..shape.len())
+                .filter(|dim| *dim != first && *dim != second)
+                .collect::<Vec<_>>();
+            let mut labels = axes.iter().map(|dim| self.labels[*dim]).collect::<Vec<_>>();
+            let mut dims = axes.iter().map(|dim| shape[*dim]).collect::<Vec<_>>();
+            axes.extend([first, second]);
+            labels.push(self.labels[first]);
+            dims.push(size * size);
+
+            let device = B::float_device(&self.tensor);
+            let indices = B::int_arange_step(0..(size * size) as i64, size + 1, &device);
+            let tensor = B::float_permute(self.tensor, &axes);
+            let tensor = B::float_reshape(tensor, Shape::from(dims));
+
+            self.tensor = B::float_select(tensor, labels.len() - 1, indices);
+            self.labels = labels;
+        }
+
+        self
+    }
+
+    fn repeated(&self) -> Option<(usize, usize)> {
+        self.labels.iter().enumerate().find_map(|(i, label)| {
+            self.labels[i + 1..]
+                .iter()
+                .position(|l| l == label)
+                .map(|j| (i, i + 1 + j))
+        })
+    }
+
+    /// Sum the dimensions whose label isn't kept.
+    fn sum_labels(self, keep: impl Fn(char) -> bool) -> Self {
+        let summed = self
+            .labels
+            .iter()
+            .map(|label| *label == UNIT_LABEL || !keep(*label))
+            .collect::<Vec<_>>();
+
+        if !summed.contains(&true) {
+            return self;
+        }
+
+        let shape = self.tensor.shape().dims;
+        let mut tensor = self.tensor;
+        for (dim, _) in summed.iter().enumerate().filter(|(_, summed)| **summed) {
+            // Dimensions of size one are only reshaped away.
+            if shape[dim] > 1 {
+                tensor = B::float_sum_dim(tensor, dim);
+            }
+        }
+
+        let (mut labels, mut dims): (Vec<_>, Vec<_>) = self
+            .labels
+            .iter()
+            .zip(shape)
+            .zip(summed)
+            .filter(|(_, summed)| !summed)
+            .map(|((label, size), _)| (*label, size))
+            .unzip();
+
+        if labels.is_empty() {
+            labels.push(UNIT_LABEL);
+            dims.push(1);
+        }
+
+        Self {
+            tensor: B::float_reshape(tensor, Shape::from(dims)),
+            labels,
+        }
+    }
+
+    /// Broadcast the dimensions to the size of their label.
+    fn expand(self, equation: &EinsumEquation) -> Self {
+        let shape = self.tensor.shape().dims;
+        let target = self
+            .labels
+            .iter()
+            .map(|label| equation.size(*label))
+            .collect::<Vec<_>>();
+
+        match shape == target {
+            true => self,
+            false => Self {
+                tensor: B::float_expand(self.tensor, Shape::from(target)),
+                labels: self.labels,
+            },
+        }
+    }
+
+    /// Permute the dimensions in the order of the groups of labels, each flattened into one
+    /// dimension.
+    fn flatten(self, groups: &[&[char]], equation: &EinsumEquation) -> FloatTensor<B> {
+        // The unit dimension, if any, is moved last and flattened away.
+        let axes = groups
+            .iter()
+            .flat_map(|group| group.iter())
+            .chain(self.labels.iter().filter(|label| **label == UNIT_LABEL))
+            .map(|label| self.dim(*label))
+            .collect::<Vec<_>>();
+        let dims = groups
+            .iter()
+            .map(|group| group.iter().map(|label| equation.size(*label)).product())
+            .collect::<Vec<usize>>();
+
+        let tensor = B::float_permute(self.tensor, &axes);
+        B::float_reshape(tensor, Shape::from(dims))
+    }
+
+    /// Multiply with the other operand, summing the labels shared that aren't needed anymore.
+    fn contract(self, other: Self, needed: &[char], equation: &EinsumEquation) -> Self {
+        let lhs = self.sum_labels(|label| needed.contains(&label) || other.labels.contains(&label));
+        let rhs = other.sum_labels(|label| needed.contains(&label) || lhs.labels.contains(&label));
+        let (lhs, rhs) = (lhs.expand(equation), rhs.expand(equation));
+
+        let is_real = |label: &&char| **label != UNIT_LABEL;
+        let (batch, contracted): (Vec<char>, Vec<char>) = lhs
+            .labels
+            .iter()
+            .filter(is_real)
+            .filter(|label| rhs.labels.contains(label))
+            .partition(|label| needed.contains(label));
+        let lhs_free = lhs
+            .labels
+            .iter()
+            .filter(is_real)
+            .filter(|label| !rhs.labels.contains(label))
+            .copied()
+            .collect::<Vec<_>>();
+        let rhs_free = rhs
+            .labels
+            .iter()
+            .filter(is_real)
+            .filter(|label| !lhs.labels.contains(label))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let lhs = lhs.flatten(&[&batch, &lhs_free, &contracted], equation);
+        let rhs = rhs.flatten(&[&batch, &contracted, &rhs_free], equation);
+        let tensor = B::float_matmul(lhs, rhs);
+
+        let mut labels = [batch, lhs_free, rhs_free].concat();
+        if labels.is_empty() {
+            labels.push(UNIT_LABEL);
+        }
+        let dims = labels
+            .iter()
+            .map(|label| equation.size(*label))
+            .collect::<Vec<_>>();
+
+        Self {
+            tensor: B::float_reshape(tensor, Shape::from(dims)),
+            labels,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn should_parse_explicit_output() {
+        let equation = EinsumEquation::parse("bij,bjk->bik", &[&[2, 3, 4], &[2, 4, 5]]).unwrap();
+
+        assert_eq!(
+            equation.inputs,
+            vec![vec!['b', 'i', 'j'], vec!['b', 'j', 'k']]
+        );
+        assert_eq!(equation.output, vec!['b', 'i', 'k']);
+        assert_eq!(equation.output_shape(), vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn should_parse_implicit_output_with_ellipsis() {
+        let equation = EinsumEquation::parse("...ij,jk", &[&[6, 2, 3, 4], &[4, 5]]).unwrap();
+
+        assert_eq!(equation.output.len(), 4);
+        assert_eq!(&equation.output[2..], &['i', 'k']);
+        assert_eq!(equation.output_shape(), vec![6, 2, 3, 5]);
+    }
+
+    #[test]
+    fn should_reject_incompatible_sizes() {
+        assert!(EinsumEquation::parse("ij,jk->ik", &[&[2, 3], &[4, 5]]).is_err());
+        assert!(EinsumEquation::parse("ij,jk->il", &[&[2, 3], &[3, 5]]).is_err());
+        assert!(EinsumEquation::parse("ij->i", &[&[2, 3, 4]]).is_err());
+    }
+}
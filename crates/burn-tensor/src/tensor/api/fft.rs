@@ -0,0 +1,218 @@
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use num_traits::Float;
+
+use crate::{DType, Shape, TensorData, TensorMetadata, backend::Backend, ops::FloatTensor};
+
+/// Computes the discrete Fourier transform of a complex tensor along a dimension.
+///
+/// Complex tensors are float tensors whose last dimension has size 2, holding the real and the
+/// imaginary parts of each element.
+///
+/// # Arguments
+///
+/// * `tensor` - The complex input tensor.
+/// * `dim` - The dimension to transform, which can't be the last one.
+/// * `inverse` - Whether to compute the inverse transform, normalized by the size of `dim`.
+///
+/// # Returns
+///
+/// The complex tensor with the same shape as the input.
+///
+/// # Remarks
+///
+/// This is a fallback solution that used only when the backend doesn't have the corresponding implementation.
+/// Ideally, it is supposed to be implemented by the backend and the backend implementation will be resolved
+/// by static dispatch. It is not designed for direct usage by users, and not recommended to import
+/// or use this function directly.
+///
+/// The transform is computed as multiplications with the DFT matrix, in `O(n²)` for a dimension
+/// of size `n`.
+pub fn fft<B: Backend>(tensor: FloatTensor<B>, dim: usize, inverse: bool) -> FloatTensor<B> {
+    let rank = tensor.shape().num_dims();
+    let n = tensor.shape().dims[dim];
+    let device = B::float_device(&tensor);
+    let dtype = tensor.dtype();
+
+    let tensor = B::float_swap_dims(tensor, dim, rank - 2);
+    let (real, imag) = split_complex::<B>(tensor);
+
+    let (scale, sign) = match inverse {
+        true => (1.0 / n as f64, 1.0),
+        false => (1.0, -1.0),
+    };
+    let cos = dft_matrix::<B>(n, n, n, |_| scale, <f64 as Float>::cos, dtype, &device);
+    let sin = dft_matrix::<B>(
+        n,
+        n,
+        n,
+        |_| sign * scale,
+        <f64 as Float>::sin,
+        dtype,
+        &device,
+    );
+
+    // (re + i im)(cos ± i sin), summed over the transformed dimension.
+    let out_real = B::float_sub(
+        dot::<B>(real.clone(), cos.clone()),
+        dot::<B>(imag.clone(), sin.clone()),
+    );
+    let out_imag = B::float_add(dot::<B>(imag, cos), dot::<B>(real, sin));
+
+    B::float_swap_dims(merge_complex::<B>(out_real, out_imag), dim, rank - 2)
+}
+
+/// Computes the discrete Fourier transform of a real tensor along a dimension, keeping only the
+/// `n / 2 + 1` non-redundant frequencies of the Hermitian-symmetric output.
+///
+/// # Arguments
+///
+/// * `tensor` - The real input tensor.
+/// * `dim` - The dimension to transform.
+///
+/// # Returns
+///
+/// The complex tensor with the size `n / 2 + 1` along `dim` and one more dimension of size 2.
+///
+/// # Remarks
+///
+/// This is a fallback solution that used only when the backend doesn't have the corresponding implementation.
+/// Ideally, it is supposed to be implemented by the backend and the backend implementation will be resolved
+/// by static dispatch. It is not designed for direct usage by users, and not recommended to import
+/// or use this function directly.
+pub fn rfft<B: Backend>(tensor: FloatTensor<B>, dim: usize) -> FloatTensor<B> {
+    let rank = tensor.shape().num_dims();
+    let n = tensor.shape().dims[dim];
+    let m = n / 2 + 1;
+    let device = B::float_device(&tensor);
+    let dtype = tensor.dtype();
+
+    let tensor = B::float_swap_dims(tensor, dim, rank - 1);
+    let cos = dft_matrix::<B>(n, m, n, |_| 1.0, <f64 as Float>::cos, dtype, &device);
+    let sin = dft_matrix::<B>(n, m, n, |_| -1.0, <f64 as Float>::sin, dtype, &device);
+
+    let out_real = dot::<B>(tensor.clone(), cos);
+    let out_imag = dot::<B>(tensor, sin);
+
+    B::float_swap_dims(merge_complex::<B>(out_real, out_imag), dim, rank - 1)
+}
+
+/// Computes the inverse of [rfft], recovering a real signal of size `n` along a dimension from
+/// its `n / 2 + 1` non-redundant frequencies.
+///
+/// # Arguments
+///
+/// * `tensor` - The complex input tensor, with the size `n / 2 + 1` along `dim`.
+/// * `dim` - The dimension to transform, which can't be the last one.
+/// * `n` - The size of the real output along `dim`.
+///
+/// # Returns
+///
+/// The real tensor with the size `n` along `dim`, without the complex dimension.
+///
+/// # Remarks
+///
+/// This is a fallback solution that used only when the backend doesn't have the corresponding implementation.
+/// Ideally, it is supposed to be implemented by the backend and the backend implementation will be resolved
+/// by static dispatch. It is not designed for direct usage by users, and not recommended to import
+/// or use this function directly.
+///
+/// The imaginary parts of the zero frequency and, for an even `n`, of the Nyquist frequency are
+/// ignored, as they are for any Hermitian-symmetric spectrum.
+pub fn irfft<B: Backend>(tensor: FloatTensor<B>, dim: usize, n: usize) -> FloatTensor<B> {
+    let rank = tensor.shape().num_dims();
+    let m = tensor.shape().dims[dim];
+    let device = B::float_device(&tensor);
+    let dtype = tensor.dtype();
+
+    let tensor = B::float_swap_dims(tensor, dim, rank - 2);
+    let (real, imag) = split_complex::<B>(tensor);
+
+    // The other half of the spectrum is the conjugate of the first one.
+    let weight = |k: usize| irfft_weight(k, n) / n as f64;
+    let cos = dft_matrix::<B>(m, n, n, weight, <f64 as Float>::cos, dtype, &device);
+    let sin = dft_matrix::<B>(m, n, n, |k| -weight(k), <f64 as Float>::sin, dtype, &device);
+
+    let output = B::float_add(dot::<B>(real, cos), dot::<B>(imag, sin));
+
+    B::float_swap_dims(output, dim, rank - 2)
+}
+
+/// How many times the frequency `k` appears in the full spectrum of a real signal of size `n`.
+fn irfft_weight(k: usize, n: usize) -> f64 {
+    match k == 0 || 2 * k == n {
+        true => 1.0,
+        false => 2.0,
+    }
+}
+
+/// The matrix of `weight(row) * trig(2π * row * col / n)`.
+fn dft_matrix<B: Backend>(
+    rows: usize,
+    cols: usize,
+    n: usize,
+    weight: impl Fn(usize) -> f64,
+    trig: impl Fn(f64) -> f64,
+    dtype: DType,
+    device: &B::Device,
+) -> FloatTensor<B> {
+    let mut values = Vec::with_capacity(rows * cols);
+
+    for row in 0..rows {
+        let weight = weight(row);
+        for col in 0..cols {
+            // The product is reduced first to keep the angle accurate for large sizes.
+            let angle = 2.0 * PI * ((row * col) % n) as f64 / n as f64;
+            values.push(weight * trig(angle));
+        }
+    }
+
+    let data = TensorData::new(values, [rows, cols]).convert_dtype(dtype);
+    B::float_from_data(data, device)
+}
+
+/// Multiplies the last dimension of the tensor with the matrix.
+fn dot<B: Backend>(tensor: FloatTensor<B>, matrix: FloatTensor<B>) -> FloatTensor<B> {
+    let mut dims = tensor.shape().dims;
+    let size = dims.pop().unwrap();
+    let batch = dims.iter().product::<usize>();
+    let cols = matrix.shape().dims[1];
+
+    let tensor = B::float_reshape(tensor, Shape::new([batch, size]));
+    let output = B::float_matmul(tensor, matrix);
+
+    dims.push(cols);
+    B::float_reshape(output, Shape::from(dims))
+}
+
+/// Splits a complex tensor into its real and imaginary parts.
+pub(crate) fn split_complex<B: Backend>(
+    tensor: FloatTensor<B>,
+) -> (FloatTensor<B>, FloatTensor<B>) {
+    let dims = tensor.shape().dims;
+    let rank = dims.len();
+    let shape = Shape::from(&dims[..rank - 1]);
+    let mut ranges = dims.iter().map(|size| 0..*size).collect::<Vec<_>>();
+
+    ranges[rank - 1] = 0..1;
+    let real = B::float_reshape(B::float_slice(tensor.clone(), &ranges), shape.clone());
+    ranges[rank - 1] = 1..2;
+    let imag = B::float_reshape(B::float_slice(tensor, &ranges), shape);
+
+    (real, imag)
+}
+
+/// Merges the real and imaginary parts into a complex tensor.
+pub(crate) fn merge_complex<B: Backend>(
+    real: FloatTensor<B>,
+    imag: FloatTensor<B>,
+) -> FloatTensor<B> {
+    let mut dims = real.shape().dims;
+    let rank = dims.len();
+    dims.push(1);
+
+    let real = B::float_reshape(real, Shape::from(dims.clone()));
+    let imag = B::float_reshape(imag, Shape::from(dims));
+
+    B::float_cat(alloc::vec![real, imag], rank)
+}
@@ -1,6 +1,10 @@
+use alloc::vec;
+
 use crate::FloatDType;
 use crate::Tensor;
 use crate::cast::ToElement;
+use crate::check;
+use crate::check::TensorCheck;
 use crate::quantization::{QuantScheme, QuantizationParameters};
 use crate::tensor::backend::Backend;
 use crate::tensor::stats;
@@ -344,6 +348,191 @@ where
             .div_scalar(n as f32 - correction_factor as f32)
     }
 
+    /// Sums the product of this tensor and the other over the labels of an einsum equation.
+    ///
+    /// Each term of the equation labels the dimensions of an operand with ASCII letters, and
+    /// the output term after `->` labels the dimensions of the result. Labels missing from the
+    /// output are summed, an ellipsis (`...`) covers the leading dimensions that aren't labeled,
+    /// and without an output term, the output has the ellipsis dimensions followed by the labels
+    /// appearing once, in alphabetical order. When the output has no dimension, the result has
+    /// shape `[1]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `equation` - The einsum equation, e.g. `bij,bjk->bik` for a batched matrix multiplication.
+    /// * `other` - The second operand of the equation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::Tensor;
+    ///
+    /// fn example<B: Backend>() {
+    ///     let device = Default::default();
+    ///     let lhs = Tensor::<B, 3>::ones([2, 3, 4], &device);
+    ///     let rhs = Tensor::<B, 3>::ones([2, 4, 5], &device);
+    ///     let output: Tensor<B, 3> = lhs.einsum("bij,bjk->bik", rhs);
+    ///     // Shape [2, 3, 5]
+    /// }
+    /// ```
+    pub fn einsum<const D2: usize, const D3: usize>(
+        self,
+        equation: &str,
+        other: Tensor<B, D2>,
+    ) -> Tensor<B, D3> {
+        check!(TensorCheck::einsum::<B, D, D2, D3>(equation, &self, &other));
+
+        Tensor::new(TensorPrimitive::Float(B::float_einsum(
+            equation,
+            vec![self.primitive.tensor(), other.primitive.tensor()],
+        )))
+    }
+
+    /// Computes the discrete Fourier transform of a complex tensor along the given dimension.
+    ///
+    /// Complex tensors are float tensors whose last dimension has size 2, holding the real and
+    /// the imaginary parts of each element. The transform isn't normalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to transform, which can't be the last one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::Tensor;
+    ///
+    /// fn example<B: Backend>() {
+    ///     let device = Default::default();
+    ///     // The complex signal [1, i, -1, -i].
+    ///     let signal = Tensor::<B, 2>::from_floats(
+    ///         [[1.0, 0.0], [0.0, 1.0], [-1.0, 0.0], [0.0, -1.0]],
+    ///         &device,
+    ///     );
+    ///     let spectrum = signal.fft(0);
+    ///     // [[0.0, 0.0], [4.0, 0.0], [0.0, 0.0], [0.0, 0.0]]
+    /// }
+    /// ```
+    pub fn fft(self, dim: usize) -> Self {
+        check!(TensorCheck::fft::<D, D>(
+            "Fft",
+            &self.shape(),
+            dim,
+            true,
+            true
+        ));
+
+        Self::new(TensorPrimitive::Float(B::float_fft(
+            self.primitive.tensor(),
+            dim,
+            false,
+        )))
+    }
+
+    /// Computes the inverse discrete Fourier transform of a complex tensor along the given
+    /// dimension, normalized by the size of the dimension.
+    ///
+    /// See [fft](Tensor::fft) for the layout of complex tensors.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to transform, which can't be the last one.
+    pub fn ifft(self, dim: usize) -> Self {
+        check!(TensorCheck::fft::<D, D>(
+            "Ifft",
+            &self.shape(),
+            dim,
+            true,
+            true
+        ));
+
+        Self::new(TensorPrimitive::Float(B::float_fft(
+            self.primitive.tensor(),
+            dim,
+            true,
+        )))
+    }
+
+    /// Computes the discrete Fourier transform of a real tensor along the given dimension.
+    ///
+    /// Since the spectrum of a real signal is Hermitian-symmetric, only the `n / 2 + 1`
+    /// non-redundant frequencies are returned, as a complex tensor with one more dimension of
+    /// size 2 holding the real and imaginary parts (see [fft](Tensor::fft)).
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to transform.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::Tensor;
+    ///
+    /// fn example<B: Backend>() {
+    ///     let device = Default::default();
+    ///     let signal = Tensor::<B, 2>::ones([8, 1024], &device);
+    ///     let spectrum: Tensor<B, 3> = signal.rfft(1);
+    ///     // Shape [8, 513, 2]
+    /// }
+    /// ```
+    pub fn rfft<const D2: usize>(self, dim: usize) -> Tensor<B, D2> {
+        check!(TensorCheck::fft::<D, D2>(
+            "Rfft",
+            &self.shape(),
+            dim,
+            false,
+            true
+        ));
+
+        Tensor::new(TensorPrimitive::Float(B::float_rfft(
+            self.primitive.tensor(),
+            dim,
+        )))
+    }
+
+    /// Computes the inverse of [rfft](Tensor::rfft), recovering a real signal from its
+    /// non-redundant frequencies along the given dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to transform, which can't be the last one.
+    /// * `n` - The size of the real output along `dim`, `2 * (size - 1)` by default. The
+    ///   frequencies past `n / 2 + 1` are ignored, and the missing ones are zeros.
+    pub fn irfft<const D2: usize>(self, dim: usize, n: Option<usize>) -> Tensor<B, D2> {
+        check!(TensorCheck::fft::<D, D2>(
+            "Irfft",
+            &self.shape(),
+            dim,
+            true,
+            false
+        ));
+
+        let size = self.dims()[dim];
+        let n = n.unwrap_or(2 * size.saturating_sub(1)).max(1);
+        let num_freqs = n / 2 + 1;
+
+        let tensor = match size.cmp(&num_freqs) {
+            core::cmp::Ordering::Equal => self,
+            core::cmp::Ordering::Greater => self.narrow(dim, 0, num_freqs),
+            core::cmp::Ordering::Less => {
+                let mut shape = self.dims();
+                shape[dim] = num_freqs - size;
+                let device = self.device();
+
+                Tensor::cat(vec![self, Tensor::zeros(shape, &device)], dim)
+            }
+        };
+
+        Tensor::new(TensorPrimitive::Float(B::float_irfft(
+            tensor.primitive.tensor(),
+            dim,
+            n,
+        )))
+    }
+
     /// Convert the tensor to a lower precision data type based on the quantization scheme.
     ///
     /// # Arguments
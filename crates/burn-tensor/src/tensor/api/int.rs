@@ -2,6 +2,7 @@ use crate::{
     Float, Int, Shape, Tensor, TensorData, TensorPrimitive, backend::Backend, cartesian_grid,
 };
 
+use burn_common::reader::try_read_sync;
 use core::ops::Range;
 
 impl<B> Tensor<B, 1, Int>
@@ -154,4 +155,160 @@ where
     pub fn bitwise_right_shift_scalar(self, other: B::IntElem) -> Self {
         Self::new(B::bitwise_right_shift_scalar(self.primitive, other))
     }
+
+    /// Returns the sorted unique elements of the tensor.
+    ///
+    /// # Returns
+    ///
+    /// A 1D tensor containing the unique elements of the given tensor in ascending order.
+    ///
+    /// # Remarks
+    ///
+    /// The number of unique elements is only known once computed, and no backend sorts on the
+    /// device yet, so the tensor is read on the host, like when [sorting](Tensor::sort) it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Int, Tensor};
+    ///
+    /// fn example<B: Backend>() {
+    ///    let device = B::Device::default();
+    ///    let tensor = Tensor::<B, 2, Int>::from_ints([[3, 1, 3], [2, 1, 7]], &device);
+    ///    let tensor = tensor.unique();
+    ///    println!("{tensor}");
+    ///    // [1, 2, 3, 7]
+    /// }
+    /// ```
+    pub fn unique(self) -> Tensor<B, 1, Int> {
+        try_read_sync(self.unique_async())
+            .expect("Failed to read tensor data synchronously. Try using unique_async instead.")
+    }
+
+    /// Returns the sorted unique elements of the tensor.
+    ///
+    /// # Returns
+    ///
+    /// A 1D tensor containing the unique elements of the given tensor in ascending order.
+    pub async fn unique_async(self) -> Tensor<B, 1, Int> {
+        let (values, _inverse, _counts) = B::int_unique(self.primitive).await;
+        Tensor::new(values)
+    }
+
+    /// Returns the sorted unique elements of the tensor, along with the number of occurrences of
+    /// each of them.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the 1D tensor of the unique elements in ascending order and the 1D tensor
+    /// of their counts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Int, Tensor};
+    ///
+    /// fn example<B: Backend>() {
+    ///    let device = B::Device::default();
+    ///    let tensor = Tensor::<B, 2, Int>::from_ints([[3, 1, 3], [2, 1, 7]], &device);
+    ///    let (values, counts) = tensor.unique_with_counts();
+    ///    println!("{values}");
+    ///    // [1, 2, 3, 7]
+    ///    println!("{counts}");
+    ///    // [2, 1, 2, 1]
+    /// }
+    /// ```
+    pub fn unique_with_counts(self) -> (Tensor<B, 1, Int>, Tensor<B, 1, Int>) {
+        try_read_sync(self.unique_with_counts_async()).expect(
+            "Failed to read tensor data synchronously. Try using unique_with_counts_async instead.",
+        )
+    }
+
+    /// Returns the sorted unique elements of the tensor, along with the number of occurrences of
+    /// each of them.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the 1D tensor of the unique elements in ascending order and the 1D tensor
+    /// of their counts.
+    pub async fn unique_with_counts_async(self) -> (Tensor<B, 1, Int>, Tensor<B, 1, Int>) {
+        let (values, _inverse, counts) = B::int_unique(self.primitive).await;
+        (Tensor::new(values), Tensor::new(counts))
+    }
+
+    /// Returns the sorted unique elements of the tensor, along with the inverse indices mapping
+    /// each element of the tensor to its position in the unique elements.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the 1D tensor of the unique elements in ascending order and the tensor
+    /// of inverse indices, with the same shape as the input tensor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Int, Tensor};
+    ///
+    /// fn example<B: Backend>() {
+    ///    let device = B::Device::default();
+    ///    let tensor = Tensor::<B, 2, Int>::from_ints([[3, 1, 3], [2, 1, 7]], &device);
+    ///    let (values, inverse) = tensor.unique_with_inverse();
+    ///    println!("{values}");
+    ///    // [1, 2, 3, 7]
+    ///    println!("{inverse}");
+    ///    // [[2, 0, 2], [1, 0, 3]]
+    /// }
+    /// ```
+    pub fn unique_with_inverse(self) -> (Tensor<B, 1, Int>, Tensor<B, D, Int>) {
+        try_read_sync(self.unique_with_inverse_async()).expect(
+            "Failed to read tensor data synchronously. Try using unique_with_inverse_async instead.",
+        )
+    }
+
+    /// Returns the sorted unique elements of the tensor, along with the inverse indices mapping
+    /// each element of the tensor to its position in the unique elements.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the 1D tensor of the unique elements in ascending order and the tensor
+    /// of inverse indices, with the same shape as the input tensor.
+    pub async fn unique_with_inverse_async(self) -> (Tensor<B, 1, Int>, Tensor<B, D, Int>) {
+        let (values, inverse, _counts) = B::int_unique(self.primitive).await;
+        (Tensor::new(values), Tensor::new(inverse))
+    }
+
+    /// Counts the number of occurrences of each value in `[0, num_bins)`.
+    ///
+    /// Values outside of the bins are ignored, so the counts are computed on the device without
+    /// reading the tensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_bins` - The number of bins.
+    ///
+    /// # Returns
+    ///
+    /// A 1D tensor of size `num_bins`, where the element at index `i` is the number of elements
+    /// equal to `i` in the given tensor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Int, Tensor};
+    ///
+    /// fn example<B: Backend>() {
+    ///    let device = B::Device::default();
+    ///    let tensor = Tensor::<B, 2, Int>::from_ints([[3, 1, 3], [2, 1, 7]], &device);
+    ///    let tensor = tensor.bincount(5);
+    ///    println!("{tensor}");
+    ///    // [0, 2, 1, 2, 0]
+    /// }
+    /// ```
+    pub fn bincount(self, num_bins: usize) -> Tensor<B, 1, Int> {
+        Tensor::new(B::int_bincount(self.primitive, num_bins))
+    }
 }
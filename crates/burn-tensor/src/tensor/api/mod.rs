@@ -5,21 +5,31 @@ mod autodiff;
 mod base;
 mod bool;
 mod cartesian_grid;
+mod complex;
+mod einsum;
+mod fft;
 mod float;
 mod int;
 mod kind;
 mod numeric;
+mod scatter;
 mod slice;
 mod sort;
 mod transaction;
+mod unique;
 
 pub use argwhere::argwhere_data;
 pub use autodiff::*;
 pub use base::*;
 pub use cartesian_grid::cartesian_grid;
+pub use complex::*;
+pub use einsum::einsum;
+pub use fft::{fft, irfft, rfft};
 pub use float::{DEFAULT_ATOL, DEFAULT_RTOL};
 pub use kind::*;
 pub use numeric::*;
+pub use scatter::{ScatterReduce, scatter_reduce};
 pub use slice::*;
 pub use sort::{argsort, sort, sort_with_indices};
 pub use transaction::*;
+pub use unique::unique_data;
@@ -1,12 +1,13 @@
 use alloc::vec::Vec;
+use burn_common::reader::try_read_sync;
 
 use crate::{TensorMetadata, alloc::borrow::ToOwned};
 
 use crate::TensorPrimitive;
 use crate::quantization::QTensorPrimitive;
 use crate::{
-    BasicOps, Bool, Distribution, Element, ElementConversion, Float, Int, Shape, Tensor,
-    TensorKind,
+    BasicOps, Bool, Distribution, Element, ElementConversion, Float, Int, ScatterReduce, Shape,
+    Tensor, TensorKind,
     backend::Backend,
     check,
     check::TensorCheck,
@@ -895,6 +896,103 @@ where
         Self::new(K::mask_fill(self.primitive, mask.primitive, value.elem()))
     }
 
+    /// Select the elements of the tensor where the mask is true.
+    ///
+    /// The size of the output depends on the number of true values in the mask, which has to be
+    /// read. Prefer [mask_select_async](Tensor::mask_select_async) on backends that don't support
+    /// synchronous reads.
+    ///
+    /// There is no dedicated intermediate representation operation: backends read the mask with
+    /// [argwhere](Tensor::argwhere) and select the elements, so the read flushes the operations
+    /// queued by fusion backends.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    ///
+    /// # Returns
+    ///
+    /// A 1D tensor of the selected elements, in row-major order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Tensor, Bool};
+    ///
+    /// fn example<B: Backend>() {
+    ///   let device = B::Device::default();
+    ///   let tensor = Tensor::<B, 2>::from_data([[1.0, -2.0, 3.0], [5.0, 9.0, 6.0]], &device);
+    ///   let mask = Tensor::<B, 2, Bool>::from_data([[true, false, true], [false, true, false]], &device);
+    ///   let tensor = tensor.mask_select(mask);
+    ///   println!("{tensor}");
+    ///   // [1.0, 3.0, 9.0]
+    /// }
+    /// ```
+    pub fn mask_select(self, mask: Tensor<B, D, Bool>) -> Tensor<B, 1, K> {
+        try_read_sync(self.mask_select_async(mask)).expect(
+            "Failed to read tensor data synchronously. Try using mask_select_async instead.",
+        )
+    }
+
+    /// Select the elements of the tensor where the mask is true.
+    ///
+    /// See [mask_select](Tensor::mask_select).
+    pub async fn mask_select_async(self, mask: Tensor<B, D, Bool>) -> Tensor<B, 1, K> {
+        check!(TensorCheck::mask(
+            "Mask Select",
+            &self.shape(),
+            &mask.shape()
+        ));
+
+        Tensor::new(K::mask_select(self.primitive, mask.primitive).await)
+    }
+
+    /// Assign the values to the elements of the tensor where the mask is true.
+    ///
+    /// This is the inverse of [mask_select](Tensor::mask_select): the values are written back to
+    /// the selected positions, in row-major order. The number of true elements isn't read, so when
+    /// there are fewer values than true elements, the last true elements keep their value, and
+    /// when there are more, the extra values are ignored.
+    ///
+    /// Backends build it from existing operations, which fusion backends record one by one.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    /// * `values` - The 1D tensor of the values to assign, with one value per true element of the
+    ///   mask.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Tensor, Bool};
+    ///
+    /// fn example<B: Backend>() {
+    ///   let device = B::Device::default();
+    ///   let tensor = Tensor::<B, 2>::from_data([[1.0, -2.0, 3.0], [5.0, 9.0, 6.0]], &device);
+    ///   let mask = Tensor::<B, 2, Bool>::from_data([[true, false, true], [false, true, false]], &device);
+    ///   let values = Tensor::<B, 1>::from_data([7.0, 8.0, 0.0], &device);
+    ///   let tensor = tensor.mask_assign(mask, values);
+    ///   println!("{tensor}");
+    ///   // [[7.0, -2.0, 8.0], [5.0, 0.0, 6.0]]
+    /// }
+    /// ```
+    pub fn mask_assign(self, mask: Tensor<B, D, Bool>, values: Tensor<B, 1, K>) -> Self {
+        check!(TensorCheck::mask_assign(
+            &self.shape(),
+            &mask.shape(),
+            &values.shape()
+        ));
+
+        Self::new(K::mask_assign(
+            self.primitive,
+            mask.primitive,
+            values.primitive,
+        ))
+    }
+
     /// Gather tensor elements corresponding to the given indices from the specified dim.
     ///
     /// Example using a 3D tensor:
@@ -956,6 +1054,68 @@ where
         ))
     }
 
+    /// Assign the gathered elements corresponding to the given indices along the specified dimension
+    /// from the value tensor to the original tensor, reducing the elements assigned to the same
+    /// position with the given reduction.
+    ///
+    /// Example using a 3D tensor with [ScatterReduce::Max]:
+    ///
+    /// `input[indices[i, j, k], j, k] = max(input[indices[i, j, k], j, k], values[i, j, k]); // dim = 0`
+    /// `input[i, indices[i, j, k], k] = max(input[i, indices[i, j, k], k], values[i, j, k]); // dim = 1`
+    /// `input[i, j, indices[i, j, k]] = max(input[i, j, indices[i, j, k]], values[i, j, k]); // dim = 2`
+    ///
+    /// # Notes
+    ///
+    /// The elements of the original tensor take part in the reduction, so [ScatterReduce::Mean]
+    /// averages each element with the values assigned to its position. Elements without any
+    /// assigned value are left unchanged.
+    ///
+    /// The index tensor should have the same shape as the original tensor except for the specified
+    /// dimension. The value and index tensors should have the same shape.
+    ///
+    /// # Warning
+    /// Not all backends have runtime bound checks for the indices, so make sure the they are valid.
+    /// Otherwise, out of bounds indices could lead to unexpected results instead of panicking.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Int, ScatterReduce, Tensor};
+    ///
+    /// fn example<B: Backend>() {
+    ///   let device = B::Device::default();
+    ///   let tensor = Tensor::<B, 1>::from_data([0.0, 0.0, 0.0], &device);
+    ///   let indices = Tensor::<B, 1, Int>::from_data([0, 2, 0, 2], &device);
+    ///   let values = Tensor::<B, 1>::from_data([1.0, -2.0, 3.0, -4.0], &device);
+    ///   let tensor = tensor.scatter_reduce(0, indices, values, ScatterReduce::Max);
+    ///   println!("{tensor}");
+    ///   // [3.0, 0.0, 0.0]
+    /// }
+    /// ```
+    pub fn scatter_reduce(
+        self,
+        dim: usize,
+        indices: Tensor<B, D, Int>,
+        values: Self,
+        reduce: ScatterReduce,
+    ) -> Self {
+        check!(TensorCheck::scatter::<D>(
+            dim,
+            &self.shape(),
+            &indices.shape(),
+            &values.shape()
+        ));
+
+        Self::new(K::scatter_reduce(
+            dim,
+            self.primitive,
+            indices.primitive,
+            values.primitive,
+            reduce,
+        ))
+    }
+
     /// Select the tensor elements along the given dimension corresponding to the given indices.
     ///
     /// Example using a 3D tensor:
@@ -1016,6 +1176,37 @@ where
         ))
     }
 
+    /// Assign the selected elements along the given dimension corresponding to the given indices
+    /// from the value tensor to the original tensor, reducing the elements assigned to the same
+    /// position with the given reduction.
+    ///
+    /// Example using a 3D tensor with [ScatterReduce::Prod]:
+    ///
+    /// `input[indices[i], j, k] *= values[i, j, k]; // dim = 0`
+    /// `input[i, indices[j], k] *= values[i, j, k]; // dim = 1`
+    /// `input[i, j, indices[k]] *= values[i, j, k]; // dim = 2`
+    ///
+    /// See [Tensor::scatter_reduce](Tensor::scatter_reduce) for the semantics of each reduction.
+    ///
+    /// # Warning
+    /// Not all backends have runtime bound checks for the indices, so make sure the they are valid.
+    /// Otherwise, out of bounds indices could lead to unexpected results instead of panicking.
+    pub fn select_assign_reduce(
+        self,
+        dim: usize,
+        indices: Tensor<B, 1, Int>,
+        values: Tensor<B, D, K>,
+        reduce: ScatterReduce,
+    ) -> Self {
+        check!(TensorCheck::select_assign::<D>(dim));
+
+        let mut shape = [1; D];
+        shape[dim] = indices.dims()[0];
+        let indices = indices.reshape(shape).expand(values.shape());
+
+        self.scatter_reduce(dim, indices, values, reduce)
+    }
+
     /// Applies the argmax function along the given dimension and returns an integer tensor.
     ///
     /// # Example
@@ -1710,7 +1901,12 @@ where
     /// ```
     pub fn sort(self, dim: usize) -> Tensor<B, D, K> {
         check!(TensorCheck::sort_dim::<D>("Sort", dim));
-        Tensor::new(K::sort(self.primitive, dim, /*descending*/ false))
+        Tensor::new(K::sort(
+            self.primitive,
+            dim,
+            /*descending*/ false,
+            /*stable*/ false,
+        ))
     }
 
     /// Sort the elements by value in descending order along a given dimension.
@@ -1744,7 +1940,12 @@ where
     /// ```
     pub fn sort_descending(self, dim: usize) -> Tensor<B, D, K> {
         check!(TensorCheck::sort_dim::<D>("Sort", dim));
-        Tensor::new(K::sort(self.primitive, dim, /*descending*/ true))
+        Tensor::new(K::sort(
+            self.primitive,
+            dim,
+            /*descending*/ true,
+            /*stable*/ false,
+        ))
     }
 
     /// Sort the elements by value in ascending order along a given dimension.
@@ -1778,8 +1979,12 @@ where
     /// ```
     pub fn sort_with_indices(self, dim: usize) -> (Tensor<B, D, K>, Tensor<B, D, Int>) {
         check!(TensorCheck::sort_dim::<D>("Sort_with_indices", dim));
-        let (values, indices) =
-            K::sort_with_indices(self.primitive, dim, /*descending*/ false);
+        let (values, indices) = K::sort_with_indices(
+            self.primitive,
+            dim,
+            /*descending*/ false,
+            /*stable*/ false,
+        );
         (Tensor::new(values), Tensor::new(indices))
     }
 
@@ -1810,7 +2015,12 @@ where
     /// ```
     pub fn sort_descending_with_indices(self, dim: usize) -> (Tensor<B, D, K>, Tensor<B, D, Int>) {
         check!(TensorCheck::sort_dim::<D>("Sort_with_indices", dim));
-        let (values, indices) = K::sort_with_indices(self.primitive, dim, /*descending*/ true);
+        let (values, indices) = K::sort_with_indices(
+            self.primitive,
+            dim,
+            /*descending*/ true,
+            /*stable*/ false,
+        );
         (Tensor::new(values), Tensor::new(indices))
     }
 
@@ -1838,7 +2048,12 @@ where
     /// ```
     pub fn argsort(self, dim: usize) -> Tensor<B, D, Int> {
         check!(TensorCheck::sort_dim::<D>("Argsort", dim));
-        Tensor::new(K::argsort(self.primitive, dim, /*descending*/ false))
+        Tensor::new(K::argsort(
+            self.primitive,
+            dim,
+            /*descending*/ false,
+            /*stable*/ false,
+        ))
     }
 
     /// Returns the indices that sort the elements by value in descending order along a given dimension.
@@ -1868,7 +2083,114 @@ where
     /// ```
     pub fn argsort_descending(self, dim: usize) -> Tensor<B, D, Int> {
         check!(TensorCheck::sort_dim::<D>("Argsort", dim));
-        Tensor::new(K::argsort(self.primitive, dim, /*descending*/ true))
+        Tensor::new(K::argsort(
+            self.primitive,
+            dim,
+            /*descending*/ true,
+            /*stable*/ false,
+        ))
+    }
+
+    /// Sort the elements by value along a given dimension, keeping the order of equal elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to sort along.
+    /// * `descending` - Whether to sort in descending order.
+    ///
+    /// # Returns
+    ///
+    /// A new tensor with the elements sorted along the given dimension.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Tensor, Shape};
+    ///
+    /// fn example<B: Backend>() {
+    ///   let device = B::Device::default();
+    ///   let tensor = Tensor::<B, 2>::from_data([[12.0, -2.0, 3.0], [5.0, 3.0, 6.0]], &device);
+    ///   let tensor = tensor.sort_stable(1, true);
+    ///   println!("{tensor}");
+    ///   // [[12.0, 3.0, -2.0], [6.0, 5.0, 3.0]]
+    /// }
+    /// ```
+    pub fn sort_stable(self, dim: usize, descending: bool) -> Tensor<B, D, K> {
+        check!(TensorCheck::sort_dim::<D>("Sort", dim));
+        Tensor::new(K::sort(
+            self.primitive,
+            dim,
+            descending,
+            /*stable*/ true,
+        ))
+    }
+
+    /// Sort the elements by value along a given dimension, keeping the order of equal elements.
+    /// Also returns the indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to sort along.
+    /// * `descending` - Whether to sort in descending order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Tensor, Shape};
+    ///
+    /// fn example<B: Backend>() {
+    ///    let device = B::Device::default();
+    ///    let tensor = Tensor::<B, 1>::from_data([3.0, 1.0, 3.0, 1.0], &device);
+    ///    let (tensor, indices) = tensor.sort_stable_with_indices(0, false);
+    ///    println!("{tensor}");
+    ///    // [1.0, 1.0, 3.0, 3.0]
+    ///    println!("{}", indices);
+    ///    // [1, 3, 0, 2]
+    /// }
+    /// ```
+    pub fn sort_stable_with_indices(
+        self,
+        dim: usize,
+        descending: bool,
+    ) -> (Tensor<B, D, K>, Tensor<B, D, Int>) {
+        check!(TensorCheck::sort_dim::<D>("Sort_with_indices", dim));
+        let (values, indices) =
+            K::sort_with_indices(self.primitive, dim, descending, /*stable*/ true);
+        (Tensor::new(values), Tensor::new(indices))
+    }
+
+    /// Returns the indices that sort the elements by value along a given dimension, keeping the
+    /// order of equal elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to sort along.
+    /// * `descending` - Whether to sort in descending order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Tensor, Shape};
+    ///
+    /// fn example<B: Backend>() {
+    ///    let device = B::Device::default();
+    ///    let tensor = Tensor::<B, 1>::from_data([3.0, 1.0, 3.0, 1.0], &device);
+    ///    let tensor = tensor.argsort_stable(0, true);
+    ///    println!("{tensor}");
+    ///    // [0, 2, 1, 3]
+    /// }
+    /// ```
+    pub fn argsort_stable(self, dim: usize, descending: bool) -> Tensor<B, D, Int> {
+        check!(TensorCheck::sort_dim::<D>("Argsort", dim));
+        Tensor::new(K::argsort(
+            self.primitive,
+            dim,
+            descending,
+            /*stable*/ true,
+        ))
     }
 
     /// Returns the `k` largest elements of the given input tensor along a given dimension.
@@ -1906,6 +2228,9 @@ where
     /// Returns the `k` largest elements of the given input tensor along a given dimension.
     /// Also returns the indices.
     ///
+    /// The values and indices are computed by a single stable sort, so equal elements are
+    /// returned in the order of their indices on every backend.
+    ///
     /// # Arguments
     ///
     /// * `k` - The number of elements to return.
@@ -1933,12 +2258,8 @@ where
     /// }
     /// ```
     pub fn topk_with_indices(self, k: usize, dim: usize) -> (Tensor<B, D, K>, Tensor<B, D, Int>) {
-        let k_indices = Tensor::arange(0..k as i64, &self.device());
-        let (values, indices) = self.sort_descending_with_indices(dim);
-        (
-            values.select(dim, k_indices.clone()),
-            indices.select(dim, k_indices),
-        )
+        let (values, indices) = self.sort_stable_with_indices(dim, /*descending*/ true);
+        (values.narrow(dim, 0, k), indices.narrow(dim, 0, k))
     }
 
     /// Pad the tensor of rank two or higher with the given value on the last two dimensions.
@@ -2877,6 +3198,57 @@ where
         value: Self::Elem,
     ) -> Self::Primitive;
 
+    /// Selects the elements of a tensor where a boolean mask is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to select elements from.
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    ///
+    /// # Returns
+    ///
+    /// A 1D tensor of the selected elements, in row-major order.
+    ///
+    /// # Remarks
+    ///
+    /// This is a low-level function used internally by the library to call different backend functions
+    /// with static dispatch. It is not designed for direct usage by users, and not recommended to import
+    /// or use this function directly.
+    ///
+    /// For selecting elements from a tensor with a boolean mask, users should prefer the
+    /// [Tensor::mask_select](Tensor::mask_select) function, which is more high-level and designed for public use.
+    fn mask_select(
+        tensor: Self::Primitive,
+        mask: B::BoolTensorPrimitive,
+    ) -> impl Future<Output = Self::Primitive> + Send;
+
+    /// Assigns values to the elements of a tensor where a boolean mask is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to assign values to.
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    /// * `values` - The 1D tensor of the values to assign, in row-major order.
+    ///
+    /// # Returns
+    ///
+    /// A tensor with the same shape as the input tensor, where the elements for which the mask is
+    /// true are replaced by the values.
+    ///
+    /// # Remarks
+    ///
+    /// This is a low-level function used internally by the library to call different backend functions
+    /// with static dispatch. It is not designed for direct usage by users, and not recommended to import
+    /// or use this function directly.
+    ///
+    /// For assigning values to a tensor with a boolean mask, users should prefer the
+    /// [Tensor::mask_assign](Tensor::mask_assign) function, which is more high-level and designed for public use.
+    fn mask_assign(
+        tensor: Self::Primitive,
+        mask: B::BoolTensorPrimitive,
+        values: Self::Primitive,
+    ) -> Self::Primitive;
+
     /// Gathers elements from a tensor along an axis.
     ///
     /// # Arguments
@@ -2935,6 +3307,39 @@ where
         values: Self::Primitive,
     ) -> Self::Primitive;
 
+    /// Scatters elements into a tensor, reducing the elements scattered to the same position.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The axis along which to scatter elements.
+    /// * `tensor` - The tensor to scatter elements into.
+    /// * `indices` - The indices of the elements to scatter.
+    /// * `values` - The values to scatter into the tensor.
+    /// * `reduce` - The reduction applied to the tensor element and the values scattered to its
+    ///   position.
+    ///
+    /// # Returns
+    ///
+    /// A tensor with the same shape as the input tensor, where each element is reduced with the
+    /// elements of the values tensor scattered to its position.
+    ///
+    /// # Remarks
+    ///
+    /// This is a low-level function used internally by the library to call different backend functions
+    /// with static dispatch. It is not designed for direct usage by users, and not recommended to import
+    /// or use this function directly.
+    ///
+    /// For scattering elements into a tensor along an axis, users should prefer the
+    /// [Tensor::scatter_reduce](Tensor::scatter_reduce) function, which is more high-level and
+    /// designed for public use.
+    fn scatter_reduce(
+        dim: usize,
+        tensor: Self::Primitive,
+        indices: B::IntTensorPrimitive,
+        values: Self::Primitive,
+        reduce: ScatterReduce,
+    ) -> Self::Primitive;
+
     /// Select tensor elements along the given dimension corresponding for the given indices.
     ///
     /// # Arguments
@@ -3343,13 +3748,14 @@ where
 
     /// Sort the elements of the input `tensor` by value along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
@@ -3362,17 +3768,19 @@ where
     ///
     /// Users should prefer the [Tensor::sort](Tensor::sort) function,
     /// which is more high-level and designed for public use.
-    fn sort(tensor: Self::Primitive, dim: usize, descending: bool) -> Self::Primitive;
+    fn sort(tensor: Self::Primitive, dim: usize, descending: bool, stable: bool)
+    -> Self::Primitive;
 
     /// Sort the elements of the input `tensor` by value along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
@@ -3391,17 +3799,19 @@ where
         tensor: Self::Primitive,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (Self::Primitive, <Int as TensorKind<B>>::Primitive);
 
     /// Returns the indices that sort the elements of the input `tensor` by value along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
@@ -3418,6 +3828,7 @@ where
         tensor: Self::Primitive,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> <Int as TensorKind<B>>::Primitive;
 
     /// Applies the matrix multiplication operation.
@@ -3553,6 +3964,18 @@ impl<B: Backend> Numeric<B> for Int {
         B::int_mask_fill(tensor, mask, value)
     }
 
+    async fn mask_select(tensor: Self::Primitive, mask: B::BoolTensorPrimitive) -> Self::Primitive {
+        B::int_mask_select(tensor, mask).await
+    }
+
+    fn mask_assign(
+        tensor: Self::Primitive,
+        mask: B::BoolTensorPrimitive,
+        values: Self::Primitive,
+    ) -> Self::Primitive {
+        B::int_mask_assign(tensor, mask, values)
+    }
+
     fn select(tensor: Self::Primitive, dim: usize, indices: Tensor<B, 1, Int>) -> Self::Primitive {
         B::int_select(tensor, dim, indices.primitive)
     }
@@ -3582,6 +4005,16 @@ impl<B: Backend> Numeric<B> for Int {
         B::int_scatter(dim, tensor, indices, values)
     }
 
+    fn scatter_reduce(
+        dim: usize,
+        tensor: Self::Primitive,
+        indices: B::IntTensorPrimitive,
+        values: Self::Primitive,
+        reduce: ScatterReduce,
+    ) -> Self::Primitive {
+        B::int_scatter_reduce(dim, tensor, indices, values, reduce)
+    }
+
     fn argmax(tensor: Self::Primitive, dim: usize) -> IntTensor<B> {
         B::int_argmax(tensor, dim)
     }
@@ -3671,24 +4104,31 @@ impl<B: Backend> Numeric<B> for Int {
         B::int_sign(tensor)
     }
 
-    fn sort(tensor: Self::Primitive, dim: usize, descending: bool) -> Self::Primitive {
-        B::int_sort(tensor, dim, descending)
+    fn sort(
+        tensor: Self::Primitive,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> Self::Primitive {
+        B::int_sort(tensor, dim, descending, stable)
     }
 
     fn sort_with_indices(
         tensor: Self::Primitive,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (Self::Primitive, <Int as TensorKind<B>>::Primitive) {
-        B::int_sort_with_indices(tensor, dim, descending)
+        B::int_sort_with_indices(tensor, dim, descending, stable)
     }
 
     fn argsort(
         tensor: Self::Primitive,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> <Int as TensorKind<B>>::Primitive {
-        B::int_argsort(tensor, dim, descending)
+        B::int_argsort(tensor, dim, descending, stable)
     }
 
     /// Applies the matrix multiplication operation.
@@ -3922,6 +4362,18 @@ impl<B: Backend> Numeric<B> for Float {
         TensorPrimitive::Float(B::float_mask_fill(tensor.tensor(), mask, value))
     }
 
+    async fn mask_select(tensor: Self::Primitive, mask: B::BoolTensorPrimitive) -> Self::Primitive {
+        TensorPrimitive::Float(B::float_mask_select(tensor.tensor(), mask).await)
+    }
+
+    fn mask_assign(
+        tensor: Self::Primitive,
+        mask: B::BoolTensorPrimitive,
+        values: Self::Primitive,
+    ) -> Self::Primitive {
+        TensorPrimitive::Float(B::float_mask_assign(tensor.tensor(), mask, values.tensor()))
+    }
+
     fn select(tensor: Self::Primitive, dim: usize, indices: Tensor<B, 1, Int>) -> Self::Primitive {
         match tensor {
             TensorPrimitive::Float(tensor) => {
@@ -3977,6 +4429,22 @@ impl<B: Backend> Numeric<B> for Float {
         ))
     }
 
+    fn scatter_reduce(
+        dim: usize,
+        tensor: Self::Primitive,
+        indices: B::IntTensorPrimitive,
+        values: Self::Primitive,
+        reduce: ScatterReduce,
+    ) -> Self::Primitive {
+        TensorPrimitive::Float(B::float_scatter_reduce(
+            dim,
+            tensor.tensor(),
+            indices,
+            values.tensor(),
+            reduce,
+        ))
+    }
+
     fn argmax(tensor: Self::Primitive, dim: usize) -> IntTensor<B> {
         match tensor {
             TensorPrimitive::Float(tensor) => B::float_argmax(tensor, dim),
@@ -4131,13 +4599,18 @@ impl<B: Backend> Numeric<B> for Float {
         TensorPrimitive::Float(B::float_sign(tensor.tensor()))
     }
 
-    fn sort(tensor: Self::Primitive, dim: usize, descending: bool) -> Self::Primitive {
+    fn sort(
+        tensor: Self::Primitive,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> Self::Primitive {
         match tensor {
             TensorPrimitive::Float(tensor) => {
-                TensorPrimitive::Float(B::float_sort(tensor, dim, descending))
+                TensorPrimitive::Float(B::float_sort(tensor, dim, descending, stable))
             }
             TensorPrimitive::QFloat(tensor) => {
-                TensorPrimitive::QFloat(B::q_sort(tensor, dim, descending))
+                TensorPrimitive::QFloat(B::q_sort(tensor, dim, descending, stable))
             }
         }
     }
@@ -4146,14 +4619,15 @@ impl<B: Backend> Numeric<B> for Float {
         tensor: Self::Primitive,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (Self::Primitive, <Int as TensorKind<B>>::Primitive) {
         match tensor {
             TensorPrimitive::Float(tensor) => {
-                let (values, indices) = B::float_sort_with_indices(tensor, dim, descending);
+                let (values, indices) = B::float_sort_with_indices(tensor, dim, descending, stable);
                 (TensorPrimitive::Float(values), indices)
             }
             TensorPrimitive::QFloat(tensor) => {
-                let (values, indices) = B::q_sort_with_indices(tensor, dim, descending);
+                let (values, indices) = B::q_sort_with_indices(tensor, dim, descending, stable);
                 (TensorPrimitive::QFloat(values), indices)
             }
         }
@@ -4163,10 +4637,11 @@ impl<B: Backend> Numeric<B> for Float {
         tensor: Self::Primitive,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> <Int as TensorKind<B>>::Primitive {
         match tensor {
-            TensorPrimitive::Float(tensor) => B::float_argsort(tensor, dim, descending),
-            TensorPrimitive::QFloat(tensor) => B::q_argsort(tensor, dim, descending),
+            TensorPrimitive::Float(tensor) => B::float_argsort(tensor, dim, descending, stable),
+            TensorPrimitive::QFloat(tensor) => B::q_argsort(tensor, dim, descending, stable),
         }
     }
 
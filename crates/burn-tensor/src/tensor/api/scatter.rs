@@ -0,0 +1,120 @@
+use crate::{BasicOps, Element, TensorData, TensorKind, backend::Backend, ops::IntTensor};
+use alloc::{vec, vec::Vec};
+use burn_common::reader::try_read_sync;
+use num_traits::{Num, NumCast};
+
+/// The reduction applied to the elements scattered to the same position.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ScatterReduce {
+    /// The scattered elements are added to the tensor element.
+    Sum,
+    /// The tensor element is multiplied by the scattered elements.
+    Prod,
+    /// The minimum of the tensor element and the scattered elements.
+    Min,
+    /// The maximum of the tensor element and the scattered elements.
+    Max,
+    /// The mean of the tensor element and the scattered elements.
+    Mean,
+}
+
+/// Assign the elements of the `value` tensor to the positions of the input `tensor` given by the
+/// `indices` along a dimension, reducing the elements assigned to the same position.
+///
+/// # Arguments
+///
+/// * `dim` - The axis along which to scatter elements.
+/// * `tensor` - The input tensor.
+/// * `indices` - The indices of the positions to scatter the elements to.
+/// * `value` - The elements to scatter.
+/// * `reduce` - The reduction applied to the elements scattered to the same position.
+///
+/// # Returns
+///
+/// A tensor with the same shape as the input tensor, where the elements are reduced with the
+/// elements scattered to their position, in the order of the `value` tensor.
+///
+/// # Remarks
+///
+/// This is a fallback solution that used only when the backend doesn't have the corresponding implementation.
+/// Ideally, it is supposed to be implemented by the backend and the backend implementation will be resolved
+/// by static dispatch. It is not designed for direct usage by users, and not recommended to import
+/// or use this function directly.
+pub fn scatter_reduce<B: Backend, K: TensorKind<B> + BasicOps<B>>(
+    dim: usize,
+    tensor: K::Primitive,
+    indices: IntTensor<B>,
+    value: K::Primitive,
+    reduce: ScatterReduce,
+) -> K::Primitive {
+    let device = K::device(&tensor);
+    let read = "Failed to synchronously read tensor data. This operation is not supported until this backend has a scatter reduce implementation.";
+    let data = try_read_sync(K::into_data_async(tensor)).expect(read);
+    let indices = try_read_sync(B::int_into_data(indices)).expect(read);
+    let value = try_read_sync(K::into_data_async(value)).expect(read);
+    let dtype = data.dtype;
+
+    let output = match dtype.is_float() {
+        true => scatter_reduce_data::<f64>(dim, data, indices, value, reduce),
+        false => scatter_reduce_data::<i64>(dim, data, indices, value, reduce),
+    };
+
+    K::from_data(output.convert_dtype(dtype), &device)
+}
+
+fn scatter_reduce_data<E: Element + Num + NumCast + PartialOrd>(
+    dim: usize,
+    data: TensorData,
+    indices: TensorData,
+    value: TensorData,
+    reduce: ScatterReduce,
+) -> TensorData {
+    let shape = data.shape.clone();
+    let shape_value = value.shape.clone();
+    let mut output = data.iter::<E>().collect::<Vec<_>>();
+    let indices = indices.iter::<i64>().collect::<Vec<_>>();
+    let value = value.iter::<E>().collect::<Vec<_>>();
+    // Each element of the tensor counts as one element of the mean.
+    let mut counts = vec![1usize; output.len()];
+
+    let strides = contiguous_strides(&shape);
+    let strides_value = contiguous_strides(&shape_value);
+
+    for (position, (index, value)) in indices.iter().zip(value).enumerate() {
+        let mut offset = 0;
+        for i in 0..shape.len() {
+            let coordinate = match i == dim {
+                true => *index as usize,
+                false => position / strides_value[i] % shape_value[i],
+            };
+            offset += coordinate * strides[i];
+        }
+
+        let current = output[offset];
+        output[offset] = match reduce {
+            ScatterReduce::Sum | ScatterReduce::Mean => current + value,
+            ScatterReduce::Prod => current * value,
+            ScatterReduce::Min if value < current => value,
+            ScatterReduce::Max if value > current => value,
+            ScatterReduce::Min | ScatterReduce::Max => current,
+        };
+        counts[offset] += 1;
+    }
+
+    if reduce == ScatterReduce::Mean {
+        output
+            .iter_mut()
+            .zip(counts)
+            .for_each(|(value, count)| *value = *value / E::from(count).unwrap());
+    }
+
+    TensorData::new(output, shape)
+}
+
+fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
@@ -10,13 +10,14 @@ use burn_common::reader::try_read_sync;
 
 /// Sort the elements of the input `tensor` by value along a given dimension.
 ///
-/// This sort is unstable (i.e., may reorder equal elements).
+/// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
 ///
 /// # Arguments
 ///
 /// * `tensor` - The input tensor.
 /// * `dim` - The axis along which to sort.
 /// * `descending` - The sorting order.
+/// * `stable` - Whether equal elements keep their relative order.
 ///
 /// # Returns
 ///
@@ -32,13 +33,14 @@ pub fn sort<B: Backend, K: TensorKind<B> + BasicOps<B>>(
     tensor: K::Primitive,
     dim: usize,
     descending: bool,
+    stable: bool,
 ) -> K::Primitive
 where
     <K as BasicOps<B>>::Elem: Element,
 {
     let device = K::device(&tensor);
     let data = try_read_sync(K::into_data_async(tensor)).expect("Failed to synchronously read tensor data. This operation is not supported until this backend has a GPU sorting implementation.");
-    sort_data::<B, K>(data, dim, &device, descending)
+    sort_data::<B, K>(data, dim, &device, descending, stable)
 }
 
 pub fn sort_data<B: Backend, K: TensorKind<B> + BasicOps<B>>(
@@ -46,6 +48,7 @@ pub fn sort_data<B: Backend, K: TensorKind<B> + BasicOps<B>>(
     dim: usize,
     device: &Device<B>,
     descending: bool,
+    stable: bool,
 ) -> K::Primitive
 where
     <K as BasicOps<B>>::Elem: Element,
@@ -54,9 +57,9 @@ where
     let data_slice = data.as_mut_slice().unwrap();
     if dims.len() == 1 {
         // 1D sort
-        data_slice.sort_unstable_by(|&a, &b| compare(&a, &b, descending));
+        sort_by(data_slice, stable, |&a, &b| compare(&a, &b, descending));
     } else {
-        sort_slice::<B, K>(data_slice, &dims, dim, None, false, descending);
+        sort_slice::<B, K>(data_slice, &dims, dim, None, false, descending, stable);
     }
 
     K::from_data(data, device)
@@ -64,13 +67,14 @@ where
 
 /// Sort the elements of the input `tensor` by value along a given dimension.
 ///
-/// This sort is unstable (i.e., may reorder equal elements).
+/// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
 ///
 /// # Arguments
 ///
 /// * `tensor` - The input tensor.
 /// * `dim` - The axis along which to sort.
 /// * `descending` - The sorting order.
+/// * `stable` - Whether equal elements keep their relative order.
 ///
 /// # Returns
 ///
@@ -87,13 +91,14 @@ pub fn sort_with_indices<B: Backend, K: TensorKind<B> + BasicOps<B>>(
     tensor: K::Primitive,
     dim: usize,
     descending: bool,
+    stable: bool,
 ) -> (K::Primitive, IntTensor<B>)
 where
     <K as BasicOps<B>>::Elem: Element,
 {
     let device = K::device(&tensor);
     let data = try_read_sync(K::into_data_async(tensor)).expect("Failed to synchronously read tensor data. This operation is not supported until this backend has a GPU sorting implementation.");
-    sort_data_with_indices::<B, K>(data, dim, &device, descending)
+    sort_data_with_indices::<B, K>(data, dim, &device, descending, stable)
 }
 
 fn sort_data_with_indices<B: Backend, K: TensorKind<B> + BasicOps<B>>(
@@ -101,6 +106,7 @@ fn sort_data_with_indices<B: Backend, K: TensorKind<B> + BasicOps<B>>(
     dim: usize,
     device: &Device<B>,
     descending: bool,
+    stable: bool,
 ) -> (K::Primitive, IntTensor<B>)
 where
     <K as BasicOps<B>>::Elem: Element,
@@ -110,7 +116,7 @@ where
     let data_slice = data.as_mut_slice().unwrap();
     if dims.len() == 1 {
         // 1D sort
-        indices_data.sort_unstable_by(|&a, &b| {
+        sort_by(&mut indices_data, stable, |&a, &b| {
             compare(
                 &data_slice[a.elem::<i64>() as usize],
                 &data_slice[b.elem::<i64>() as usize],
@@ -149,6 +155,7 @@ where
             Some(&mut indices_data),
             true,
             descending,
+            stable,
         );
     }
 
@@ -161,13 +168,14 @@ where
 
 /// Returns the indices that sort the elements of the input `tensor` along a given dimension.
 ///
-/// This sort is unstable (i.e., may reorder equal elements).
+/// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
 ///
 /// # Arguments
 ///
 /// * `tensor` - The input tensor.
 /// * `dim` - The axis along which to sort.
 /// * `descending` - The sorting order.
+/// * `stable` - Whether equal elements keep their relative order.
 ///
 /// # Returns
 ///
@@ -183,6 +191,7 @@ pub fn argsort<B: Backend, K: TensorKind<B> + BasicOps<B>>(
     tensor: K::Primitive,
     dim: usize,
     descending: bool,
+    stable: bool,
 ) -> IntTensor<B>
 where
     <K as BasicOps<B>>::Elem: Element,
@@ -190,7 +199,7 @@ where
     let device = K::device(&tensor);
     let data = try_read_sync(K::into_data_async(tensor)).expect("Failed to synchronously read tensor data. This operation is not supported until this backend has a GPU sorting implementation.");
 
-    argsort_data::<B, K>(data, dim, &device, descending)
+    argsort_data::<B, K>(data, dim, &device, descending, stable)
 }
 
 fn argsort_data<B: Backend, K: TensorKind<B> + BasicOps<B>>(
@@ -198,6 +207,7 @@ fn argsort_data<B: Backend, K: TensorKind<B> + BasicOps<B>>(
     dim: usize,
     device: &Device<B>,
     descending: bool,
+    stable: bool,
 ) -> IntTensor<B>
 where
     <K as BasicOps<B>>::Elem: Element,
@@ -207,7 +217,7 @@ where
     if dims.len() == 1 {
         // 1D sort
         let slice = data.as_slice::<<K as BasicOps<B>>::Elem>().unwrap();
-        indices_data.sort_unstable_by(|&a, &b| {
+        sort_by(&mut indices_data, stable, |&a, &b| {
             compare(
                 &slice[a.elem::<i64>() as usize],
                 &slice[b.elem::<i64>() as usize],
@@ -222,6 +232,7 @@ where
             Some(&mut indices_data),
             false,
             descending,
+            stable,
         );
     }
 
@@ -234,7 +245,7 @@ where
 /// Otherwise, the `indices` are sorted based on the value of the elements in `data`,
 /// and if `permute_both` is enabled then the data is also sorted.
 ///
-/// This sort is unstable (i.e., may reorder equal elements).
+/// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
 fn sort_slice<B: Backend, K: BasicOps<B>>(
     data: &mut [<K as BasicOps<B>>::Elem],
     dims: &[usize],
@@ -242,6 +253,7 @@ fn sort_slice<B: Backend, K: BasicOps<B>>(
     mut indices: Option<&mut [IntElem<B>]>,
     permute_both: bool,
     descending: bool,
+    stable: bool,
 ) where
     <K as BasicOps<B>>::Elem: Element,
 {
@@ -296,7 +308,9 @@ fn sort_slice<B: Backend, K: BasicOps<B>>(
             })
             .collect::<Vec<_>>();
 
-        elements.sort_unstable_by(|&(_, _, a), &(_, _, b)| compare(&a, &b, descending));
+        sort_by(&mut elements, stable, |&(_, _, a), &(_, _, b)| {
+            compare(&a, &b, descending)
+        });
 
         // Permute data in-place by the sorted indices
         for idx in 0..elements.len() {
@@ -358,6 +372,15 @@ fn dim_indices<B: Backend>(dims: &[usize], dim: usize) -> Vec<IntElem<B>> {
     }
 }
 
+/// Sorts the values with the comparator, keeping the order of equal values if `stable` is true.
+fn sort_by<T>(values: &mut [T], stable: bool, compare: impl FnMut(&T, &T) -> Ordering) {
+    if stable {
+        values.sort_by(compare);
+    } else {
+        values.sort_unstable_by(compare);
+    }
+}
+
 /// Compare two elements
 fn compare<E: ElementComparison>(a: &E, b: &E, descending: bool) -> Ordering {
     if descending { b.cmp(a) } else { a.cmp(b) }
@@ -0,0 +1,60 @@
+use crate::{Device, ElementConversion, Shape, TensorData, backend::Backend, ops::IntTensor};
+use alloc::{vec, vec::Vec};
+
+/// Compute the sorted unique elements of the tensor data, along with the inverse indices and
+/// the counts of each unique element.
+///
+/// # Arguments
+///
+/// * `data` - The input tensor data.
+/// * `device` - The device on which the output tensors are created.
+///
+/// # Returns
+///
+/// A tuple containing the 1D tensor of the unique elements in ascending order, the tensor with the
+/// shape of the input holding the index of each element in the unique elements, and the 1D tensor
+/// holding the number of occurrences of each unique element.
+///
+/// # Remarks
+///
+/// This is a fallback solution that used only when the backend doesn't have the corresponding implementation.
+/// Ideally, it is supposed to be implemented by the backend and the backend implementation will be resolved
+/// by static dispatch. It is not designed for direct usage by users, and not recommended to import
+/// or use this function directly.
+pub fn unique_data<B: Backend>(
+    data: TensorData,
+    device: &Device<B>,
+) -> (IntTensor<B>, IntTensor<B>, IntTensor<B>) {
+    let shape = data.shape.clone();
+    let values = data.iter::<i64>().collect::<Vec<_>>();
+
+    let mut uniques = values.clone();
+    uniques.sort_unstable();
+    uniques.dedup();
+
+    let mut counts = vec![0i64; uniques.len()];
+    let inverse = values
+        .iter()
+        .map(|value| {
+            // Every value is present in the unique elements.
+            let index = uniques.binary_search(value).unwrap();
+            counts[index] += 1;
+            (index as i64).elem::<B::IntElem>()
+        })
+        .collect::<Vec<_>>();
+
+    let num_uniques = uniques.len();
+    let into_data = |values: Vec<i64>| {
+        let values = values
+            .into_iter()
+            .map(|value| value.elem::<B::IntElem>())
+            .collect::<Vec<_>>();
+        TensorData::new(values, Shape::new([num_uniques]))
+    };
+
+    (
+        B::int_from_data(into_data(uniques), device),
+        B::int_from_data(TensorData::new(inverse, shape), device),
+        B::int_from_data(into_data(counts), device),
+    )
+}
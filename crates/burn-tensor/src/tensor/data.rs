@@ -84,6 +84,68 @@ impl TensorData {
         }
     }
 
+    /// Creates a new complex tensor data structure, where each value holds the real and the
+    /// imaginary parts of an element.
+    ///
+    /// # Panics
+    ///
+    /// If the parts aren't `f32` or `f64`.
+    pub fn complex<E: Element, S: Into<Vec<usize>>>(value: Vec<[E; 2]>, shape: S) -> Self {
+        let parts = value.into_iter().flatten().collect::<Vec<_>>();
+        let mut shape = shape.into();
+        shape.push(2);
+
+        Self::from_complex_parts(Self::new(parts, shape))
+    }
+
+    /// Creates complex data from float data whose last dimension has size 2, holding the real and
+    /// the imaginary parts of each element.
+    ///
+    /// # Panics
+    ///
+    /// If the last dimension doesn't have size 2, or the parts aren't `f32` or `f64`.
+    pub fn from_complex_parts(data: TensorData) -> Self {
+        let mut shape = data.shape;
+        let dtype = data.dtype.complex().unwrap_or_else(|| {
+            panic!(
+                "The parts of complex data should be f32 or f64, got {:?}",
+                data.dtype
+            )
+        });
+        assert_eq!(
+            shape.pop(),
+            Some(2),
+            "The last dimension of complex parts should have size 2"
+        );
+
+        Self {
+            bytes: data.bytes,
+            shape,
+            dtype,
+        }
+    }
+
+    /// Returns the parts of complex data, as float data with one more dimension of size 2
+    /// holding the real and the imaginary parts of each element.
+    ///
+    /// # Panics
+    ///
+    /// If the data isn't complex.
+    pub fn into_complex_parts(self) -> TensorData {
+        let dtype = self
+            .dtype
+            .complex_part()
+            .unwrap_or_else(|| panic!("Expected complex data, got {:?}", self.dtype));
+        let mut shape = self.shape;
+        shape.push(2);
+
+        Self {
+            bytes: self.bytes,
+            shape,
+            dtype,
+        }
+    }
+
     // Check that the input vector contains a correct number of elements
     fn check_data_len<E: Element>(data: &[E], shape: &Vec<usize>) {
         let expected_data_len = Self::numel(shape);
@@ -250,6 +312,17 @@ impl TensorData {
                 ),
                 // bool is a byte value equal to either 0 or 1
                 DType::Bool => Box::new(self.bytes.iter().map(|e| e.elem::<E>())),
+                // The real and imaginary parts of each element
+                DType::Complex64 => Box::new(
+                    bytemuck::checked::cast_slice(&self.bytes)
+                        .iter()
+                        .map(|e: &f64| e.elem::<E>()),
+                ),
+                DType::Complex32 => Box::new(
+                    bytemuck::checked::cast_slice(&self.bytes)
+                        .iter()
+                        .map(|e: &f32| e.elem::<E>()),
+                ),
                 DType::QFloat(scheme) => match scheme {
                     QuantScheme {
                         level: QuantLevel::Tensor,
@@ -351,6 +424,15 @@ impl TensorData {
     pub fn convert_dtype(self, dtype: DType) -> Self {
         if dtype == self.dtype {
             self
+        } else if self.dtype.is_complex() || dtype.is_complex() {
+            let part = dtype.complex_part();
+            assert!(
+                self.dtype.is_complex() && part.is_some(),
+                "Complex data can only be converted to another complex data type, got {:?} to {dtype:?}",
+                self.dtype
+            );
+
+            Self::from_complex_parts(self.into_complex_parts().convert_dtype(part.unwrap()))
         } else if dtype.size() == self.dtype.size()
             && !matches!(self.dtype, DType::Bool | DType::QFloat(_))
             && !matches!(dtype, DType::Bool | DType::QFloat(_))
@@ -368,7 +450,9 @@ impl TensorData {
                 DType::U32 => self.convert_inplace_dtype::<u32>(dtype),
                 DType::U16 => self.convert_inplace_dtype::<u16>(dtype),
                 DType::U8 => self.convert_inplace_dtype::<u8>(dtype),
-                DType::Bool | DType::QFloat(_) => unreachable!(),
+                DType::Bool | DType::QFloat(_) | DType::Complex64 | DType::Complex32 => {
+                    unreachable!()
+                }
             }
         } else {
             match self.dtype {
@@ -385,7 +469,7 @@ impl TensorData {
                 DType::U16 => self.convert_clone_dtype::<u16>(dtype),
                 DType::U8 => self.convert_clone_dtype::<u8>(dtype),
                 DType::Bool => self.convert_clone_dtype::<bool>(dtype),
-                DType::QFloat(_) => unreachable!(),
+                DType::QFloat(_) | DType::Complex64 | DType::Complex32 => unreachable!(),
             }
         }
     }
@@ -404,7 +488,7 @@ impl TensorData {
             DType::U32 => self.convert_inplace::<Current, u32>(),
             DType::U16 => self.convert_inplace::<Current, u16>(),
             DType::U8 => self.convert_inplace::<Current, u8>(),
-            DType::Bool | DType::QFloat(_) => unreachable!(),
+            DType::Bool | DType::QFloat(_) | DType::Complex64 | DType::Complex32 => unreachable!(),
         }
     }
 
@@ -437,7 +521,7 @@ impl TensorData {
             DType::U16 => self.convert_clone::<Current, u16>(),
             DType::U8 => self.convert_clone::<Current, u8>(),
             DType::Bool => self.convert_clone::<Current, bool>(),
-            DType::QFloat(_) => unreachable!(),
+            DType::QFloat(_) | DType::Complex64 | DType::Complex32 => unreachable!(),
         }
     }
 
@@ -534,6 +618,8 @@ impl TensorData {
             DType::U16 => self.assert_eq_elem::<u16>(other),
             DType::U8 => self.assert_eq_elem::<u8>(other),
             DType::Bool => self.assert_eq_elem::<bool>(other),
+            DType::Complex64 => self.assert_eq_elem::<f64>(other),
+            DType::Complex32 => self.assert_eq_elem::<f32>(other),
             DType::QFloat(q) => {
                 // Strict or not, it doesn't make sense to compare quantized data to not quantized data for equality
                 let q_other = if let DType::QFloat(q_other) = other.dtype {
@@ -809,6 +895,10 @@ impl core::fmt::Display for TensorData {
             DType::U16 => format!("{:?}", self.as_slice::<u16>().unwrap()),
             DType::U8 => format!("{:?}", self.as_slice::<u8>().unwrap()),
             DType::Bool => format!("{:?}", self.as_slice::<bool>().unwrap()),
+            DType::Complex64 | DType::Complex32 => {
+                let parts = self.iter::<f64>().collect::<Vec<_>>();
+                format!("{:?}", parts.chunks(2).collect::<Vec<_>>())
+            }
             DType::QFloat(scheme) => match scheme {
                 QuantScheme {
                     level: QuantLevel::Tensor,
@@ -1168,4 +1258,25 @@ mod tests {
             Tolerance::default(),
         );
     }
+
+    #[test]
+    fn should_convert_complex_data() {
+        let data = TensorData::complex(vec![[1.0f32, -2.0], [3.5, 0.5]], [2]);
+        assert_eq!(data.dtype, DType::Complex32);
+        assert_eq!(data.shape, vec![2]);
+
+        let data = data.convert_dtype(DType::Complex64);
+        assert_eq!(data.dtype, DType::Complex64);
+
+        let parts = data.into_complex_parts();
+        assert_eq!(parts.shape, vec![2, 2]);
+        parts.assert_eq(&TensorData::from([[1.0f64, -2.0], [3.5, 0.5]]), true);
+    }
+
+    #[test]
+    #[should_panic = "Complex data can only be converted to another complex data type"]
+    fn should_not_convert_complex_data_to_real() {
+        let data = TensorData::complex(vec![[1.0f32, -2.0]], [1]);
+        let _ = data.convert_dtype(DType::F32);
+    }
 }
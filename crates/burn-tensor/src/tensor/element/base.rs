@@ -317,6 +317,16 @@ pub enum DType {
     U8,
     Bool,
     QFloat(QuantScheme),
+    /// Complex numbers with `f64` parts, only held by [TensorData](crate::TensorData).
+    ///
+    /// Backends never store complex data: [ComplexTensor](crate::ComplexTensor) splits it into
+    /// real and imaginary float tensors.
+    Complex64,
+    /// Complex numbers with `f32` parts, only held by [TensorData](crate::TensorData).
+    ///
+    /// Backends never store complex data: [ComplexTensor](crate::ComplexTensor) splits it into
+    /// real and imaginary float tensors.
+    Complex32,
 }
 
 #[cfg(feature = "cubecl")]
@@ -369,6 +379,8 @@ impl DType {
             DType::QFloat(scheme) => match scheme.q_type {
                 QuantInputType::QInt8 => core::mem::size_of::<i8>(),
             },
+            DType::Complex64 => 2 * core::mem::size_of::<f64>(),
+            DType::Complex32 => 2 * core::mem::size_of::<f32>(),
         }
     }
     /// Returns true if the data type is a floating point type.
@@ -385,6 +397,29 @@ impl DType {
         matches!(self, DType::Bool)
     }
 
+    /// Returns true if the data type is a complex type.
+    pub fn is_complex(&self) -> bool {
+        matches!(self, DType::Complex64 | DType::Complex32)
+    }
+
+    /// Returns the complex data type whose real and imaginary parts have this data type, if any.
+    pub fn complex(&self) -> Option<DType> {
+        match self {
+            DType::F64 => Some(DType::Complex64),
+            DType::F32 | DType::Flex32 => Some(DType::Complex32),
+            _ => None,
+        }
+    }
+
+    /// Returns the data type of the real and imaginary parts of a complex data type.
+    pub fn complex_part(&self) -> Option<DType> {
+        match self {
+            DType::Complex64 => Some(DType::F64),
+            DType::Complex32 => Some(DType::F32),
+            _ => None,
+        }
+    }
+
     /// Returns the data type name.
     pub fn name(&self) -> &'static str {
         match self {
@@ -403,6 +438,8 @@ impl DType {
             DType::U8 => "u8",
             DType::Bool => "bool",
             DType::QFloat(_) => "qfloat",
+            DType::Complex64 => "complex64",
+            DType::Complex32 => "complex32",
         }
     }
 }
@@ -2,6 +2,8 @@ mod base;
 mod dims;
 mod matmul;
 mod swap_dims;
+mod with_dims;
 
 pub use base::*;
 pub use dims::*;
+pub use with_dims::*;
@@ -0,0 +1,288 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::backend::Backend;
+use crate::check;
+use crate::check::TensorCheck;
+use crate::{BasicOps, Float, Int, Numeric, Shape, Tensor, TensorKind};
+
+/// A tensor with dimensions named at runtime.
+///
+/// Reductions, permutations and broadcasts are specified with the names of the dimensions
+/// instead of their positions, and mismatching names or sizes panic with an error describing the
+/// dimensions involved.
+#[derive(Debug, Clone)]
+pub struct TensorWithDims<B: Backend, const D: usize, K: TensorKind<B> = Float> {
+    tensor: Tensor<B, D, K>,
+    names: [String; D],
+}
+
+impl<B: Backend, const D: usize, K: BasicOps<B>> Tensor<B, D, K> {
+    /// Name the dimensions of the tensor.
+    ///
+    /// # Panics
+    ///
+    /// If the names aren't unique.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use burn_tensor::backend::Backend;
+    /// use burn_tensor::{Distribution, Tensor};
+    ///
+    /// fn example<B: Backend>() {
+    ///     let device = B::Device::default();
+    ///     let images = Tensor::<B, 4>::random([8, 3, 32, 32], Distribution::Default, &device)
+    ///         .with_dims(["batch", "channel", "h", "w"]);
+    ///     let per_channel = images.mean_dim("h").mean_dim("w");
+    ///     println!("{:?}", per_channel.names());
+    ///     // ["batch", "channel", "h", "w"]
+    /// }
+    /// ```
+    pub fn with_dims(self, names: [&str; D]) -> TensorWithDims<B, D, K> {
+        check!(TensorCheck::dim_names("With Dims", &names));
+
+        TensorWithDims {
+            tensor: self,
+            names: names.map(|name| name.to_string()),
+        }
+    }
+}
+
+impl<B: Backend, const D: usize, K: BasicOps<B>> TensorWithDims<B, D, K> {
+    /// Returns the names of the dimensions.
+    pub fn names(&self) -> [&str; D] {
+        core::array::from_fn(|i| self.names[i].as_str())
+    }
+
+    /// Returns the position of the dimension with the given name.
+    ///
+    /// # Panics
+    ///
+    /// If there is no dimension with the given name.
+    pub fn dim(&self, name: &str) -> usize {
+        self.checked_position("Dim", name)
+    }
+
+    /// Returns the size of the dimension with the given name.
+    ///
+    /// # Panics
+    ///
+    /// If there is no dimension with the given name.
+    pub fn size(&self, name: &str) -> usize {
+        self.tensor.dims()[self.dim(name)]
+    }
+
+    /// Returns the shape of the tensor.
+    pub fn shape(&self) -> Shape {
+        self.tensor.shape()
+    }
+
+    /// Returns a reference to the underlying tensor.
+    pub fn tensor(&self) -> &Tensor<B, D, K> {
+        &self.tensor
+    }
+
+    /// Returns the underlying tensor, dropping the names of the dimensions.
+    pub fn into_tensor(self) -> Tensor<B, D, K> {
+        self.tensor
+    }
+
+    /// Rename the dimension `name` to `new_name`.
+    ///
+    /// # Panics
+    ///
+    /// If there is no dimension named `name`, or if another dimension is already named `new_name`.
+    pub fn rename(mut self, name: &str, new_name: &str) -> Self {
+        let dim = self.checked_position("Rename", name);
+        self.names[dim] = new_name.to_string();
+        check!(TensorCheck::dim_names("Rename", &self.names()));
+
+        self
+    }
+
+    /// Permute the dimensions of the tensor to the given order of names.
+    ///
+    /// # Panics
+    ///
+    /// If the names aren't a permutation of the names of the dimensions.
+    pub fn permute(self, names: [&str; D]) -> Self {
+        check!(TensorCheck::dim_names_target(
+            "Permute",
+            &self.names,
+            &names
+        ));
+
+        let axes = names.map(|name| self.position(name).unwrap() as isize);
+
+        self.tensor.permute(axes).with_dims(names)
+    }
+
+    /// Align the tensor to the given names, permuting its dimensions to their order and inserting
+    /// dimensions of size 1 for the names that don't exist yet.
+    ///
+    /// The aligned tensor can then be broadcasted against any tensor with the given names.
+    ///
+    /// # Panics
+    ///
+    /// If the names aren't unique or don't include all the names of the dimensions.
+    pub fn align_to<const D2: usize>(self, names: [&str; D2]) -> TensorWithDims<B, D2, K> {
+        check!(TensorCheck::dim_names_target(
+            "Align To",
+            &self.names,
+            &names
+        ));
+
+        let dims = self.tensor.dims();
+        let axes = names
+            .iter()
+            .filter_map(|name| self.position(name))
+            .collect::<Vec<_>>();
+        let sizes = names.map(|name| self.position(name).map_or(1, |dim| dims[dim]));
+
+        // Once permuted in the order of the target, inserting the new dimensions doesn't move any
+        // element.
+        let tensor = self
+            .tensor
+            .permute(core::array::from_fn(|i| axes[i] as isize));
+
+        tensor.reshape(sizes).with_dims(names)
+    }
+
+    /// Remove the dimension with the given name, which must have a size of 1.
+    ///
+    /// # Panics
+    ///
+    /// If there is no dimension with the given name or its size isn't 1.
+    pub fn squeeze<const D2: usize>(self, name: &str) -> TensorWithDims<B, D2, K> {
+        let dim = self.checked_position("Squeeze", name);
+        let tensor = self.tensor.squeeze(dim);
+        let names = core::array::from_fn(|i| match i < dim {
+            true => self.names[i].clone(),
+            false => self.names[i + 1].clone(),
+        });
+
+        TensorWithDims { tensor, names }
+    }
+
+    fn checked_position(&self, ops: &str, name: &str) -> usize {
+        check!(TensorCheck::dim_name(ops, &self.names, name));
+
+        self.position(name).unwrap()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    fn map<K2: TensorKind<B>>(
+        self,
+        func: impl FnOnce(Tensor<B, D, K>) -> Tensor<B, D, K2>,
+    ) -> TensorWithDims<B, D, K2> {
+        TensorWithDims {
+            tensor: func(self.tensor),
+            names: self.names,
+        }
+    }
+}
+
+impl<B: Backend, const D: usize, K> TensorWithDims<B, D, K>
+where
+    K: Numeric<B>,
+    K::Elem: crate::Element,
+{
+    /// Sum the elements along the dimension with the given name, keeping it with a size of 1.
+    pub fn sum_dim(self, name: &str) -> Self {
+        let dim = self.checked_position("Sum Dim", name);
+        self.map(|tensor| tensor.sum_dim(dim))
+    }
+
+    /// Average the elements along the dimension with the given name, keeping it with a size of 1.
+    pub fn mean_dim(self, name: &str) -> Self {
+        let dim = self.checked_position("Mean Dim", name);
+        self.map(|tensor| tensor.mean_dim(dim))
+    }
+
+    /// Find the maximum along the dimension with the given name, keeping it with a size of 1.
+    pub fn max_dim(self, name: &str) -> Self {
+        let dim = self.checked_position("Max Dim", name);
+        self.map(|tensor| tensor.max_dim(dim))
+    }
+
+    /// Find the minimum along the dimension with the given name, keeping it with a size of 1.
+    pub fn min_dim(self, name: &str) -> Self {
+        let dim = self.checked_position("Min Dim", name);
+        self.map(|tensor| tensor.min_dim(dim))
+    }
+
+    /// Find the indices of the maximum along the dimension with the given name, keeping it with a
+    /// size of 1.
+    pub fn argmax(self, name: &str) -> TensorWithDims<B, D, Int> {
+        let dim = self.checked_position("Argmax", name);
+        self.map(|tensor| tensor.argmax(dim))
+    }
+
+    /// Find the indices of the minimum along the dimension with the given name, keeping it with a
+    /// size of 1.
+    pub fn argmin(self, name: &str) -> TensorWithDims<B, D, Int> {
+        let dim = self.checked_position("Argmin", name);
+        self.map(|tensor| tensor.argmin(dim))
+    }
+
+    /// Applies element wise addition, broadcasting the dimensions of `rhs` to the dimensions with
+    /// the same names.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<const D2: usize>(self, rhs: TensorWithDims<B, D2, K>) -> Self {
+        self.binary("Add", rhs, Tensor::add)
+    }
+
+    /// Applies element wise subtraction, broadcasting the dimensions of `rhs` to the dimensions
+    /// with the same names.
+    #[allow(clippy::should_implement_trait)]
+    pub fn sub<const D2: usize>(self, rhs: TensorWithDims<B, D2, K>) -> Self {
+        self.binary("Sub", rhs, Tensor::sub)
+    }
+
+    /// Applies element wise multiplication, broadcasting the dimensions of `rhs` to the dimensions
+    /// with the same names.
+    #[allow(clippy::should_implement_trait)]
+    pub fn mul<const D2: usize>(self, rhs: TensorWithDims<B, D2, K>) -> Self {
+        self.binary("Mul", rhs, Tensor::mul)
+    }
+
+    /// Applies element wise division, broadcasting the dimensions of `rhs` to the dimensions with
+    /// the same names.
+    #[allow(clippy::should_implement_trait)]
+    pub fn div<const D2: usize>(self, rhs: TensorWithDims<B, D2, K>) -> Self {
+        self.binary("Div", rhs, Tensor::div)
+    }
+
+    fn binary<const D2: usize>(
+        self,
+        ops: &str,
+        rhs: TensorWithDims<B, D2, K>,
+        func: impl FnOnce(Tensor<B, D, K>, Tensor<B, D, K>) -> Tensor<B, D, K>,
+    ) -> Self {
+        check!(TensorCheck::dim_names_broadcast(
+            ops,
+            &self.names,
+            &self.shape(),
+            &rhs.names,
+            &rhs.shape(),
+        ));
+
+        let rhs = rhs.align_to(self.names()).tensor;
+        self.map(|lhs| func(lhs, rhs))
+    }
+}
+
+impl<B: Backend, const D: usize, K: BasicOps<B>> core::fmt::Display for TensorWithDims<B, D, K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&format!(
+            "TensorWithDims[shape={:?}, dims={:?}]",
+            self.shape().dims,
+            self.names,
+        ))
+    }
+}
@@ -2,7 +2,9 @@ use super::cat::cat_with_slice_assign;
 use super::repeat_dim::repeat_with_slice_assign;
 use super::{BoolTensor, Device, FloatTensor, IntElem, IntTensor};
 use crate::{Distribution, ElementConversion, Int, TensorData, backend::Backend, tensor::Shape};
-use crate::{TensorMetadata, argsort, sort, sort_with_indices};
+use crate::{
+    ScatterReduce, TensorMetadata, argsort, scatter_reduce, sort, sort_with_indices, unique_data,
+};
 use alloc::vec::Vec;
 use core::ops::Range;
 
@@ -140,6 +142,83 @@ pub trait IntTensorOps<B: Backend> {
     /// The tensor with the values filled.
     fn int_mask_fill(tensor: IntTensor<B>, mask: BoolTensor<B>, value: IntElem<B>) -> IntTensor<B>;
 
+    /// Selects the elements of the tensor where the mask is true.
+    ///
+    /// The default implementation reads the mask with [bool_argwhere](super::BoolTensorOps::bool_argwhere)
+    /// and selects the elements; there is no dedicated intermediate representation operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to select from.
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    ///
+    /// # Returns
+    ///
+    /// The 1D tensor of the selected elements, in row-major order.
+    fn int_mask_select(
+        tensor: IntTensor<B>,
+        mask: BoolTensor<B>,
+    ) -> impl Future<Output = IntTensor<B>> + 'static + Send {
+        async {
+            let shape = Shape::new([tensor.shape().num_elements()]);
+            let tensor = B::int_reshape(tensor, shape.clone());
+            // The number of selected elements depends on the mask, which has to be read.
+            let indices = B::bool_argwhere(B::bool_reshape(mask, shape)).await;
+            let shape = Shape::new([indices.shape().dims[0]]);
+
+            B::int_select(tensor, 0, B::int_reshape(indices, shape))
+        }
+    }
+
+    /// Assigns the values to the elements of the tensor where the mask is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to assign to.
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    /// * `values` - The 1D tensor of the values to assign, with one value per true element of the
+    ///   mask, in row-major order.
+    ///
+    /// # Returns
+    ///
+    /// The tensor with the values assigned to the elements where the mask is true. When there are
+    /// fewer values than true elements, the last true elements keep their value, and when there
+    /// are more, the extra values are ignored.
+    fn int_mask_assign(
+        tensor: IntTensor<B>,
+        mask: BoolTensor<B>,
+        values: IntTensor<B>,
+    ) -> IntTensor<B> {
+        let shape = tensor.shape();
+        let shape_flat = Shape::new([shape.num_elements()]);
+        let num_values = values.shape().num_elements();
+        let device = B::int_device(&tensor);
+
+        let tensor = B::int_reshape(tensor, shape_flat.clone());
+        // The stable descending sort moves the positions of the true elements first, in order.
+        let mask = B::bool_into_int(B::bool_reshape(mask, shape_flat.clone()));
+        let positions = B::int_argsort(mask.clone(), 0, true, true);
+        #[allow(clippy::single_range_in_vec_init)]
+        let positions = B::int_slice(positions, &[0..num_values]);
+        // Extra values are matched with false elements, so they are replaced by zeros.
+        let assigned = B::int_gather(0, mask, positions.clone());
+        let values = B::int_mask_fill(
+            values,
+            B::int_equal_elem(assigned.clone(), 0.elem()),
+            0.elem(),
+        );
+        // Only the elements receiving a value are cleared, since the values are added to them.
+        let assigned = B::int_select_assign(
+            B::int_zeros(shape_flat, &device),
+            0,
+            positions.clone(),
+            assigned,
+        );
+        let tensor = B::int_mask_fill(tensor, B::int_equal_elem(assigned, 1.elem()), 0.elem());
+
+        B::int_reshape(B::int_select_assign(tensor, 0, positions, values), shape)
+    }
+
     /// Gather elements from the tensor at the given indices.
     ///
     /// # Arguments
@@ -168,6 +247,46 @@ pub trait IntTensorOps<B: Backend> {
         value: IntTensor<B>,
     ) -> IntTensor<B>;
 
+    /// Scatter a given value to the tensor at the given indices, reducing the values scattered to
+    /// the same position.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to scatter to.
+    /// * `tensor` - The tensor.
+    /// * `indices` - The indices.
+    /// * `value` - The value.
+    /// * `reduce` - The reduction applied to the tensor element and the values scattered to its
+    ///   position, the mean being rounded as the int division.
+    ///
+    /// # Returns
+    ///
+    /// The tensor with the values scattered.
+    fn int_scatter_reduce(
+        dim: usize,
+        tensor: IntTensor<B>,
+        indices: IntTensor<B>,
+        value: IntTensor<B>,
+        reduce: ScatterReduce,
+    ) -> IntTensor<B> {
+        match reduce {
+            ScatterReduce::Sum => B::int_scatter(dim, tensor, indices, value),
+            ScatterReduce::Mean => {
+                let device = B::int_device(&tensor);
+                let counts = B::int_scatter(
+                    dim,
+                    B::int_ones(tensor.shape(), &device),
+                    indices.clone(),
+                    B::int_ones(value.shape(), &device),
+                );
+                let sum = B::int_scatter(dim, tensor, indices, value);
+
+                B::int_div(sum, counts)
+            }
+            _ => scatter_reduce::<B, Int>(dim, tensor, indices, value, reduce),
+        }
+    }
+
     /// Select tensor elements along the given dimension corresponding to the given indices.
     ///
     /// # Arguments
@@ -1081,24 +1200,25 @@ pub trait IntTensorOps<B: Backend> {
 
     /// Sort the elements of the input `tensor` by value along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
     /// A tensor with the same shape as the input tensor, where the elements are sorted by value.
-    fn int_sort(tensor: IntTensor<B>, dim: usize, descending: bool) -> IntTensor<B> {
-        sort::<B, Int>(tensor, dim, descending)
+    fn int_sort(tensor: IntTensor<B>, dim: usize, descending: bool, stable: bool) -> IntTensor<B> {
+        sort::<B, Int>(tensor, dim, descending, stable)
     }
 
     /// Sort the elements of the input `tensor` by value along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
@@ -1113,26 +1233,101 @@ pub trait IntTensorOps<B: Backend> {
         tensor: IntTensor<B>,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (IntTensor<B>, IntTensor<B>) {
-        sort_with_indices::<B, Int>(tensor, dim, descending)
+        sort_with_indices::<B, Int>(tensor, dim, descending, stable)
     }
 
     /// Returns the indices that sort the elements of the input `tensor` by value
     /// along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
     /// A tensor with the same shape as the input tensor the indices map back to the original input tensor.
-    fn int_argsort(tensor: IntTensor<B>, dim: usize, descending: bool) -> IntTensor<B> {
-        argsort::<B, Int>(tensor, dim, descending)
+    fn int_argsort(
+        tensor: IntTensor<B>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<B> {
+        argsort::<B, Int>(tensor, dim, descending, stable)
+    }
+
+    /// Computes the sorted unique elements of the tensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The input tensor.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the 1D tensor of the unique elements in ascending order, the tensor with
+    /// the shape of the input holding the index of each element in the unique elements, and the 1D
+    /// tensor holding the number of occurrences of each unique element.
+    ///
+    /// # Remarks
+    ///
+    /// The default implementation reads the tensor on the host, since the number of unique
+    /// elements is data dependent and no backend sorts on the device yet.
+    fn int_unique(
+        tensor: IntTensor<B>,
+    ) -> impl Future<Output = (IntTensor<B>, IntTensor<B>, IntTensor<B>)> + 'static + Send {
+        async {
+            // Size of the output tensors is variable (= number of unique elements in the tensor).
+            // Reading the data to find the unique elements might cause sync but is required.
+            let device = B::int_device(&tensor);
+            let data = B::int_into_data(tensor).await;
+            unique_data::<B>(data, &device)
+        }
+    }
+
+    /// Counts the number of occurrences of each value in the tensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The input tensor.
+    /// * `num_bins` - The number of bins, values outside of `[0, num_bins)` being ignored.
+    ///
+    /// # Returns
+    ///
+    /// A 1D tensor of size `num_bins`, holding the number of occurrences of each bin value.
+    fn int_bincount(tensor: IntTensor<B>, num_bins: usize) -> IntTensor<B> {
+        let device = B::int_device(&tensor);
+        let num_elements = tensor.shape().num_elements();
+
+        if num_bins == 0 {
+            return B::int_zeros(Shape::new([0]), &device);
+        }
+
+        let tensor = B::int_reshape(tensor, Shape::new([num_elements]));
+        let outside = B::bool_or(
+            B::int_lower_elem(tensor.clone(), 0.elem()),
+            B::int_greater_equal_elem(tensor.clone(), (num_bins as i64).elem()),
+        );
+
+        // Values outside of the bins are added to the first bin with a weight of zero.
+        let indices = B::int_mask_fill(tensor, outside.clone(), 0.elem());
+        let weights = B::int_mask_fill(
+            B::int_ones(Shape::new([num_elements]), &device),
+            outside,
+            0.elem(),
+        );
+
+        B::int_scatter(
+            0,
+            B::int_zeros(Shape::new([num_bins]), &device),
+            indices,
+            weights,
+        )
     }
 
     /// Bitwise AND operation for Int Tensors
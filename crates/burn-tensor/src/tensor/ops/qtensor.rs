@@ -1203,35 +1203,42 @@ pub trait QTensorOps<B: Backend> {
 
     /// Sort the elements of the input `tensor` by value in along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
     /// A tensor with the same shape as the input tensor, where the elements are sorted by value.
-    fn q_sort(tensor: QuantizedTensor<B>, dim: usize, descending: bool) -> QuantizedTensor<B> {
+    fn q_sort(
+        tensor: QuantizedTensor<B>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> QuantizedTensor<B> {
         // Default implementation. Backends can sort on the int values since qparams remain the same.
         dequant_op_quant!(
             ty Self,
-            float_op |tensor| B::float_sort(tensor, dim, descending),
+            float_op |tensor| B::float_sort(tensor, dim, descending, stable),
             tensor
         )
     }
 
     /// Sort the elements of the input `tensor` by value in along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
@@ -1241,32 +1248,39 @@ pub trait QTensorOps<B: Backend> {
         tensor: QuantizedTensor<B>,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (QuantizedTensor<B>, IntTensor<B>) {
         // Default implementation. Backends can sort on the int values since qparams remain the same.
         let scheme = *tensor.scheme();
 
         let tensor_f = Self::dequantize(tensor);
-        let (out_f, indices) = B::float_sort_with_indices(tensor_f, dim, descending);
+        let (out_f, indices) = B::float_sort_with_indices(tensor_f, dim, descending, stable);
 
         (Self::quantize_dynamic(out_f, &scheme), indices)
     }
 
     /// Returns the indices that sort the elements of the input `tensor` by value along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
     /// A tensor with the same shape as the input tensor the indices map back to the original input tensor.
-    fn q_argsort(tensor: QuantizedTensor<B>, dim: usize, descending: bool) -> IntTensor<B> {
+    fn q_argsort(
+        tensor: QuantizedTensor<B>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<B> {
         // Default implementation. Backends can sort on the int values since qparams remain the same.
         let tensor_f = Self::dequantize(tensor);
-        B::float_argsort(tensor_f, dim, descending)
+        B::float_argsort(tensor_f, dim, descending, stable)
     }
 }
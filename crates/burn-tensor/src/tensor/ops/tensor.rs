@@ -6,7 +6,9 @@ use crate::{FloatDType, TensorMetadata, TensorPrimitive};
 use alloc::vec::Vec;
 use core::ops::Range;
 
-use crate::{argsort, sort, sort_with_indices};
+use crate::{
+    ScatterReduce, argsort, einsum, fft, irfft, rfft, scatter_reduce, sort, sort_with_indices,
+};
 
 /// Operations on float tensors.
 pub trait FloatTensorOps<B: Backend> {
@@ -321,6 +323,74 @@ pub trait FloatTensorOps<B: Backend> {
     /// The result of multiplying the two tensors together using matrix multiplication.
     fn float_matmul(lhs: FloatTensor<B>, rhs: FloatTensor<B>) -> FloatTensor<B>;
 
+    /// Sums the product of the operands over the labels of an einsum equation.
+    ///
+    /// The default implementation lowers the equation to existing operations with
+    /// [einsum](crate::einsum), which fusion backends record one by one. There is no dedicated
+    /// fused kernel.
+    ///
+    /// # Arguments
+    ///
+    /// * `equation` - The einsum equation, e.g. `bij,bjk->bik`.
+    /// * `operands` - The tensors labeled by the equation, in order.
+    ///
+    /// # Returns
+    ///
+    /// The tensor with the output dimensions of the equation, or a tensor of shape `[1]` when the
+    /// output has none.
+    fn float_einsum(equation: &str, operands: Vec<FloatTensor<B>>) -> FloatTensor<B> {
+        einsum::<B>(equation, operands)
+    }
+
+    /// Computes the discrete Fourier transform of a complex tensor along a dimension.
+    ///
+    /// Complex tensors are float tensors whose last dimension has size 2, holding the real and
+    /// the imaginary parts of each element.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The complex input tensor.
+    /// * `dim` - The dimension to transform, which can't be the last one.
+    /// * `inverse` - Whether to compute the inverse transform, normalized by the size of `dim`.
+    ///
+    /// # Returns
+    ///
+    /// The complex tensor with the same shape as the input.
+    fn float_fft(tensor: FloatTensor<B>, dim: usize, inverse: bool) -> FloatTensor<B> {
+        fft::<B>(tensor, dim, inverse)
+    }
+
+    /// Computes the discrete Fourier transform of a real tensor along a dimension, keeping only
+    /// the `n / 2 + 1` non-redundant frequencies.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The real input tensor.
+    /// * `dim` - The dimension to transform.
+    ///
+    /// # Returns
+    ///
+    /// The complex tensor with the size `n / 2 + 1` along `dim` and one more dimension of size 2.
+    fn float_rfft(tensor: FloatTensor<B>, dim: usize) -> FloatTensor<B> {
+        rfft::<B>(tensor, dim)
+    }
+
+    /// Computes the inverse of [float_rfft](FloatTensorOps::float_rfft), recovering a real signal
+    /// of size `n` along a dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The complex input tensor, with the size `n / 2 + 1` along `dim`.
+    /// * `dim` - The dimension to transform, which can't be the last one.
+    /// * `n` - The size of the real output along `dim`.
+    ///
+    /// # Returns
+    ///
+    /// The real tensor with the size `n` along `dim`, without the complex dimension.
+    fn float_irfft(tensor: FloatTensor<B>, dim: usize, n: usize) -> FloatTensor<B> {
+        irfft::<B>(tensor, dim, n)
+    }
+
     /// Negates a tensor element-wise.
     fn float_neg(tensor: FloatTensor<B>) -> FloatTensor<B> {
         Self::float_mul_scalar(tensor, (-1.0_f32).elem::<FloatElem<B>>())
@@ -421,6 +491,52 @@ pub trait FloatTensorOps<B: Backend> {
         value: FloatTensor<B>,
     ) -> FloatTensor<B>;
 
+    /// Scatter elements into a tensor, reducing the elements scattered to the same position.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The dimension to scatter into.
+    /// * `tensor` - The tensor to scatter into.
+    /// * `indices` - The indices to scatter into.
+    /// * `value` - The value to scatter.
+    /// * `reduce` - The reduction applied to the tensor element and the elements scattered to its
+    ///   position.
+    ///
+    /// # Returns
+    ///
+    /// The tensor with the scattered elements.
+    fn float_scatter_reduce(
+        dim: usize,
+        tensor: FloatTensor<B>,
+        indices: IntTensor<B>,
+        value: FloatTensor<B>,
+        reduce: ScatterReduce,
+    ) -> FloatTensor<B> {
+        match reduce {
+            ScatterReduce::Sum => B::float_scatter(dim, tensor, indices, value),
+            ScatterReduce::Mean => {
+                let device = B::float_device(&tensor);
+                let counts = B::float_scatter(
+                    dim,
+                    B::float_ones(tensor.shape(), &device),
+                    indices.clone(),
+                    B::float_ones(value.shape(), &device),
+                );
+                let sum = B::float_scatter(dim, tensor, indices, value);
+
+                B::float_div(sum, counts)
+            }
+            _ => scatter_reduce::<B, Float>(
+                dim,
+                TensorPrimitive::Float(tensor),
+                indices,
+                TensorPrimitive::Float(value),
+                reduce,
+            )
+            .tensor(),
+        }
+    }
+
     /// Select tensor elements along the given dimension corresponding for the given indices.
     ///
     /// # Arguments
@@ -517,6 +633,83 @@ pub trait FloatTensorOps<B: Backend> {
         value: FloatElem<B>,
     ) -> FloatTensor<B>;
 
+    /// Selects the elements of the tensor where the mask is true.
+    ///
+    /// The default implementation reads the mask with [bool_argwhere](super::BoolTensorOps::bool_argwhere)
+    /// and selects the elements; there is no dedicated intermediate representation operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to select from.
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    ///
+    /// # Returns
+    ///
+    /// The 1D tensor of the selected elements, in row-major order.
+    fn float_mask_select(
+        tensor: FloatTensor<B>,
+        mask: BoolTensor<B>,
+    ) -> impl Future<Output = FloatTensor<B>> + 'static + Send {
+        async {
+            let shape = Shape::new([tensor.shape().num_elements()]);
+            let tensor = B::float_reshape(tensor, shape.clone());
+            // The number of selected elements depends on the mask, which has to be read.
+            let indices = B::bool_argwhere(B::bool_reshape(mask, shape)).await;
+            let shape = Shape::new([indices.shape().dims[0]]);
+
+            B::float_select(tensor, 0, B::int_reshape(indices, shape))
+        }
+    }
+
+    /// Assigns the values to the elements of the tensor where the mask is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to assign to.
+    /// * `mask` - The boolean mask with the same shape as the tensor.
+    /// * `values` - The 1D tensor of the values to assign, with one value per true element of the
+    ///   mask, in row-major order.
+    ///
+    /// # Returns
+    ///
+    /// The tensor with the values assigned to the elements where the mask is true. When there are
+    /// fewer values than true elements, the last true elements keep their value, and when there
+    /// are more, the extra values are ignored.
+    fn float_mask_assign(
+        tensor: FloatTensor<B>,
+        mask: BoolTensor<B>,
+        values: FloatTensor<B>,
+    ) -> FloatTensor<B> {
+        let shape = tensor.shape();
+        let shape_flat = Shape::new([shape.num_elements()]);
+        let num_values = values.shape().num_elements();
+        let device = B::float_device(&tensor);
+
+        let tensor = B::float_reshape(tensor, shape_flat.clone());
+        // The stable descending sort moves the positions of the true elements first, in order.
+        let mask = B::bool_into_int(B::bool_reshape(mask, shape_flat.clone()));
+        let positions = B::int_argsort(mask.clone(), 0, true, true);
+        #[allow(clippy::single_range_in_vec_init)]
+        let positions = B::int_slice(positions, &[0..num_values]);
+        // Extra values are matched with false elements, so they are replaced by zeros.
+        let assigned = B::int_gather(0, mask, positions.clone());
+        let values = B::float_mask_fill(
+            values,
+            B::int_equal_elem(assigned.clone(), 0.elem()),
+            0.elem(),
+        );
+        // Only the elements receiving a value are cleared, since the values are added to them.
+        let assigned = B::int_select_assign(
+            B::int_zeros(shape_flat, &device),
+            0,
+            positions.clone(),
+            assigned,
+        );
+        let tensor = B::float_mask_fill(tensor, B::int_equal_elem(assigned, 1.elem()), 0.elem());
+
+        B::float_reshape(B::float_select_assign(tensor, 0, positions, values), shape)
+    }
+
     /// Equal comparison of two tensors.
     ///
     /// # Arguments
@@ -1287,30 +1480,37 @@ pub trait FloatTensorOps<B: Backend> {
 
     /// Sort the elements of the input `tensor` by value in along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
     /// A tensor with the same shape as the input tensor, where the elements are sorted by value.
-    fn float_sort(tensor: FloatTensor<B>, dim: usize, descending: bool) -> FloatTensor<B> {
-        sort::<B, Float>(TensorPrimitive::Float(tensor), dim, descending).tensor()
+    fn float_sort(
+        tensor: FloatTensor<B>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> FloatTensor<B> {
+        sort::<B, Float>(TensorPrimitive::Float(tensor), dim, descending, stable).tensor()
     }
 
     /// Sort the elements of the input `tensor` by value in along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
@@ -1320,26 +1520,33 @@ pub trait FloatTensorOps<B: Backend> {
         tensor: FloatTensor<B>,
         dim: usize,
         descending: bool,
+        stable: bool,
     ) -> (FloatTensor<B>, IntTensor<B>) {
         let (values, indices) =
-            sort_with_indices::<B, Float>(TensorPrimitive::Float(tensor), dim, descending);
+            sort_with_indices::<B, Float>(TensorPrimitive::Float(tensor), dim, descending, stable);
         (values.tensor(), indices)
     }
 
     /// Returns the indices that sort the elements of the input `tensor` by value along a given dimension.
     ///
-    /// This sort is unstable (i.e., may reorder equal elements).
+    /// The sort is stable (i.e., keeps the order of equal elements) only when `stable` is true.
     ///
     /// # Arguments
     ///
     /// * `tensor` - The input tensor.
     /// * `dim` - The axis along which to sort.
     /// * `descending` - The sorting order.
+    /// * `stable` - Whether equal elements keep their relative order.
     ///
     /// # Returns
     ///
     /// A tensor with the same shape as the input tensor the indices map back to the original input tensor.
-    fn float_argsort(tensor: FloatTensor<B>, dim: usize, descending: bool) -> IntTensor<B> {
-        argsort::<B, Float>(TensorPrimitive::Float(tensor), dim, descending)
+    fn float_argsort(
+        tensor: FloatTensor<B>,
+        dim: usize,
+        descending: bool,
+        stable: bool,
+    ) -> IntTensor<B> {
+        argsort::<B, Float>(TensorPrimitive::Float(tensor), dim, descending, stable)
     }
 }
@@ -188,6 +188,7 @@ macro_rules! testgen_with_float_param {
 
         // test ops
         burn_tensor::testgen_gather_scatter!();
+        burn_tensor::testgen_scatter_reduce!();
         burn_tensor::testgen_narrow!();
         burn_tensor::testgen_add!();
         burn_tensor::testgen_aggregation!();
@@ -199,12 +200,15 @@ macro_rules! testgen_with_float_param {
         burn_tensor::testgen_chunk!();
         burn_tensor::testgen_clamp!();
         burn_tensor::testgen_close!();
+        burn_tensor::testgen_complex!();
         burn_tensor::testgen_cos!();
         burn_tensor::testgen_cosh!();
         burn_tensor::testgen_create_like!();
         burn_tensor::testgen_div!();
+        burn_tensor::testgen_einsum!();
         burn_tensor::testgen_erf!();
         burn_tensor::testgen_exp!();
+        burn_tensor::testgen_fft!();
         burn_tensor::testgen_flatten!();
         burn_tensor::testgen_full!();
         burn_tensor::testgen_init!();
@@ -250,6 +254,8 @@ macro_rules! testgen_with_float_param {
         burn_tensor::testgen_tri_mask!();
         burn_tensor::testgen_sort_argsort!();
         burn_tensor::testgen_topk!();
+        burn_tensor::testgen_unique!();
+        burn_tensor::testgen_bincount!();
         burn_tensor::testgen_remainder!();
         burn_tensor::testgen_cartesian_grid!();
         burn_tensor::testgen_nan!();
@@ -298,7 +304,10 @@ macro_rules! testgen_with_int_param {
         burn_tensor::testgen_sub!();
         burn_tensor::testgen_transpose!();
         burn_tensor::testgen_gather_scatter!();
+        burn_tensor::testgen_scatter_reduce!();
         burn_tensor::testgen_bitwise!();
+        burn_tensor::testgen_unique!();
+        burn_tensor::testgen_bincount!();
 
         // test stats
         burn_tensor::testgen_eye!();
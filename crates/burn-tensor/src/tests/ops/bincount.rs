@@ -0,0 +1,47 @@
+#[burn_tensor_testgen::testgen(bincount)]
+mod tests {
+    use super::*;
+    use burn_tensor::TensorData;
+
+    #[test]
+    fn test_bincount_1d() {
+        let tensor = TestTensorInt::<1>::from([1, 3, 1, 0, 1, 3]);
+
+        let counts = tensor.bincount(4);
+
+        counts
+            .into_data()
+            .assert_eq(&TensorData::from([1, 3, 0, 2]), false);
+    }
+
+    #[test]
+    fn test_bincount_2d_with_empty_bins() {
+        let tensor = TestTensorInt::<2>::from([[3, 1, 3], [2, 1, 2]]);
+
+        let counts = tensor.bincount(6);
+
+        counts
+            .into_data()
+            .assert_eq(&TensorData::from([0, 2, 2, 2, 0, 0]), false);
+    }
+
+    #[test]
+    fn test_bincount_ignores_values_outside_bins() {
+        let tensor = TestTensorInt::<1>::from([0, 5, 2, 7, 2, 0]);
+
+        let counts = tensor.bincount(3);
+
+        counts
+            .into_data()
+            .assert_eq(&TensorData::from([2, 0, 2]), false);
+    }
+
+    #[test]
+    fn test_bincount_no_bins() {
+        let tensor = TestTensorInt::<1>::from([0, 1]);
+
+        let counts = tensor.bincount(0);
+
+        assert_eq!(counts.dims(), [0]);
+    }
+}
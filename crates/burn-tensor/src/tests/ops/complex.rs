@@ -0,0 +1,160 @@
+#[burn_tensor_testgen::testgen(complex)]
+mod tests {
+    use super::*;
+    use burn_tensor::{ComplexTensor, TensorData};
+    use burn_tensor::{Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    fn complex<const D: usize>(
+        real: TestTensor<D>,
+        imag: TestTensor<D>,
+    ) -> ComplexTensor<TestBackend, D> {
+        ComplexTensor::new(real, imag)
+    }
+
+    fn assert_complex<const D: usize>(output: ComplexTensor<TestBackend, D>, expected: TensorData) {
+        assert!(output.dtype().is_complex());
+        output
+            .into_data()
+            .into_complex_parts()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_complex_arithmetic() {
+        // lhs = [1 + 2i, 3 - i], rhs = [2 - i, 1 + i]
+        let lhs = complex(
+            TestTensor::<1>::from([1.0, 3.0]),
+            TestTensor::from([2.0, -1.0]),
+        );
+        let rhs = complex(
+            TestTensor::<1>::from([2.0, 1.0]),
+            TestTensor::from([-1.0, 1.0]),
+        );
+
+        assert_complex(
+            lhs.clone() + rhs.clone(),
+            TensorData::from([[3.0, 1.0], [4.0, 0.0]]),
+        );
+        assert_complex(
+            lhs.clone() - rhs.clone(),
+            TensorData::from([[-1.0, 3.0], [2.0, -2.0]]),
+        );
+        assert_complex(
+            lhs.clone() * rhs.clone(),
+            TensorData::from([[4.0, 3.0], [4.0, 2.0]]),
+        );
+        assert_complex(lhs / rhs, TensorData::from([[0.0, 1.0], [1.0, -2.0]]));
+    }
+
+    #[test]
+    fn should_support_real_imag_conj_abs() {
+        let tensor = complex(
+            TestTensor::<1>::from([3.0, 0.0]),
+            TestTensor::from([4.0, -2.0]),
+        );
+
+        tensor
+            .real()
+            .into_data()
+            .assert_approx_eq::<FT>(&TensorData::from([3.0, 0.0]), Tolerance::default());
+        tensor
+            .imag()
+            .into_data()
+            .assert_approx_eq::<FT>(&TensorData::from([4.0, -2.0]), Tolerance::default());
+        tensor
+            .clone()
+            .abs()
+            .into_data()
+            .assert_approx_eq::<FT>(&TensorData::from([5.0, 2.0]), Tolerance::default());
+        assert_complex(tensor.conj(), TensorData::from([[3.0, -4.0], [0.0, 2.0]]));
+    }
+
+    #[test]
+    fn should_support_complex_matmul() {
+        // lhs = [[1, i], [2i, 1]], rhs = [[1 + i], [1]]
+        let lhs = complex(
+            TestTensor::<2>::from([[1.0, 0.0], [0.0, 1.0]]),
+            TestTensor::from([[0.0, 1.0], [2.0, 0.0]]),
+        );
+        let rhs = complex(
+            TestTensor::<2>::from([[1.0], [1.0]]),
+            TestTensor::from([[1.0], [0.0]]),
+        );
+
+        assert_complex(
+            lhs.matmul(rhs),
+            TensorData::from([[[1.0, 2.0]], [[-1.0, 2.0]]]),
+        );
+    }
+
+    #[test]
+    fn should_support_complex_fft_round_trip() {
+        // The complex signal [1, i, -1, -i].
+        let tensor = complex(
+            TestTensor::<1>::from([1.0, 0.0, -1.0, 0.0]),
+            TestTensor::from([0.0, 1.0, 0.0, -1.0]),
+        );
+
+        let output = tensor.fft(0);
+        assert_complex(
+            output.clone(),
+            TensorData::from([[0.0, 0.0], [4.0, 0.0], [0.0, 0.0], [0.0, 0.0]]),
+        );
+        assert_complex(
+            output.ifft(0),
+            TensorData::from([[1.0, 0.0], [0.0, 1.0], [-1.0, 0.0], [0.0, -1.0]]),
+        );
+    }
+
+    #[test]
+    fn should_support_complex_rfft_round_trip() {
+        let signal = TestTensor::<1>::from([1.0, 2.0, 3.0, 4.0]);
+
+        let spectrum = ComplexTensor::rfft(signal.clone(), 0);
+        assert_complex(
+            spectrum.clone(),
+            TensorData::from([[10.0, 0.0], [-2.0, 2.0], [-2.0, 0.0]]),
+        );
+
+        let interleaved =
+            ComplexTensor::<TestBackend, 1>::from_interleaved(signal.clone().rfft::<2>(0));
+        assert_complex(
+            interleaved,
+            TensorData::from([[10.0, 0.0], [-2.0, 2.0], [-2.0, 0.0]]),
+        );
+
+        spectrum
+            .irfft(0, Some(4))
+            .into_data()
+            .assert_approx_eq::<FT>(&signal.into_data(), Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_complex_data_round_trip() {
+        let device = Default::default();
+        let data = TensorData::complex(vec![[1.0f64, -1.0], [0.5, 2.0]], [2]);
+
+        let tensor = ComplexTensor::<TestBackend, 1>::from_data(data, &device);
+
+        tensor
+            .real()
+            .into_data()
+            .assert_approx_eq::<FT>(&TensorData::from([1.0, 0.5]), Tolerance::default());
+        assert_complex(tensor, TensorData::from([[1.0, -1.0], [0.5, 2.0]]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_creating_a_float_tensor_from_complex_data() {
+        let data = TensorData::complex(vec![[1.0f32, -1.0], [0.5, 2.0]], [2]);
+
+        let _ = TestTensor::<1>::from_data(data, &Default::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_parts_have_different_shapes() {
+        let _ = complex(TestTensor::<1>::from([1.0, 2.0]), TestTensor::from([1.0]));
+    }
+}
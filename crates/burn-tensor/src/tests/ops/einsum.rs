@@ -0,0 +1,182 @@
+#[burn_tensor_testgen::testgen(einsum)]
+mod tests {
+    use super::*;
+    use burn_tensor::{Tensor, TensorData};
+    use burn_tensor::{Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    #[test]
+    fn should_support_einsum_matmul() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<2>::from([[5.0, 6.0], [7.0, 8.0]]);
+
+        let output: TestTensor<2> = lhs.einsum("ij,jk->ik", rhs);
+        let expected = TensorData::from([[19.0, 22.0], [43.0, 50.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_batched_matmul() {
+        let lhs = TestTensor::<3>::from([[[1.0, 2.0], [3.0, 4.0]], [[1.0, 0.0], [0.0, 1.0]]]);
+        let rhs = TestTensor::<3>::from([[[5.0, 6.0], [7.0, 8.0]], [[2.0, 3.0], [4.0, 5.0]]]);
+
+        let output: TestTensor<3> = lhs.einsum("bij,bjk->bik", rhs);
+        let expected = TensorData::from([[[19.0, 22.0], [43.0, 50.0]], [[2.0, 3.0], [4.0, 5.0]]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_transposed_operand() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<2>::from([[5.0, 6.0], [7.0, 8.0]]);
+
+        let output: TestTensor<2> = lhs.einsum("ij,kj->ik", rhs);
+        let expected = TensorData::from([[17.0, 23.0], [39.0, 53.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_transposed_output() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<2>::from([[5.0, 6.0], [7.0, 8.0]]);
+
+        let output: TestTensor<2> = lhs.einsum("ij,jk->ki", rhs);
+        let expected = TensorData::from([[19.0, 43.0], [22.0, 50.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_outer_product() {
+        let lhs = TestTensor::<1>::from([1.0, 2.0]);
+        let rhs = TestTensor::<1>::from([3.0, 4.0, 5.0]);
+
+        let output: TestTensor<2> = lhs.einsum("i,j->ij", rhs);
+        let expected = TensorData::from([[3.0, 4.0, 5.0], [6.0, 8.0, 10.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_dot_product() {
+        let lhs = TestTensor::<1>::from([1.0, 2.0, 3.0]);
+        let rhs = TestTensor::<1>::from([4.0, 5.0, 6.0]);
+
+        let output: TestTensor<1> = lhs.einsum("i,i->", rhs);
+        let expected = TensorData::from([32.0]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_full_contraction() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<1>::from([1.0, 1.0]);
+
+        let output: TestTensor<1> = lhs.einsum("ij,j->", rhs);
+        let expected = TensorData::from([10.0]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_diagonal() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<1>::from([10.0, 20.0]);
+
+        let output: TestTensor<1> = lhs.einsum("ii,i->i", rhs);
+        let expected = TensorData::from([10.0, 80.0]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_trace() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<1>::from([1.0, 2.0]);
+
+        let output: TestTensor<1> = lhs.einsum("ii,j->j", rhs);
+        let expected = TensorData::from([5.0, 10.0]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_implicit_output() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<2>::from([[5.0, 6.0], [7.0, 8.0]]);
+
+        let output: TestTensor<2> = lhs.einsum("ij,jk", rhs);
+        let expected = TensorData::from([[19.0, 22.0], [43.0, 50.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_broadcast_ellipsis() {
+        let lhs = TestTensor::<3>::from([[[1.0, 2.0], [3.0, 4.0]], [[1.0, 0.0], [0.0, 1.0]]]);
+        let rhs = TestTensor::<2>::from([[5.0, 6.0], [7.0, 8.0]]);
+
+        let output: TestTensor<3> = lhs.einsum("...ij,...jk->...ik", rhs);
+        let expected = TensorData::from([[[19.0, 22.0], [43.0, 50.0]], [[5.0, 6.0], [7.0, 8.0]]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_einsum_broadcast_label() {
+        let lhs = TestTensor::<3>::from([[[1.0, 2.0]], [[3.0, 4.0]]]);
+        let rhs = TestTensor::<3>::from([[[1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]]);
+
+        // The label `b` has size 2 on the lhs and 1 on the rhs.
+        let output: TestTensor<3> = lhs.einsum("bij,bkj->bik", rhs);
+        let expected = TensorData::from([[[1.0, 2.0, 3.0]], [[3.0, 4.0, 7.0]]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_output_rank_differs() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<2>::from([[5.0, 6.0], [7.0, 8.0]]);
+
+        let _output: TestTensor<3> = lhs.einsum("ij,jk->ik", rhs);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_label_sizes_differ() {
+        let lhs = TestTensor::<2>::from([[1.0, 2.0], [3.0, 4.0]]);
+        let rhs = TestTensor::<2>::from([[5.0, 6.0, 7.0]]);
+
+        let _output: TestTensor<2> = lhs.einsum("ij,kj->ik", rhs);
+    }
+}
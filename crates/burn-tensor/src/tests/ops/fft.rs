@@ -0,0 +1,139 @@
+#[burn_tensor_testgen::testgen(fft)]
+mod tests {
+    use super::*;
+    use burn_tensor::{Tensor, TensorData};
+    use burn_tensor::{Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    #[test]
+    fn should_support_fft() {
+        // The complex signal [1, i, -1, -i].
+        let tensor = TestTensor::<2>::from([[1.0, 0.0], [0.0, 1.0], [-1.0, 0.0], [0.0, -1.0]]);
+
+        let output = tensor.fft(0);
+        let expected = TensorData::from([[0.0, 0.0], [4.0, 0.0], [0.0, 0.0], [0.0, 0.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_ifft() {
+        let tensor = TestTensor::<2>::from([[0.0, 0.0], [4.0, 0.0], [0.0, 0.0], [0.0, 0.0]]);
+
+        let output = tensor.ifft(0);
+        let expected = TensorData::from([[1.0, 0.0], [0.0, 1.0], [-1.0, 0.0], [0.0, -1.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_fft_along_any_dim() {
+        let tensor = TestTensor::<3>::from([[[1.0, 0.0], [2.0, 0.0]], [[0.0, 1.0], [0.0, 2.0]]]);
+
+        let output = tensor.clone().fft(1);
+        let expected = TensorData::from([[[3.0, 0.0], [-1.0, 0.0]], [[0.0, 3.0], [0.0, -1.0]]]);
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+
+        let output = tensor.fft(0);
+        let expected = TensorData::from([[[1.0, 1.0], [2.0, 2.0]], [[1.0, -1.0], [2.0, -2.0]]]);
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_recover_signal_with_ifft() {
+        let tensor = TestTensor::<3>::from([
+            [[1.0, -2.0], [0.5, 3.0], [-4.0, 1.5]],
+            [[2.0, 0.0], [-1.0, -1.0], [0.0, 2.5]],
+        ]);
+
+        let output = tensor.clone().fft(1).ifft(1);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&tensor.into_data(), Tolerance::rel_abs(1e-4, 1e-4));
+    }
+
+    #[test]
+    fn should_support_rfft() {
+        let tensor = TestTensor::<1>::from([1.0, 2.0, 3.0, 4.0]);
+
+        let output: TestTensor<2> = tensor.rfft(0);
+        let expected = TensorData::from([[10.0, 0.0], [-2.0, 2.0], [-2.0, 0.0]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_support_rfft_odd_size() {
+        let tensor = TestTensor::<2>::from([[1.0, 2.0, 3.0]]);
+
+        let output: TestTensor<3> = tensor.rfft(1);
+        let expected = TensorData::from([[[6.0, 0.0], [-1.5, 0.866_025_4]]]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::rel_abs(1e-4, 1e-4));
+    }
+
+    #[test]
+    fn should_support_irfft() {
+        let tensor = TestTensor::<2>::from([[10.0, 0.0], [-2.0, 2.0], [-2.0, 0.0]]);
+
+        let output: TestTensor<1> = tensor.irfft(0, None);
+        let expected = TensorData::from([1.0, 2.0, 3.0, 4.0]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    fn should_recover_signal_with_irfft() {
+        let tensor = TestTensor::<2>::from([[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]]);
+
+        let spectrum: TestTensor<3> = tensor.clone().rfft(1);
+        let output: TestTensor<2> = spectrum.irfft(1, Some(3));
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&tensor.into_data(), Tolerance::rel_abs(1e-4, 1e-4));
+    }
+
+    #[test]
+    fn should_pad_missing_frequencies_with_irfft() {
+        let tensor = TestTensor::<2>::from([[4.0, 0.0]]);
+
+        let output: TestTensor<1> = tensor.irfft(0, Some(4));
+        let expected = TensorData::from([1.0, 1.0, 1.0, 1.0]);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected, Tolerance::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_fft_input_is_not_complex() {
+        let tensor = TestTensor::<2>::from([[1.0, 2.0, 3.0]]);
+
+        let _output = tensor.fft(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_fft_dim_is_complex() {
+        let tensor = TestTensor::<2>::from([[1.0, 0.0], [2.0, 0.0]]);
+
+        let _output = tensor.fft(1);
+    }
+}
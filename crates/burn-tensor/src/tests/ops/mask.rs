@@ -197,4 +197,105 @@ mod tests {
 
         output.into_data().assert_eq(&expected, false);
     }
+
+    #[test]
+    fn should_support_mask_select() {
+        let device = Default::default();
+        let tensor = TestTensor::<2>::from_data([[1.0, -2.0, 3.0], [5.0, 9.0, 6.0]], &device);
+        let mask = TestTensorBool::<2>::from_bool(
+            TensorData::from([[true, false, true], [false, true, false]]),
+            &device,
+        );
+
+        let output = tensor.mask_select(mask);
+        let expected = TensorData::from([1.0, 3.0, 9.0]);
+
+        output.into_data().assert_eq(&expected, false);
+    }
+
+    #[test]
+    fn should_support_mask_select_swap_dims_int() {
+        let device = Default::default();
+        let tensor = TestTensorInt::arange(0..6, &device)
+            .reshape([2, 3])
+            .swap_dims(0, 1);
+        let mask = tensor.clone().greater_elem(1);
+
+        let output = tensor.mask_select(mask);
+        let expected = TensorData::from([3, 4, 2, 5]);
+
+        output.into_data().assert_eq(&expected, false);
+    }
+
+    #[test]
+    fn should_support_mask_select_none() {
+        let device = Default::default();
+        let tensor = TestTensor::<2>::from_data([[1.0, 7.0], [2.0, 3.0]], &device);
+        let mask = TestTensorBool::<2>::from_bool(
+            TensorData::from([[false, false], [false, false]]),
+            &device,
+        );
+
+        let output = tensor.mask_select(mask);
+
+        assert_eq!(output.dims(), [0]);
+    }
+
+    #[test]
+    fn should_support_mask_assign() {
+        let device = Default::default();
+        let tensor = TestTensor::<2>::from_data([[1.0, -2.0, 3.0], [5.0, 9.0, 6.0]], &device);
+        let mask = TestTensorBool::<2>::from_bool(
+            TensorData::from([[true, false, true], [false, true, false]]),
+            &device,
+        );
+        let values = TestTensor::<1>::from_data([7.0, 8.0, 0.0], &device);
+
+        let output = tensor.mask_assign(mask, values);
+        let expected = TensorData::from([[7.0, -2.0, 8.0], [5.0, 0.0, 6.0]]);
+
+        output.into_data().assert_eq(&expected, false);
+    }
+
+    #[test]
+    fn should_support_mask_assign_inverse_of_mask_select_int() {
+        let device = Default::default();
+        let tensor = TestTensorInt::<2>::from_data([[1, 7, 4], [2, 3, 8]], &device);
+        let mask = tensor.clone().greater_elem(3);
+
+        let values = tensor.clone().mask_select(mask.clone()).sub_scalar(1);
+        let output = tensor.mask_assign(mask, values);
+        let expected = TensorData::from([[1, 6, 3], [2, 3, 7]]);
+
+        output.into_data().assert_eq(&expected, false);
+    }
+
+    #[test]
+    fn should_keep_masked_elements_without_values_in_mask_assign() {
+        let device = Default::default();
+        let tensor = TestTensor::<2>::from_data([[1.0, -2.0, 3.0], [5.0, 9.0, 6.0]], &device);
+        let mask = TestTensorBool::<2>::from_bool(
+            TensorData::from([[true, false, true], [false, true, false]]),
+            &device,
+        );
+        let values = TestTensor::<1>::from_data([7.0, 8.0], &device);
+
+        let output = tensor.mask_assign(mask, values);
+        let expected = TensorData::from([[7.0, -2.0, 8.0], [5.0, 9.0, 6.0]]);
+
+        output.into_data().assert_eq(&expected, false);
+    }
+
+    #[test]
+    fn should_ignore_extra_values_in_mask_assign_int() {
+        let device = Default::default();
+        let tensor = TestTensorInt::<2>::from_data([[1, 7, 4], [2, 3, 8]], &device);
+        let mask = tensor.clone().greater_elem(3);
+        let values = TestTensorInt::<1>::from_data([10, 20, 30, 40, 50], &device);
+
+        let output = tensor.mask_assign(mask, values);
+        let expected = TensorData::from([[1, 10, 20], [2, 3, 30]]);
+
+        output.into_data().assert_eq(&expected, false);
+    }
 }
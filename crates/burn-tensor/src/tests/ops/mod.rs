@@ -7,6 +7,7 @@ mod arange;
 mod arange_step;
 mod arg;
 mod argwhere_nonzero;
+mod bincount;
 mod bitwise;
 mod bool;
 mod cartesian_grid;
@@ -16,13 +17,16 @@ mod ceil;
 mod chunk;
 mod clamp;
 mod close;
+mod complex;
 mod cos;
 mod cosh;
 mod create_like;
 mod div;
+mod einsum;
 mod erf;
 mod exp;
 mod expand;
+mod fft;
 mod finite;
 mod flatten;
 mod flip;
@@ -57,6 +61,7 @@ mod repeat_dim;
 mod reshape;
 mod roll;
 mod round;
+mod scatter_reduce;
 mod select;
 mod sign;
 mod sin;
@@ -74,3 +79,4 @@ mod topk;
 mod transpose;
 mod tri;
 mod tri_mask;
+mod unique;
@@ -0,0 +1,119 @@
+#[burn_tensor_testgen::testgen(scatter_reduce)]
+mod tests {
+    use super::*;
+    use burn_tensor::{ScatterReduce, TensorData, Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    #[test]
+    fn should_scatter_reduce_sum_1d() {
+        let device = Default::default();
+        let tensor = TestTensor::<1>::from_floats([1.0, 2.0, 3.0], &device);
+        let indices = TestTensorInt::from_ints([1, 0, 1, 1], &device);
+        let values = TestTensor::from_floats([5.0, 4.0, 3.0, 2.0], &device);
+
+        let output = tensor.scatter_reduce(0, indices, values, ScatterReduce::Sum);
+
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([5.0, 12.0, 3.0]), false);
+    }
+
+    #[test]
+    fn should_scatter_reduce_prod_2d_dim1() {
+        let device = Default::default();
+        let tensor = TestTensor::<2>::from_floats([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]], &device);
+        let indices = TestTensorInt::from_ints([[2, 0], [1, 1]], &device);
+        let values = TestTensor::from_floats([[2.0, -1.0], [0.5, 3.0]], &device);
+
+        let output = tensor.scatter_reduce(1, indices, values, ScatterReduce::Prod);
+
+        output.into_data().assert_approx_eq::<FT>(
+            &TensorData::from([[-1.0, 2.0, 6.0], [4.0, 7.5, 6.0]]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn should_scatter_reduce_min_max_2d_dim0() {
+        let device = Default::default();
+        let tensor = TestTensor::<2>::zeros([2, 3], &device);
+        let indices = TestTensorInt::from_ints([[0, 1, 0], [0, 1, 1], [1, 1, 0]], &device);
+        let values = TestTensor::from_floats(
+            [[3.0, -1.0, 2.0], [-4.0, 5.0, 1.0], [6.0, 2.0, -3.0]],
+            &device,
+        );
+
+        let output =
+            tensor
+                .clone()
+                .scatter_reduce(0, indices.clone(), values.clone(), ScatterReduce::Min);
+        output.into_data().assert_eq(
+            &TensorData::from([[-4.0, 0.0, -3.0], [0.0, -1.0, 0.0]]),
+            false,
+        );
+
+        let output = tensor.scatter_reduce(0, indices, values, ScatterReduce::Max);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[3.0, 0.0, 2.0], [6.0, 5.0, 1.0]]), false);
+    }
+
+    #[test]
+    fn should_scatter_reduce_mean_including_tensor_elements() {
+        let device = Default::default();
+        let tensor = TestTensor::<1>::from_floats([1.0, 2.0, 3.0], &device);
+        let indices = TestTensorInt::from_ints([0, 0, 2], &device);
+        let values = TestTensor::from_floats([2.0, 6.0, 5.0], &device);
+
+        let output = tensor.scatter_reduce(0, indices, values, ScatterReduce::Mean);
+
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&TensorData::from([3.0, 2.0, 4.0]), Tolerance::default());
+    }
+
+    #[test]
+    fn should_scatter_reduce_int() {
+        let device = Default::default();
+        let tensor = TestTensorInt::<2>::from_ints([[1, 2], [3, 4]], &device);
+        let indices = TestTensorInt::from_ints([[1, 1, 0], [0, 0, 1]], &device);
+        let values = TestTensorInt::from_ints([[5, 1, 2], [7, 3, 3]], &device);
+
+        let output =
+            tensor
+                .clone()
+                .scatter_reduce(1, indices.clone(), values.clone(), ScatterReduce::Max);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[2, 5], [7, 4]]), false);
+
+        let output =
+            tensor
+                .clone()
+                .scatter_reduce(1, indices.clone(), values.clone(), ScatterReduce::Prod);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[2, 10], [63, 12]]), false);
+
+        let output = tensor.scatter_reduce(1, indices, values, ScatterReduce::Mean);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[1, 2], [4, 3]]), false);
+    }
+
+    #[test]
+    fn should_select_assign_reduce_max() {
+        let device = Default::default();
+        let tensor = TestTensor::<2>::from_floats([[0.0, 0.0], [0.0, 0.0], [0.0, 0.0]], &device);
+        let indices = TestTensorInt::from_ints([2, 0, 2, 2], &device);
+        let values =
+            TestTensor::from_floats([[1.0, -1.0], [2.0, 3.0], [-5.0, 4.0], [0.5, 0.25]], &device);
+
+        let output = tensor.select_assign_reduce(0, indices, values, ScatterReduce::Max);
+
+        output.into_data().assert_eq(
+            &TensorData::from([[2.0, 3.0], [0.0, 0.0], [1.0, 4.0]]),
+            false,
+        );
+    }
+}
@@ -365,4 +365,59 @@ mod tests {
             .into_data()
             .assert_approx_eq::<FT>(&values_expected, Tolerance::default());
     }
+
+    #[test]
+    fn test_argsort_stable_1d_float() {
+        let tensor = TestTensor::<1>::from([
+            2., 1., 2., 0., 1., 2., 0., 1., 2., 0., 1., 2., 0., 1., 2., 0., 1., 2., 0., 1., 2., 0.,
+            1., 2.,
+        ]);
+
+        let indices = tensor.clone().argsort_stable(0, false);
+        let indices_expected = TensorData::from([
+            3, 6, 9, 12, 15, 18, 21, 1, 4, 7, 10, 13, 16, 19, 22, 0, 2, 5, 8, 11, 14, 17, 20, 23,
+        ]);
+        indices.into_data().assert_eq(&indices_expected, false);
+
+        let indices = tensor.argsort_stable(0, true);
+        let indices_expected = TensorData::from([
+            0, 2, 5, 8, 11, 14, 17, 20, 23, 1, 4, 7, 10, 13, 16, 19, 22, 3, 6, 9, 12, 15, 18, 21,
+        ]);
+        indices.into_data().assert_eq(&indices_expected, false);
+    }
+
+    #[test]
+    fn test_sort_stable_with_indices_int() {
+        let tensor = TestTensorInt::<2>::from([[1, 0, 1, 0], [0, 0, 1, 1], [1, 1, 0, 0]]);
+
+        // Sort along dim=0
+        let (values, indices) = tensor.clone().sort_stable_with_indices(0, false);
+
+        let values_expected = TensorData::from([[0, 0, 0, 0], [1, 0, 1, 0], [1, 1, 1, 1]]);
+        values.into_data().assert_eq(&values_expected, false);
+
+        let indices_expected = TensorData::from([[1, 0, 2, 0], [0, 1, 0, 2], [2, 2, 1, 1]]);
+        indices.into_data().assert_eq(&indices_expected, false);
+
+        // Sort along dim=1
+        let (values, indices) = tensor.sort_stable_with_indices(1, true);
+
+        let values_expected = TensorData::from([[1, 1, 0, 0], [1, 1, 0, 0], [1, 1, 0, 0]]);
+        values.into_data().assert_eq(&values_expected, false);
+
+        let indices_expected = TensorData::from([[0, 2, 1, 3], [2, 3, 0, 1], [0, 1, 2, 3]]);
+        indices.into_data().assert_eq(&indices_expected, false);
+    }
+
+    #[test]
+    fn test_sort_stable_float() {
+        let tensor = TestTensor::<2>::from([[0.5, -1., 0.5], [2., 2., -3.]]);
+
+        let values = tensor.sort_stable(1, false);
+
+        let values_expected = TensorData::from([[-1., 0.5, 0.5], [-3., 2., 2.]]);
+        values
+            .into_data()
+            .assert_approx_eq::<FT>(&values_expected, Tolerance::default());
+    }
 }
@@ -78,4 +78,19 @@ mod tests {
 
         indices.into_data().assert_eq(&indices_expected, false);
     }
+
+    #[test]
+    fn test_topk_with_indices_ties() {
+        let tensor = TestTensor::<2>::from([[1., 3., 3., 2., 3.], [0., 0., 0., 0., 0.]]);
+
+        let (values, indices) = tensor.topk_with_indices(3, /*dim*/ 1);
+
+        let values_expected = TensorData::from([[3., 3., 3.], [0., 0., 0.]]);
+        values
+            .into_data()
+            .assert_approx_eq::<FT>(&values_expected, Tolerance::default());
+
+        let indices_expected = TensorData::from([[1, 2, 4], [0, 1, 2]]);
+        indices.into_data().assert_eq(&indices_expected, false);
+    }
 }
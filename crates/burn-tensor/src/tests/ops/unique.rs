@@ -0,0 +1,60 @@
+#[burn_tensor_testgen::testgen(unique)]
+mod tests {
+    use super::*;
+    use burn_tensor::TensorData;
+
+    #[test]
+    fn test_unique_1d() {
+        let tensor = TestTensorInt::<1>::from([4, 1, 4, 0, 9, 1, 4]);
+
+        let values = tensor.unique();
+
+        values
+            .into_data()
+            .assert_eq(&TensorData::from([0, 1, 4, 9]), false);
+    }
+
+    #[test]
+    fn test_unique_with_counts_2d() {
+        let tensor = TestTensorInt::<2>::from([[3, 1, 3], [2, 1, 7]]);
+
+        let (values, counts) = tensor.unique_with_counts();
+
+        values
+            .into_data()
+            .assert_eq(&TensorData::from([1, 2, 3, 7]), false);
+        counts
+            .into_data()
+            .assert_eq(&TensorData::from([2, 1, 2, 1]), false);
+    }
+
+    #[test]
+    fn test_unique_with_inverse_2d() {
+        let tensor = TestTensorInt::<2>::from([[3, 1, 3], [2, 1, 7]]);
+
+        let (values, inverse) = tensor.clone().unique_with_inverse();
+
+        values
+            .clone()
+            .into_data()
+            .assert_eq(&TensorData::from([1, 2, 3, 7]), false);
+        inverse
+            .clone()
+            .into_data()
+            .assert_eq(&TensorData::from([[2, 0, 2], [1, 0, 3]]), false);
+
+        // The inverse indices recover the input from the unique elements.
+        let recovered = values.select(0, inverse.flatten::<1>(0, 1)).reshape([2, 3]);
+        recovered.into_data().assert_eq(&tensor.into_data(), false);
+    }
+
+    #[test]
+    fn test_unique_single_value() {
+        let tensor = TestTensorInt::<2>::from([[5, 5], [5, 5]]);
+
+        let (values, counts) = tensor.unique_with_counts();
+
+        values.into_data().assert_eq(&TensorData::from([5]), false);
+        counts.into_data().assert_eq(&TensorData::from([4]), false);
+    }
+}
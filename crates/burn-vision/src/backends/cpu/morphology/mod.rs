@@ -106,6 +106,7 @@ pub fn morph<B: Backend, K: BasicOps<B>>(
         DType::U8 => morph_typed::<B, K, u8>(data, shape, kernel, op, iter, btype, bvalue, &device),
         DType::Bool => morph_bool::<B, K>(data, shape, kernel, op, iter, btype, bvalue, &device),
         DType::QFloat(_) => unimplemented!(),
+        DType::Complex64 | DType::Complex32 => unreachable!("Tensors never hold complex data"),
     }
 }
 
@@ -0,0 +1,185 @@
+use burn_cubecl::{BoolElement, CubeBackend, FloatElement, IntElement, tensor::CubeTensor};
+use burn_tensor::{DType, Shape, Tensor, backend::Backend, ops::FloatTensor};
+use cubecl::wgpu::{WgpuResource, WgpuRuntime};
+
+/// The buffer of a tensor shared with a user render pipeline.
+///
+/// The buffer can be bound in a render pass using [WgpuResource::as_wgpu_bind_resource], so that
+/// simulation results are visualized without reading them back to the CPU.
+///
+/// # Synchronization
+///
+/// Every operation writing the tensor is submitted to the queue of the device when the buffer is
+/// shared. Since a wgpu queue executes its submissions in order, a render pass submitted
+/// afterward on the same queue observes the final values of the tensor. The render pipeline must
+/// therefore use the device and queue of the backend, e.g. by creating them beforehand and
+/// registering them with [init_device](crate::init_device).
+///
+/// The allocation stays reserved for the tensor as long as the shared buffer is alive, so it is
+/// neither reused nor written in place by following operations.
+pub struct SharedBuffer {
+    resource: WgpuResource,
+    tensor: CubeTensor<WgpuRuntime>,
+}
+
+impl SharedBuffer {
+    fn new(tensor: CubeTensor<WgpuRuntime>) -> Self {
+        tensor.client.flush();
+
+        let binding = tensor.client.get_resource(tensor.handle.clone().binding());
+        let resource = binding.resource();
+        let resource = WgpuResource::new(
+            resource.buffer().clone(),
+            resource.offset(),
+            resource.size(),
+        );
+
+        Self { resource, tensor }
+    }
+
+    /// The wgpu buffer along with the range holding the tensor.
+    pub fn resource(&self) -> &WgpuResource {
+        &self.resource
+    }
+
+    /// The shape of the tensor.
+    pub fn shape(&self) -> &Shape {
+        &self.tensor.shape
+    }
+
+    /// The strides of the tensor, in number of elements.
+    ///
+    /// The tensor isn't necessarily contiguous, so shaders should index the buffer with them.
+    pub fn strides(&self) -> &[usize] {
+        &self.tensor.strides
+    }
+
+    /// The data type of the elements of the buffer.
+    pub fn dtype(&self) -> DType {
+        self.tensor.dtype
+    }
+}
+
+/// Backends able to share the buffers of their tensors with wgpu render pipelines.
+pub trait RenderInterop: Backend {
+    /// Share the buffer of the given tensor.
+    fn shared_buffer(tensor: FloatTensor<Self>) -> SharedBuffer;
+}
+
+/// Share the buffer of the tensor with a user render pipeline.
+///
+/// See [SharedBuffer] for the synchronization guarantees.
+pub fn share_buffer<B: RenderInterop, const D: usize>(tensor: &Tensor<B, D>) -> SharedBuffer {
+    B::shared_buffer(tensor.clone().into_primitive().tensor())
+}
+
+impl<F, I, BT> RenderInterop for CubeBackend<WgpuRuntime, F, I, BT>
+where
+    F: FloatElement,
+    I: IntElement,
+    BT: BoolElement,
+{
+    fn shared_buffer(tensor: FloatTensor<Self>) -> SharedBuffer {
+        SharedBuffer::new(tensor)
+    }
+}
+
+#[cfg(feature = "fusion")]
+impl<F, I, BT> RenderInterop for burn_fusion::Fusion<CubeBackend<WgpuRuntime, F, I, BT>>
+where
+    F: FloatElement,
+    I: IntElement,
+    BT: BoolElement,
+{
+    fn shared_buffer(tensor: FloatTensor<Self>) -> SharedBuffer {
+        use burn_fusion::client::FusionClient;
+
+        // Executes the queued operations writing the tensor.
+        let client = tensor.client.clone();
+        let tensor = client.resolve_tensor_float::<CubeBackend<WgpuRuntime, F, I, BT>>(tensor);
+
+        SharedBuffer::new(tensor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RuntimeOptions, WgpuDevice, WgpuSetup, init_device};
+    use cubecl::future::block_on;
+
+    /// Create the wgpu device and queue of a render pipeline and register them with the backend.
+    fn create_device() -> (WgpuDevice, wgpu::Device, wgpu::Queue) {
+        let instance = wgpu::Instance::default();
+        let adapter = block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .expect("A wgpu adapter should be available");
+        let backend = adapter.get_info().backend;
+        let (device, queue) = block_on(
+            adapter.request_device(&wgpu::DeviceDescriptor {
+                required_features: adapter
+                    .features()
+                    .difference(wgpu::Features::MAPPABLE_PRIMARY_BUFFERS),
+                required_limits: adapter.limits(),
+                ..Default::default()
+            }),
+        )
+        .expect("The wgpu device should be created");
+
+        let setup = WgpuSetup {
+            instance,
+            adapter,
+            device: device.clone(),
+            queue: queue.clone(),
+            backend,
+        };
+
+        (init_device(setup, RuntimeOptions::default()), device, queue)
+    }
+
+    /// Share the buffer of a tensor written by queued operations and copy it back to the CPU
+    /// with the queue of the render pipeline.
+    fn read_back_shared_buffer<B: RenderInterop<Device = WgpuDevice>>() {
+        let (device, wgpu_device, queue) = create_device();
+        let tensor = Tensor::<B, 1>::from_floats([1.0, 2.0, 3.0], &device);
+        let tensor = tensor.clone() + tensor;
+
+        let shared = share_buffer(&tensor);
+        let resource = shared.resource();
+        let size = 3 * core::mem::size_of::<f32>() as u64;
+        assert!(resource.size() >= size);
+        assert_eq!(shared.shape(), &Shape::new([3]));
+        assert_eq!(shared.dtype(), DType::F32);
+
+        let staging = wgpu_device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = wgpu_device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(resource.buffer(), resource.offset(), &staging, 0, size);
+        queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| {
+            result.expect("The staging buffer should be mapped")
+        });
+        wgpu_device
+            .poll(wgpu::PollType::Wait)
+            .expect("The device should be polled");
+
+        let values: Vec<f32> = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        assert_eq!(values, [2.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn should_read_back_the_shared_buffer() {
+        read_back_shared_buffer::<CubeBackend<WgpuRuntime, f32, i32, u32>>();
+    }
+
+    #[cfg(feature = "fusion")]
+    #[test]
+    fn should_read_back_the_shared_buffer_of_a_fused_tensor() {
+        read_back_shared_buffer::<crate::Wgpu>();
+    }
+}
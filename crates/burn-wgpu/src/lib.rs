@@ -2,6 +2,12 @@
 
 extern crate alloc;
 
+#[cfg(feature = "interop")]
+mod interop;
+
+#[cfg(feature = "interop")]
+pub use interop::*;
+
 #[cfg(feature = "template")]
 pub use burn_cubecl::{
     kernel::{KernelMetadata, into_contiguous},
@@ -1,4 +1,4 @@
-use burn::tensor::{Dim, Distribution, NamedDim, NamedTensor, backend::Backend};
+use burn::tensor::{Dim, Distribution, NamedDim, NamedTensor, Tensor, backend::Backend};
 
 NamedDim!(Batch);
 NamedDim!(SeqLength);
@@ -45,4 +45,18 @@ pub fn run<B: Backend>(device: &B::Device) {
     println!("Input   => {input}");
     println!("Output  => {output}");
     println!("Permut  => {permut}");
+
+    // Dimensions can also be named at runtime, with mismatches reported when the tensors are used.
+    let images = Tensor::<B, 4>::random([batch_size, 3, 8, 8], Distribution::Default, device)
+        .with_dims(["batch", "channel", "h", "w"]);
+    let bias = Tensor::<B, 1>::random([3], Distribution::Default, device).with_dims(["channel"]);
+
+    // The bias is broadcasted along the dimensions it doesn't have.
+    let output = images.add(bias).mean_dim("h").mean_dim("w");
+    let output = output.permute(["channel", "batch", "h", "w"]);
+
+    // Panics, since there is no dimension named `width`
+    // let output = output.sum_dim("width");
+
+    println!("Images  => {output}");
 }